@@ -0,0 +1,314 @@
+// Arena mode: bump allocation for the length of a scope.
+//
+// Inside `scope`, `Lab` serves the thread's allocations from *chunks*:
+// big blocks from the system allocator, handed out front to back by
+// bumping an offset. Freeing one allocation doesn't make its memory
+// reusable. Instead, each chunk counts its live allocations, and once
+// its scope has moved on from it and the count reaches zero, the whole
+// chunk goes back to the system at once.
+//
+// Counting is what makes arena mode safe to switch on around arbitrary
+// code. A value allocated in a scope can outlive it, or be freed on
+// another thread, and its chunk simply stays until it's gone.
+//
+// To free a pointer, `Lab` has to know whether an arena allocated it.
+// Chunks are aligned to their size, so rounding a pointer down gives the
+// start of the chunk it would belong to. A chunk is in use only if it's
+// listed in a fixed table of open chunks, and it's removed from the table
+// before its memory is released. So the memory can't be handed out again,
+// and its pointers later mistaken for arena pointers, while it's still
+// listed.
+//
+// An emptied chunk isn't released in the middle of freeing its last
+// allocation, though. The code freeing it, `Box`'s destructor say, may
+// still hold a reference to that allocation until the free returns, and
+// Rust's aliasing rules forbid deallocating memory out from under a live
+// reference, even one that won't be used again. Miri catches exactly that.
+// So the thread that empties a chunk sets it aside, and releases it the
+// next time it calls into the allocator, or when it exits.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering::SeqCst};
+
+/// Each chunk's size, and alignment. Smaller under Miri, which is slow.
+pub const CHUNK_SIZE: usize = if cfg!(miri) { 1 << 16 } else { 1 << 20 };
+
+/// Larger allocations bypass the arena, so that no one allocation wastes
+/// much of a chunk.
+pub const LARGEST: usize = CHUNK_SIZE / 8;
+
+/// The most chunks that can be open at once, across all threads. Past
+/// that, arena scopes fall back to the system allocator.
+pub const MAX_CHUNKS: usize = 64;
+
+/// The front of each chunk holds its count of live allocations.
+const HEADER: usize = 64;
+
+/// Set in a chunk's count once its scope has finished with it.
+const RETIRED: usize = 1 << (usize::BITS - 1);
+
+static CHUNKS: [AtomicPtr<u8>; MAX_CHUNKS] =
+    [const { AtomicPtr::new(ptr::null_mut()) }; MAX_CHUNKS];
+static OPEN: AtomicUsize = AtomicUsize::new(0);
+/// One past the highest slot in `CHUNKS` ever used, to shorten searches.
+static USED: AtomicUsize = AtomicUsize::new(0);
+
+struct Local {
+    depth: Cell<usize>,
+    chunk: Cell<*mut u8>,
+    offset: Cell<usize>,
+    /// An emptied chunk waiting to be released.
+    pending: Cell<*mut u8>,
+}
+
+thread_local! {
+    static LOCAL: Local = const {
+        Local {
+            depth: Cell::new(0),
+            chunk: Cell::new(ptr::null_mut()),
+            offset: Cell::new(0),
+            pending: Cell::new(ptr::null_mut()),
+        }
+    };
+}
+
+/// Releases the thread's pending chunk when the thread exits. Kept apart
+/// from `Local`, which the allocator must be able to use at any point,
+/// even while this destructor is being registered.
+struct Collector;
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        collect();
+    }
+}
+
+thread_local! {
+    static COLLECTOR: Collector = const { Collector };
+}
+
+/// Run `f` with this thread's allocations served from an arena. Scopes
+/// nest, sharing the outermost scope's arena. This has no effect unless
+/// `Lab` is the global allocator.
+pub fn scope<R>(f: impl FnOnce() -> R) -> R {
+    struct Exit;
+
+    impl Drop for Exit {
+        fn drop(&mut self) {
+            LOCAL.with(|local| {
+                local.depth.set(local.depth.get() - 1);
+                if local.depth.get() == 0 {
+                    let chunk = local.chunk.replace(ptr::null_mut());
+                    if !chunk.is_null() {
+                        // SAFETY: this thread opened the chunk and hasn't
+                        // retired it yet.
+                        unsafe { retire(chunk) };
+                    }
+                }
+            });
+        }
+    }
+
+    LOCAL.with(|local| local.depth.set(local.depth.get() + 1));
+    // Runs even if `f` panics, so a panic can't leave arena mode on.
+    let _exit = Exit;
+    f()
+}
+
+/// Whether this thread is inside a `scope`.
+pub fn active() -> bool {
+    LOCAL
+        .try_with(|local| local.depth.get() > 0)
+        .unwrap_or(false)
+}
+
+/// How many chunks are allocated, across all threads: those in use by a
+/// scope, and those kept for allocations that outlived theirs. This
+/// thread's emptied chunk, if it has one waiting, is released first.
+pub fn open_chunks() -> usize {
+    collect();
+    OPEN.load(SeqCst)
+}
+
+/// Release the chunk this thread set aside, if any. `Lab` calls this on
+/// every entry, when no earlier call is still running on this thread.
+pub(crate) fn collect() {
+    let chunk = LOCAL
+        .try_with(|local| local.pending.replace(ptr::null_mut()))
+        .unwrap_or(ptr::null_mut());
+    if !chunk.is_null() {
+        // SAFETY: only empty, retired chunks are set aside, by one thread.
+        unsafe { release(chunk) };
+    }
+}
+
+/// Set an empty, retired chunk aside to release later.
+///
+/// SAFETY: `chunk` must be retired, with nothing in it live, and this
+/// thread the one that found it so.
+unsafe fn defer(chunk: *mut u8) {
+    // Registering the destructor may allocate, which collects any chunk
+    // set aside earlier: do it before setting this one aside.
+    let _ = COLLECTOR.try_with(|_| {});
+    match LOCAL.try_with(|local| local.pending.replace(chunk)) {
+        Ok(earlier) if !earlier.is_null() => release(earlier),
+        Ok(_) => {}
+        // The thread is exiting, and this is the last chance.
+        Err(_) => release(chunk),
+    }
+}
+
+fn chunk_layout() -> Layout {
+    Layout::from_size_align(CHUNK_SIZE, CHUNK_SIZE).unwrap()
+}
+
+/// The live count at the front of `chunk`.
+///
+/// SAFETY: `chunk` must be open.
+unsafe fn count<'a>(chunk: *mut u8) -> &'a AtomicUsize {
+    &*chunk.cast::<AtomicUsize>()
+}
+
+/// Allocate from this thread's arena, if it's in a scope and the request
+/// isn't too big. `None` means the caller should use the system allocator.
+pub(crate) fn alloc(layout: Layout) -> Option<*mut u8> {
+    if layout.size() > LARGEST || layout.align() > LARGEST {
+        return None;
+    }
+    LOCAL
+        .try_with(|local| {
+            if local.depth.get() == 0 {
+                return None;
+            }
+            let mut chunk = local.chunk.get();
+            let mut start = align_up(local.offset.get(), layout.align());
+            if chunk.is_null() || start + layout.size() > CHUNK_SIZE {
+                if !chunk.is_null() {
+                    // SAFETY: this thread opened it and hasn't retired it.
+                    unsafe { retire(chunk) };
+                }
+                chunk = open().unwrap_or(ptr::null_mut());
+                local.chunk.set(chunk);
+                if chunk.is_null() {
+                    return None;
+                }
+                start = align_up(HEADER, layout.align());
+            }
+            local.offset.set(start + layout.size());
+            // SAFETY: the chunk is open, and not retired, so it's ours to
+            // count allocations in. `start + size` is within the chunk.
+            unsafe {
+                count(chunk).fetch_add(1, SeqCst);
+                Some(chunk.add(start))
+            }
+        })
+        .ok()
+        .flatten()
+}
+
+/// Grow or shrink `ptr` where it is, if it's the last thing this thread's
+/// arena allocated and there's room: the common case of a `Vec` or `String`
+/// being built up in a scope.
+pub(crate) fn resize_in_place(ptr: *mut u8, old_size: usize, new_size: usize) -> bool {
+    if new_size > LARGEST {
+        return false;
+    }
+    LOCAL
+        .try_with(|local| {
+            let chunk = local.chunk.get();
+            if chunk.is_null() || ptr.addr() & !(CHUNK_SIZE - 1) != chunk.addr() {
+                return false;
+            }
+            let start = ptr.addr() - chunk.addr();
+            if start + old_size != local.offset.get() || start + new_size > CHUNK_SIZE {
+                return false;
+            }
+            local.offset.set(start + new_size);
+            true
+        })
+        .unwrap_or(false)
+}
+
+/// Chunks are aligned to their size, so aligning an offset aligns the
+/// address.
+fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
+
+/// The open chunk `ptr` was allocated from, if any.
+pub(crate) fn owner(ptr: *mut u8) -> Option<*mut u8> {
+    if OPEN.load(SeqCst) == 0 {
+        return None;
+    }
+    let base = ptr.addr() & !(CHUNK_SIZE - 1);
+    // Most frees inside a scope are of something just allocated in it.
+    let current = LOCAL
+        .try_with(|local| local.chunk.get())
+        .unwrap_or(ptr::null_mut());
+    if !current.is_null() && current.addr() == base {
+        return Some(current);
+    }
+    CHUNKS[..USED.load(SeqCst)]
+        .iter()
+        .map(|slot| slot.load(SeqCst))
+        .find(|chunk| !chunk.is_null() && chunk.addr() == base)
+}
+
+/// Free an allocation from `chunk`.
+///
+/// SAFETY: `chunk` must be `owner` of a live allocation, which this frees.
+pub(crate) unsafe fn free(chunk: *mut u8) {
+    if count(chunk).fetch_sub(1, SeqCst) == RETIRED | 1 {
+        defer(chunk);
+    }
+}
+
+fn open() -> Option<*mut u8> {
+    // SAFETY: the layout's size isn't zero.
+    let chunk = unsafe { System.alloc(chunk_layout()) };
+    if chunk.is_null() {
+        return None;
+    }
+    // SAFETY: the chunk is fresh, and big and aligned enough for a count.
+    unsafe { chunk.cast::<AtomicUsize>().write(AtomicUsize::new(0)) };
+    OPEN.fetch_add(1, SeqCst);
+    for (i, slot) in CHUNKS.iter().enumerate() {
+        if slot
+            .compare_exchange(ptr::null_mut(), chunk, SeqCst, SeqCst)
+            .is_ok()
+        {
+            USED.fetch_max(i + 1, SeqCst);
+            return Some(chunk);
+        }
+    }
+    OPEN.fetch_sub(1, SeqCst);
+    // SAFETY: allocated above, and never handed out.
+    unsafe { System.dealloc(chunk, chunk_layout()) };
+    None
+}
+
+/// Stop allocating from `chunk`, releasing it soon if nothing in it is live.
+///
+/// SAFETY: `chunk` must be open, and not already retired.
+unsafe fn retire(chunk: *mut u8) {
+    if count(chunk).fetch_or(RETIRED, SeqCst) == 0 {
+        defer(chunk);
+    }
+}
+
+/// SAFETY: `chunk` must be retired, with nothing in it live.
+unsafe fn release(chunk: *mut u8) {
+    // Unlist the chunk before the system can reuse its memory.
+    for slot in &CHUNKS {
+        if slot
+            .compare_exchange(chunk, ptr::null_mut(), SeqCst, SeqCst)
+            .is_ok()
+        {
+            break;
+        }
+    }
+    OPEN.fetch_sub(1, SeqCst);
+    System.dealloc(chunk, chunk_layout());
+}
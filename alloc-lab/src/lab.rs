@@ -0,0 +1,87 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ptr;
+
+use crate::{arena, stats};
+
+/// The allocator: the system allocator, counted, with arena mode. Install
+/// it with:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: Lab = Lab;
+/// ```
+pub struct Lab;
+
+unsafe impl GlobalAlloc for Lab {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        arena::collect();
+        let (ptr, from_arena) = match arena::alloc(layout) {
+            Some(ptr) => (ptr, true),
+            None => (System.alloc(layout), false),
+        };
+        if !ptr.is_null() {
+            stats::allocated(layout.size(), from_arena);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        arena::collect();
+        let (ptr, from_arena) = match arena::alloc(layout) {
+            Some(ptr) => {
+                // Chunks aren't zeroed, unlike fresh pages from `calloc`.
+                ptr::write_bytes(ptr, 0, layout.size());
+                (ptr, true)
+            }
+            None => (System.alloc_zeroed(layout), false),
+        };
+        if !ptr.is_null() {
+            stats::allocated(layout.size(), from_arena);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        arena::collect();
+        stats::freed(layout.size());
+        match arena::owner(ptr) {
+            Some(chunk) => arena::free(chunk),
+            None => System.dealloc(ptr, layout),
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        arena::collect();
+        let owner = arena::owner(ptr);
+        if owner.is_none() && !arena::active() {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                stats::reallocated(layout.size(), new_size);
+            }
+            return new;
+        }
+
+        if owner.is_some() && arena::resize_in_place(ptr, layout.size(), new_size) {
+            stats::reallocated(layout.size(), new_size);
+            return ptr;
+        }
+
+        // Into an arena, out of one, or within one: either way, no one
+        // allocator can resize the block in place, so move it.
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let new = match arena::alloc(new_layout) {
+            Some(new) => new,
+            None => System.alloc(new_layout),
+        };
+        if new.is_null() {
+            return new;
+        }
+        ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+        match owner {
+            Some(chunk) => arena::free(chunk),
+            None => System.dealloc(ptr, layout),
+        }
+        stats::reallocated(layout.size(), new_size);
+        new
+    }
+}
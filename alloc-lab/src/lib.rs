@@ -0,0 +1,17 @@
+// Watching, and changing, how a program uses the heap.
+//
+// `Lab` is a global allocator that passes each request on to the system
+// allocator and counts it: allocations, frees, bytes live, and the peak.
+// Inside `arena::scope`, it instead serves the thread's allocations from a
+// bump arena, which is as cheap as allocation gets, and frees the arena's
+// memory in bulk once everything allocated in it has been freed.
+//
+//     #[global_allocator]
+//     static ALLOC: Lab = Lab;
+
+pub mod arena;
+mod lab;
+mod stats;
+
+pub use lab::Lab;
+pub use stats::{measure, stats, LeakCheck, Stats};
@@ -0,0 +1,151 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use alloc_lab::{arena, measure, Lab, LeakCheck, Stats};
+
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+#[derive(Debug)]
+struct Arguments {
+    size: usize,
+    leak: bool,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            size: 2000,
+            leak: false,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-n" => {
+                    let n = args
+                        .next()
+                        .unwrap_or_else(|| usage_error("-n needs a size"));
+                    arguments.size = n
+                        .parse()
+                        .unwrap_or_else(|_| usage_error(&format!("bad size {:?}", n)));
+                }
+                "--leak" => arguments.leak = true,
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        arguments
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "alloc-lab - count the allocations other chapters' code makes, with and without an arena"
+    );
+    eprintln!("Usage: alloc-lab [-n SIZE] [--leak]");
+    eprintln!("  -n SIZE   records in each workload's input (default 2000)");
+    eprintln!("  --leak    leak some memory on purpose, to see the report at exit");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+/// A JSON document of `n` records, for tiny-json to parse and print.
+fn json_document(n: usize) -> String {
+    let mut text = String::from("[\n");
+    for i in 0..n {
+        let comma = if i + 1 < n { "," } else { "" };
+        writeln!(
+            text,
+            r#"  {{"id": {i}, "name": "user{i}", "score": {}, "tags": ["a", "b{}"], "active": {}}}{comma}"#,
+            i as f64 * 1.5,
+            i % 7,
+            i % 2 == 0
+        )
+        .unwrap();
+    }
+    text.push(']');
+    text
+}
+
+/// A calculator program of `n` statements, for the lexer.
+fn calc_program(n: usize) -> String {
+    let mut text = String::new();
+    for i in 0..n {
+        writeln!(text, "let x{i} = (x{} + {i}.5) * 2 ^ 3 # step {i}", i / 2).unwrap();
+    }
+    text
+}
+
+/// Each workload builds a result and returns its size, so that every
+/// allocation it makes is freed before the measurement ends.
+type Workload = fn(&str) -> usize;
+
+fn tiny_json(input: &str) -> usize {
+    let value = tiny_json::parse(input).expect("the generated document is valid");
+    tiny_json::Printer::new().print(&value).len()
+}
+
+fn lexer(input: &str) -> usize {
+    let tokens = lexer::tokenize(lexer::calc::Calc::new(), input);
+    lexer::dump(input, &tokens).len()
+}
+
+fn run(workload: Workload, input: &str, in_arena: bool) -> (Stats, Duration) {
+    let mut best = Duration::MAX;
+    let mut stats = Stats::default();
+    for _ in 0..5 {
+        let start = Instant::now();
+        let (_, measured) = if in_arena {
+            measure(|| arena::scope(|| workload(input)))
+        } else {
+            measure(|| workload(input))
+        };
+        best = best.min(start.elapsed());
+        stats = measured;
+    }
+    (stats, best)
+}
+
+fn main() {
+    let args = Arguments::parse();
+    println!(
+        "{:<10} {:<7} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "workload", "mode", "allocs", "from arena", "reallocs", "peak KiB", "time"
+    );
+    let _leak_check = LeakCheck::new();
+
+    let workloads: [(&str, Workload, String); 2] = [
+        ("tiny-json", tiny_json, json_document(args.size)),
+        ("lexer", lexer, calc_program(args.size)),
+    ];
+    for (name, workload, input) in &workloads {
+        for in_arena in [false, true] {
+            let (stats, time) = run(*workload, input, in_arena);
+            println!(
+                "{:<10} {:<7} {:>10} {:>10} {:>10} {:>10.1} {:>10.2?}",
+                name,
+                if in_arena { "arena" } else { "system" },
+                stats.allocations,
+                stats.arena_allocations,
+                stats.reallocations,
+                stats.peak_bytes as f64 / 1024.0,
+                time
+            );
+        }
+    }
+    drop(workloads);
+    println!("arena chunks still open: {}", arena::open_chunks());
+
+    if args.leak {
+        Box::leak(vec![0u8; 4096].into_boxed_slice());
+        Box::leak(Box::new(String::from("forgotten")));
+    }
+}
@@ -0,0 +1,265 @@
+// The counts `Lab` keeps. Each allocation is counted twice: once in
+// process-wide atomics, for leak checks and overall totals, and once in
+// the allocating thread's own counters, so that `measure` sees only what
+// the code it measures did, not whatever other threads were doing.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+/// A snapshot of the allocator's counts, or the difference between two.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub deallocations: usize,
+    pub reallocations: usize,
+    /// How many of `allocations` came from an arena.
+    pub arena_allocations: usize,
+    /// Bytes requested, counting each reallocation's new size.
+    pub bytes_allocated: usize,
+    pub live_bytes: usize,
+    pub peak_bytes: usize,
+}
+
+impl Stats {
+    pub fn live_allocations(&self) -> usize {
+        self.allocations.saturating_sub(self.deallocations)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations ({} from arenas), {} reallocations, {} frees; \
+             {} allocated, {} live, {} peak",
+            self.allocations,
+            self.arena_allocations,
+            self.reallocations,
+            self.deallocations,
+            Bytes(self.bytes_allocated),
+            Bytes(self.live_bytes),
+            Bytes(self.peak_bytes),
+        )
+    }
+}
+
+struct Bytes(usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let units = ["B", "KiB", "MiB", "GiB"];
+        let mut size = self.0 as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit + 1 < units.len() {
+            size /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            write!(f, "{} B", self.0)
+        } else {
+            write!(f, "{:.1} {}", size, units[unit])
+        }
+    }
+}
+
+struct Global {
+    allocations: AtomicUsize,
+    deallocations: AtomicUsize,
+    reallocations: AtomicUsize,
+    arena_allocations: AtomicUsize,
+    bytes_allocated: AtomicUsize,
+    live_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+}
+
+static GLOBAL: Global = Global {
+    allocations: AtomicUsize::new(0),
+    deallocations: AtomicUsize::new(0),
+    reallocations: AtomicUsize::new(0),
+    arena_allocations: AtomicUsize::new(0),
+    bytes_allocated: AtomicUsize::new(0),
+    live_bytes: AtomicUsize::new(0),
+    peak_bytes: AtomicUsize::new(0),
+};
+
+// A thread can free more than it allocated, memory another thread
+// allocated, so its live count can go below zero.
+struct Local {
+    allocations: Cell<usize>,
+    deallocations: Cell<usize>,
+    reallocations: Cell<usize>,
+    arena_allocations: Cell<usize>,
+    bytes_allocated: Cell<usize>,
+    live_bytes: Cell<isize>,
+    peak_bytes: Cell<isize>,
+}
+
+// A `const` initializer and no destructor: the allocator can use this
+// without allocating, and at any point in the thread's life.
+thread_local! {
+    static LOCAL: Local = const {
+        Local {
+            allocations: Cell::new(0),
+            deallocations: Cell::new(0),
+            reallocations: Cell::new(0),
+            arena_allocations: Cell::new(0),
+            bytes_allocated: Cell::new(0),
+            live_bytes: Cell::new(0),
+            peak_bytes: Cell::new(0),
+        }
+    };
+}
+
+fn bump(cell: &Cell<usize>, n: usize) {
+    cell.set(cell.get().wrapping_add(n));
+}
+
+pub(crate) fn allocated(size: usize, arena: bool) {
+    GLOBAL.allocations.fetch_add(1, Relaxed);
+    GLOBAL.arena_allocations.fetch_add(arena as usize, Relaxed);
+    resized(0, size);
+    let _ = LOCAL.try_with(|local| {
+        bump(&local.allocations, 1);
+        bump(&local.arena_allocations, arena as usize);
+    });
+}
+
+pub(crate) fn reallocated(old_size: usize, new_size: usize) {
+    GLOBAL.reallocations.fetch_add(1, Relaxed);
+    resized(old_size, new_size);
+    let _ = LOCAL.try_with(|local| bump(&local.reallocations, 1));
+}
+
+pub(crate) fn freed(size: usize) {
+    GLOBAL.deallocations.fetch_add(1, Relaxed);
+    GLOBAL.live_bytes.fetch_sub(size, Relaxed);
+    let _ = LOCAL.try_with(|local| {
+        bump(&local.deallocations, 1);
+        local.live_bytes.set(local.live_bytes.get() - size as isize);
+    });
+}
+
+/// Account for a block of `old_size` bytes becoming `new_size` bytes.
+fn resized(old_size: usize, new_size: usize) {
+    GLOBAL.bytes_allocated.fetch_add(new_size, Relaxed);
+    let live = GLOBAL
+        .live_bytes
+        .fetch_add(new_size.wrapping_sub(old_size), Relaxed)
+        .wrapping_add(new_size.wrapping_sub(old_size));
+    GLOBAL.peak_bytes.fetch_max(live, Relaxed);
+    let _ = LOCAL.try_with(|local| {
+        bump(&local.bytes_allocated, new_size);
+        let live = local.live_bytes.get() + new_size as isize - old_size as isize;
+        local.live_bytes.set(live);
+        local.peak_bytes.set(local.peak_bytes.get().max(live));
+    });
+}
+
+/// The process-wide counts so far.
+pub fn stats() -> Stats {
+    Stats {
+        allocations: GLOBAL.allocations.load(Relaxed),
+        deallocations: GLOBAL.deallocations.load(Relaxed),
+        reallocations: GLOBAL.reallocations.load(Relaxed),
+        arena_allocations: GLOBAL.arena_allocations.load(Relaxed),
+        bytes_allocated: GLOBAL.bytes_allocated.load(Relaxed),
+        live_bytes: GLOBAL.live_bytes.load(Relaxed),
+        peak_bytes: GLOBAL.peak_bytes.load(Relaxed),
+    }
+}
+
+/// Run `f`, and return what it did on this thread: what it allocated and
+/// freed, how many bytes it left live, and how far above the starting
+/// point its usage peaked. All zeros unless `Lab` is the global allocator.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Stats) {
+    let before = LOCAL.with(|local| {
+        local.peak_bytes.set(local.live_bytes.get());
+        snapshot(local)
+    });
+    let result = f();
+    let after = LOCAL.with(snapshot);
+    let base = before.live;
+    let stats = Stats {
+        allocations: after.stats.allocations - before.stats.allocations,
+        deallocations: after.stats.deallocations - before.stats.deallocations,
+        reallocations: after.stats.reallocations - before.stats.reallocations,
+        arena_allocations: after.stats.arena_allocations - before.stats.arena_allocations,
+        bytes_allocated: after.stats.bytes_allocated - before.stats.bytes_allocated,
+        live_bytes: (after.live - base).max(0) as usize,
+        peak_bytes: (after.peak - base).max(0) as usize,
+    };
+    (result, stats)
+}
+
+struct Snapshot {
+    stats: Stats,
+    live: isize,
+    peak: isize,
+}
+
+fn snapshot(local: &Local) -> Snapshot {
+    Snapshot {
+        stats: Stats {
+            allocations: local.allocations.get(),
+            deallocations: local.deallocations.get(),
+            reallocations: local.reallocations.get(),
+            arena_allocations: local.arena_allocations.get(),
+            bytes_allocated: local.bytes_allocated.get(),
+            live_bytes: 0,
+            peak_bytes: 0,
+        },
+        live: local.live_bytes.get(),
+        peak: local.peak_bytes.get(),
+    }
+}
+
+/// Reports, when dropped, any allocations made since it was created that
+/// are still live. Create one at the top of `main` for a report at exit:
+///
+/// ```text
+/// leak check: 3 allocations (4.0 KiB) still live
+/// ```
+///
+/// The counts are process-wide, so anything allocated once and kept for
+/// good counts too: stdout's buffer, say, if the first `println!` comes
+/// after the check starts.
+pub struct LeakCheck {
+    start: Stats,
+}
+
+impl LeakCheck {
+    pub fn new() -> LeakCheck {
+        LeakCheck { start: stats() }
+    }
+
+    /// The allocations made since the check started that are still live,
+    /// and their size.
+    pub fn leaked(&self) -> (usize, usize) {
+        let now = stats();
+        (
+            now.live_allocations()
+                .saturating_sub(self.start.live_allocations()),
+            now.live_bytes.saturating_sub(self.start.live_bytes),
+        )
+    }
+}
+
+impl Default for LeakCheck {
+    fn default() -> LeakCheck {
+        LeakCheck::new()
+    }
+}
+
+impl Drop for LeakCheck {
+    fn drop(&mut self) {
+        match self.leaked() {
+            (0, _) => eprintln!("leak check: nothing leaked"),
+            (count, bytes) => eprintln!(
+                "leak check: {} allocations ({}) still live",
+                count,
+                Bytes(bytes)
+            ),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::alloc::{self, Layout};
+use std::sync::{mpsc, Mutex, MutexGuard};
+use std::{panic, thread};
+
+use alloc_lab::arena::{self, CHUNK_SIZE, LARGEST, MAX_CHUNKS};
+use alloc_lab::{measure, Lab};
+
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+// Chunks are shared by the whole process, so tests that count them take
+// turns.
+fn serial() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Wait for other threads to release what they've emptied: a thread
+/// releases its last chunk as it exits, which can be after a scoped thread
+/// is joined.
+fn settle(expected: usize) {
+    for _ in 0..1000 {
+        if arena::open_chunks() == expected {
+            return;
+        }
+        thread::yield_now();
+    }
+    assert_eq!(arena::open_chunks(), expected);
+}
+
+#[test]
+fn allocations_come_from_the_arena() {
+    let _serial = serial();
+    let (total, stats) = measure(|| {
+        arena::scope(|| {
+            let words: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+            words.iter().map(String::len).sum::<usize>()
+        })
+    });
+    assert_eq!(total, 190);
+    assert_eq!(stats.allocations, 101);
+    assert_eq!(stats.arena_allocations, 101);
+    assert_eq!(stats.live_bytes, 0);
+
+    let (_, stats) = measure(|| Box::new(1));
+    assert_eq!(stats.arena_allocations, 0);
+    assert!(!arena::active());
+}
+
+#[test]
+fn chunks_are_released_when_empty() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    arena::scope(|| {
+        let _b = Box::new(1);
+        assert_eq!(arena::open_chunks(), base + 1);
+    });
+    assert_eq!(arena::open_chunks(), base);
+    // A scope that allocates nothing doesn't take a chunk.
+    arena::scope(|| assert_eq!(arena::open_chunks(), base));
+}
+
+#[test]
+fn values_can_outlive_their_scope() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    let words = arena::scope(|| (0..500).map(|i| format!("word {}", i)).collect::<Vec<_>>());
+    // The chunk stays while anything in it is live.
+    assert_eq!(arena::open_chunks(), base + 1);
+    // Allocations after the scope aren't from its chunk, and don't
+    // overwrite anything in it.
+    let others: Vec<String> = (0..500).map(|i| format!("other {}", i)).collect();
+    for (i, word) in words.iter().enumerate() {
+        assert_eq!(*word, format!("word {}", i));
+    }
+    drop(others);
+    drop(words);
+    assert_eq!(arena::open_chunks(), base);
+}
+
+#[test]
+fn freeing_on_another_thread() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    let (sender, receiver) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        for value in receiver {
+            let value: Vec<u64> = value;
+            assert_eq!(value.iter().sum::<u64>(), 45);
+        }
+    });
+    arena::scope(|| {
+        for _ in 0..20 {
+            sender.send((0..10).collect::<Vec<u64>>()).unwrap();
+        }
+    });
+    drop(sender);
+    worker.join().unwrap();
+    assert_eq!(arena::open_chunks(), base);
+}
+
+#[test]
+fn large_allocations_bypass_the_arena() {
+    let _serial = serial();
+    let (_, stats) = measure(|| {
+        arena::scope(|| {
+            let big = vec![1u8; LARGEST + 1];
+            let small = vec![1u8; LARGEST];
+            big.len() + small.len()
+        })
+    });
+    assert_eq!((stats.allocations, stats.arena_allocations), (2, 1));
+}
+
+#[test]
+fn filling_chunks() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    let count = 3 * CHUNK_SIZE / 1024;
+    let blocks = arena::scope(|| {
+        (0..count)
+            .map(|i| vec![(i % 251) as u8; 1000])
+            .collect::<Vec<_>>()
+    });
+    assert!(arena::open_chunks() >= base + 3);
+    for (i, block) in blocks.iter().enumerate() {
+        assert!(block.iter().all(|&b| b == (i % 251) as u8));
+    }
+    drop(blocks);
+    assert_eq!(arena::open_chunks(), base);
+}
+
+#[test]
+fn alignment() {
+    let _serial = serial();
+    arena::scope(|| {
+        let mut blocks = Vec::new();
+        for shift in 0..13 {
+            for size in [1, 3, 64, 100] {
+                let layout = Layout::from_size_align(size, 1 << shift).unwrap();
+                // SAFETY: the layout's size isn't zero.
+                let ptr = unsafe { alloc::alloc(layout) };
+                assert!(!ptr.is_null());
+                assert_eq!(ptr.addr() % layout.align(), 0, "{:?}", layout);
+                // SAFETY: the block is `size` bytes.
+                unsafe { ptr.write_bytes(shift as u8, size) };
+                blocks.push((ptr, layout));
+            }
+        }
+        for (i, &(ptr, layout)) in blocks.iter().enumerate() {
+            // SAFETY: allocated above with this layout, and written.
+            unsafe {
+                assert_eq!(*ptr.add(layout.size() - 1), (i / 4) as u8);
+                alloc::dealloc(ptr, layout);
+            }
+        }
+    });
+}
+
+#[test]
+fn zeroed() {
+    let _serial = serial();
+    arena::scope(|| {
+        let garbage = vec![0xffu8; 4096];
+        drop(garbage);
+        let layout = Layout::array::<u64>(512).unwrap();
+        // SAFETY: the layout's size isn't zero.
+        let ptr = unsafe { alloc::alloc_zeroed(layout) }.cast::<u64>();
+        // SAFETY: 512 zeroed u64s.
+        let values = unsafe { std::slice::from_raw_parts(ptr, 512) };
+        assert!(values.iter().all(|&v| v == 0));
+        // SAFETY: allocated above with this layout.
+        unsafe { alloc::dealloc(ptr.cast(), layout) };
+    });
+}
+
+#[test]
+fn growing_across_modes() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    // Allocated by the system, grown in an arena.
+    let mut numbers: Vec<u32> = (0..10).collect();
+    arena::scope(|| numbers.extend(10..1000));
+    // Allocated in an arena, grown after it.
+    let mut text = arena::scope(|| String::from("arena"));
+    text.push_str(&"!".repeat(1000));
+    numbers.extend(1000..2000);
+    numbers.shrink_to_fit();
+
+    assert!(numbers.iter().copied().eq(0..2000));
+    assert_eq!(text.len(), 1005);
+    assert!(text.starts_with("arena!!!"));
+    drop((numbers, text));
+    assert_eq!(arena::open_chunks(), base);
+}
+
+#[test]
+fn growing_in_place() {
+    let _serial = serial();
+    arena::scope(|| {
+        let mut text = String::with_capacity(8);
+        let start = text.as_ptr();
+        for _ in 0..100 {
+            text.push_str("grow ");
+        }
+        // The most recent allocation grows where it is.
+        assert_eq!(text.as_ptr(), start);
+
+        let mut first = Vec::<u8>::with_capacity(8);
+        let _second = Box::new(0u8);
+        let before = first.as_ptr();
+        first.extend([0; 100]);
+        // Anything older has to move.
+        assert_ne!(first.as_ptr(), before);
+    });
+}
+
+#[test]
+fn nesting() {
+    let _serial = serial();
+    assert!(!arena::active());
+    arena::scope(|| {
+        arena::scope(|| assert!(arena::active()));
+        assert!(arena::active());
+        let (_, stats) = measure(|| Box::new(1));
+        assert_eq!(stats.arena_allocations, 1);
+    });
+    assert!(!arena::active());
+}
+
+#[test]
+fn panics_end_the_scope() {
+    let _serial = serial();
+    let result = panic::catch_unwind(|| {
+        arena::scope(|| {
+            let _data = Box::new([1, 2, 3]);
+            panic!("in the arena");
+        })
+    });
+    let message = result.unwrap_err();
+    assert_eq!(message.downcast_ref::<&str>(), Some(&"in the arena"));
+    // The chunk may stay open: printing the panic allocates caches that
+    // last the life of the process.
+    assert!(!arena::active());
+}
+
+#[test]
+fn running_out_of_chunks() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    // Each scope's chunk is kept open by the box that escapes it.
+    let (boxes, stats) = measure(|| {
+        (0..MAX_CHUNKS + 5)
+            .map(|i| arena::scope(|| Box::new(i)))
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(arena::open_chunks(), MAX_CHUNKS);
+    // Past the limit, scopes quietly use the system allocator.
+    assert_eq!(stats.arena_allocations, MAX_CHUNKS - base);
+    assert!(boxes.iter().enumerate().all(|(i, b)| **b == i));
+    drop(boxes);
+    assert_eq!(arena::open_chunks(), base);
+}
+
+#[test]
+fn threads_sharing_values() {
+    let _serial = serial();
+    let base = arena::open_chunks();
+    let rounds = if cfg!(miri) { 20 } else { 2000 };
+    let (sender, receiver) = mpsc::sync_channel::<Vec<usize>>(16);
+    let receiver = Mutex::new(receiver);
+    thread::scope(|s| {
+        for t in 0..4 {
+            let sender = sender.clone();
+            let receiver = &receiver;
+            s.spawn(move || {
+                arena::scope(|| {
+                    for i in 0..rounds {
+                        sender.send(vec![t, i]).unwrap();
+                        // Free whatever some thread sent.
+                        let value = receiver.lock().unwrap().recv().unwrap();
+                        assert_eq!(value.len(), 2);
+                    }
+                })
+            });
+        }
+    });
+    drop(sender);
+    settle(base);
+}
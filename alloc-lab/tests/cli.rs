@@ -0,0 +1,48 @@
+use std::process::Command;
+
+#[test]
+fn report() {
+    let output = Command::new(env!("CARGO_BIN_EXE_alloc-lab"))
+        .args(["-n", "20"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .skip(1)
+        .take(4)
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    for row in &rows {
+        let allocations: usize = row[2].parse().unwrap();
+        let from_arena: usize = row[3].parse().unwrap();
+        assert!(allocations > 20);
+        let expected = if row[1] == "arena" { allocations } else { 0 };
+        assert_eq!(from_arena, expected, "{:?}", row);
+    }
+    // The workloads allocate the same either way.
+    assert_eq!(rows[0][2], rows[1][2]);
+    assert_eq!(rows[2][2], rows[3][2]);
+    assert!(
+        stdout.ends_with("arena chunks still open: 0\n"),
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "leak check: nothing leaked\n"
+    );
+}
+
+#[test]
+fn leak_report() {
+    let output = Command::new(env!("CARGO_BIN_EXE_alloc-lab"))
+        .args(["-n", "5", "--leak"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "leak check: 3 allocations (4.0 KiB) still live\n"
+    );
+}
@@ -0,0 +1,20 @@
+use alloc_lab::{Lab, LeakCheck};
+
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+fn main() {
+    let check = LeakCheck::new();
+    let kept = vec![0u8; 100];
+    assert_eq!(check.leaked(), (1, 100));
+    drop(kept);
+    assert_eq!(check.leaked(), (0, 0));
+
+    // Kept to the end rather than truly leaked, so that Miri's own leak
+    // check, when the tests run under it, doesn't object.
+    let forgotten = Box::new([0u64; 4]);
+    assert_eq!(check.leaked(), (1, 32));
+    std::mem::forget(check);
+    drop(forgotten);
+    println!("leak check: ok");
+}
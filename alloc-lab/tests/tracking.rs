@@ -0,0 +1,101 @@
+use std::thread;
+
+use alloc_lab::{measure, stats, Lab, Stats};
+
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+#[test]
+fn one_box() {
+    let (boxed, stats) = measure(|| Box::new(7u64));
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.deallocations, 0);
+    assert_eq!(stats.bytes_allocated, 8);
+    assert_eq!((stats.live_bytes, stats.peak_bytes), (8, 8));
+    assert_eq!(stats.live_allocations(), 1);
+
+    let ((), stats) = measure(|| drop(boxed));
+    assert_eq!((stats.allocations, stats.deallocations), (0, 1));
+    assert_eq!((stats.live_bytes, stats.peak_bytes), (0, 0));
+}
+
+#[test]
+fn growing_a_vec() {
+    let (_, stats) = measure(|| {
+        let mut v = Vec::new();
+        for i in 0..100u64 {
+            v.push(i);
+        }
+        v.len()
+    });
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.deallocations, 1);
+    // Doubling from 4 elements: 8, 16, 32, 64, then 128.
+    assert_eq!(stats.reallocations, 5);
+    assert_eq!(stats.bytes_allocated, 8 * (4 + 8 + 16 + 32 + 64 + 128));
+    assert_eq!(stats.live_bytes, 0);
+    assert_eq!(stats.peak_bytes, 128 * 8);
+}
+
+#[test]
+fn peak_is_the_high_water_mark() {
+    let (_, stats) = measure(|| {
+        drop(vec![0u8; 10_000]);
+        let small = vec![0u8; 100];
+        let medium = vec![0u8; 1000];
+        (small, medium)
+    });
+    assert_eq!(stats.peak_bytes, 10_000);
+    assert_eq!(stats.live_bytes, 1100);
+}
+
+#[test]
+fn measurements_are_per_thread() {
+    let (_, stats) = measure(|| {
+        thread::scope(|s| {
+            s.spawn(|| vec![0u8; 1 << 16]);
+        });
+    });
+    // Whatever starting a thread allocates here, the other thread's big
+    // vector isn't counted.
+    assert!(stats.bytes_allocated < 1 << 16, "{:?}", stats);
+}
+
+#[test]
+fn freeing_another_threads_memory() {
+    let shared = thread::spawn(|| vec![1u32; 256]).join().unwrap();
+    let ((), stats) = measure(|| drop(shared));
+    assert_eq!((stats.allocations, stats.deallocations), (0, 1));
+    // This thread's live bytes went down, which `measure` reports as zero.
+    assert_eq!(stats.live_bytes, 0);
+}
+
+#[test]
+fn process_wide_counts() {
+    let before = stats();
+    let boxes: Vec<Box<u32>> = (0..10).map(Box::new).collect();
+    let after = stats();
+    // Other tests are allocating at the same time, so these are lower bounds.
+    assert!(after.allocations >= before.allocations + 11);
+    assert!(after.bytes_allocated >= before.bytes_allocated + 40);
+    assert!(after.peak_bytes >= after.live_bytes);
+    drop(boxes);
+}
+
+#[test]
+fn display() {
+    let stats = Stats {
+        allocations: 12,
+        deallocations: 10,
+        reallocations: 3,
+        arena_allocations: 4,
+        bytes_allocated: 5000,
+        live_bytes: 900,
+        peak_bytes: 3 << 20,
+    };
+    assert_eq!(
+        stats.to_string(),
+        "12 allocations (4 from arenas), 3 reallocations, 10 frees; \
+         4.9 KiB allocated, 900 B live, 3.0 MiB peak"
+    );
+}
@@ -0,0 +1,133 @@
+// One argument in a command's description.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// `--verbose`: present or not, or counted if repeated.
+    Flag,
+    /// `--limit 64k`: takes a value.
+    Option,
+    /// Identified by position rather than by name.
+    Positional,
+}
+
+#[derive(Clone, Debug)]
+pub struct Arg<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) kind: Kind,
+    pub(crate) short: Option<char>,
+    pub(crate) help: Option<&'a str>,
+    pub(crate) value_name: Option<&'a str>,
+    pub(crate) default: Option<&'a str>,
+    pub(crate) required: bool,
+    pub(crate) multiple: bool,
+}
+
+impl<'a> Arg<'a> {
+    fn new(name: &'a str, kind: Kind) -> Arg<'a> {
+        Arg {
+            name,
+            kind,
+            short: None,
+            help: None,
+            value_name: None,
+            default: None,
+            required: kind == Kind::Positional,
+            multiple: false,
+        }
+    }
+
+    /// A flag, given as `--NAME`, or `-c` with `short('c')`.
+    pub fn flag(name: &'a str) -> Arg<'a> {
+        Arg::new(name, Kind::Flag)
+    }
+
+    /// An option taking a value: `--NAME VALUE`, `--NAME=VALUE`, and with
+    /// `short('c')`, `-c VALUE` or `-cVALUE`. Optional unless `required`
+    /// is called.
+    pub fn option(name: &'a str) -> Arg<'a> {
+        Arg::new(name, Kind::Option)
+    }
+
+    /// A positional argument. Required unless `optional` is called.
+    pub fn positional(name: &'a str) -> Arg<'a> {
+        Arg::new(name, Kind::Positional)
+    }
+
+    pub fn short(mut self, short: char) -> Arg<'a> {
+        assert!(
+            self.kind != Kind::Positional,
+            "positional {} can't have a short name",
+            self.name
+        );
+        assert!(
+            short.is_alphanumeric(),
+            "short name {:?} for {} isn't a letter or digit",
+            short,
+            self.name
+        );
+        self.short = Some(short);
+        self
+    }
+
+    pub fn help(mut self, help: &'a str) -> Arg<'a> {
+        self.help = Some(help);
+        self
+    }
+
+    /// What to call the value in help text. Defaults to the name in
+    /// capitals.
+    pub fn value_name(mut self, value_name: &'a str) -> Arg<'a> {
+        assert!(self.kind != Kind::Flag, "flag {} takes no value", self.name);
+        self.value_name = Some(value_name);
+        self
+    }
+
+    /// The value to report when the argument isn't given.
+    pub fn default(mut self, default: &'a str) -> Arg<'a> {
+        assert!(self.kind != Kind::Flag, "flag {} takes no value", self.name);
+        self.default = Some(default);
+        self.required = false;
+        self
+    }
+
+    pub fn required(mut self) -> Arg<'a> {
+        assert!(
+            self.kind != Kind::Flag,
+            "flag {} can't be required",
+            self.name
+        );
+        self.required = true;
+        self
+    }
+
+    pub fn optional(mut self) -> Arg<'a> {
+        self.required = false;
+        self
+    }
+
+    /// Allow the option to be repeated, or the positional to take every
+    /// remaining argument. `Matches::values` returns them all.
+    pub fn multiple(mut self) -> Arg<'a> {
+        assert!(
+            self.kind != Kind::Flag,
+            "flags are counted instead; see Matches::count"
+        );
+        self.multiple = true;
+        self
+    }
+
+    pub(crate) fn value_label(&self) -> String {
+        match self.value_name {
+            Some(name) => name.to_string(),
+            None => self.name.to_uppercase().replace('-', "_"),
+        }
+    }
+
+    /// How errors refer to the argument: `--name` or `NAME`.
+    pub(crate) fn display(&self) -> String {
+        match self.kind {
+            Kind::Positional => self.value_label(),
+            Kind::Flag | Kind::Option => format!("--{}", self.name),
+        }
+    }
+}
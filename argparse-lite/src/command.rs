@@ -0,0 +1,317 @@
+// Describing a command, and parsing a command line against it.
+
+use std::collections::HashMap;
+
+use crate::arg::{Arg, Kind};
+use crate::error::{Error, ErrorKind};
+use crate::help;
+use crate::matches::Matches;
+
+#[derive(Clone, Debug)]
+pub struct Command<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) about: Option<&'a str>,
+    pub(crate) args: Vec<Arg<'a>>,
+    pub(crate) subcommands: Vec<Command<'a>>,
+    pub(crate) footer: Option<&'a str>,
+}
+
+impl<'a> Command<'a> {
+    pub fn new(name: &'a str) -> Command<'a> {
+        Command {
+            name,
+            about: None,
+            args: Vec::new(),
+            subcommands: Vec::new(),
+            footer: None,
+        }
+    }
+
+    /// A one-line description, shown after the name in help, and beside
+    /// the command in its parent's list of subcommands.
+    pub fn about(mut self, about: &'a str) -> Command<'a> {
+        self.about = Some(about);
+        self
+    }
+
+    /// Text to show at the end of the help.
+    pub fn footer(mut self, footer: &'a str) -> Command<'a> {
+        self.footer = Some(footer);
+        self
+    }
+
+    /// Add an argument.
+    ///
+    /// # Panics
+    ///
+    /// Mistakes in a description are bugs in the program, not the user's
+    /// fault, so they panic: a name or short name used twice, or used by
+    /// `--help`; a required positional after an optional one; anything
+    /// after a `multiple` positional; positionals in a command that has
+    /// subcommands.
+    pub fn arg(mut self, arg: Arg<'a>) -> Command<'a> {
+        assert!(arg.name != "help", "--help is built in");
+        assert!(arg.short != Some('h'), "-h is built in");
+        for other in &self.args {
+            assert!(other.name != arg.name, "{} declared twice", arg.name);
+            if let Some(short) = arg.short {
+                assert!(other.short != Some(short), "-{} declared twice", short);
+            }
+        }
+        if arg.kind == Kind::Positional {
+            assert!(
+                self.subcommands.is_empty(),
+                "a command with subcommands can't take positionals"
+            );
+            if let Some(last) = self.positionals().last() {
+                assert!(
+                    !last.multiple,
+                    "no positional can follow {}, which takes the rest",
+                    last.name
+                );
+                assert!(
+                    !arg.required || last.required,
+                    "required {} can't follow optional {}",
+                    arg.name,
+                    last.name
+                );
+            }
+        }
+        self.args.push(arg);
+        self
+    }
+
+    /// Add a subcommand. Once a command has subcommands, one must be given.
+    pub fn subcommand(mut self, subcommand: Command<'a>) -> Command<'a> {
+        assert!(
+            self.positionals().next().is_none(),
+            "a command with positionals can't have subcommands"
+        );
+        assert!(
+            self.find_subcommand(subcommand.name).is_none(),
+            "command {} declared twice",
+            subcommand.name
+        );
+        self.subcommands.push(subcommand);
+        self
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The help text that `--help` prints.
+    pub fn render_help(&self) -> String {
+        help::render(self, self.name)
+    }
+
+    pub(crate) fn positionals(&self) -> impl Iterator<Item = &Arg<'a>> {
+        self.args.iter().filter(|arg| arg.kind == Kind::Positional)
+    }
+
+    pub(crate) fn find(&self, name: &str) -> Option<&Arg<'a>> {
+        self.args.iter().find(|arg| arg.name == name)
+    }
+
+    fn find_long(&self, name: &str) -> Option<&Arg<'a>> {
+        self.args
+            .iter()
+            .find(|arg| arg.kind != Kind::Positional && arg.name == name)
+    }
+
+    fn find_short(&self, short: char) -> Option<&Arg<'a>> {
+        self.args.iter().find(|arg| arg.short == Some(short))
+    }
+
+    fn find_subcommand(&self, name: &str) -> Option<&Command<'a>> {
+        self.subcommands.iter().find(|command| command.name == name)
+    }
+
+    /// Parse a command line, not including the program's name: pass
+    /// `env::args().skip(1)`.
+    pub fn parse<I>(&self, args: I) -> Result<Matches<'_>, Error>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let args: Vec<String> = args.into_iter().map(Into::into).collect();
+        Parser {
+            command: self,
+            path: self.name.to_string(),
+            flags: HashMap::new(),
+            values: HashMap::new(),
+        }
+        .parse(&args)
+    }
+}
+
+/// The state of parsing one command's part of the command line.
+struct Parser<'c, 'a> {
+    command: &'c Command<'a>,
+    /// The command's name, preceded by its parents'.
+    path: String,
+    flags: HashMap<&'a str, u32>,
+    values: HashMap<&'a str, Vec<String>>,
+}
+
+impl<'c, 'a> Parser<'c, 'a> {
+    fn error(&self, kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            help: help::render(self.command, &self.path),
+        }
+    }
+
+    fn parse(mut self, args: &[String]) -> Result<Matches<'c>, Error> {
+        let mut positionals = Vec::new();
+        let mut only_positionals = false;
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            if only_positionals || arg == "-" || !arg.starts_with('-') {
+                if self.command.subcommands.is_empty() {
+                    positionals.push(arg.clone());
+                    continue;
+                }
+                let Some(subcommand) = self.command.find_subcommand(arg) else {
+                    return Err(self.error(ErrorKind::UnknownCommand(arg.clone())));
+                };
+                let remaining: Vec<String> = rest.cloned().collect();
+                let matches = Parser {
+                    command: subcommand,
+                    path: format!("{} {}", self.path, subcommand.name),
+                    flags: HashMap::new(),
+                    values: HashMap::new(),
+                }
+                .parse(&remaining)?;
+                return self.finish(positionals, Some(matches));
+            }
+            if arg == "--" {
+                only_positionals = true;
+            } else if let Some(long) = arg.strip_prefix("--") {
+                self.long(long, &mut rest)?;
+            } else {
+                self.shorts(&arg[1..], &mut rest)?;
+            }
+        }
+        if !self.command.subcommands.is_empty() {
+            return Err(self.error(ErrorKind::MissingCommand));
+        }
+        self.finish(positionals, None)
+    }
+
+    /// `--name`, `--name value`, or `--name=value`.
+    fn long(&mut self, long: &str, rest: &mut std::slice::Iter<'_, String>) -> Result<(), Error> {
+        let (name, inline) = match long.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (long, None),
+        };
+        if name == "help" {
+            return Err(self.error(ErrorKind::HelpRequested));
+        }
+        let Some(arg) = self.command.find_long(name) else {
+            return Err(self.error(ErrorKind::UnknownOption(format!("--{}", name))));
+        };
+        match arg.kind {
+            Kind::Flag if inline.is_some() => {
+                Err(self.error(ErrorKind::UnexpectedValue(arg.display())))
+            }
+            Kind::Flag => {
+                *self.flags.entry(arg.name).or_default() += 1;
+                Ok(())
+            }
+            _ => {
+                let value = match inline {
+                    Some(value) => value,
+                    None => self.next_value(arg, rest)?,
+                };
+                self.push_value(arg, value)
+            }
+        }
+    }
+
+    /// A cluster of short names: `-v`, `-xvf FILE`, or `-fFILE`.
+    fn shorts(
+        &mut self,
+        cluster: &str,
+        rest: &mut std::slice::Iter<'_, String>,
+    ) -> Result<(), Error> {
+        for (i, short) in cluster.char_indices() {
+            if short == 'h' {
+                return Err(self.error(ErrorKind::HelpRequested));
+            }
+            let Some(arg) = self.command.find_short(short) else {
+                return Err(self.error(ErrorKind::UnknownOption(format!("-{}", short))));
+            };
+            if arg.kind == Kind::Flag {
+                *self.flags.entry(arg.name).or_default() += 1;
+                continue;
+            }
+            // An option takes the rest of the cluster as its value, or
+            // failing that, the next argument.
+            let attached = &cluster[i + short.len_utf8()..];
+            let value = if attached.is_empty() {
+                self.next_value(arg, rest)?
+            } else {
+                attached.to_string()
+            };
+            return self.push_value(arg, value);
+        }
+        Ok(())
+    }
+
+    /// The argument after an option is its value, even if it starts with
+    /// `-`: `--offset -5` means what it looks like.
+    fn next_value(
+        &self,
+        arg: &Arg<'a>,
+        rest: &mut std::slice::Iter<'_, String>,
+    ) -> Result<String, Error> {
+        rest.next()
+            .cloned()
+            .ok_or_else(|| self.error(ErrorKind::MissingValue(arg.display())))
+    }
+
+    fn push_value(&mut self, arg: &Arg<'a>, value: String) -> Result<(), Error> {
+        let values = self.values.entry(arg.name).or_default();
+        if !values.is_empty() && !arg.multiple {
+            return Err(self.error(ErrorKind::Repeated(arg.display())));
+        }
+        values.push(value);
+        Ok(())
+    }
+
+    /// Hand out the positionals, and check that nothing required is
+    /// missing.
+    fn finish(
+        mut self,
+        positionals: Vec<String>,
+        subcommand: Option<Matches<'c>>,
+    ) -> Result<Matches<'c>, Error> {
+        let mut positionals = positionals.into_iter();
+        for arg in self.command.positionals() {
+            let values: Vec<String> = if arg.multiple {
+                positionals.by_ref().collect()
+            } else {
+                positionals.next().into_iter().collect()
+            };
+            if !values.is_empty() {
+                self.values.insert(arg.name, values);
+            }
+        }
+        if let Some(extra) = positionals.next() {
+            return Err(self.error(ErrorKind::UnexpectedArgument(extra)));
+        }
+        for arg in &self.command.args {
+            if arg.required && !self.values.contains_key(arg.name) {
+                return Err(self.error(ErrorKind::MissingArgument(arg.display())));
+            }
+        }
+        Ok(Matches {
+            command: self.command,
+            path: self.path,
+            flags: self.flags,
+            values: self.values,
+            subcommand: subcommand.map(Box::new),
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::process;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// `-h` or `--help` was given. Not a mistake, but parsing stops all
+    /// the same, and the help text is in `Error::help`.
+    HelpRequested,
+    UnknownOption(String),
+    /// An option came last, with no value after it.
+    MissingValue(String),
+    /// `--flag=value`, for a flag that takes none.
+    UnexpectedValue(String),
+    /// An option that isn't `multiple`, given twice.
+    Repeated(String),
+    /// A required positional or option wasn't given.
+    MissingArgument(String),
+    /// More positionals than the command takes.
+    UnexpectedArgument(String),
+    UnknownCommand(String),
+    MissingCommand,
+    /// `Matches::get` couldn't parse a value.
+    InvalidValue {
+        arg: String,
+        value: String,
+        reason: String,
+    },
+}
+
+/// A problem with the command line, along with the help text of the
+/// command it was found in, for showing the user.
+#[derive(Clone, Debug)]
+pub struct Error {
+    pub(crate) kind: ErrorKind,
+    pub(crate) help: String,
+}
+
+impl Error {
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn help(&self) -> &str {
+        &self.help
+    }
+
+    /// 0 for a help request, 2 for a usage error, as is conventional.
+    pub fn exit_code(&self) -> i32 {
+        match self.kind {
+            ErrorKind::HelpRequested => 0,
+            _ => 2,
+        }
+    }
+
+    /// Print the help, to stdout if it was asked for, or to stderr
+    /// followed by the error, and exit.
+    pub fn exit(&self) -> ! {
+        if self.kind == ErrorKind::HelpRequested {
+            print!("{}", self.help);
+        } else {
+            eprint!("{}", self.help);
+            eprintln!("Error: {}", self);
+        }
+        process::exit(self.exit_code());
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::HelpRequested => write!(f, "help requested"),
+            ErrorKind::UnknownOption(option) => write!(f, "unknown option {}", option),
+            ErrorKind::MissingValue(option) => write!(f, "{} needs a value", option),
+            ErrorKind::UnexpectedValue(flag) => write!(f, "{} doesn't take a value", flag),
+            ErrorKind::Repeated(option) => write!(f, "{} given more than once", option),
+            ErrorKind::MissingArgument(arg) => write!(f, "missing {}", arg),
+            ErrorKind::UnexpectedArgument(arg) => write!(f, "unexpected argument {:?}", arg),
+            ErrorKind::UnknownCommand(command) => write!(f, "unknown command {}", command),
+            ErrorKind::MissingCommand => write!(f, "no command given"),
+            ErrorKind::InvalidValue { arg, value, reason } => {
+                write!(f, "bad value {:?} for {}: {}", value, arg, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
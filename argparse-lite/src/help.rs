@@ -0,0 +1,105 @@
+// Generating help text from a command's description.
+
+use crate::arg::{Arg, Kind};
+use crate::command::Command;
+
+/// The help for `command`, which is reached as `path`: its name preceded
+/// by its parents'.
+pub(crate) fn render(command: &Command<'_>, path: &str) -> String {
+    let mut out = String::new();
+    match command.about {
+        Some(about) => out.push_str(&format!("{} - {}\n", path, about)),
+        None => out.push_str(&format!("{}\n", path)),
+    }
+
+    let mut usage = format!("Usage: {}", path);
+    if command.args.iter().any(|arg| arg.kind != Kind::Positional) {
+        usage.push_str(" [options]");
+    }
+    for arg in command.positionals() {
+        let mut word = arg.value_label();
+        if arg.multiple {
+            word.push_str("...");
+        }
+        if !arg.required {
+            word = format!("[{}]", word);
+        }
+        usage.push(' ');
+        usage.push_str(&word);
+    }
+    if !command.subcommands.is_empty() {
+        usage.push_str(" <command>");
+    }
+    out.push_str(&usage);
+    out.push('\n');
+
+    let positionals: Vec<_> = command
+        .positionals()
+        .filter(|arg| arg.help.is_some())
+        .map(|arg| (arg.value_label(), describe(arg)))
+        .collect();
+    section(&mut out, "Arguments", positionals);
+
+    let mut options: Vec<_> = command
+        .args
+        .iter()
+        .filter(|arg| arg.kind != Kind::Positional)
+        .map(|arg| {
+            let short = match arg.short {
+                Some(short) => format!("-{}, ", short),
+                None => "    ".to_string(),
+            };
+            let mut left = format!("{}--{}", short, arg.name);
+            if arg.kind == Kind::Option {
+                left.push(' ');
+                left.push_str(&arg.value_label());
+            }
+            (left, describe(arg))
+        })
+        .collect();
+    options.push(("-h, --help".to_string(), "print this help".to_string()));
+    section(&mut out, "Options", options);
+
+    let commands = command
+        .subcommands
+        .iter()
+        .map(|sub| (sub.name.to_string(), sub.about.unwrap_or("").to_string()))
+        .collect();
+    section(&mut out, "Commands", commands);
+
+    if let Some(footer) = command.footer {
+        out.push_str(footer);
+        out.push('\n');
+    }
+    out
+}
+
+fn describe(arg: &Arg<'_>) -> String {
+    let mut text = arg.help.unwrap_or("").to_string();
+    if let Some(default) = arg.default {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&format!("(default {})", default));
+    }
+    text
+}
+
+/// A heading, then two aligned columns.
+fn section(out: &mut String, heading: &str, rows: Vec<(String, String)>) {
+    if rows.is_empty() {
+        return;
+    }
+    out.push_str(heading);
+    out.push_str(":\n");
+    let width = rows
+        .iter()
+        .map(|(left, _)| left.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (left, right) in rows {
+        let line = format!("  {:<width$}   {}", left, right, width = width);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+}
@@ -0,0 +1,20 @@
+// A small declarative command-line parser.
+//
+// Describe a program's arguments with a `Command` built from `Arg`s, and
+// `parse` does the rest: long and short flags, options with values,
+// positional arguments, subcommands, and `--help` text generated from the
+// same description, so the two can't drift apart. The command's strings
+// are borrowed, usually from `'static` literals, and the `Matches` it
+// returns borrows the command, which is how it can answer questions by
+// argument name and report mistakes using the command's help.
+
+mod arg;
+mod command;
+mod error;
+mod help;
+mod matches;
+
+pub use arg::Arg;
+pub use command::Command;
+pub use error::{Error, ErrorKind};
+pub use matches::Matches;
@@ -0,0 +1,82 @@
+use std::env;
+
+use argparse_lite::{Arg, Command};
+
+/// A to-do list's command line, to try the parser on. It shows what was
+/// parsed instead of keeping a list.
+fn command() -> Command<'static> {
+    Command::new("todo")
+        .about("keep a to-do list (a demonstration: it only shows what it parsed)")
+        .arg(
+            Arg::option("file")
+                .short('f')
+                .value_name("PATH")
+                .default("todo.txt")
+                .help("where the list is kept"),
+        )
+        .arg(
+            Arg::flag("verbose")
+                .short('v')
+                .help("say more; repeat for even more"),
+        )
+        .subcommand(
+            Command::new("add")
+                .about("add an item")
+                .arg(
+                    Arg::option("tag")
+                        .short('t')
+                        .multiple()
+                        .help("tag the item; may be repeated"),
+                )
+                .arg(
+                    Arg::option("priority")
+                        .short('p')
+                        .default("3")
+                        .help("1 (urgent) to 5"),
+                )
+                .arg(Arg::positional("text").multiple().help("what needs doing")),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("show items")
+                .arg(Arg::flag("all").short('a').help("include finished items"))
+                .arg(
+                    Arg::option("limit")
+                        .short('n')
+                        .value_name("N")
+                        .help("show at most N items"),
+                ),
+        )
+        .subcommand(
+            Command::new("done")
+                .about("mark items finished")
+                .arg(Arg::positional("id").multiple().help("the items' numbers")),
+        )
+        .footer("Try: todo -vv add -t home -p1 buy milk")
+}
+
+fn main() {
+    let command = command();
+    let matches = command
+        .parse(env::args().skip(1))
+        .unwrap_or_else(|e| e.exit());
+    println!("file: {}", matches.value("file").unwrap());
+    println!("verbosity: {}", matches.count("verbose"));
+    match matches.subcommand() {
+        Some(("add", add)) => {
+            let priority: u8 = add.get("priority").unwrap_or_else(|e| e.exit()).unwrap();
+            println!("add {:?}", add.values("text").join(" "));
+            println!("  priority {}, tags {:?}", priority, add.values("tag"));
+        }
+        Some(("list", list)) => {
+            let limit: Option<usize> = list.get("limit").unwrap_or_else(|e| e.exit());
+            println!("list all={} limit={:?}", list.flag("all"), limit);
+        }
+        Some(("done", done)) => {
+            for id in done.values("id") {
+                println!("done {}", id);
+            }
+        }
+        _ => unreachable!("subcommands are required"),
+    }
+}
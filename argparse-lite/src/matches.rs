@@ -0,0 +1,95 @@
+// The result of parsing: what was given, looked up by argument name.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::arg::{Arg, Kind};
+use crate::command::Command;
+use crate::error::{Error, ErrorKind};
+use crate::help;
+
+/// The arguments given to one command. Borrows the `Command` it came
+/// from, to check names and supply defaults.
+///
+/// Asking about a name the command doesn't declare, or asking for a flag's
+/// value, is a bug in the program, and panics.
+#[derive(Debug)]
+pub struct Matches<'a> {
+    pub(crate) command: &'a Command<'a>,
+    pub(crate) path: String,
+    pub(crate) flags: HashMap<&'a str, u32>,
+    pub(crate) values: HashMap<&'a str, Vec<String>>,
+    pub(crate) subcommand: Option<Box<Matches<'a>>>,
+}
+
+impl<'a> Matches<'a> {
+    fn arg(&self, name: &str, flag: bool) -> &'a Arg<'a> {
+        let command: &'a Command<'a> = self.command;
+        let Some(arg) = command.find(name) else {
+            panic!("{} has no argument named {}", self.path, name);
+        };
+        assert!(
+            (arg.kind == Kind::Flag) == flag,
+            "{} is {}a flag",
+            name,
+            if flag { "not " } else { "" }
+        );
+        arg
+    }
+
+    /// Whether the flag was given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.count(name) > 0
+    }
+
+    /// How many times the flag was given: `-vvv` is 3.
+    pub fn count(&self, name: &str) -> u32 {
+        let arg = self.arg(name, true);
+        self.flags.get(arg.name).copied().unwrap_or(0)
+    }
+
+    /// The argument's value, or its default, or `None`.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        let arg = self.arg(name, false);
+        match self.values.get(arg.name) {
+            Some(values) => values.first().map(String::as_str),
+            None => arg.default,
+        }
+    }
+
+    /// Every value given for a `multiple` argument, in order.
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        match self.values.get(self.arg(name, false).name) {
+            Some(values) => values.iter().map(String::as_str).collect(),
+            None => self.value(name).into_iter().collect(),
+        }
+    }
+
+    /// The argument's value parsed as a `T`, with a usage error if it
+    /// doesn't parse.
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>, Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(value) = self.value(name) else {
+            return Ok(None);
+        };
+        value.parse().map(Some).map_err(|e: T::Err| Error {
+            kind: ErrorKind::InvalidValue {
+                arg: self.arg(name, false).display(),
+                value: value.to_string(),
+                reason: e.to_string(),
+            },
+            help: help::render(self.command, &self.path),
+        })
+    }
+
+    /// The subcommand given, and its own matches.
+    pub fn subcommand(&self) -> Option<(&'a str, &Matches<'a>)> {
+        self.subcommand
+            .as_deref()
+            .map(|matches| (matches.command.name, matches))
+    }
+}
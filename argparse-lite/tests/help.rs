@@ -0,0 +1,66 @@
+use argparse_lite::{Arg, Command};
+
+#[test]
+fn full_help() {
+    let command = Command::new("iocopy")
+        .about("copy bytes")
+        .arg(Arg::flag("compress").short('z').help("gzip the input"))
+        .arg(
+            Arg::option("limit")
+                .value_name("RATE")
+                .help("copy at most RATE bytes per second"),
+        )
+        .arg(Arg::option("level").default("6").help("compression level"))
+        .arg(Arg::positional("input").optional().help("read from here"))
+        .arg(Arg::positional("more").multiple().optional())
+        .footer("'-' means stdin or stdout.");
+    assert_eq!(
+        command.render_help(),
+        "\
+iocopy - copy bytes
+Usage: iocopy [options] [INPUT] [MORE...]
+Arguments:
+  INPUT   read from here
+Options:
+  -z, --compress      gzip the input
+      --limit RATE    copy at most RATE bytes per second
+      --level LEVEL   compression level (default 6)
+  -h, --help          print this help
+'-' means stdin or stdout.
+"
+    );
+}
+
+#[test]
+fn subcommand_help() {
+    let command = Command::new("kv")
+        .about("a store")
+        .subcommand(
+            Command::new("get")
+                .about("print a value")
+                .arg(Arg::positional("key")),
+        )
+        .subcommand(Command::new("compact"));
+    assert_eq!(
+        command.render_help(),
+        "\
+kv - a store
+Usage: kv <command>
+Options:
+  -h, --help   print this help
+Commands:
+  get       print a value
+  compact
+"
+    );
+    let help = command.parse(["get", "--help"]).unwrap_err();
+    assert_eq!(
+        help.help(),
+        "\
+kv get - print a value
+Usage: kv get KEY
+Options:
+  -h, --help   print this help
+"
+    );
+}
@@ -0,0 +1,350 @@
+use argparse_lite::{Arg, Command, ErrorKind, Matches};
+
+fn copy() -> Command<'static> {
+    Command::new("copy")
+        .about("copy things")
+        .arg(Arg::flag("compress").short('z'))
+        .arg(Arg::flag("verbose").short('v'))
+        .arg(Arg::option("limit").short('l').value_name("RATE"))
+        .arg(Arg::option("level").default("6"))
+        .arg(Arg::option("exclude").short('x').multiple())
+        .arg(Arg::positional("input"))
+        .arg(Arg::positional("output").optional())
+}
+
+fn parse<'a>(command: &'a Command<'a>, args: &[&str]) -> Matches<'a> {
+    command
+        .parse(args.iter().copied())
+        .unwrap_or_else(|e| panic!("{:?}: {}", args, e))
+}
+
+fn error(command: &Command<'_>, args: &[&str]) -> ErrorKind {
+    match command.parse(args.iter().copied()) {
+        Ok(_) => panic!("{:?} parsed", args),
+        Err(e) => e.kind().clone(),
+    }
+}
+
+#[test]
+fn flags() {
+    let command = copy();
+    let m = parse(&command, &["in"]);
+    assert!(!m.flag("compress"));
+    assert_eq!(m.count("verbose"), 0);
+
+    for args in [&["--compress", "in"][..], &["-z", "in"], &["in", "-z"]] {
+        assert!(parse(&command, args).flag("compress"), "{:?}", args);
+    }
+    let m = parse(&command, &["-vzv", "--verbose", "in"]);
+    assert!(m.flag("compress"));
+    assert_eq!(m.count("verbose"), 3);
+}
+
+#[test]
+fn option_spellings() {
+    let command = copy();
+    for args in [
+        &["--limit", "64k", "in"][..],
+        &["--limit=64k", "in"],
+        &["-l", "64k", "in"],
+        &["-l64k", "in"],
+        &["-zl", "64k", "in"],
+        &["-zl64k", "in"],
+        &["in", "--limit", "64k"],
+    ] {
+        let m = parse(&command, args);
+        assert_eq!(m.value("limit"), Some("64k"), "{:?}", args);
+        assert_eq!(m.value("input"), Some("in"), "{:?}", args);
+    }
+}
+
+#[test]
+fn option_values_can_look_like_options() {
+    let command = copy();
+    assert_eq!(
+        parse(&command, &["--limit", "-5", "in"]).value("limit"),
+        Some("-5")
+    );
+    assert_eq!(
+        parse(&command, &["-l", "--", "in"]).value("limit"),
+        Some("--")
+    );
+    assert_eq!(
+        parse(&command, &["--limit=", "in"]).value("limit"),
+        Some("")
+    );
+    assert_eq!(
+        parse(&command, &["--limit=a=b", "in"]).value("limit"),
+        Some("a=b")
+    );
+}
+
+#[test]
+fn defaults_and_absent_options() {
+    let command = copy();
+    let m = parse(&command, &["in"]);
+    assert_eq!(m.value("limit"), None);
+    assert_eq!(m.value("level"), Some("6"));
+    assert_eq!(m.values("level"), ["6"]);
+    assert_eq!(
+        parse(&command, &["--level", "9", "in"]).value("level"),
+        Some("9")
+    );
+    assert!(m.values("exclude").is_empty());
+}
+
+#[test]
+fn repeated_options() {
+    let command = copy();
+    let m = parse(&command, &["-x", "a", "--exclude=b", "-xc", "in"]);
+    assert_eq!(m.values("exclude"), ["a", "b", "c"]);
+    assert_eq!(m.value("exclude"), Some("a"));
+    assert_eq!(
+        error(&command, &["--limit", "1", "-l2", "in"]),
+        ErrorKind::Repeated("--limit".to_string())
+    );
+}
+
+#[test]
+fn positionals() {
+    let command = copy();
+    let m = parse(&command, &["a"]);
+    assert_eq!((m.value("input"), m.value("output")), (Some("a"), None));
+    let m = parse(&command, &["a", "b"]);
+    assert_eq!(
+        (m.value("input"), m.value("output")),
+        (Some("a"), Some("b"))
+    );
+    assert_eq!(
+        error(&command, &[]),
+        ErrorKind::MissingArgument("INPUT".to_string())
+    );
+    assert_eq!(
+        error(&command, &["a", "b", "c"]),
+        ErrorKind::UnexpectedArgument("c".to_string())
+    );
+}
+
+#[test]
+fn dashes() {
+    let command = copy();
+    // A lone dash conventionally means stdin or stdout.
+    let m = parse(&command, &["-", "-"]);
+    assert_eq!(
+        (m.value("input"), m.value("output")),
+        (Some("-"), Some("-"))
+    );
+    // After `--`, nothing is an option.
+    let m = parse(&command, &["-z", "--", "-v", "--limit"]);
+    assert!(m.flag("compress"));
+    assert_eq!(m.count("verbose"), 0);
+    assert_eq!(
+        (m.value("input"), m.value("output")),
+        (Some("-v"), Some("--limit"))
+    );
+}
+
+#[test]
+fn variadic_positionals() {
+    let command = Command::new("cat")
+        .arg(Arg::flag("number").short('n'))
+        .arg(Arg::positional("files").multiple().optional());
+    assert!(parse(&command, &[]).values("files").is_empty());
+    let m = parse(&command, &["a", "-n", "b", "c"]);
+    assert_eq!(m.values("files"), ["a", "b", "c"]);
+    assert!(m.flag("number"));
+
+    let required = Command::new("rm").arg(Arg::positional("paths").multiple());
+    assert_eq!(
+        error(&required, &[]),
+        ErrorKind::MissingArgument("PATHS".to_string())
+    );
+}
+
+#[test]
+fn usage_errors() {
+    let command = copy();
+    let cases: &[(&[&str], ErrorKind)] = &[
+        (
+            &["--frobnicate", "in"],
+            ErrorKind::UnknownOption("--frobnicate".to_string()),
+        ),
+        (
+            &["--frobnicate=1", "in"],
+            ErrorKind::UnknownOption("--frobnicate".to_string()),
+        ),
+        (&["-q", "in"], ErrorKind::UnknownOption("-q".to_string())),
+        (&["-zq", "in"], ErrorKind::UnknownOption("-q".to_string())),
+        (
+            &["in", "--limit"],
+            ErrorKind::MissingValue("--limit".to_string()),
+        ),
+        (
+            &["in", "-l"],
+            ErrorKind::MissingValue("--limit".to_string()),
+        ),
+        (
+            &["--compress=yes", "in"],
+            ErrorKind::UnexpectedValue("--compress".to_string()),
+        ),
+        (&["--help"], ErrorKind::HelpRequested),
+        (&["in", "-zh"], ErrorKind::HelpRequested),
+    ];
+    for (args, kind) in cases {
+        assert_eq!(error(&command, args), *kind, "{:?}", args);
+    }
+}
+
+#[test]
+fn error_messages_and_exit_codes() {
+    let command = copy();
+    let e = command.parse(["--limit"]).unwrap_err();
+    assert_eq!(e.to_string(), "--limit needs a value");
+    assert_eq!(e.exit_code(), 2);
+    assert!(e.help().starts_with("copy - copy things\nUsage: copy"));
+    let help = command.parse(["-h"]).unwrap_err();
+    assert_eq!(help.exit_code(), 0);
+    assert_eq!(help.help(), command.render_help());
+}
+
+#[test]
+fn typed_values() {
+    let command = copy();
+    let m = parse(&command, &["--limit", "4096", "in"]);
+    assert_eq!(m.get::<u32>("limit").unwrap(), Some(4096));
+    assert_eq!(m.get::<u8>("level").unwrap(), Some(6));
+    assert_eq!(m.get::<u8>("output").unwrap(), None);
+
+    let m = parse(&command, &["--limit", "lots", "in"]);
+    let e = m.get::<u32>("limit").unwrap_err();
+    assert_eq!(
+        *e.kind(),
+        ErrorKind::InvalidValue {
+            arg: "--limit".to_string(),
+            value: "lots".to_string(),
+            reason: "invalid digit found in string".to_string(),
+        }
+    );
+    assert_eq!(
+        e.to_string(),
+        r#"bad value "lots" for --limit: invalid digit found in string"#
+    );
+    assert_eq!(e.exit_code(), 2);
+}
+
+fn git() -> Command<'static> {
+    Command::new("git")
+        .arg(Arg::option("dir").short('C'))
+        .subcommand(
+            Command::new("commit")
+                .about("record changes")
+                .arg(Arg::option("message").short('m').required())
+                .arg(Arg::flag("all").short('a')),
+        )
+        .subcommand(
+            Command::new("remote")
+                .about("manage remotes")
+                .subcommand(
+                    Command::new("add")
+                        .arg(Arg::positional("name"))
+                        .arg(Arg::positional("url")),
+                )
+                .subcommand(Command::new("list")),
+        )
+}
+
+#[test]
+fn subcommands() {
+    let command = git();
+    let m = parse(&command, &["-C", "repo", "commit", "-am", "fix"]);
+    assert_eq!(m.value("dir"), Some("repo"));
+    let (name, commit) = m.subcommand().unwrap();
+    assert_eq!(name, "commit");
+    assert!(commit.flag("all"));
+    assert_eq!(commit.value("message"), Some("fix"));
+
+    let m = parse(
+        &command,
+        &["remote", "add", "origin", "https://example.com"],
+    );
+    let (_, remote) = m.subcommand().unwrap();
+    let (name, add) = remote.subcommand().unwrap();
+    assert_eq!(name, "add");
+    assert_eq!(add.value("url"), Some("https://example.com"));
+    assert!(parse(&command, &["commit", "-m", "x"])
+        .value("dir")
+        .is_none());
+}
+
+#[test]
+fn subcommand_errors() {
+    let command = git();
+    assert_eq!(error(&command, &[]), ErrorKind::MissingCommand);
+    assert_eq!(
+        error(&command, &["push"]),
+        ErrorKind::UnknownCommand("push".to_string())
+    );
+    assert_eq!(error(&command, &["remote"]), ErrorKind::MissingCommand);
+    assert_eq!(
+        error(&command, &["commit"]),
+        ErrorKind::MissingArgument("--message".to_string())
+    );
+    // Options belong to the command they follow.
+    assert_eq!(
+        error(&command, &["commit", "-m", "x", "-C", "repo"]),
+        ErrorKind::UnknownOption("-C".to_string())
+    );
+    // Errors carry the help of the command they were found in.
+    let e = command.parse(["remote", "add", "origin"]).unwrap_err();
+    assert_eq!(*e.kind(), ErrorKind::MissingArgument("URL".to_string()));
+    assert!(
+        e.help().contains("Usage: git remote add NAME URL\n"),
+        "{}",
+        e.help()
+    );
+}
+
+#[test]
+#[should_panic(expected = "git has no argument named message")]
+fn asking_about_undeclared_arguments() {
+    let command = git();
+    parse(&command, &["commit", "-m", "x"]).value("message");
+}
+
+#[test]
+#[should_panic(expected = "all is a flag")]
+fn asking_for_a_flags_value() {
+    let command = git();
+    let m = parse(&command, &["commit", "-m", "x"]);
+    m.subcommand().unwrap().1.value("all");
+}
+
+#[test]
+#[should_panic(expected = "-z declared twice")]
+fn duplicate_short_names() {
+    Command::new("x")
+        .arg(Arg::flag("zip").short('z'))
+        .arg(Arg::flag("zap").short('z'));
+}
+
+#[test]
+#[should_panic(expected = "required b can't follow optional a")]
+fn required_after_optional() {
+    Command::new("x")
+        .arg(Arg::positional("a").optional())
+        .arg(Arg::positional("b"));
+}
+
+#[test]
+#[should_panic(expected = "no positional can follow a")]
+fn positional_after_variadic() {
+    Command::new("x")
+        .arg(Arg::positional("a").multiple())
+        .arg(Arg::positional("b").optional());
+}
+
+#[test]
+#[should_panic(expected = "-h is built in")]
+fn help_is_reserved() {
+    Command::new("x").arg(Arg::flag("hello").short('h'));
+}
@@ -0,0 +1,154 @@
+// The book's canonical example of an unsafe invariant: an `Ascii` string
+// type whose every constructor must guarantee that the bytes are ASCII, so
+// that conversions to `String` can skip UTF-8 validation.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+
+/// An ASCII-encoded string.
+///
+/// Invariant: every byte in the vector is in `0x00..=0x7f`. Since ASCII is a
+/// subset of UTF-8, this means the bytes are always well-formed UTF-8.
+/// Safe code can only build an `Ascii` through `from_bytes`, which checks;
+/// `from_bytes_unchecked` shifts that responsibility to its caller.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub struct Ascii(
+    // This must hold only well-formed ASCII text:
+    // bytes from `0` to `0x7f`.
+    Vec<u8>,
+);
+
+impl Ascii {
+    /// Create an `Ascii` from the ASCII text in `bytes`. Return a
+    /// `NotAsciiError` error if `bytes` contains any non-ASCII characters.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Ascii, NotAsciiError> {
+        if let Some(index) = bytes.iter().position(|&byte| !byte.is_ascii()) {
+            return Err(NotAsciiError { bytes, index });
+        }
+        Ok(Ascii(bytes))
+    }
+
+    /// Construct an `Ascii` value from `bytes`, without checking
+    /// whether `bytes` actually contains well-formed ASCII.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains only ASCII
+    /// characters: bytes no greater than 0x7f. Otherwise, the effect is
+    /// undefined.
+    pub unsafe fn from_bytes_unchecked(bytes: Vec<u8>) -> Ascii {
+        Ascii(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn as_str(&self) -> &str {
+        debug_assert!(
+            self.0.is_ascii(),
+            "Ascii invariant violated: non-ASCII byte"
+        );
+        // Well-formed ASCII is also well-formed UTF-8.
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Append `byte`, which must be ASCII. Returns `false`, leaving the
+    /// string unchanged, if it isn't.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if !byte.is_ascii() {
+            return false;
+        }
+        self.0.push(byte);
+        true
+    }
+
+    pub fn make_ascii_uppercase(&mut self) {
+        // Case conversion maps ASCII to ASCII, so the invariant holds.
+        self.0.make_ascii_uppercase();
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl From<Ascii> for String {
+    fn from(ascii: Ascii) -> String {
+        // Debug builds double-check the invariant that justifies skipping
+        // UTF-8 validation. If an unsafe caller broke it, this is where the
+        // damage would otherwise escape into a `String` that violates *its*
+        // invariant.
+        debug_assert!(
+            ascii.0.is_ascii(),
+            "Ascii invariant violated: non-ASCII byte"
+        );
+
+        // If this module has no bugs, this is safe, because
+        // well-formed ASCII text is also well-formed UTF-8.
+        unsafe { String::from_utf8_unchecked(ascii.0) }
+    }
+}
+
+impl TryFrom<Vec<u8>> for Ascii {
+    type Error = NotAsciiError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Ascii, NotAsciiError> {
+        Ascii::from_bytes(bytes)
+    }
+}
+
+impl TryFrom<&str> for Ascii {
+    type Error = NotAsciiError;
+
+    fn try_from(text: &str) -> Result<Ascii, NotAsciiError> {
+        Ascii::from_bytes(text.as_bytes().to_vec())
+    }
+}
+
+impl Deref for Ascii {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Ascii {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<[u8]> for Ascii {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Ascii {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// When conversion fails, we give back the vector we couldn't convert,
+/// along with the index of the first offending byte.
+#[derive(Debug, Eq, PartialEq)]
+pub struct NotAsciiError {
+    pub bytes: Vec<u8>,
+    pub index: usize,
+}
+
+impl fmt::Display for NotAsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "byte 0x{:02x} at index {} is not ASCII",
+            self.bytes[self.index], self.index
+        )
+    }
+}
+
+impl Error for NotAsciiError {}
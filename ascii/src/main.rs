@@ -0,0 +1,19 @@
+use ascii::Ascii;
+
+fn main() {
+    let bytes: Vec<u8> = b"ASCII and ye shall receive".to_vec();
+
+    // This call entails no allocation or text copies, just a scan.
+    let ascii: Ascii = Ascii::from_bytes(bytes).unwrap(); // We know these chosen bytes are ok.
+
+    // This call is zero-cost: no allocation, copies, or scans.
+    let string = String::from(ascii);
+
+    assert_eq!(string, "ASCII and ye shall receive");
+    println!("{}", string);
+
+    match Ascii::from_bytes("caf\u{e9}".as_bytes().to_vec()) {
+        Ok(_) => println!("unexpectedly accepted non-ASCII text"),
+        Err(e) => println!("rejected: {}", e),
+    }
+}
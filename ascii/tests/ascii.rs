@@ -0,0 +1,69 @@
+use ascii::{Ascii, NotAsciiError};
+
+#[test]
+fn good_ascii() {
+    let bytes: Vec<u8> = b"ASCII and ye shall receive".to_vec();
+    let ascii = Ascii::from_bytes(bytes).unwrap();
+    assert_eq!(ascii.as_str(), "ASCII and ye shall receive");
+    assert_eq!(ascii.len(), 26, "Deref to str gives access to str methods");
+    assert_eq!(String::from(ascii), "ASCII and ye shall receive");
+}
+
+#[test]
+fn every_ascii_byte_is_accepted() {
+    let all: Vec<u8> = (0..=0x7f).collect();
+    let ascii = Ascii::from_bytes(all.clone()).unwrap();
+    assert_eq!(ascii.into_bytes(), all);
+}
+
+#[test]
+fn bad_ascii_returns_the_bytes() {
+    let bytes = "na\u{ef}ve".as_bytes().to_vec();
+    let err = Ascii::from_bytes(bytes.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        NotAsciiError {
+            bytes: bytes.clone(),
+            index: 2
+        }
+    );
+    assert_eq!(err.to_string(), "byte 0xc3 at index 2 is not ASCII");
+    assert!(Ascii::try_from(bytes).is_err());
+    assert!(Ascii::try_from("naïve").is_err());
+}
+
+#[test]
+fn safe_mutation_preserves_the_invariant() {
+    let mut ascii = Ascii::try_from("shout").unwrap();
+    assert!(ascii.push(b'!'));
+    assert!(!ascii.push(0xff));
+    ascii.make_ascii_uppercase();
+    assert_eq!(ascii.to_string(), "SHOUT!");
+}
+
+#[test]
+fn unchecked_constructor_with_valid_bytes() {
+    let ascii = unsafe { Ascii::from_bytes_unchecked(b"ok".to_vec()) };
+    assert_eq!(String::from(ascii), "ok");
+}
+
+/// Breaking the invariant through the unsafe constructor. In a release build
+/// this would produce a `String` holding ill-formed UTF-8 — undefined
+/// behavior that could surface much later, in code that did nothing wrong.
+/// The debug assertion in the conversion catches it at the point of escape.
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Ascii invariant violated")]
+fn violating_the_invariant_is_caught_in_debug_builds() {
+    // Imagine that this vector is the result of some complicated process
+    // that we expected to produce ASCII. Something went wrong!
+    let bytes = vec![0xf7, 0xbf, 0xbf, 0xbf];
+
+    let ascii = unsafe {
+        // This unsafe function's contract is violated
+        // when `bytes` holds non-ASCII bytes.
+        Ascii::from_bytes_unchecked(bytes)
+    };
+
+    let _bogus: String = ascii.into();
+}
@@ -0,0 +1,68 @@
+// Moving messages between threads, through each channel in the
+// workspace and std's.
+//
+// mini-channel's own benchmark goes into more detail about its two
+// channels; this one keeps to the single-producer case that every
+// implementation supports, so it can sit next to the others here.
+
+use std::sync::mpsc as std_mpsc;
+use std::thread;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use mini_channel::{mpsc, spsc};
+
+const MESSAGES: u64 = 100_000;
+const CAPACITY: usize = 1024;
+
+fn one_to_one(c: &mut Criterion) {
+    let mut group = c.benchmark_group("channels");
+    group.throughput(Throughput::Elements(MESSAGES));
+    group.bench_function("std channel", |b| {
+        b.iter(|| {
+            let (sender, receiver) = std_mpsc::channel();
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.bench_function("std sync_channel", |b| {
+        b.iter(|| {
+            let (sender, receiver) = std_mpsc::sync_channel(CAPACITY);
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.bench_function("mini-channel mpsc", |b| {
+        b.iter(|| {
+            let (sender, receiver) = mpsc::channel();
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.bench_function("mini-channel spsc", |b| {
+        b.iter(|| {
+            let (sender, receiver) = spsc::channel(CAPACITY);
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, one_to_one);
+criterion_main!(benches);
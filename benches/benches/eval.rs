@@ -0,0 +1,36 @@
+// Reading arithmetic expressions.
+//
+// The workspace has one evaluator, chapter 3's, which parses with a pest
+// grammar and evaluates the parse tree. The lexer member's hand-written
+// calculator lexer reads the same syntax into tokens without evaluating
+// anything, which puts a floor under what any evaluator of this language
+// could cost; the gap between the two is pest's parsing plus the
+// evaluation itself.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fixtures::expression;
+use lexer::calc::Calc;
+
+const SIZES: [usize; 3] = [10, 100, 1000];
+
+fn eval(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval");
+    for operators in SIZES {
+        let expr = expression(operators, 42);
+        group.throughput(Throughput::Bytes(expr.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("ch_03::eval", operators),
+            &expr,
+            |b, expr| b.iter(|| ch_03::eval(black_box(expr)).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("lexer tokens", operators),
+            &expr,
+            |b, expr| b.iter(|| lexer::tokenize(Calc::new(), black_box(expr))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, eval);
+criterion_main!(benches);
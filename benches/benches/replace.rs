@@ -0,0 +1,68 @@
+// Ways of doing quickreplace's job.
+//
+// `ch_02::replace` compiles its regular expression on every call, which is
+// right for a program that makes one replacement and exits, but a cost a
+// long-running caller can avoid by compiling once. When the target is
+// plain text, `str::replace` needs no regular expression at all.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use fixtures::text;
+use regex::Regex;
+
+const SIZES: [usize; 3] = [4 * 1024, 64 * 1024, 1024 * 1024];
+
+fn literal(c: &mut Criterion) {
+    let regex = Regex::new("ka").unwrap();
+    let mut group = c.benchmark_group("replace_literal");
+    for size in SIZES {
+        let input = text(size, 42);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("ch_02::replace", size),
+            &input,
+            |b, input| b.iter(|| ch_02::replace("ka", "KA", black_box(input)).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("compiled once", size),
+            &input,
+            |b, input| b.iter(|| regex.replace_all(black_box(input), "KA").into_owned()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("str::replace", size),
+            &input,
+            |b, input| b.iter(|| black_box(input).replace("ka", "KA")),
+        );
+    }
+    group.finish();
+}
+
+fn captures(c: &mut Criterion) {
+    const PATTERN: &str = r"(\w+)@(\w+)\.org";
+    const REPLACEMENT: &str = "$2 at $1";
+    let regex = Regex::new(PATTERN).unwrap();
+    let mut group = c.benchmark_group("replace_captures");
+    for size in SIZES {
+        let input = text(size, 42);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("ch_02::replace", size),
+            &input,
+            |b, input| b.iter(|| ch_02::replace(PATTERN, REPLACEMENT, black_box(input)).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("compiled once", size),
+            &input,
+            |b, input| {
+                b.iter(|| {
+                    regex
+                        .replace_all(black_box(input), REPLACEMENT)
+                        .into_owned()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, literal, captures);
+criterion_main!(benches);
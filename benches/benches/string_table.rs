@@ -0,0 +1,98 @@
+// Prefix search over the words an autocomplete box offers.
+//
+// Chapter 5's `StringTable` keeps its words in insertion order and scans
+// them all. Two alternatives that keep them sorted are defined here for
+// comparison: a sorted `Vec` searched with `partition_point`, and a
+// `BTreeSet` searched with `range`. Both find the first match in
+// logarithmic time, and pay for it when words are added.
+
+use std::collections::BTreeSet;
+use std::ops::Bound;
+
+use ch_05::StringTable;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use fixtures::words;
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+const PREFIXES: &[&str] = &["ka", "zenqua", "strstr", "x"];
+
+struct SortedVec(Vec<String>);
+
+impl SortedVec {
+    fn new(words: &[String]) -> SortedVec {
+        let mut words = words.to_vec();
+        words.sort();
+        SortedVec(words)
+    }
+
+    fn completions<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let start = self.0.partition_point(|w| w.as_str() < prefix);
+        self.0[start..]
+            .iter()
+            .map(String::as_str)
+            .take_while(move |w| w.starts_with(prefix))
+    }
+}
+
+fn tree_completions<'a>(
+    tree: &'a BTreeSet<String>,
+    prefix: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+    tree.range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+        .map(String::as_str)
+        .take_while(move |w| w.starts_with(prefix))
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("string_table_build");
+    for size in SIZES {
+        let list = words(size, 42);
+        group.bench_with_input(BenchmarkId::new("StringTable", size), &list, |b, list| {
+            b.iter(|| list.iter().cloned().collect::<StringTable>())
+        });
+        group.bench_with_input(BenchmarkId::new("sorted Vec", size), &list, |b, list| {
+            b.iter(|| SortedVec::new(list))
+        });
+        group.bench_with_input(BenchmarkId::new("BTreeSet", size), &list, |b, list| {
+            b.iter(|| list.iter().cloned().collect::<BTreeSet<_>>())
+        });
+    }
+    group.finish();
+}
+
+/// Every completion for each prefix: what the dashboard's autocomplete
+/// pane asks for on each keystroke.
+fn complete(c: &mut Criterion) {
+    let mut group = c.benchmark_group("string_table_complete");
+    for size in SIZES {
+        let list = words(size, 42);
+        let table: StringTable = list.iter().cloned().collect();
+        let sorted = SortedVec::new(&list);
+        let tree: BTreeSet<String> = list.iter().cloned().collect();
+        group.bench_function(BenchmarkId::new("StringTable", size), |b| {
+            b.iter(|| {
+                for prefix in PREFIXES {
+                    black_box(table.completions(black_box(prefix)).count());
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("sorted Vec", size), |b| {
+            b.iter(|| {
+                for prefix in PREFIXES {
+                    black_box(sorted.completions(black_box(prefix)).count());
+                }
+            })
+        });
+        group.bench_function(BenchmarkId::new("BTreeSet", size), |b| {
+            b.iter(|| {
+                for prefix in PREFIXES {
+                    black_box(tree_completions(&tree, black_box(prefix)).count());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build, complete);
+criterion_main!(benches);
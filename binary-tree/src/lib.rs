@@ -0,0 +1,195 @@
+// The book's `BinaryTree<T>`, an ordered binary tree built from an enum and
+// boxes, with an iterator that walks it in order using an explicit stack.
+
+use std::iter::FusedIterator;
+use std::mem;
+
+/// An ordered collection of `T`s.
+#[derive(Default)]
+pub enum BinaryTree<T> {
+    #[default]
+    Empty,
+    NonEmpty(Box<TreeNode<T>>),
+}
+
+/// A part of a BinaryTree.
+pub struct TreeNode<T> {
+    pub element: T,
+    pub left: BinaryTree<T>,
+    pub right: BinaryTree<T>,
+}
+
+use BinaryTree::*;
+
+impl<T> BinaryTree<T> {
+    pub fn new() -> BinaryTree<T> {
+        Empty
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Empty)
+    }
+
+    /// Count the elements. This walks the whole tree.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// The number of nodes on the longest path from the root to a leaf.
+    pub fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((tree, depth)) = stack.pop() {
+            match tree {
+                Empty => deepest = deepest.max(depth),
+                NonEmpty(node) => {
+                    stack.push((&node.left, depth + 1));
+                    stack.push((&node.right, depth + 1));
+                }
+            }
+        }
+        deepest
+    }
+
+    pub fn iter(&self) -> TreeIter<'_, T> {
+        let mut iter = TreeIter {
+            unvisited: Vec::new(),
+        };
+        iter.push_left_edge(self);
+        iter
+    }
+}
+
+impl<T: Ord> BinaryTree<T> {
+    /// Add `value` to the tree. Values equal to an existing element go to
+    /// its left, so equal elements come out in insertion order.
+    pub fn add(&mut self, value: T) {
+        // Walk down iteratively rather than recursing, so that adding to a
+        // badly unbalanced tree can't overflow the stack.
+        let mut place = self;
+        while let NonEmpty(node) = place {
+            place = if value <= node.element {
+                &mut node.left
+            } else {
+                &mut node.right
+            };
+        }
+        *place = NonEmpty(Box::new(TreeNode {
+            element: value,
+            left: Empty,
+            right: Empty,
+        }));
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let mut tree = self;
+        while let NonEmpty(node) = tree {
+            if *value == node.element {
+                return true;
+            }
+            tree = if *value < node.element {
+                &node.left
+            } else {
+                &node.right
+            };
+        }
+        false
+    }
+}
+
+impl<T> Drop for TreeNode<T> {
+    // The default drop glue would recurse once per level, overflowing the
+    // stack on a degenerate tree (say, one built from sorted input). Detach
+    // the subtrees onto a heap-allocated stack instead, so each node is
+    // dropped with empty children.
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        stack.push(mem::take(&mut self.left));
+        stack.push(mem::take(&mut self.right));
+        while let Some(tree) = stack.pop() {
+            if let NonEmpty(mut node) = tree {
+                stack.push(mem::take(&mut node.left));
+                stack.push(mem::take(&mut node.right));
+            }
+        }
+    }
+}
+
+/// The state of an in-order traversal of a `BinaryTree`.
+pub struct TreeIter<'a, T> {
+    // A stack of references to tree nodes. Since we use `Vec`'s
+    // `push` and `pop` methods, the top of the stack is the end of the
+    // vector.
+    //
+    // The node the iterator will visit next is at the top of the stack,
+    // with those ancestors still unvisited below it. If the stack is empty,
+    // the iteration is over.
+    unvisited: Vec<&'a TreeNode<T>>,
+}
+
+impl<'a, T: 'a> TreeIter<'a, T> {
+    fn push_left_edge(&mut self, mut tree: &'a BinaryTree<T>) {
+        while let NonEmpty(ref node) = *tree {
+            self.unvisited.push(node);
+            tree = &node.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for TreeIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        // Find the node this iteration must produce,
+        // or finish the iteration. (Use the `?` operator
+        // to return immediately if it's `None`.)
+        let node = self.unvisited.pop()?;
+
+        // After `node`, the next thing we produce must be the leftmost
+        // child in `node`'s right subtree, so push the path from here
+        // down. Our helper method turns out to be just what we need.
+        self.push_left_edge(&node.right);
+
+        // Produce a reference to this node's value.
+        Some(&node.element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every node on the stack is still to be produced; their right
+        // subtrees may hold any number more.
+        (self.unvisited.len(), None)
+    }
+}
+
+impl<T> FusedIterator for TreeIter<'_, T> {}
+
+impl<'a, T: 'a> IntoIterator for &'a BinaryTree<T> {
+    type Item = &'a T;
+    type IntoIter = TreeIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Extend<T> for BinaryTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = BinaryTree::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for BinaryTree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,31 @@
+use binary_tree::BinaryTree;
+
+fn main() {
+    // Build a small tree.
+    let mut tree = BinaryTree::new();
+    tree.add("jaeger");
+    tree.add("robot");
+    tree.add("droid");
+    tree.add("mecha");
+
+    // Iterate over it.
+    let mut v = Vec::new();
+    for kind in &tree {
+        v.push(*kind);
+    }
+    assert_eq!(v, ["droid", "jaeger", "mecha", "robot"]);
+    println!("in order: {:?}", v);
+
+    // Because `&BinaryTree` implements `IntoIterator`, all the usual
+    // adapters work too.
+    let shouted: Vec<String> = tree.iter().map(|name| format!("mega-{}", name)).collect();
+    println!("mapped: {:?}", shouted);
+
+    let numbers: BinaryTree<i32> = [5, 3, 8, 1, 4, 9].into_iter().collect();
+    println!(
+        "{:?} has {} elements and depth {}",
+        numbers,
+        numbers.len(),
+        numbers.depth()
+    );
+}
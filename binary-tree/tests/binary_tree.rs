@@ -0,0 +1,163 @@
+use binary_tree::{BinaryTree, TreeNode};
+use proptest::prelude::*;
+
+#[test]
+fn empty_tree() {
+    let tree: BinaryTree<i32> = BinaryTree::new();
+    assert!(tree.is_empty());
+    assert_eq!(tree.len(), 0);
+    assert_eq!(tree.depth(), 0);
+    assert_eq!(tree.iter().next(), None);
+}
+
+#[test]
+fn add_builds_an_ordered_tree() {
+    let mut tree = BinaryTree::new();
+    tree.add("Mercury");
+    tree.add("Venus");
+    tree.add("Mars");
+
+    // The shape follows insertion order: Mercury at the root, Mars on its
+    // left, Venus on its right.
+    match &tree {
+        BinaryTree::NonEmpty(root) => {
+            let TreeNode {
+                element,
+                left,
+                right,
+            } = &**root;
+            assert_eq!(*element, "Mercury");
+            assert!(matches!(left, BinaryTree::NonEmpty(n) if n.element == "Mars"));
+            assert!(matches!(right, BinaryTree::NonEmpty(n) if n.element == "Venus"));
+        }
+        BinaryTree::Empty => panic!("tree is empty"),
+    }
+    assert_eq!(tree.depth(), 2);
+}
+
+#[test]
+fn iterates_in_order() {
+    let mut tree = BinaryTree::new();
+    tree.add("jaeger");
+    tree.add("robot");
+    tree.add("droid");
+    tree.add("mecha");
+
+    let mut v = Vec::new();
+    for kind in &tree {
+        v.push(*kind);
+    }
+    assert_eq!(v, ["droid", "jaeger", "mecha", "robot"]);
+
+    assert_eq!(
+        tree.iter()
+            .map(|name| format!("mega-{}", name))
+            .collect::<Vec<_>>(),
+        vec!["mega-droid", "mega-jaeger", "mega-mecha", "mega-robot"]
+    );
+}
+
+#[test]
+fn duplicates_are_kept() {
+    let tree: BinaryTree<i32> = vec![2, 1, 2, 3, 2].into_iter().collect();
+    assert_eq!(tree.iter().copied().collect::<Vec<_>>(), [1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn references_outlive_the_iterator() {
+    let tree: BinaryTree<String> = ["bb", "a", "ccc"].iter().map(|s| s.to_string()).collect();
+
+    // The items borrow from the tree, not from the iterator, so they stay
+    // usable after the iterator is gone.
+    let refs: Vec<&String> = tree.iter().collect();
+    let longest = refs.iter().max_by_key(|s| s.len()).unwrap();
+    assert_eq!(refs, ["a", "bb", "ccc"]);
+    assert_eq!(*longest, "ccc");
+}
+
+#[test]
+fn several_iterators_at_once() {
+    let tree: BinaryTree<u32> = [4, 2, 6, 1, 3, 5, 7].into_iter().collect();
+    let pairs: Vec<(u32, u32)> = tree
+        .iter()
+        .flat_map(|&a| tree.iter().filter(move |&&b| b > a).map(move |&b| (a, b)))
+        .collect();
+    assert_eq!(pairs.len(), 7 * 6 / 2);
+    assert!(tree.iter().zip(tree.iter().skip(1)).all(|(a, b)| a < b));
+}
+
+#[test]
+fn contains_and_extend() {
+    let mut tree: BinaryTree<i32> = BinaryTree::default();
+    tree.extend([10, 5, 15]);
+    assert!(tree.contains(&5));
+    assert!(!tree.contains(&7));
+    assert_eq!(format!("{:?}", tree), "{5, 10, 15}");
+}
+
+#[test]
+fn sorted_input_does_not_overflow_the_stack() {
+    // Inserting sorted data makes the tree a linked list 20,000 nodes deep,
+    // more than enough to exhaust a test thread's stack if `add`, `depth`,
+    // iteration, or dropping recursed.
+    let n = 20_000;
+    let tree: BinaryTree<u32> = (0..n).collect();
+    assert_eq!(tree.depth(), n as usize);
+    assert!(tree.iter().copied().eq(0..n));
+    drop(tree);
+}
+
+/// A small deterministic generator, so the large test is reproducible
+/// without pulling in a random number crate.
+fn xorshift(seed: &mut u64) -> u64 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 7;
+    *seed ^= *seed << 17;
+    *seed
+}
+
+#[test]
+fn large_randomized_insert_and_traverse() {
+    let mut seed = 0x2545_f491_4f6c_dd1d;
+    let values: Vec<u64> = (0..50_000).map(|_| xorshift(&mut seed) % 10_000).collect();
+
+    let tree: BinaryTree<u64> = values.iter().copied().collect();
+    let mut expected = values.clone();
+    expected.sort();
+
+    assert_eq!(tree.len(), values.len());
+    assert!(tree.iter().copied().eq(expected.iter().copied()));
+    // A tree built from random input should be nowhere near degenerate.
+    assert!(tree.depth() < 100, "depth {}", tree.depth());
+    for v in values.iter().take(1000) {
+        assert!(tree.contains(v));
+    }
+}
+
+proptest! {
+    #[test]
+    fn iteration_matches_sorted_input(values in prop::collection::vec(any::<i16>(), 0..300)) {
+        let tree: BinaryTree<i16> = values.iter().copied().collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+        prop_assert_eq!(tree.iter().copied().collect::<Vec<_>>(), sorted);
+        prop_assert_eq!(tree.len(), values.len());
+        prop_assert!(tree.depth() <= values.len());
+    }
+
+    #[test]
+    fn size_hint_is_a_lower_bound(values in prop::collection::vec(any::<u8>(), 0..100)) {
+        let tree: BinaryTree<u8> = values.into_iter().collect();
+        let mut iter = tree.iter();
+        let mut remaining = tree.len();
+        loop {
+            let (lower, upper) = iter.size_hint();
+            prop_assert!(lower <= remaining);
+            prop_assert!(upper.is_none_or(|u| u >= remaining));
+            if iter.next().is_none() {
+                break;
+            }
+            remaining -= 1;
+        }
+    }
+}
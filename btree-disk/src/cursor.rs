@@ -0,0 +1,145 @@
+// Cursors and range scans.
+//
+// A cursor holds a decoded copy of one leaf and a position within it.
+// Moving past the end of the leaf follows its `next` link to the leaf on
+// the right, so a scan reads each leaf page once and never goes back up
+// the tree. Cursors borrow the tree, so it can't change under them.
+
+use std::ops::Bound;
+
+use crate::error::Result;
+use crate::node::Leaf;
+use crate::tree::BTree;
+
+pub struct Cursor<'a> {
+    tree: &'a BTree,
+    leaf: Leaf,
+    index: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(tree: &'a BTree) -> Cursor<'a> {
+        Cursor {
+            tree,
+            leaf: Leaf::default(),
+            index: 0,
+        }
+    }
+
+    /// Move to the first entry whose key is at least `key`.
+    pub fn seek(&mut self, key: &[u8]) -> Result<()> {
+        self.leaf = self.tree.leaf_for(Some(key))?;
+        self.index = self.leaf.keys.partition_point(|k| k.as_slice() < key);
+        self.settle()
+    }
+
+    /// Move to the first entry in the tree.
+    pub fn seek_first(&mut self) -> Result<()> {
+        self.leaf = self.tree.leaf_for(None)?;
+        self.index = 0;
+        self.settle()
+    }
+
+    /// Move to the next entry. Does nothing once the cursor is past the end.
+    pub fn advance(&mut self) -> Result<()> {
+        if self.index < self.leaf.keys.len() {
+            self.index += 1;
+        }
+        self.settle()
+    }
+
+    /// The key at the cursor, or `None` if it's past the end of the tree
+    /// or hasn't been positioned yet.
+    pub fn key(&self) -> Option<&[u8]> {
+        self.leaf.keys.get(self.index).map(Vec::as_slice)
+    }
+
+    pub fn value(&self) -> Option<&[u8]> {
+        self.leaf.values.get(self.index).map(Vec::as_slice)
+    }
+
+    /// If the cursor has run off the end of its leaf, move to the start of
+    /// the next one that has any entries.
+    fn settle(&mut self) -> Result<()> {
+        while self.index == self.leaf.keys.len() {
+            match self.leaf.next {
+                Some(next) => {
+                    self.leaf = self.tree.read_leaf(next)?;
+                    self.index = 0;
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the entries in a range of keys, in order.
+///
+/// Reading pages can fail, so each item is a `Result`; after an error the
+/// iterator ends.
+pub struct Range<'a> {
+    cursor: Cursor<'a>,
+    /// Where to seek on the first call to `next`: seeking reads pages, and
+    /// `BTree::range` would rather not return a `Result` itself.
+    start: Option<Bound<Vec<u8>>>,
+    end: Bound<Vec<u8>>,
+    done: bool,
+}
+
+impl<'a> Range<'a> {
+    pub(crate) fn new(cursor: Cursor<'a>, start: Bound<Vec<u8>>, end: Bound<Vec<u8>>) -> Range<'a> {
+        Range {
+            cursor,
+            start: Some(start),
+            end,
+            done: false,
+        }
+    }
+
+    fn step(&mut self) -> Result<()> {
+        match self.start.take() {
+            None => self.cursor.advance(),
+            Some(Bound::Unbounded) => self.cursor.seek_first(),
+            Some(Bound::Included(key)) => self.cursor.seek(&key),
+            Some(Bound::Excluded(key)) => {
+                self.cursor.seek(&key)?;
+                if self.cursor.key() == Some(&key[..]) {
+                    self.cursor.advance()?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Iterator for Range<'_> {
+    type Item = Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Err(e) = self.step() {
+            self.done = true;
+            return Some(Err(e));
+        }
+        let (key, value) = match (self.cursor.key(), self.cursor.value()) {
+            (Some(key), Some(value)) => (key, value),
+            _ => {
+                self.done = true;
+                return None;
+            }
+        };
+        let in_range = match &self.end {
+            Bound::Unbounded => true,
+            Bound::Included(end) => key <= end.as_slice(),
+            Bound::Excluded(end) => key < end.as_slice(),
+        };
+        if !in_range {
+            self.done = true;
+            return None;
+        }
+        Some(Ok((key.to_vec(), value.to_vec())))
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The file isn't a B-tree, or a page doesn't decode.
+    Corrupt(String),
+    /// A key and value too large to store: each entry must fit in a
+    /// quarter of a page, so that any node can always be split in two.
+    EntryTooLarge {
+        len: usize,
+        max: usize,
+    },
+    /// A page size outside the supported range.
+    BadPageSize(usize),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Corrupt(what) => write!(f, "corrupt B-tree: {}", what),
+            Error::EntryTooLarge { len, max } => {
+                write!(
+                    f,
+                    "entry of {} bytes is larger than the maximum of {}",
+                    len, max
+                )
+            }
+            Error::BadPageSize(size) => write!(f, "unsupported page size {}", size),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
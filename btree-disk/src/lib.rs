@@ -0,0 +1,19 @@
+// A B+ tree stored in a file of fixed-size pages.
+//
+// Page 0 holds metadata; every other page holds one node, or is on the
+// free list. Keys and values are byte strings, kept sorted in the leaves,
+// and the leaves are linked left to right, so range scans walk along the
+// bottom of the tree without going back up. There is no `unsafe` here:
+// pages are decoded into ordinary `Vec`s and encoded back.
+
+#![forbid(unsafe_code)]
+
+mod cursor;
+mod error;
+mod node;
+mod pager;
+mod tree;
+
+pub use cursor::{Cursor, Range};
+pub use error::{Error, Result};
+pub use tree::{BTree, Stats, DEFAULT_PAGE_SIZE, MIN_PAGE_SIZE};
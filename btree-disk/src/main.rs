@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+use std::{env, process};
+
+use btree_disk::BTree;
+
+#[derive(Debug)]
+enum Command {
+    Get(String),
+    Put(String, String),
+    Remove(String),
+    Scan(Option<String>, Option<String>),
+    Check,
+}
+
+#[derive(Debug)]
+struct Arguments {
+    file: String,
+    command: Command,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut file = String::from("index.btree");
+        let mut words = Vec::new();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-f" | "--file" => {
+                    file = args
+                        .next()
+                        .unwrap_or_else(|| usage_error("--file needs a path"));
+                }
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ if arg.starts_with('-') && words.is_empty() => {
+                    usage_error(&format!("unknown option {}", arg))
+                }
+                _ => words.push(arg),
+            }
+        }
+
+        let mut words = words.into_iter();
+        let command = match (words.next().as_deref(), words.len()) {
+            (Some("get"), 1) => Command::Get(words.next().unwrap()),
+            (Some("put"), 2) => Command::Put(words.next().unwrap(), words.next().unwrap()),
+            (Some("rm"), 1) => Command::Remove(words.next().unwrap()),
+            (Some("scan"), 0..=2) => Command::Scan(words.next(), words.next()),
+            (Some("check"), 0) => Command::Check,
+            (Some(command @ ("get" | "put" | "rm" | "scan" | "check")), _) => {
+                usage_error(&format!("wrong number of arguments for {}", command))
+            }
+            (Some(command), _) => usage_error(&format!("unknown command {}", command)),
+            (None, _) => usage_error("no command given"),
+        };
+        Arguments { file, command }
+    }
+}
+
+fn print_usage() {
+    eprintln!("btree-disk - an ordered key-value index in a single file");
+    eprintln!("Usage: btree-disk [-f FILE] <command>");
+    eprintln!("  get KEY          print the value of KEY");
+    eprintln!("  put KEY VALUE    set KEY to VALUE");
+    eprintln!("  rm KEY           delete KEY");
+    eprintln!("  scan [FROM [TO]] print entries with FROM <= key < TO, in order");
+    eprintln!("  check            verify the tree's structure and print its shape");
+    eprintln!("The index lives in FILE, which defaults to index.btree.");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", e);
+    process::exit(1);
+}
+
+fn main() {
+    let args = Arguments::parse();
+    let mut tree = BTree::open(&args.file).unwrap_or_else(|e| fail(e));
+
+    match args.command {
+        Command::Get(key) => match tree.get(key.as_bytes()).unwrap_or_else(|e| fail(e)) {
+            Some(value) => {
+                let mut stdout = io::stdout().lock();
+                stdout
+                    .write_all(&value)
+                    .and_then(|()| writeln!(stdout))
+                    .unwrap_or_else(|e| fail(e));
+            }
+            None => fail(format!("key not found: {}", key)),
+        },
+        Command::Put(key, value) => {
+            tree.insert(key.as_bytes(), value.as_bytes())
+                .unwrap_or_else(|e| fail(e));
+        }
+        Command::Remove(key) => {
+            if tree
+                .remove(key.as_bytes())
+                .unwrap_or_else(|e| fail(e))
+                .is_none()
+            {
+                fail(format!("key not found: {}", key));
+            }
+        }
+        Command::Scan(from, to) => {
+            let entries = match (from, to) {
+                (Some(from), Some(to)) => tree.range(from..to),
+                (Some(from), None) => tree.range(from..),
+                _ => tree.iter(),
+            };
+            let mut stdout = io::stdout().lock();
+            for entry in entries {
+                let (key, value) = entry.unwrap_or_else(|e| fail(e));
+                writeln!(
+                    stdout,
+                    "{}\t{}",
+                    String::from_utf8_lossy(&key),
+                    String::from_utf8_lossy(&value)
+                )
+                .unwrap_or_else(|e| fail(e));
+            }
+        }
+        Command::Check => {
+            let stats = tree.check().unwrap_or_else(|e| fail(e));
+            println!("entries:        {}", tree.len());
+            println!("depth:          {}", stats.depth);
+            println!("leaves:         {}", stats.leaves);
+            println!("internal nodes: {}", stats.internal_nodes);
+            println!("free pages:     {}", stats.free_pages);
+            println!(
+                "file size:      {} pages of {} bytes",
+                stats.pages,
+                tree.page_size()
+            );
+        }
+    }
+    tree.sync().unwrap_or_else(|e| fail(e));
+}
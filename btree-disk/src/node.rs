@@ -0,0 +1,258 @@
+// Nodes, and their encoding as pages.
+//
+// A leaf page:
+//
+//     type      u8   LEAF
+//     count     u16  number of entries
+//     next      u32  the next leaf to the right, or 0 for none
+//     entries   count × (key_len u16, value_len u16, key, value)
+//
+// An internal page:
+//
+//     type      u8   INTERNAL
+//     count     u16  number of keys
+//     child0    u32
+//     entries   count × (key_len u16, key, child u32)
+//
+// In an internal node, keys[i] separates children[i], which holds keys
+// less than it, from children[i + 1], which holds keys greater or equal.
+// All integers are little-endian; the rest of the page is zeros.
+
+use crate::error::{Error, Result};
+
+pub(crate) type PageId = u32;
+
+const LEAF: u8 = 1;
+const INTERNAL: u8 = 2;
+pub(crate) const FREE: u8 = 3;
+
+/// The size of either kind of node's header.
+pub(crate) const NODE_HEADER: usize = 7;
+
+/// The largest encoded entry, in either kind of node, for `page_size`. A
+/// quarter page guarantees that splitting an overfull node at its middle
+/// byte leaves both halves at least a quarter full.
+pub(crate) fn max_entry(page_size: usize) -> usize {
+    (page_size - NODE_HEADER) / 4
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Leaf {
+    pub(crate) keys: Vec<Vec<u8>>,
+    pub(crate) values: Vec<Vec<u8>>,
+    pub(crate) next: Option<PageId>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Internal {
+    pub(crate) keys: Vec<Vec<u8>>,
+    pub(crate) children: Vec<PageId>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Node {
+    Leaf(Leaf),
+    Internal(Internal),
+}
+
+/// The encoded size of a leaf entry. An internal entry for the same key
+/// is never larger, since a value's length field is as big as a child ID's
+/// extra bytes.
+pub(crate) fn leaf_entry_len(key: &[u8], value: &[u8]) -> usize {
+    4 + key.len() + value.len()
+}
+
+fn internal_entry_len(key: &[u8]) -> usize {
+    6 + key.len()
+}
+
+/// The index at which to split entries of the given encoded sizes so
+/// each side gets about half the bytes. Always leaves at least `min`
+/// entries on each side.
+fn split_point(sizes: impl Iterator<Item = usize> + Clone, min: usize) -> usize {
+    let total: usize = sizes.clone().sum();
+    let count = sizes.clone().count();
+    let mut running = 0;
+    for (i, size) in sizes.enumerate() {
+        if running + size / 2 >= total / 2 {
+            return i.clamp(min, count - min);
+        }
+        running += size;
+    }
+    count - min
+}
+
+impl Leaf {
+    pub(crate) fn encoded_len(&self) -> usize {
+        NODE_HEADER
+            + self
+                .keys
+                .iter()
+                .zip(&self.values)
+                .map(|(k, v)| leaf_entry_len(k, v))
+                .sum::<usize>()
+    }
+
+    /// Move the upper half of the entries, by size, into a new leaf, and
+    /// return it with its first key, which separates the two. The caller
+    /// must link the leaves together.
+    pub(crate) fn split(&mut self) -> (Vec<u8>, Leaf) {
+        let sizes = self
+            .keys
+            .iter()
+            .zip(&self.values)
+            .map(|(k, v)| leaf_entry_len(k, v));
+        let at = split_point(sizes, 1);
+        let right = Leaf {
+            keys: self.keys.split_off(at),
+            values: self.values.split_off(at),
+            next: self.next,
+        };
+        (right.keys[0].clone(), right)
+    }
+}
+
+impl Internal {
+    pub(crate) fn encoded_len(&self) -> usize {
+        NODE_HEADER
+            + self
+                .keys
+                .iter()
+                .map(|k| internal_entry_len(k))
+                .sum::<usize>()
+    }
+
+    /// Which child to descend into to find `key`.
+    pub(crate) fn child_index(&self, key: &[u8]) -> usize {
+        self.keys.partition_point(|k| k.as_slice() <= key)
+    }
+
+    /// Split into two nodes around a middle key, which moves up to the
+    /// parent: it's returned along with the new right-hand node.
+    pub(crate) fn split(&mut self) -> (Vec<u8>, Internal) {
+        // The middle key leaves; each side keeps at least one key.
+        let at = split_point(self.keys.iter().map(|k| internal_entry_len(k)), 1);
+        let right_keys = self.keys.split_off(at + 1);
+        let middle = self
+            .keys
+            .pop()
+            .expect("split of an internal node with too few keys");
+        let right_children = self.children.split_off(at + 1);
+        (
+            middle,
+            Internal {
+                keys: right_keys,
+                children: right_children,
+            },
+        )
+    }
+}
+
+impl Node {
+    pub(crate) fn encoded_len(&self) -> usize {
+        match self {
+            Node::Leaf(leaf) => leaf.encoded_len(),
+            Node::Internal(node) => node.encoded_len(),
+        }
+    }
+
+    /// Encode into a page. The node must fit.
+    pub(crate) fn encode(&self, page_size: usize) -> Vec<u8> {
+        let mut page = Vec::with_capacity(page_size);
+        match self {
+            Node::Leaf(leaf) => {
+                page.push(LEAF);
+                page.extend_from_slice(&(leaf.keys.len() as u16).to_le_bytes());
+                page.extend_from_slice(&leaf.next.unwrap_or(0).to_le_bytes());
+                for (key, value) in leaf.keys.iter().zip(&leaf.values) {
+                    page.extend_from_slice(&(key.len() as u16).to_le_bytes());
+                    page.extend_from_slice(&(value.len() as u16).to_le_bytes());
+                    page.extend_from_slice(key);
+                    page.extend_from_slice(value);
+                }
+            }
+            Node::Internal(node) => {
+                page.push(INTERNAL);
+                page.extend_from_slice(&(node.keys.len() as u16).to_le_bytes());
+                page.extend_from_slice(&node.children[0].to_le_bytes());
+                for (key, child) in node.keys.iter().zip(&node.children[1..]) {
+                    page.extend_from_slice(&(key.len() as u16).to_le_bytes());
+                    page.extend_from_slice(key);
+                    page.extend_from_slice(&child.to_le_bytes());
+                }
+            }
+        }
+        assert!(page.len() <= page_size, "node doesn't fit in a page");
+        page.resize(page_size, 0);
+        page
+    }
+
+    pub(crate) fn decode(page: &[u8], id: PageId) -> Result<Node> {
+        let mut reader = PageReader { page, pos: 0, id };
+        let kind = reader.u8()?;
+        let count = reader.u16()? as usize;
+        match kind {
+            LEAF => {
+                let next = reader.u32()?;
+                let mut leaf = Leaf {
+                    keys: Vec::with_capacity(count),
+                    values: Vec::with_capacity(count),
+                    next: (next != 0).then_some(next),
+                };
+                for _ in 0..count {
+                    let key_len = reader.u16()? as usize;
+                    let value_len = reader.u16()? as usize;
+                    leaf.keys.push(reader.bytes(key_len)?.to_vec());
+                    leaf.values.push(reader.bytes(value_len)?.to_vec());
+                }
+                Ok(Node::Leaf(leaf))
+            }
+            INTERNAL => {
+                let mut node = Internal {
+                    keys: Vec::with_capacity(count),
+                    children: vec![reader.u32()?],
+                };
+                for _ in 0..count {
+                    let key_len = reader.u16()? as usize;
+                    node.keys.push(reader.bytes(key_len)?.to_vec());
+                    node.children.push(reader.u32()?);
+                }
+                Ok(Node::Internal(node))
+            }
+            other => Err(Error::Corrupt(format!(
+                "page {} has unknown type {}",
+                id, other
+            ))),
+        }
+    }
+}
+
+/// Reads fields from a page, turning running off the end into an error.
+struct PageReader<'a> {
+    page: &'a [u8],
+    pos: usize,
+    id: PageId,
+}
+
+impl<'a> PageReader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .page
+            .get(self.pos..self.pos + n)
+            .ok_or_else(|| Error::Corrupt(format!("page {} overflows", self.id)))?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+}
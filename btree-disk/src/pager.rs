@@ -0,0 +1,207 @@
+// The file as an array of pages.
+//
+// Page 0 is the metadata page:
+//
+//     magic       8 bytes  "BTREEDSK"
+//     version     u32
+//     page_size   u32
+//     root        u32      the root node's page
+//     page_count  u32      pages in the file, including this one
+//     free_head   u32      the first page on the free list, or 0 for none
+//     len         u64      number of entries in the tree
+//
+// A free page starts with the FREE type byte and the u32 ID of the next
+// free page. Pages freed by merges go on the list and are handed out
+// again before the file grows.
+//
+// Every write goes straight to the file, and nothing is journaled: a
+// crash in the middle of an insert that splits nodes can leave the tree
+// inconsistent. Making that safe is a job for a write-ahead log.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::node::{PageId, FREE};
+
+const MAGIC: &[u8; 8] = b"BTREEDSK";
+const VERSION: u32 = 1;
+const META_LEN: usize = 36;
+
+pub(crate) struct Pager {
+    file: File,
+    pub(crate) page_size: usize,
+    pub(crate) root: PageId,
+    pub(crate) page_count: u32,
+    pub(crate) free_head: PageId,
+    pub(crate) len: u64,
+}
+
+impl Pager {
+    /// Open the file at `path`. If it's new or empty, set it up with a root
+    /// page encoded by `init_root`; otherwise, the page size recorded in
+    /// the file wins over `page_size`.
+    pub(crate) fn open(
+        path: &Path,
+        page_size: usize,
+        init_root: impl FnOnce(usize) -> Vec<u8>,
+    ) -> Result<Pager> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        if file.metadata()?.len() == 0 {
+            let mut pager = Pager {
+                file,
+                page_size,
+                root: 1,
+                page_count: 2,
+                free_head: 0,
+                len: 0,
+            };
+            pager.write(1, &init_root(page_size))?;
+            pager.write_meta()?;
+            return Ok(pager);
+        }
+
+        let mut meta = [0; META_LEN];
+        (&file).read_exact(&mut meta).map_err(|_| not_a_tree())?;
+        if &meta[..8] != MAGIC {
+            return Err(not_a_tree());
+        }
+        let field = |at: usize| u32::from_le_bytes(meta[at..at + 4].try_into().unwrap());
+        if field(8) != VERSION {
+            return Err(Error::Corrupt(format!("unknown version {}", field(8))));
+        }
+        let pager = Pager {
+            file,
+            page_size: field(12) as usize,
+            root: field(16),
+            page_count: field(20),
+            free_head: field(24),
+            len: u64::from_le_bytes(meta[28..36].try_into().unwrap()),
+        };
+        if !crate::tree::valid_page_size(pager.page_size) {
+            return Err(Error::BadPageSize(pager.page_size));
+        }
+        let expected = pager.page_count as u64 * pager.page_size as u64;
+        if pager.file.metadata()?.len() < expected {
+            return Err(Error::Corrupt(format!(
+                "file is shorter than its {} pages",
+                pager.page_count
+            )));
+        }
+        pager.check_id(pager.root)?;
+        Ok(pager)
+    }
+
+    pub(crate) fn write_meta(&mut self) -> Result<()> {
+        let mut page = Vec::with_capacity(self.page_size);
+        page.extend_from_slice(MAGIC);
+        page.extend_from_slice(&VERSION.to_le_bytes());
+        page.extend_from_slice(&(self.page_size as u32).to_le_bytes());
+        page.extend_from_slice(&self.root.to_le_bytes());
+        page.extend_from_slice(&self.page_count.to_le_bytes());
+        page.extend_from_slice(&self.free_head.to_le_bytes());
+        page.extend_from_slice(&self.len.to_le_bytes());
+        page.resize(self.page_size, 0);
+        self.write(0, &page)
+    }
+
+    fn check_id(&self, id: PageId) -> Result<()> {
+        if id == 0 || id >= self.page_count {
+            return Err(Error::Corrupt(format!(
+                "reference to page {} of {}",
+                id, self.page_count
+            )));
+        }
+        Ok(())
+    }
+
+    fn offset(&self, id: PageId) -> u64 {
+        id as u64 * self.page_size as u64
+    }
+
+    /// Read a page. This only needs a shared reference, so cursors can read
+    /// through a `&BTree`: `&File` implements `Read` and `Seek` too.
+    pub(crate) fn read(&self, id: PageId) -> Result<Vec<u8>> {
+        self.check_id(id)?;
+        let mut page = vec![0; self.page_size];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(self.offset(id)))?;
+        file.read_exact(&mut page)?;
+        Ok(page)
+    }
+
+    pub(crate) fn write(&mut self, id: PageId, page: &[u8]) -> Result<()> {
+        debug_assert_eq!(page.len(), self.page_size);
+        self.file.seek(SeekFrom::Start(self.offset(id)))?;
+        self.file.write_all(page)?;
+        Ok(())
+    }
+
+    /// Hand out a page for a new node: the head of the free list if there
+    /// is one, else a new page at the end of the file. The caller writes it.
+    pub(crate) fn allocate(&mut self) -> Result<PageId> {
+        if self.free_head == 0 {
+            let id = self.page_count;
+            self.page_count = self
+                .page_count
+                .checked_add(1)
+                .ok_or_else(|| Error::Corrupt("out of page IDs".to_string()))?;
+            return Ok(id);
+        }
+        let id = self.free_head;
+        let page = self.read(id)?;
+        if page[0] != FREE {
+            return Err(Error::Corrupt(format!(
+                "page {} on the free list is in use",
+                id
+            )));
+        }
+        self.free_head = u32::from_le_bytes(page[1..5].try_into().unwrap());
+        Ok(id)
+    }
+
+    pub(crate) fn free(&mut self, id: PageId) -> Result<()> {
+        let mut page = vec![0; self.page_size];
+        page[0] = FREE;
+        page[1..5].copy_from_slice(&self.free_head.to_le_bytes());
+        self.write(id, &page)?;
+        self.free_head = id;
+        Ok(())
+    }
+
+    /// The pages on the free list, in order.
+    pub(crate) fn free_pages(&self) -> Result<Vec<PageId>> {
+        let mut pages = Vec::new();
+        let mut id = self.free_head;
+        while id != 0 {
+            if pages.len() >= self.page_count as usize {
+                return Err(Error::Corrupt("the free list has a cycle".to_string()));
+            }
+            let page = self.read(id)?;
+            if page[0] != FREE {
+                return Err(Error::Corrupt(format!(
+                    "page {} on the free list is in use",
+                    id
+                )));
+            }
+            pages.push(id);
+            id = u32::from_le_bytes(page[1..5].try_into().unwrap());
+        }
+        Ok(pages)
+    }
+
+    pub(crate) fn sync(&self) -> Result<()> {
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
+
+fn not_a_tree() -> Error {
+    Error::Corrupt("not a B-tree file".to_string())
+}
@@ -0,0 +1,522 @@
+use std::ops::RangeBounds;
+use std::path::Path;
+
+use crate::cursor::{Cursor, Range};
+use crate::error::{Error, Result};
+use crate::node::{self, Internal, Leaf, Node, PageId};
+use crate::pager::Pager;
+
+pub const DEFAULT_PAGE_SIZE: usize = 4096;
+/// The smallest page size `open_with_page_size` accepts. Tiny pages make
+/// for deep trees, which is handy for testing.
+pub const MIN_PAGE_SIZE: usize = 128;
+/// Lengths within a page are stored as `u16`s.
+const MAX_PAGE_SIZE: usize = 1 << 16;
+
+/// Trees deeper than this are assumed to be a cycle in a corrupt file: a
+/// real one would need more pages than a `u32` can number.
+const MAX_DEPTH: usize = 64;
+
+pub(crate) fn valid_page_size(page_size: usize) -> bool {
+    (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size)
+}
+
+/// An ordered map from byte strings to byte strings, kept in a file.
+pub struct BTree {
+    pager: Pager,
+}
+
+/// What happened to a node when it was written back, which its parent
+/// may need to deal with.
+enum Fixup {
+    Fine,
+    /// The node overflowed and was split; the new right-hand node and the
+    /// key separating it from the old one must go in the parent.
+    Split(Vec<u8>, PageId),
+    /// The node is less than a quarter full, and should be merged with a
+    /// sibling or take some of its entries.
+    Underflow,
+}
+
+/// The shape of a tree, as found by `BTree::check`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of levels: 1 for a tree that's just a root leaf.
+    pub depth: usize,
+    pub leaves: u64,
+    pub internal_nodes: u64,
+    pub free_pages: u64,
+    /// Pages in the file, including the metadata page.
+    pub pages: u64,
+}
+
+impl BTree {
+    /// Open the tree in the file at `path`, creating it if necessary.
+    pub fn open(path: impl AsRef<Path>) -> Result<BTree> {
+        BTree::open_with_page_size(path, DEFAULT_PAGE_SIZE)
+    }
+
+    /// Open the tree at `path`, creating it with the given page size if
+    /// necessary. An existing file keeps the page size it was created with.
+    pub fn open_with_page_size(path: impl AsRef<Path>, page_size: usize) -> Result<BTree> {
+        if !valid_page_size(page_size) {
+            return Err(Error::BadPageSize(page_size));
+        }
+        let pager = Pager::open(path.as_ref(), page_size, |page_size| {
+            Node::Leaf(Leaf::default()).encode(page_size)
+        })?;
+        Ok(BTree { pager })
+    }
+
+    pub fn page_size(&self) -> usize {
+        self.pager.page_size
+    }
+
+    /// The largest combined length of a key and value that fits.
+    pub fn max_entry_len(&self) -> usize {
+        node::max_entry(self.pager.page_size) - 6
+    }
+
+    pub fn len(&self) -> u64 {
+        self.pager.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pager.len == 0
+    }
+
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let leaf = self.leaf_for(Some(key))?;
+        Ok(
+            match leaf.keys.binary_search_by(|k| k.as_slice().cmp(key)) {
+                Ok(i) => Some(leaf.values[i].clone()),
+                Err(_) => None,
+            },
+        )
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Insert or replace the value for `key`, returning the old one.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<Option<Vec<u8>>> {
+        let len = key.len() + value.len();
+        if len > self.max_entry_len() {
+            return Err(Error::EntryTooLarge {
+                len,
+                max: self.max_entry_len(),
+            });
+        }
+        let (old, fixup) = self.insert_into(self.pager.root, key, value, 0)?;
+        self.fix_root(fixup)?;
+        if old.is_none() {
+            self.pager.len += 1;
+        }
+        self.pager.write_meta()?;
+        Ok(old)
+    }
+
+    /// Remove `key`, returning its value if it was there.
+    pub fn remove(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let (old, fixup) = self.remove_from(self.pager.root, key, 0)?;
+        if old.is_some() {
+            self.fix_root(fixup)?;
+            self.pager.len -= 1;
+            self.pager.write_meta()?;
+        }
+        Ok(old)
+    }
+
+    /// A cursor over the tree, not yet positioned: call `seek` or
+    /// `seek_first` before reading from it.
+    pub fn cursor(&self) -> Cursor<'_> {
+        Cursor::new(self)
+    }
+
+    /// Every entry, in key order.
+    pub fn iter(&self) -> Range<'_> {
+        self.range::<&[u8], _>(..)
+    }
+
+    /// The entries with keys in `range`, in order. Any type that can be
+    /// viewed as bytes will do for the bounds: `tree.range("a".."m")`.
+    pub fn range<K: AsRef<[u8]>, R: RangeBounds<K>>(&self, range: R) -> Range<'_> {
+        let owned = |bound: std::ops::Bound<&K>| bound.map(|k| k.as_ref().to_vec());
+        Range::new(
+            self.cursor(),
+            owned(range.start_bound()),
+            owned(range.end_bound()),
+        )
+    }
+
+    /// Flush everything to stable storage.
+    pub fn sync(&self) -> Result<()> {
+        self.pager.sync()
+    }
+
+    pub(crate) fn read_node(&self, id: PageId) -> Result<Node> {
+        Node::decode(&self.pager.read(id)?, id)
+    }
+
+    fn write_node(&mut self, id: PageId, node: &Node) -> Result<()> {
+        let page = node.encode(self.pager.page_size);
+        self.pager.write(id, &page)
+    }
+
+    fn min_fill(&self) -> usize {
+        self.pager.page_size / 4
+    }
+
+    /// The leaf that would hold `key`, or the leftmost leaf for `None`.
+    pub(crate) fn leaf_for(&self, key: Option<&[u8]>) -> Result<Leaf> {
+        let mut id = self.pager.root;
+        for _ in 0..MAX_DEPTH {
+            match self.read_node(id)? {
+                Node::Leaf(leaf) => return Ok(leaf),
+                Node::Internal(node) => {
+                    id = match key {
+                        Some(key) => node.children[node.child_index(key)],
+                        None => node.children[0],
+                    }
+                }
+            }
+        }
+        Err(too_deep())
+    }
+
+    pub(crate) fn read_leaf(&self, id: PageId) -> Result<Leaf> {
+        match self.read_node(id)? {
+            Node::Leaf(leaf) => Ok(leaf),
+            Node::Internal(_) => Err(Error::Corrupt(format!(
+                "leaf chain leads to internal page {}",
+                id
+            ))),
+        }
+    }
+
+    fn insert_into(
+        &mut self,
+        id: PageId,
+        key: &[u8],
+        value: &[u8],
+        depth: usize,
+    ) -> Result<(Option<Vec<u8>>, Fixup)> {
+        if depth == MAX_DEPTH {
+            return Err(too_deep());
+        }
+        match self.read_node(id)? {
+            Node::Leaf(mut leaf) => {
+                let old = match leaf.keys.binary_search_by(|k| k.as_slice().cmp(key)) {
+                    Ok(i) => Some(std::mem::replace(&mut leaf.values[i], value.to_vec())),
+                    Err(i) => {
+                        leaf.keys.insert(i, key.to_vec());
+                        leaf.values.insert(i, value.to_vec());
+                        None
+                    }
+                };
+                // Replacing a value with a shorter one can leave the leaf
+                // underfull, so this can need a rebalance as well as a split.
+                Ok((old, self.store(id, Node::Leaf(leaf))?))
+            }
+            Node::Internal(node) => {
+                let i = node.child_index(key);
+                let (old, fixup) = self.insert_into(node.children[i], key, value, depth + 1)?;
+                Ok((old, self.fix_child(id, node, i, fixup)?))
+            }
+        }
+    }
+
+    fn remove_from(
+        &mut self,
+        id: PageId,
+        key: &[u8],
+        depth: usize,
+    ) -> Result<(Option<Vec<u8>>, Fixup)> {
+        if depth == MAX_DEPTH {
+            return Err(too_deep());
+        }
+        match self.read_node(id)? {
+            Node::Leaf(mut leaf) => match leaf.keys.binary_search_by(|k| k.as_slice().cmp(key)) {
+                Ok(i) => {
+                    leaf.keys.remove(i);
+                    let old = leaf.values.remove(i);
+                    Ok((Some(old), self.store(id, Node::Leaf(leaf))?))
+                }
+                Err(_) => Ok((None, Fixup::Fine)),
+            },
+            // The separators above a removed key are left alone: they still
+            // divide the children correctly, even if no entry matches them.
+            Node::Internal(node) => {
+                let i = node.child_index(key);
+                let (old, fixup) = self.remove_from(node.children[i], key, depth + 1)?;
+                Ok((old, self.fix_child(id, node, i, fixup)?))
+            }
+        }
+    }
+
+    /// Write `node` to page `id`, splitting it if it doesn't fit.
+    fn store(&mut self, id: PageId, node: Node) -> Result<Fixup> {
+        let len = node.encoded_len();
+        if len <= self.pager.page_size {
+            self.write_node(id, &node)?;
+            return Ok(if len < self.min_fill() {
+                Fixup::Underflow
+            } else {
+                Fixup::Fine
+            });
+        }
+        let right_id = self.pager.allocate()?;
+        let (separator, left, right) = match node {
+            Node::Leaf(mut left) => {
+                let (separator, right) = left.split();
+                left.next = Some(right_id);
+                (separator, Node::Leaf(left), Node::Leaf(right))
+            }
+            Node::Internal(mut left) => {
+                let (separator, right) = left.split();
+                (separator, Node::Internal(left), Node::Internal(right))
+            }
+        };
+        self.write_node(right_id, &right)?;
+        self.write_node(id, &left)?;
+        Ok(Fixup::Split(separator, right_id))
+    }
+
+    /// Deal with whatever happened to `node`'s `i`th child, then write
+    /// `node` back to page `id` if it changed.
+    fn fix_child(
+        &mut self,
+        id: PageId,
+        mut node: Internal,
+        i: usize,
+        fixup: Fixup,
+    ) -> Result<Fixup> {
+        match fixup {
+            Fixup::Fine => return Ok(Fixup::Fine),
+            Fixup::Split(separator, right) => {
+                node.keys.insert(i, separator);
+                node.children.insert(i + 1, right);
+            }
+            Fixup::Underflow => self.rebalance(&mut node, i)?,
+        }
+        self.store(id, Node::Internal(node))
+    }
+
+    /// Fix `parent`'s underfull `i`th child by merging it with a sibling,
+    /// or, if the two don't fit in one page, by sharing their entries out
+    /// evenly. Either way, the separator between them in `parent` changes,
+    /// so `parent` itself may end up overfull or underfull.
+    fn rebalance(&mut self, parent: &mut Internal, i: usize) -> Result<()> {
+        if parent.children.len() < 2 {
+            return Ok(());
+        }
+        let l = if i == 0 { 0 } else { i - 1 };
+        let (left_id, right_id) = (parent.children[l], parent.children[l + 1]);
+        let page_size = self.pager.page_size;
+        let (merged, split) = match (self.read_node(left_id)?, self.read_node(right_id)?) {
+            (Node::Leaf(mut left), Node::Leaf(right)) => {
+                left.keys.extend(right.keys);
+                left.values.extend(right.values);
+                left.next = right.next;
+                if left.encoded_len() <= page_size {
+                    (Node::Leaf(left), None)
+                } else {
+                    let (separator, right) = left.split();
+                    left.next = Some(right_id);
+                    (Node::Leaf(left), Some((separator, Node::Leaf(right))))
+                }
+            }
+            (Node::Internal(mut left), Node::Internal(right)) => {
+                // The separator comes down between the two sets of children.
+                left.keys.push(std::mem::take(&mut parent.keys[l]));
+                left.keys.extend(right.keys);
+                left.children.extend(right.children);
+                if left.encoded_len() <= page_size {
+                    (Node::Internal(left), None)
+                } else {
+                    let (separator, right) = left.split();
+                    (
+                        Node::Internal(left),
+                        Some((separator, Node::Internal(right))),
+                    )
+                }
+            }
+            _ => {
+                return Err(Error::Corrupt(format!(
+                    "sibling pages {} and {} are at different depths",
+                    left_id, right_id
+                )))
+            }
+        };
+        self.write_node(left_id, &merged)?;
+        match split {
+            Some((separator, right)) => {
+                self.write_node(right_id, &right)?;
+                parent.keys[l] = separator;
+            }
+            None => {
+                self.pager.free(right_id)?;
+                parent.keys.remove(l);
+                parent.children.remove(l + 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// The root is allowed to be underfull, but a root split grows the tree
+    /// by a level, and a root with a single child shrinks it by one.
+    fn fix_root(&mut self, fixup: Fixup) -> Result<()> {
+        let root = self.pager.root;
+        match fixup {
+            Fixup::Fine => {}
+            Fixup::Split(separator, right) => {
+                let new_root = self.pager.allocate()?;
+                let node = Internal {
+                    keys: vec![separator],
+                    children: vec![root, right],
+                };
+                self.write_node(new_root, &Node::Internal(node))?;
+                self.pager.root = new_root;
+            }
+            Fixup::Underflow => {
+                if let Node::Internal(node) = self.read_node(root)? {
+                    if node.keys.is_empty() {
+                        self.pager.root = node.children[0];
+                        self.pager.free(root)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Walk the whole tree and the free list, checking every structural
+    /// invariant: keys in order and within their parents' separators,
+    /// every leaf at the same depth, every node but the root at least a
+    /// quarter full, the leaf chain in order, and every page accounted
+    /// for exactly once.
+    pub fn check(&self) -> Result<Stats> {
+        let page_count = self.pager.page_count as usize;
+        let mut walk = Walk {
+            seen: vec![false; page_count],
+            leaves: Vec::new(),
+            entries: 0,
+            stats: Stats::default(),
+        };
+        walk.seen[0] = true;
+        walk.stats.depth = self.check_node(self.pager.root, None, None, 0, &mut walk)?;
+        if walk.entries != self.pager.len {
+            return Err(Error::Corrupt(format!(
+                "tree holds {} entries, but its length is {}",
+                walk.entries, self.pager.len
+            )));
+        }
+
+        // The leaf chain visits the leaves in the same order as the walk.
+        let mut chain = Vec::new();
+        let mut next = Some(walk.leaves[0]);
+        while let Some(id) = next {
+            if chain.len() > walk.leaves.len() {
+                break;
+            }
+            chain.push(id);
+            next = self.read_leaf(id)?.next;
+        }
+        if chain != walk.leaves {
+            return Err(Error::Corrupt("leaf chain is out of order".to_string()));
+        }
+
+        for id in self.pager.free_pages()? {
+            if std::mem::replace(&mut walk.seen[id as usize], true) {
+                return Err(Error::Corrupt(format!("free page {} is in use", id)));
+            }
+            walk.stats.free_pages += 1;
+        }
+        if let Some(lost) = walk.seen.iter().position(|&seen| !seen) {
+            return Err(Error::Corrupt(format!("page {} is unreachable", lost)));
+        }
+        walk.stats.pages = page_count as u64;
+        Ok(walk.stats)
+    }
+
+    /// Check the subtree at `id`, whose keys must all lie in `low..high`,
+    /// and return its height.
+    fn check_node(
+        &self,
+        id: PageId,
+        low: Option<&[u8]>,
+        high: Option<&[u8]>,
+        depth: usize,
+        walk: &mut Walk,
+    ) -> Result<usize> {
+        let corrupt = |what: &str| Error::Corrupt(format!("page {}: {}", id, what));
+        if depth == MAX_DEPTH {
+            return Err(too_deep());
+        }
+        let node = self.read_node(id)?;
+        if std::mem::replace(&mut walk.seen[id as usize], true) {
+            return Err(corrupt("referenced twice"));
+        }
+        if id != self.pager.root && node.encoded_len() < self.min_fill() {
+            return Err(corrupt("underfull"));
+        }
+        let keys = match &node {
+            Node::Leaf(leaf) => &leaf.keys,
+            Node::Internal(node) => &node.keys,
+        };
+        if keys.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(corrupt("keys out of order"));
+        }
+        if let (Some(low), Some(first)) = (low, keys.first()) {
+            if first.as_slice() < low {
+                return Err(corrupt("key below its separator"));
+            }
+        }
+        if let (Some(high), Some(last)) = (high, keys.last()) {
+            if last.as_slice() >= high {
+                return Err(corrupt("key above its separator"));
+            }
+        }
+
+        match node {
+            Node::Leaf(leaf) => {
+                walk.stats.leaves += 1;
+                walk.entries += leaf.keys.len() as u64;
+                walk.leaves.push(id);
+                Ok(1)
+            }
+            Node::Internal(node) => {
+                walk.stats.internal_nodes += 1;
+                if node.keys.is_empty() {
+                    return Err(corrupt("internal node with no keys"));
+                }
+                let mut height = None;
+                for (i, &child) in node.children.iter().enumerate() {
+                    let low = if i == 0 {
+                        low
+                    } else {
+                        Some(node.keys[i - 1].as_slice())
+                    };
+                    let high = node.keys.get(i).map(Vec::as_slice).or(high);
+                    let h = self.check_node(child, low, high, depth + 1, walk)?;
+                    if *height.get_or_insert(h) != h {
+                        return Err(corrupt("leaves at different depths"));
+                    }
+                }
+                Ok(height.unwrap() + 1)
+            }
+        }
+    }
+}
+
+struct Walk {
+    seen: Vec<bool>,
+    leaves: Vec<PageId>,
+    entries: u64,
+    stats: Stats,
+}
+
+fn too_deep() -> Error {
+    Error::Corrupt(format!("tree is more than {} levels deep", MAX_DEPTH))
+}
@@ -0,0 +1,136 @@
+// Random operations against the tree and a `BTreeMap`, which must agree
+// about everything. Pages are tiny and keys come from a small alphabet,
+// so the trees get deep and every split, merge, and redistribution path
+// gets exercised.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use btree_disk::{BTree, MIN_PAGE_SIZE};
+use proptest::prelude::*;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(Vec<u8>, Vec<u8>),
+    Remove(Vec<u8>),
+    Get(Vec<u8>),
+    Range(Bound<Vec<u8>>, Bound<Vec<u8>>),
+    Reopen,
+}
+
+fn key() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(b'a'..=b'f', 0..6)
+}
+
+fn value() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..12)
+}
+
+fn bound() -> impl Strategy<Value = Bound<Vec<u8>>> {
+    prop_oneof![
+        Just(Bound::Unbounded),
+        key().prop_map(Bound::Included),
+        key().prop_map(Bound::Excluded),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        6 => (key(), value()).prop_map(|(k, v)| Op::Insert(k, v)),
+        4 => key().prop_map(Op::Remove),
+        2 => key().prop_map(Op::Get),
+        1 => (bound(), bound()).prop_map(|(start, end)| Op::Range(start, end)),
+        1 => Just(Op::Reopen),
+    ]
+}
+
+/// `BTreeMap::range` panics on ranges that start after they end, or that
+/// exclude the same key at both ends; the tree just returns nothing.
+fn model_range(
+    model: &BTreeMap<Vec<u8>, Vec<u8>>,
+    start: &Bound<Vec<u8>>,
+    end: &Bound<Vec<u8>>,
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+    model
+        .iter()
+        .filter(|(k, _)| {
+            let after_start = match start {
+                Bound::Unbounded => true,
+                Bound::Included(s) => *k >= s,
+                Bound::Excluded(s) => *k > s,
+            };
+            let before_end = match end {
+                Bound::Unbounded => true,
+                Bound::Included(e) => *k <= e,
+                Bound::Excluded(e) => *k < e,
+            };
+            after_start && before_end
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn agrees_with_btreemap(ops in prop::collection::vec(op(), 1..400)) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tree");
+        let mut tree = BTree::open_with_page_size(&path, MIN_PAGE_SIZE).unwrap();
+        let mut model = BTreeMap::new();
+
+        for op in ops {
+            match op {
+                Op::Insert(k, v) => {
+                    prop_assert_eq!(tree.insert(&k, &v).unwrap(), model.insert(k, v));
+                }
+                Op::Remove(k) => {
+                    prop_assert_eq!(tree.remove(&k).unwrap(), model.remove(&k));
+                }
+                Op::Get(k) => {
+                    prop_assert_eq!(tree.get(&k).unwrap(), model.get(&k).cloned());
+                }
+                Op::Range(start, end) => {
+                    let expected = model_range(&model, &start, &end);
+                    let actual: Vec<_> = tree
+                        .range::<Vec<u8>, _>((start, end))
+                        .collect::<Result<_, _>>()
+                        .unwrap();
+                    prop_assert_eq!(actual, expected);
+                }
+                Op::Reopen => {
+                    drop(tree);
+                    tree = BTree::open(&path).unwrap();
+                }
+            }
+            prop_assert_eq!(tree.len(), model.len() as u64);
+            tree.check().unwrap();
+        }
+
+        let all: Vec<_> = tree.iter().collect::<Result<_, _>>().unwrap();
+        let expected: Vec<_> = model.into_iter().collect();
+        prop_assert_eq!(all, expected);
+    }
+
+    /// Filling a tree and then emptying it in a random order leaves a
+    /// single empty leaf, with every other page on the free list.
+    #[test]
+    fn empties_completely(keys in prop::collection::btree_set(prop::collection::vec(any::<u8>(), 1..16), 1..300)) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tree = BTree::open_with_page_size(dir.path().join("tree"), MIN_PAGE_SIZE).unwrap();
+        for k in &keys {
+            tree.insert(k, b"value").unwrap();
+        }
+        tree.check().unwrap();
+        let mut order: Vec<_> = keys.into_iter().collect();
+        order.reverse();
+        order.sort_by_key(|k| k.iter().map(|&b| b as u32).sum::<u32>() % 7);
+        for k in &order {
+            prop_assert_eq!(tree.remove(k).unwrap(), Some(b"value".to_vec()));
+        }
+        let stats = tree.check().unwrap();
+        prop_assert_eq!(stats.depth, 1);
+        prop_assert_eq!(stats.free_pages, stats.pages - 2);
+    }
+}
@@ -0,0 +1,189 @@
+use std::fs;
+
+use btree_disk::{BTree, Error, MIN_PAGE_SIZE};
+
+fn key(i: u32) -> Vec<u8> {
+    format!("key{:06}", i).into_bytes()
+}
+
+fn collect(entries: btree_disk::Range) -> Vec<(Vec<u8>, Vec<u8>)> {
+    entries.map(Result::unwrap).collect()
+}
+
+#[test]
+fn insert_get_remove() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tree = BTree::open(dir.path().join("t")).unwrap();
+    assert!(tree.is_empty());
+    assert_eq!(tree.get(b"a").unwrap(), None);
+
+    assert_eq!(tree.insert(b"a", b"1").unwrap(), None);
+    assert_eq!(tree.insert(b"b", b"").unwrap(), None);
+    assert_eq!(tree.insert(b"a", b"2").unwrap(), Some(b"1".to_vec()));
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.get(b"a").unwrap(), Some(b"2".to_vec()));
+    assert!(tree.contains_key(b"b").unwrap());
+
+    assert_eq!(tree.remove(b"a").unwrap(), Some(b"2".to_vec()));
+    assert_eq!(tree.remove(b"a").unwrap(), None);
+    assert_eq!(tree.len(), 1);
+    tree.check().unwrap();
+}
+
+#[test]
+fn grows_and_shrinks() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tree = BTree::open_with_page_size(dir.path().join("t"), 256).unwrap();
+    for i in 0..2000 {
+        tree.insert(&key(i), &i.to_le_bytes()).unwrap();
+    }
+    let stats = tree.check().unwrap();
+    assert!(stats.depth >= 3, "{:?}", stats);
+    assert_eq!(stats.free_pages, 0);
+
+    for i in (0..2000).filter(|i| i % 7 != 0) {
+        assert_eq!(
+            tree.remove(&key(i)).unwrap(),
+            Some(i.to_le_bytes().to_vec())
+        );
+    }
+    let after = tree.check().unwrap();
+    assert!(after.depth < stats.depth, "{:?}", after);
+    assert!(after.free_pages > 0);
+    assert_eq!(tree.len(), 286);
+
+    // Freed pages are reused before the file grows.
+    for i in (0..2000).filter(|i| i % 7 != 0) {
+        tree.insert(&key(i), b"again").unwrap();
+    }
+    let refilled = tree.check().unwrap();
+    assert!(refilled.pages <= stats.pages + 10, "{:?}", refilled);
+}
+
+#[test]
+fn persists_across_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("t");
+    {
+        let mut tree = BTree::open_with_page_size(&path, 512).unwrap();
+        for i in 0..500 {
+            tree.insert(&key(i), format!("value {}", i).as_bytes())
+                .unwrap();
+        }
+        tree.remove(&key(250)).unwrap();
+        tree.sync().unwrap();
+    }
+    // The page size recorded in the file wins.
+    let tree = BTree::open(&path).unwrap();
+    assert_eq!(tree.page_size(), 512);
+    assert_eq!(tree.len(), 499);
+    assert_eq!(tree.get(&key(499)).unwrap(), Some(b"value 499".to_vec()));
+    assert_eq!(tree.get(&key(250)).unwrap(), None);
+    tree.check().unwrap();
+}
+
+#[test]
+fn ranges() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tree = BTree::open_with_page_size(dir.path().join("t"), MIN_PAGE_SIZE).unwrap();
+    for word in ["kiwi", "apple", "fig", "cherry", "banana", "grape", "date"] {
+        tree.insert(word.as_bytes(), &[word.len() as u8]).unwrap();
+    }
+    let keys = |entries| -> Vec<String> {
+        collect(entries)
+            .into_iter()
+            .map(|(k, _)| String::from_utf8(k).unwrap())
+            .collect()
+    };
+    assert_eq!(
+        keys(tree.iter()),
+        ["apple", "banana", "cherry", "date", "fig", "grape", "kiwi"]
+    );
+    assert_eq!(
+        keys(tree.range("banana".."fig")),
+        ["banana", "cherry", "date"]
+    );
+    assert_eq!(keys(tree.range("c"..="fig")), ["cherry", "date", "fig"]);
+    assert_eq!(keys(tree.range(.."b")), ["apple"]);
+    assert_eq!(keys(tree.range("grape"..)), ["grape", "kiwi"]);
+    assert_eq!(keys(tree.range("x"..)), Vec::<String>::new());
+    assert_eq!(keys(tree.range("d".."c")), Vec::<String>::new());
+
+    use std::ops::Bound::{Excluded, Unbounded};
+    let after_date = tree.range::<&str, _>((Excluded("date"), Unbounded));
+    assert_eq!(keys(after_date), ["fig", "grape", "kiwi"]);
+}
+
+#[test]
+fn cursor() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tree = BTree::open_with_page_size(dir.path().join("t"), MIN_PAGE_SIZE).unwrap();
+    let mut cursor = tree.cursor();
+    assert_eq!(cursor.key(), None);
+    cursor.seek_first().unwrap();
+    assert_eq!(cursor.key(), None);
+
+    for i in (0..300).step_by(3) {
+        tree.insert(&key(i), &i.to_le_bytes()).unwrap();
+    }
+    let mut cursor = tree.cursor();
+    cursor.seek(&key(100)).unwrap();
+    assert_eq!(cursor.key(), Some(&key(102)[..]));
+    assert_eq!(cursor.value(), Some(&102u32.to_le_bytes()[..]));
+    cursor.advance().unwrap();
+    assert_eq!(cursor.key(), Some(&key(105)[..]));
+
+    // Walking off the end leaves the cursor there.
+    cursor.seek(&key(297)).unwrap();
+    cursor.advance().unwrap();
+    assert_eq!(cursor.key(), None);
+    cursor.advance().unwrap();
+    assert_eq!(cursor.key(), None);
+
+    let mut count = 0;
+    cursor.seek_first().unwrap();
+    while cursor.key().is_some() {
+        count += 1;
+        cursor.advance().unwrap();
+    }
+    assert_eq!(count, 100);
+}
+
+#[test]
+fn entry_size_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut tree = BTree::open(dir.path().join("t")).unwrap();
+    let max = tree.max_entry_len();
+    tree.insert(b"k", &vec![0; max - 1]).unwrap();
+    match tree.insert(b"k", &vec![0; max]) {
+        Err(Error::EntryTooLarge { len, .. }) => assert_eq!(len, max + 1),
+        other => panic!("expected EntryTooLarge, got {:?}", other),
+    }
+    tree.check().unwrap();
+}
+
+#[test]
+fn rejects_bad_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("t");
+    fs::write(&path, b"this is not a B-tree").unwrap();
+    assert!(matches!(BTree::open(&path), Err(Error::Corrupt(_))));
+
+    assert!(matches!(
+        BTree::open_with_page_size(dir.path().join("u"), 100),
+        Err(Error::BadPageSize(100))
+    ));
+
+    // A page whose type byte has been scribbled on.
+    let path = dir.path().join("v");
+    let mut tree = BTree::open_with_page_size(&path, 256).unwrap();
+    for i in 0..100 {
+        tree.insert(&key(i), b"x").unwrap();
+    }
+    drop(tree);
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[256 * 2] = 0xee;
+    fs::write(&path, bytes).unwrap();
+    let tree = BTree::open(&path).unwrap();
+    assert!(matches!(tree.check(), Err(Error::Corrupt(_))));
+}
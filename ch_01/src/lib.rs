@@ -0,0 +1,39 @@
+// The library half of this chapter: one small function for each of the
+// promises the Readme makes, returning what it found rather than printing
+// it, so that `main` does the talking and tests can check the answers.
+
+use std::thread;
+
+/// Safety: a mutable borrow of `data` pushes `value`, and once the borrow
+/// ends, `data` is ours to use again.
+pub fn push_through_borrow(mut data: Vec<i32>, value: i32) -> Vec<i32> {
+    let reference_to_data = &mut data;
+    reference_to_data.push(value);
+    data
+}
+
+/// Concurrency: run `work` on a new thread and return its result. If the
+/// thread panics, the panic is returned as an error instead of taking this
+/// thread down with it.
+pub fn on_another_thread<T, F>(work: F) -> thread::Result<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    thread::spawn(work).join()
+}
+
+/// Predictable behavior: indexing is bounds-checked. `arr[index]` would
+/// panic out of bounds; `get` says so with `None`.
+pub fn checked_index(arr: &[i32], index: usize) -> Option<i32> {
+    arr.get(index).copied()
+}
+
+/// Zero overhead: an `Option` makes "no value" explicit, at no cost over a
+/// nullable value.
+pub fn describe(maybe_number: Option<i32>) -> String {
+    match maybe_number {
+        Some(value) => format!("Handling Option type safely - Value: {}", value),
+        None => "Handling Option type safely - No value!".to_string(),
+    }
+}
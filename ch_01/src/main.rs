@@ -1,55 +1,34 @@
 // A Rust program showcasing safety, concurrency, performance, and zero-overhead principles
 
-// Safety: Demonstrating memory safety with ownership and borrowing
-fn demonstrate_safety() {
-    let mut data = vec![1, 2, 3];
-    let reference_to_data = &mut data;
+use std::process::ExitCode;
 
-    reference_to_data.push(4);
-    println!("Demonstrating memory safety: {:?}", data);
-}
-
-// Concurrency: Using threads for concurrent execution
-fn demonstrate_concurrency() {
-    use std::thread;
+use ch_1::{checked_index, describe, on_another_thread, push_through_borrow};
+use common::Error;
+use tracing::debug;
 
-    let handle = thread::spawn(|| {
-        println!("Concurrent execution using threads");
-    });
+fn summary() -> Result<(), Error> {
+    common::logging::args()?;
+    println!("Rust Program Summary:");
 
-    handle.join().unwrap();
-}
+    let data = push_through_borrow(vec![1, 2, 3], 4);
+    println!("Demonstrating memory safety: {:?}", data);
 
-// Performance: Leveraging Rust's optimization and predictable performance
-fn demonstrate_performance() {
-    let _arr = [1, 2, 3];
+    debug!("spawning a thread");
+    let message = on_another_thread(|| "Concurrent execution using threads").unwrap();
+    debug!("the thread finished");
+    println!("{}", message);
 
-    // Demonstrating array bounds checking for predictable performance
-    // Uncommenting the line below would cause a run-time panic (index out of bounds)
-    // let value = arr[5];
-}
+    let arr = [1, 2, 3];
+    println!(
+        "Bounds checking: arr[1] is {:?}, arr[5] is {:?}",
+        checked_index(&arr, 1),
+        checked_index(&arr, 5)
+    );
 
-// Zero-Overhead Principle: Emphasizing efficient abstractions without runtime overhead
-fn zero_overhead_principle() {
-    // Rust provides high-level abstractions without performance penalties
-    let maybe_number: Option<i32> = Some(42);
-
-    if let Some(value) = maybe_number {
-        println!(
-            "Zero-overhead principle: Handling Option type safely - Value: {}",
-            value
-        );
-    } else {
-        println!("Zero-overhead principle: Handling Option type safely - No value!");
-    }
+    println!("Zero-overhead principle: {}", describe(Some(42)));
+    Ok(())
 }
 
-fn main() {
-    println!("Rust Program Summary:");
-
-    // Demonstrating key aspects of Rust
-    demonstrate_safety();
-    demonstrate_concurrency();
-    demonstrate_performance();
-    zero_overhead_principle();
+fn main() -> ExitCode {
+    common::run(summary)
 }
@@ -0,0 +1,36 @@
+use ch_1::{checked_index, describe, on_another_thread, push_through_borrow};
+
+#[test]
+fn borrow_then_use() {
+    assert_eq!(push_through_borrow(vec![1, 2, 3], 4), [1, 2, 3, 4]);
+    assert_eq!(push_through_borrow(Vec::new(), 7), [7]);
+}
+
+#[test]
+fn threads_return_results_and_panics() {
+    assert_eq!(on_another_thread(|| 6 * 7).unwrap(), 42);
+    let data = [1, 2, 3];
+    assert_eq!(
+        on_another_thread(move || data.iter().sum::<i32>()).unwrap(),
+        6
+    );
+    assert!(on_another_thread(|| -> i32 { panic!("thread failed") }).is_err());
+}
+
+#[test]
+fn indexing_is_checked() {
+    let arr = [1, 2, 3];
+    assert_eq!(checked_index(&arr, 0), Some(1));
+    assert_eq!(checked_index(&arr, 2), Some(3));
+    assert_eq!(checked_index(&arr, 5), None);
+    assert_eq!(checked_index(&[], 0), None);
+}
+
+#[test]
+fn options_say_which() {
+    assert_eq!(
+        describe(Some(42)),
+        "Handling Option type safely - Value: 42"
+    );
+    assert_eq!(describe(None), "Handling Option type safely - No value!");
+}
@@ -0,0 +1,165 @@
+# quickreplace's messages, in English. Every other catalog falls back to
+# this one for anything it lacks.
+# Fluent drops leading spaces, so indentation is a quoted string.
+
+about = change occurrences of one string into another
+replace-about = Replace every match of a pattern, in a file or in every file under a directory
+count-about = Count the matches of a pattern, in a file or in each file under a directory
+undo-about = Put back the changes written down by --journal
+find-about = Show the lines matching a pattern, in a file or in each file under a directory
+usage-replace = quickreplace [replace] [OPTIONS] <target> <replacement> [<input_filename> <output_filename>]
+usage-replace-in-place = {"       "}quickreplace [replace] [OPTIONS] --in-place[=SUFFIX] | --diff | --highlight <target> <replacement> <filename>...
+usage-replace-rules = {"       "}quickreplace [replace] [OPTIONS] --rules FILE [input_filename] [output_filename]
+usage-replace-exec = {"       "}quickreplace [replace] [OPTIONS] --exec CMD <target> [input_filename] [output_filename]
+usage-count = quickreplace count [OPTIONS] <target> [input_filename]...
+usage-find = quickreplace find [OPTIONS] <target> [input_filename]...
+usage-undo = quickreplace undo <journal>
+usage-dash = A filename of - means standard input or standard output, and with no filenames at all, standard input is replaced into standard output.
+usage-dir = If the input is a directory, every file under it matching a --glob PATTERN is used.
+usage-files-from = With --files-from LIST, the files named in LIST are the input, and no filenames are given.
+help-in-place = Rewrite the input itself, keeping the original as its name plus SUFFIX, or the backup_suffix setting; --in-place= keeps no backup
+help-max = Replace only the first N matches in each file
+help-dry-run = Show the lines that would change, and write nothing
+help-expand-env = Put environment variables, as { "${NAME}" } or { "${NAME:-default}" }, into the replacement; { "$${NAME}" } is a literal { "${NAME}" }
+help-diff = Print the changes as a unified diff, in place of the output, and write nothing
+help-highlight = Show each change in color among the lines around it, and write nothing
+help-journal = With --in-place, write down each change in FILE, for undo to put back
+help-preserve = Give each file written the permissions, owner, and times of the file it was made from, or only the ATTRIBUTES listed, of mode, ownership, and timestamps
+help-report = Print a report of every replacement, in FORMAT, which can only be json, instead of the usual messages
+help-report-file = Write the --report to FILE, and print the usual messages as well
+help-stats = After rewriting a directory or a list of files, print a table of each file's matches, replacements, bytes changed, and time
+help-plugin = A shared library that every replacement is passed through
+help-exec = Replace each match with what CMD prints, run by the shell with the match on its standard input and in QUICKREPLACE_MATCH, and its groups in QUICKREPLACE_GROUP_1, QUICKREPLACE_GROUP_name, and so on; no replacement is given
+help-ignore-case = Match letters in either case
+help-case-sensitive = Match letters only in the case given, whatever the ignore_case setting says
+help-smart-case = Match letters in either case, unless the pattern has an uppercase one
+help-fixed-strings = Take the target, and the replacement, as plain text, not a regex and a template
+help-word-regexp = Only match whole words, with no letter, digit, or underscore just before or after
+help-multiline = Let ^ and $ match at the start and end of each line, not just of the text
+help-dotall = Let . match a newline too, so a match can span lines
+help-rules = A TOML file of [[rule]]s, each a pattern and its replacement, in place of the target and replacement; all apply in one pass
+help-only-lines-matching = Only replace matches on lines that REGEX matches somewhere
+help-skip-lines-matching = Leave matches on lines that REGEX matches as they are
+help-csv = Take each file as CSV, with a header, and only replace in the --columns given, keeping its quoting and commas
+help-columns = With --csv, the columns to replace in, by the names in the header, separated by commas
+help-json = Take each file as JSON, and only replace in the strings each --pointer addresses, leaving the rest as it was
+help-pointer = With --json, the strings to replace in, by JSON pointer, like /users/*/email, where * is any key or index
+help-regex-size-limit = Refuse a pattern that compiles to more than SIZE of memory, like 512K or 100M; by default, 10M
+help-dfa-size-limit = Let each search cache up to SIZE of memory, like 512K or 10M; a pattern that needs more is searched more slowly; by default, 2M
+help-glob = With a directory as input, only the files under it matching PATTERN
+help-exclude = With a directory as input, leave out the files and directories under it matching PATTERN, as well as the exclude setting's
+help-no-ignore = With a directory as input, don't leave out what .gitignore and .ignore files say to
+help-files-from = Rewrite the files named in LIST, one per line, or - to read them from standard input, instead of an input file
+help-null = The names in the --files-from list are each ended by a NUL, as find -print0 writes them, not a newline
+help-jobs = With a directory as input, how many files to do at once; by default, one per CPU
+help-stream = Read a line at a time, so a huge file fits in memory; no match can span lines
+help-compress = The input and output are gzipped, whatever they're named; files named .gz always are
+help-watch = After replacing, wait for the input to change, and replace again each time it does, until interrupted
+help-encoding = What the input is encoded in, and the output is written back in; auto tells from each file's byte order mark, or takes it as UTF-8 if it can be, and Latin-1 if not
+help-fail-if-no-match = Exit with 1 if nothing matched
+help-no-progress = Don't show a progress bar, even on a terminal
+help-verbose = Log more: info, then debug, then trace
+help-log-format = Log as text or json
+help-lang = The language to speak, like en or es
+help-config = Read settings from FILE instead of ~/.quickreplace.toml
+wrong-args = wrong number of arguments: expected { $expected }, got { $count }.
+glob-not-dir = --glob only applies when the input is a directory
+stream-dir = --stream only applies to a single file, not a directory or a list of files
+compress-file = --compress only applies to a single file, not a directory or a list of files
+bad-glob = invalid glob '{ $glob }': unclosed [
+tree-stdout = a directory's files can't all be written to standard output
+report-stdout = --report needs a --report-file when the output is standard output
+stats-many = --stats only applies to a directory or a list of files
+journal-invalid = invalid journal '{ $file }'
+undo-changed = '{ $file }' has changed since, so it was left as it is
+undone = Restored '{ $file }'
+undo-failed = { $count ->
+        [one] 1 file
+       *[other] { $count } files
+    } couldn't be restored
+in-place-stdin = --in-place needs a file to rewrite, not standard input
+stdin-among-files = standard input can only be read on its own, not among other files
+watch-file = --watch needs a file to watch, not standard input, a directory, or a list of files
+files-from-in-place = the files in a --files-from list can only be rewritten --in-place, or shown with --dry-run, --diff, or --highlight
+expand-unset = environment variable '{ $name }' isn't set, for --expand-env; give it a default with { "${" }{ $name }{ ":-default}" }
+
+read-failed = failed to read from file '{ $file }'
+read-stdin-failed = failed to read from standard input
+replace-failed = failed to replace text
+replace-in-failed = failed to replace text in '{ $file }'
+plugin-load-failed = failed to load plugin '{ $file }'
+rules-invalid = invalid rules file '{ $file }'
+rules-empty = rules file '{ $file }' has no [[rule]]s
+guard-invalid = invalid line pattern '{ $pattern }'
+group-missing = the replacement refers to a group '{ $group }', which the pattern doesn't have
+group-missing-braces = the replacement refers to a group named '{ $group }', which the pattern doesn't have; for a group by number followed by more text, write { $braced }
+rule-invalid = invalid pattern in rule { $number } of '{ $file }'
+regex-too-big = the pattern compiles to more than { $limit }, the regex size limit; simplify it, or raise the limit with --regex-size-limit
+csv-column-missing = '{ $file }' has no column '{ $column }'; its columns are { $columns }
+json-invalid = '{ $file }' isn't valid JSON
+rule-replacement-invalid = invalid replacement in rule { $number } of '{ $file }'
+write-failed = failed to write to file '{ $file }'
+write-stdout-failed = failed to write to standard output
+backup-failed = failed to back up '{ $file }' to '{ $backup }'
+watch-failed = failed to watch '{ $file }' for changes
+watching = Watching '{ $file }' for changes; press Ctrl-C to stop
+wrote-output = Successfully replaced text and wrote output to '{ $file }'
+replaced-in-place = Successfully replaced text in '{ $file }'
+replaced-in-place-backup = Successfully replaced text in '{ $file }', keeping the original as '{ $backup }'
+dry-run-summary = Would make { $count ->
+        [one] 1 replacement
+       *[other] { $count } replacements
+    }; nothing was written
+counted = { $file }: { $count ->
+        [one] 1 match
+       *[other] { $count } matches
+    }
+
+walk-failed = failed to read directory '{ $dir }'
+tree-changed = { $file }: { $count ->
+        [one] 1 replacement
+       *[other] { $count } replacements
+    }
+tree-summary = Changed { $changed } of { $matched ->
+        [one] 1 file
+       *[other] { $matched } files
+    } { $from ->
+        [list] listed in '{ $dir }'
+        [stdin] listed on standard input
+        [args] named on the command line
+       *[dir] under '{ $dir }'
+    }
+tree-dry-run-summary = Would change { $changed } of { $matched ->
+        [one] 1 file
+       *[other] { $matched } files
+    } { $from ->
+        [list] listed in '{ $dir }'
+        [stdin] listed on standard input
+        [args] named on the command line
+       *[dir] under '{ $dir }'
+    }; nothing was written
+tree-count-summary = { $count ->
+        [one] 1 match
+       *[other] { $count } matches
+    } in { $files } of { $matched ->
+        [one] 1 file
+       *[other] { $matched } files
+    } { $from ->
+        [list] listed in '{ $dir }'
+        [stdin] listed on standard input
+        [args] named on the command line
+       *[dir] under '{ $dir }'
+    }
+stats-file = File
+stats-matches = Matches
+stats-replacements = Replacements
+stats-bytes = Bytes changed
+stats-time = Time
+stats-total = Total, { $files ->
+        [one] 1 file
+       *[other] { $files } files
+    }
+tree-failed = { $count ->
+        [one] 1 file
+       *[other] { $count } files
+    } couldn't be processed
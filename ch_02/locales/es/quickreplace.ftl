@@ -0,0 +1,166 @@
+# Los mensajes de quickreplace, en español.
+
+about = cambia las apariciones de una cadena por otra
+replace-about = Reemplaza cada coincidencia de un patrón, en un archivo o en cada archivo de un directorio
+count-about = Cuenta las coincidencias de un patrón, en un archivo o en cada archivo de un directorio
+undo-about = Deshace los cambios anotados por --journal
+find-about = Muestra las líneas que coinciden con un patrón, en un archivo o en cada archivo de un directorio
+usage-replace = quickreplace [replace] [OPCIONES] <objetivo> <reemplazo> [<archivo_de_entrada> <archivo_de_salida>]
+usage-replace-in-place = {"       "}quickreplace [replace] [OPCIONES] --in-place[=SUFIJO] | --diff | --highlight <objetivo> <reemplazo> <archivo>...
+usage-replace-rules = {"       "}quickreplace [replace] [OPCIONES] --rules ARCHIVO [archivo_de_entrada] [archivo_de_salida]
+usage-replace-exec = {"       "}quickreplace [replace] [OPCIONES] --exec ORDEN <objetivo> [archivo_de_entrada] [archivo_de_salida]
+usage-count = quickreplace count [OPCIONES] <objetivo> [archivo_de_entrada]...
+usage-find = quickreplace find [OPCIONES] <objetivo> [archivo_de_entrada]...
+usage-undo = quickreplace undo <diario>
+usage-dash = Un nombre de archivo - significa la entrada o la salida estándar, y sin ningún nombre de archivo, se reemplaza de la entrada estándar a la salida estándar.
+usage-dir = Si la entrada es un directorio, se usa cada archivo que contiene que coincida con un --glob PATRÓN.
+usage-files-from = Con --files-from LIST, la entrada son los archivos nombrados en LIST, y no se da ningún nombre de archivo.
+help-in-place = Reescribe la propia entrada, guardando el original con su nombre más SUFIJO, o el ajuste backup_suffix; --in-place= no guarda copia
+help-max = Reemplaza solo las primeras N coincidencias de cada archivo
+help-dry-run = Muestra las líneas que cambiarían, y no escribe nada
+help-expand-env = Pone variables de entorno, como { "${NAME}" } o { "${NAME:-predeterminado}" }, en el reemplazo; { "$${NAME}" } es un { "${NAME}" } literal
+help-diff = Imprime los cambios como un diff unificado, en lugar de la salida, y no escribe nada
+help-highlight = Muestra cada cambio en color entre las líneas de alrededor, y no escribe nada
+help-journal = Con --in-place, anota cada cambio en ARCHIVO, para que undo los deshaga
+help-preserve = Da a cada archivo escrito los permisos, el dueño y las fechas del archivo del que sale, o solo los ATRIBUTOS indicados, de mode, ownership y timestamps
+help-report = Imprime un informe de cada reemplazo, en FORMATO, que solo puede ser json, en lugar de los mensajes habituales
+help-report-file = Escribe el --report en ARCHIVO, e imprime también los mensajes habituales
+help-stats = Tras reescribir un directorio o una lista de archivos, imprime una tabla con las coincidencias, los reemplazos, los bytes cambiados y el tiempo de cada archivo
+help-plugin = Una biblioteca compartida por la que pasa cada reemplazo
+help-exec = Reemplaza cada coincidencia con lo que imprime CMD, una orden ejecutada por el shell con la coincidencia en su entrada estándar y en QUICKREPLACE_MATCH, y sus grupos en QUICKREPLACE_GROUP_1, QUICKREPLACE_GROUP_nombre, etc.; no se da reemplazo
+help-ignore-case = Coincide con letras en mayúsculas o minúsculas
+help-case-sensitive = Coincide con las letras solo como están escritas, diga lo que diga el ajuste ignore_case
+help-smart-case = Coincide con letras en mayúsculas o minúsculas, a menos que el patrón tenga alguna mayúscula
+help-fixed-strings = Toma el objetivo y el reemplazo como texto literal, no como una expresión regular y una plantilla
+help-word-regexp = Solo coincide con palabras completas, sin letra, dígito ni guion bajo justo antes o después
+help-multiline = Hace que ^ y $ coincidan al principio y al final de cada línea, no solo del texto
+help-dotall = Hace que . coincida también con un salto de línea, para que una coincidencia abarque varias líneas
+help-rules = Un archivo TOML de [[rule]]s, cada una un patrón y su reemplazo, en lugar del objetivo y el reemplazo; se aplican todas en una sola pasada
+help-only-lines-matching = Solo reemplaza coincidencias en las líneas en que REGEX coincide
+help-skip-lines-matching = Deja como están las coincidencias en las líneas en que REGEX coincide
+help-csv = Toma cada archivo como CSV, con una cabecera, y solo reemplaza en las --columns dadas, manteniendo sus comillas y comas
+help-columns = Con --csv, las columnas en que reemplazar, por los nombres de la cabecera, separados por comas
+help-json = Toma cada archivo como JSON, y solo reemplaza en las cadenas a que apunta cada --pointer, dejando el resto como estaba
+help-pointer = Con --json, las cadenas en que reemplazar, por puntero JSON, como /users/*/email, donde * es cualquier clave o índice
+help-regex-size-limit = Rechaza un patrón que compilado ocupe más de SIZE de memoria, como 512K o 100M; por defecto, 10M
+help-dfa-size-limit = Deja que cada búsqueda guarde hasta SIZE de memoria, como 512K o 10M; un patrón que necesite más se busca más despacio; por defecto, 2M
+help-glob = Con un directorio como entrada, solo los archivos que contiene que coincidan con PATRÓN
+help-exclude = Con un directorio como entrada, omite los archivos y directorios que contiene que coincidan con PATRÓN, además de los del ajuste exclude
+help-no-ignore = Con un directorio como entrada, no omite lo que dicen los archivos .gitignore e .ignore
+help-files-from = Reescribe los archivos nombrados en LIST, uno por línea, o - para leerlos de la entrada estándar, en lugar de un archivo de entrada
+help-null = Los nombres de la lista de --files-from terminan cada uno en un NUL, como los escribe find -print0, no en un salto de línea
+help-jobs = Con un directorio como entrada, cuántos archivos procesar a la vez; por defecto, uno por CPU
+help-stream = Lee línea a línea, así que un archivo enorme cabe en memoria; ninguna coincidencia puede abarcar varias líneas
+help-compress = La entrada y la salida están comprimidas con gzip, se llamen como se llamen; los archivos .gz lo están siempre
+help-watch = Tras reemplazar, espera a que cambie la entrada, y reemplaza de nuevo cada vez que cambia, hasta que se interrumpa
+help-encoding = La codificación de la entrada, en la que también se escribe la salida; auto la deduce de la marca de orden de bytes de cada archivo, o la toma como UTF-8 si puede serlo, y Latin-1 si no
+help-fail-if-no-match = Sale con 1 si nada coincidió
+help-no-progress = No muestra una barra de progreso, ni siquiera en una terminal
+help-verbose = Registra más: info, luego debug, luego trace
+help-log-format = Registra como text o json
+help-lang = El idioma en que hablar, como en o es
+help-config = Lee los ajustes de ARCHIVO en lugar de ~/.quickreplace.toml
+wrong-args = número incorrecto de argumentos: se esperaban { $expected } y { $count ->
+        [one] se recibió { $count }
+       *[other] se recibieron { $count }
+    }.
+glob-not-dir = --glob solo sirve cuando la entrada es un directorio
+stream-dir = --stream solo sirve para un archivo, no para un directorio ni una lista de archivos
+compress-file = --compress solo sirve para un archivo, no para un directorio ni una lista de archivos
+bad-glob = el patrón '{ $glob }' no es válido: falta cerrar [
+tree-stdout = los archivos de un directorio no pueden escribirse todos en la salida estándar
+report-stdout = --report necesita un --report-file cuando la salida es la salida estándar
+stats-many = --stats solo sirve para un directorio o una lista de archivos
+journal-invalid = diario no válido '{ $file }'
+undo-changed = '{ $file }' ha cambiado desde entonces, así que se dejó como está
+undone = Restaurado '{ $file }'
+undo-failed = { $count ->
+        [one] 1 archivo
+       *[other] { $count } archivos
+    } no se pudieron restaurar
+in-place-stdin = --in-place necesita un archivo que reescribir, no la entrada estándar
+stdin-among-files = la entrada estándar solo puede leerse sola, no entre otros archivos
+watch-file = --watch necesita un archivo que vigilar, no la entrada estándar, un directorio ni una lista de archivos
+files-from-in-place = los archivos de una lista de --files-from solo se pueden reescribir con --in-place, o mostrar con --dry-run, --diff o --highlight
+expand-unset = la variable de entorno '{ $name }' no está definida, para --expand-env; dale un valor predeterminado con { "${" }{ $name }{ ":-valor}" }
+
+read-failed = no se pudo leer el archivo '{ $file }'
+read-stdin-failed = no se pudo leer la entrada estándar
+replace-failed = no se pudo reemplazar el texto
+replace-in-failed = no se pudo reemplazar el texto de '{ $file }'
+plugin-load-failed = no se pudo cargar el plugin '{ $file }'
+rules-invalid = archivo de reglas no válido '{ $file }'
+rules-empty = el archivo de reglas '{ $file }' no tiene [[rule]]s
+guard-invalid = patrón de línea no válido '{ $pattern }'
+group-missing = el reemplazo se refiere a un grupo '{ $group }', que el patrón no tiene
+group-missing-braces = el reemplazo se refiere a un grupo llamado '{ $group }', que el patrón no tiene; para un grupo por número seguido de más texto, escribe { $braced }
+rule-invalid = patrón no válido en la regla { $number } de '{ $file }'
+regex-too-big = el patrón compilado ocupa más de { $limit }, el límite de tamaño de las expresiones regulares; simplifícalo, o sube el límite con --regex-size-limit
+csv-column-missing = '{ $file }' no tiene la columna '{ $column }'; sus columnas son { $columns }
+json-invalid = '{ $file }' no es JSON válido
+rule-replacement-invalid = reemplazo no válido en la regla { $number } de '{ $file }'
+write-failed = no se pudo escribir en el archivo '{ $file }'
+write-stdout-failed = no se pudo escribir en la salida estándar
+backup-failed = no se pudo copiar '{ $file }' a '{ $backup }'
+watch-failed = no se pudo vigilar '{ $file }'
+watching = Vigilando los cambios en '{ $file }'; pulsa Ctrl-C para parar
+wrote-output = Texto reemplazado; el resultado se escribió en '{ $file }'
+replaced-in-place = Texto reemplazado en '{ $file }'
+replaced-in-place-backup = Texto reemplazado en '{ $file }'; el original se guardó en '{ $backup }'
+dry-run-summary = Se { $count ->
+        [one] haría 1 reemplazo
+       *[other] harían { $count } reemplazos
+    }; no se escribió nada
+counted = { $file }: { $count ->
+        [one] 1 coincidencia
+       *[other] { $count } coincidencias
+    }
+
+walk-failed = no se pudo leer el directorio '{ $dir }'
+tree-changed = { $file }: { $count ->
+        [one] 1 reemplazo
+       *[other] { $count } reemplazos
+    }
+tree-summary = Se cambiaron { $changed } de { $matched ->
+        [one] 1 archivo
+       *[other] { $matched } archivos
+    } { $from ->
+        [list] de la lista '{ $dir }'
+        [stdin] de la lista en la entrada estándar
+        [args] nombrados en la línea de órdenes
+       *[dir] en '{ $dir }'
+    }
+tree-dry-run-summary = Se cambiarían { $changed } de { $matched ->
+        [one] 1 archivo
+       *[other] { $matched } archivos
+    } { $from ->
+        [list] de la lista '{ $dir }'
+        [stdin] de la lista en la entrada estándar
+        [args] nombrados en la línea de órdenes
+       *[dir] en '{ $dir }'
+    }; no se escribió nada
+tree-count-summary = { $count ->
+        [one] 1 coincidencia
+       *[other] { $count } coincidencias
+    } en { $files } de { $matched ->
+        [one] 1 archivo
+       *[other] { $matched } archivos
+    } { $from ->
+        [list] de la lista '{ $dir }'
+        [stdin] de la lista en la entrada estándar
+        [args] nombrados en la línea de órdenes
+       *[dir] en '{ $dir }'
+    }
+stats-file = Archivo
+stats-matches = Coincidencias
+stats-replacements = Reemplazos
+stats-bytes = Bytes cambiados
+stats-time = Tiempo
+stats-total = Total, { $files ->
+        [one] 1 archivo
+       *[other] { $files } archivos
+    }
+tree-failed = no se { $count ->
+        [one] pudo procesar 1 archivo
+       *[other] pudieron procesar { $count } archivos
+    }
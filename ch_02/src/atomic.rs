@@ -0,0 +1,246 @@
+// Writing a file all at once or not at all. The new contents go to a
+// temporary file next to the destination, which is flushed to the disk
+// and then renamed over it. A rename within a directory is atomic, so
+// whatever happens partway, a full disk, a crash, a plugin failing, a
+// reader sees the old file or the new one, never half of one, and a
+// failure leaves the old one as it was.
+//
+// The temporary file is in the same directory as the file it replaces,
+// since a rename can't move a file to another filesystem. For that, a
+// symbolic link is followed to the file it points to, which is replaced,
+// and the link left as it is. Its name starts with a dot, so that a walk
+// of the directory skips it, and has the process's id and a count in it,
+// and it's only ever created anew, so that two runs, or two threads of
+// one, can't write the same one. It's readable by its owner alone until
+// it has the permissions it's to have, which it's given before anything
+// is written to it, so a private file's contents are never open to others
+// on their way to replacing it. If the destination is on another
+// filesystem all the same, as a file bind-mounted on its own is, the
+// rename fails, and the temporary file is copied over it instead, which
+// is as good as can be done there.
+//
+// An existing file keeps its permissions. `--preserve` carries more over
+// from the original, the file rewritten in place or copied: its
+// permissions, its owner and group, and its access and modification
+// times, as `cp --preserve` does. Only root can give a file to another
+// user, so as in `cp`, a file that can't have the original's owner is
+// given its group if it can be, and is otherwise left to whoever ran it.
+
+use std::fs::{self, File, FileTimes, Metadata, OpenOptions, Permissions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::{tr, Context, Error, Messages};
+use tracing::debug;
+
+/// What to carry over to a file written from the original it's made
+/// from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Preserve {
+    /// Permissions.
+    pub mode: bool,
+    /// Owner and group.
+    pub ownership: bool,
+    /// Access and modification times.
+    pub timestamps: bool,
+}
+
+/// Write `filename` atomically, with `write` writing the contents to the
+/// new file. An existing file keeps its permissions, and `original`, the
+/// file it's made from, if there is one, gives it what it says to
+/// preserve.
+pub fn write_with(
+    filename: &str,
+    original: Option<(&str, Preserve)>,
+    messages: &Messages,
+    write: impl FnOnce(&mut File) -> Result<(), Error>,
+) -> Result<(), Error> {
+    write_if(filename, original, messages, |file| {
+        write(file).map(|()| true)
+    })
+    .map(drop)
+}
+
+/// `write_with`, with `write` saying, once it's written the new file,
+/// whether to keep it. If not, it's thrown away, and `filename` left as it
+/// was. Returns whether it was kept.
+pub fn write_if(
+    filename: &str,
+    original: Option<(&str, Preserve)>,
+    messages: &Messages,
+    write: impl FnOnce(&mut File) -> Result<bool, Error>,
+) -> Result<bool, Error> {
+    let write_failed = || tr!(messages, "write-failed", file = filename);
+    let destination = resolve(Path::new(filename));
+    let existing = fs::metadata(&destination).ok();
+    let original = match original {
+        Some((path, preserve)) if preserve != Preserve::default() => Some((
+            fs::metadata(path).with_context(|| tr!(messages, "read-failed", file = path))?,
+            preserve,
+        )),
+        _ => None,
+    };
+    let permissions = match &original {
+        Some((original, preserve)) if preserve.mode => Some(original.permissions()),
+        _ => existing.map(|existing| existing.permissions()),
+    };
+    let (temporary, mut file) =
+        create(&destination, permissions.is_some()).with_context(write_failed)?;
+    let result = carry_over(&file, permissions, original.as_ref())
+        .with_context(write_failed)
+        .and_then(|()| write(&mut file))
+        .and_then(|keep| {
+            if keep {
+                keep_times(&file, original.as_ref()).with_context(write_failed)?;
+                file.sync_all().with_context(write_failed)?;
+                rename(&temporary, &destination).with_context(write_failed)?;
+            }
+            Ok(keep)
+        });
+    if !matches!(result, Ok(true)) {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+/// Write `bytes` to `filename` atomically, made from `original`.
+pub fn write(
+    filename: &str,
+    bytes: &[u8],
+    original: Option<(&str, Preserve)>,
+    messages: &Messages,
+) -> Result<(), Error> {
+    write_with(filename, original, messages, |file| {
+        io::Write::write_all(file, bytes)
+            .with_context(|| tr!(messages, "write-failed", file = filename))
+    })
+}
+
+/// Give `file`, before anything is written to it, its `permissions`, and
+/// the `original`'s owner, if that's to be preserved.
+fn carry_over(
+    file: &File,
+    permissions: Option<Permissions>,
+    original: Option<&(Metadata, Preserve)>,
+) -> io::Result<()> {
+    // Changing the owner can clear the set-user-ID bit, so it comes
+    // before the permissions.
+    #[cfg(unix)]
+    if let Some((original, preserve)) = original {
+        if preserve.ownership {
+            use std::os::unix::fs::{fchown, MetadataExt};
+            if let Err(error) = fchown(file, Some(original.uid()), Some(original.gid())) {
+                debug!(%error, "can't give the file its owner; trying its group");
+                let _ = fchown(file, None, Some(original.gid()));
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = original;
+    match permissions {
+        Some(permissions) => file.set_permissions(permissions),
+        None => Ok(()),
+    }
+}
+
+/// Give `file` the `original`'s access and modification times, if
+/// they're to be preserved, once it's written, which would change them.
+fn keep_times(file: &File, original: Option<&(Metadata, Preserve)>) -> io::Result<()> {
+    match original {
+        Some((original, preserve)) if preserve.timestamps => {
+            let times = FileTimes::new()
+                .set_accessed(original.accessed()?)
+                .set_modified(original.modified()?);
+            file.set_times(times)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Create a temporary file to write `destination` by, that no other
+/// process or thread is writing, and where it is. One that's to be given
+/// permissions of its own is only its owner's until it is.
+fn create(destination: &Path, private: bool) -> io::Result<(PathBuf, File)> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    loop {
+        let temporary = temporary(destination);
+        match options.open(&temporary) {
+            // Left behind by a run that was killed; the next count's
+            // name is free.
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            result => return result.map(|file| (temporary, file)),
+        }
+    }
+}
+
+/// The file `path` names, following symbolic links, or `path` itself if
+/// there's no such file yet.
+fn resolve(path: &Path) -> PathBuf {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// How many temporary files this process has named.
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A name for a temporary file to write `destination` by, different each
+/// time.
+fn temporary(destination: &Path) -> PathBuf {
+    let name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    destination.with_file_name(format!(
+        ".{}.quickreplace-{}-{}.tmp",
+        name,
+        process::id(),
+        count
+    ))
+}
+
+/// Move `temporary` over `destination`, and make the move last.
+fn rename(temporary: &Path, destination: &Path) -> io::Result<()> {
+    match fs::rename(temporary, destination) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            fs::copy(temporary, destination)?;
+            File::open(destination)?.sync_all()?;
+            fs::remove_file(temporary)
+        }
+        Err(e) => Err(e),
+        Ok(()) => {
+            sync_dir(destination);
+            Ok(())
+        }
+    }
+}
+
+/// Flush the directory holding `path`, so that a rename in it survives a
+/// crash. Not every filesystem can, so this is the best it can do.
+fn sync_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        let _ = File::open(dir).and_then(|dir| dir.sync_all());
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
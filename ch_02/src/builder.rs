@@ -0,0 +1,378 @@
+// `Replacer::builder()`: a `Replacer` from patterns as text, with the
+// flags they're compiled with, for a program that has strings from a user
+// rather than regexes of its own.
+//
+//     let replacer = Replacer::builder()
+//         .pattern("colou?r")
+//         .replacement("hue")
+//         .case_insensitive(true)
+//         .build()?;
+//
+// Each `pattern` starts a rule, and `replacement` gives the last one its
+// replacement; a rule without one replaces its matches with nothing. The
+// flags hold for every pattern, and the line patterns too, whenever they're
+// set, and nothing is compiled until `build`, which says which pattern, if
+// any, isn't a regex, or has a replacement referring to a group it doesn't
+// have: the regex crate would put nothing in its place, in every match.
+//
+// A pattern from a user can be far bigger compiled than it looks:
+// `\w{1000}` is a thousand copies of every Unicode letter and digit. The
+// regex crate refuses one over its size limit, 10 MiB unless
+// `size_limit` says otherwise, as a `Pattern` error whose cause is
+// `regex::Error::CompiledTooBig`. `dfa_size_limit` caps the cache each
+// search fills as it goes instead; a search that needs more is slower, not
+// refused.
+//
+// `smart_case` is ripgrep's: a pattern is matched in either case unless
+// it has an uppercase letter, so `color` finds `Color`, and `Color` finds
+// only itself. What counts is what it matches, read from the pattern's
+// syntax: its literal letters and its classes' ranges, whether written as
+// they are or as escapes, like `\x41`, and not the `W` of `\W` or the `L`
+// of `\p{L}`. A pattern with nothing literal in it, like `\p{Lu}+`, keeps
+// its case, since in either case it would match every letter.
+
+use std::fmt;
+
+use regex::{Regex, RegexBuilder};
+use regex_syntax::ast::{self, Ast, ClassSet, ClassSetItem};
+
+use crate::{Columns, Guard, Pointers, Replacer, Template};
+
+/// What to build a `Replacer` from.
+#[derive(Debug, Clone, Default)]
+pub struct ReplacerBuilder {
+    /// Each pattern, and its replacement.
+    rules: Vec<(String, String)>,
+    case_insensitive: bool,
+    smart_case: bool,
+    multi_line: bool,
+    dot_matches_new_line: bool,
+    fixed_strings: bool,
+    whole_words: bool,
+    limit: Option<usize>,
+    /// The most memory each compiled regex may take, in bytes; `None` for
+    /// the regex crate's default.
+    size_limit: Option<usize>,
+    /// The most memory each regex's lazy DFA cache may take, in bytes;
+    /// `None` for the regex crate's default.
+    dfa_size_limit: Option<usize>,
+    /// Each line pattern, and whether a line must match it, or mustn't.
+    guards: Vec<(String, bool)>,
+    /// With CSV, the only columns to replace in.
+    columns: Option<Columns>,
+    /// With JSON, the only strings to replace in.
+    pointers: Option<Pointers>,
+}
+
+/// Why `ReplacerBuilder::build` couldn't build a `Replacer`.
+#[derive(Debug)]
+pub enum BuildError {
+    /// There were no patterns.
+    NoPattern,
+    /// The pattern at `index`, counting from 0, isn't a valid regex.
+    Pattern { index: usize, error: regex::Error },
+    /// The replacement for the pattern at `index` refers to a group,
+    /// by number or name, that the pattern doesn't have.
+    Group { index: usize, group: String },
+    /// A line pattern isn't a valid regex.
+    LinePattern {
+        pattern: String,
+        error: regex::Error,
+    },
+}
+
+impl ReplacerBuilder {
+    /// Start a rule replacing matches of `pattern`.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> ReplacerBuilder {
+        self.rules.push((pattern.into(), String::new()));
+        self
+    }
+
+    /// Replace the last pattern's matches with `replacement`, which may
+    /// refer to its capture groups, as `Replacer::new`'s does.
+    ///
+    /// # Panics
+    ///
+    /// If no pattern has been given yet.
+    pub fn replacement(mut self, replacement: impl Into<String>) -> ReplacerBuilder {
+        let (_, last) = self
+            .rules
+            .last_mut()
+            .expect("a replacement comes after its pattern");
+        *last = replacement.into();
+        self
+    }
+
+    /// Match letters in either case.
+    pub fn case_insensitive(mut self, yes: bool) -> ReplacerBuilder {
+        self.case_insensitive = yes;
+        self
+    }
+
+    /// Match a pattern's letters in either case if none of them is
+    /// uppercase. `case_insensitive` still matches every pattern's in
+    /// either case.
+    pub fn smart_case(mut self, yes: bool) -> ReplacerBuilder {
+        self.smart_case = yes;
+        self
+    }
+
+    /// Let `^` and `$` match at the start and end of each line.
+    pub fn multi_line(mut self, yes: bool) -> ReplacerBuilder {
+        self.multi_line = yes;
+        self
+    }
+
+    /// Let `.` match a newline.
+    pub fn dot_matches_new_line(mut self, yes: bool) -> ReplacerBuilder {
+        self.dot_matches_new_line = yes;
+        self
+    }
+
+    /// Take the patterns, and their replacements, as plain text: a `$`
+    /// or a backslash in a replacement is just that. Line patterns are
+    /// still regexes.
+    pub fn fixed_strings(mut self, yes: bool) -> ReplacerBuilder {
+        self.fixed_strings = yes;
+        self
+    }
+
+    /// Only match whole words: a match can't have a letter, digit, or
+    /// underscore just before it or just after it. Line patterns can.
+    pub fn whole_words(mut self, yes: bool) -> ReplacerBuilder {
+        self.whole_words = yes;
+        self
+    }
+
+    /// Replace only the first `max` matches in each text, as
+    /// `Replacer::limit` does.
+    pub fn limit(mut self, max: usize) -> ReplacerBuilder {
+        self.limit = Some(max);
+        self
+    }
+
+    /// Take each text as CSV, and only replace in `columns`, as
+    /// `Replacer::columns` does.
+    pub fn columns(mut self, columns: Columns) -> ReplacerBuilder {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// Take each text as JSON, and only replace in the strings `pointers`
+    /// address, as `Replacer::pointers` does.
+    pub fn pointers(mut self, pointers: Pointers) -> ReplacerBuilder {
+        self.pointers = Some(pointers);
+        self
+    }
+
+    /// Refuse to compile any pattern, or line pattern, to more than
+    /// `bytes` of memory, as `RegexBuilder::size_limit` does.
+    pub fn size_limit(mut self, bytes: usize) -> ReplacerBuilder {
+        self.size_limit = Some(bytes);
+        self
+    }
+
+    /// Let each regex's lazy DFA cache grow to `bytes` at most, as
+    /// `RegexBuilder::dfa_size_limit` does.
+    pub fn dfa_size_limit(mut self, bytes: usize) -> ReplacerBuilder {
+        self.dfa_size_limit = Some(bytes);
+        self
+    }
+
+    /// Only replace matches on lines that `pattern` matches somewhere, as
+    /// `Replacer::only_lines_matching` does.
+    pub fn only_lines_matching(mut self, pattern: impl Into<String>) -> ReplacerBuilder {
+        self.guards.push((pattern.into(), true));
+        self
+    }
+
+    /// Leave matches on lines that `pattern` matches as they are, as
+    /// `Replacer::skip_lines_matching` does.
+    pub fn skip_lines_matching(mut self, pattern: impl Into<String>) -> ReplacerBuilder {
+        self.guards.push((pattern.into(), false));
+        self
+    }
+
+    /// Compile the patterns into a `Replacer`, in the order they were
+    /// given.
+    pub fn build(&self) -> Result<Replacer, BuildError> {
+        if self.rules.is_empty() {
+            return Err(BuildError::NoPattern);
+        }
+        let mut replacer = Replacer::empty();
+        for (index, (pattern, replacement)) in self.rules.iter().enumerate() {
+            // A literal pattern has no groups for the replacement to
+            // refer to, so a `$` in it is literal too, as `$$`, and a
+            // backslash, as `\\`.
+            let (pattern, replacement) = if self.fixed_strings {
+                (
+                    regex::escape(pattern),
+                    replacement.replace('\\', "\\\\").replace('$', "$$"),
+                )
+            } else {
+                (pattern.clone(), replacement.clone())
+            };
+            // Half boundaries, not `\b`, which would want a word character
+            // inside the match as well as none outside it, and so never
+            // match a pattern like `-x` or `x!` after a space.
+            let pattern = if self.whole_words {
+                format!(r"\b{{start-half}}(?:{})\b{{end-half}}", pattern)
+            } else {
+                pattern
+            };
+            let regex = self
+                .regex(&pattern)
+                .map_err(|error| BuildError::Pattern { index, error })?;
+            if let Some(group) = missing_group(&regex, &replacement) {
+                return Err(BuildError::Group { index, group });
+            }
+            replacer = replacer.rule(regex, &replacement);
+        }
+        for (pattern, matching) in &self.guards {
+            let regex = self
+                .regex(pattern)
+                .map_err(|error| BuildError::LinePattern {
+                    pattern: pattern.clone(),
+                    error,
+                })?;
+            replacer.guards.push(Guard {
+                regex,
+                matching: *matching,
+            });
+        }
+        replacer.limit = self.limit;
+        replacer.columns = self.columns.clone();
+        replacer.pointers = self.pointers.clone();
+        Ok(replacer)
+    }
+
+    /// `pattern` compiled with the flags, within the limits.
+    fn regex(&self, pattern: &str) -> Result<Regex, regex::Error> {
+        let mut builder = RegexBuilder::new(pattern);
+        builder
+            .case_insensitive(self.case_insensitive || (self.smart_case && lowercase_only(pattern)))
+            .multi_line(self.multi_line)
+            .dot_matches_new_line(self.dot_matches_new_line);
+        if let Some(bytes) = self.size_limit {
+            builder.size_limit(bytes);
+        }
+        if let Some(bytes) = self.dfa_size_limit {
+            builder.dfa_size_limit(bytes);
+        }
+        builder.build()
+    }
+}
+
+/// Whether `pattern` matches something literal, and nothing uppercase; a
+/// pattern that isn't a regex doesn't.
+fn lowercase_only(pattern: &str) -> bool {
+    let Ok(ast) = ast::parse::Parser::new().parse(pattern) else {
+        return false;
+    };
+    let mut letters = Letters::default();
+    letters.ast(&ast);
+    letters.literal && !letters.uppercase
+}
+
+/// What's literal in a pattern.
+#[derive(Default)]
+struct Letters {
+    /// Whether there's anything literal.
+    literal: bool,
+    /// Whether any of it is uppercase.
+    uppercase: bool,
+}
+
+impl Letters {
+    fn ast(&mut self, ast: &Ast) {
+        match ast {
+            Ast::Literal(literal) => self.char(literal.c),
+            Ast::ClassBracketed(class) => self.class(&class.kind),
+            Ast::Repetition(repetition) => self.ast(&repetition.ast),
+            Ast::Group(group) => self.ast(&group.ast),
+            Ast::Alternation(alternation) => alternation.asts.iter().for_each(|ast| self.ast(ast)),
+            Ast::Concat(concat) => concat.asts.iter().for_each(|ast| self.ast(ast)),
+            Ast::Empty(_)
+            | Ast::Flags(_)
+            | Ast::Dot(_)
+            | Ast::Assertion(_)
+            | Ast::ClassUnicode(_)
+            | Ast::ClassPerl(_) => {}
+        }
+    }
+
+    fn class(&mut self, set: &ClassSet) {
+        match set {
+            ClassSet::BinaryOp(op) => {
+                self.class(&op.lhs);
+                self.class(&op.rhs);
+            }
+            ClassSet::Item(item) => self.item(item),
+        }
+    }
+
+    fn item(&mut self, item: &ClassSetItem) {
+        match item {
+            ClassSetItem::Literal(literal) => self.char(literal.c),
+            ClassSetItem::Range(range) => {
+                self.char(range.start.c);
+                self.char(range.end.c);
+            }
+            ClassSetItem::Bracketed(class) => self.class(&class.kind),
+            ClassSetItem::Union(union) => union.items.iter().for_each(|item| self.item(item)),
+            ClassSetItem::Empty(_)
+            | ClassSetItem::Ascii(_)
+            | ClassSetItem::Unicode(_)
+            | ClassSetItem::Perl(_) => {}
+        }
+    }
+
+    fn char(&mut self, c: char) {
+        self.literal = true;
+        self.uppercase |= c.is_uppercase();
+    }
+}
+
+/// The first group `replacement` refers to that `regex` doesn't have, which
+/// the regex crate would quietly replace with nothing.
+fn missing_group(regex: &Regex, replacement: &str) -> Option<String> {
+    Template::parse(replacement)
+        .groups()
+        .into_iter()
+        .find(|&group| match group.parse::<usize>() {
+            Ok(number) => number >= regex.captures_len(),
+            Err(_) => !regex.capture_names().any(|name| name == Some(group)),
+        })
+        .map(str::to_string)
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::NoPattern => f.write_str("no pattern to replace"),
+            BuildError::Pattern { index, .. } => write!(f, "invalid pattern {}", index + 1),
+            BuildError::Group { index, group } => {
+                write!(
+                    f,
+                    "replacement {} refers to no group '{}'",
+                    index + 1,
+                    group
+                )
+            }
+            BuildError::LinePattern { pattern, .. } => {
+                write!(f, "invalid line pattern '{}'", pattern)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::NoPattern | BuildError::Group { .. } => None,
+            BuildError::Pattern { error, .. } | BuildError::LinePattern { error, .. } => {
+                Some(error)
+            }
+        }
+    }
+}
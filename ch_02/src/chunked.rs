@@ -0,0 +1,166 @@
+// Replacing a huge text in pieces, so each piece can be done on a thread
+// of its own, and the pieces put back together into exactly what
+// `Replacer::replace` would have made of the whole:
+//
+//     let sections = split(text.as_bytes(), 8 << 20);
+//     let replaced: Vec<Section> = sections
+//         .par_iter()
+//         .map(|range| replacer.replace_section(text, range.clone()))
+//         .collect();
+//     let mut stitch = Stitch::new(&replacer, text);
+//     for section in replaced {
+//         output.write_all(stitch.next(section).text.as_bytes())?;
+//     }
+//
+// The text is split after newlines, and each section replaces the matches
+// that start in it, searching the whole text from its start, so `^`, `$`,
+// and `\b` see what's on either side of the edge, and a match can run on
+// past the end of its section. Most of the time, one section's matches are
+// the same as if the whole text had been searched up to it, but not when
+// a match from the section before runs into it, since a search from where
+// that match ends may find different ones, and not when it starts with an
+// empty match right where the match before ended, which the regex crate
+// wouldn't have taken. `Stitch` sees these as they come, in order, and
+// replaces such a section again, from where the last match ended: a
+// section at a time, so rarely, and only ever the one.
+//
+// A `Replacer`'s limit is counted in each section, not the whole text, so
+// one with a limit should replace the whole text at once.
+
+use std::ops::Range;
+
+use crate::Replacer;
+
+/// A piece of a text, replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// What part of the text was replaced: where the section starts, to
+    /// where it ends, or where the last match in it does, if that's later.
+    pub range: Range<usize>,
+    /// The part, replaced.
+    pub text: String,
+    /// How many matches it had.
+    pub count: usize,
+    /// Where it was to end, before any match ran past it.
+    until: usize,
+    /// Where its last match ended, if it had any.
+    last_end: Option<usize>,
+    /// Whether its first match was empty, at its start.
+    starts_empty: bool,
+}
+
+/// `text` cut into ranges of about `size` bytes, each but the last ending
+/// just after a newline, so none splits a line, or a character. A line
+/// longer than `size` is a range of its own. An empty text is one empty
+/// range, since even it can have a match.
+pub fn split(text: &[u8], size: usize) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = match text.get(start + size.max(1)..) {
+            Some(rest) => rest
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(text.len(), |i| start + size.max(1) + i + 1),
+            None => text.len(),
+        };
+        ranges.push(start..end);
+        if end == text.len() {
+            return ranges;
+        }
+        start = end;
+    }
+}
+
+impl Replacer {
+    /// The section `range` of `text` with the matches starting in it
+    /// replaced, as part of replacing `text` a section at a time. Put
+    /// each through a `Stitch`, in order, before using it.
+    pub fn replace_section(&self, text: &str, range: Range<usize>) -> Section {
+        self.section(text, range, None)
+    }
+
+    /// `replace_section`, as if the last match had ended at `last_end`.
+    fn section(&self, text: &str, range: Range<usize>, last_end: Option<usize>) -> Section {
+        let mut replaced = String::with_capacity(range.len());
+        let mut copied = range.start;
+        let mut count = 0;
+        let mut starts_empty = false;
+        let mut end = None;
+        for (rule, captures) in self.matches_from(text, range.start, last_end) {
+            let m = captures.get(0).unwrap();
+            // The next section's, unless this is the last, where an empty
+            // match at the very end is this one's.
+            if m.start() >= range.end && range.end < text.len() {
+                break;
+            }
+            if count == 0 {
+                starts_empty = m.is_empty() && m.start() == range.start;
+            }
+            replaced.push_str(&text[copied..m.start()]);
+            self.expand(rule, &captures, &mut replaced);
+            copied = m.end();
+            count += 1;
+            end = Some(m.end());
+        }
+        if copied < range.end {
+            replaced.push_str(&text[copied..range.end]);
+        }
+        Section {
+            range: range.start..copied.max(range.end),
+            text: replaced,
+            count,
+            until: range.end,
+            last_end: end,
+            starts_empty,
+        }
+    }
+}
+
+/// Sections of a text, replaced one by one, put back together in order,
+/// with any that a match before them changed replaced again.
+#[derive(Debug)]
+pub struct Stitch<'r, 't> {
+    replacer: &'r Replacer,
+    text: &'t str,
+    /// How much of the text the sections so far have covered.
+    covered: usize,
+    /// Where the last match so far ended.
+    last_end: Option<usize>,
+}
+
+impl<'r, 't> Stitch<'r, 't> {
+    pub fn new(replacer: &'r Replacer, text: &'t str) -> Stitch<'r, 't> {
+        Stitch {
+            replacer,
+            text,
+            covered: 0,
+            last_end: None,
+        }
+    }
+
+    /// `section`, the next one of the text, as it goes after those before
+    /// it: as it is, or replaced again from where they left off, or, if a
+    /// match before it took all of it, empty.
+    pub fn next(&mut self, section: Section) -> Section {
+        let (start, until) = (section.range.start, section.until);
+        let stale = start < self.covered || (section.starts_empty && self.last_end == Some(start));
+        let section = match self.covered {
+            _ if !stale => section,
+            from if from > until || (from == until && until < self.text.len()) => Section {
+                range: from..from,
+                text: String::new(),
+                count: 0,
+                until,
+                last_end: None,
+                starts_empty: false,
+            },
+            from => self.replacer.section(self.text, from..until, self.last_end),
+        };
+        self.covered = self.covered.max(section.range.end);
+        if section.last_end.is_some() {
+            self.last_end = section.last_end;
+        }
+        section
+    }
+}
@@ -0,0 +1,602 @@
+// The command line, as clap sees it: a subcommand, and its options.
+//
+//     quickreplace replace [OPTIONS] <target> <replacement> <input> <output>
+//     quickreplace count [OPTIONS] <target> <input>...
+//     quickreplace find [OPTIONS] <target> <input>...
+//     quickreplace undo <journal>
+//
+// `replace` is the default, so `quickreplace <target> <replacement> ...`
+// still works as it always has. The help is in the language of the
+// `Messages`; clap's own errors are in English.
+//
+// The positional arguments depend on the options: with `--rules`, there's
+// no target or replacement, with `--exec`, no replacement, with `--in-place`, `--diff`, or
+// `--highlight`, no output, and with `--files-from`, no input either.
+// Clap can't say that, so they're taken as up to four values, and
+// counted here. Where there's no output, any more are more input files,
+// which are rewritten on the thread pool, as a directory's files are:
+// `quickreplace old new a.txt b.txt c.txt --in-place`.
+//
+// With no filenames at all, quickreplace is a filter, like `sed`, from
+// standard input to standard output, and a line at a time, as with
+// `--stream`, unless an option needs the whole text: `--multiline`,
+// `--dotall`, `--max`, `--encoding`, `--report`, `--diff`, or
+// `--highlight`.
+//
+// What isn't on the command line comes from the `Settings`: `-i` unless
+// `--case-sensitive`, a backup suffix for a bare `--in-place`, globs to
+// exclude besides the `--exclude`s, and the regex size limits.
+//
+// A size is a number of bytes, with `K`, `M`, or `G` after it for KiB,
+// MiB, or GiB: `512K`, `10M`, or `10MiB`.
+
+use std::num::NonZeroUsize;
+
+use ch_02::atomic::Preserve;
+use ch_02::encoding::Encoding;
+use ch_02::quickreplace::{parse_size, Arguments, Output, Rules, MMAP_THRESHOLD, STDIO};
+use ch_02::rules::Rule;
+use ch_02::{source, Pointers};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use common::{tr, Error, Messages};
+
+use crate::{Settings, Task};
+
+/// The subcommands, and what clap takes instead of one.
+const COMMANDS: &[&str] = &["replace", "count", "find", "undo", "help", "-h", "--help"];
+
+/// Names for the positional arguments, in order.
+const POSITIONALS: [&str; 4] = ["first", "second", "third", "fourth"];
+
+/// The name for any more input files, after the positional arguments.
+const MORE: &str = "more";
+
+/// quickreplace's command line, with its help in `messages`' language.
+pub fn command(messages: &Messages) -> Command {
+    // Settings::load takes these out before clap sees them; they're here
+    // for the help.
+    let global = [
+        Arg::new("verbose")
+            .short('v')
+            .long("verbose")
+            .action(ArgAction::Count)
+            .global(true)
+            .display_order(100)
+            .help(tr!(messages, "help-verbose")),
+        Arg::new("log-format")
+            .long("log-format")
+            .value_name("FORMAT")
+            .global(true)
+            .display_order(100)
+            .help(tr!(messages, "help-log-format")),
+        Arg::new("lang")
+            .long("lang")
+            .value_name("LANG")
+            .global(true)
+            .display_order(100)
+            .help(tr!(messages, "help-lang")),
+        Arg::new("config")
+            .long("config")
+            .value_name("FILE")
+            .global(true)
+            .display_order(100)
+            .help(tr!(messages, "help-config")),
+    ];
+    let after_help = format!(
+        "{}\n{}\n{}",
+        tr!(messages, "usage-dash"),
+        tr!(messages, "usage-dir"),
+        tr!(messages, "usage-files-from")
+    );
+    Command::new("quickreplace")
+        .about(tr!(messages, "about"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .args(global)
+        .subcommand(
+            Command::new("replace")
+                .about(tr!(messages, "replace-about"))
+                .override_usage(format!(
+                    "{}\n{}\n{}\n{}",
+                    tr!(messages, "usage-replace"),
+                    tr!(messages, "usage-replace-in-place"),
+                    tr!(messages, "usage-replace-rules"),
+                    tr!(messages, "usage-replace-exec")
+                ))
+                .after_help(after_help.clone())
+                .args(positionals(4))
+                .args(matching(messages))
+                .arg(
+                    Arg::new("in-place")
+                        .long("in-place")
+                        .value_name("SUFFIX")
+                        .num_args(0..=1)
+                        .require_equals(true)
+                        .help(tr!(messages, "help-in-place")),
+                )
+                .arg(
+                    Arg::new("max")
+                        .short('n')
+                        .long("max")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(NonZeroUsize))
+                        .conflicts_with_all(["stream", "csv", "json"])
+                        .help(tr!(messages, "help-max")),
+                )
+                .arg(flag("dry-run", None, tr!(messages, "help-dry-run")))
+                .arg(flag("expand-env", None, tr!(messages, "help-expand-env")))
+                .arg(
+                    flag("diff", None, tr!(messages, "help-diff"))
+                        .conflicts_with_all(["in-place", "dry-run", "stream"]),
+                )
+                .arg(
+                    flag("highlight", None, tr!(messages, "help-highlight"))
+                        .conflicts_with_all(["in-place", "dry-run", "diff", "stream"]),
+                )
+                .arg(
+                    Arg::new("journal")
+                        .long("journal")
+                        .value_name("FILE")
+                        .requires("in-place")
+                        .conflicts_with_all(["dry-run", "stream"])
+                        .help(tr!(messages, "help-journal")),
+                )
+                .arg(
+                    Arg::new("preserve")
+                        .long("preserve")
+                        .value_name("ATTRIBUTES")
+                        .num_args(0..=1)
+                        .require_equals(true)
+                        .value_delimiter(',')
+                        .value_parser(["mode", "ownership", "timestamps"])
+                        .default_missing_value("mode,ownership,timestamps")
+                        .action(ArgAction::Append)
+                        .conflicts_with("dry-run")
+                        .help(tr!(messages, "help-preserve")),
+                )
+                .arg(
+                    Arg::new("report")
+                        .long("report")
+                        .value_name("FORMAT")
+                        .value_parser(["json"])
+                        .conflicts_with_all(["diff", "highlight", "stream"])
+                        .help(tr!(messages, "help-report")),
+                )
+                .arg(
+                    flag("stats", None, tr!(messages, "help-stats"))
+                        .conflicts_with_all(["diff", "highlight"]),
+                )
+                .arg(
+                    Arg::new("report-file")
+                        .long("report-file")
+                        .value_name("FILE")
+                        .requires("report")
+                        .help(tr!(messages, "help-report-file")),
+                )
+                .arg(
+                    // Rewriting the input in place would set it off again.
+                    flag("watch", None, tr!(messages, "help-watch")).conflicts_with("in-place"),
+                )
+                .arg(
+                    Arg::new("plugin")
+                        .long("plugin")
+                        .value_name("LIBRARY")
+                        .action(ArgAction::Append)
+                        .help(tr!(messages, "help-plugin")),
+                )
+                .arg(
+                    Arg::new("exec")
+                        .long("exec")
+                        .value_name("CMD")
+                        .conflicts_with_all(["rules", "expand-env"])
+                        .help(tr!(messages, "help-exec")),
+                ),
+        )
+        .subcommand(
+            Command::new("count")
+                .about(tr!(messages, "count-about"))
+                .override_usage(tr!(messages, "usage-count"))
+                .after_help(after_help.clone())
+                .args(positionals(2))
+                .args(matching(messages)),
+        )
+        .subcommand(
+            Command::new("find")
+                .about(tr!(messages, "find-about"))
+                .override_usage(tr!(messages, "usage-find"))
+                .after_help(after_help)
+                .args(positionals(2))
+                .args(matching(messages)),
+        )
+        .subcommand(
+            Command::new("undo")
+                .about(tr!(messages, "undo-about"))
+                .override_usage(tr!(messages, "usage-undo"))
+                .arg(Arg::new("journal").required(true).hide(true)),
+        )
+}
+
+/// The first `count` positional arguments, unnamed in the help, which the
+/// usage describes, and then any more input files. Any value starting
+/// with `-` that isn't an option is taken as one of the first, so a target
+/// can be `-x`.
+fn positionals(count: usize) -> Vec<Arg> {
+    POSITIONALS[..count]
+        .iter()
+        .map(|&name| Arg::new(name).allow_hyphen_values(true).hide(true))
+        .chain([Arg::new(MORE)
+            .num_args(1..)
+            .action(ArgAction::Append)
+            .hide(true)])
+        .collect()
+}
+
+/// The options every subcommand has: what to match, and where.
+fn matching(messages: &Messages) -> [Arg; 27] {
+    [
+        flag("ignore-case", Some('i'), tr!(messages, "help-ignore-case"))
+            .overrides_with_all(["case-sensitive", "smart-case"]),
+        flag(
+            "case-sensitive",
+            Some('s'),
+            tr!(messages, "help-case-sensitive"),
+        )
+        .overrides_with_all(["ignore-case", "smart-case"]),
+        flag("smart-case", Some('S'), tr!(messages, "help-smart-case"))
+            .overrides_with_all(["ignore-case", "case-sensitive"]),
+        flag(
+            "fixed-strings",
+            Some('F'),
+            tr!(messages, "help-fixed-strings"),
+        ),
+        flag("word-regexp", Some('w'), tr!(messages, "help-word-regexp")),
+        flag("multiline", None, tr!(messages, "help-multiline")),
+        flag("dotall", None, tr!(messages, "help-dotall")),
+        Arg::new("rules")
+            .long("rules")
+            .value_name("FILE")
+            .action(ArgAction::Append)
+            .help(tr!(messages, "help-rules")),
+        Arg::new("only-lines-matching")
+            .long("only-lines-matching")
+            .value_name("REGEX")
+            .action(ArgAction::Append)
+            .help(tr!(messages, "help-only-lines-matching")),
+        Arg::new("skip-lines-matching")
+            .long("skip-lines-matching")
+            .value_name("REGEX")
+            .action(ArgAction::Append)
+            .help(tr!(messages, "help-skip-lines-matching")),
+        flag("csv", None, tr!(messages, "help-csv")).requires("columns"),
+        Arg::new("columns")
+            .long("columns")
+            .value_name("NAMES")
+            .value_delimiter(',')
+            .action(ArgAction::Append)
+            .requires("csv")
+            .conflicts_with_all(["stream", "only-lines-matching", "skip-lines-matching"])
+            .help(tr!(messages, "help-columns")),
+        flag("json", None, tr!(messages, "help-json"))
+            .requires("pointer")
+            .conflicts_with("csv"),
+        Arg::new("pointer")
+            .long("pointer")
+            .value_name("POINTER")
+            .action(ArgAction::Append)
+            .value_parser(parse_pointer)
+            .requires("json")
+            .conflicts_with_all(["stream", "only-lines-matching", "skip-lines-matching"])
+            .help(tr!(messages, "help-pointer")),
+        Arg::new("regex-size-limit")
+            .long("regex-size-limit")
+            .value_name("SIZE")
+            .value_parser(parse_size)
+            .help(tr!(messages, "help-regex-size-limit")),
+        Arg::new("dfa-size-limit")
+            .long("dfa-size-limit")
+            .value_name("SIZE")
+            .value_parser(parse_size)
+            .help(tr!(messages, "help-dfa-size-limit")),
+        Arg::new("glob")
+            .long("glob")
+            .value_name("PATTERN")
+            .action(ArgAction::Append)
+            .help(tr!(messages, "help-glob")),
+        Arg::new("exclude")
+            .long("exclude")
+            .value_name("PATTERN")
+            .action(ArgAction::Append)
+            .help(tr!(messages, "help-exclude")),
+        flag("no-ignore", None, tr!(messages, "help-no-ignore")),
+        Arg::new("files-from")
+            .long("files-from")
+            .value_name("LIST")
+            .help(tr!(messages, "help-files-from")),
+        flag("null", Some('0'), tr!(messages, "help-null")).requires("files-from"),
+        Arg::new("jobs")
+            .long("jobs")
+            .value_name("N")
+            .value_parser(clap::value_parser!(NonZeroUsize))
+            .help(tr!(messages, "help-jobs")),
+        flag("stream", None, tr!(messages, "help-stream")),
+        flag("compress", Some('z'), tr!(messages, "help-compress")),
+        flag(
+            "fail-if-no-match",
+            None,
+            tr!(messages, "help-fail-if-no-match"),
+        ),
+        flag("no-progress", None, tr!(messages, "help-no-progress")),
+        Arg::new("encoding")
+            .long("encoding")
+            .value_name("NAME")
+            .value_parser(["utf-8", "utf-16le", "utf-16be", "latin1", "auto"])
+            .ignore_case(true)
+            .default_value("utf-8")
+            .conflicts_with("stream")
+            .help(tr!(messages, "help-encoding")),
+    ]
+}
+
+/// Whether the options given need the whole text at once, rather than a
+/// line at a time.
+fn whole_text(matches: &ArgMatches) -> bool {
+    // Not every subcommand has every option, and clap panics if asked
+    // about one it doesn't.
+    matches.ids().any(|id| {
+        [
+            "multiline",
+            "dotall",
+            "max",
+            "csv",
+            "json",
+            "encoding",
+            "report",
+            "diff",
+            "highlight",
+        ]
+        .contains(&id.as_str())
+            && matches.value_source(id.as_str()) == Some(ValueSource::CommandLine)
+    })
+}
+
+/// A JSON pointer, like `/users/*/email`, checked.
+fn parse_pointer(value: &str) -> Result<String, String> {
+    Pointers::parse([value])
+        .map(|_| value.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn flag(name: &'static str, short: Option<char>, help: String) -> Arg {
+    Arg::new(name)
+        .long(name)
+        .short(short)
+        .action(ArgAction::SetTrue)
+        .help(help)
+}
+
+impl Task {
+    /// Parse the command line, without the program's name, with
+    /// `settings` for what it leaves out. Help, and errors clap finds, are
+    /// printed, and the program exits.
+    pub fn parse(
+        mut args: Vec<String>,
+        settings: &Settings,
+        messages: &Messages,
+    ) -> Result<Task, Error> {
+        if args
+            .first()
+            .is_some_and(|arg| !COMMANDS.contains(&arg.as_str()))
+        {
+            args.insert(0, "replace".to_string());
+        }
+        args.insert(0, "quickreplace".to_string());
+        let mut command = command(messages);
+        let matches = command
+            .try_get_matches_from_mut(args)
+            .unwrap_or_else(|e| e.exit());
+        let (name, matches) = matches.subcommand().expect("a subcommand is required");
+        if name == "undo" {
+            let journal = matches.get_one::<String>("journal").expect("it's required");
+            return Ok(Task::Undo {
+                journal: journal.clone(),
+            });
+        }
+
+        let flag = |id: &str| matches!(matches.try_get_one::<bool>(id), Ok(Some(true)));
+        let values = |id: &str| -> Vec<String> {
+            match matches.try_get_many::<String>(id) {
+                Ok(Some(values)) => values.cloned().collect(),
+                _ => Vec::new(),
+            }
+        };
+        let size = |id: &str| matches.try_get_one::<usize>(id).ok().flatten().copied();
+        // A bare `--in-place` backs up with the settings' suffix, if they
+        // have one, and `--in-place=` doesn't back up at all.
+        let in_place = match matches.try_get_one::<String>("in-place") {
+            Ok(Some(suffix)) => Some(Some(suffix.clone()).filter(|suffix| !suffix.is_empty())),
+            Ok(None) if matches.contains_id("in-place") => Some(settings.backup_suffix.clone()),
+            _ => None,
+        };
+        // The last of `-i`, `-s`, and `-S` wins, and without any, the
+        // settings say.
+        let (ignore_case, smart_case) = match (
+            flag("ignore-case"),
+            flag("case-sensitive"),
+            flag("smart-case"),
+        ) {
+            (false, false, false) => (settings.ignore_case, settings.smart_case),
+            (ignore_case, _, smart_case) => (ignore_case, smart_case),
+        };
+        let rule_files = values("rules");
+        let diff = flag("diff");
+        let highlight = flag("highlight");
+
+        let exec = matches
+            .try_get_one::<String>("exec")
+            .ok()
+            .flatten()
+            .cloned();
+        // With rules files, there's no target or replacement to give, and
+        // counting, finding, and a command's replacements need no
+        // replacement.
+        let patterns = match (rule_files.is_empty(), name) {
+            (false, _) => 0,
+            (true, "replace") if exec.is_none() => 2,
+            (true, _) => 1,
+        };
+        let files_from = matches
+            .try_get_one::<String>("files-from")
+            .ok()
+            .flatten()
+            .cloned();
+        let files = match name {
+            _ if files_from.is_some() => 0,
+            "replace" if in_place.is_none() && !diff && !highlight => 2,
+            _ => 1,
+        };
+        let mut args: Vec<String> = POSITIONALS
+            .iter()
+            .filter_map(|id| matches.try_get_one::<String>(id).ok().flatten().cloned())
+            .chain(values(MORE))
+            .collect();
+        let expected = patterns + files;
+        let filter = files_from.is_none() && args.len() == patterns;
+        if filter {
+            args.resize(expected, STDIO.to_string());
+        }
+        // Where there's no output file, any number of input files can be
+        // given, and they're rewritten as a list's would be.
+        let several = files == 1 && args.len() > expected;
+        if args.len() != expected && !several {
+            command.build();
+            let usage = command.find_subcommand_mut(name).unwrap().render_usage();
+            eprintln!("{}", usage);
+            return Err(Error::usage(tr!(
+                messages,
+                "wrong-args",
+                expected = expected,
+                count = args.len()
+            )));
+        }
+        // A list is the input, as far as what's done with it goes.
+        let files = match &files_from {
+            Some(list) => vec![list.clone()],
+            None => args.split_off(patterns),
+        };
+        let output = match (name, in_place) {
+            ("count", _) => Output::Count,
+            ("find", _) => Output::Find,
+            _ if diff => Output::Diff,
+            _ if highlight => Output::Highlight,
+            // There's nowhere to copy a list's files to, so they're only
+            // rewritten in place, and `--dry-run` shows what would be.
+            (_, Some(backup_suffix)) if files_from.is_some() => Output::InPlace { backup_suffix },
+            (_, None) if files_from.is_some() && flag("dry-run") => Output::InPlace {
+                backup_suffix: None,
+            },
+            (_, None) if files_from.is_some() => {
+                return Err(Error::usage(tr!(messages, "files-from-in-place")));
+            }
+            _ if several && files.iter().any(|file| file == STDIO) => {
+                return Err(Error::usage(tr!(messages, "stdin-among-files")));
+            }
+            (_, Some(_)) if files[0] == STDIO => {
+                return Err(Error::usage(tr!(messages, "in-place-stdin")));
+            }
+            (_, Some(backup_suffix)) => Output::InPlace { backup_suffix },
+            (_, None) => Output::File(files[1].clone()),
+        };
+        // The report goes to the file given, or where the messages would.
+        let report = match matches.try_get_one::<String>("report") {
+            Ok(Some(_)) => Some(
+                matches
+                    .get_one::<String>("report-file")
+                    .cloned()
+                    .unwrap_or_else(|| STDIO.to_string()),
+            ),
+            _ => None,
+        };
+        if report.as_deref() == Some(STDIO)
+            && matches!(&output, Output::File(file) if file == STDIO)
+        {
+            return Err(Error::usage(tr!(messages, "report-stdout")));
+        }
+        let rules = match &args[..] {
+            [] => Rules::Files(rule_files),
+            [target, replacement @ ..] => Rules::Given(Rule {
+                pattern: target.clone(),
+                replacement: replacement.first().cloned().unwrap_or_default(),
+            }),
+        };
+        Ok(Task::Rewrite(Box::new(Arguments {
+            rules,
+            input_filename: files[0].clone(),
+            inputs: if several { files } else { Vec::new() },
+            files_from: files_from.map(|_| {
+                if flag("null") {
+                    source::NUL
+                } else {
+                    source::NEWLINE
+                }
+            }),
+            output,
+            globs: values("glob"),
+            exclude: [settings.exclude.clone(), values("exclude")].concat(),
+            no_ignore: flag("no-ignore"),
+            dry_run: flag("dry-run"),
+            ignore_case,
+            smart_case,
+            fixed_strings: flag("fixed-strings"),
+            whole_words: flag("word-regexp"),
+            expand_env: flag("expand-env"),
+            multiline: flag("multiline"),
+            dotall: flag("dotall"),
+            columns: values("columns"),
+            pointers: values("pointer"),
+            only_lines_matching: values("only-lines-matching"),
+            skip_lines_matching: values("skip-lines-matching"),
+            stream: flag("stream") || (filter && !whole_text(matches)),
+            compress: flag("compress"),
+            watch: flag("watch"),
+            progress: !flag("no-progress"),
+            jobs: matches
+                .try_get_one::<NonZeroUsize>("jobs")
+                .ok()
+                .flatten()
+                .map(|jobs| jobs.get()),
+            plugins: values("plugin"),
+            exec,
+            max: matches
+                .try_get_one::<NonZeroUsize>("max")
+                .ok()
+                .flatten()
+                .map(|max| max.get()),
+            fail_if_no_match: flag("fail-if-no-match"),
+            encoding: match matches.try_get_one::<String>("encoding") {
+                Ok(Some(name)) if name.eq_ignore_ascii_case("auto") => None,
+                Ok(Some(name)) => Some(name.parse().expect("clap knows the names")),
+                _ => Some(Encoding::Utf8),
+            },
+            report,
+            journal: matches
+                .try_get_one::<String>("journal")
+                .ok()
+                .flatten()
+                .cloned(),
+            stats: flag("stats"),
+            preserve: {
+                let preserve = values("preserve");
+                let has = |attribute: &str| preserve.iter().any(|value| value == attribute);
+                Preserve {
+                    mode: has("mode"),
+                    ownership: has("ownership"),
+                    timestamps: has("timestamps"),
+                }
+            },
+            mmap_threshold: settings.mmap_threshold.unwrap_or(MMAP_THRESHOLD),
+            regex_size_limit: size("regex-size-limit").or(settings.regex_size_limit),
+            dfa_size_limit: size("dfa-size-limit").or(settings.dfa_size_limit),
+        })))
+    }
+}
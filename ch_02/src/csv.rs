@@ -0,0 +1,291 @@
+// Replacing only in some columns of a CSV file, and leaving the rest of
+// it, quotes, commas, line endings and all, just as it was:
+//
+//     let replacer = Replacer::new("@old\\.com$", "@new.com")?
+//         .columns(Columns::new(["email"]));
+//
+// The first record is the header, which names the columns, and is never
+// replaced. In each record after it, each chosen column's field is
+// replaced on its own, as a text of its own, so `^` and `$` are its start
+// and end, and a match never runs into the next field. A quoted field's
+// value is what's between the quotes, with each `""` a `"`, and a field
+// that was quoted stays quoted, with any `"` the replacement puts in
+// doubled. One that wasn't is quoted only if its new value has to be,
+// with a comma, a quote, or a line break in it. A record can span lines,
+// inside quotes; each one that changes is a `Change` of all its lines.
+//
+// The reading is as lenient as most spreadsheets': a quote that's never
+// closed runs to the end of the text, and a field with more after its
+// closing quote is taken as it is, unquoted. A `Replacer`'s limit is
+// counted in each field.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{apply, Change, Replaced, Replacer};
+
+/// What separates fields.
+const DELIMITER: u8 = b',';
+
+/// The columns of a CSV text to replace in, by the names its header gives
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Columns {
+    names: Vec<String>,
+}
+
+/// A column that the header doesn't have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingColumn {
+    /// The column asked for.
+    pub column: String,
+    /// The columns there are, in order.
+    pub header: Vec<String>,
+}
+
+impl Columns {
+    pub fn new<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Columns {
+        Columns {
+            names: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Which fields of each of `text`'s records are the columns, counting
+    /// from 0, in order, or the first column its header doesn't have. A
+    /// text with no records has no columns to look for.
+    pub fn find(&self, text: &str) -> Result<Vec<usize>, MissingColumn> {
+        let header = header(text).unwrap_or_default();
+        match self.names.iter().find(|name| !header.contains(name)) {
+            Some(name) if !header.is_empty() => Err(MissingColumn {
+                column: name.clone(),
+                header,
+            }),
+            _ => Ok(self.indices(&header)),
+        }
+    }
+
+    /// Which of `header`'s columns these are.
+    fn indices(&self, header: &[String]) -> Vec<usize> {
+        (0..header.len())
+            .filter(|&i| self.names.contains(&header[i]))
+            .collect()
+    }
+}
+
+/// The names in `text`'s header, if it has one.
+fn header(text: &str) -> Option<Vec<String>> {
+    let header = Records::new(text).next()?;
+    let names = header
+        .fields
+        .iter()
+        .map(|field| field.value(text).into_owned());
+    Some(names.collect())
+}
+
+impl Replacer {
+    /// Only replace matches in `columns`, taking each text as CSV.
+    pub fn columns(mut self, columns: Columns) -> Replacer {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// `preview` of the columns' fields, a `Change` for each record
+    /// they're in. The columns the header doesn't have are left out.
+    pub(crate) fn preview_columns(&self, columns: &Columns, text: &str) -> Vec<Change> {
+        let indices = columns.indices(&header(text).unwrap_or_default());
+        let mut changes = Vec::new();
+        for record in Records::new(text).skip(1) {
+            let mut after = String::new();
+            let mut copied = record.range.start;
+            let mut replaced = Vec::new();
+            for field in indices.iter().filter_map(|&i| record.fields.get(i)) {
+                let value = field.value(text);
+                let field_changes = self.preview_text(&value);
+                if field_changes.is_empty() {
+                    continue;
+                }
+                // Where each match is in the record, as it's written.
+                let base = field.range.start - record.range.start;
+                for change in &field_changes {
+                    for r in &change.replaced {
+                        let start = change.offset + r.range.start;
+                        let end = change.offset + r.range.end;
+                        replaced.push(Replaced {
+                            range: base + field.raw(&value, start)..base + field.raw(&value, end),
+                            replacement: r.replacement.clone(),
+                        });
+                    }
+                }
+                after.push_str(&text[copied..field.range.start]);
+                after.push_str(&field.write(&apply(&value, &field_changes)));
+                copied = field.range.end;
+            }
+            if replaced.is_empty() {
+                continue;
+            }
+            after.push_str(&text[copied..record.range.end]);
+            changes.push(Change {
+                line: text[..record.range.start].matches('\n').count() + 1,
+                offset: record.range.start,
+                before: text[record.range.clone()].to_string(),
+                after,
+                replacements: replaced.len(),
+                replaced,
+            });
+        }
+        changes
+    }
+
+    /// `count` of the columns' fields.
+    pub(crate) fn count_columns(&self, columns: &Columns, text: &str) -> usize {
+        let indices = columns.indices(&header(text).unwrap_or_default());
+        let mut count = 0;
+        for record in Records::new(text).skip(1) {
+            for field in indices.iter().filter_map(|&i| record.fields.get(i)) {
+                count += self.count_text(&field.value(text));
+            }
+        }
+        count
+    }
+}
+
+/// One record: where it is, without its line ending, and its fields.
+#[derive(Debug)]
+struct Record {
+    range: Range<usize>,
+    fields: Vec<Field>,
+}
+
+/// One field, as it's written.
+#[derive(Debug)]
+struct Field {
+    range: Range<usize>,
+    /// Whether it's in quotes, with nothing after the closing one.
+    quoted: bool,
+}
+
+impl Field {
+    /// What the field says, in `text`.
+    fn value<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        let raw = &text[self.range.clone()];
+        if self.quoted {
+            let inside = &raw[1..raw.len() - 1];
+            if inside.contains('"') {
+                Cow::Owned(inside.replace("\"\"", "\""))
+            } else {
+                Cow::Borrowed(inside)
+            }
+        } else {
+            Cow::Borrowed(raw)
+        }
+    }
+
+    /// Where `offset` in the field's `value` is in the field as it's
+    /// written, from its start.
+    fn raw(&self, value: &str, offset: usize) -> usize {
+        if self.quoted {
+            1 + offset + value[..offset].matches('"').count()
+        } else {
+            offset
+        }
+    }
+
+    /// `value`, written in place of this field.
+    fn write(&self, value: &str) -> String {
+        if self.quoted || value.contains([DELIMITER as char, '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+/// A text's records, in order.
+struct Records<'t> {
+    text: &'t [u8],
+    /// Where the next record starts.
+    next: usize,
+}
+
+impl<'t> Records<'t> {
+    fn new(text: &'t str) -> Records<'t> {
+        Records {
+            text: text.as_bytes(),
+            next: 0,
+        }
+    }
+
+    /// The field starting at `start`, and where the delimiter, line
+    /// ending, or end of the text after it is.
+    fn field(&self, start: usize) -> (Field, usize) {
+        let bytes = self.text;
+        let mut i = start;
+        let mut closed = None;
+        if bytes.get(i) == Some(&b'"') {
+            i += 1;
+            loop {
+                match bytes[i..].iter().position(|&b| b == b'"') {
+                    None => {
+                        i = bytes.len();
+                        break;
+                    }
+                    Some(at) if bytes.get(i + at + 1) == Some(&b'"') => i += at + 2,
+                    Some(at) => {
+                        i += at + 1;
+                        closed = Some(i);
+                        break;
+                    }
+                }
+            }
+        }
+        while i < bytes.len() && bytes[i] != DELIMITER && bytes[i] != b'\n' {
+            i += 1;
+        }
+        let mut end = i;
+        if bytes.get(i) != Some(&DELIMITER) && end > start && bytes[end - 1] == b'\r' {
+            end -= 1;
+        }
+        let field = Field {
+            range: start..end,
+            quoted: closed == Some(end),
+        };
+        (field, i)
+    }
+}
+
+impl Iterator for Records<'_> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        let start = self.next;
+        if start >= self.text.len() {
+            return None;
+        }
+        let mut fields = Vec::new();
+        let mut i = start;
+        loop {
+            let (field, end) = self.field(i);
+            let field_end = field.range.end;
+            fields.push(field);
+            if self.text.get(end) == Some(&DELIMITER) {
+                i = end + 1;
+                continue;
+            }
+            self.next = end + 1;
+            return Some(Record {
+                range: start..field_end,
+                fields,
+            });
+        }
+    }
+}
+
+impl fmt::Display for MissingColumn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no column '{}'", self.column)
+    }
+}
+
+impl Error for MissingColumn {}
@@ -0,0 +1,163 @@
+// Unified diffs, for showing a replacement as a patch.
+//
+// `edits` finds the shortest way to turn one list of lines into another
+// with Myers' algorithm: for each number of insertions and deletions `d`
+// in turn, it follows every path with `d` of them as far down the
+// diagonals of matching lines as it goes, until one reaches the end. The
+// furthest point on each diagonal is kept for each `d`, so the path can
+// be traced back. `unified` groups the edits into hunks, with three lines
+// of context around each change, as `diff -u` and `git diff` do.
+
+use std::fmt::Write;
+
+/// Lines of context kept around each change.
+const CONTEXT: usize = 3;
+
+/// What to do with the next line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    /// It's in both texts.
+    Keep,
+    /// It's only in the old one.
+    Delete,
+    /// It's only in the new one.
+    Insert,
+}
+
+/// The changes from `old` to `new` as a unified diff, with `name` as the
+/// file on both sides, or `""` if they're the same.
+pub fn unified(name: &str, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+    let edits = edits(&old, &new);
+
+    // Each edit, with the old and new line it's at.
+    let mut lines = Vec::with_capacity(edits.len());
+    let (mut o, mut n) = (0, 0);
+    for edit in edits {
+        lines.push((edit, o, n));
+        match edit {
+            Edit::Keep => (o, n) = (o + 1, n + 1),
+            Edit::Delete => o += 1,
+            Edit::Insert => n += 1,
+        }
+    }
+
+    let mut diff = String::new();
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].0 != Edit::Keep)
+        .collect();
+    let mut rest = &changed[..];
+    while let [first, ..] = rest {
+        // A hunk takes in every change whose context would meet the last
+        // one's.
+        let mut last = *first;
+        let mut taken = 1;
+        while let Some(&next) = rest.get(taken) {
+            if next - last - 1 > 2 * CONTEXT {
+                break;
+            }
+            last = next;
+            taken += 1;
+        }
+        rest = &rest[taken..];
+        let hunk = &lines[first.saturating_sub(CONTEXT)..(last + 1 + CONTEXT).min(lines.len())];
+
+        if diff.is_empty() {
+            let _ = write!(diff, "--- {}\n+++ {}\n", name, name);
+        }
+        let (_, old_start, new_start) = hunk[0];
+        let old_len = hunk.iter().filter(|l| l.0 != Edit::Insert).count();
+        let new_len = hunk.iter().filter(|l| l.0 != Edit::Delete).count();
+        let _ = writeln!(
+            diff,
+            "@@ -{} +{} @@",
+            range(old_start, old_len),
+            range(new_start, new_len)
+        );
+        for &(edit, o, n) in hunk {
+            let (sign, line) = match edit {
+                Edit::Keep => (' ', old[o]),
+                Edit::Delete => ('-', old[o]),
+                Edit::Insert => ('+', new[n]),
+            };
+            diff.push(sign);
+            diff.push_str(line);
+            if !line.ends_with('\n') {
+                diff.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    diff
+}
+
+/// A hunk's lines on one side, as `start,len`, counting from 1, or just
+/// `start` for one line. An empty range starts at the line before it.
+fn range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+/// The shortest list of edits turning `old` into `new`.
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    // The furthest `x` reached on each diagonal `k = x - y`, at
+    // `v[k + max]`, after each number of edits.
+    let mut v = vec![0; 2 * max as usize + 2];
+    let mut trace = Vec::new();
+    let at = |k: isize| (k + max) as usize;
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            // Down from the diagonal above, an insertion, or right from
+            // the one below, a deletion: whichever got further.
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let previous = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = v[at(previous)];
+        let previous_y = previous_x - previous;
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Keep);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == previous_x {
+                Edit::Insert
+            } else {
+                Edit::Delete
+            });
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    edits.reverse();
+    edits
+}
@@ -0,0 +1,206 @@
+// Text in encodings other than UTF-8, for files from older tools and
+// other platforms. A file's bytes are decoded into a `String` to be
+// replaced, then encoded back the same way, byte order mark and all, so
+// only what was replaced changes.
+//
+// The decoding is encoding_rs's, as browsers do it, without replacing
+// what's malformed, so a bad byte is an error with its offset rather than
+// a U+FFFD written back into the file. It only encodes what a web page
+// can be sent in, which UTF-16 isn't, so that's `str::encode_utf16`.
+// Latin-1, ISO 8859-1, is the first 256 code points, one byte each: not
+// the WHATWG's `latin1`, which encoding_rs takes as Windows-1252, but its
+// `mem` functions for Latin-1 itself. `Encoding::detect` guesses a file's
+// encoding from its byte order mark, or failing that, takes it as UTF-8
+// if it is valid UTF-8, and Latin-1, in which any bytes are valid, if
+// not.
+
+use std::fmt;
+use std::str::FromStr;
+
+use encoding_rs::{mem, DecoderResult, UTF_16BE, UTF_16LE, UTF_8};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO 8859-1. Unlike the WHATWG's `latin1`, which is Windows-1252,
+    /// bytes 0x80 to 0x9F are the C1 control characters.
+    Latin1,
+}
+
+/// Text decoded from bytes, and how to encode it back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub text: String,
+    pub encoding: Encoding,
+    /// Whether the bytes started with a byte order mark, which isn't part
+    /// of `text`.
+    pub bom: bool,
+}
+
+/// Bytes that aren't text in the encoding they were taken to be in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub encoding: Encoding,
+    /// Where the first bad byte is.
+    pub offset: usize,
+}
+
+/// A character that the encoding has no bytes for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeError {
+    pub encoding: Encoding,
+    pub char: char,
+}
+
+/// A name for an encoding that isn't one of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownEncoding(pub String);
+
+impl Encoding {
+    /// The encoding `bytes` are in, as far as can be told: the one its
+    /// byte order mark is for, or UTF-8 if they're valid UTF-8, or
+    /// Latin-1.
+    pub fn detect(bytes: &[u8]) -> Encoding {
+        match encoding_rs::Encoding::for_bom(bytes) {
+            Some((encoding, _)) if encoding == UTF_16LE => Encoding::Utf16Le,
+            Some((encoding, _)) if encoding == UTF_16BE => Encoding::Utf16Be,
+            Some(_) => Encoding::Utf8,
+            None if std::str::from_utf8(bytes).is_ok() => Encoding::Utf8,
+            None => Encoding::Latin1,
+        }
+    }
+
+    /// encoding_rs's name for this encoding; Latin-1 has none.
+    fn codec(self) -> Option<&'static encoding_rs::Encoding> {
+        match self {
+            Encoding::Utf8 => Some(UTF_8),
+            Encoding::Utf16Le => Some(UTF_16LE),
+            Encoding::Utf16Be => Some(UTF_16BE),
+            Encoding::Latin1 => None,
+        }
+    }
+
+    /// `bytes` as text in this encoding, without the byte order mark, if
+    /// they start with this encoding's.
+    pub fn decode(self, bytes: &[u8]) -> Result<Decoded, DecodeError> {
+        let Some(codec) = self.codec() else {
+            return Ok(Decoded {
+                text: mem::decode_latin1(bytes).into_owned(),
+                encoding: self,
+                bom: false,
+            });
+        };
+        let skipped = match encoding_rs::Encoding::for_bom(bytes) {
+            Some((encoding, length)) if encoding == codec => length,
+            _ => 0,
+        };
+        let body = &bytes[skipped..];
+        let mut decoder = codec.new_decoder_without_bom_handling();
+        let capacity = decoder
+            .max_utf8_buffer_length_without_replacement(body.len())
+            .expect("a file that fits in memory fits in a usize");
+        let mut text = String::with_capacity(capacity);
+        match decoder.decode_to_string_without_replacement(body, &mut text, true) {
+            (DecoderResult::InputEmpty, _) => {}
+            // `read` counts the bytes after the bad ones that were read
+            // before it was found to be bad.
+            (DecoderResult::Malformed(bad, after), read) => {
+                return Err(DecodeError {
+                    encoding: self,
+                    offset: skipped + read - usize::from(bad) - usize::from(after),
+                })
+            }
+            (DecoderResult::OutputFull, _) => unreachable!("the text has room for the most"),
+        }
+        Ok(Decoded {
+            text,
+            encoding: self,
+            bom: skipped > 0,
+        })
+    }
+
+    /// `text` in this encoding, after a byte order mark if `bom` is set
+    /// and it has one.
+    pub fn encode(self, text: &str, bom: bool) -> Result<Vec<u8>, EncodeError> {
+        let bom = if bom && self != Encoding::Latin1 {
+            "\u{FEFF}"
+        } else {
+            ""
+        };
+        let utf16 = || bom.encode_utf16().chain(text.encode_utf16());
+        match self {
+            Encoding::Utf8 => Ok([bom, text].concat().into_bytes()),
+            Encoding::Utf16Le => Ok(utf16().flat_map(u16::to_le_bytes).collect()),
+            Encoding::Utf16Be => Ok(utf16().flat_map(u16::to_be_bytes).collect()),
+            Encoding::Latin1 => match text[mem::str_latin1_up_to(text)..].chars().next() {
+                Some(char) => Err(EncodeError {
+                    encoding: self,
+                    char,
+                }),
+                None => Ok(mem::encode_latin1_lossy(text).into_owned()),
+            },
+        }
+    }
+}
+
+impl Decoded {
+    /// `text`, in the encoding this was decoded from, with its byte order
+    /// mark if it had one.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, EncodeError> {
+        self.encoding.encode(text, self.bom)
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = UnknownEncoding;
+
+    /// An encoding's name, in any case, with or without its hyphen:
+    /// `utf-8`, `utf-16le`, `utf-16be`, or `latin1`, also called
+    /// `iso-8859-1`.
+    fn from_str(name: &str) -> Result<Encoding, UnknownEncoding> {
+        match name.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "utf8" => Ok(Encoding::Utf8),
+            "utf16le" => Ok(Encoding::Utf16Le),
+            "utf16be" => Ok(Encoding::Utf16Be),
+            "latin1" | "iso88591" => Ok(Encoding::Latin1),
+            _ => Err(UnknownEncoding(name.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "Latin-1",
+        })
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid {} at byte {}", self.encoding, self.offset)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} can't be written in {}", self.char, self.encoding)
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl fmt::Display for UnknownEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown encoding '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownEncoding {}
@@ -0,0 +1,144 @@
+// `--exec CMD`: each replacement worked out by a command, for what a
+// template can't say, like a hash or a lookup:
+//
+//     quickreplace --exec 'sha256sum | cut -c1-12' '[\w.]+@[\w.]+' users.csv out.csv
+//     quickreplace --exec 'grep "^$QUICKREPLACE_GROUP_1=" ids.txt | cut -d= -f2' 'id:(\d+)' in.txt out.txt
+//
+// The command is run by the shell, once for each match, with the match on
+// its standard input and in `QUICKREPLACE_MATCH`, and each group that took
+// part in `QUICKREPLACE_GROUP_1`, `QUICKREPLACE_GROUP_2`, and so on, or for
+// a named group, `QUICKREPLACE_GROUP_name`. What it prints is the
+// replacement, less one newline at the end, since most commands end with
+// one. What it says on standard error goes to quickreplace's.
+//
+// A command that can't be run, exits with a failure, or prints something
+// that isn't UTF-8 is an error, and the text isn't written; see `Plugins`
+// for how it gets back to the caller.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+use regex::{Captures, Regex};
+
+/// The command that works out each replacement.
+#[derive(Debug)]
+pub struct Exec {
+    command: String,
+}
+
+/// Why a replacement couldn't be worked out.
+#[derive(Debug)]
+pub enum Error {
+    /// The shell couldn't be started.
+    Spawn { command: String, error: io::Error },
+    /// The command exited with a failure.
+    Failed {
+        command: String,
+        matched: String,
+        status: ExitStatus,
+    },
+    /// What the command printed isn't UTF-8.
+    NotUtf8 { command: String },
+}
+
+/// The prefix of the environment variables a match is given in.
+const VAR: &str = "QUICKREPLACE_";
+
+impl Exec {
+    pub fn new(command: &str) -> Exec {
+        Exec {
+            command: command.to_string(),
+        }
+    }
+
+    /// The replacement for the match of `regex` in `captures`: what the
+    /// command prints for it.
+    pub fn run(&self, regex: &Regex, captures: &Captures) -> Result<String, Error> {
+        let matched = &captures[0];
+        let mut command = shell(&self.command);
+        command
+            .env(format!("{}MATCH", VAR), matched)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+        let groups = captures.iter().zip(regex.capture_names());
+        for (i, (group, name)) in groups.enumerate().skip(1) {
+            if let Some(group) = group {
+                let name = name.map_or_else(|| i.to_string(), str::to_string);
+                command.env(format!("{}GROUP_{}", VAR, name), group.as_str());
+            }
+        }
+        let spawn = |error| Error::Spawn {
+            command: self.command.clone(),
+            error,
+        };
+        let mut child = command.spawn().map_err(spawn)?;
+        let mut stdin = child.stdin.take().expect("standard input is piped");
+        // Written on a thread of its own, so a command that prints before
+        // it's read everything can't fill a pipe and wait forever.
+        let output = thread::scope(|scope| {
+            scope.spawn(move || {
+                // A command that doesn't read its input closes it early,
+                // which is no error.
+                let _ = stdin.write_all(matched.as_bytes());
+            });
+            child.wait_with_output()
+        })
+        .map_err(spawn)?;
+        if !output.status.success() {
+            return Err(Error::Failed {
+                command: self.command.clone(),
+                matched: matched.to_string(),
+                status: output.status,
+            });
+        }
+        let mut text = String::from_utf8(output.stdout).map_err(|_| Error::NotUtf8 {
+            command: self.command.clone(),
+        })?;
+        if text.ends_with('\n') {
+            text.pop();
+            if text.ends_with('\r') {
+                text.pop();
+            }
+        }
+        Ok(text)
+    }
+}
+
+/// `command`, to be run by the shell.
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Spawn { command, .. } => write!(f, "couldn't run '{}'", command),
+            Error::Failed {
+                command,
+                matched,
+                status,
+            } => write!(f, "'{}' failed on '{}': {}", command, matched, status),
+            Error::NotUtf8 { command } => write!(f, "'{}' printed invalid UTF-8", command),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Spawn { error, .. } => Some(error),
+            Error::Failed { .. } | Error::NotUtf8 { .. } => None,
+        }
+    }
+}
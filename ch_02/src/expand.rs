@@ -0,0 +1,89 @@
+// Environment variables in a replacement, for quickreplace's
+// `--expand-env`: `${HOME}` or `${CI_COMMIT_SHA}` is put in before the
+// replacement is ever used, so it's the same for every match.
+//
+// The regex crate's replacements already use `$` for capture groups, so
+// only the braced form is a variable, and only with a name a variable can
+// have: letters, digits, and underscores, not starting with a digit.
+// `$1`, `$name`, and `${1}` are still groups; a named group in braces has
+// to be written without them. As in a shell, `${NAME:-default}` is
+// `default` if the variable isn't set, or is empty, and a variable that
+// isn't set, without a default, is an error rather than nothing, so a typo
+// doesn't quietly delete text. `$$` is a `$`, as it is to the regex crate,
+// so `$${HOME}` is `${HOME}` as it is.
+//
+// A variable's value is text, not a template: a `$` or a backslash in it,
+// as in a Windows path, is escaped so the regex crate and `\U` don't take
+// it for anything else. A template that's going to be taken literally, as
+// with `--fixed-strings`, is expanded with nothing escaped, and `$$` as
+// `$`, since it will all be escaped later.
+
+use std::fmt;
+
+/// A variable named in a template, without a default, that isn't set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsetVariable(pub String);
+
+/// `template` with each `${NAME}` in it replaced by what `lookup` says
+/// `NAME` is. If `escape`, the template is for a `Replacer`, so values are
+/// escaped, and `$$` left for it; if not, it's plain text.
+pub fn expand_env(
+    template: &str,
+    escape: bool,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, UnsetVariable> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        rest = &rest[dollar..];
+        if let Some(after) = rest.strip_prefix("$$") {
+            expanded.push_str(if escape { "$$" } else { "$" });
+            rest = after;
+            continue;
+        }
+        let Some((name, default, after)) = variable(rest) else {
+            expanded.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let value = match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+            (Some(value), _) if escape => value.replace('\\', "\\\\").replace('$', "$$"),
+            (Some(value), _) => value,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => return Err(UnsetVariable(name.to_string())),
+        };
+        expanded.push_str(&value);
+        rest = after;
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// The variable `text` starts with, as `${NAME}` or `${NAME:-default}`:
+/// its name, its default, and what's after it.
+fn variable(text: &str) -> Option<(&str, Option<&str>, &str)> {
+    let inside = text.strip_prefix("${")?;
+    let end = inside.find('}')?;
+    let (name, default) = match inside[..end].split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (&inside[..end], None),
+    };
+    let mut chars = name.chars();
+    let starts = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !starts || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some((name, default, &inside[end + 1..]))
+}
+
+impl fmt::Display for UnsetVariable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "environment variable '{}' isn't set", self.0)
+    }
+}
+
+impl std::error::Error for UnsetVariable {}
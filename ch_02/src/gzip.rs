@@ -0,0 +1,103 @@
+// Gzipped files, rewritten without a `gunzip` first and a `gzip` after:
+//
+//     quickreplace '\d+\.\d+\.\d+\.\d+' 'x.x.x.x' access.log.gz --in-place
+//
+// An input named `.gz` is decompressed as it's read, and an output named
+// `.gz` is compressed as it's written, so `a.log.gz a.log` decompresses,
+// `a.log a.log.gz` compresses, and `--in-place` puts a `.gz` file back
+// compressed. `-z`, `--compress`, says the input and output are gzipped
+// whatever they're named, as they are for standard input and output in a
+// pipeline, where there's no name to go by:
+//
+//     curl -s https://example.com/dump.sql.gz | quickreplace -z prod staging - - > staging.sql.gz
+//
+// Decompressing takes any number of gzip members one after another, as
+// `gunzip` does, and as `cat a.gz b.gz` makes. A whole text is compressed
+// once it's replaced; a stream, a line at a time, through a `Writer`,
+// which is what finishes the gzip trailer, so a stream has to be finished
+// for its output to be whole.
+
+use std::io::{self, Read, Write};
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::quickreplace::{Arguments, Output};
+
+/// What names a gzipped file.
+const SUFFIX: &str = ".gz";
+
+/// Whether the input is gzipped.
+pub fn input(args: &Arguments) -> bool {
+    args.compress || args.input_filename.ends_with(SUFFIX)
+}
+
+/// Whether the replaced text is to be gzipped: it goes to a file named
+/// `.gz`, or back into a gzipped input.
+pub fn output(args: &Arguments) -> bool {
+    match &args.output {
+        Output::File(name) => args.compress || name.ends_with(SUFFIX),
+        Output::InPlace { .. } => input(args),
+        Output::Count | Output::Diff | Output::Find | Output::Highlight => false,
+    }
+}
+
+/// `input`, decompressed as it's read if `gzip`.
+pub fn reader<'a>(input: Box<dyn Read + 'a>, gzip: bool) -> Box<dyn Read + 'a> {
+    if gzip {
+        Box::new(MultiGzDecoder::new(input))
+    } else {
+        input
+    }
+}
+
+/// `bytes`, gzipped.
+pub fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut writer = Writer::new(Vec::new(), true);
+    writer.write_all(bytes)?;
+    writer.finish()
+}
+
+/// An output, compressed as it's written, or not.
+#[derive(Debug)]
+pub enum Writer<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+}
+
+impl<W: Write> Writer<W> {
+    /// Write to `output`, gzipped if `gzip`.
+    pub fn new(output: W, gzip: bool) -> Writer<W> {
+        if gzip {
+            Writer::Gzip(GzEncoder::new(output, Compression::default()))
+        } else {
+            Writer::Plain(output)
+        }
+    }
+
+    /// Write the end of the gzip stream, if there is one, and return the
+    /// output.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Writer::Plain(output) => Ok(output),
+            Writer::Gzip(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Writer::Plain(output) => output.write(buf),
+            Writer::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Writer::Plain(output) => output.flush(),
+            Writer::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// `--highlight`: each change shown where it is, among the lines around it,
+// with what each match takes out in red and what goes in in green, so a
+// regex can be checked by eye before it's let loose on the files. Lines
+// are numbered as `grep -n -C` numbers them, `file:12:` for a line with a
+// match and `file-11-` for one around it, with `--` between lines that
+// aren't next to each other.
+//
+// Without color, as when the output is piped or `NO_COLOR` is set, the
+// spans are marked as `git diff --word-diff=plain` marks them instead:
+// `[-colour-]{+hue+}`. A newline in a replacement is shown as `\n`, so
+// that every line shown is one of the file's own, with its own number.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use colored::{ColoredString, Colorize};
+
+use crate::Change;
+
+/// Lines shown before and after each change.
+const CONTEXT: usize = 2;
+
+/// `changes` to `text`, from `file`, highlighted among the lines around
+/// them, or `""` if there are none.
+pub fn render(file: &str, text: &str, changes: &[Change]) -> String {
+    let lines: Vec<&str> = text
+        .split_inclusive('\n')
+        .map(|line| line.strip_suffix('\n').unwrap_or(line))
+        .collect();
+    let color = colored::control::SHOULD_COLORIZE.should_colorize();
+    let mut out = String::new();
+    // The lines before this have been shown.
+    let mut shown = 0;
+    for (i, change) in changes.iter().enumerate() {
+        let start = change.line - 1;
+        let end = start + change.before.split('\n').count();
+        let from = start.saturating_sub(CONTEXT).max(shown);
+        if shown > 0 && from > shown {
+            out.push_str("--\n");
+        }
+        context(&mut out, file, &lines, from..start);
+        for (offset, line) in mark(change, color).split('\n').enumerate() {
+            let _ = writeln!(out, "{}:{}:{}", file, start + offset + 1, line);
+        }
+        let next = changes.get(i + 1).map_or(lines.len(), |c| c.line - 1);
+        let to = (end + CONTEXT).min(next).min(lines.len());
+        context(&mut out, file, &lines, end..to);
+        shown = to;
+    }
+    out
+}
+
+/// Append `lines[range]` to `out`, as lines around a change.
+fn context(out: &mut String, file: &str, lines: &[&str], range: Range<usize>) {
+    for (number, line) in range.clone().zip(&lines[range]) {
+        let _ = writeln!(out, "{}-{}-{}", file, number + 1, line);
+    }
+}
+
+/// `change`'s lines as they are, with each match followed by its
+/// replacement, in color or marked.
+fn mark(change: &Change, color: bool) -> String {
+    let mut marked = String::new();
+    let mut copied = 0;
+    for replaced in &change.replaced {
+        marked.push_str(&change.before[copied..replaced.range.start]);
+        let old = &change.before[replaced.range.clone()];
+        let new = replaced.replacement.replace('\n', "\\n");
+        if color {
+            marked.push_str(&paint(old, |line| line.red().strikethrough()));
+            marked.push_str(&paint(&new, |line| line.green()));
+        } else {
+            if !old.is_empty() {
+                let _ = write!(marked, "[-{}-]", old);
+            }
+            if !new.is_empty() {
+                let _ = write!(marked, "{{+{}+}}", new);
+            }
+        }
+        copied = replaced.range.end;
+    }
+    marked.push_str(&change.before[copied..]);
+    marked
+}
+
+/// `text` with `style` applied to each of its lines on its own, so that no
+/// color runs on into the numbers of the next line.
+fn paint(text: &str, style: impl Fn(&str) -> ColoredString) -> String {
+    text.split('\n')
+        .map(|line| match line {
+            "" => String::new(),
+            line => style(line).to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,169 @@
+// `--journal FILE` and `undo`: a way back from `--in-place`. Every line a
+// run changes is written down, with where it is in the file now, what it
+// was, and what it became, and `quickreplace undo FILE` puts each one
+// back as it was. A backup does the same for one file, but a journal
+// covers a whole tree, and only holds the lines that changed.
+//
+// The journal is JSON, with each file's absolute path, so `undo` can be
+// run from anywhere:
+//
+//     {"files":[{"file":"/home/me/src/main.rs","encoding":"UTF-8",
+//       "changes":[{"offset":12,"before":"fn old() {}","after":"fn new() {}"}]}]}
+//
+// A line that isn't what the run left there any more, because the file
+// was edited since, isn't guessed at: that file is left alone, and said
+// to be, and the others are still restored.
+
+use std::fs;
+use std::io::Read;
+
+use common::{exit, tr, Context, Error, Messages};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::atomic::Preserve;
+use crate::encoding::Encoding;
+use crate::quickreplace::{decode, encode, write_in_place, write_output};
+use crate::{gzip, Change};
+
+/// The files a run rewrote in place, and how.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Journal {
+    files: Vec<FileJournal>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileJournal {
+    file: String,
+    /// What the file's text is encoded in, which the offsets aren't.
+    encoding: String,
+    /// Whether the file is gzipped, which the offsets aren't either.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    gzip: bool,
+    /// In order, none overlapping.
+    changes: Vec<Entry>,
+}
+
+/// Some lines as they were, and as they are.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    /// Where `after` starts in the rewritten text, in bytes.
+    offset: usize,
+    before: String,
+    after: String,
+}
+
+impl Journal {
+    /// Add `changes`, just made to `file`'s text, which is in `encoding`,
+    /// and gzipped if `gzip`.
+    pub fn add(
+        &mut self,
+        file: &str,
+        encoding: Encoding,
+        gzip: bool,
+        changes: &[Change],
+        messages: &Messages,
+    ) -> Result<(), Error> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let path =
+            fs::canonicalize(file).with_context(|| tr!(messages, "write-failed", file = file))?;
+        // Each change moves those after it by as much as it grew.
+        let mut moved = 0isize;
+        let changes = changes
+            .iter()
+            .map(|change| {
+                let offset = (change.offset as isize + moved) as usize;
+                moved += change.after.len() as isize - change.before.len() as isize;
+                Entry {
+                    offset,
+                    before: change.before.clone(),
+                    after: change.after.clone(),
+                }
+            })
+            .collect();
+        self.files.push(FileJournal {
+            file: path.display().to_string(),
+            encoding: encoding.to_string(),
+            gzip,
+            changes,
+        });
+        Ok(())
+    }
+
+    /// Write the journal to `file`.
+    pub fn write(&self, file: &str, messages: &Messages) -> Result<(), Error> {
+        let json =
+            serde_json::to_string_pretty(self).expect("a journal is all strings and numbers");
+        write_output(file, (json + "\n").as_bytes(), None, messages)
+    }
+}
+
+/// Put back every change in the journal `file`, returning how many
+/// files were restored. A file that can't be is reported, and the rest
+/// are still done; the run fails at the end if any couldn't be.
+pub fn undo(file: &str, messages: &Messages) -> Result<usize, Error> {
+    let text =
+        fs::read_to_string(file).with_context(|| tr!(messages, "read-failed", file = file))?;
+    let journal: Journal = serde_json::from_str(&text)
+        .map_err(|e| Error::from(e).with_code(exit::DATA_ERR))
+        .with_context(|| tr!(messages, "journal-invalid", file = file))?;
+    debug!(files = journal.files.len(), "read journal");
+    let mut restored = 0;
+    let mut failed = 0;
+    for entry in &journal.files {
+        match restore(entry, messages) {
+            Ok(()) => {
+                restored += 1;
+                println!("{}", tr!(messages, "undone", file = entry.file.as_str()));
+            }
+            Err(error) => {
+                common::report(&error);
+                failed += 1;
+            }
+        }
+    }
+    info!(restored, failed, "undone");
+    if failed > 0 {
+        return Err(Error::new(tr!(messages, "undo-failed", count = failed)));
+    }
+    Ok(restored)
+}
+
+/// Put `entry`'s file back as it was, if it's still as the run left it.
+fn restore(entry: &FileJournal, messages: &Messages) -> Result<(), Error> {
+    let file = entry.file.as_str();
+    let changed = || Error::new(tr!(messages, "undo-changed", file = file));
+    let encoding = entry
+        .encoding
+        .parse::<Encoding>()
+        .map_err(|e| Error::from(e).with_code(exit::DATA_ERR))
+        .with_context(|| tr!(messages, "read-failed", file = file))?;
+    let bytes = fs::File::open(file)
+        .and_then(|file| {
+            let mut bytes = Vec::new();
+            gzip::reader(Box::new(file), entry.gzip).read_to_end(&mut bytes)?;
+            Ok(bytes)
+        })
+        .with_context(|| tr!(messages, "read-failed", file = file))?;
+    let input = decode(&bytes, Some(encoding)).map_err(|_| changed())?;
+    let mut text = input.text.clone();
+    for change in entry.changes.iter().rev() {
+        let range = change.offset..change.offset + change.after.len();
+        if text.get(range.clone()) != Some(change.after.as_str()) {
+            return Err(changed());
+        }
+        text.replace_range(range, &change.before);
+    }
+    let mut bytes = encode(&input, &text, file, messages)?;
+    if entry.gzip {
+        bytes =
+            gzip::compress(&bytes).with_context(|| tr!(messages, "write-failed", file = file))?;
+    }
+    debug!(file, changes = entry.changes.len(), "restoring");
+    write_in_place(file, &bytes, None, Preserve::default(), messages)
+}
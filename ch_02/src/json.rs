@@ -0,0 +1,521 @@
+// Replacing only in some of a JSON document's strings, chosen by JSON
+// pointers, and leaving the rest of it, spacing, key order, numbers and
+// all, just as it was:
+//
+//     let replacer = Replacer::new("@old\\.com$", "@new.com")?
+//         .pointers(Pointers::parse(["/users/*/email"])?);
+//
+// A pointer is RFC 6901's, a `/` before each key or array index on the
+// way down from the top, with `~1` for a `/` in a key and `~0` for a `~`,
+// and one more thing: a `*` is any key, or any index. It addresses the
+// values at the end of that path, and of those, only strings are
+// replaced; an object, an array, a number, or a key is left alone, as is
+// a path that isn't there. Each string is replaced as a text of its own,
+// with its escapes decoded, so `^` and `$` are its start and end, and one
+// that changes is written back with only what JSON needs escaped: a `"`,
+// a `\`, and control characters. A `Replacer`'s limit is counted in each
+// string.
+//
+// A text that isn't JSON has nothing to replace; `Pointers::find` says
+// where it goes wrong.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{apply, Change, Replaced, Replacer, Span};
+
+/// How deep arrays and objects can be nested in a document, as with
+/// serde_json, so a hostile one can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+/// The strings of a JSON document to replace in, by JSON pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pointers {
+    pointers: Vec<Vec<Token>>,
+}
+
+/// One step of a pointer's path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// `*`: any key or index.
+    Any,
+    /// A key, or an index, as text.
+    Name(String),
+}
+
+/// A pointer that isn't one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPointer {
+    pub pointer: String,
+}
+
+/// Where, and how, a text isn't JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidJson {
+    /// What was expected, or found.
+    pub problem: &'static str,
+    /// The line, counting from 1.
+    pub line: usize,
+    /// The character in the line, counting from 1.
+    pub column: usize,
+}
+
+impl Pointers {
+    pub fn parse<S: AsRef<str>>(
+        pointers: impl IntoIterator<Item = S>,
+    ) -> Result<Pointers, InvalidPointer> {
+        let pointers = pointers.into_iter().map(|pointer| {
+            let pointer = pointer.as_ref();
+            let invalid = || InvalidPointer {
+                pointer: pointer.to_string(),
+            };
+            if !pointer.is_empty() && !pointer.starts_with('/') {
+                return Err(invalid());
+            }
+            let tokens = pointer.split('/').skip(1).map(|token| match token {
+                "*" => Ok(Token::Any),
+                _ => unescape(token).map(Token::Name).ok_or_else(invalid),
+            });
+            tokens.collect()
+        });
+        Ok(Pointers {
+            pointers: pointers.collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Where the strings the pointers address are in `text`, in order,
+    /// quotes and all, or where it isn't JSON.
+    pub fn find(&self, text: &str) -> Result<Vec<Range<usize>>, InvalidJson> {
+        let mut scanner = Scanner {
+            text,
+            at: 0,
+            pointers: &self.pointers,
+            path: Vec::new(),
+            found: Vec::new(),
+        };
+        scanner.space();
+        scanner.value(0)?;
+        scanner.space();
+        if scanner.at < text.len() {
+            return Err(scanner.invalid("expected the end of the text"));
+        }
+        Ok(scanner.found)
+    }
+}
+
+/// A pointer's token with `~1` and `~0` made `/` and `~`, or `None` if it
+/// has a `~` that's neither.
+fn unescape(token: &str) -> Option<String> {
+    let mut name = String::new();
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        if c != '~' {
+            name.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => name.push('~'),
+            Some('1') => name.push('/'),
+            _ => return None,
+        }
+    }
+    Some(name)
+}
+
+impl Replacer {
+    /// Only replace matches in the strings `pointers` address, taking each
+    /// text as JSON.
+    pub fn pointers(mut self, pointers: Pointers) -> Replacer {
+        self.pointers = Some(pointers);
+        self
+    }
+
+    /// `preview` of the pointers' strings, a `Change` for the lines of
+    /// each that changes, or of several on the same lines.
+    pub(crate) fn preview_strings(&self, pointers: &Pointers, text: &str) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let mut current: Option<Span> = None;
+        for string in pointers.find(text).unwrap_or_default() {
+            let inside = &text[string.start + 1..string.end - 1];
+            let value = decode(inside);
+            let string_changes = self.preview_text(&value);
+            if string_changes.is_empty() {
+                continue;
+            }
+            let start = text[..string.start].rfind('\n').map_or(0, |i| i + 1);
+            let end = text[string.end..]
+                .find('\n')
+                .map_or(text.len(), |i| string.end + i);
+            let mut span = match current.take() {
+                Some(span) if start <= span.end => span,
+                previous => {
+                    changes.extend(previous.map(|span| span.finish(text)));
+                    Span {
+                        start,
+                        end,
+                        copied: start,
+                        after: String::new(),
+                        replaced: Vec::new(),
+                    }
+                }
+            };
+            // Where each match is in the lines, as it's written.
+            let base = string.start + 1 - span.start;
+            for change in &string_changes {
+                for r in &change.replaced {
+                    let start = raw(inside, change.offset + r.range.start);
+                    let end = raw(inside, change.offset + r.range.end);
+                    span.replaced.push(Replaced {
+                        range: base + start..base + end,
+                        replacement: r.replacement.clone(),
+                    });
+                }
+            }
+            span.after.push_str(&text[span.copied..string.start]);
+            span.after.push_str(&write(&apply(&value, &string_changes)));
+            span.copied = string.end;
+            span.end = span.end.max(end);
+            current = Some(span);
+        }
+        changes.extend(current.map(|span| span.finish(text)));
+        changes
+    }
+
+    /// `count` of the pointers' strings.
+    pub(crate) fn count_strings(&self, pointers: &Pointers, text: &str) -> usize {
+        let strings = pointers.find(text).unwrap_or_default();
+        strings
+            .iter()
+            .map(|string| self.count_text(&decode(&text[string.start + 1..string.end - 1])))
+            .sum()
+    }
+}
+
+/// Each character of a string's `inside`, between its quotes, with its
+/// escapes decoded, and where it starts in `inside`. The string has been
+/// checked by `Scanner::string`.
+fn chars(inside: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut chars = inside.char_indices();
+    std::iter::from_fn(move || {
+        let (at, c) = chars.next()?;
+        if c != '\\' {
+            return Some((at, c));
+        }
+        let (_, escaped) = chars.next()?;
+        let c = match escaped {
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = hex(&inside[at + 2..at + 6]);
+                chars.nth(3);
+                // A surrogate pair is two escapes; a lone surrogate isn't
+                // a character.
+                let low = inside[at + 6..]
+                    .strip_prefix("\\u")
+                    .map(|rest| hex(&rest[..4]))
+                    .filter(|low| (0xDC00..0xE000).contains(low));
+                match (high, low) {
+                    (0xD800..=0xDBFF, Some(low)) => {
+                        chars.nth(5);
+                        let c = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                        char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER)
+                    }
+                    _ => char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER),
+                }
+            }
+            c => c,
+        };
+        Some((at, c))
+    })
+}
+
+/// Four hex digits' value.
+fn hex(digits: &str) -> u32 {
+    u32::from_str_radix(digits, 16).expect("the scanner checked them")
+}
+
+/// What a string's `inside`, between its quotes, says.
+fn decode(inside: &str) -> Cow<'_, str> {
+    if inside.contains('\\') {
+        Cow::Owned(chars(inside).map(|(_, c)| c).collect())
+    } else {
+        Cow::Borrowed(inside)
+    }
+}
+
+/// Where `offset` in what a string's `inside` says is in `inside`.
+fn raw(inside: &str, offset: usize) -> usize {
+    let mut decoded = 0;
+    for (at, c) in chars(inside) {
+        if decoded >= offset {
+            return at;
+        }
+        decoded += c.len_utf8();
+    }
+    inside.len()
+}
+
+/// `value` as a JSON string.
+fn write(value: &str) -> String {
+    let mut written = String::with_capacity(value.len() + 2);
+    written.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => written.push_str("\\\""),
+            '\\' => written.push_str("\\\\"),
+            '\n' => written.push_str("\\n"),
+            '\r' => written.push_str("\\r"),
+            '\t' => written.push_str("\\t"),
+            '\u{8}' => written.push_str("\\b"),
+            '\u{c}' => written.push_str("\\f"),
+            c if c < ' ' => written.push_str(&format!("\\u{:04x}", c as u32)),
+            c => written.push(c),
+        }
+    }
+    written.push('"');
+    written
+}
+
+/// A key or index on the way down to a value.
+enum Segment<'t> {
+    Key(Cow<'t, str>),
+    Index(usize),
+}
+
+/// Reads a document, keeping where each string the pointers address is.
+struct Scanner<'t, 'p> {
+    text: &'t str,
+    /// How far it's read.
+    at: usize,
+    pointers: &'p [Vec<Token>],
+    /// The way down to the value being read.
+    path: Vec<Segment<'t>>,
+    found: Vec<Range<usize>>,
+}
+
+impl<'t> Scanner<'t, '_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.at).copied()
+    }
+
+    fn space(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.at += 1;
+        }
+    }
+
+    /// Read `byte`, after any space.
+    fn expect(&mut self, byte: u8, problem: &'static str) -> Result<(), InvalidJson> {
+        self.space();
+        if self.peek() != Some(byte) {
+            return Err(self.invalid(problem));
+        }
+        self.at += 1;
+        Ok(())
+    }
+
+    fn invalid(&self, problem: &'static str) -> InvalidJson {
+        let before = &self.text[..self.at];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        InvalidJson {
+            problem,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Whether the value at the end of the path is one the pointers
+    /// address.
+    fn addressed(&self) -> bool {
+        self.pointers.iter().any(|tokens| {
+            tokens.len() == self.path.len()
+                && tokens.iter().zip(&self.path).all(|pair| match pair {
+                    (Token::Any, _) => true,
+                    (Token::Name(name), Segment::Key(key)) => name == key,
+                    (Token::Name(name), Segment::Index(i)) => *name == i.to_string(),
+                })
+        })
+    }
+
+    /// Read a value, `depth` arrays and objects down.
+    fn value(&mut self, depth: usize) -> Result<(), InvalidJson> {
+        if depth > MAX_DEPTH {
+            return Err(self.invalid("nested too deeply"));
+        }
+        match self.peek() {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => {
+                let string = self.string()?;
+                if self.addressed() {
+                    self.found.push(string);
+                }
+                Ok(())
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => {
+                let rest = &self.text[self.at..];
+                match ["true", "false", "null"]
+                    .into_iter()
+                    .find(|literal| rest.starts_with(literal))
+                {
+                    Some(literal) => {
+                        self.at += literal.len();
+                        Ok(())
+                    }
+                    None => Err(self.invalid("expected a value")),
+                }
+            }
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<(), InvalidJson> {
+        self.at += 1;
+        self.space();
+        if self.peek() == Some(b'}') {
+            self.at += 1;
+            return Ok(());
+        }
+        loop {
+            self.space();
+            if self.peek() != Some(b'"') {
+                return Err(self.invalid("expected a key"));
+            }
+            let key = self.string()?;
+            self.expect(b':', "expected ':'")?;
+            self.space();
+            let text = self.text;
+            let key = decode(&text[key.start + 1..key.end - 1]);
+            self.path.push(Segment::Key(key));
+            self.value(depth + 1)?;
+            self.path.pop();
+            self.space();
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b'}') => {
+                    self.at += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.invalid("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<(), InvalidJson> {
+        self.at += 1;
+        self.space();
+        if self.peek() == Some(b']') {
+            self.at += 1;
+            return Ok(());
+        }
+        for i in 0.. {
+            self.space();
+            self.path.push(Segment::Index(i));
+            self.value(depth + 1)?;
+            self.path.pop();
+            self.space();
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b']') => break,
+                _ => return Err(self.invalid("expected ',' or ']'")),
+            }
+        }
+        self.at += 1;
+        Ok(())
+    }
+
+    /// Read a string, returning where it is, quotes and all.
+    fn string(&mut self) -> Result<Range<usize>, InvalidJson> {
+        let start = self.at;
+        self.at += 1;
+        loop {
+            match self.peek() {
+                None => {
+                    self.at = start;
+                    return Err(self.invalid("a string that's never closed"));
+                }
+                Some(b'"') => {
+                    self.at += 1;
+                    return Ok(start..self.at);
+                }
+                Some(b'\\') => {
+                    self.at += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.at += 1
+                        }
+                        Some(b'u')
+                            if self.text.as_bytes()[self.at + 1..]
+                                .get(..4)
+                                .is_some_and(|digits| digits.iter().all(u8::is_ascii_hexdigit)) =>
+                        {
+                            self.at += 5
+                        }
+                        _ => return Err(self.invalid("an invalid escape")),
+                    }
+                }
+                Some(0..=0x1f) => return Err(self.invalid("a control character in a string")),
+                Some(_) => self.at += 1,
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<(), InvalidJson> {
+        let digits = |scanner: &mut Self| {
+            let start = scanner.at;
+            while scanner.peek().is_some_and(|b| b.is_ascii_digit()) {
+                scanner.at += 1;
+            }
+            if scanner.at > start {
+                Ok(())
+            } else {
+                Err(scanner.invalid("expected a digit"))
+            }
+        };
+        if self.peek() == Some(b'-') {
+            self.at += 1;
+        }
+        digits(self)?;
+        if self.peek() == Some(b'.') {
+            self.at += 1;
+            digits(self)?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.at += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.at += 1;
+            }
+            digits(self)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for InvalidPointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' isn't a JSON pointer, like /users/*/email",
+            self.pointer
+        )
+    }
+}
+
+impl Error for InvalidPointer {}
+
+impl fmt::Display for InvalidJson {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.problem, self.line, self.column
+        )
+    }
+}
+
+impl Error for InvalidJson {}
@@ -0,0 +1,802 @@
+// The engine behind quickreplace, kept apart from the command line and
+// the files it reads and writes, so that other programs (and other
+// targets, like WebAssembly) can use it.
+//
+// `Replacer::builder` takes patterns as text, with the flags to compile
+// them with, for a program that has them from a user, as quickreplace
+// does from its command line and its rules files. `Replacer::rule` adds
+// more patterns to replace in the same pass, for its `--rules`, and
+// replacements can change case as sed's do, with `\U`, `\L`, and `\E`.
+//
+// A replacement can be worked on as it's made. `Replacer::filter` can
+// rework each one before it goes in, which is how quickreplace's
+// `--plugin`s get their say, and `Replacer::compute` works each one out
+// from the match instead, as its `--exec` command does. `expand` puts
+// environment variables into a replacement, for its `--expand-env`.
+//
+// Which matches are replaced can be narrowed: `Replacer::limit` replaces
+// only the first few in a text, for its `--max`, and
+// `Replacer::only_lines_matching` and `Replacer::skip_lines_matching` only
+// those on some lines, for the options of the same names.
+// `Replacer::columns` only replaces in some of a CSV text's columns, for
+// its `--csv`, and `Replacer::pointers` in some of a JSON text's strings,
+// for its `--json`.
+//
+// `Replacer::preview` shows what a replacement would change, line by
+// line, without making it, and `apply` makes a preview's changes, so a
+// caller that needs to know each match, like its `--report`, can have
+// them and the text both from one pass. `diff` shows a replacement as a
+// unified diff, for its `--diff`, each `Change` says where its matches
+// are, for its `--highlight`, and `Stats` sums up what a replacement did,
+// for its `--stats`.
+//
+// The rest is about the text and where it comes from. `encoding` reads
+// and writes text in UTF-16 and Latin-1, as well as UTF-8, for its
+// `--encoding`. `chunked` replaces a huge text in pieces, on as many
+// threads as there are, for its single huge files, and `walk` finds the
+// files under a directory, for rewriting a whole tree. `rules` reads its
+// `--rules` files, and `quickreplace` is the rest of the program, which
+// `run`s what its command line asks for.
+
+mod builder;
+pub mod chunked;
+pub mod csv;
+pub mod diff;
+pub mod encoding;
+pub mod expand;
+pub mod json;
+pub mod rules;
+pub mod walk;
+
+// The program, but for its command line, which only it parses, and none
+// of it for WebAssembly, where there are no threads to replace on, no
+// plugins to load, and no files to rewrite.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod atomic;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod exec;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gzip;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod highlight;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod journal;
+#[cfg(unix)]
+pub mod mapped;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod plugins;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod progress;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod quickreplace;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod report;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod source;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stats;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stream;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tree;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watch;
+
+use std::borrow::Cow;
+use std::fmt;
+use std::iter::Take;
+use std::ops::{AddAssign, Range};
+
+use regex::{CaptureMatches, Captures, Regex};
+
+pub use builder::{BuildError, ReplacerBuilder};
+pub use csv::Columns;
+pub use json::Pointers;
+
+/// Replace every match of the regular expression `target` in `text` with
+/// `replacement`, which may refer to capture groups as `$1` or `$name`.
+///
+/// As in sed, `\U` in the replacement puts everything after it in upper
+/// case, captures and all, `\L` in lower case, and `\E` ends either.
+/// `\\` is a backslash; any other backslash is just a backslash.
+pub fn replace(target: &str, replacement: &str, text: &str) -> Result<String, regex::Error> {
+    Ok(Replacer::new(target, replacement)?
+        .replace(text)
+        .0
+        .into_owned())
+}
+
+/// `text` with `changes`, from `Replacer::preview` on it, made: the same
+/// text `Replacer::replace` gives, without finding the matches again or
+/// expanding their replacements twice.
+pub fn apply(text: &str, changes: &[Change]) -> String {
+    let mut applied = String::with_capacity(text.len());
+    let mut copied = 0;
+    for change in changes {
+        applied.push_str(&text[copied..change.offset]);
+        applied.push_str(&change.after);
+        copied = change.offset + change.before.len();
+    }
+    applied.push_str(&text[copied..]);
+    applied
+}
+
+/// `replace`, with the regular expression compiled once, for rewriting
+/// many texts.
+///
+/// A `Replacer` can have several rules, each a pattern and its
+/// replacement. They're all applied in a single pass: at each point in the
+/// text, the rule whose match starts first wins, or on a tie, the one
+/// added first. A replacement is never searched again, so rules can swap
+/// two words without the second undoing the first.
+pub struct Replacer {
+    rules: Vec<Rule>,
+    filters: Vec<Filter>,
+    /// What works out each replacement instead of the rules' own, if
+    /// anything.
+    compute: Option<Compute>,
+    /// How many matches to replace in each text; `None` for all of them.
+    limit: Option<usize>,
+    /// What the line a match starts on must be like for it to be
+    /// replaced.
+    guards: Vec<Guard>,
+    /// With a CSV text, the only columns to replace in.
+    columns: Option<Columns>,
+    /// With a JSON text, the only strings to replace in.
+    pointers: Option<Pointers>,
+}
+
+/// A regex that a match's line must match, or must not.
+#[derive(Debug)]
+struct Guard {
+    regex: Regex,
+    /// Whether the line must match; if not, it mustn't.
+    matching: bool,
+}
+
+/// A pattern, and what to put in place of its matches.
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    replacement: Template,
+}
+
+/// A replacement, split where `\U`, `\L`, or `\E` changes its case. Each
+/// part is expanded by the regex crate, then put in its case.
+#[derive(Debug)]
+struct Template {
+    parts: Vec<(Case, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Keep,
+    Upper,
+    Lower,
+}
+
+impl Template {
+    fn parse(replacement: &str) -> Template {
+        let mut parts = vec![(Case::Keep, String::new())];
+        let mut chars = replacement.chars().peekable();
+        while let Some(c) = chars.next() {
+            let case = match (c, chars.peek()) {
+                ('\\', Some('U')) => Case::Upper,
+                ('\\', Some('L')) => Case::Lower,
+                ('\\', Some('E')) => Case::Keep,
+                ('\\', Some('\\')) => {
+                    chars.next();
+                    parts.last_mut().unwrap().1.push('\\');
+                    continue;
+                }
+                _ => {
+                    parts.last_mut().unwrap().1.push(c);
+                    continue;
+                }
+            };
+            chars.next();
+            match parts.last_mut().unwrap() {
+                (last, text) if text.is_empty() => *last = case,
+                _ => parts.push((case, String::new())),
+            }
+        }
+        Template { parts }
+    }
+
+    /// The template as the regex crate takes it, if it changes no case.
+    fn plain(&self) -> Option<&str> {
+        match &self.parts[..] {
+            [(Case::Keep, text)] => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The groups the template refers to, by number or name, as the regex
+    /// crate reads them: `$$` is a `$`, `${...}` is whatever's in the
+    /// braces, and `$name` is as many letters, digits, and underscores as
+    /// follow, so `$1a` is a group named `1a`, not group 1.
+    fn groups(&self) -> Vec<&str> {
+        let mut groups = Vec::new();
+        for (_, text) in &self.parts {
+            let mut rest = text.as_str();
+            while let Some(dollar) = rest.find('$') {
+                rest = &rest[dollar + 1..];
+                if let Some(after) = rest.strip_prefix('$') {
+                    rest = after;
+                } else if let Some((group, after)) = rest
+                    .strip_prefix('{')
+                    .and_then(|inside| inside.split_once('}'))
+                {
+                    groups.push(group);
+                    rest = after;
+                } else {
+                    let end = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                        .unwrap_or(rest.len());
+                    if end > 0 {
+                        groups.push(&rest[..end]);
+                    }
+                    rest = &rest[end..];
+                }
+            }
+        }
+        groups
+    }
+
+    /// Append the replacement for `captures` to `out`.
+    fn expand(&self, captures: &Captures, out: &mut String) {
+        for (case, text) in &self.parts {
+            match case {
+                Case::Keep => captures.expand(text, out),
+                Case::Upper | Case::Lower => {
+                    let mut expanded = String::new();
+                    captures.expand(text, &mut expanded);
+                    out.push_str(&if *case == Case::Upper {
+                        expanded.to_uppercase()
+                    } else {
+                        expanded.to_lowercase()
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// A function each replacement is passed through, after its capture
+/// groups are expanded.
+pub type Filter = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// A function that works out each replacement from the match and its
+/// capture groups, and the regex that matched, for the groups' names.
+pub type Compute = Box<dyn Fn(&Regex, &Captures) -> String + Send + Sync>;
+
+impl Replacer {
+    pub fn new(target: &str, replacement: &str) -> Result<Replacer, regex::Error> {
+        Ok(Replacer::from_regex(Regex::new(target)?, replacement))
+    }
+
+    /// A `Replacer` for a regular expression already compiled, perhaps
+    /// with a `RegexBuilder` to ignore case.
+    pub fn from_regex(regex: Regex, replacement: &str) -> Replacer {
+        Replacer::empty().rule(regex, replacement)
+    }
+
+    /// A `Replacer` built from patterns as text, compiled with flags set
+    /// on the builder.
+    pub fn builder() -> ReplacerBuilder {
+        ReplacerBuilder::default()
+    }
+
+    /// A `Replacer` with no rules yet, which replaces nothing.
+    fn empty() -> Replacer {
+        Replacer {
+            rules: Vec::new(),
+            filters: Vec::new(),
+            compute: None,
+            limit: None,
+            guards: Vec::new(),
+            columns: None,
+            pointers: None,
+        }
+    }
+
+    /// Also replace matches of `regex`, wherever no rule already added has
+    /// a match starting as early.
+    pub fn rule(mut self, regex: Regex, replacement: &str) -> Replacer {
+        self.rules.push(Rule {
+            regex,
+            replacement: Template::parse(replacement),
+        });
+        self
+    }
+
+    /// Pass each replacement through `filter`, after any filters already
+    /// added.
+    pub fn filter(mut self, filter: impl Fn(&str) -> String + Send + Sync + 'static) -> Replacer {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Put what `compute` makes of each match, and its capture groups, in
+    /// its place, rather than the rule's replacement. It's given the
+    /// rule's regex too, for the groups' names. The filters still rework
+    /// what it returns.
+    pub fn compute(
+        mut self,
+        compute: impl Fn(&Regex, &Captures) -> String + Send + Sync + 'static,
+    ) -> Replacer {
+        self.compute = Some(Box::new(compute));
+        self
+    }
+
+    /// Replace only the first `max` matches in each text, as
+    /// `str::replacen` does, and leave the rest as they are. Counting and
+    /// previewing stop there too.
+    pub fn limit(mut self, max: usize) -> Replacer {
+        self.limit = Some(max);
+        self
+    }
+
+    /// Only replace matches on lines that `regex` matches somewhere: a
+    /// match is on the line it starts on, without its newline.
+    pub fn only_lines_matching(mut self, regex: Regex) -> Replacer {
+        self.guards.push(Guard {
+            regex,
+            matching: true,
+        });
+        self
+    }
+
+    /// Leave matches on lines that `regex` matches as they are.
+    pub fn skip_lines_matching(mut self, regex: Regex) -> Replacer {
+        self.guards.push(Guard {
+            regex,
+            matching: false,
+        });
+        self
+    }
+
+    /// Append the replacement for one of `rule`'s matches to `out`.
+    fn expand(&self, rule: &Rule, captures: &Captures, out: &mut String) {
+        let mut text = match &self.compute {
+            Some(compute) => compute(&rule.regex, captures),
+            None if self.filters.is_empty() => {
+                rule.replacement.expand(captures, out);
+                return;
+            }
+            None => {
+                let mut text = String::new();
+                rule.replacement.expand(captures, &mut text);
+                text
+            }
+        };
+        for filter in &self.filters {
+            text = filter(&text);
+        }
+        out.push_str(&text);
+    }
+
+    /// `text` with every match replaced, and how many matches there were.
+    /// With none, the text comes back borrowed, unchanged.
+    pub fn replace<'t>(&self, text: &'t str) -> (Cow<'t, str>, usize) {
+        if let Some(changes) = self.preview_fields(text) {
+            let count = changes.iter().map(|c| c.replacements).sum();
+            return match count {
+                0 => (Cow::Borrowed(text), 0),
+                _ => (Cow::Owned(apply(text, &changes)), count),
+            };
+        }
+        let plain = self.filters.is_empty() && self.compute.is_none();
+        if let ([rule], true) = (self.simple(), plain) {
+            if let Some(replacement) = rule.replacement.plain() {
+                let count = self.count(text);
+                if count == 0 {
+                    return (Cow::Borrowed(text), 0);
+                }
+                return (rule.regex.replacen(text, count, replacement), count);
+            }
+        }
+        let mut replaced = String::new();
+        let mut copied = 0;
+        let mut count = 0;
+        for (rule, captures) in self.matches(text) {
+            let m = captures.get(0).unwrap();
+            replaced.push_str(&text[copied..m.start()]);
+            self.expand(rule, &captures, &mut replaced);
+            copied = m.end();
+            count += 1;
+        }
+        if count == 0 {
+            return (Cow::Borrowed(text), 0);
+        }
+        replaced.push_str(&text[copied..]);
+        (Cow::Owned(replaced), count)
+    }
+
+    /// `replace`, with what it did in more detail than how many matches
+    /// there were. Each replacement is compared with its match, so this
+    /// is a little slower.
+    pub fn replace_with_stats<'t>(&self, text: &'t str) -> (Cow<'t, str>, Stats) {
+        if let Some(changes) = self.preview_fields(text) {
+            let stats = Stats::of(&changes);
+            return match stats.matches {
+                0 => (Cow::Borrowed(text), stats),
+                _ => (Cow::Owned(apply(text, &changes)), stats),
+            };
+        }
+        let mut replaced = String::new();
+        let mut copied = 0;
+        let mut stats = Stats::default();
+        for (rule, captures) in self.matches(text) {
+            let m = captures.get(0).unwrap();
+            replaced.push_str(&text[copied..m.start()]);
+            let start = replaced.len();
+            self.expand(rule, &captures, &mut replaced);
+            stats.add(m.as_str(), &replaced[start..]);
+            copied = m.end();
+        }
+        if stats.matches == 0 {
+            return (Cow::Borrowed(text), stats);
+        }
+        replaced.push_str(&text[copied..]);
+        (Cow::Owned(replaced), stats)
+    }
+
+    /// How many matches `replace` would replace in `text`, found without
+    /// expanding or filtering any replacements.
+    pub fn count(&self, text: &str) -> usize {
+        match (&self.columns, &self.pointers) {
+            (Some(columns), _) => self.count_columns(columns, text),
+            (_, Some(pointers)) => self.count_strings(pointers, text),
+            _ => self.count_text(text),
+        }
+    }
+
+    /// `count`, of the whole text.
+    fn count_text(&self, text: &str) -> usize {
+        match self.simple() {
+            [rule] => rule.regex.find_iter(text).take(self.max()).count(),
+            _ => self.matches(text).count(),
+        }
+    }
+
+    /// The rules, if their regexes' matches are all that's replaced, up
+    /// to the limit; with guards, none, so every match goes through
+    /// `matches`.
+    fn simple(&self) -> &[Rule] {
+        match self.guards[..] {
+            [] => &self.rules,
+            _ => &[],
+        }
+    }
+
+    /// How many matches to replace in a text.
+    fn max(&self) -> usize {
+        self.limit.unwrap_or(usize::MAX)
+    }
+
+    /// Every rule's matches in `text`, in order, none overlapping, up to
+    /// the limit.
+    fn matches<'r, 't>(&'r self, text: &'t str) -> Take<Guarded<'r, 't>> {
+        self.matches_from(text, 0, None)
+    }
+
+    /// `matches`, starting at `start` rather than the beginning, as if
+    /// the last match had ended at `last_end`. What's before `start` is
+    /// still there for `^` and `\b` to look at.
+    fn matches_from<'r, 't>(
+        &'r self,
+        text: &'t str,
+        start: usize,
+        last_end: Option<usize>,
+    ) -> Take<Guarded<'r, 't>> {
+        let matches = match &self.rules[..] {
+            [rule] if start == 0 => Matches::One(rule, rule.regex.captures_iter(text)),
+            rules => Matches::Many {
+                rules,
+                text,
+                next: rules
+                    .iter()
+                    .map(|rule| search(&rule.regex, text, start, last_end))
+                    .collect(),
+                last_end,
+            },
+        };
+        Guarded {
+            matches,
+            guards: &self.guards,
+            text,
+            line: None,
+        }
+        .take(self.max())
+    }
+
+    /// The lines of `text` that replacing would change, each with what it
+    /// would become. A match spanning several lines makes one `Change` of
+    /// all of them, as do matches on neighboring lines' shared ends.
+    pub fn preview(&self, text: &str) -> Vec<Change> {
+        self.preview_fields(text)
+            .unwrap_or_else(|| self.preview_text(text))
+    }
+
+    /// `preview` of only the CSV columns or JSON strings to replace in,
+    /// if there are any.
+    fn preview_fields(&self, text: &str) -> Option<Vec<Change>> {
+        match (&self.columns, &self.pointers) {
+            (Some(columns), _) => Some(self.preview_columns(columns, text)),
+            (_, Some(pointers)) => Some(self.preview_strings(pointers, text)),
+            _ => None,
+        }
+    }
+
+    /// `preview`, of the whole text.
+    fn preview_text(&self, text: &str) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let mut current: Option<Span> = None;
+        for (rule, captures) in self.matches(text) {
+            let m = captures.get(0).unwrap();
+            let start = text[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+            // A match that takes a newline joins the next line to its own,
+            // so that line is part of the change too.
+            let end = text[m.end()..]
+                .find('\n')
+                .map_or(text.len(), |i| m.end() + i);
+            let mut span = match current.take() {
+                Some(span) if start <= span.end => span,
+                previous => {
+                    if let Some(span) = previous {
+                        changes.push(span.finish(text));
+                    }
+                    Span {
+                        start,
+                        end,
+                        copied: start,
+                        after: String::new(),
+                        replaced: Vec::new(),
+                    }
+                }
+            };
+            span.after.push_str(&text[span.copied..m.start()]);
+            let mut replacement = String::new();
+            self.expand(rule, &captures, &mut replacement);
+            span.after.push_str(&replacement);
+            span.replaced.push(Replaced {
+                range: m.start() - span.start..m.end() - span.start,
+                replacement,
+            });
+            span.copied = m.end();
+            span.end = span.end.max(end);
+            current = Some(span);
+        }
+        changes.extend(current.map(|span| span.finish(text)));
+        changes
+    }
+}
+
+impl fmt::Debug for Replacer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Replacer")
+            .field("rules", &self.rules)
+            .field("filters", &self.filters.len())
+            .field("compute", &self.compute.is_some())
+            .field("limit", &self.limit)
+            .field("guards", &self.guards)
+            .field("columns", &self.columns)
+            .field("pointers", &self.pointers)
+            .finish()
+    }
+}
+
+/// A `Replacer`'s matches. With one rule, from the start, they're the
+/// regex's own; otherwise, each rule's next match is kept, and the
+/// earliest taken.
+enum Matches<'r, 't> {
+    One(&'r Rule, CaptureMatches<'r, 't>),
+    Many {
+        rules: &'r [Rule],
+        text: &'t str,
+        /// Each rule's next match, or `None` when it has no more.
+        next: Vec<Option<Captures<'t>>>,
+        last_end: Option<usize>,
+    },
+}
+
+impl<'r, 't> Iterator for Matches<'r, 't> {
+    type Item = (&'r Rule, Captures<'t>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Matches::One(rule, captures) => Some((*rule, captures.next()?)),
+            Matches::Many {
+                rules,
+                text,
+                next,
+                last_end,
+            } => {
+                let (index, _) = next
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, c)| Some((i, c.as_ref()?.get(0).unwrap().start())))
+                    .min_by_key(|&(i, start)| (start, i))?;
+                let captures = next[index].take().unwrap();
+                let end = captures.get(0).unwrap().end();
+                *last_end = Some(end);
+                // This rule needs its next match, and the others' may
+                // overlap this one, or be empty right at its end. A rule
+                // with none left has none later.
+                for (i, (rule, next)) in rules.iter().zip(next.iter_mut()).enumerate() {
+                    let stale = match next {
+                        Some(c) => {
+                            let m = c.get(0).unwrap();
+                            m.start() < end || (m.is_empty() && m.start() == end)
+                        }
+                        None => i == index,
+                    };
+                    if stale {
+                        *next = search(&rule.regex, text, end, *last_end);
+                    }
+                }
+                Some((&rules[index], captures))
+            }
+        }
+    }
+}
+
+/// A `Replacer`'s matches on the lines its guards let through.
+struct Guarded<'r, 't> {
+    matches: Matches<'r, 't>,
+    guards: &'r [Guard],
+    text: &'t str,
+    /// The last match's line, without its newline, and whether it passed.
+    line: Option<(Range<usize>, bool)>,
+}
+
+impl<'r, 't> Iterator for Guarded<'r, 't> {
+    type Item = (&'r Rule, Captures<'t>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (rule, captures) = self.matches.next()?;
+            if self.guards.is_empty() {
+                return Some((rule, captures));
+            }
+            let start = captures.get(0).unwrap().start();
+            let passed = match &self.line {
+                // A match at a line's newline is on that line.
+                Some((line, passed)) if line.start <= start && start <= line.end => *passed,
+                _ => {
+                    let text = self.text;
+                    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+                    let line_end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+                    let line = &text[line_start..line_end];
+                    let passed = self
+                        .guards
+                        .iter()
+                        .all(|guard| guard.regex.is_match(line) == guard.matching);
+                    self.line = Some((line_start..line_end, passed));
+                    passed
+                }
+            };
+            if passed {
+                return Some((rule, captures));
+            }
+        }
+    }
+}
+
+/// `regex`'s first match in `text` from `start` on. As in the regex
+/// crate's own iterators, an empty match can't come right at the end of
+/// the last match.
+fn search<'t>(
+    regex: &Regex,
+    text: &'t str,
+    mut start: usize,
+    last_end: Option<usize>,
+) -> Option<Captures<'t>> {
+    loop {
+        let captures = regex.captures_at(text, start)?;
+        let m = captures.get(0).unwrap();
+        if !m.is_empty() || Some(m.start()) != last_end {
+            return Some(captures);
+        }
+        start = m.start() + text[m.start()..].chars().next()?.len_utf8();
+    }
+}
+
+/// Some lines a replacement would change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The first line's number, counting from 1.
+    pub line: usize,
+    /// Where the first line starts in the text, in bytes.
+    pub offset: usize,
+    /// The lines as they are, without the final newline.
+    pub before: String,
+    /// The lines as they would be.
+    pub after: String,
+    pub replacements: usize,
+    /// Each match, and what it would become, in order: `replacements` of
+    /// them.
+    pub replaced: Vec<Replaced>,
+}
+
+/// One match in a `Change`, and its replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replaced {
+    /// Where the match is in the change's `before`, in bytes.
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// What replacing a text did, or would do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// How many matches there were.
+    pub matches: usize,
+    /// How many of them were replaced with something else: a match
+    /// replaced with just what it was is a match, but changes nothing.
+    pub replacements: usize,
+    /// How many bytes of the text those replacements took out.
+    pub removed: usize,
+    /// How many bytes they put in.
+    pub added: usize,
+}
+
+impl Stats {
+    /// What `changes`, from `Replacer::preview`, would do.
+    pub fn of(changes: &[Change]) -> Stats {
+        let mut stats = Stats::default();
+        for change in changes {
+            for replaced in &change.replaced {
+                stats.add(
+                    &change.before[replaced.range.clone()],
+                    &replaced.replacement,
+                );
+            }
+        }
+        stats
+    }
+
+    /// Count a match of `before`, replaced with `after`.
+    fn add(&mut self, before: &str, after: &str) {
+        self.matches += 1;
+        if before != after {
+            self.replacements += 1;
+            self.removed += before.len();
+            self.added += after.len();
+        }
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, other: Stats) {
+        self.matches += other.matches;
+        self.replacements += other.replacements;
+        self.removed += other.removed;
+        self.added += other.added;
+    }
+}
+
+/// A `Change` being built: lines `start..end` of the text, in bytes, of
+/// which `..copied` have been replaced into `after`.
+struct Span {
+    start: usize,
+    end: usize,
+    copied: usize,
+    after: String,
+    replaced: Vec<Replaced>,
+}
+
+impl Span {
+    fn finish(mut self, text: &str) -> Change {
+        self.after.push_str(&text[self.copied..self.end]);
+        Change {
+            line: text[..self.start].matches('\n').count() + 1,
+            offset: self.start,
+            before: text[self.start..self.end].to_string(),
+            after: self.after,
+            replacements: self.replaced.len(),
+            replaced: self.replaced,
+        }
+    }
+}
@@ -1,84 +1,153 @@
-use std::{env, fs};
+// quickreplace's command line: the settings and the arguments are parsed
+// here, into `ch_02::quickreplace::Arguments`, and the library does what
+// they say. All that's left is to turn what it returns into an exit code.
 
-use colored::*;
-use regex::Regex;
+use std::env;
+use std::process::ExitCode;
 
+use ch_02::journal;
+use ch_02::quickreplace::{self, Arguments, CATALOGS};
+use common::config::Loader;
+use common::i18n;
+use common::{Error, LogFormat, LogOptions, Messages};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+mod cli;
+
+/// What the command line asks for.
 #[derive(Debug)]
-struct Arguments {
-    target: String,
-    replacement: String,
-    input_filename: String,
-    output_filename: String,
+enum Task {
+    /// Replace, count, or find.
+    Rewrite(Box<Arguments>),
+    /// Put back the changes written down in a journal.
+    Undo { journal: String },
+}
+
+/// What can be set in `~/.quickreplace.toml`, or the file given with
+/// `--config`, or a `QUICKREPLACE_...` environment variable, as well as on
+/// the command line.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Settings {
+    verbosity: u8,
+    log_format: LogFormat,
+    /// The language to speak; `None` follows the locale.
+    lang: Option<String>,
+    /// Match letters in either case, unless `--case-sensitive` says not to.
+    ignore_case: bool,
+    /// Match letters in either case in a pattern without an uppercase one,
+    /// unless `-i` or `-s` says otherwise.
+    smart_case: bool,
+    /// The suffix for backups made by `--in-place` without one of its own.
+    backup_suffix: Option<String>,
+    /// Globs for files and directories to leave alone when rewriting a
+    /// directory, as well as any `--exclude`s.
+    exclude: Vec<String>,
+    /// How many bytes a file has to have to be mapped into memory and
+    /// replaced on every CPU; `None` for `MMAP_THRESHOLD`.
+    mmap_threshold: Option<u64>,
+    /// How many bytes a compiled regex may take, unless
+    /// `--regex-size-limit` says; `None` for the regex crate's default.
+    regex_size_limit: Option<usize>,
+    /// How many bytes a regex's lazy DFA cache may take, unless
+    /// `--dfa-size-limit` says; `None` for the regex crate's default.
+    dfa_size_limit: Option<usize>,
 }
 
-impl Arguments {
-    fn parse() -> Self {
-        let args: Vec<String> = env::args().skip(1).collect();
-        if args.len() != 4 {
-            print_usage();
-            eprintln!(
-                "{} wrong number of arguments: expected 4, got {}.",
-                "Error:".red().bold(),
-                args.len()
-            );
-            std::process::exit(1);
+/// Remove `--config FILE` or `--config=FILE` from `args`, and return the
+/// last file given. Nothing after a `--` is touched.
+fn take_config(args: &mut Vec<String>) -> Result<Option<String>, Error> {
+    let mut config = None;
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            break;
         }
-        Self {
-            target: args[0].clone(),
-            replacement: args[1].clone(),
-            input_filename: args[2].clone(),
-            output_filename: args[3].clone(),
+        if let Some(path) = arg.strip_prefix("--config=") {
+            config = Some(path.to_string());
+        } else if arg == "--config" {
+            let path = args
+                .get(i + 1)
+                .ok_or_else(|| Error::usage("--config needs a file"))?;
+            config = Some(path.clone());
+            args.remove(i + 1);
+        } else {
+            i += 1;
+            continue;
         }
+        args.remove(i);
     }
+    Ok(config)
 }
 
-fn print_usage() {
-    eprintln!(
-        "{} - change occurrences of one string into another",
-        "quickreplace".green()
-    );
-    eprintln!("Usage: quickreplace <target> <replacement> <input_filename> <output_filename>");
-}
-
-fn main() {
-    let args = Arguments::parse();
-    let input_data = match fs::read_to_string(&args.input_filename) {
-        Ok(v) => v,
-        Err(e) => {
-            eprintln!(
-                "{} failed to read from file '{}': {}",
-                "Error:".red().bold(),
-                args.input_filename,
-                e
-            );
-            std::process::exit(1);
+impl Settings {
+    /// Load the settings, taking the ones given on the command line out
+    /// of `args`. The rest of the command line is left to
+    /// `Task::parse`, which uses these as its defaults.
+    fn load(args: &mut Vec<String>) -> Result<Settings, Error> {
+        let flags = LogOptions::take_from(args)?;
+        let lang = i18n::take_lang(args)?;
+        // A file named on the command line must be there, and is read
+        // instead of the one in the home directory, not as well.
+        let loader = match take_config(args)? {
+            Some(path) => Loader::new("QUICKREPLACE").file(path, true),
+            None => Loader::new("QUICKREPLACE").file("~/.quickreplace.toml", false),
+        };
+        let mut loader = loader.env();
+        if flags.verbosity > 0 {
+            loader = loader.set("verbosity", flags.verbosity.to_string());
         }
-    };
-    let replaced_data = match replace(&args.target, &args.replacement, &input_data) {
-        Ok(v) => v,
-        Err(e) => {
-            eprintln!("{} failed to replace text: {}", "Error:".red().bold(), e);
-            std::process::exit(1);
+        if let Some(format) = flags.format {
+            loader = loader.set("log_format", format.to_string());
         }
-    };
-    match fs::write(&args.output_filename, replaced_data) {
-        Ok(_) => println!(
-            "Successfully replaced text and wrote output to '{}'",
-            args.output_filename
-        ),
-        Err(e) => {
-            eprintln!(
-                "{} failed to write to file '{}': {}",
-                "Error:".red().bold(),
-                args.output_filename,
-                e
-            );
-            std::process::exit(1);
+        if let Some(lang) = lang {
+            loader = loader.set("lang", lang);
+        }
+        loader.load()
+    }
+}
+
+/// Whether the run went as asked: it can only not if nothing matched and
+/// `--fail-if-no-match` was given.
+fn quickreplace() -> Result<bool, Error> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let settings = Settings::load(&mut args)?;
+    common::init_logging(settings.verbosity, settings.log_format);
+    debug!(?settings, "loaded settings");
+    let messages = Messages::new(CATALOGS, settings.lang.as_deref());
+    debug!(lang = %messages.lang(), "chose messages");
+    let args = match Task::parse(args, &settings, &messages)? {
+        Task::Rewrite(args) => *args,
+        Task::Undo { journal } => {
+            journal::undo(&journal, &messages)?;
+            return Ok(true);
         }
     };
+    debug!(?args, "parsed arguments");
+    let found = quickreplace::run(&args, &messages)?;
+    Ok(found > 0 || !args.fail_if_no_match)
 }
 
-fn replace(target: &str, replacement: &str, text: &str) -> Result<String, regex::Error> {
-    let regex = Regex::new(target)?;
-    Ok(regex.replace_all(text, replacement).to_string())
+/// quickreplace's exit codes, as grep's are: 0 for a run that went as
+/// asked, 1 only for finding nothing, with `--fail-if-no-match`, and 2 for
+/// any failure at all, from a bad regex to a file that couldn't be read or
+/// written, leaving 1 unambiguous. An error's own code, from
+/// `common::exit`, is for the library's other callers.
+const NO_MATCH: u8 = 1;
+const FAILED: u8 = 2;
+
+fn main() -> ExitCode {
+    let mut matched = true;
+    let code = common::run(|| {
+        quickreplace()
+            .map(|ok| matched = ok)
+            .map_err(|e| e.with_code(FAILED))
+    });
+    if matched {
+        code
+    } else {
+        ExitCode::from(NO_MATCH)
+    }
 }
@@ -0,0 +1,236 @@
+// Huge files, replaced on every CPU at once. A single file of at least
+// `mmap_threshold` bytes, 64 MiB unless the settings say otherwise, isn't
+// read into a `String`: it's mapped into memory, split into sections of a
+// few megabytes, each ending after a newline, and the sections replaced on
+// a pool of threads, a batch at a time, then stitched back together in
+// order and written, with `chunked`. The whole file is there for
+// each section's search to look at, so a match can span sections, and the
+// result is exactly what replacing it all at once would have made.
+//
+// Only a plain rewrite of UTF-8 is done this way: into a file, in place, or
+// to standard output. Counting, finding, diffs, dry runs, reports,
+// journals, `--max`, which counts matches across the whole file, and
+// `--csv` and `--json`, which need the whole of it from the start, all
+// read it as before, as does a gzipped file, which can only be
+// decompressed from the start, and a file that turns out not to be valid
+// UTF-8, so that it's decoded, or refused, the way it always was. The mapping is
+// only read, and a new file written and renamed over the old one, so a
+// rewrite in place never writes to what's mapped; a file truncated by some
+// other program while it's being read is, as with any mapped file, the
+// end of the run.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::os::fd::AsRawFd;
+use std::{ptr, slice, str};
+
+use common::{tr, Context, Error, Messages};
+use rayon::prelude::*;
+use tracing::{debug, info};
+
+use crate::chunked::{self, Stitch};
+use crate::encoding::Encoding;
+use crate::gzip::{self, Writer};
+use crate::plugins::Plugins;
+use crate::progress::Progress;
+use crate::quickreplace::{print_written, write_in_place_with, Arguments, Output, STDIO};
+use crate::{atomic, Replacer};
+
+/// About how big each section is, at most and at least: big enough that
+/// a thread spends its time searching, not starting and stitching, and
+/// small enough that every thread has a few.
+const SECTION: Range<usize> = (64 << 10)..(4 << 20);
+
+/// UTF-8's byte order mark.
+const BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// Replace the matches in the input by mapping it, if it's a file big
+/// enough to be worth it and `args` ask for nothing that needs it whole,
+/// returning how many there were, or `None` if it should be read instead.
+pub fn rewrite(
+    args: &Arguments,
+    replacer: &Replacer,
+    plugins: &Plugins,
+    messages: &Messages,
+) -> Result<Option<usize>, Error> {
+    let input_name = args.input_filename.as_str();
+    let plain = matches!(args.output, Output::File(_) | Output::InPlace { .. })
+        && !args.dry_run
+        && !args.keep_changes()
+        && args.max.is_none()
+        && args.columns.is_empty()
+        && args.pointers.is_empty()
+        && matches!(args.encoding, None | Some(Encoding::Utf8));
+    // A gzipped file has to be decompressed from the start, a byte at a
+    // time.
+    if !plain || input_name == STDIO || gzip::input(args) {
+        return Ok(None);
+    }
+    let read_failed = || tr!(messages, "read-failed", file = input_name);
+    let file = File::open(input_name).with_context(read_failed)?;
+    let metadata = file.metadata().with_context(read_failed)?;
+    if !metadata.is_file() || metadata.len() < args.mmap_threshold.max(1) {
+        return Ok(None);
+    }
+    let map = match Map::new(&file, metadata.len() as usize) {
+        Ok(map) => map,
+        Err(error) => {
+            debug!(%error, file = input_name, "can't map the file; reading it instead");
+            return Ok(None);
+        }
+    };
+    let bytes = map.bytes();
+    let (bom, bytes) = match bytes.strip_prefix(BOM) {
+        Some(rest) => (BOM, rest),
+        None => (&[][..], bytes),
+    };
+
+    // `None` is a thread for each CPU.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.jobs.unwrap_or(0))
+        .build()?;
+    let threads = pool.current_num_threads();
+    let size = (bytes.len() / (threads * 4)).clamp(SECTION.start, SECTION.end);
+    let ranges = chunked::split(bytes, size);
+    // Each section ends after a newline, so between characters, and the
+    // text is UTF-8 if each section is.
+    let utf8 = pool.install(|| {
+        ranges
+            .par_iter()
+            .all(|range| str::from_utf8(&bytes[range.clone()]).is_ok())
+    });
+    if !utf8 {
+        debug!(file = input_name, "not UTF-8; reading it instead");
+        return Ok(None);
+    }
+    // SAFETY: every section of `bytes` is valid UTF-8, and they cover it.
+    let text = unsafe { str::from_utf8_unchecked(bytes) };
+    info!(
+        bytes = text.len(),
+        sections = ranges.len(),
+        threads,
+        file = input_name,
+        "mapped input"
+    );
+
+    let progress = Progress::bytes(Some(metadata.len()), args);
+    let compress = gzip::output(args);
+    let replace = |output: &mut dyn Write, name: &str| {
+        let write_failed = || {
+            if name == STDIO {
+                tr!(messages, "write-stdout-failed")
+            } else {
+                tr!(messages, "write-failed", file = name)
+            }
+        };
+        let mut output = Writer::new(output, compress);
+        output.write_all(bom).with_context(write_failed)?;
+        let mut stitch = Stitch::new(replacer, text);
+        let mut count = 0;
+        // A batch at a time, so only a few sections' replacements are in
+        // memory at once.
+        for batch in ranges.chunks(threads * 4) {
+            let sections: Vec<Result<_, Error>> = pool.install(|| {
+                batch
+                    .par_iter()
+                    .map(|range| {
+                        let section = replacer.replace_section(text, range.clone());
+                        plugins
+                            .check()
+                            .with_context(|| tr!(messages, "replace-failed"))?;
+                        Ok(section)
+                    })
+                    .collect()
+            });
+            for (range, section) in batch.iter().zip(sections) {
+                let section = stitch.next(section?);
+                plugins
+                    .check()
+                    .with_context(|| tr!(messages, "replace-failed"))?;
+                output
+                    .write_all(section.text.as_bytes())
+                    .with_context(write_failed)?;
+                count += section.count;
+                progress.advance(range.len() as u64);
+            }
+        }
+        output
+            .finish()
+            .and_then(|output| output.flush())
+            .with_context(write_failed)?;
+        Ok::<_, Error>(count)
+    };
+    let count = match &args.output {
+        Output::File(name) if name == STDIO => replace(&mut io::stdout().lock(), name)?,
+        Output::File(name) => {
+            let mut count = 0;
+            let original = Some((input_name, args.preserve));
+            atomic::write_with(name, original, messages, |file| {
+                count = replace(file, name)?;
+                Ok(())
+            })?;
+            count
+        }
+        Output::InPlace { backup_suffix } => {
+            let mut count = 0;
+            let backup_suffix = backup_suffix.as_deref();
+            write_in_place_with(input_name, backup_suffix, args.preserve, messages, |file| {
+                count = replace(file, input_name)?;
+                Ok(count > 0)
+            })?;
+            count
+        }
+        Output::Count | Output::Find | Output::Diff | Output::Highlight => {
+            unreachable!("only a plain rewrite is mapped")
+        }
+    };
+    progress.finish();
+    info!(count, "replaced mapped input");
+    print_written(args, messages);
+    Ok(Some(count))
+}
+
+/// A file mapped into memory, read-only, until it's dropped.
+#[derive(Debug)]
+struct Map {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Map {
+    /// Map the first `len` bytes of `file`, which mustn't be 0.
+    fn new(file: &File, len: usize) -> io::Result<Map> {
+        // SAFETY: a new mapping, with no address asked for, touches no
+        // memory of ours, and stays valid after `file` is closed.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Map { ptr, len })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: the mapping is `len` readable bytes until `drop` unmaps
+        // it, which can't happen while they're borrowed.
+        unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Map {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `len` are a mapping of ours, unmapped once.
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// The `--plugin`s: shared libraries, loaded with `replace_plugin`, that
+// each replacement is passed through, in the order they were given, and
+// the `--exec` command, which works each replacement out before they see
+// it.
+//
+// A `Replacer`'s filter can't fail, so when a plugin does, the replacement
+// it was given stands, and the error is kept for `check` to return once
+// the text is done, before any of it is written. The same goes for the
+// command, whose match stands, and which isn't run again on that thread
+// until `check`. Files under a directory are replaced on several threads
+// at once, so each thread's failure is kept apart, for its own `check`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, ThreadId};
+
+use common::{tr, Context, Error, Messages};
+use regex::{Captures, Regex};
+use replace_plugin::Plugin;
+use tracing::debug;
+
+use crate::exec::{self, Exec};
+use crate::Replacer;
+
+#[derive(Debug)]
+pub struct Plugins {
+    plugins: Vec<Plugin>,
+    exec: Option<Exec>,
+    /// The first failure on each thread since its last `check`.
+    failures: Mutex<HashMap<ThreadId, Failure>>,
+}
+
+/// What went wrong with a replacement.
+#[derive(Debug)]
+pub enum Failure {
+    Plugin(replace_plugin::Error),
+    Exec(exec::Error),
+}
+
+impl Plugins {
+    pub fn load(
+        paths: &[String],
+        exec: Option<&str>,
+        messages: &Messages,
+    ) -> Result<Arc<Plugins>, Error> {
+        let mut plugins = Vec::new();
+        for path in paths {
+            // Safety: running the library's code is what the user asked
+            // for by naming it.
+            let plugin = unsafe { Plugin::load(path) }
+                .with_context(|| tr!(messages, "plugin-load-failed", file = path.as_str()))?;
+            debug!(name = plugin.name(), path, "loaded plugin");
+            plugins.push(plugin);
+        }
+        Ok(Arc::new(Plugins {
+            plugins,
+            exec: exec.map(Exec::new),
+            failures: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// `replacer`, working out each replacement with the command, and
+    /// passing it through the plugins.
+    pub fn attach(self: &Arc<Self>, mut replacer: Replacer) -> Replacer {
+        if self.exec.is_some() {
+            let plugins = Arc::clone(self);
+            replacer = replacer.compute(move |regex, captures| plugins.run(regex, captures));
+        }
+        if self.plugins.is_empty() {
+            return replacer;
+        }
+        let plugins = Arc::clone(self);
+        replacer.filter(move |text| plugins.transform(text))
+    }
+
+    fn run(&self, regex: &Regex, captures: &Captures) -> String {
+        let exec = self.exec.as_ref().expect("only attached with a command");
+        // Once it's failed, the text won't be written, so there's no use
+        // running it on the rest of the matches.
+        if self.failures().contains_key(&thread::current().id()) {
+            return captures[0].to_string();
+        }
+        exec.run(regex, captures).unwrap_or_else(|e| {
+            self.fail(Failure::Exec(e));
+            captures[0].to_string()
+        })
+    }
+
+    fn transform(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for plugin in &self.plugins {
+            match plugin.transform(&text) {
+                Ok(transformed) => text = transformed,
+                Err(e) => {
+                    self.fail(Failure::Plugin(e));
+                    break;
+                }
+            }
+        }
+        text
+    }
+
+    fn failures(&self) -> MutexGuard<'_, HashMap<ThreadId, Failure>> {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Keep `failure` for this thread's `check`, unless it already has
+    /// one.
+    fn fail(&self, failure: Failure) {
+        self.failures()
+            .entry(thread::current().id())
+            .or_insert(failure);
+    }
+
+    /// The first failure on this thread since its last call, if there was
+    /// one.
+    pub fn check(&self) -> Result<(), Failure> {
+        match self.failures().remove(&thread::current().id()) {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Plugin(e) => e.fmt(f),
+            Failure::Exec(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Failure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Failure::Plugin(e) => e.source(),
+            Failure::Exec(e) => e.source(),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+// A progress bar, for a run long enough to want one: how many bytes of a
+// single file have been read, or how many of a directory's files are done.
+//
+//     [===========>                  ]  38%  1.2 GiB of 3.1 GiB
+//
+// It's drawn on standard error, over itself with a carriage return, as
+// iocopy's `--progress` is, and rubbed out when the run is done, so all
+// that's left is what would have been printed without it. It's only for a
+// person watching: if standard output or standard error isn't a terminal,
+// or the replaced text is going to standard output, or a report is, there
+// isn't one. Nor is there for a run that's over in a moment, since it
+// isn't drawn until a quarter of a second in, and then at most ten times a
+// second, however fast the bytes or files go by.
+//
+// A directory's files are done on several threads at once, so what's done
+// is counted in an atomic, and drawing is behind a lock.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::quickreplace::{Arguments, Output, STDIO};
+
+/// How long a run goes before the bar is drawn.
+const DELAY: Duration = Duration::from_millis(250);
+
+/// How long the bar goes between draws.
+const INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many characters wide the bar itself is.
+const WIDTH: usize = 30;
+
+/// How far through the input a run is.
+#[derive(Debug)]
+pub struct Progress {
+    unit: Unit,
+    /// How many there are in all, if that's known.
+    total: Option<u64>,
+    done: AtomicU64,
+    /// `None` if the bar isn't to be drawn.
+    bar: Option<Mutex<Bar>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Unit {
+    Bytes,
+    Files,
+}
+
+/// When the bar was started and last drawn, and how wide it was.
+#[derive(Debug)]
+struct Bar {
+    started: Instant,
+    drawn: Option<Instant>,
+    width: usize,
+}
+
+impl Progress {
+    /// Progress through a file of `total` bytes, or standard input, of
+    /// `None`.
+    pub fn bytes(total: Option<u64>, args: &Arguments) -> Progress {
+        Progress::new(Unit::Bytes, total, args)
+    }
+
+    /// Progress through `total` files.
+    pub fn files(total: usize, args: &Arguments) -> Progress {
+        Progress::new(Unit::Files, Some(total as u64), args)
+    }
+
+    fn new(unit: Unit, total: Option<u64>, args: &Arguments) -> Progress {
+        let shown = args.progress
+            && !args.quiet()
+            && !matches!(&args.output, Output::File(file) if file == STDIO)
+            && io::stdout().is_terminal()
+            && io::stderr().is_terminal();
+        Progress {
+            unit,
+            total,
+            done: AtomicU64::new(0),
+            bar: shown.then(|| {
+                Mutex::new(Bar {
+                    started: Instant::now(),
+                    drawn: None,
+                    width: 0,
+                })
+            }),
+        }
+    }
+
+    /// Count `n` more bytes or files done, and draw the bar, if it's time.
+    pub fn advance(&self, n: u64) {
+        let done = self.done.fetch_add(n, Ordering::Relaxed) + n;
+        let Some(bar) = &self.bar else {
+            return;
+        };
+        let mut bar = bar.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let due = match bar.drawn {
+            None => now - bar.started >= DELAY,
+            Some(drawn) => now - drawn >= INTERVAL,
+        };
+        if !due {
+            return;
+        }
+        let line = self.render(done);
+        // Rub out whatever's left of a longer line drawn before.
+        let padding = bar.width.saturating_sub(line.chars().count());
+        eprint!("\r{}{}", line, " ".repeat(padding));
+        let _ = io::stderr().flush();
+        bar.drawn = Some(now);
+        bar.width = line.chars().count();
+    }
+
+    /// Rub out the bar, if it was drawn, so what's printed next starts at
+    /// the beginning of the line.
+    pub fn finish(&self) {
+        let Some(bar) = &self.bar else {
+            return;
+        };
+        let mut bar = bar.lock().unwrap_or_else(|e| e.into_inner());
+        if bar.drawn.take().is_some() {
+            eprint!("\r{}\r", " ".repeat(bar.width));
+            let _ = io::stderr().flush();
+        }
+    }
+
+    /// The bar, with `done` of the total.
+    fn render(&self, done: u64) -> String {
+        let amount = |n| match self.unit {
+            Unit::Bytes => size(n),
+            Unit::Files => n.to_string(),
+        };
+        let Some(total) = self.total.filter(|&total| total > 0) else {
+            return amount(done);
+        };
+        let done = done.min(total);
+        let filled = (done as u128 * WIDTH as u128 / total as u128) as usize;
+        let arrow = if filled < WIDTH { ">" } else { "" };
+        let files = match self.unit {
+            Unit::Bytes => "",
+            Unit::Files => " files",
+        };
+        format!(
+            "[{}{}{}] {:>3}%  {} of {}{}",
+            "=".repeat(filled),
+            arrow,
+            " ".repeat(WIDTH - filled - arrow.len()),
+            done * 100 / total,
+            amount(done),
+            amount(total),
+            files
+        )
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// `bytes` in the largest binary unit it comes to at least one of.
+fn size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
@@ -0,0 +1,798 @@
+// quickreplace itself, for a program that has its `Arguments` some other
+// way than from a command line: `run` replaces, counts, or finds what they
+// say to, in a file, standard input, a directory, or a list of files, and
+// `journal::undo` puts back what a run wrote down. quickreplace's own
+// `main` only parses its command line into `Arguments`, calls these, and
+// turns what they return into an exit code.
+//
+// A single file is read whole, replaced, and written back all at once,
+// here, unless it's to be streamed, with `stream`, or it's big enough to
+// be mapped into memory, with `mapped`; a directory or a list of files is
+// rewritten by `tree`.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::{env, fs};
+
+use colored::*;
+use common::i18n::Catalogs;
+use common::{exit, tr, Context, Error, Messages};
+use tracing::{debug, info};
+
+use crate::atomic::{self, Preserve};
+use crate::encoding::{DecodeError, Decoded, Encoding};
+use crate::expand::{expand_env, UnsetVariable};
+use crate::journal::Journal;
+use crate::plugins::Plugins;
+use crate::progress::Progress;
+use crate::report::Report;
+use crate::rules::{self, Rule};
+use crate::source::Source;
+use crate::watch::Watch;
+use crate::{
+    diff, gzip, highlight, stream, tree, BuildError, Change, Columns, Pointers, Replacer,
+    ReplacerBuilder,
+};
+
+/// What quickreplace says, in each language it speaks.
+pub const CATALOGS: Catalogs = &[
+    ("en", include_str!("../locales/en/quickreplace.ftl")),
+    ("es", include_str!("../locales/es/quickreplace.ftl")),
+];
+
+/// What to do, and how: the command line, with the settings filling in
+/// what it leaves out.
+#[derive(Debug)]
+pub struct Arguments {
+    pub rules: Rules,
+    /// A file, `-` for standard input, or a directory, or with
+    /// `files_from`, a list of files.
+    pub input_filename: String,
+    /// Every input file, when more than one is named; otherwise empty,
+    /// and the input is `input_filename` alone.
+    pub inputs: Vec<String>,
+    /// With `--files-from`, what ends each name in the list: a newline,
+    /// or with `-0`, a NUL.
+    pub files_from: Option<u8>,
+    pub output: Output,
+    /// With a directory as input, which files under it to rewrite.
+    pub globs: Vec<String>,
+    /// With a directory as input, which files and directories under it to
+    /// leave alone.
+    pub exclude: Vec<String>,
+    /// With a directory as input, rewrite what `.gitignore` and `.ignore`
+    /// files say to leave out, too.
+    pub no_ignore: bool,
+    /// Show what would change instead of writing anything.
+    pub dry_run: bool,
+    /// Match letters in either case.
+    pub ignore_case: bool,
+    /// Match letters in either case in a pattern without an uppercase one.
+    pub smart_case: bool,
+    /// Take the target, and the replacement, literally.
+    pub fixed_strings: bool,
+    /// Only match whole words.
+    pub whole_words: bool,
+    /// Put environment variables into the replacements.
+    pub expand_env: bool,
+    /// Let `^` and `$` match at the start and end of each line.
+    pub multiline: bool,
+    /// Let `.` match a newline.
+    pub dotall: bool,
+    /// Read, replace, and write a line at a time.
+    pub stream: bool,
+    /// The input, and the output, are gzipped, whatever they're named.
+    pub compress: bool,
+    /// Replace again each time the input changes.
+    pub watch: bool,
+    /// Show a progress bar, if standard output and standard error are
+    /// terminals.
+    pub progress: bool,
+    /// How many files under a directory to replace at once; `None` for
+    /// one per CPU.
+    pub jobs: Option<usize>,
+    /// Shared libraries to pass each replacement through.
+    pub plugins: Vec<String>,
+    /// A command to work out each replacement, instead of the replacement
+    /// given.
+    pub exec: Option<String>,
+    /// With `--csv`, the columns to replace in; otherwise empty.
+    pub columns: Vec<String>,
+    /// With `--json`, the pointers to the strings to replace in;
+    /// otherwise empty.
+    pub pointers: Vec<String>,
+    /// Only replace matches on lines matching each of these regexes.
+    pub only_lines_matching: Vec<String>,
+    /// Leave matches on lines matching any of these regexes alone.
+    pub skip_lines_matching: Vec<String>,
+    /// Replace only the first this many matches in each file.
+    pub max: Option<usize>,
+    /// Exit with `NO_MATCH` if nothing matched.
+    pub fail_if_no_match: bool,
+    /// What the input is encoded in, and the output is encoded back in;
+    /// `None` to tell from each file's bytes.
+    pub encoding: Option<Encoding>,
+    /// Where to write a JSON report of the replacements, if anywhere: a
+    /// file, or `-` for standard output.
+    pub report: Option<String>,
+    /// Where to write down the changes made in place, for `undo`.
+    pub journal: Option<String>,
+    /// After rewriting a directory or a list of files, print a table of
+    /// what was done to each.
+    pub stats: bool,
+    /// What to carry over from each file rewritten or copied.
+    pub preserve: Preserve,
+    /// How big a single file has to be to be mapped into memory and
+    /// replaced on several threads, rather than read.
+    pub mmap_threshold: u64,
+    /// The most memory each compiled regex may take, in bytes; `None` for
+    /// the regex crate's default.
+    pub regex_size_limit: Option<usize>,
+    /// The most memory each regex's lazy DFA cache may take, in bytes;
+    /// `None` for the regex crate's default.
+    pub dfa_size_limit: Option<usize>,
+}
+
+impl Arguments {
+    /// Whether the report takes standard output, so the usual messages
+    /// aren't printed there.
+    pub fn quiet(&self) -> bool {
+        self.report.as_deref() == Some(STDIO)
+    }
+
+    /// Whether each file's changes are needed, for a report or a journal,
+    /// so they're found first and then made.
+    pub fn keep_changes(&self) -> bool {
+        self.report.is_some() || self.journal.is_some()
+    }
+}
+
+/// What to replace, and with what.
+#[derive(Debug)]
+pub enum Rules {
+    /// The `<target> <replacement>` on the command line, or for `count`
+    /// and `find`, just the target.
+    Given(Rule),
+    /// The rules in each `--rules` file, in order.
+    Files(Vec<String>),
+}
+
+/// Where the replaced text goes.
+#[derive(Debug)]
+pub enum Output {
+    /// A file, or `-` for standard output.
+    File(String),
+    /// Back into the input file, first copying the original to the
+    /// input's name plus `backup_suffix`, if there is one.
+    InPlace { backup_suffix: Option<String> },
+    /// Nowhere: the matches are only counted.
+    Count,
+    /// Nowhere: the changes are shown as a unified diff.
+    Diff,
+    /// Nowhere: the lines with matches are shown.
+    Find,
+    /// Nowhere: the changes are shown in color among the lines around
+    /// them.
+    Highlight,
+}
+
+/// Replace, count, or find the matches, as `args` say, returning how many
+/// there were. With `args.watch`, this goes on replacing each time the
+/// input changes, and only returns if it can't watch it any longer.
+pub fn run(args: &Arguments, messages: &Messages) -> Result<usize, Error> {
+    let plugins = Plugins::load(&args.plugins, args.exec.as_deref(), messages)?;
+    let replacer = build(args, messages)?;
+    let replacer = plugins.attach(replacer);
+    let source = Source::of(args);
+    let many = !matches!(source, Source::File(_));
+    if many && args.stream {
+        return Err(Error::usage(tr!(messages, "stream-dir")));
+    } else if many && args.compress {
+        return Err(Error::usage(tr!(messages, "compress-file")));
+    } else if !many && args.stats {
+        return Err(Error::usage(tr!(messages, "stats-many")));
+    } else if !matches!(source, Source::Dir(_)) && !args.globs.is_empty() {
+        return Err(Error::usage(tr!(messages, "glob-not-dir")));
+    }
+    let run = || {
+        if many {
+            tree::rewrite(args, source, &replacer, &plugins, messages)
+        } else if args.stream {
+            stream::rewrite(args, &replacer, &plugins, messages)
+        } else {
+            #[cfg(unix)]
+            if let Some(count) = crate::mapped::rewrite(args, &replacer, &plugins, messages)? {
+                return Ok(count);
+            }
+            rewrite(args, &replacer, &plugins, messages)
+        }
+    };
+    if args.watch {
+        if many || args.input_filename == STDIO {
+            return Err(Error::usage(tr!(messages, "watch-file")));
+        }
+        return Err(watch(&args.input_filename, run, messages));
+    }
+    let found = run()?;
+    debug!(found, "done");
+    Ok(found)
+}
+
+/// Print the lines of `file` that would change, as they are and as they
+/// would be, like a diff: `-` before, in red, and `+` after, in green.
+pub fn print_preview(file: &str, changes: &[Change]) {
+    for change in changes {
+        println!("{}:{}", file, change.line);
+        for line in change.before.split('\n') {
+            println!("{}", format!("- {}", line).red());
+        }
+        for line in change.after.split('\n') {
+            println!("{}", format!("+ {}", line).green());
+        }
+    }
+}
+
+/// Print the lines of `file` with a match, each after the file's name and
+/// its number, as `grep -n` does with several files.
+pub fn print_found(file: &str, changes: &[Change]) {
+    for change in changes {
+        for (i, line) in change.before.split('\n').enumerate() {
+            println!("{}:{}:{}", file, change.line + i, line);
+        }
+    }
+}
+
+/// A builder for the `Replacer` the flags ask for, with the
+/// `--only-lines-matching` and `--skip-lines-matching` regexes, the
+/// `--max` and size limits, and the `--csv` columns or `--json` pointers,
+/// but no patterns yet.
+fn builder(args: &Arguments) -> ReplacerBuilder {
+    let mut builder = Replacer::builder()
+        .case_insensitive(args.ignore_case)
+        .smart_case(args.smart_case)
+        .multi_line(args.multiline)
+        .dot_matches_new_line(args.dotall)
+        .fixed_strings(args.fixed_strings)
+        .whole_words(args.whole_words);
+    for pattern in &args.only_lines_matching {
+        builder = builder.only_lines_matching(pattern);
+    }
+    for pattern in &args.skip_lines_matching {
+        builder = builder.skip_lines_matching(pattern);
+    }
+    if let Some(max) = args.max {
+        builder = builder.limit(max);
+    }
+    if let Some(bytes) = args.regex_size_limit {
+        builder = builder.size_limit(bytes);
+    }
+    if let Some(bytes) = args.dfa_size_limit {
+        builder = builder.dfa_size_limit(bytes);
+    }
+    if !args.columns.is_empty() {
+        builder = builder.columns(Columns::new(&args.columns));
+    }
+    if !args.pointers.is_empty() {
+        builder = builder.pointers(pointers(args));
+    }
+    builder
+}
+
+fn pointers(args: &Arguments) -> Pointers {
+    Pointers::parse(&args.pointers).expect("clap checked them")
+}
+
+/// Whether `text`, from `file`, is what `--csv` or `--json` need: CSV
+/// with a header with every column in it, or JSON.
+pub fn check_fields(
+    args: &Arguments,
+    text: &str,
+    file: &str,
+    messages: &Messages,
+) -> Result<(), Error> {
+    if !args.pointers.is_empty() {
+        pointers(args)
+            .find(text)
+            .map_err(|e| Error::from(e).with_code(exit::DATA_ERR))
+            .with_context(|| tr!(messages, "json-invalid", file = file))?;
+    }
+    if args.columns.is_empty() {
+        return Ok(());
+    }
+    Columns::new(&args.columns).find(text).map_err(|missing| {
+        let message = tr!(
+            messages,
+            "csv-column-missing",
+            file = file,
+            column = missing.column.as_str(),
+            columns = missing.header.join(",")
+        );
+        Error::new(message).with_code(exit::DATA_ERR)
+    })?;
+    Ok(())
+}
+
+/// The `Replacer` for `args`' rules: the target and replacement given, or
+/// every rule in the `--rules` files, in order.
+pub fn build(args: &Arguments, messages: &Messages) -> Result<Replacer, Error> {
+    let mut builder = builder(args);
+    let replacement = |replacement: &str| -> Result<String, Error> {
+        if !args.expand_env {
+            return Ok(replacement.to_string());
+        }
+        expand_env(replacement, !args.fixed_strings, |name| env::var(name).ok())
+            .map_err(|UnsetVariable(name)| Error::usage(tr!(messages, "expand-unset", name = name)))
+    };
+    // The file each rule came from, and its number there, for an error.
+    let mut origins = Vec::new();
+    match &args.rules {
+        Rules::Given(rule) => {
+            builder = builder
+                .pattern(&rule.pattern)
+                .replacement(replacement(&rule.replacement)?);
+        }
+        Rules::Files(files) => {
+            for file in files {
+                let rules = rules::load(file, messages)?;
+                for (index, rule) in rules.rule.into_iter().enumerate() {
+                    builder = builder
+                        .pattern(rule.pattern)
+                        .replacement(replacement(&rule.replacement)?);
+                    origins.push((file.as_str(), index + 1));
+                }
+            }
+        }
+    }
+    builder.build().or_else(|error| match error {
+        BuildError::Pattern { index, error } => {
+            let error = invalid_pattern(error, messages);
+            match origins.get(index) {
+                Some(&(file, number)) => Err(error.with_code(exit::CONFIG))
+                    .with_context(|| tr!(messages, "rule-invalid", number = number, file = file)),
+                None => Err(error).with_context(|| tr!(messages, "replace-failed")),
+            }
+        }
+        BuildError::Group { index, group } => {
+            let error = Error::new(missing_group(&group, messages));
+            match origins.get(index) {
+                Some(&(file, number)) => Err(error.with_code(exit::CONFIG)).with_context(|| {
+                    tr!(
+                        messages,
+                        "rule-replacement-invalid",
+                        number = number,
+                        file = file
+                    )
+                }),
+                None => Err(error.with_code(exit::USAGE)),
+            }
+        }
+        BuildError::LinePattern { pattern, error } => Err(invalid_pattern(error, messages))
+            .with_context(|| tr!(messages, "guard-invalid", pattern = pattern.as_str())),
+        // `Task::parse` always gives a target, or rules files, and each
+        // has a rule.
+        BuildError::NoPattern => unreachable!("no rules"),
+    })
+}
+
+/// What to say about a pattern the regex crate wouldn't compile. One too
+/// big for the size limit is named as such, with what to do about it,
+/// since the regex crate's own message doesn't say.
+fn invalid_pattern(error: regex::Error, messages: &Messages) -> Error {
+    match error {
+        regex::Error::CompiledTooBig(limit) => {
+            let limit = show_size(limit);
+            Error::new(tr!(messages, "regex-too-big", limit = limit.as_str()))
+        }
+        error => Error::from(error),
+    }
+}
+
+/// What to say about a replacement's reference to `group`, which its
+/// pattern doesn't have. One like `$1st` is a group named `1st`, to the
+/// regex crate, when group 1 and then `st` was meant.
+fn missing_group(group: &str, messages: &Messages) -> String {
+    let digits = group.len() - group.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits == group.len() {
+        return tr!(messages, "group-missing", group = group);
+    }
+    let braced = format!("${{{}}}{}", &group[..digits], &group[digits..]);
+    tr!(
+        messages,
+        "group-missing-braces",
+        group = group,
+        braced = braced.as_str()
+    )
+}
+
+/// The name `-` stands for standard input or output, as it does for most
+/// Unix tools, so quickreplace can sit in a pipeline.
+pub const STDIO: &str = "-";
+
+/// `bytes` as text in `encoding`, or if that's `None`, in the encoding
+/// they seem to be in.
+pub fn decode(bytes: &[u8], encoding: Option<Encoding>) -> Result<Decoded, DecodeError> {
+    let encoding = encoding.unwrap_or_else(|| Encoding::detect(bytes));
+    encoding.decode(bytes)
+}
+
+/// `text`, to be written to `file`, in the encoding `input` was in.
+pub fn encode(
+    input: &Decoded,
+    text: &str,
+    file: &str,
+    messages: &Messages,
+) -> Result<Vec<u8>, Error> {
+    input
+        .encode(text)
+        .map_err(|e| Error::from(e).with_code(exit::DATA_ERR))
+        .with_context(|| tr!(messages, "write-failed", file = file))
+}
+
+/// How many bytes are read at a time, so progress can be shown between.
+const CHUNK: u64 = 1 << 20;
+
+/// How big a single file has to be to be mapped into memory, unless the
+/// settings say otherwise.
+pub const MMAP_THRESHOLD: u64 = 64 << 20;
+
+fn read_input(
+    filename: &str,
+    encoding: Option<Encoding>,
+    gzip: bool,
+    progress: &Progress,
+    messages: &Messages,
+) -> Result<Decoded, Error> {
+    let read_failed = || {
+        if filename == STDIO {
+            tr!(messages, "read-stdin-failed")
+        } else {
+            tr!(messages, "read-failed", file = filename)
+        }
+    };
+    let input: Box<dyn Read> = if filename == STDIO {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(File::open(filename).with_context(read_failed)?)
+    };
+    let mut input = gzip::reader(input, gzip);
+    let mut bytes = Vec::with_capacity(file_size(filename).unwrap_or(0) as usize);
+    loop {
+        let read = (&mut input)
+            .take(CHUNK)
+            .read_to_end(&mut bytes)
+            .with_context(read_failed)?;
+        if read == 0 {
+            break;
+        }
+        progress.advance(read as u64);
+    }
+    let input = decode(&bytes, encoding)
+        .map_err(|e| Error::from(e).with_code(exit::DATA_ERR))
+        .with_context(read_failed)?;
+    debug!(encoding = %input.encoding, bom = input.bom, "decoded input");
+    Ok(input)
+}
+
+/// `bytes`, to be written to `file`, gzipped if `args` say the output is
+/// to be.
+fn compress(
+    args: &Arguments,
+    bytes: Vec<u8>,
+    file: &str,
+    messages: &Messages,
+) -> Result<Vec<u8>, Error> {
+    if !gzip::output(args) {
+        return Ok(bytes);
+    }
+    gzip::compress(&bytes).with_context(|| {
+        if file == STDIO {
+            tr!(messages, "write-stdout-failed")
+        } else {
+            tr!(messages, "write-failed", file = file)
+        }
+    })
+}
+
+/// How big the file `filename` is, or `None` for standard input.
+pub fn file_size(filename: &str) -> Option<u64> {
+    if filename == STDIO {
+        return None;
+    }
+    fs::metadata(filename).ok().map(|metadata| metadata.len())
+}
+
+/// Write `bytes` to `filename`, all at once or not at all, made from
+/// `original`, or to standard output for `-`.
+pub fn write_output(
+    filename: &str,
+    bytes: &[u8],
+    original: Option<(&str, Preserve)>,
+    messages: &Messages,
+) -> Result<(), Error> {
+    if filename == STDIO {
+        io::stdout()
+            .write_all(bytes)
+            .with_context(|| tr!(messages, "write-stdout-failed"))
+    } else {
+        atomic::write(filename, bytes, original, messages)
+    }
+}
+
+/// Replace `filename`'s contents with `bytes`, with `atomic::write`, so a
+/// failure partway leaves the original as it was, and never half-written.
+pub fn write_in_place(
+    filename: &str,
+    bytes: &[u8],
+    backup_suffix: Option<&str>,
+    preserve: Preserve,
+    messages: &Messages,
+) -> Result<(), Error> {
+    write_in_place_with(filename, backup_suffix, preserve, messages, |file| {
+        file.write_all(bytes)
+            .with_context(|| tr!(messages, "write-failed", file = filename))?;
+        Ok(true)
+    })
+    .map(drop)
+}
+
+/// `write_in_place`, with `write` writing the new contents to the new
+/// file, so that they needn't all be in memory at once, and saying whether
+/// to keep them: a file with nothing replaced is better left as it was.
+/// The backup is only made of a file that's replaced. Returns whether it
+/// was.
+pub fn write_in_place_with(
+    filename: &str,
+    backup_suffix: Option<&str>,
+    preserve: Preserve,
+    messages: &Messages,
+    write: impl FnOnce(&mut File) -> Result<bool, Error>,
+) -> Result<bool, Error> {
+    atomic::write_if(filename, Some((filename, preserve)), messages, |file| {
+        if !write(file)? {
+            return Ok(false);
+        }
+        // The original is still there, until the new file is renamed
+        // over it.
+        if let Some(suffix) = backup_suffix {
+            let backup = format!("{}{}", filename, suffix);
+            fs::copy(filename, &backup).with_context(|| {
+                tr!(
+                    messages,
+                    "backup-failed",
+                    file = filename,
+                    backup = backup.as_str()
+                )
+            })?;
+        }
+        Ok(true)
+    })
+}
+
+/// Say where the replaced text went, unless it was standard output, where
+/// the message would end up mixed into the text.
+pub fn print_written(args: &Arguments, messages: &Messages) {
+    if args.quiet() {
+        return;
+    }
+    match &args.output {
+        Output::File(filename) if filename == STDIO => {}
+        Output::Count | Output::Diff | Output::Find | Output::Highlight => {}
+        Output::File(filename) => println!(
+            "{}",
+            tr!(messages, "wrote-output", file = filename.as_str())
+        ),
+        Output::InPlace {
+            backup_suffix: Some(suffix),
+        } => {
+            let file = args.input_filename.as_str();
+            println!(
+                "{}",
+                tr!(
+                    messages,
+                    "replaced-in-place-backup",
+                    file = file,
+                    backup = format!("{}{}", file, suffix)
+                )
+            )
+        }
+        Output::InPlace {
+            backup_suffix: None,
+        } => println!(
+            "{}",
+            tr!(
+                messages,
+                "replaced-in-place",
+                file = args.input_filename.as_str()
+            )
+        ),
+    }
+}
+
+/// Run `run`, and again each time `file` changes, until interrupted,
+/// returning only if the file can't be watched. A failed run is reported,
+/// and the next change tried all the same, since it may well be what
+/// fixes it.
+fn watch(file: &str, run: impl Fn() -> Result<usize, Error>, messages: &Messages) -> Error {
+    let mut watch = Watch::new(file);
+    eprintln!("{}", tr!(messages, "watching", file = file));
+    loop {
+        if let Err(error) = run() {
+            common::report(&error);
+        }
+        let waited = watch.wait();
+        if let Err(error) = waited.with_context(|| tr!(messages, "watch-failed", file = file)) {
+            return error;
+        }
+        info!(file, "changed; replacing again");
+    }
+}
+
+/// Replace the matches in one file, or standard input, as `args` say,
+/// returning how many there were.
+fn rewrite(
+    args: &Arguments,
+    replacer: &Replacer,
+    plugins: &Plugins,
+    messages: &Messages,
+) -> Result<usize, Error> {
+    // What's counted is what's decompressed, of a size there's no telling
+    // from the file's.
+    let gzipped = gzip::input(args);
+    let size = file_size(&args.input_filename).filter(|_| !gzipped);
+    let progress = Progress::bytes(size, args);
+    let input = read_input(
+        &args.input_filename,
+        args.encoding,
+        gzipped,
+        &progress,
+        messages,
+    )?;
+    progress.finish();
+    let input_data = input.text.as_str();
+    info!(bytes = input_data.len(), file = %args.input_filename, "read input");
+    check_fields(args, input_data, &args.input_filename, messages)?;
+    if let Output::Count = args.output {
+        let count = replacer.count(input_data);
+        info!(count, "counted");
+        println!(
+            "{}",
+            tr!(
+                messages,
+                "counted",
+                file = args.input_filename.as_str(),
+                count = count
+            )
+        );
+        return Ok(count);
+    }
+    if let Output::Find = args.output {
+        let changes = replacer.preview(input_data);
+        info!(changes = changes.len(), "found");
+        print_found(&args.input_filename, &changes);
+        return Ok(changes.len());
+    }
+    if let Output::Highlight = args.output {
+        let changes = replacer.preview(input_data);
+        plugins
+            .check()
+            .with_context(|| tr!(messages, "replace-failed"))?;
+        let file = args.input_filename.as_str();
+        print!("{}", highlight::render(file, input_data, &changes));
+        return Ok(changes.iter().map(|c| c.replacements).sum());
+    }
+    if args.dry_run {
+        let changes = replacer.preview(input_data);
+        plugins
+            .check()
+            .with_context(|| tr!(messages, "replace-failed"))?;
+        let count: usize = changes.iter().map(|c| c.replacements).sum();
+        info!(count, changes = changes.len(), "dry run");
+        if !args.quiet() {
+            print_preview(&args.input_filename, &changes);
+            println!("{}", tr!(messages, "dry-run-summary", count = count));
+        }
+        report(args, &changes, messages)?;
+        return Ok(count);
+    }
+    // For a report or a journal, the changes are found first, and then
+    // made, so each replacement is only expanded once.
+    let (replaced_data, count, changes) = if args.keep_changes() {
+        let changes = replacer.preview(input_data);
+        let count = changes.iter().map(|c| c.replacements).sum();
+        (
+            Cow::Owned(crate::apply(input_data, &changes)),
+            count,
+            changes,
+        )
+    } else {
+        let (replaced_data, count) = replacer.replace(input_data);
+        (replaced_data, count, Vec::new())
+    };
+    plugins
+        .check()
+        .with_context(|| tr!(messages, "replace-failed"))?;
+    match &args.output {
+        Output::Diff => {
+            let file = args.input_filename.as_str();
+            print!("{}", diff::unified(file, input_data, &replaced_data));
+        }
+        Output::File(filename) => {
+            let bytes = encode(&input, &replaced_data, filename, messages)?;
+            let bytes = compress(args, bytes, filename, messages)?;
+            info!(bytes = bytes.len(), file = %filename, "writing output");
+            let original = Some(args.input_filename.as_str()).filter(|file| *file != STDIO);
+            write_output(
+                filename,
+                &bytes,
+                original.map(|file| (file, args.preserve)),
+                messages,
+            )?;
+        }
+        // With nothing to replace, the file is left as it was, rather
+        // than written again the same.
+        Output::InPlace { .. } if count == 0 => info!("nothing to replace in place"),
+        Output::InPlace { backup_suffix } => {
+            let file = args.input_filename.as_str();
+            let bytes = encode(&input, &replaced_data, file, messages)?;
+            let bytes = compress(args, bytes, file, messages)?;
+            info!(bytes = bytes.len(), file, "rewriting in place");
+            write_in_place(
+                file,
+                &bytes,
+                backup_suffix.as_deref(),
+                args.preserve,
+                messages,
+            )?;
+            if let Some(journal_file) = &args.journal {
+                let mut journal = Journal::default();
+                journal.add(file, input.encoding, gzipped, &changes, messages)?;
+                journal.write(journal_file, messages)?;
+            }
+        }
+        Output::Count | Output::Find | Output::Highlight => {
+            unreachable!("counted, found, or highlighted above")
+        }
+    }
+    report(args, &changes, messages)?;
+    print_written(args, messages);
+    Ok(count)
+}
+
+/// Write a report of `changes` to the input, if one was asked for.
+pub fn report(args: &Arguments, changes: &[Change], messages: &Messages) -> Result<(), Error> {
+    if let Some(file) = &args.report {
+        let mut report = Report::default();
+        report.add(&args.input_filename, changes);
+        report.write(file, messages)?;
+    }
+    Ok(())
+}
+
+/// The units a size can have after its number, and how many bytes each is.
+const UNITS: [(&str, usize); 3] = [("G", 1 << 30), ("M", 1 << 20), ("K", 1 << 10)];
+
+/// A size, like `10M`, in bytes.
+pub fn parse_size(value: &str) -> Result<usize, String> {
+    let upper = value.trim().to_ascii_uppercase();
+    let number = upper.strip_suffix("IB").unwrap_or(&upper);
+    let (number, unit) = UNITS
+        .iter()
+        .find_map(|&(suffix, unit)| Some((number.strip_suffix(suffix)?, unit)))
+        .unwrap_or((number, 1));
+    number
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit))
+        .ok_or_else(|| format!("'{}' isn't a size, like 512K or 10M", value))
+}
+
+/// `bytes` as a size, in the biggest unit it's a whole number of.
+pub fn show_size(bytes: usize) -> String {
+    UNITS
+        .iter()
+        .find(|&&(_, unit)| bytes >= unit && bytes.is_multiple_of(unit))
+        .map_or(bytes.to_string(), |(suffix, unit)| {
+            format!("{}{}iB", bytes / unit, suffix)
+        })
+}
@@ -0,0 +1,78 @@
+// `--report json`: every replacement made, or with `--dry-run` that would
+// be, as JSON for another program to read, rather than as messages for a
+// person. Each file with a replacement has its name, how many it had, and
+// for each, its line and column, counted from 1, the column in bytes as
+// ripgrep's `--column` counts it, the text matched, and what went in its
+// place:
+//
+//     {"files":[{"file":"notes.txt","count":1,"replacements":[
+//       {"line":3,"column":5,"matched":"colour","replacement":"hue"}]}],
+//      "count":1}
+//
+// The report goes to standard output, instead of the usual messages, or
+// with `--report-file`, to a file, and the messages are printed as ever.
+
+use common::{Error, Messages};
+use serde::Serialize;
+
+use crate::quickreplace::write_output;
+use crate::Change;
+
+/// The replacements in each file, in the order the files were done.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    files: Vec<FileReport>,
+    /// Replacements in all the files.
+    count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct FileReport {
+    file: String,
+    count: usize,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Debug, Serialize)]
+struct Replacement {
+    line: usize,
+    column: usize,
+    matched: String,
+    replacement: String,
+}
+
+impl Report {
+    /// Add `changes` to `file`, if there are any.
+    pub fn add(&mut self, file: &str, changes: &[Change]) {
+        let mut replacements = Vec::new();
+        for change in changes {
+            for replaced in &change.replaced {
+                let before = &change.before[..replaced.range.start];
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                replacements.push(Replacement {
+                    line: change.line + before.matches('\n').count(),
+                    column: replaced.range.start - line_start + 1,
+                    matched: change.before[replaced.range.clone()].to_string(),
+                    replacement: replaced.replacement.clone(),
+                });
+            }
+        }
+        if replacements.is_empty() {
+            return;
+        }
+        self.count += replacements.len();
+        self.files.push(FileReport {
+            file: file.to_string(),
+            count: replacements.len(),
+            replacements,
+        });
+    }
+
+    /// Write the report to `file`, or standard output for `-`, as a line
+    /// of JSON.
+    pub fn write(&self, file: &str, messages: &Messages) -> Result<(), Error> {
+        let mut json = serde_json::to_string(self).expect("a report is all strings and numbers");
+        json.push('\n');
+        write_output(file, json.as_bytes(), None, messages)
+    }
+}
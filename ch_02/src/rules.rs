@@ -0,0 +1,60 @@
+// Rules files, for `--rules`: a TOML file of patterns, each with its
+// replacement, made in order in a single pass over the text.
+//
+//     [[rule]]
+//     pattern = 'colou?r'
+//     replacement = 'hue'
+//
+// A rules file is written by hand, and read from wherever it's named, so
+// `RulesFile::parse` takes any text at all, and rejects what isn't rules
+// with an error, never a panic.
+
+use std::fs;
+
+use common::{exit, tr, Context, Error, Messages};
+use serde::Deserialize;
+use tracing::debug;
+
+/// A pattern and its replacement, as written in a rules file:
+///
+/// ```toml
+/// [[rule]]
+/// pattern = 'colou?r'
+/// replacement = 'hue'
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+/// The rules in a rules file, in order.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFile {
+    #[serde(default)]
+    pub rule: Vec<Rule>,
+}
+
+impl RulesFile {
+    /// The rules in `text`, which may have none.
+    pub fn parse(text: &str) -> Result<RulesFile, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
+
+/// The rules in `file`. A file that isn't rules, or has none, is a
+/// configuration error.
+pub fn load(file: &str, messages: &Messages) -> Result<RulesFile, Error> {
+    let text =
+        fs::read_to_string(file).with_context(|| tr!(messages, "read-failed", file = file))?;
+    let rules = RulesFile::parse(&text)
+        .map_err(|e| Error::from(e).with_code(exit::CONFIG))
+        .with_context(|| tr!(messages, "rules-invalid", file = file))?;
+    if rules.rule.is_empty() {
+        return Err(Error::new(tr!(messages, "rules-empty", file = file)).with_code(exit::CONFIG));
+    }
+    debug!(file, count = rules.rule.len(), "loaded rules");
+    Ok(rules)
+}
@@ -0,0 +1,119 @@
+// Where the text to rewrite comes from: a single file, or standard input;
+// every file under a directory; several files named on the command line;
+// or, with `--files-from LIST`, every file named in a list, for a set of
+// files some other program has picked out:
+//
+//     find . -name '*.rs' -newer Cargo.lock -print0 |
+//         quickreplace --files-from - -0 old new --in-place
+//
+// The list has a name on each line, or with `-0`, a NUL after each, as
+// `find -print0`, `fd -0`, and `git ls-files -z` write them, so a name can
+// have any byte in it but a NUL, as names can. A name listed twice is only
+// rewritten once, and blank entries are skipped, since a list ends with a
+// separator more often than not, and the same goes for files named on the
+// command line. Lists, directories, and several files are all rewritten by
+// `tree`, which only needs to know which files to do.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use common::{tr, Context, Error, Messages};
+use tracing::debug;
+
+use crate::quickreplace::{Arguments, STDIO};
+
+/// What ends each name in a list without `-0`.
+pub const NEWLINE: u8 = b'\n';
+
+/// What ends each name in a list with `-0`.
+pub const NUL: u8 = 0;
+
+/// Where the text to rewrite comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source<'a> {
+    /// A file, or `-` for standard input.
+    File(&'a str),
+    /// Every file under a directory.
+    Dir(&'a str),
+    /// Several files, named on the command line.
+    Files(&'a [String]),
+    /// Every file named in a file, or on standard input, each ended by
+    /// `separator`.
+    List { list: &'a str, separator: u8 },
+}
+
+impl Source<'_> {
+    /// Where `args` say the text comes from.
+    pub fn of(args: &Arguments) -> Source<'_> {
+        let input = args.input_filename.as_str();
+        match args.files_from {
+            None if !args.inputs.is_empty() => Source::Files(&args.inputs),
+            Some(separator) => Source::List {
+                list: input,
+                separator,
+            },
+            None if input != STDIO && Path::new(input).is_dir() => Source::Dir(input),
+            None => Source::File(input),
+        }
+    }
+}
+
+/// The files named in `list`, or standard input, in order, each once.
+pub fn read_list(list: &str, separator: u8, messages: &Messages) -> Result<Vec<PathBuf>, Error> {
+    let read_failed = || {
+        if list == STDIO {
+            tr!(messages, "read-stdin-failed")
+        } else {
+            tr!(messages, "read-failed", file = list)
+        }
+    };
+    let mut bytes = Vec::new();
+    if list == STDIO {
+        io::stdin().lock().read_to_end(&mut bytes)
+    } else {
+        fs::File::open(list).and_then(|mut file| file.read_to_end(&mut bytes))
+    }
+    .with_context(read_failed)?;
+    let mut seen = HashSet::new();
+    let paths: Vec<PathBuf> = bytes
+        .split(|&b| b == separator)
+        // A list written on Windows has a carriage return before each
+        // newline.
+        .map(|name| match separator {
+            NEWLINE => name.strip_suffix(b"\r").unwrap_or(name),
+            _ => name,
+        })
+        .filter(|name| !name.is_empty())
+        .map(path)
+        .filter(|path| seen.insert(path.clone()))
+        .collect();
+    debug!(list, files = paths.len(), "read file list");
+    Ok(paths)
+}
+
+/// The files named in `files`, in order, each once.
+pub fn named(files: &[String]) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    files
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| seen.insert(path.clone()))
+        .collect()
+}
+
+/// A name from a list as a path. Anywhere but Unix, a name has to be
+/// UTF-8.
+fn path(name: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(OsStr::from_bytes(name))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(name).into_owned())
+    }
+}
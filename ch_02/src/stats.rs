@@ -0,0 +1,91 @@
+// `--stats`: after a directory or a list of files is rewritten, a table of
+// what happened to each file that had a match, and the totals:
+//
+//     File            Matches  Replacements  Bytes changed     Time
+//     -------------------------------------------------------------
+//     src/a.txt             3             2        -12 +10  923.6µs
+//     src/b.txt             1             0          -0 +0  293.2µs
+//     Total, 2 files        4             2        -12 +10    1.9ms
+//
+// A match replaced with just what it was is counted as a match, but not as
+// a replacement. Bytes changed are what the replacements took out and put
+// in. Each file's time is how long it took to read, replace, and write,
+// on its thread; the total's is the whole run's, from the start of the walk,
+// which with several threads is less than the files' added up.
+
+use std::time::Duration;
+
+use common::{tr, Messages};
+
+use crate::Stats;
+
+/// The files' statistics, in the order they were walked.
+#[derive(Debug, Default)]
+pub struct Table {
+    rows: Vec<(String, Stats, Duration)>,
+}
+
+impl Table {
+    /// Add a row for `file`, which took `elapsed`.
+    pub fn add(&mut self, file: &str, stats: Stats, elapsed: Duration) {
+        self.rows.push((file.to_string(), stats, elapsed));
+    }
+
+    /// The table, with a header and a row of totals, for a run that took
+    /// `elapsed`.
+    pub fn render(&self, elapsed: Duration, messages: &Messages) -> String {
+        let mut total = Stats::default();
+        for (_, stats, _) in &self.rows {
+            total += *stats;
+        }
+        let total_label = tr!(messages, "stats-total", files = self.rows.len());
+        let cells = |file: &str, stats: &Stats, elapsed: &Duration| {
+            [
+                file.to_string(),
+                stats.matches.to_string(),
+                stats.replacements.to_string(),
+                format!("-{} +{}", stats.removed, stats.added),
+                format!("{:.1?}", elapsed),
+            ]
+        };
+        let mut lines = vec![[
+            tr!(messages, "stats-file"),
+            tr!(messages, "stats-matches"),
+            tr!(messages, "stats-replacements"),
+            tr!(messages, "stats-bytes"),
+            tr!(messages, "stats-time"),
+        ]];
+        lines.extend(
+            self.rows
+                .iter()
+                .map(|(file, stats, elapsed)| cells(file, stats, elapsed)),
+        );
+        lines.push(cells(&total_label, &total, &elapsed));
+
+        let mut widths = [0; 5];
+        for line in &lines {
+            for (width, cell) in widths.iter_mut().zip(line) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let mut table = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            let mut row = String::new();
+            for (column, (cell, width)) in line.iter().zip(widths).enumerate() {
+                // Names on the left, numbers on the right.
+                match column {
+                    0 => row.push_str(&format!("{:<width$}", cell)),
+                    _ => row.push_str(&format!("  {:>width$}", cell)),
+                }
+            }
+            table.push_str(row.trim_end());
+            table.push('\n');
+            if i == 0 {
+                let width = widths.iter().sum::<usize>() + 2 * (widths.len() - 1);
+                table.push_str(&"-".repeat(width));
+                table.push('\n');
+            }
+        }
+        table
+    }
+}
@@ -0,0 +1,236 @@
+// `--stream`: the input is read a line at a time, and each line is
+// replaced and written before the next is read, so a file of any size is
+// rewritten in the memory its longest line takes. Only one line is ever
+// in memory, so a match can't span lines; everywhere else, quickreplace
+// reads the whole text first.
+//
+// Each line is replaced without its newline, so `$` matches at its end,
+// and a pattern that can't match a newline does the same here as it would
+// on the whole text.
+//
+// What's written is buffered, but only until all that's been read is
+// replaced: before a read that might wait for more input, it's flushed.
+// A file is written in large pieces all the same, and in a pipeline, each
+// line from a slow program upstream, like `tail -f`, goes on downstream as
+// soon as it comes, without waiting for the buffer to fill.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+
+use common::{tr, Context, Error, Messages};
+use tracing::info;
+
+use crate::gzip::{self, Writer};
+use crate::plugins::Plugins;
+use crate::progress::Progress;
+use crate::quickreplace::{
+    file_size, print_preview, print_written, write_in_place_with, Arguments, Output, STDIO,
+};
+use crate::{atomic, Replacer};
+
+/// Replace, count, or find the matches in the input, a line at a time,
+/// returning how many there were.
+pub fn rewrite(
+    args: &Arguments,
+    replacer: &Replacer,
+    plugins: &Plugins,
+    messages: &Messages,
+) -> Result<usize, Error> {
+    let input_name = args.input_filename.as_str();
+    let input: Box<dyn Read> = if input_name == STDIO {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(input_name)
+            .with_context(|| tr!(messages, "read-failed", file = input_name))?;
+        Box::new(file)
+    };
+    let gzipped = gzip::input(args);
+    let input = gzip::reader(input, gzipped);
+    // Lines found, or changes previewed, are printed as they're read, and
+    // a progress bar would be in their way.
+    let progress = !matches!(args.output, Output::Find) && !args.dry_run;
+    let mut lines = Lines {
+        input: BufReader::new(input),
+        name: input_name,
+        line: String::new(),
+        progress: progress.then(|| {
+            let size = file_size(input_name).filter(|_| !gzipped);
+            Progress::bytes(size, args)
+        }),
+        messages,
+    };
+
+    if let Output::Count = args.output {
+        let mut count = 0;
+        while let Some(line) = lines.next()? {
+            count += replacer.count(line);
+        }
+        lines.finish();
+        info!(count, "counted");
+        println!(
+            "{}",
+            tr!(messages, "counted", file = input_name, count = count)
+        );
+        return Ok(count);
+    }
+
+    if let Output::Find = args.output {
+        let mut found = 0;
+        let mut number = 0;
+        while let Some(line) = lines.next()? {
+            number += 1;
+            if replacer.count(line) > 0 {
+                found += 1;
+                println!("{}:{}:{}", input_name, number, line);
+            }
+        }
+        return Ok(found);
+    }
+
+    if args.dry_run {
+        let mut count = 0;
+        let mut number = 0;
+        while let Some(line) = lines.next()? {
+            number += 1;
+            let mut changes = replacer.preview(line);
+            plugins
+                .check()
+                .with_context(|| tr!(messages, "replace-failed"))?;
+            for change in &mut changes {
+                change.line = number;
+                count += change.replacements;
+            }
+            print_preview(input_name, &changes);
+        }
+        info!(count, lines = number, "dry run");
+        println!("{}", tr!(messages, "dry-run-summary", count = count));
+        return Ok(count);
+    }
+
+    let compress = gzip::output(args);
+    let count = match &args.output {
+        Output::File(name) if name == STDIO => {
+            let output = Writer::new(io::stdout().lock(), compress);
+            replace_lines(&mut lines, output, name, replacer, plugins)?
+        }
+        Output::File(name) => {
+            let mut count = 0;
+            let original = Some(input_name).filter(|file| *file != STDIO);
+            let original = original.map(|file| (file, args.preserve));
+            atomic::write_with(name, original, messages, |file| {
+                let output = Writer::new(file, compress);
+                count = replace_lines(&mut lines, output, name, replacer, plugins)?;
+                Ok(())
+            })?;
+            count
+        }
+        Output::InPlace { backup_suffix } => {
+            let mut count = 0;
+            let backup_suffix = backup_suffix.as_deref();
+            write_in_place_with(input_name, backup_suffix, args.preserve, messages, |file| {
+                let output = Writer::new(file, compress);
+                count = replace_lines(&mut lines, output, input_name, replacer, plugins)?;
+                Ok(count > 0)
+            })?;
+            count
+        }
+        Output::Count | Output::Find | Output::Diff | Output::Highlight => {
+            unreachable!("counted or found above, or a diff or highlight, which isn't streamed")
+        }
+    };
+    lines.finish();
+    info!(count, "streamed");
+    print_written(args, messages);
+    Ok(count)
+}
+
+/// Replace each of `lines` into `output`, named `name`, returning how many
+/// replacements were made. A plugin's failure stops it at that line.
+fn replace_lines<W: Write>(
+    lines: &mut Lines,
+    output: Writer<W>,
+    name: &str,
+    replacer: &Replacer,
+    plugins: &Plugins,
+) -> Result<usize, Error> {
+    let messages = lines.messages;
+    let write_failed = || {
+        if name == STDIO {
+            tr!(messages, "write-stdout-failed")
+        } else {
+            tr!(messages, "write-failed", file = name)
+        }
+    };
+    let mut output = BufWriter::new(output);
+    let mut count = 0;
+    while let Some(line) = lines.next()? {
+        let (replaced, n) = replacer.replace(line);
+        plugins
+            .check()
+            .with_context(|| tr!(messages, "replace-failed"))?;
+        output
+            .write_all(replaced.as_bytes())
+            .and_then(|()| output.write_all(lines.newline()))
+            .with_context(write_failed)?;
+        count += n;
+        if lines.input.buffer().is_empty() {
+            output.flush().with_context(write_failed)?;
+        }
+    }
+    output
+        .into_inner()
+        .map_err(|e| e.into_error())
+        .and_then(Writer::finish)
+        .and_then(|mut output| output.flush())
+        .with_context(write_failed)?;
+    Ok(count)
+}
+
+/// The input's lines, one at a time, in a buffer reused for each.
+struct Lines<'a> {
+    /// A `BufReader`, not any `BufRead`, to see what's left in its
+    /// buffer.
+    input: BufReader<Box<dyn Read + 'a>>,
+    name: &'a str,
+    line: String,
+    progress: Option<Progress>,
+    messages: &'a Messages,
+}
+
+impl Lines<'_> {
+    /// The next line, without its newline, or `None` at the end.
+    fn next(&mut self) -> Result<Option<&str>, Error> {
+        self.line.clear();
+        let read = self.input.read_line(&mut self.line).with_context(|| {
+            if self.name == STDIO {
+                tr!(self.messages, "read-stdin-failed")
+            } else {
+                tr!(self.messages, "read-failed", file = self.name)
+            }
+        })?;
+        if read == 0 {
+            return Ok(None);
+        }
+        if let Some(progress) = &self.progress {
+            progress.advance(read as u64);
+        }
+        Ok(Some(self.line.strip_suffix('\n').unwrap_or(&self.line)))
+    }
+
+    /// Rub out the progress bar, if there is one, to print what's next.
+    fn finish(&self) {
+        if let Some(progress) = &self.progress {
+            progress.finish();
+        }
+    }
+
+    /// The newline the last line ended with: none, for a last line
+    /// without one.
+    fn newline(&self) -> &'static [u8] {
+        if self.line.ends_with('\n') {
+            b"\n"
+        } else {
+            b""
+        }
+    }
+}
@@ -0,0 +1,430 @@
+// Rewriting every file under a directory: the input is walked with
+// `walk`, and each file matching the `--glob`s, and not left out
+// by an `--exclude` or an ignore file, is rewritten in place or copied,
+// rewritten, to the same place under an output directory. The files in a
+// `--files-from` list are rewritten the same way, but only in place. A file that
+// can't be read or written is reported and counted, and the rest are
+// still done; the run fails at the end if any did. With `--dry-run`, each
+// file's changes are shown instead, and nothing is written; with
+// `--diff`, a unified diff of them all, and nothing else, and with
+// `--highlight`, the changes in color. `count` says how many matches each
+// file has, and `find` shows the lines they're on. A `--report` has every
+// file's replacements, in the order the files were walked, and `--stats`
+// a table of what was done to each, after the summary.
+//
+// The files are replaced on a pool of `--jobs` threads, but each file's
+// result waits to be printed until every file walked before it has been,
+// so the output is the same however many threads there are.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use common::{tr, Context, Error, Messages};
+use rayon::prelude::*;
+use tracing::{debug, info, warn};
+
+use crate::encoding::{Decoded, Encoding};
+use crate::journal::Journal;
+use crate::plugins::Plugins;
+use crate::progress::Progress;
+use crate::quickreplace::{
+    check_fields, decode, encode, print_found, print_preview, write_in_place, Arguments, Output,
+    STDIO,
+};
+use crate::report::Report;
+use crate::source::{self, Source};
+use crate::stats::Table;
+use crate::walk::{Glob, Walk};
+use crate::{atomic, diff, highlight, Change, Replacer, Stats};
+
+/// What happened to the files.
+#[derive(Debug, Default)]
+struct Summary {
+    matched: usize,
+    changed: usize,
+    /// Replacements made, or for `count`, matches found, or for `find`,
+    /// changes.
+    count: usize,
+    failed: usize,
+}
+
+/// What to show for a file, once the files before it have been shown.
+enum Shown {
+    Nothing,
+    Preview(Vec<Change>),
+    Found(Vec<Change>),
+    /// Text to print as it is: a diff, or highlighted changes.
+    Text(String),
+    /// Nothing, but the changes made, to text in `encoding`, are kept for
+    /// the report or the journal.
+    Made(Vec<Change>, Encoding),
+}
+
+/// Replace, count, or find the matches in each file under the input
+/// directory, in the list of files, or named on the command line,
+/// returning how many there were in all.
+pub fn rewrite(
+    args: &Arguments,
+    source: Source,
+    replacer: &Replacer,
+    plugins: &Plugins,
+    messages: &Messages,
+) -> Result<usize, Error> {
+    let started = Instant::now();
+    let root = Path::new(&args.input_filename);
+    let counting = matches!(args.output, Output::Count);
+    let mut summary = Summary::default();
+    let paths = match source {
+        Source::Dir(_) => walk(root, args, &mut summary, messages)?,
+        Source::List { list, separator } => source::read_list(list, separator, messages)?,
+        Source::Files(files) => source::named(files),
+        Source::File(_) => unreachable!("a single file is rewritten on its own"),
+    };
+    summary.matched = paths.len();
+
+    // `None` is a thread for each CPU.
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.jobs.unwrap_or(0))
+        .build()?;
+    debug!(threads = pool.current_num_threads(), "replacing files");
+    let progress = Progress::files(paths.len(), args);
+    let results: Vec<_> = pool.install(|| {
+        paths
+            .par_iter()
+            .map(|path| {
+                let started = Instant::now();
+                let result = rewrite_file(root, path, args, replacer, plugins, messages);
+                (result, started.elapsed())
+            })
+            .inspect(|_| progress.advance(1))
+            .collect()
+    });
+    // Nothing is printed until every file is done, so the bar is never in
+    // the way.
+    progress.finish();
+
+    let mut report = args.report.as_ref().map(|_| Report::default());
+    let mut journal = args.journal.as_ref().map(|_| Journal::default());
+    let mut table = args.stats.then(Table::default);
+    for (path, (result, elapsed)) in paths.iter().zip(results) {
+        match result {
+            Ok((0, ..)) => {}
+            // The diff is all that's printed, so it can go straight to
+            // `patch` or `git apply`, and so is the highlight.
+            Ok((count, Shown::Text(text), _)) => {
+                summary.changed += 1;
+                summary.count += count;
+                print!("{}", text);
+            }
+            // As are the lines found.
+            Ok((count, Shown::Found(changes), _)) => {
+                summary.count += count;
+                print_found(&path.display().to_string(), &changes);
+            }
+            Ok((count, shown, stats)) => {
+                summary.changed += 1;
+                summary.count += count;
+                let file = path.display().to_string();
+                if let Some(table) = &mut table {
+                    table.add(&file, stats, elapsed);
+                }
+                if let (Shown::Preview(changes) | Shown::Made(changes, _), Some(report)) =
+                    (&shown, &mut report)
+                {
+                    report.add(&file, changes);
+                }
+                if let (Shown::Made(changes, encoding), Some(journal)) = (&shown, &mut journal) {
+                    if let Err(error) = journal.add(&file, *encoding, false, changes, messages) {
+                        common::report(&error);
+                        summary.failed += 1;
+                    }
+                }
+                if args.quiet() {
+                    continue;
+                }
+                if let Shown::Preview(changes) = shown {
+                    print_preview(&file, &changes);
+                }
+                println!(
+                    "{}",
+                    tr!(
+                        messages,
+                        if counting { "counted" } else { "tree-changed" },
+                        file = file,
+                        count = count
+                    )
+                );
+            }
+            Err(error) => {
+                common::report(&error);
+                summary.failed += 1;
+            }
+        }
+    }
+
+    info!(?summary, "done");
+    if let (Some(report), Some(file)) = (&report, &args.report) {
+        report.write(file, messages)?;
+    }
+    if let (Some(journal), Some(file)) = (&journal, &args.journal) {
+        journal.write(file, messages)?;
+    }
+    let dir = args.input_filename.as_str();
+    // Where the files were: under a directory, listed in a file or on
+    // standard input, or named on the command line.
+    let from = match source {
+        Source::List { list, .. } if list == STDIO => "stdin",
+        Source::List { .. } => "list",
+        Source::Files(_) => "args",
+        _ => "dir",
+    };
+    match args.output {
+        _ if args.quiet() => {}
+        Output::Diff | Output::Find | Output::Highlight => {}
+        Output::Count => println!(
+            "{}",
+            tr!(
+                messages,
+                "tree-count-summary",
+                count = summary.count,
+                files = summary.changed,
+                matched = summary.matched,
+                dir = dir,
+                from = from
+            )
+        ),
+        _ => println!(
+            "{}",
+            tr!(
+                messages,
+                if args.dry_run {
+                    "tree-dry-run-summary"
+                } else {
+                    "tree-summary"
+                },
+                changed = summary.changed,
+                matched = summary.matched,
+                dir = dir,
+                from = from
+            )
+        ),
+    }
+    // After the summary, so it's the last thing printed, or on standard
+    // error if a report has standard output.
+    if let Some(table) = &table {
+        let table = table.render(started.elapsed(), messages);
+        if args.quiet() {
+            eprint!("{}", table);
+        } else {
+            print!("{}", table);
+        }
+    }
+    if summary.failed > 0 {
+        return Err(Error::new(tr!(
+            messages,
+            "tree-failed",
+            count = summary.failed
+        )));
+    }
+    Ok(summary.count)
+}
+
+/// Replace, count, or find the matches in the file at `path`, returning
+/// how many there were, what to show for it, and, for `--stats`, what
+/// replacing them did.
+fn rewrite_file(
+    root: &Path,
+    path: &Path,
+    args: &Arguments,
+    replacer: &Replacer,
+    plugins: &Plugins,
+    messages: &Messages,
+) -> Result<(usize, Shown, Stats), Error> {
+    let file = path.display().to_string();
+    let Some(input) = read_file(&file, path, args, messages)? else {
+        return Ok((0, Shown::Nothing, Stats::default()));
+    };
+    let text = input.text.as_str();
+    check_fields(args, text, &file, messages)?;
+    let none = Stats::default();
+    match &args.output {
+        Output::Count => Ok((replacer.count(text), Shown::Nothing, none)),
+        Output::Diff => {
+            let (replaced, count) = replacer.replace(text);
+            check(plugins, &file, messages)?;
+            let diff = diff::unified(&file, text, &replaced);
+            Ok((count, Shown::Text(diff), none))
+        }
+        Output::Highlight => {
+            let changes = replacer.preview(text);
+            check(plugins, &file, messages)?;
+            let count = changes.iter().map(|c| c.replacements).sum();
+            let highlighted = highlight::render(&file, text, &changes);
+            Ok((count, Shown::Text(highlighted), none))
+        }
+        Output::Find => {
+            let changes = replacer.preview(text);
+            check(plugins, &file, messages)?;
+            Ok((changes.len(), Shown::Found(changes), none))
+        }
+        _ if args.dry_run => {
+            let changes = replacer.preview(text);
+            check(plugins, &file, messages)?;
+            let count = changes.iter().map(|c| c.replacements).sum();
+            let stats = Stats::of(&changes);
+            Ok((count, Shown::Preview(changes), stats))
+        }
+        output if args.keep_changes() => {
+            let changes = replacer.preview(text);
+            check(plugins, &file, messages)?;
+            let count = changes.iter().map(|c| c.replacements).sum();
+            if count > 0 || matches!(output, Output::File(_)) {
+                let replaced = crate::apply(text, &changes);
+                let bytes = encode(&input, &replaced, &file, messages)?;
+                write_file(root, path, &bytes, args, messages)?;
+            }
+            let stats = Stats::of(&changes);
+            Ok((count, Shown::Made(changes, input.encoding), stats))
+        }
+        output => {
+            // Only `--stats` needs each replacement compared with its
+            // match.
+            let (replaced, stats) = if args.stats {
+                replacer.replace_with_stats(text)
+            } else {
+                let (replaced, matches) = replacer.replace(text);
+                (replaced, Stats { matches, ..none })
+            };
+            check(plugins, &file, messages)?;
+            if stats.matches > 0 || matches!(output, Output::File(_)) {
+                let bytes = encode(&input, &replaced, &file, messages)?;
+                write_file(root, path, &bytes, args, messages)?;
+            }
+            Ok((stats.matches, Shown::Nothing, stats))
+        }
+    }
+}
+
+/// The files under the directory `root` to rewrite. A directory that
+/// can't be read is reported, and counted in `summary`, and the rest of
+/// the walk goes on.
+fn walk(
+    root: &Path,
+    args: &Arguments,
+    summary: &mut Summary,
+    messages: &Messages,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut walk = Walk::new(root).ignore_files(!args.no_ignore);
+    for pattern in &args.globs {
+        let glob = Glob::new(pattern)
+            .map_err(|_| Error::usage(tr!(messages, "bad-glob", glob = pattern.as_str())))?;
+        walk = walk.glob(glob);
+    }
+    for pattern in &args.exclude {
+        let glob = Glob::new(pattern)
+            .map_err(|_| Error::usage(tr!(messages, "bad-glob", glob = pattern.as_str())))?;
+        walk = walk.exclude(glob);
+    }
+    // Files written under an output directory inside the input mustn't be
+    // read back in as input.
+    let out_dir = match &args.output {
+        // Nothing is written, but an existing output directory is still
+        // left out.
+        Output::File(dir) if args.dry_run => fs::canonicalize(dir).ok(),
+        Output::File(dir) if dir == STDIO => {
+            return Err(Error::usage(tr!(messages, "tree-stdout")));
+        }
+        Output::File(dir) => {
+            fs::create_dir_all(dir)
+                .with_context(|| tr!(messages, "write-failed", file = dir.as_str()))?;
+            Some(fs::canonicalize(dir)?)
+        }
+        Output::InPlace { .. }
+        | Output::Count
+        | Output::Diff
+        | Output::Find
+        | Output::Highlight => None,
+    };
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for entry in walk {
+        let path = match entry {
+            Ok(path) => path,
+            Err(e) => {
+                let dir = e.path.display().to_string();
+                let error = Err::<(), _>(e.error)
+                    .with_context(|| tr!(messages, "walk-failed", dir = dir))
+                    .unwrap_err();
+                common::report(&error);
+                summary.failed += 1;
+                continue;
+            }
+        };
+        if let Some(out_dir) = &out_dir {
+            if fs::canonicalize(&path).is_ok_and(|p| p.starts_with(out_dir)) {
+                continue;
+            }
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Whether the plugins failed on `file`.
+fn check(plugins: &Plugins, file: &str, messages: &Messages) -> Result<(), Error> {
+    plugins
+        .check()
+        .with_context(|| tr!(messages, "replace-in-failed", file = file))
+}
+
+/// Write `bytes`, the rewritten `path`, back in place or to the same
+/// place under the output directory.
+fn write_file(
+    root: &Path,
+    path: &Path,
+    bytes: &[u8],
+    args: &Arguments,
+    messages: &Messages,
+) -> Result<(), Error> {
+    let file = path.display().to_string();
+    match &args.output {
+        Output::InPlace { backup_suffix } => write_in_place(
+            &file,
+            bytes,
+            backup_suffix.as_deref(),
+            args.preserve,
+            messages,
+        ),
+        Output::File(dir) => {
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let destination = Path::new(dir).join(relative);
+            let written = destination.display().to_string();
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| tr!(messages, "write-failed", file = written.as_str()))?;
+            }
+            atomic::write(&written, bytes, Some((&file, args.preserve)), messages)
+        }
+        Output::Count | Output::Diff | Output::Find | Output::Highlight => {
+            unreachable!("counting, diffing, finding, or highlighting writes nothing")
+        }
+    }
+}
+
+/// `path`'s text, or `None` if it isn't text in the `--encoding` and
+/// should be skipped.
+fn read_file(
+    file: &str,
+    path: &Path,
+    args: &Arguments,
+    messages: &Messages,
+) -> Result<Option<Decoded>, Error> {
+    let bytes = fs::read(path).with_context(|| tr!(messages, "read-failed", file = file))?;
+    match decode(&bytes, args.encoding) {
+        Ok(decoded) => Ok(Some(decoded)),
+        Err(error) => {
+            warn!(file, %error, "skipping a file that isn't text");
+            Ok(None)
+        }
+    }
+}
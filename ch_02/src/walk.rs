@@ -0,0 +1,424 @@
+// Finding the files under a directory, for rewriting a whole tree.
+//
+// `Walk` lists a directory's files depth first, in order by name, so runs
+// are repeatable. It skips hidden files and directories, whose names
+// start with a dot, such as `.git`, and doesn't follow symbolic links,
+// which could lead outside the tree or around in a circle. A `Glob` picks
+// out the files wanted: `*.rs` matches by file name anywhere in the tree,
+// and a pattern with a slash, like `src/**/*.rs`, matches the path from
+// the top. An excluded glob leaves out the files it matches, and the
+// directories, with everything in them: excluding `target` skips a
+// build's output wherever it is.
+//
+// As `git` and ripgrep do, the walk leaves out what a `.gitignore` or
+// `.ignore` file in the tree says to, so a project's `target/` or
+// `node_modules/` isn't rewritten with its sources. A rule applies in the
+// directory of its file and under it; a later rule, or one in a deeper
+// file, wins over an earlier one, and `.ignore` over `.gitignore`, so a
+// `!` can take a file back. A directory left out is left out whole, as
+// in git: nothing under it can be taken back.
+//
+// When the tree is in a git repository, the rules above it count too:
+// `.git/info/exclude` first, then the ignore files in each directory from
+// the repository's top down to the tree's, each matched against the path
+// from its own directory, as git would. Git's global excludes file isn't
+// read.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// A shell-style pattern: `*` matches any run of characters except `/`,
+/// `?` any one of them, `[abc]` or `[a-z]` one from a set, `[!abc]` one
+/// not in it, and `**` any run of characters at all, slashes included.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    regex: Regex,
+    /// Whether to match the whole relative path, not just the file name.
+    whole_path: bool,
+}
+
+/// A glob that can't be compiled: an unclosed `[`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobError {
+    pub pattern: String,
+}
+
+impl fmt::Display for GlobError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid glob '{}': unclosed [", self.pattern)
+    }
+}
+
+impl std::error::Error for GlobError {}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Glob, GlobError> {
+        let error = || GlobError {
+            pattern: pattern.to_string(),
+        };
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    // `**/` can match nothing at all, so `src/**/*.rs`
+                    // includes `src/main.rs`.
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '[' => {
+                    regex.push('[');
+                    if chars.peek() == Some(&'!') {
+                        chars.next();
+                        regex.push('^');
+                    }
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            closed = true;
+                            break;
+                        }
+                        if c == '\\' || c == '[' || c == '^' {
+                            regex.push('\\');
+                        }
+                        regex.push(c);
+                    }
+                    if !closed {
+                        return Err(error());
+                    }
+                    regex.push(']');
+                }
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        Ok(Glob {
+            pattern: pattern.to_string(),
+            regex: Regex::new(&regex).map_err(|_| error())?,
+            whole_path: pattern.contains('/'),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether `relative`, a path from the top of the tree, matches.
+    pub fn matches(&self, relative: &Path) -> bool {
+        if self.whole_path {
+            let path: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            self.regex.is_match(&path.join("/"))
+        } else {
+            relative
+                .file_name()
+                .is_some_and(|name| self.regex.is_match(&name.to_string_lossy()))
+        }
+    }
+}
+
+/// A directory that couldn't be read.
+#[derive(Debug)]
+pub struct WalkError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl fmt::Display for WalkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "can't read '{}': {}", self.path.display(), self.error)
+    }
+}
+
+/// A line of a `.gitignore` or `.ignore` file.
+#[derive(Debug, Clone)]
+struct Ignore {
+    /// The directory the file is in, which the pattern is relative to.
+    dir: PathBuf,
+    glob: Glob,
+    /// A `!` rule, which takes back what an earlier rule left out.
+    negated: bool,
+    /// A rule ending in `/`, which only matches directories.
+    dir_only: bool,
+    /// For a rule from above the tree, whose `dir` is then the top of
+    /// the tree, the path from the rule's own directory down to it;
+    /// otherwise empty.
+    above: PathBuf,
+}
+
+impl Ignore {
+    /// The rule on `line` of an ignore file in `dir`, if there is one.
+    /// Blank lines, comments, and patterns that aren't globs are none.
+    fn parse(dir: &Path, line: &str) -> Option<Ignore> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            // `\#` and `\!` are a `#` or `!` at the start of a name.
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // A slash anywhere but the end ties the pattern to `dir`, as a
+        // `Glob` with a slash matches the whole path; without one, it
+        // matches a name at any depth.
+        let (anchored, line) = match line.strip_prefix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        if line.is_empty() {
+            return None;
+        }
+        let mut glob = Glob::new(line).ok()?;
+        glob.whole_path |= anchored;
+        Some(Ignore {
+            dir: dir.to_path_buf(),
+            glob,
+            negated,
+            dir_only,
+            above: PathBuf::new(),
+        })
+    }
+
+    /// Whether this rule is about `path`: it's under the rule's directory
+    /// and matches.
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        match path.strip_prefix(&self.dir) {
+            Ok(relative) => self.glob.matches(&self.above.join(relative)),
+            Err(_) => false,
+        }
+    }
+}
+
+impl std::error::Error for WalkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// An iterator over the files under a directory that match any of a set
+/// of globs, or all of them if there are none, and no excluded glob or
+/// ignore file's rule. It yields each file's path under the directory as
+/// given, so `Walk::new("src")` yields `src/main.rs`. A directory that
+/// can't be read is an error, and the walk goes on past it.
+#[derive(Debug)]
+pub struct Walk {
+    root: PathBuf,
+    globs: Vec<Glob>,
+    excluded: Vec<Glob>,
+    /// Whether to read `.gitignore` and `.ignore` files.
+    ignore_files: bool,
+    /// The rules from the ignore files read so far, outer directories'
+    /// first.
+    ignores: Vec<Ignore>,
+    /// Paths found but not yet visited, the next one last.
+    pending: Vec<PathBuf>,
+    started: bool,
+}
+
+impl Walk {
+    pub fn new(root: impl Into<PathBuf>) -> Walk {
+        Walk {
+            root: root.into(),
+            globs: Vec::new(),
+            excluded: Vec::new(),
+            ignore_files: true,
+            ignores: Vec::new(),
+            pending: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Only yield files matching `glob`, or any other glob given.
+    pub fn glob(mut self, glob: Glob) -> Walk {
+        self.globs.push(glob);
+        self
+    }
+
+    /// Skip files, and directories, matching `glob`, even if another glob
+    /// matches them.
+    pub fn exclude(mut self, glob: Glob) -> Walk {
+        self.excluded.push(glob);
+        self
+    }
+
+    /// Whether to leave out what `.gitignore` and `.ignore` files say to,
+    /// as it does unless told not to.
+    pub fn ignore_files(mut self, yes: bool) -> Walk {
+        self.ignore_files = yes;
+        self
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+
+    fn wanted(&self, path: &Path) -> bool {
+        let relative = self.relative(path);
+        self.globs.is_empty() || self.globs.iter().any(|glob| glob.matches(relative))
+    }
+
+    fn excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = self.relative(path);
+        if self.excluded.iter().any(|glob| glob.matches(relative)) {
+            return true;
+        }
+        // The last rule about the path decides.
+        self.ignores
+            .iter()
+            .rev()
+            .find(|ignore| ignore.matches(path, is_dir))
+            .is_some_and(|ignore| !ignore.negated)
+    }
+
+    /// Add the rules in the ignore file at `path`, if there is one, to
+    /// those already read, for `dir`, `above` the path from the file's
+    /// own directory to it.
+    fn read_rules(&mut self, path: PathBuf, dir: &Path, above: &Path) -> Result<(), WalkError> {
+        let text = match fs::read(&path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(WalkError { path, error }),
+        };
+        self.ignores.extend(
+            text.lines()
+                .filter_map(|line| Ignore::parse(dir, line))
+                .map(|ignore| Ignore {
+                    above: above.to_path_buf(),
+                    ..ignore
+                }),
+        );
+        Ok(())
+    }
+
+    /// Add the rules in `dir`'s ignore files to those already read.
+    fn read_ignores(&mut self, dir: &Path) -> Result<(), WalkError> {
+        for name in [".gitignore", ".ignore"] {
+            self.read_rules(dir.join(name), dir, Path::new(""))?;
+        }
+        Ok(())
+    }
+
+    /// Add the rules from above the tree, if it's in a git repository:
+    /// `.git/info/exclude`, then the ignore files in each directory from
+    /// the repository's top down to the tree's parent.
+    fn read_outer_ignores(&mut self) -> Result<(), WalkError> {
+        // A root that can't be found is reported when it's read.
+        let Ok(top) = fs::canonicalize(&self.root) else {
+            return Ok(());
+        };
+        let Some(repository) = top.ancestors().find(|dir| dir.join(".git").exists()) else {
+            return Ok(());
+        };
+        let root = self.root.clone();
+        let above = |dir: &Path| top.strip_prefix(dir).unwrap_or(&top).to_path_buf();
+        let git = repository.join(".git");
+        self.read_rules(
+            git_dir(&git).join("info").join("exclude"),
+            &root,
+            &above(repository),
+        )?;
+        let outer: Vec<&Path> = top
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(repository))
+            .collect();
+        for dir in outer.into_iter().rev() {
+            for name in [".gitignore", ".ignore"] {
+                self.read_rules(dir.join(name), &root, &above(dir))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue `dir`'s entries, sorted so they come out in order by name.
+    fn read(&mut self, dir: &Path) -> Result<(), WalkError> {
+        let error = |error| WalkError {
+            path: dir.to_path_buf(),
+            error,
+        };
+        let read_dir = fs::read_dir(dir).map_err(error)?;
+        if self.ignore_files {
+            self.read_ignores(dir)?;
+        }
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let entry = entry.map_err(error)?;
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let is_dir = entry.file_type().map_err(error)?.is_dir();
+            if !self.excluded(&path, is_dir) {
+                entries.push(path);
+            }
+        }
+        entries.sort_by(|a, b| b.cmp(a));
+        self.pending.extend(entries);
+        Ok(())
+    }
+}
+
+impl Iterator for Walk {
+    type Item = Result<PathBuf, WalkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            if self.ignore_files {
+                if let Err(e) = self.read_outer_ignores() {
+                    return Some(Err(e));
+                }
+            }
+            let root = self.root.clone();
+            if let Err(e) = self.read(&root) {
+                return Some(Err(e));
+            }
+        }
+        while let Some(path) = self.pending.pop() {
+            let file_type = match fs::symlink_metadata(&path) {
+                Ok(metadata) => metadata.file_type(),
+                Err(error) => return Some(Err(WalkError { path, error })),
+            };
+            if file_type.is_dir() {
+                if let Err(e) = self.read(&path) {
+                    return Some(Err(e));
+                }
+            } else if file_type.is_file() && self.wanted(&path) {
+                return Some(Ok(path));
+            }
+        }
+        None
+    }
+}
+
+/// The git directory a `.git` names: itself, or in a submodule, where
+/// `.git` is a file, the directory after its `gitdir:`.
+fn git_dir(git: &Path) -> PathBuf {
+    let named = fs::read_to_string(git).ok().and_then(|text| {
+        let dir = text.lines().next()?.strip_prefix("gitdir:")?.trim();
+        Some(git.parent()?.join(dir))
+    });
+    named.unwrap_or_else(|| git.to_path_buf())
+}
@@ -0,0 +1,179 @@
+// `--watch`: replace once, then again each time the input changes, for
+// output that keeps up with a file being edited.
+//
+// On Linux, the kernel says when something changes, through inotify. The
+// watch is on the file's directory, not the file itself: an editor that
+// saves by writing a new file and renaming it over the old one, as
+// quickreplace itself does, leaves a watch on the old file watching a file
+// no longer there. Of what happens in the directory, a file closed after
+// writing, or renamed in, with the input's name, is a change. Elsewhere,
+// or if inotify can't be had, the file is looked at four times a second,
+// as `read_and_sum --follow` does.
+//
+// Either way, a change only counts if the file's size or modification
+// time is new, so a save that writes the file more than once, or a
+// program that opens it for writing and writes nothing, doesn't set off
+// a run for each, and a file missing for a moment, between an editor
+// removing it and writing it again, is waited out.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use tracing::debug;
+
+/// A file to wait for changes to.
+#[derive(Debug)]
+pub struct Watch {
+    path: PathBuf,
+    /// What the file was like the last time it was seen to change.
+    seen: Option<Stamp>,
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+}
+
+/// What's looked at to tell if a file has changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+/// How often to look at the file, without inotify.
+const POLL: Duration = Duration::from_millis(250);
+
+impl Watch {
+    /// Start watching `path`. Changes from here on count, so this comes
+    /// before the first run, and a change made during it isn't missed.
+    pub fn new(path: impl Into<PathBuf>) -> Watch {
+        let path = path.into();
+        let seen = stamp(&path);
+        #[cfg(target_os = "linux")]
+        let inotify = {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            inotify::Inotify::new(dir)
+                .map_err(|error| debug!(%error, "can't use inotify; polling instead"))
+                .ok()
+        };
+        Watch {
+            path,
+            seen,
+            #[cfg(target_os = "linux")]
+            inotify,
+        }
+    }
+
+    /// Wait until the file has changed since it was last seen to.
+    pub fn wait(&mut self) -> io::Result<()> {
+        loop {
+            self.event()?;
+            let stamp = stamp(&self.path);
+            if stamp.is_some() && stamp != self.seen {
+                debug!(?stamp, file = %self.path.display(), "changed");
+                self.seen = stamp;
+                return Ok(());
+            }
+        }
+    }
+
+    /// Wait until the file might have changed.
+    fn event(&self) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = &self.inotify {
+            let name = self.path.file_name().unwrap_or_default();
+            while !inotify.read()?.iter().any(|changed| changed == name) {}
+            return Ok(());
+        }
+        thread::sleep(POLL);
+        Ok(())
+    }
+}
+
+/// `path`'s size and modification time, if there's a file there.
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(Stamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::{CString, OsString};
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::fd::{AsRawFd, FromRawFd};
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+    use std::path::Path;
+
+    /// The events that mean a file in the directory has new contents: a
+    /// file written and closed, or renamed into it.
+    const CHANGED: u32 = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO;
+
+    /// The size of an event before its name: its watch, what happened,
+    /// a cookie tying a rename's two halves together, and the name's
+    /// length.
+    const HEADER: usize = 16;
+
+    /// An inotify instance watching one directory.
+    #[derive(Debug)]
+    pub struct Inotify {
+        file: File,
+    }
+
+    impl Inotify {
+        pub fn new(dir: &Path) -> io::Result<Inotify> {
+            // SAFETY: `inotify_init1` takes no pointers, and a descriptor
+            // it returns is ours alone, for the `File` to close.
+            let file = unsafe {
+                let fd = libc::inotify_init1(libc::IN_CLOEXEC);
+                if fd < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                File::from_raw_fd(fd)
+            };
+            let dir = CString::new(dir.as_os_str().as_bytes())?;
+            // SAFETY: `dir` is a NUL-terminated string that outlives the
+            // call.
+            let watch = unsafe { libc::inotify_add_watch(file.as_raw_fd(), dir.as_ptr(), CHANGED) };
+            if watch < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Inotify { file })
+        }
+
+        /// Wait for events, and return the names of the files they're
+        /// about.
+        pub fn read(&self) -> io::Result<Vec<OsString>> {
+            // Room for at least one event with the longest name there is.
+            let mut buffer = [0; 4096];
+            let read = loop {
+                match (&self.file).read(&mut buffer) {
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    read => break read?,
+                }
+            };
+            let mut names = Vec::new();
+            let mut events = &buffer[..read];
+            while events.len() >= HEADER {
+                let field = |at: usize| u32::from_ne_bytes(events[at..at + 4].try_into().unwrap());
+                let mask = field(4);
+                let len = field(12) as usize;
+                // The name is padded with NULs.
+                let name = &events[HEADER..HEADER + len];
+                let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(len)];
+                if mask & CHANGED != 0 {
+                    names.push(OsString::from_vec(name.to_vec()));
+                }
+                events = &events[HEADER + len..];
+            }
+            Ok(names)
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::ops::Range;
+
+use ch_02::chunked::{split, Stitch};
+use ch_02::Replacer;
+
+/// `text` replaced a section of about `size` bytes at a time, each section
+/// on its own, then stitched, as quickreplace does on several threads.
+fn in_sections(replacer: &Replacer, text: &str, size: usize) -> (String, usize) {
+    let sections: Vec<_> = split(text.as_bytes(), size)
+        .into_iter()
+        .map(|range| replacer.replace_section(text, range))
+        .collect();
+    let mut stitch = Stitch::new(replacer, text);
+    let mut replaced = String::new();
+    let mut count = 0;
+    for section in sections {
+        let section = stitch.next(section);
+        replaced.push_str(&section.text);
+        count += section.count;
+    }
+    (replaced, count)
+}
+
+#[test]
+fn splits_after_newlines() {
+    let text = b"one\ntwo\nthree\n\nfour";
+    assert_eq!(split(text, 5), [0..8, 8..14, 14..19]);
+    assert_eq!(split(text, 100), [Range { start: 0, end: 19 }]);
+    // A line longer than the size is a section of its own.
+    assert_eq!(split(b"aaaaaaaa\nb\n", 2), [0..9, 9..11]);
+    assert_eq!(split(b"", 4), [Range { start: 0, end: 0 }]);
+}
+
+#[test]
+fn sections_stitch_into_the_whole_replaced() {
+    let text = fixtures::text(4 * 1024, 7);
+    let text = format!("{}\n\nthe end\n", text.replace(". ", ".\n"));
+    let replacers = [
+        Replacer::new(r"\bthe\b", "THE").unwrap(),
+        Replacer::new(r"(\w+) (\w+)", "$2 $1").unwrap(),
+        // Matches that span newlines, and so sections, and whole texts.
+        Replacer::new(r"\.\n\w+", "<$0>").unwrap(),
+        Replacer::new(r"[^x]+", "all").unwrap(),
+        Replacer::new(r"(?s).{0,300}", "[$0]").unwrap(),
+        // `^` without multiline is only the text's start, and `$` its end.
+        Replacer::new(r"^\w+|\w+\n$", "edge").unwrap(),
+        Replacer::new(r"(?m)^", "> ").unwrap(),
+        Replacer::new(r"(?m)$", " <").unwrap(),
+        // Empty matches, right after a match and right at a section's
+        // start.
+        Replacer::new(r"e*", "-").unwrap(),
+        Replacer::new(r"\n*", "|").unwrap(),
+        Replacer::builder()
+            .pattern(r"\w+\n")
+            .replacement("NL\n")
+            .pattern(r"\n\n|s")
+            .replacement("$$")
+            .pattern("e?")
+            .replacement("E")
+            .build()
+            .unwrap(),
+        Replacer::builder()
+            .pattern("t")
+            .replacement("T")
+            .only_lines_matching("^[A-Z]")
+            .build()
+            .unwrap(),
+    ];
+    for replacer in &replacers {
+        let (whole, count) = replacer.replace(&text);
+        for size in [1, 2, 7, 64, 250, 1000, 100_000] {
+            assert_eq!(
+                in_sections(replacer, &text, size),
+                (whole.to_string(), count),
+                "{:?} in sections of {}",
+                replacer,
+                size
+            );
+        }
+    }
+    let empty = Replacer::new("^", "start").unwrap();
+    assert_eq!(in_sections(&empty, "", 4), ("start".to_string(), 1));
+}
@@ -0,0 +1,82 @@
+use ch_02::csv::MissingColumn;
+use ch_02::{Columns, Replacer};
+
+const PEOPLE: &str = "name,email,note\r\n\
+                      Ann,ann@old.com,\"wrote to ann@old.com, twice\"\r\n\
+                      \"Bob \"\"the\"\" Builder\",bob@old.com,ann@old.com\r\n";
+
+fn in_columns(pattern: &str, replacement: &str, columns: &[&str]) -> Replacer {
+    Replacer::new(pattern, replacement)
+        .unwrap()
+        .columns(Columns::new(columns.iter().copied()))
+}
+
+#[test]
+fn replaces_only_in_the_columns() {
+    let replacer = in_columns("@old.com", "@new.com", &["email"]);
+    let (text, count) = replacer.replace(PEOPLE);
+    assert_eq!(
+        text,
+        "name,email,note\r\n\
+         Ann,ann@new.com,\"wrote to ann@old.com, twice\"\r\n\
+         \"Bob \"\"the\"\" Builder\",bob@new.com,ann@old.com\r\n"
+    );
+    assert_eq!(count, 2);
+    assert_eq!(replacer.count(PEOPLE), 2);
+
+    // Each field is a text of its own, and the header is left alone.
+    let replacer = in_columns("^.*$", "x", &["note", "name"]);
+    assert_eq!(
+        replacer.replace(PEOPLE).0,
+        "name,email,note\r\n\
+         x,ann@old.com,\"x\"\r\n\
+         \"x\",bob@old.com,x\r\n"
+    );
+}
+
+#[test]
+fn keeps_quoting_as_it_was_unless_it_has_to_change() {
+    // A quoted field's `""` is a `"`, and one the replacement puts in is
+    // doubled; an unquoted field is quoted once it needs to be.
+    let replacer = in_columns(r#""(\w+)""#, "'$1'", &["name"]);
+    assert_eq!(
+        replacer.replace(PEOPLE).0,
+        PEOPLE.replace(r#""Bob ""the"" Builder""#, r#""Bob 'the' Builder""#)
+    );
+    let replacer = in_columns("Ann", r#"Ann "A", Smith"#, &["name"]);
+    assert_eq!(
+        replacer.replace(PEOPLE).0.lines().nth(1),
+        Some(r#""Ann ""A"", Smith",ann@old.com,"wrote to ann@old.com, twice""#)
+    );
+    // A record spanning lines is one change, its matches where they're
+    // written.
+    let text = "id,text\n1,\"two\nlines \"\"two\"\"\"\n";
+    let changes = in_columns("two", "2", &["text"]).preview(text);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].line, 2);
+    assert_eq!(changes[0].before, "1,\"two\nlines \"\"two\"\"\"");
+    assert_eq!(changes[0].after, "1,\"2\nlines \"\"2\"\"\"");
+    let ranges: Vec<_> = changes[0]
+        .replaced
+        .iter()
+        .map(|r| r.range.clone())
+        .collect();
+    assert_eq!(ranges, [3..6, 15..18]);
+}
+
+#[test]
+fn finds_the_columns_in_the_header() {
+    let columns = Columns::new(["note", "name"]);
+    assert_eq!(columns.find(PEOPLE), Ok(vec![0, 2]));
+    assert_eq!(columns.find(""), Ok(vec![]));
+    assert_eq!(
+        Columns::new(["name", "phone"]).find(PEOPLE),
+        Err(MissingColumn {
+            column: "phone".to_string(),
+            header: vec!["name".into(), "email".into(), "note".into()],
+        })
+    );
+    // A column that isn't there has nothing to replace.
+    let replacer = in_columns("a", "b", &["phone"]);
+    assert_eq!(replacer.replace(PEOPLE).1, 0);
+}
@@ -0,0 +1,124 @@
+use ch_02::diff::unified;
+use fixtures::Rng;
+
+#[test]
+fn no_changes_no_diff() {
+    assert_eq!(unified("same.txt", "a\nb\n", "a\nb\n"), "");
+    assert_eq!(unified("empty.txt", "", ""), "");
+}
+
+#[test]
+fn hunks_with_context() {
+    let old: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+    let new = old
+        .replace("line 2\n", "line two\n")
+        .replace("line 16\n", "")
+        .replace("line 20\n", "line 20\nline 21\n");
+    assert_eq!(
+        unified("numbers.txt", &old, &new),
+        "--- numbers.txt\n\
+         +++ numbers.txt\n\
+         @@ -1,5 +1,5 @@\n\
+         \x20line 1\n\
+         -line 2\n\
+         +line two\n\
+         \x20line 3\n\
+         \x20line 4\n\
+         \x20line 5\n\
+         @@ -13,8 +13,8 @@\n\
+         \x20line 13\n\
+         \x20line 14\n\
+         \x20line 15\n\
+         -line 16\n\
+         \x20line 17\n\
+         \x20line 18\n\
+         \x20line 19\n\
+         \x20line 20\n\
+         +line 21\n"
+    );
+}
+
+#[test]
+fn edges_of_the_file() {
+    assert_eq!(
+        unified("new.txt", "", "hello\n"),
+        "--- new.txt\n+++ new.txt\n@@ -0,0 +1 @@\n+hello\n"
+    );
+    assert_eq!(
+        unified("gone.txt", "a\nb\n", ""),
+        "--- gone.txt\n+++ gone.txt\n@@ -1,2 +0,0 @@\n-a\n-b\n"
+    );
+    // A last line without a newline is different from one with.
+    assert_eq!(
+        unified("end.txt", "a\nb", "a\nb\n"),
+        "--- end.txt\n+++ end.txt\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+    );
+}
+
+/// `old` with `diff` applied to it, as `patch` would, checking each
+/// context and deleted line as it goes.
+fn apply(old: &str, diff: &str) -> String {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let mut new = String::new();
+    let mut copied = 0;
+    let mut lines = diff.split_inclusive('\n').skip(2).peekable();
+    while let Some(header) = lines.next() {
+        let start: usize = header[4..]
+            .split([',', ' '])
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        // An empty range starts after the line it names.
+        let start = if header.starts_with(&format!("@@ -{},0 ", start)) {
+            start
+        } else {
+            start - 1
+        };
+        for line in &old[copied..start] {
+            new.push_str(line);
+        }
+        copied = start;
+        while let Some(line) = lines.next_if(|line| !line.starts_with("@@")) {
+            let mut text = line[1..].to_string();
+            if lines.next_if(|l| l.starts_with('\\')).is_some() {
+                text.pop();
+            }
+            match &line[..1] {
+                " " | "-" => {
+                    assert_eq!(old[copied], text);
+                    copied += 1;
+                    if line.starts_with(' ') {
+                        new.push_str(&text);
+                    }
+                }
+                _ => new.push_str(&text),
+            }
+        }
+    }
+    for line in &old[copied..] {
+        new.push_str(line);
+    }
+    new
+}
+
+#[test]
+fn diffs_apply_back() {
+    let mut rng = Rng::new(7);
+    for _ in 0..200 {
+        let words = ["a\n", "b\n", "c\n", "d\n", "e"];
+        let old: String = (0..rng.below(30)).map(|_| rng.pick(&words)).collect();
+        // Some lines dropped, and others added.
+        let mut new = String::new();
+        for line in old.split_inclusive('\n') {
+            if rng.chance(0.2) {
+                new.push_str(rng.pick(&words));
+            }
+            if !rng.chance(0.2) {
+                new.push_str(line);
+            }
+        }
+        let diff = unified("f", &old, &new);
+        assert_eq!(apply(&old, &diff), new, "{:?} -> {:?}:\n{}", old, new, diff);
+    }
+}
@@ -0,0 +1,66 @@
+use ch_02::encoding::{DecodeError, Encoding};
+
+#[test]
+fn round_trips() {
+    let text = "naïve café, 1€ 𝄞\n";
+    for encoding in [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be] {
+        for bom in [false, true] {
+            let bytes = encoding.encode(text, bom).unwrap();
+            let decoded = encoding.decode(&bytes).unwrap();
+            assert_eq!(decoded.text, text);
+            assert_eq!(decoded.bom, bom);
+            assert_eq!(decoded.encode(&decoded.text).unwrap(), bytes);
+        }
+    }
+    let latin1 = Encoding::Latin1.encode("naïve café\n", false).unwrap();
+    assert_eq!(latin1, b"na\xEFve caf\xE9\n");
+    assert_eq!(
+        Encoding::Latin1.decode(&latin1).unwrap().text,
+        "naïve café\n"
+    );
+}
+
+#[test]
+fn detects_from_the_bytes() {
+    let detect = |bytes: &[u8]| Encoding::detect(bytes);
+    assert_eq!(detect(b"\xFF\xFEh\0i\0"), Encoding::Utf16Le);
+    assert_eq!(detect(b"\xFE\xFF\0h\0i"), Encoding::Utf16Be);
+    assert_eq!(detect(b"\xEF\xBB\xBFhi"), Encoding::Utf8);
+    assert_eq!(detect("café".as_bytes()), Encoding::Utf8);
+    assert_eq!(detect(b"caf\xE9"), Encoding::Latin1);
+    assert_eq!(detect(b""), Encoding::Utf8);
+}
+
+#[test]
+fn bad_bytes_and_characters() {
+    assert_eq!(
+        Encoding::Utf8.decode(b"ok\xFF").unwrap_err(),
+        DecodeError {
+            encoding: Encoding::Utf8,
+            offset: 2
+        }
+    );
+    // An odd byte out, and a lone surrogate, after the byte order mark.
+    let error = Encoding::Utf16Le.decode(b"\xFF\xFEa\0b").unwrap_err();
+    assert_eq!(error.offset, 4);
+    let error = Encoding::Utf16Le.decode(b"a\0\x00\xD8b\0").unwrap_err();
+    assert_eq!(error.to_string(), "invalid UTF-16LE at byte 2");
+    let error = Encoding::Latin1.encode("1€", false).unwrap_err();
+    assert_eq!(error.to_string(), "'€' can't be written in Latin-1");
+}
+
+#[test]
+fn names() {
+    for (name, encoding) in [
+        ("utf-8", Encoding::Utf8),
+        ("UTF8", Encoding::Utf8),
+        ("utf-16le", Encoding::Utf16Le),
+        ("UTF_16BE", Encoding::Utf16Be),
+        ("latin1", Encoding::Latin1),
+        ("ISO-8859-1", Encoding::Latin1),
+    ] {
+        assert_eq!(name.parse::<Encoding>(), Ok(encoding));
+    }
+    let error = "ebcdic".parse::<Encoding>().unwrap_err();
+    assert_eq!(error.to_string(), "unknown encoding 'ebcdic'");
+}
@@ -0,0 +1,50 @@
+use ch_02::expand::{expand_env, UnsetVariable};
+
+fn env(name: &str) -> Option<String> {
+    match name {
+        "HOME" => Some("/home/ferris".to_string()),
+        "DIR" => Some(r"C:\$Users".to_string()),
+        "EMPTY" => Some(String::new()),
+        _ => None,
+    }
+}
+
+#[test]
+fn variables_are_put_in() {
+    let expand = |template| expand_env(template, true, env);
+    assert_eq!(expand("${HOME}/bin").unwrap(), "/home/ferris/bin");
+    assert_eq!(expand("$1 at ${HOME}").unwrap(), "$1 at /home/ferris");
+    // Groups aren't variables, and neither is anything else.
+    assert_eq!(
+        expand("${1}$name${} ${a-b} ${").unwrap(),
+        "${1}$name${} ${a-b} ${"
+    );
+    assert_eq!(
+        expand("${MISSING}"),
+        Err(UnsetVariable("MISSING".to_string()))
+    );
+}
+
+#[test]
+fn defaults_stand_in_for_unset_and_empty_variables() {
+    let expand = |template| expand_env(template, true, env);
+    assert_eq!(expand("${MISSING:-/tmp}").unwrap(), "/tmp");
+    assert_eq!(expand("${EMPTY:-none}").unwrap(), "none");
+    assert_eq!(expand("${HOME:-/tmp}").unwrap(), "/home/ferris");
+    assert_eq!(expand("[${EMPTY}]").unwrap(), "[]");
+}
+
+#[test]
+fn escaping() {
+    // For a `Replacer`, a value's `$` and backslashes are escaped, and
+    // `$$` is left for it.
+    assert_eq!(
+        expand_env("${DIR} $${HOME}", true, env).unwrap(),
+        r"C:\\$$Users $${HOME}"
+    );
+    // As plain text, nothing is.
+    assert_eq!(
+        expand_env("${DIR} $${HOME}", false, env).unwrap(),
+        r"C:\$Users ${HOME}"
+    );
+}
@@ -0,0 +1,97 @@
+use ch_02::json::{InvalidJson, InvalidPointer};
+use ch_02::{Pointers, Replacer};
+
+const USERS: &str = r#"{
+  "users": [
+    {"name": "Ann", "email": "ann@old.com", "note": "ann@old.com"},
+    {"name": "Bob \"B\"", "email": "bob@old.com", "age": 40}
+  ],
+  "admin": {"email": "root@old.com"}
+}
+"#;
+
+fn at(pattern: &str, replacement: &str, pointers: &[&str]) -> Replacer {
+    Replacer::new(pattern, replacement)
+        .unwrap()
+        .pointers(Pointers::parse(pointers).unwrap())
+}
+
+#[test]
+fn replaces_only_the_strings_pointed_at() {
+    let replacer = at("@old.com", "@new.com", &["/users/*/email"]);
+    let (text, count) = replacer.replace(USERS);
+    assert_eq!(
+        text,
+        USERS
+            .replace("\"ann@old.com\", \"note", "\"ann@new.com\", \"note")
+            .replace("bob@old", "bob@new")
+    );
+    assert_eq!(count, 2);
+    assert_eq!(replacer.count(USERS), 2);
+
+    // Keys, numbers, and indices can be named too; a key is never
+    // replaced, nor anything but a string.
+    let replacer = at("^.*$", "x", &["/users/1/*", "/admin", "/users/0/name"]);
+    assert_eq!(
+        replacer.replace(USERS).0,
+        USERS
+            .replace("\"Ann\"", "\"x\"")
+            .replace(r#""Bob \"B\"""#, r#""x""#)
+            .replace(r#""bob@old.com""#, r#""x""#)
+    );
+}
+
+#[test]
+fn escapes_are_read_and_written() {
+    // A string's value is what its escapes say, and what goes back is
+    // escaped only as much as JSON needs.
+    let text = r#"["café 😀", "a\/b", "tab\there"]"#;
+    let replacer = at("é 😀|/|\t", "\"\n", &["/*"]);
+    assert_eq!(
+        replacer.replace(text).0,
+        r#"["caf\"\n", "a\"\nb", "tab\"\nhere"]"#
+    );
+    let changes = replacer.preview(text);
+    assert_eq!(changes.len(), 1);
+    let ranges: Vec<_> = changes[0]
+        .replaced
+        .iter()
+        .map(|r| r.range.clone())
+        .collect();
+    assert_eq!(ranges, [5..12, 17..19, 27..29]);
+
+    // Matches on different lines are different changes.
+    let changes = at("old", "new", &["/users/*/email"]).preview(USERS);
+    let lines: Vec<_> = changes.iter().map(|c| c.line).collect();
+    assert_eq!(lines, [3, 4]);
+}
+
+#[test]
+fn finds_the_strings_or_where_it_isnt_json() {
+    let pointers = Pointers::parse(["/admin/email", "/users/~1"]).unwrap();
+    let found = pointers.find(USERS).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(&USERS[found[0].clone()], "\"root@old.com\"");
+    assert_eq!(
+        pointers.find("{\"admin\": {\"email\": 1,}}"),
+        Err(InvalidJson {
+            problem: "expected a key",
+            line: 1,
+            column: 23,
+        })
+    );
+    assert_eq!(
+        pointers.find("[1]\n]").unwrap_err().to_string(),
+        "expected the end of the text at line 2, column 1"
+    );
+    assert_eq!(
+        Pointers::parse(["users"]),
+        Err(InvalidPointer {
+            pointer: "users".to_string()
+        })
+    );
+    assert!(Pointers::parse(["/a~2"]).is_err());
+
+    // A text that isn't JSON has nothing to replace.
+    assert_eq!(at("a", "b", &["/*"]).replace("[\"a\"").1, 0);
+}
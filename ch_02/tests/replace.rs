@@ -0,0 +1,543 @@
+use std::borrow::Cow;
+
+use std::ops::Range;
+
+use ch_02::{replace, BuildError, Change, Replaced, Replacer, Stats};
+
+#[test]
+fn replaces_every_match() {
+    assert_eq!(
+        replace("world", "Rust", "hello world, world").unwrap(),
+        "hello Rust, Rust"
+    );
+    assert_eq!(replace("x", "y", "no match").unwrap(), "no match");
+}
+
+#[test]
+fn capture_groups() {
+    assert_eq!(
+        replace(r"(\w+)@(\w+)\.com", "$1 at $2", "ferris@rust.com").unwrap(),
+        "ferris at rust"
+    );
+    assert_eq!(
+        replace(r"(?P<y>\d{4})-(?P<m>\d{2})", "$m/$y", "2024-03").unwrap(),
+        "03/2024"
+    );
+}
+
+#[test]
+fn bad_patterns_are_errors() {
+    assert!(replace("(", "x", "text").is_err());
+}
+
+#[test]
+fn replacer_counts_matches() {
+    let replacer = Replacer::new("o", "0").unwrap();
+    let (text, count) = replacer.replace("foo boo");
+    assert_eq!((text.as_ref(), count), ("f00 b00", 4));
+    let (text, count) = replacer.replace("ask Ferris");
+    assert!(matches!(text, Cow::Borrowed(_)));
+    assert_eq!(count, 0);
+}
+
+fn replaced(range: Range<usize>, replacement: &str) -> Replaced {
+    Replaced {
+        range,
+        replacement: replacement.to_string(),
+    }
+}
+
+#[test]
+fn preview_shows_changed_lines() {
+    let replacer = Replacer::new("world", "Rust").unwrap();
+    let text = "hello world\nunchanged\nworld, world\nlast";
+    let changes = replacer.preview(text);
+    assert_eq!(
+        changes,
+        [
+            Change {
+                line: 1,
+                offset: 0,
+                before: "hello world".to_string(),
+                after: "hello Rust".to_string(),
+                replacements: 1,
+                replaced: vec![replaced(6..11, "Rust")],
+            },
+            Change {
+                line: 3,
+                offset: 22,
+                before: "world, world".to_string(),
+                after: "Rust, Rust".to_string(),
+                replacements: 2,
+                replaced: vec![replaced(0..5, "Rust"), replaced(7..12, "Rust")],
+            },
+        ]
+    );
+    assert!(replacer.preview("nothing to see").is_empty());
+}
+
+#[test]
+fn preview_of_matches_across_lines() {
+    // Joining lines shows every line involved, as it is and as it would be.
+    let replacer = Replacer::new(r"-\n", "").unwrap();
+    let changes = replacer.preview("first\nhyphen-\nated word\nlast\n");
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].line, 2);
+    assert_eq!(changes[0].before, "hyphen-\nated word");
+    assert_eq!(changes[0].after, "hyphenated word");
+    assert_eq!(changes[0].replaced, [replaced(6..8, "")]);
+
+    // Capture groups expand as they would in the replacement.
+    let replacer = Replacer::new(r"(?m)^(\w+)", "[$1]").unwrap();
+    let changes = replacer.preview("a b\nc d");
+    let after: Vec<&str> = changes.iter().map(|c| c.after.as_str()).collect();
+    assert_eq!(after, ["[a] b", "[c] d"]);
+}
+
+#[test]
+fn preview_agrees_with_replace() {
+    let text = "one two\nthree\n\nfour five six\n";
+    for (pattern, replacement) in [(r"\w+", "<$0>"), (r"e\n", "E "), (r"(?m)$", ";"), ("o", "")] {
+        let replacer = Replacer::new(pattern, replacement).unwrap();
+        let (replaced, count) = replacer.replace(text);
+        let changes = replacer.preview(text);
+        assert_eq!(changes.iter().map(|c| c.replacements).sum::<usize>(), count);
+        // Splicing each change's lines into the text gives the replaced
+        // text.
+        let mut spliced = text.to_string();
+        for change in changes.iter().rev() {
+            let offset: usize = text
+                .split_inclusive('\n')
+                .take(change.line - 1)
+                .map(str::len)
+                .sum();
+            assert_eq!(change.offset, offset);
+            assert!(text[offset..].starts_with(&change.before));
+            spliced.replace_range(offset..offset + change.before.len(), &change.after);
+            // As does splicing each match's replacement into the lines.
+            let mut after = change.before.clone();
+            for replaced in change.replaced.iter().rev() {
+                after.replace_range(replaced.range.clone(), &replaced.replacement);
+            }
+            assert_eq!(after, change.after);
+        }
+        assert_eq!(spliced, replaced, "{} -> {}", pattern, replacement);
+        assert_eq!(ch_02::apply(text, &changes), replaced);
+    }
+}
+
+#[test]
+fn counting_calls_no_filters() {
+    let replacer = Replacer::new("a", "b")
+        .unwrap()
+        .rule(regex::Regex::new("aa|c").unwrap(), "d")
+        .filter(|_| panic!("a count shouldn't expand a replacement"));
+    // The first rule wins each tie, so "aa" is two matches, not one.
+    assert_eq!(replacer.count("aa c a"), 4);
+    assert_eq!(replacer.count("none"), 0);
+    assert_eq!(Replacer::new("o", "0").unwrap().count("foo bar"), 2);
+}
+
+#[test]
+fn case_modifiers() {
+    let swap = |target, replacement, text| replace(target, replacement, text).unwrap();
+    assert_eq!(
+        swap(r"(\w+) (\w+)", r"\U$1\E $2", "hello world"),
+        "HELLO world"
+    );
+    assert_eq!(swap(r"(\w+)", r"\L$1!", "SHOUT"), "shout!");
+    // Literal text after `\U` is in upper case too, as in sed.
+    assert_eq!(swap(r"key=(\w+)", r"\Ukey=$1", "key=v"), "KEY=V");
+    assert_eq!(swap(r"(\w+)", r"\U$1\L$1", "Mixed"), "MIXEDmixed");
+    // `\\` is one backslash; any other backslash stands for itself.
+    assert_eq!(swap("x", r"\\U\n", "x"), r"\U\n");
+    // With several rules and filters, each rule changes its own case.
+    let replacer = Replacer::new("a+", r"\U$0")
+        .unwrap()
+        .rule(regex::Regex::new("B+").unwrap(), r"\L$0")
+        .filter(|text| format!("[{}]", text));
+    assert_eq!(replacer.replace("aaBB").0, "[AA][bb]");
+    assert_eq!(replacer.preview("aBc")[0].after, "[A][b]c");
+}
+
+#[test]
+fn filters_rework_each_replacement() {
+    let replacer = Replacer::new(r"(\w+)@(\w+)", "$1 at $2")
+        .unwrap()
+        .filter(|text| text.to_uppercase())
+        .filter(|text| format!("<{}>", text));
+    let (text, count) = replacer.replace("me@home, you@work.");
+    assert_eq!(text, "<ME AT HOME>, <YOU AT WORK>.");
+    assert_eq!(count, 2);
+    assert_eq!(replacer.preview("me@home")[0].after, "<ME AT HOME>");
+}
+
+#[test]
+fn compute_works_out_each_replacement() {
+    // In place of the template, from the groups, by name too, and before
+    // the filters.
+    let replacer = Replacer::new(r"(?<n>\d+)\+(\d+)", "ignored")
+        .unwrap()
+        .compute(|regex, captures| {
+            assert_eq!(regex.capture_names().nth(1), Some(Some("n")));
+            let sum: u32 =
+                captures["n"].parse::<u32>().unwrap() + captures[2].parse::<u32>().unwrap();
+            sum.to_string()
+        })
+        .filter(|text| format!("={}", text));
+    let (text, count) = replacer.replace("1+2, 20+22");
+    assert_eq!(text, "=3, =42");
+    assert_eq!(count, 2);
+    assert_eq!(replacer.preview("3+4")[0].after, "=7");
+    // Each rule's own regex.
+    let replacer = Replacer::new("a", "")
+        .unwrap()
+        .rule(regex::Regex::new("(b)").unwrap(), "")
+        .compute(|regex, _| regex.captures_len().to_string());
+    assert_eq!(replacer.replace("ab").0, "12");
+}
+
+#[test]
+fn replacer_from_a_compiled_regex() {
+    let regex = regex::RegexBuilder::new("rust")
+        .case_insensitive(true)
+        .build()
+        .unwrap();
+    let replacer = Replacer::from_regex(regex, "Rust");
+    let (text, count) = replacer.replace("rust, RUST, and Rust");
+    assert_eq!(text, "Rust, Rust, and Rust");
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn replacer_from_a_builder() {
+    let replacer = Replacer::builder()
+        .pattern("rust")
+        .replacement("Rust")
+        .case_insensitive(true)
+        .build()
+        .unwrap();
+    assert_eq!(
+        replacer.replace("rust, RUST, and Rust").0,
+        "Rust, Rust, and Rust"
+    );
+
+    // Each pattern is a rule, in order, and all the flags hold for each.
+    let replacer = Replacer::builder()
+        .pattern("^a.")
+        .replacement("[$0]")
+        .pattern("b$")
+        .multi_line(true)
+        .dot_matches_new_line(true)
+        .build()
+        .unwrap();
+    assert_eq!(replacer.replace("a\nb\nab").0, "[a\n]\n[ab]");
+
+    // Plain text, but line patterns are still regexes.
+    let replacer = Replacer::builder()
+        .pattern("a.b($1)")
+        .replacement(r"$1\U")
+        .fixed_strings(true)
+        .skip_lines_matching("^#")
+        .limit(1)
+        .build()
+        .unwrap();
+    assert_eq!(
+        replacer.replace("# a.b($1)\na.b($1) a.b($1)").0,
+        "# a.b($1)\n$1\\U a.b($1)"
+    );
+}
+
+#[test]
+fn size_limits_from_a_builder() {
+    // Every Unicode word character, a hundred times over, is too big for
+    // a small limit, and not for the regex crate's own.
+    let builder = Replacer::builder().pattern(r"\w{100}").replacement("x");
+    match builder.clone().size_limit(64 << 10).build() {
+        Err(BuildError::Pattern {
+            index: 0,
+            error: regex::Error::CompiledTooBig(limit),
+        }) => assert_eq!(limit, 64 << 10),
+        other => panic!("expected a pattern too big, got {:?}", other),
+    }
+    assert!(builder.clone().build().is_ok());
+
+    // The limits hold for line patterns too, and a small DFA cache only
+    // makes a search slower.
+    assert!(matches!(
+        Replacer::builder()
+            .pattern("a")
+            .skip_lines_matching(r"\w{100}")
+            .size_limit(64 << 10)
+            .build(),
+        Err(BuildError::LinePattern { .. })
+    ));
+    let replacer = builder.dfa_size_limit(1).build().unwrap();
+    assert_eq!(replacer.replace(&"é".repeat(100)).0, "x");
+}
+
+#[test]
+fn smart_case_from_a_builder() {
+    let replace = |pattern: &str, text: &str| {
+        let builder = Replacer::builder().pattern(pattern).replacement("_");
+        builder
+            .smart_case(true)
+            .build()
+            .unwrap()
+            .replace(text)
+            .0
+            .into_owned()
+    };
+    // Only a letter the pattern would match, as it's written or escaped,
+    // or in a class, makes the case count.
+    assert_eq!(replace("color", "Color COLOR color"), "_ _ _");
+    assert_eq!(replace(r"\Wcolor\b", "a Color"), "a_");
+    assert_eq!(replace(r"\p{Greek}a", "ΩA"), "_");
+    assert_eq!(replace("Color", "Color COLOR color"), "_ COLOR color");
+    assert_eq!(replace(r"\x43olor", "Color color"), "_ color");
+    assert_eq!(replace("[A-C]x", "ax Bx"), "ax _");
+    assert_eq!(replace(r"(?<Name>x)", "x X"), "_ _");
+    // Nothing literal keeps its case.
+    assert_eq!(replace(r"\p{Lu}", "aB"), "a_");
+
+    // Line patterns are each looked at on their own, and fixed strings
+    // too.
+    let replacer = Replacer::builder()
+        .pattern("A.B")
+        .replacement("_")
+        .only_lines_matching("keep")
+        .fixed_strings(true)
+        .smart_case(true)
+        .build()
+        .unwrap();
+    assert_eq!(
+        replacer.replace("A.B KEEP\nA.B\nAxB keep").0,
+        "_ KEEP\nA.B\nAxB keep"
+    );
+}
+
+#[test]
+fn whole_words_from_a_builder() {
+    let replacer = Replacer::builder()
+        .pattern(r"foo|-x|x!")
+        .replacement("<$0>")
+        .whole_words(true)
+        .build()
+        .unwrap();
+    // A pattern starting or ending with punctuation still matches after
+    // or before a space, but not inside a word.
+    assert_eq!(
+        replacer.replace("foo foobar _foo a-x -x x! x!y (x!)").0,
+        "<foo> foobar _foo a-x <-x> <x!> x!y (<x!>)"
+    );
+    let replacer = Replacer::builder()
+        .pattern("a.b")
+        .replacement("X")
+        .fixed_strings(true)
+        .whole_words(true)
+        .build()
+        .unwrap();
+    assert_eq!(replacer.replace("a.b a.bc axb").0, "X a.bc axb");
+}
+
+#[test]
+fn a_builder_says_which_pattern_is_bad() {
+    let built = Replacer::builder()
+        .pattern("a")
+        .pattern("(")
+        .only_lines_matching("[")
+        .build();
+    assert!(matches!(built, Err(BuildError::Pattern { index: 1, .. })));
+    let built = Replacer::builder()
+        .pattern("a")
+        .only_lines_matching("[")
+        .build();
+    assert!(matches!(built, Err(BuildError::LinePattern { pattern, .. }) if pattern == "["));
+    assert!(matches!(
+        Replacer::builder().build(),
+        Err(BuildError::NoPattern)
+    ));
+}
+
+#[test]
+fn a_builder_checks_the_groups_a_replacement_refers_to() {
+    let group = |pattern: &str, replacement: &str| match Replacer::builder()
+        .pattern(pattern)
+        .replacement(replacement)
+        .build()
+    {
+        Err(BuildError::Group { index: 0, group }) => Some(group),
+        Err(error) => panic!("{}", error),
+        Ok(_) => None,
+    };
+    assert_eq!(group(r"(?P<y>\d+)-(\d+)", "$0 ${1} $2 $y ${y} $$3"), None);
+    assert_eq!(group(r"(\d+)", "$2"), Some("2".to_string()));
+    assert_eq!(group(r"(\d+)", "${year}"), Some("year".to_string()));
+    // `$1st` is a group named `1st`, and `\U` doesn't hide one.
+    assert_eq!(group(r"(\d+)", "$1st"), Some("1st".to_string()));
+    assert_eq!(group(r"(\d+)", r"\U$x\E"), Some("x".to_string()));
+    // A literal replacement refers to nothing.
+    let built = Replacer::builder()
+        .pattern("a")
+        .replacement("$1")
+        .fixed_strings(true)
+        .build();
+    assert!(built.is_ok());
+    let built = Replacer::builder()
+        .pattern("(a)")
+        .replacement("$1")
+        .pattern("b")
+        .replacement("$1")
+        .build();
+    assert!(matches!(built, Err(BuildError::Group { index: 1, .. })));
+}
+
+#[test]
+fn counts_every_planted_match() {
+    // Regex metacharacters in the needle, escaped, match only the needle.
+    let planted = fixtures::planted(64 * 1024, "a.b(c)", 25, 4);
+    let replacer = Replacer::new(&regex::escape("a.b(c)"), "x").unwrap();
+    let (text, count) = replacer.replace(&planted.text);
+    assert_eq!(count, planted.count);
+    assert!(!text.contains("a.b(c)"));
+    assert_eq!(text.len(), planted.text.len() - 5 * planted.count);
+}
+
+#[test]
+fn rules_apply_in_one_pass() {
+    let rule = |pattern| regex::Regex::new(pattern).unwrap();
+    // A replacement isn't searched again, so two words can swap.
+    let replacer = Replacer::new("cat", "dog")
+        .unwrap()
+        .rule(rule("dog"), "cat");
+    let (text, count) = replacer.replace("cat chases dog");
+    assert_eq!((text.as_ref(), count), ("dog chases cat", 2));
+
+    // The earliest match wins; on a tie, the rule added first.
+    let replacer = Replacer::new("ab", "1")
+        .unwrap()
+        .rule(rule("abc"), "2")
+        .rule(rule(r"b(c)"), "<$1>");
+    assert_eq!(replacer.replace("abc xbc").0, "1c x<c>");
+    assert_eq!(
+        replacer.preview("abc\nxbc")[1],
+        Change {
+            line: 2,
+            offset: 4,
+            before: "xbc".to_string(),
+            after: "x<c>".to_string(),
+            replacements: 1,
+            replaced: vec![replaced(1..3, "<c>")],
+        }
+    );
+    let (text, count) = replacer.replace("none here");
+    assert!(matches!(text, Cow::Borrowed(_)));
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn rules_match_as_the_regex_crate_does() {
+    // With a rule that never matches, every match comes from the first,
+    // found the way several rules' are, and must agree with `replace_all`,
+    // empty matches included.
+    let never = regex::Regex::new("[^\\s\\S]").unwrap();
+    for (pattern, text) in [
+        ("a*", "baaab"),
+        (r"\b", "two words"),
+        ("x?", "éxé"),
+        ("(?m)^", "one\ntwo\n"),
+        ("", ""),
+    ] {
+        let regex = regex::Regex::new(pattern).unwrap();
+        let expected = regex.replace_all(text, "[$0]");
+        let replacer = Replacer::new(pattern, "[$0]")
+            .unwrap()
+            .rule(never.clone(), "");
+        assert_eq!(replacer.replace(text).0, expected, "{:?}", pattern);
+    }
+}
+
+#[test]
+fn a_limit_replaces_only_the_first_matches() {
+    let text = "x x x x";
+    let replacer = Replacer::new("x", "y").unwrap().limit(2);
+    assert_eq!(replacer.replace(text), (Cow::from("y y x x"), 2));
+    assert_eq!(replacer.count(text), 2);
+    assert_eq!(replacer.preview(text)[0].after, "y y x x");
+    // The same with the slower path, through a filter and a second rule.
+    let replacer = Replacer::new("x", "y")
+        .unwrap()
+        .rule(regex::Regex::new("z").unwrap(), "w")
+        .filter(|s| s.to_uppercase())
+        .limit(3);
+    assert_eq!(replacer.replace("z x x x").0, "W Y Y x");
+    assert_eq!(replacer.count("z x x x"), 3);
+    // A limit past the matches there are changes nothing.
+    let replacer = Replacer::new("x", "y").unwrap().limit(10);
+    assert_eq!(replacer.replace(text).1, 4);
+}
+
+#[test]
+fn guards_pick_the_lines_to_replace_on() {
+    let text = "let x = 1; // x\nfn x() {}\n// x\n";
+    let comment = regex::Regex::new("//").unwrap();
+    let replacer = Replacer::new("x", "y")
+        .unwrap()
+        .only_lines_matching(comment.clone());
+    assert_eq!(
+        replacer.replace(text),
+        (Cow::from("let y = 1; // y\nfn x() {}\n// y\n"), 3)
+    );
+    assert_eq!(replacer.count(text), 3);
+    let replacer = Replacer::new("x", "y")
+        .unwrap()
+        .skip_lines_matching(comment.clone());
+    assert_eq!(
+        replacer.replace(text).0,
+        "let x = 1; // x\nfn y() {}\n// x\n"
+    );
+    // Both at once, with a limit counted after the guards.
+    let replacer = Replacer::new("x", "y")
+        .unwrap()
+        .only_lines_matching(comment)
+        .skip_lines_matching(regex::Regex::new("let").unwrap())
+        .limit(1);
+    assert_eq!(
+        replacer.replace(text).0,
+        "let x = 1; // x\nfn x() {}\n// y\n"
+    );
+    // A match is on the line it starts on, even if it goes on past it.
+    let replacer = Replacer::new(r"\d\n", "")
+        .unwrap()
+        .only_lines_matching(regex::Regex::new("^b").unwrap());
+    assert_eq!(replacer.replace("a1\nb2\nc3\n").0, "a1\nbc3\n");
+}
+
+#[test]
+fn stats_say_what_a_replacement_did() {
+    let replacer = Replacer::new(r"colou?r", "color").unwrap();
+    let text = "color, colour, and more colour";
+    let (replaced, stats) = replacer.replace_with_stats(text);
+    assert_eq!(replaced, replacer.replace(text).0);
+    // The first match is replaced with itself, which changes nothing.
+    assert_eq!(
+        stats,
+        Stats {
+            matches: 3,
+            replacements: 2,
+            removed: 12,
+            added: 10,
+        }
+    );
+    // A preview's changes say the same.
+    assert_eq!(Stats::of(&replacer.preview(text)), stats);
+
+    let mut total = stats;
+    total += replacer.replace_with_stats("no match here").1;
+    total += replacer.replace_with_stats("colour").1;
+    assert_eq!((total.matches, total.replacements), (4, 3));
+    assert_eq!(
+        replacer.replace_with_stats("nothing"),
+        (Cow::Borrowed("nothing"), Stats::default())
+    );
+}
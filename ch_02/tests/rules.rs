@@ -0,0 +1,46 @@
+use std::fs;
+
+use ch_02::quickreplace::CATALOGS;
+use ch_02::rules::{self, RulesFile};
+use common::{exit, CliError, Messages};
+
+#[test]
+fn rules_are_read_in_order() {
+    let rules = RulesFile::parse(
+        "[[rule]]\npattern = 'colou?r'\nreplacement = 'hue'\n\n\
+         [[rule]]\npattern = '(\\w+)@(\\w+)'\nreplacement = '$1 at $2'\n",
+    )
+    .unwrap();
+    let rules: Vec<_> = rules
+        .rule
+        .iter()
+        .map(|rule| (rule.pattern.as_str(), rule.replacement.as_str()))
+        .collect();
+    assert_eq!(rules, [("colou?r", "hue"), (r"(\w+)@(\w+)", "$1 at $2")]);
+
+    // No rules at all is rules, for the parser; `load` is what minds.
+    assert!(RulesFile::parse("").unwrap().rule.is_empty());
+    assert!(RulesFile::parse("[[rule]]\npattern = 'a'\n").is_err());
+    assert!(RulesFile::parse("[[rule]]\npattern = 'a'\nreplacement = 'b'\nflags = 'i'\n").is_err());
+    assert!(RulesFile::parse("[[rule]\n").is_err());
+}
+
+#[test]
+fn a_file_without_rules_is_a_configuration_error() {
+    let messages = Messages::new(CATALOGS, Some("en"));
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("rules.toml");
+    let file = file.to_str().unwrap();
+
+    fs::write(file, "# nothing yet\n").unwrap();
+    let error = rules::load(file, &messages).unwrap_err();
+    assert_eq!(error.exit_code(), exit::CONFIG);
+    assert!(error.to_string().contains("has no [[rule]]s"), "{}", error);
+
+    fs::write(file, "[[rule]]\npattern = 'a'\n").unwrap();
+    let error = rules::load(file, &messages).unwrap_err();
+    assert_eq!(error.exit_code(), exit::CONFIG);
+
+    fs::write(file, "[[rule]]\npattern = 'a'\nreplacement = 'b'\n").unwrap();
+    assert_eq!(rules::load(file, &messages).unwrap().rule.len(), 1);
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ch_02::walk::{Glob, Walk};
+
+fn glob(pattern: &str) -> Glob {
+    Glob::new(pattern).unwrap()
+}
+
+#[test]
+fn globs_match_file_names() {
+    let rs = glob("*.rs");
+    assert!(rs.matches(Path::new("main.rs")));
+    assert!(rs.matches(Path::new("src/deep/lib.rs")));
+    assert!(!rs.matches(Path::new("main.rs.bak")));
+    assert!(!rs.matches(Path::new("rs")));
+
+    assert!(glob("?.txt").matches(Path::new("a.txt")));
+    assert!(!glob("?.txt").matches(Path::new("ab.txt")));
+    assert!(glob("[abc].md").matches(Path::new("b.md")));
+    assert!(!glob("[!abc].md").matches(Path::new("b.md")));
+    assert!(glob("[a-c]*").matches(Path::new("cargo")));
+    // Regex syntax means nothing special.
+    assert!(glob("a+b.(x)").matches(Path::new("a+b.(x)")));
+    assert!(!glob("a+b").matches(Path::new("aab")));
+}
+
+#[test]
+fn globs_with_slashes_match_paths() {
+    let src = glob("src/*.rs");
+    assert!(src.matches(Path::new("src/main.rs")));
+    assert!(!src.matches(Path::new("src/bin/tool.rs")));
+    assert!(!src.matches(Path::new("main.rs")));
+    let deep = glob("src/**/*.rs");
+    assert!(deep.matches(Path::new("src/main.rs")));
+    assert!(deep.matches(Path::new("src/bin/tool.rs")));
+    assert!(!deep.matches(Path::new("tests/main.rs")));
+}
+
+#[test]
+fn bad_globs() {
+    let error = Glob::new("[abc").unwrap_err();
+    assert_eq!(error.to_string(), "invalid glob '[abc': unclosed [");
+}
+
+fn tree() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for file in [
+        "b.rs",
+        "a.txt",
+        "src/main.rs",
+        "src/bin/tool.rs",
+        ".git/config.rs",
+        "src/.hidden.rs",
+    ] {
+        let path = dir.path().join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "text").unwrap();
+    }
+    dir
+}
+
+fn relative(dir: &Path, walk: Walk) -> Vec<PathBuf> {
+    walk.map(|path| path.unwrap().strip_prefix(dir).unwrap().to_path_buf())
+        .collect()
+}
+
+#[test]
+fn walks_in_order_skipping_hidden_files() {
+    let dir = tree();
+    assert_eq!(
+        relative(dir.path(), Walk::new(dir.path())),
+        ["a.txt", "b.rs", "src/bin/tool.rs", "src/main.rs"].map(PathBuf::from)
+    );
+}
+
+#[test]
+fn walks_only_matching_files() {
+    let dir = tree();
+    let walk = Walk::new(dir.path()).glob(glob("*.rs"));
+    assert_eq!(
+        relative(dir.path(), walk),
+        ["b.rs", "src/bin/tool.rs", "src/main.rs"].map(PathBuf::from)
+    );
+    let walk = Walk::new(dir.path())
+        .glob(glob("src/*.rs"))
+        .glob(glob("*.txt"));
+    assert_eq!(
+        relative(dir.path(), walk),
+        ["a.txt", "src/main.rs"].map(PathBuf::from)
+    );
+}
+
+#[test]
+fn skips_excluded_files_and_directories() {
+    let dir = tree();
+    let walk = Walk::new(dir.path())
+        .exclude(glob("bin"))
+        .exclude(glob("*.txt"));
+    assert_eq!(
+        relative(dir.path(), walk),
+        ["b.rs", "src/main.rs"].map(PathBuf::from)
+    );
+    // Exclusion wins over a glob that matches.
+    let walk = Walk::new(dir.path())
+        .glob(glob("*.rs"))
+        .exclude(glob("src/main.rs"));
+    assert_eq!(
+        relative(dir.path(), walk),
+        ["b.rs", "src/bin/tool.rs"].map(PathBuf::from)
+    );
+}
+
+#[test]
+fn a_missing_directory_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing");
+    let results: Vec<_> = Walk::new(&missing).collect();
+    assert_eq!(results.len(), 1);
+    let error = results[0].as_ref().unwrap_err();
+    assert_eq!(error.path, missing);
+}
+
+#[cfg(unix)]
+#[test]
+fn symbolic_links_are_not_followed() {
+    let dir = tree();
+    std::os::unix::fs::symlink(dir.path().join("src"), dir.path().join("link")).unwrap();
+    std::os::unix::fs::symlink(dir.path().join("b.rs"), dir.path().join("c.rs")).unwrap();
+    assert_eq!(Walk::new(dir.path()).count(), 4);
+}
+
+#[test]
+fn skips_what_ignore_files_say_to() {
+    let dir = tree();
+    for file in [
+        "target/debug/out.rs",
+        "src/gen.rs",
+        "src/bin/keep.log",
+        "x.log",
+    ] {
+        let path = dir.path().join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "text").unwrap();
+    }
+    fs::write(
+        dir.path().join(".gitignore"),
+        "# build output\ntarget/\n*.log\n",
+    )
+    .unwrap();
+    // Anchored to `src`, and taking back one of the logs.
+    fs::write(dir.path().join("src/.gitignore"), "/gen.rs\n!keep.log\n").unwrap();
+    fs::write(dir.path().join(".ignore"), "a.txt\n").unwrap();
+    assert_eq!(
+        relative(dir.path(), Walk::new(dir.path())),
+        ["b.rs", "src/bin/keep.log", "src/bin/tool.rs", "src/main.rs"].map(PathBuf::from)
+    );
+    assert_eq!(
+        relative(dir.path(), Walk::new(dir.path()).ignore_files(false)).len(),
+        8
+    );
+}
+
+#[test]
+fn reads_ignore_files_up_to_the_repository() {
+    let dir = tree();
+    let src = dir.path().join("src");
+    fs::write(src.join("lib.rs"), "text").unwrap();
+    fs::create_dir_all(dir.path().join(".git/info")).unwrap();
+    fs::write(dir.path().join(".git/info/exclude"), "tool.rs\n").unwrap();
+    // Anchored to the top of the repository, not of the walk.
+    fs::write(dir.path().join(".gitignore"), "/src/main.rs\n/lib.rs\n").unwrap();
+    assert_eq!(relative(&src, Walk::new(&src)), [PathBuf::from("lib.rs")]);
+    assert_eq!(relative(&src, Walk::new(&src).ignore_files(false)).len(), 3);
+    // Outside a repository, the directories above aren't looked at.
+    fs::remove_dir_all(dir.path().join(".git")).unwrap();
+    assert_eq!(relative(&src, Walk::new(&src)).len(), 3);
+}
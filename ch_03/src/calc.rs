@@ -0,0 +1,214 @@
+use std::fmt;
+
+use pest::error::{Error, ErrorVariant, LineColLocation};
+use pest::iterators::Pairs;
+use pest::pratt_parser::{Assoc, Op, PrattParser};
+use pest::Parser;
+use pest_derive::Parser;
+
+#[derive(Parser)]
+#[grammar = "arithmetic.pest"]
+struct ArithmeticParser;
+
+/// How deeply an expression may nest. Parsing and evaluating both recurse
+/// once per parenthesis, unary minus, and `^` in a chain, so without a
+/// limit a long enough input overflows the stack.
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    /// The input isn't an expression. `expected` describes what would
+    /// have made sense at `column`, counting from 1.
+    Syntax {
+        column: usize,
+        expected: String,
+    },
+    /// Nesting deeper than `MAX_DEPTH`, first reached at `column`.
+    TooDeep {
+        column: usize,
+    },
+    UnknownConstant(String),
+    UnknownFunction(String),
+    DivisionByZero,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Syntax { column, expected } => {
+                write!(f, "expected {} at column {}", expected, column)
+            }
+            EvalError::TooDeep { column } => {
+                write!(
+                    f,
+                    "nesting deeper than {} levels at column {}",
+                    MAX_DEPTH, column
+                )
+            }
+            EvalError::UnknownConstant(name) => write!(f, "unknown constant '{}'", name),
+            EvalError::UnknownFunction(name) => write!(f, "unknown function '{}'", name),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Evaluate an arithmetic expression: numbers, `+ - * / % ^`, parentheses,
+/// the constants `pi` and `e`, and functions of one argument like
+/// `sqrt(2)`. `^` binds tightest and groups to the right, so `-2 ^ 2` is
+/// -4 and `2 ^ 3 ^ 2` is 512.
+///
+/// ```
+/// assert_eq!(ch_03::eval("1 + 2 * 3"), Ok(7.0));
+/// ```
+pub fn eval(input: &str) -> Result<f64, EvalError> {
+    check_depth(input)?;
+    let mut pairs = ArithmeticParser::parse(Rule::calculation, input).map_err(syntax_error)?;
+    let expr = pairs.next().unwrap();
+    evaluate(expr.into_inner(), &pratt())
+}
+
+/// Reject input that nests deeper than `MAX_DEPTH`, before pest gets a
+/// chance to recurse through it. This looks only at characters, so it
+/// also counts nesting in input that turns out not to parse; that input
+/// would be rejected anyway.
+fn check_depth(input: &str) -> Result<(), EvalError> {
+    // The depths in force at each open parenthesis, to return to at its
+    // close.
+    let mut opened: Vec<(usize, usize)> = Vec::new();
+    // The depth of the innermost group, which binary operators other
+    // than `^` return to: the Pratt parser loops over them rather than
+    // recursing.
+    let mut base = 0;
+    let mut depth = 0;
+    let mut after_operand = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => {
+                opened.push((base, depth));
+                depth += 1;
+                base = depth;
+                after_operand = false;
+            }
+            ')' => {
+                (base, depth) = opened.pop().unwrap_or((0, 0));
+                after_operand = true;
+            }
+            '^' => {
+                depth += 1;
+                after_operand = false;
+            }
+            '-' if !after_operand => depth += 1,
+            '+' | '-' | '*' | '/' | '%' => {
+                depth = base;
+                after_operand = false;
+            }
+            c if c.is_whitespace() => {}
+            _ => after_operand = true,
+        }
+        if depth > MAX_DEPTH {
+            return Err(EvalError::TooDeep {
+                column: input[..i].chars().count() + 1,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Describe what pest expected in a calculator user's terms, rather than
+/// the grammar's: "a value", not "number, name, or neg".
+fn syntax_error(e: Error<Rule>) -> EvalError {
+    let column = match e.line_col {
+        LineColLocation::Pos((_, column)) | LineColLocation::Span((_, column), _) => column,
+    };
+    let expected = match e.variant {
+        ErrorVariant::ParsingError { positives, .. } => {
+            let mut names: Vec<&str> = Vec::new();
+            for rule in positives {
+                let name = match rule {
+                    Rule::EOI => "the end",
+                    Rule::close => "')'",
+                    Rule::add | Rule::sub | Rule::mul | Rule::div | Rule::rem | Rule::pow => {
+                        "an operator"
+                    }
+                    _ => "a value",
+                };
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            names.join(" or ")
+        }
+        ErrorVariant::CustomError { message } => message,
+    };
+    EvalError::Syntax { column, expected }
+}
+
+fn pratt() -> PrattParser<Rule> {
+    // Each `op` call binds tighter than the ones before it.
+    PrattParser::new()
+        .op(Op::infix(Rule::add, Assoc::Left) | Op::infix(Rule::sub, Assoc::Left))
+        .op(Op::infix(Rule::mul, Assoc::Left)
+            | Op::infix(Rule::div, Assoc::Left)
+            | Op::infix(Rule::rem, Assoc::Left))
+        .op(Op::prefix(Rule::neg))
+        .op(Op::infix(Rule::pow, Assoc::Right))
+}
+
+fn evaluate(pairs: Pairs<Rule>, pratt: &PrattParser<Rule>) -> Result<f64, EvalError> {
+    pratt
+        .map_primary(|primary| match primary.as_rule() {
+            Rule::number => Ok(primary.as_str().parse().unwrap()),
+            Rule::constant => constant(primary.as_str()),
+            Rule::call => {
+                let mut inner = primary.into_inner();
+                let name = inner.next().unwrap().as_str();
+                let argument = evaluate(inner.next().unwrap().into_inner(), pratt)?;
+                call(name, argument)
+            }
+            Rule::group => evaluate(primary.into_inner().next().unwrap().into_inner(), pratt),
+            rule => unreachable!("{:?} isn't an operand", rule),
+        })
+        .map_prefix(|_neg, operand| Ok(-operand?))
+        .map_infix(|left, op, right| {
+            let (left, right) = (left?, right?);
+            match op.as_rule() {
+                Rule::add => Ok(left + right),
+                Rule::sub => Ok(left - right),
+                Rule::mul => Ok(left * right),
+                Rule::div | Rule::rem if right == 0.0 => Err(EvalError::DivisionByZero),
+                Rule::div => Ok(left / right),
+                Rule::rem => Ok(left % right),
+                Rule::pow => Ok(left.powf(right)),
+                rule => unreachable!("{:?} isn't an operator", rule),
+            }
+        })
+        .parse(pairs)
+}
+
+fn constant(name: &str) -> Result<f64, EvalError> {
+    match name {
+        "pi" => Ok(std::f64::consts::PI),
+        "e" => Ok(std::f64::consts::E),
+        _ => Err(EvalError::UnknownConstant(name.to_string())),
+    }
+}
+
+fn call(name: &str, x: f64) -> Result<f64, EvalError> {
+    let f: fn(f64) -> f64 = match name {
+        "sqrt" => f64::sqrt,
+        "abs" => f64::abs,
+        "exp" => f64::exp,
+        "ln" => f64::ln,
+        "log10" => f64::log10,
+        "sin" => f64::sin,
+        "cos" => f64::cos,
+        "tan" => f64::tan,
+        "floor" => f64::floor,
+        "ceil" => f64::ceil,
+        "round" => f64::round,
+        _ => return Err(EvalError::UnknownFunction(name.to_string())),
+    };
+    Ok(f(x))
+}
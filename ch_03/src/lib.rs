@@ -0,0 +1,17 @@
+// The library half of this chapter: an arithmetic evaluator built on the
+// `pest` grammar from the Readme, and the file reading `main` does.
+//
+// Reading files is left out of WebAssembly builds, which have no file
+// system to read from, so that the evaluator can run in a browser.
+
+mod calc;
+
+pub use calc::{eval, EvalError, MAX_DEPTH};
+
+pub type Result<T> = std::result::Result<T, String>;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_file(file_name: &str) -> Result<String> {
+    let contents = std::fs::read_to_string(file_name).map_err(|e| e.to_string())?;
+    Ok(contents)
+}
@@ -1,11 +1,34 @@
-type Result<T> = std::result::Result<T, String>;
+use std::process::ExitCode;
 
-fn read_file(file_name: &str) -> Result<String> {
-    let contents = std::fs::read_to_string(file_name).map_err(|e| e.to_string())?;
-    Ok(contents)
+use ch_03::{eval, read_file};
+use common::{Context, Error};
+use tracing::debug;
+
+/// `ch_03 [FILE]` prints FILE (by default, file.txt); `ch_03 -e EXPR`
+/// evaluates EXPR with the chapter's calculator.
+fn ch_03() -> Result<(), Error> {
+    let args = common::logging::args()?;
+    match args.as_slice() {
+        [flag, expr] if flag == "-e" => {
+            debug!(%expr, "evaluating");
+            let value = eval(expr).with_context(|| format!("can't evaluate {:?}", expr))?;
+            println!("{}", value);
+        }
+        [] | [_] => {
+            let file_name = args.first().map_or("file.txt", String::as_str);
+            debug!(file = %file_name, "reading");
+            let contents = read_file(file_name).map_err(Error::new)?;
+            println!("File contents: {}", contents);
+        }
+        _ => {
+            eprintln!("Usage: ch_03 [FILE]");
+            eprintln!("       ch_03 -e EXPRESSION");
+            return Err(Error::usage("too many arguments"));
+        }
+    }
+    Ok(())
 }
 
-fn main() {
-    let contents = read_file("file.txt").unwrap();
-    println!("File contents: {}", contents);
+fn main() -> ExitCode {
+    common::run(ch_03)
 }
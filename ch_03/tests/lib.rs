@@ -0,0 +1,50 @@
+use std::fs;
+
+use ch_03::{eval, read_file, EvalError, MAX_DEPTH};
+
+#[test]
+fn reads_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("file.txt");
+    fs::write(&path, "contents\n").unwrap();
+    assert_eq!(read_file(path.to_str().unwrap()).unwrap(), "contents\n");
+
+    let missing = dir.path().join("missing.txt");
+    let e = read_file(missing.to_str().unwrap()).unwrap_err();
+    assert!(e.contains("No such file"), "{}", e);
+}
+
+#[test]
+fn evaluates() {
+    assert_eq!(eval("1 + 2 * 3"), Ok(7.0));
+    assert_eq!(eval("2 ^ 3 ^ 2"), Ok(512.0));
+    assert_eq!(eval("1 / 0"), Err(EvalError::DivisionByZero));
+    assert!(matches!(
+        eval("2 +"),
+        Err(EvalError::Syntax { column: 4, .. })
+    ));
+}
+
+#[test]
+fn limits_nesting() {
+    // Found by the calculator fuzz target: each of these used to overflow
+    // the stack.
+    let deep = [
+        format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000)),
+        format!("{}1", "-".repeat(10_000)),
+        format!("{}1", "2 ^ ".repeat(10_000)),
+        format!("{}1{}", "sqrt(".repeat(10_000), ")".repeat(10_000)),
+    ];
+    for input in &deep {
+        assert!(matches!(eval(input), Err(EvalError::TooDeep { .. })));
+    }
+
+    let just_deep_enough = format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+    assert_eq!(eval(&just_deep_enough), Ok(1.0));
+    let too_deep = format!("({}", just_deep_enough);
+    assert_eq!(eval(&too_deep), Err(EvalError::TooDeep { column: 129 }));
+
+    // Long expressions that don't nest are fine.
+    assert_eq!(eval(&format!("{}0", "1 - 1 + ".repeat(10_000))), Ok(0.0));
+    assert_eq!(eval(&format!("{}1", "2 ^ 0 * ".repeat(10_000))), Ok(1.0));
+}
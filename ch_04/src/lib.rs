@@ -0,0 +1,32 @@
+// The library half of this chapter: shared ownership with `Arc`, and a
+// `Mutex` so that several threads can change what they share.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Start at `start`, and add each of `increments` on a thread of its own,
+/// all sharing one `Arc<Mutex<i64>>`. Returns the final value, and the
+/// `Arc`'s reference count once the threads are done: 1, since each
+/// thread's clone is dropped when it finishes.
+pub fn add_concurrently(start: i64, increments: &[i64]) -> (i64, usize) {
+    let shared_data = Arc::new(Mutex::new(start));
+
+    let threads: Vec<_> = increments
+        .iter()
+        .map(|&increment| {
+            // Each thread gets its own clone of the `Arc`, which it moves.
+            let shared_data = Arc::clone(&shared_data);
+            thread::spawn(move || {
+                let mut data = shared_data.lock().unwrap();
+                *data += increment;
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let count = Arc::strong_count(&shared_data);
+    let value = *shared_data.lock().unwrap();
+    (value, count)
+}
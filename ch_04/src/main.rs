@@ -1,33 +1,19 @@
-use std::sync::{Arc, Mutex};
+use std::process::ExitCode;
 
-fn main() {
-    // Creating shared data wrapped in an Arc and a Mutex
-    let shared_data = Arc::new(Mutex::new(0));
+use ch_04::add_concurrently;
+use common::Error;
+use tracing::debug;
 
-    // Cloning Arc to create multiple references for different threads
-    let shared_data_clone1 = Arc::clone(&shared_data);
-    let shared_data_clone2 = Arc::clone(&shared_data);
-
-    // Spawn threads to concurrently modify the shared data
-    let thread1 = std::thread::spawn(move || {
-        let mut data = shared_data_clone1.lock().unwrap();
-        *data += 1; // Modifying the shared data
-    });
-
-    let thread2 = std::thread::spawn(move || {
-        let mut data = shared_data_clone2.lock().unwrap();
-        *data += 2; // Modifying the shared data
-    });
-
-    // Waiting for threads to complete execution
-    thread1.join().unwrap();
-    thread2.join().unwrap();
-
-    // Accessing the modified shared data
-    let final_data = shared_data.lock().unwrap();
-    println!("Final value: {}", *final_data);
-
-    // Get the current reference count
-    let count = Arc::strong_count(&shared_data);
+fn shared() -> Result<(), Error> {
+    common::logging::args()?;
+    // Two threads add 1 and 2 to shared data that starts at 0.
+    debug!(start = 0, increments = ?[1, 2], "adding on two threads");
+    let (final_data, count) = add_concurrently(0, &[1, 2]);
+    println!("Final value: {}", final_data);
     println!("Reference count of shared_data: {}", count);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    common::run(shared)
 }
@@ -0,0 +1,17 @@
+use ch_04::add_concurrently;
+
+#[test]
+fn two_threads() {
+    assert_eq!(add_concurrently(0, &[1, 2]), (3, 1));
+}
+
+#[test]
+fn no_updates_are_lost() {
+    let increments: Vec<i64> = (1..=100).collect();
+    assert_eq!(add_concurrently(10, &increments), (5060, 1));
+}
+
+#[test]
+fn no_threads() {
+    assert_eq!(add_concurrently(7, &[]), (7, 1));
+}
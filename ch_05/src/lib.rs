@@ -0,0 +1,51 @@
+// The library half of this chapter: the `StringTable` from the Readme's
+// section on returning references, grown into something an autocomplete
+// box can use.
+
+/// A list of strings to search by prefix. Every string it hands back is
+/// borrowed from the table, so the table must outlive the results.
+#[derive(Clone, Debug, Default)]
+pub struct StringTable {
+    pub elements: Vec<String>,
+}
+
+impl StringTable {
+    pub fn new() -> StringTable {
+        StringTable::default()
+    }
+
+    pub fn push(&mut self, s: impl Into<String>) {
+        self.elements.push(s.into());
+    }
+
+    /// The first string starting with `prefix`, if any.
+    pub fn find_by_prefix(&self, prefix: &str) -> Option<&String> {
+        self.elements.iter().find(|s| s.starts_with(prefix))
+    }
+
+    /// Every string starting with `prefix`, in the table's order. The
+    /// iterator borrows both the table and `prefix`, so it carries both
+    /// lifetimes.
+    pub fn completions<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.elements
+            .iter()
+            .map(String::as_str)
+            .filter(move |s| s.starts_with(prefix))
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for StringTable {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> StringTable {
+        StringTable {
+            elements: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -1,25 +1,16 @@
-struct StringTable {
-    elements: Vec<String>,
-}
+use std::process::ExitCode;
 
-impl StringTable {
-    fn find_by_prefix(&self, prefix: &str) -> Option<&String> {
-        for i in 0..self.elements.len() {
-            if self.elements[i].starts_with(prefix) {
-                return Some(&self.elements[i]);
-            }
-        }
-        None
-    }
-}
+use ch_05::StringTable;
+use common::Error;
+use tracing::debug;
 
-fn main() {
-    let mut table = StringTable {
-        elements: Vec::new(), // Create an empty StringTable
-    };
-    table.elements.push("apple".to_string());
-    table.elements.push("banana".to_string());
-    table.elements.push("orange".to_string());
+fn lookup() -> Result<(), Error> {
+    common::logging::args()?;
+    let mut table = StringTable::new();
+    table.push("apple");
+    table.push("banana");
+    table.push("orange");
+    debug!(strings = table.len(), "built the table");
 
     // Search for strings starting with a specific prefix
     let prefix = "app";
@@ -33,4 +24,9 @@ fn main() {
             // Handle the case where no matching string is found...
         }
     }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    common::run(lookup)
 }
@@ -0,0 +1,50 @@
+use ch_05::StringTable;
+
+fn fruit() -> StringTable {
+    ["apple", "apricot", "banana", "avocado", "application"]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn find_by_prefix() {
+    let table = fruit();
+    assert_eq!(
+        table.find_by_prefix("ap").map(String::as_str),
+        Some("apple")
+    );
+    assert_eq!(
+        table.find_by_prefix("b").map(String::as_str),
+        Some("banana")
+    );
+    assert_eq!(table.find_by_prefix("cherry"), None);
+    assert_eq!(table.find_by_prefix("").map(String::as_str), Some("apple"));
+}
+
+#[test]
+fn completions() {
+    let table = fruit();
+    let found: Vec<&str> = table.completions("ap").collect();
+    assert_eq!(found, ["apple", "apricot", "application"]);
+    assert_eq!(table.completions("").count(), table.len());
+    assert_eq!(table.completions("z").next(), None);
+    assert!(StringTable::new().is_empty());
+}
+
+#[test]
+fn a_large_dictionary() {
+    let words = fixtures::words(5000, 2);
+    let table: StringTable = words.iter().cloned().collect();
+    for prefix in ["ka", "quazen", "str", "lo1", "nothing"] {
+        let expected: Vec<&str> = words
+            .iter()
+            .map(String::as_str)
+            .filter(|w| w.starts_with(prefix))
+            .collect();
+        assert_eq!(table.completions(prefix).collect::<Vec<_>>(), expected);
+        assert_eq!(
+            table.find_by_prefix(prefix).map(String::as_str),
+            expected.first().copied()
+        );
+    }
+}
@@ -0,0 +1,21 @@
+// The library half of this chapter: a newtype that implements `Deref`, so
+// that deref coercion lets it go wherever a `&str` is wanted.
+
+use std::ops::Deref;
+
+pub struct MyString(pub String);
+
+impl Deref for MyString {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The first word of `s`, or all of it if there's no space. Takes a
+/// `&str`, so a `&MyString` coerces to it: `&MyString` to `&String` by
+/// our `Deref`, then to `&str` by `String`'s.
+pub fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or(s)
+}
@@ -1,24 +1,21 @@
-use std::ops::Deref;
+use std::process::ExitCode;
 
-struct MyString(String);
+use ch_06::{first_word, MyString};
+use common::Error;
+use tracing::debug;
 
-impl Deref for MyString {
-    type Target = String;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-fn print_string(s: &str) {
-    println!("{}", s);
-}
-
-fn main() {
+fn deref() -> Result<(), Error> {
+    common::logging::args()?;
     let my_string = MyString("hello".to_string());
+    debug!(contents = %my_string.0, "made a MyString");
 
     // The MyString type implements Deref, so we can pass a &MyString to a function
     // that takes a &str. The compiler will automatically apply deref coercion to
     // convert the &MyString to a &String, which can then be converted to a &str.
-    print_string(&my_string);
+    println!("{}", first_word(&my_string));
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    common::run(deref)
 }
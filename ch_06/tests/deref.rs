@@ -0,0 +1,16 @@
+use ch_06::{first_word, MyString};
+
+#[test]
+fn methods_come_through_deref() {
+    let s = MyString("hello world".to_string());
+    assert_eq!(s.len(), 11);
+    assert!(s.starts_with("hello"));
+}
+
+#[test]
+fn coerces_to_str() {
+    let s = MyString("hello world".to_string());
+    assert_eq!(first_word(&s), "hello");
+    assert_eq!(first_word(&MyString("single".to_string())), "single");
+    assert_eq!(first_word(""), "");
+}
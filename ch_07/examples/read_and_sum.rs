@@ -0,0 +1,53 @@
+// Sum the numbers in a file, one per line. With `--follow`, keep watching
+// the file and print the running total as lines are added.
+
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use ch_07::{read_and_sum, SumFollower, Update};
+use common::{Context, Error};
+use tracing::warn;
+
+fn main() -> ExitCode {
+    common::run(|| {
+        let mut args = common::logging::args()?.into_iter();
+        let (path, follow) = match (args.next(), args.next().as_deref()) {
+            (Some(path), None) => (path, false),
+            (Some(path), Some("--follow")) => (path, true),
+            _ => {
+                eprintln!("Usage: read_and_sum FILE [--follow]");
+                return Err(Error::usage("expected a file name, and perhaps --follow"));
+            }
+        };
+
+        if !follow {
+            // A parse error exits with `exit::DATA_ERR`, a missing file
+            // with `exit::NO_INPUT`: `common` finds out which by
+            // downcasting, as this chapter describes.
+            let sum = read_and_sum(&path)
+                .map_err(Error::from_boxed)
+                .with_context(|| format!("failed to sum the numbers in {}", path))?;
+            println!("Sum of numbers in file: {}", sum);
+            return Ok(());
+        }
+
+        let mut follower = SumFollower::new(&path);
+        loop {
+            let updates = follower
+                .poll()
+                .with_context(|| format!("failed to read {}", path))?;
+            if !updates.is_empty() {
+                for update in updates {
+                    match update {
+                        Update::Number(n) => println!("{:+}", n),
+                        Update::Skipped { line, error } => warn!(?line, %error, "skipped"),
+                        Update::Restarted => println!("(file truncated; starting over)"),
+                    }
+                }
+                println!("sum = {}", follower.sum());
+            }
+            thread::sleep(Duration::from_millis(250));
+        }
+    })
+}
@@ -0,0 +1,54 @@
+// The Readme's custom error type: a JSON parse error that keeps the
+// position apart from the message.
+
+use std::error::Error;
+use std::fmt;
+
+use common::{exit, CliError};
+
+#[derive(Debug, Clone)]
+pub struct JsonError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {} column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for JsonError {}
+
+/// Malformed input, so `common::run` exits with `exit::DATA_ERR`.
+impl CliError for JsonError {
+    fn exit_code(&self) -> u8 {
+        exit::DATA_ERR
+    }
+}
+
+impl JsonError {
+    pub fn new(message: String, line: usize, column: usize) -> Self {
+        Self {
+            message,
+            line,
+            column,
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(err: serde_json::Error) -> JsonError {
+        JsonError::new(err.to_string(), err.line(), err.column())
+    }
+}
+
+/// Parse `input` as JSON, reporting failure as a `JsonError`.
+pub fn parse_json(input: &str) -> Result<serde_json::Value, JsonError> {
+    Ok(serde_json::from_str(input)?)
+}
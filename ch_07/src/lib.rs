@@ -0,0 +1,133 @@
+// The library half of this chapter: `read_and_sum` from the Readme's
+// section on working with multiple error types, and a follow mode that
+// keeps a running sum of a file as lines are added to it, like
+// `tail -f`. `json` has the Readme's custom error type.
+
+mod json;
+
+pub use json::{parse_json, JsonError};
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+
+pub type GenericError = Box<dyn Error + Send + Sync + 'static>;
+pub type GenericResult<T> = Result<T, GenericError>;
+
+/// Sum the integers in a file, one per line. Fails on the first line that
+/// isn't one, or on any I/O error; the caller can tell which with
+/// `downcast_ref`.
+pub fn read_and_sum<P: AsRef<Path>>(filename: P) -> GenericResult<i64> {
+    let file = File::open(filename)?;
+    sum_lines(BufReader::new(file))
+}
+
+/// `read_and_sum`, reading from anything buffered: a file, or a string's
+/// bytes.
+pub fn sum_lines<R: BufRead>(reader: R) -> GenericResult<i64> {
+    let mut sum = 0;
+    for line_result in reader.lines() {
+        let line = line_result?;
+        let num = line.trim().parse::<i64>()?;
+        sum += num;
+    }
+
+    Ok(sum)
+}
+
+/// What `SumFollower::poll` found.
+#[derive(Debug, PartialEq)]
+pub enum Update {
+    /// A number was added to the sum.
+    Number(i64),
+    /// A line that isn't a number. Following a file someone else is
+    /// writing, one bad line shouldn't stop everything, so it's reported
+    /// and skipped.
+    Skipped { line: String, error: ParseIntError },
+    /// The file got shorter, so it was replaced or truncated. The sum
+    /// starts again from the beginning.
+    Restarted,
+}
+
+/// A running sum of a file that's still being written.
+///
+/// Each `poll` reads whatever has been appended since the last one. A
+/// line without its newline yet may be only partly written, so it waits
+/// for the next poll.
+#[derive(Debug)]
+pub struct SumFollower {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    sum: i64,
+    count: u64,
+}
+
+impl SumFollower {
+    pub fn new(path: impl Into<PathBuf>) -> SumFollower {
+        SumFollower {
+            path: path.into(),
+            offset: 0,
+            partial: Vec::new(),
+            sum: 0,
+            count: 0,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    /// How many numbers have been summed.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Read what's new in the file. A file that doesn't exist yet is
+    /// treated as empty.
+    pub fn poll(&mut self) -> io::Result<Vec<Update>> {
+        let mut updates = Vec::new();
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(updates),
+            Err(e) => return Err(e),
+        };
+        if file.metadata()?.len() < self.offset {
+            *self = SumFollower::new(self.path.clone());
+            updates.push(Update::Restarted);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut new = Vec::new();
+        self.offset += file.read_to_end(&mut new)? as u64;
+        self.partial.extend_from_slice(&new);
+
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(updates);
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        for line in String::from_utf8_lossy(&complete).lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.parse::<i64>() {
+                Ok(n) => {
+                    self.sum += n;
+                    self.count += 1;
+                    updates.push(Update::Number(n));
+                }
+                Err(error) => updates.push(Update::Skipped {
+                    line: line.to_string(),
+                    error,
+                }),
+            }
+        }
+        Ok(updates)
+    }
+}
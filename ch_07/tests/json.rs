@@ -0,0 +1,16 @@
+use ch_07::{parse_json, JsonError};
+use common::{exit, CliError};
+
+#[test]
+fn parses() {
+    let value = parse_json(r#"{"name": "Alice", "age": "30"}"#).unwrap();
+    assert_eq!(value["name"], "Alice");
+}
+
+#[test]
+fn errors_keep_their_position() {
+    let e: JsonError = parse_json("{\n  \"name\": }").unwrap_err();
+    assert_eq!((e.line, e.column), (2, 11));
+    assert!(e.to_string().starts_with("line 2 column 11: "), "{}", e);
+    assert_eq!(e.exit_code(), exit::DATA_ERR);
+}
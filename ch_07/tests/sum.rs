@@ -0,0 +1,90 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::num::ParseIntError;
+
+use ch_07::{read_and_sum, SumFollower, Update};
+
+#[test]
+fn sums_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    fs::write(&path, "1\n2\n 39 \n").unwrap();
+    assert_eq!(read_and_sum(&path).unwrap(), 42);
+
+    fs::write(&path, "1\ntwo\n").unwrap();
+    let error = read_and_sum(&path).unwrap_err();
+    assert!(error.downcast_ref::<ParseIntError>().is_some());
+
+    let error = read_and_sum(dir.path().join("missing")).unwrap_err();
+    assert!(error.downcast_ref::<io::Error>().is_some());
+}
+
+#[test]
+fn sums_a_large_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    let numbers = fixtures::numbers(100_000, 0.0, 1);
+    fs::write(&path, &numbers.text).unwrap();
+    assert_eq!(read_and_sum(&path).unwrap(), numbers.sum);
+}
+
+#[test]
+fn follows_appends() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    let mut follower = SumFollower::new(&path);
+    // Not there yet.
+    assert_eq!(follower.poll().unwrap(), []);
+
+    fs::write(&path, "10\n20\n").unwrap();
+    assert_eq!(
+        follower.poll().unwrap(),
+        [Update::Number(10), Update::Number(20)]
+    );
+    assert_eq!((follower.sum(), follower.count()), (30, 2));
+    assert_eq!(follower.poll().unwrap(), []);
+
+    // A line arrives in pieces, and only counts once it's complete.
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"1").unwrap();
+    assert_eq!(follower.poll().unwrap(), []);
+    file.write_all(b"2\n\nx\n").unwrap();
+    let updates = follower.poll().unwrap();
+    assert_eq!(updates[0], Update::Number(12));
+    assert!(matches!(&updates[1], Update::Skipped { line, .. } if line == "x"));
+    assert_eq!(updates.len(), 2);
+    assert_eq!(follower.sum(), 42);
+}
+
+#[test]
+fn starts_again_when_truncated() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    let mut follower = SumFollower::new(&path);
+    fs::write(&path, "100\n200\n").unwrap();
+    follower.poll().unwrap();
+    fs::write(&path, "5\n").unwrap();
+    assert_eq!(
+        follower.poll().unwrap(),
+        [Update::Restarted, Update::Number(5)]
+    );
+    assert_eq!((follower.sum(), follower.count()), (5, 1));
+}
+
+#[test]
+fn following_skips_exactly_the_bad_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    let numbers = fixtures::numbers(2000, 0.02, 6);
+    fs::write(&path, &numbers.text).unwrap();
+    let mut follower = SumFollower::new(&path);
+    let skipped = follower
+        .poll()
+        .unwrap()
+        .iter()
+        .filter(|update| matches!(update, Update::Skipped { .. }))
+        .count();
+    assert_eq!(skipped, numbers.bad_lines.len());
+    assert_eq!(follower.sum(), numbers.sum);
+    assert_eq!(follower.count() as usize, 2000 - skipped);
+}
@@ -1,3 +1,14 @@
-fn main() {
+use std::process::ExitCode;
+
+use common::Error;
+
+fn hello() -> Result<(), Error> {
+    common::logging::args()?;
+    tracing::info!("saying hello");
     println!("Hello, world!");
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    common::run(hello)
 }
@@ -0,0 +1,162 @@
+// End-to-end tests of the chapters' command-line programs, run as a user
+// would run them: real binaries, real files, real exit codes. The tests
+// are in `tests/`; this is what they share.
+//
+// A test can only ask Cargo for the path of a binary in its own package,
+// so this crate builds the programs it needs itself, once per test run.
+// Building every time, rather than looking for a binary left behind by an
+// earlier build, means a test never runs a stale program.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use std::sync::Mutex;
+use std::{env, fs};
+
+use assert_cmd::Command;
+
+/// The programs under test, and what they load: package, target, and
+/// which kind of target.
+struct Target {
+    package: &'static str,
+    name: &'static str,
+    kind: Kind,
+}
+
+enum Kind {
+    Bin,
+    Example,
+    /// A shared library, like quickreplace's plugins.
+    Library,
+}
+
+/// quickreplace.
+pub fn quickreplace() -> Command {
+    command(Target {
+        package: "ch_02",
+        name: "ch_02",
+        kind: Kind::Bin,
+    })
+}
+
+/// quickreplace's path, for tests that need a `std::process::Command`,
+/// to change its input while it runs.
+pub fn quickreplace_path() -> PathBuf {
+    build(&Target {
+        package: "ch_02",
+        name: "ch_02",
+        kind: Kind::Bin,
+    })
+}
+
+/// Chapter 3's file reader and calculator.
+pub fn calculator() -> Command {
+    command(Target {
+        package: "ch_03",
+        name: "ch_03",
+        kind: Kind::Bin,
+    })
+}
+
+/// Chapter 7's `read_and_sum` example.
+pub fn read_and_sum() -> Command {
+    command(Target {
+        package: "ch_07",
+        name: "read_and_sum",
+        kind: Kind::Example,
+    })
+}
+
+/// `read_and_sum`'s path, for tests that need a `std::process::Command`,
+/// to read its output while it runs.
+pub fn read_and_sum_path() -> PathBuf {
+    build(&Target {
+        package: "ch_07",
+        name: "read_and_sum",
+        kind: Kind::Example,
+    })
+}
+
+/// The example quickreplace plugin's shared library, which puts each
+/// replacement in capitals.
+pub fn upper_plugin() -> PathBuf {
+    build(&Target {
+        package: "upper-plugin",
+        name: "upper_plugin",
+        kind: Kind::Library,
+    })
+}
+
+fn command(target: Target) -> Command {
+    let mut command = Command::new(build(&target));
+    // Errors should look the same whatever the terminal, and settings,
+    // including the language, shouldn't leak in from whoever runs the
+    // tests.
+    command.env("NO_COLOR", "1").env("HOME", "/nonexistent");
+    for (var, _) in std::env::vars() {
+        if var.starts_with("QUICKREPLACE_")
+            || var.starts_with("LC_")
+            || ["RUST_LOG", "LANG", "LANGUAGE"].contains(&var.as_str())
+        {
+            command.env_remove(var);
+        }
+    }
+    command
+}
+
+/// Targets already built by this test process.
+static BUILT: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// Build `target`, if this process hasn't already, and return its path.
+fn build(target: &Target) -> PathBuf {
+    let mut built = BUILT.lock().unwrap_or_else(|e| e.into_inner());
+    if !built.contains(&target.name) {
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let mut command = StdCommand::new(cargo);
+        command.args(["build", "--quiet", "--package", target.package]);
+        match target.kind {
+            Kind::Bin => command.args(["--bin", target.name]),
+            Kind::Example => command.args(["--example", target.name]),
+            Kind::Library => command.arg("--lib"),
+        };
+        let status = command
+            .arg("--manifest-path")
+            .arg(workspace().join("Cargo.toml"))
+            .status()
+            .expect("can't run cargo");
+        assert!(status.success(), "building {} failed", target.name);
+        built.push(target.name);
+    }
+
+    // Tests run from `target/debug/deps`; programs are built one level up.
+    let exe = env::current_exe().unwrap();
+    let mut dir = exe.parent().unwrap();
+    if dir.ends_with("deps") {
+        dir = dir.parent().unwrap();
+    }
+    match target.kind {
+        Kind::Bin => dir.join(format!("{}{}", target.name, env::consts::EXE_SUFFIX)),
+        Kind::Example => {
+            dir.join("examples")
+                .join(format!("{}{}", target.name, env::consts::EXE_SUFFIX))
+        }
+        Kind::Library => dir.join(format!(
+            "{}{}{}",
+            env::consts::DLL_PREFIX,
+            target.name,
+            env::consts::DLL_SUFFIX
+        )),
+    }
+}
+
+fn workspace() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+}
+
+/// The contents of `tests/golden/NAME`. After a deliberate change to a
+/// program's output, rerun its command and review the diff.
+pub fn golden(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,66 @@
+use std::fs;
+
+use cli_tests::{calculator, golden};
+use predicates::prelude::*;
+
+#[test]
+fn evaluates_expressions() {
+    for (expr, value) in [
+        ("1 + 2", "3"),
+        ("2 ^ 3 ^ 2", "512"),
+        ("sqrt(2) ^ 2", "2.0000000000000004"),
+    ] {
+        calculator()
+            .args(["-e", expr])
+            .assert()
+            .success()
+            .stdout(format!("{}\n", value));
+    }
+}
+
+#[test]
+fn reports_evaluation_errors() {
+    calculator()
+        .args(["-e", "1 / 0"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("Error: can't evaluate \"1 / 0\": division by zero\n");
+    calculator()
+        .args(["-e", "2 +"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("at column 4"));
+}
+
+#[test]
+fn prints_files() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("file.txt"), golden("poem.txt")).unwrap();
+    // With no arguments, it reads file.txt.
+    calculator()
+        .current_dir(dir.path())
+        .assert()
+        .success()
+        .stdout(format!("File contents: {}\n", golden("poem.txt")));
+}
+
+#[test]
+fn missing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    calculator()
+        .current_dir(dir.path())
+        .arg("nope.txt")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::starts_with("Error: No such file"));
+}
+
+#[test]
+fn too_many_arguments() {
+    calculator()
+        .args(["a", "b", "c"])
+        .assert()
+        .code(2)
+        .stderr(golden("calculator-usage.stderr"));
+}
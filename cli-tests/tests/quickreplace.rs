@@ -0,0 +1,2315 @@
+use std::fs;
+use std::io::{Read, Write};
+
+use cli_tests::{golden, quickreplace, quickreplace_path, upper_plugin};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use predicates::prelude::*;
+
+#[test]
+fn replaces_into_the_output_file() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), golden("poem.txt")).unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["(?m)^(\\w+)", "[$1]", "in.txt", "out.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text and wrote output to 'out.txt'\n")
+        .stderr("");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        golden("poem-bracketed.txt")
+    );
+}
+
+#[test]
+fn dash_means_standard_input_and_output() {
+    let dir = tempfile::tempdir().unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["(?m)^(\\w+)", "[$1]", "-", "-"])
+        .write_stdin(golden("poem.txt"))
+        .assert()
+        .success()
+        .stdout(golden("poem-bracketed.txt"))
+        .stderr("");
+    assert!(!dir.path().join("-").exists());
+
+    // Either end can be a file.
+    fs::write(dir.path().join("in.txt"), "hello world").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["world", "Rust", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("hello Rust");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["world", "Rust", "-", "out.txt"])
+        .write_stdin("goodbye world")
+        .assert()
+        .success()
+        .stdout("Successfully replaced text and wrote output to 'out.txt'\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "goodbye Rust"
+    );
+}
+
+#[test]
+fn no_filenames_make_a_filter() {
+    quickreplace()
+        .args(["colou?r", "hue"])
+        .write_stdin("one colour\nno newline, one color")
+        .assert()
+        .success()
+        .stdout("one hue\nno newline, one hue");
+    quickreplace()
+        .args(["count", "colou?r"])
+        .write_stdin("colour\ncolor\n")
+        .assert()
+        .success()
+        .stdout("-: 2 matches\n");
+    // What needs the whole text still gets it.
+    quickreplace()
+        .args(["--dotall", "a.b", "x"])
+        .write_stdin("a\nb\n")
+        .assert()
+        .success()
+        .stdout("x\n");
+}
+
+#[test]
+fn a_filter_passes_each_line_on_as_it_comes() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    let mut child = Command::new(quickreplace_path())
+        .args(["colour", "hue"])
+        .env("HOME", "/nonexistent")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (lines, received) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if lines.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let next = || received.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    // Standard input is still open, so nothing has made it flush but
+    // having no more to read.
+    writeln!(stdin, "one colour").unwrap();
+    assert_eq!(next(), "one hue");
+    writeln!(stdin, "two colours").unwrap();
+    assert_eq!(next(), "two hues");
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn no_progress_bar_off_a_terminal() {
+    use std::io::{Read, Write};
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = Command::new(quickreplace_path())
+        .current_dir(dir.path())
+        .args(["--stream", "colour", "hue", "-", "out.txt"])
+        .env("HOME", "/nonexistent")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    // Long enough that a bar would have been drawn.
+    for _ in 0..5 {
+        writeln!(stdin, "colour").unwrap();
+        thread::sleep(Duration::from_millis(100));
+    }
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+    let mut stderr = String::new();
+    child.stderr.unwrap().read_to_string(&mut stderr).unwrap();
+    assert_eq!(stderr, "");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "hue\n".repeat(5)
+    );
+}
+
+#[test]
+fn in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("poem.txt");
+    fs::write(&file, golden("poem.txt")).unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "(?m)^(\\w+)", "[$1]", "poem.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text in 'poem.txt'\n");
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        golden("poem-bracketed.txt")
+    );
+    // Nothing else is left behind.
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn writes_all_at_once_or_not_at_all() {
+    let dir = tempfile::tempdir().unwrap();
+    // Streamed, the lines before the bad byte are written before it's
+    // read.
+    let mut input = "old\n".repeat(10_000).into_bytes();
+    input.extend_from_slice(b"\xff\n");
+    fs::write(dir.path().join("in.txt"), input).unwrap();
+    fs::write(dir.path().join("out.txt"), "previous\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stream", "old", "new", "in.txt", "out.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("in.txt"));
+    // The output is as it was, and there's no temporary file left.
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "previous\n"
+    );
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn in_place_through_a_symbolic_link() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("real.txt");
+    fs::write(&file, "old\n").unwrap();
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+    std::os::unix::fs::symlink("real.txt", dir.path().join("link.txt")).unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "old", "new", "link.txt"])
+        .assert()
+        .success();
+    // The file the link points to is rewritten, and the link kept.
+    assert_eq!(fs::read_to_string(&file).unwrap(), "new\n");
+    let link = fs::symlink_metadata(dir.path().join("link.txt")).unwrap();
+    assert!(link.file_type().is_symlink());
+    let mode = fs::metadata(&file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn watch_replaces_again_when_the_input_changes() {
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in.txt");
+    let output = dir.path().join("out.txt");
+    fs::write(&input, "one colour\n").unwrap();
+    let mut child = Command::new(quickreplace_path())
+        .current_dir(dir.path())
+        .args(["replace", "colour", "hue", "in.txt", "out.txt", "--watch"])
+        .env("HOME", "/nonexistent")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let output_becomes = |expected: &str| {
+        let start = Instant::now();
+        while fs::read_to_string(&output).ok().as_deref() != Some(expected) {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "out.txt never became {:?}",
+                expected
+            );
+            thread::sleep(Duration::from_millis(20));
+        }
+    };
+
+    output_becomes("one hue\n");
+    fs::write(&input, "two colours\n").unwrap();
+    output_becomes("two hues\n");
+    // Saved as an editor might, by renaming a new file over it.
+    fs::write(dir.path().join("in.txt.new"), "three colours\n").unwrap();
+    fs::rename(dir.path().join("in.txt.new"), &input).unwrap();
+    output_becomes("three hues\n");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn preserve_carries_mode_and_times_over() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, SystemTime};
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("in.sh");
+    fs::write(&file, "echo old\n").unwrap();
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+    let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&file)
+        .unwrap()
+        .set_modified(then)
+        .unwrap();
+    let mode = |name| {
+        fs::metadata(dir.path().join(name))
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    let modified = |name| {
+        fs::metadata(dir.path().join(name))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let replace = |args: &[&str]| {
+        quickreplace()
+            .current_dir(dir.path())
+            .args(["replace", "old", "new"])
+            .args(args)
+            .assert()
+            .success();
+    };
+
+    // A new file is made as any other would be, unless told otherwise.
+    replace(&["in.sh", "plain.sh"]);
+    assert_ne!(mode("plain.sh"), 0o755);
+    assert_ne!(modified("plain.sh"), then);
+    replace(&["in.sh", "copy.sh", "--preserve"]);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("copy.sh")).unwrap(),
+        "echo new\n"
+    );
+    assert_eq!(mode("copy.sh"), 0o755);
+    assert_eq!(modified("copy.sh"), then);
+    replace(&["in.sh", "mode.sh", "--preserve=mode"]);
+    assert_eq!(mode("mode.sh"), 0o755);
+    assert_ne!(modified("mode.sh"), then);
+
+    // In place, the file keeps its own time.
+    replace(&["in.sh", "--in-place", "--preserve=timestamps"]);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "echo new\n");
+    assert_eq!(modified("in.sh"), then);
+}
+
+#[test]
+fn in_place_with_a_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "hello world").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["world", "Rust", "in.txt", "--in-place=.bak"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text in 'in.txt', keeping the original as 'in.txt.bak'\n");
+    let read = |name| fs::read_to_string(dir.path().join(name)).unwrap();
+    assert_eq!(read("in.txt"), "hello Rust");
+    assert_eq!(read("in.txt.bak"), "hello world");
+}
+
+#[test]
+fn in_place_without_a_match_or_a_pattern() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "hello world\n").unwrap();
+    let names = || {
+        let mut names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    };
+    // Nothing to replace is still a run that went fine, and the text is
+    // left as it was.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "goodbye", "hi", "in.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text in 'in.txt'\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "hello world\n"
+    );
+    // A bad regex is found before the file is touched: no backup, and no
+    // temporary file.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place=.bak", "(hello", "hi", "in.txt"])
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicate::str::contains("regex parse error"));
+    assert_eq!(names(), ["in.txt"]);
+    // And a missing file isn't made.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place=.bak", "hello", "hi", "missing.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: failed to read from file 'missing.txt': ",
+        ));
+    assert_eq!(names(), ["in.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn in_place_without_a_match_writes_nothing() {
+    use std::os::unix::fs::MetadataExt;
+    use std::time::{Duration, SystemTime};
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("in.txt");
+    fs::write(&file, "hello world\n").unwrap();
+    // Far enough in the past that a rewrite couldn't keep it by chance.
+    let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&file)
+        .unwrap()
+        .set_modified(then)
+        .unwrap();
+    let before = fs::metadata(&file).unwrap();
+    // Read whole, streamed, and mapped, the file is neither renamed over
+    // nor touched.
+    for (args, threshold) in [
+        (&["--in-place", "goodbye", "hi", "in.txt"][..], None),
+        (&["--in-place", "--stream", "goodbye", "hi", "in.txt"], None),
+        (&["--in-place", "goodbye", "hi", "in.txt"], Some("1")),
+    ] {
+        let mut command = quickreplace();
+        command.current_dir(dir.path()).args(args);
+        if let Some(threshold) = threshold {
+            command.env("QUICKREPLACE_MMAP_THRESHOLD", threshold);
+        }
+        command.assert().success().stderr("");
+        let after = fs::metadata(&file).unwrap();
+        assert_eq!(after.ino(), before.ino(), "{:?}", args);
+        assert_eq!(after.modified().unwrap(), then, "{:?}", args);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1, "{:?}", args);
+    }
+    // A match is written, to a new file renamed over the old one.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "hello", "hi", "in.txt"])
+        .assert()
+        .success();
+    let after = fs::metadata(&file).unwrap();
+    assert_ne!(after.ino(), before.ino());
+    assert_ne!(after.modified().unwrap(), then);
+}
+
+#[test]
+fn in_place_usage_errors() {
+    // Any number of files, but not standard input among them.
+    quickreplace()
+        .args(["--in-place", "a", "b", "in.txt", "-"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: standard input can only be read on its own, not among other files\n",
+        ));
+    quickreplace()
+        .args(["--in-place", "a", "b", "-"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: --in-place needs a file to rewrite, not standard input\n",
+        ));
+    // A failed replacement leaves the file alone.
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "text").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place=.bak", "(unclosed", "b", "in.txt"])
+        .assert()
+        .code(2);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "text"
+    );
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn ignore_case() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "Hello, hello, HELLO").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["-i", "hello", "bye", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("bye, bye, bye");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["hello", "bye", "in.txt", "-", "--ignore-case"])
+        .assert()
+        .success()
+        .stdout("bye, bye, bye");
+    // Without it, case counts, as it always has.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["hello", "bye", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("Hello, bye, HELLO");
+}
+
+#[test]
+fn smart_case() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "Hello, hello, HELLO").unwrap();
+    let run = |args: &[&str], expected: &str| {
+        quickreplace()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["in.txt", "-"])
+            .assert()
+            .success()
+            .stdout(expected.to_string());
+    };
+    // Either case for a pattern in lowercase, and only its own for one
+    // with an uppercase letter.
+    run(&["-S", "hello", "bye"], "bye, bye, bye");
+    run(&["--smart-case", "Hello", "bye"], "bye, hello, HELLO");
+    run(
+        &["--smart-case", "-F", "[h]ello", "bye"],
+        "Hello, hello, HELLO",
+    );
+    // The last of `-i`, `-s`, and `-S` wins.
+    run(&["-S", "-i", "Hello", "bye"], "bye, bye, bye");
+    run(&["-i", "-s", "-S", "hello", "bye"], "bye, bye, bye");
+    run(&["-S", "-s", "hello", "bye"], "Hello, bye, HELLO");
+}
+
+#[test]
+fn fixed_strings() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "call a.b(c), not axb(c)").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["-F", "a.b(c)", r"$cost\U\\", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout(r"call $cost\U\\, not axb(c)");
+    // It goes with -i, and as a regex, the same target is an error.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--fixed-strings", "-i", "A.B(C)", "d", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("call d, not axb(c)");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["(c", "d", "in.txt", "-"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn word_regexp() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "let id = user_id + id2 + id;\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["-w", "id", "key", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("let key = user_id + id2 + key;\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "--word-regexp", "id", "in.txt"])
+        .assert()
+        .success()
+        .stdout("in.txt: 2 matches\n");
+}
+
+#[test]
+fn expand_env() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "version = 0\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .env("SHA", r"3f2a\U$1")
+        .args(["--expand-env", r"(\d+)", "${SHA}-$1 ${NONE:-x} $${SHA}"])
+        .args(["in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("version = 3f2a\\U$1-0 x ${SHA}\n");
+    // Without the option, the braces are a group, which isn't there.
+    quickreplace()
+        .current_dir(dir.path())
+        .env("SHA", "3f2a")
+        .args([r"\d+", "${SHA}", "in.txt", "-"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("group 'SHA'"));
+    quickreplace()
+        .current_dir(dir.path())
+        .env_remove("NONE")
+        .args(["--expand-env", r"\d+", "${NONE}", "in.txt", "-"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("'NONE' isn't set"));
+}
+
+#[test]
+fn a_missing_group_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "1 2 3\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args([r"(\d)", "$1st", "in.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(
+            "Error: the replacement refers to a group named '1st', which the pattern \
+             doesn't have; for a group by number followed by more text, write ${1}st\n",
+        );
+    // Nothing was written.
+    assert!(!dir.path().join("out.txt").exists());
+    quickreplace()
+        .current_dir(dir.path())
+        .args([r"(\d)", "${1}st", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("1st 2st 3st\n");
+}
+
+#[test]
+fn rules_files() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "Cats chase dogs; a.b").unwrap();
+    fs::write(
+        dir.path().join("swap.toml"),
+        "[[rule]]\npattern = 'cat'\nreplacement = 'dog'\n\n\
+         [[rule]]\npattern = 'dog'\nreplacement = 'cat'\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("dot.toml"),
+        "[[rule]]\npattern = '.'\nreplacement = '$'\n",
+    )
+    .unwrap();
+    // One pass, so the words swap; the flags apply to every rule; and the
+    // files' rules apply in order.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["-i", "-F", "--rules", "swap.toml", "--rules=dot.toml"])
+        .args(["in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("dogs chase cats; a$b");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "--rules", "swap.toml", "in.txt"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "Cats chase cats; a.b"
+    );
+}
+
+#[test]
+fn rules_file_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "text").unwrap();
+    let run = |rules: &str| {
+        fs::write(dir.path().join("rules.toml"), rules).unwrap();
+        quickreplace()
+            .current_dir(dir.path())
+            .args(["--rules", "rules.toml", "in.txt", "-"])
+            .assert()
+    };
+    run("[[rule]]\npattern = 'x'\n")
+        .code(2)
+        .stderr(predicate::str::contains("invalid rules file 'rules.toml'"));
+    run("")
+        .code(2)
+        .stderr("Error: rules file 'rules.toml' has no [[rule]]s\n");
+    run("[[rule]]\npattern = 'x'\nreplacement = 'y'\n[[rule]]\npattern = '('\nreplacement = ''\n")
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: invalid pattern in rule 2 of 'rules.toml'",
+        ));
+    run("[[rule]]\npattern = '(x)'\nreplacement = '$2'\n")
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: invalid replacement in rule 1 of 'rules.toml'",
+        ));
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--rules", "missing.toml", "in.txt", "-"])
+        .assert()
+        .code(2);
+    // With rules, a target and replacement are too many arguments.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--rules", "rules.toml", "x", "y", "in.txt", "-"])
+        .assert()
+        .code(2);
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["in.txt", "-", "--rules"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "error: a value is required for '--rules <FILE>'",
+        ));
+}
+
+#[test]
+fn streams_line_by_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let planted = fixtures::planted(256 * 1024, "TODO", 30, 2);
+    fs::write(dir.path().join("in.txt"), &planted.text).unwrap();
+    // Line by line, the result is the same as all at once.
+    let whole = quickreplace()
+        .current_dir(dir.path())
+        .args([r"TODO|\w+@(\w+)", "<$1>", "in.txt", "-"])
+        .output()
+        .unwrap();
+    assert!(whole.status.success());
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stream", r"TODO|\w+@(\w+)", "<$1>", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout(whole.stdout);
+
+    // Newlines, and the lack of one at the end, are kept, and `$` matches
+    // before each.
+    fs::write(dir.path().join("in.txt"), "one  \r\ntwo \nthree ").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stream", "--in-place=.bak", r" +(\r?)$", "$1", "in.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text in 'in.txt', keeping the original as 'in.txt.bak'\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "one\r\ntwo\nthree"
+    );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stream", "--dry-run", "t(w|h)", "T$1", "in.txt", "-"])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(
+            "in.txt:2\n- two\n+ Two\nin.txt:3\n- three\n+ Three\n\
+             Would make 2 replacements; nothing was written\n",
+        );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stream", "a", "b", "-", "-"])
+        .write_stdin("banana\n")
+        .assert()
+        .success()
+        .stdout("bbnbnb\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stream", "a", "b", ".", "out"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: --stream only applies to a single file, not a directory or a list of files\n",
+        ));
+}
+
+#[test]
+fn maps_a_huge_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let planted = fixtures::planted(256 * 1024, "TODO", 100, 3);
+    let text = format!("\u{feff}{}", planted.text.replace(". ", ".\n"));
+    fs::write(dir.path().join("in.txt"), &text).unwrap();
+    let patterns = [r"TODO|\w+@(\w+)", r"\.\n\w+", r"(?m)^"];
+    for pattern in patterns {
+        let read = quickreplace()
+            .current_dir(dir.path())
+            .args([pattern, "<$0>", "in.txt", "-"])
+            .output()
+            .unwrap();
+        assert!(read.status.success());
+        // Anything over a byte is mapped, and replaced a section at a
+        // time on four threads, to the same result.
+        quickreplace()
+            .current_dir(dir.path())
+            .env("QUICKREPLACE_MMAP_THRESHOLD", "1")
+            .env("QUICKREPLACE_VERBOSITY", "1")
+            .args(["--jobs", "4", pattern, "<$0>", "in.txt", "-"])
+            .assert()
+            .success()
+            .stdout(read.stdout)
+            .stderr(predicate::str::contains("mapped input"));
+    }
+    quickreplace()
+        .current_dir(dir.path())
+        .env("QUICKREPLACE_MMAP_THRESHOLD", "1")
+        .args(["--in-place", "TODO", "DONE", "in.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text in 'in.txt'\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        text.replace("TODO", "DONE")
+    );
+
+    // Latin-1 isn't mapped, but read, and decoded, as ever.
+    fs::write(dir.path().join("in.txt"), b"caf\xe9 TODO\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .env("QUICKREPLACE_MMAP_THRESHOLD", "1")
+        .args(["--encoding", "auto", "TODO", "DONE", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout(&b"caf\xe9 DONE\n"[..]);
+}
+
+#[test]
+fn gzip_in_and_out() {
+    let gzip = |text: &str| {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    };
+    let gunzip = |bytes: &[u8]| {
+        let mut text = String::new();
+        MultiGzDecoder::new(bytes)
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    };
+    let dir = tempfile::tempdir().unwrap();
+    let file = |name: &str| dir.path().join(name);
+    fs::write(file("a.log.gz"), gzip("hello world\nworld again\n")).unwrap();
+
+    // A `.gz` input is decompressed, whole or a line at a time.
+    for stream in [&[][..], &["--stream"]] {
+        quickreplace()
+            .current_dir(dir.path())
+            .args(stream)
+            .args(["world", "Rust", "a.log.gz", "-"])
+            .assert()
+            .success()
+            .stdout("hello Rust\nRust again\n");
+        quickreplace()
+            .current_dir(dir.path())
+            .args(stream)
+            .args(["world", "Rust", "a.log.gz", "out.gz"])
+            .assert()
+            .success();
+        assert_eq!(
+            gunzip(&fs::read(file("out.gz")).unwrap()),
+            "hello Rust\nRust again\n"
+        );
+    }
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "world", "a.log.gz"])
+        .assert()
+        .success()
+        .stdout("a.log.gz: 2 matches\n");
+
+    // In place, it's put back compressed, and so is what's undone.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place=.bak", "--journal", "j.json", "world", "Rust"])
+        .arg("a.log.gz")
+        .assert()
+        .success();
+    assert_eq!(
+        gunzip(&fs::read(file("a.log.gz")).unwrap()),
+        "hello Rust\nRust again\n"
+    );
+    assert_eq!(
+        gunzip(&fs::read(file("a.log.gz.bak")).unwrap()),
+        "hello world\nworld again\n"
+    );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["undo", "j.json"])
+        .assert()
+        .success();
+    assert_eq!(
+        gunzip(&fs::read(file("a.log.gz")).unwrap()),
+        "hello world\nworld again\n"
+    );
+
+    // `-z` says standard input and output are gzipped, and takes one
+    // member after another, as `cat a.gz b.gz` makes.
+    let output = quickreplace()
+        .args(["-z", "world", "Rust", "-", "-"])
+        .write_stdin([gzip("world\n"), gzip("old world\n")].concat())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(gunzip(&output.stdout), "Rust\nold Rust\n");
+
+    fs::write(file("bad.gz"), "not gzip at all\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["world", "Rust", "bad.gz", "-"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with(
+            "Error: failed to read from file 'bad.gz'",
+        ));
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["-z", "world", "Rust", ".", "out"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: --compress only applies to a single file, not a directory or a list of files\n",
+        ));
+}
+
+#[test]
+fn wrong_number_of_arguments() {
+    quickreplace()
+        .args(["only", "three", "args"])
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(golden("quickreplace-usage.stderr"));
+}
+
+#[test]
+fn missing_input_file() {
+    let dir = tempfile::tempdir().unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["a", "b", "missing.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: failed to read from file 'missing.txt': ",
+        ));
+    assert!(!dir.path().join("out.txt").exists());
+}
+
+#[test]
+fn bad_regex() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "text").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["(unclosed", "b", "in.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "failed to replace text: regex parse error",
+        ));
+    assert!(!dir.path().join("out.txt").exists());
+}
+
+#[test]
+fn regex_size_limit() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "three hundred words").unwrap();
+    // Small to look at, but too big once compiled, so it says what to do.
+    quickreplace()
+        .current_dir(dir.path())
+        .args([r"\w{300}", "b", "in.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(
+            "Error: failed to replace text: the pattern compiles to more than 10MiB, the regex \
+             size limit; simplify it, or raise the limit with --regex-size-limit\n",
+        );
+    assert!(!dir.path().join("out.txt").exists());
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--regex-size-limit",
+            "20M",
+            r"\w{300}",
+            "b",
+            "in.txt",
+            "out.txt",
+        ])
+        .assert()
+        .success();
+
+    // The limit is counted in bytes, or KiB, MiB, or GiB, and holds for
+    // line patterns, and for `count` and `find`.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "--regex-size-limit=4kib", "--only-lines-matching"])
+        .args([r"\w{10}", "words", "in.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            r"invalid line pattern '\w{10}': the pattern compiles to more than 4KiB",
+        ));
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["find", "--dfa-size-limit", "1", r"\w+s", "in.txt"])
+        .assert()
+        .success()
+        .stdout("in.txt:1:three hundred words\n");
+    quickreplace()
+        .args(["--regex-size-limit", "lots", "a", "b", "in.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "'lots' isn't a size, like 512K or 10M",
+        ));
+}
+
+#[test]
+fn csv_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let people = "name,email,note\n\
+                  Ann,ann@old.com,\"wrote to ann@old.com, twice\"\n\
+                  \"Bob \"\"B\"\"\",bob@old.com,ann@old.com\n";
+    fs::write(dir.path().join("people.csv"), people).unwrap();
+    // Only the email column changes, and the quoted fields stay quoted.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "--csv", "--columns", "email"])
+        .args(["@old.com", "@new.com", "people.csv"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("people.csv")).unwrap(),
+        people
+            .replace("ann@old.com,\"", "ann@new.com,\"")
+            .replace("bob@old", "bob@new")
+    );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "--csv", "--columns", "name,note", "old|B"])
+        .arg("people.csv")
+        .assert()
+        .success()
+        .stdout("people.csv: 4 matches\n");
+
+    // A column the header doesn't have is an error, naming the ones it
+    // does, and the file is left as it was.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "--csv", "--columns", "email,phone"])
+        .args(["new", "old", "people.csv"])
+        .assert()
+        .code(2)
+        .stderr("Error: 'people.csv' has no column 'phone'; its columns are name,email,note\n");
+    assert!(fs::read_to_string(dir.path().join("people.csv"))
+        .unwrap()
+        .contains("ann@new.com"));
+    quickreplace()
+        .args(["--csv", "a", "b", "people.csv"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--columns <NAMES>"));
+}
+
+#[test]
+fn json_pointers() {
+    let dir = tempfile::tempdir().unwrap();
+    let users = "{\n  \"users\": [\n    \
+                 {\"email\": \"ann@old.com\", \"note\": \"ann@old.com\"},\n    \
+                 {\"email\": \"bob@old.com\", \"id\": 7}\n  ]\n}\n";
+    fs::write(dir.path().join("users.json"), users).unwrap();
+    // Only the emails change, and the rest is as it was.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "--json", "--pointer", "/users/*/email"])
+        .args(["@old.com", "@new.com", "users.json"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("users.json")).unwrap(),
+        users
+            .replace("\"ann@old.com\", \"note", "\"ann@new.com\", \"note")
+            .replace("bob@old", "bob@new")
+    );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "--json", "--pointer", "/users/0/note"])
+        .args(["--pointer", "/users/1/id", "old|7", "users.json"])
+        .assert()
+        .success()
+        .stdout("users.json: 1 match\n");
+
+    // A file that isn't JSON is an error, saying where, and is left as it
+    // was; so is a pointer that isn't one.
+    fs::write(dir.path().join("bad.json"), "{\"users\": [1,]}").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place", "--json", "--pointer", "/users/*"])
+        .args(["1", "2", "bad.json"])
+        .assert()
+        .code(2)
+        .stderr("Error: 'bad.json' isn't valid JSON: expected a value at line 1, column 14\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("bad.json")).unwrap(),
+        "{\"users\": [1,]}"
+    );
+    quickreplace()
+        .args(["--json", "--pointer", "users", "a", "b", "users.json"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "'users' isn't a JSON pointer, like /users/*/email",
+        ));
+}
+
+#[test]
+fn unwritable_output() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "text").unwrap();
+    // The output is a directory, which can't be written as a file.
+    fs::create_dir(dir.path().join("out")).unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["text", "b", "in.txt", "out"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: failed to write to file 'out': ",
+        ));
+}
+
+#[test]
+fn logs_to_stderr() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "text").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["-v", "text", "b", "in.txt", "out.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text and wrote output to 'out.txt'\n")
+        .stderr(predicate::str::contains(
+            "INFO read input bytes=4 file=in.txt",
+        ));
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--log-format",
+            "json",
+            "-v",
+            "text",
+            "b",
+            "in.txt",
+            "out.txt",
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            r#""level":"INFO","fields":{"message":"read input","bytes":4"#,
+        ));
+    // Without -v, only warnings and errors are shown.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["text", "b", "in.txt", "out.txt"])
+        .assert()
+        .success()
+        .stderr("");
+}
+
+#[test]
+fn settings_from_the_environment_and_a_file() {
+    let home = tempfile::tempdir().unwrap();
+    fs::write(home.path().join("in.txt"), "text").unwrap();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command
+            .current_dir(home.path())
+            .env("HOME", home.path())
+            .args(args)
+            .args(["text", "b", "in.txt", "out.txt"]);
+        command
+    };
+    let json = r#""level":"INFO""#;
+
+    fs::write(
+        home.path().join(".quickreplace.toml"),
+        "log_format = \"json\"\n",
+    )
+    .unwrap();
+    // The file says JSON, but nothing asks for info.
+    run(&[]).assert().success().stderr("");
+    // The environment does.
+    run(&[])
+        .env("QUICKREPLACE_VERBOSITY", "1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(json));
+    // The command line beats both.
+    run(&["--log-format", "text"])
+        .env("QUICKREPLACE_VERBOSITY", "1")
+        .env("QUICKREPLACE_LOG_FORMAT", "json")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("INFO read input"));
+
+    run(&[])
+        .env("QUICKREPLACE_VERBOSITY", "loud")
+        .assert()
+        .code(2)
+        .stderr("Error: \"loud\" from $QUICKREPLACE_VERBOSITY: expected a whole number\n");
+    fs::write(home.path().join(".quickreplace.toml"), "colour = true\n").unwrap();
+    run(&[])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown setting 'colour'"));
+}
+
+#[test]
+fn defaults_from_a_config_file() {
+    let dir = source_tree();
+    let config = dir.path().join("quickreplace.toml");
+    fs::write(
+        &config,
+        "ignore_case = true\nbackup_suffix = \".orig\"\nexclude = [\"src\"]\n",
+    )
+    .unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command
+            .current_dir(dir.path())
+            .env("HOME", home.path())
+            .arg("--config")
+            .arg(&config)
+            .args(args);
+        command.assert().success().stderr("")
+    };
+    let read = |file: &str| fs::read_to_string(dir.path().join(file)).unwrap();
+
+    // `ignore_case`, unless `-s` says otherwise.
+    run(&["count", "OLD", "tree/main.rs"]).stdout("tree/main.rs: 2 matches\n");
+    run(&["count", "-s", "OLD", "tree/main.rs"]).stdout("tree/main.rs: 0 matches\n");
+    // `exclude`, along with any `--exclude`.
+    run(&["count", "old", "tree"]).stdout(
+        "tree/main.rs: 2 matches\n\
+         tree/notes.txt: 1 match\n\
+         3 matches in 2 of 2 files under 'tree'\n",
+    );
+    run(&["count", "--exclude", "*.txt", "old", "tree"])
+        .stdout("tree/main.rs: 2 matches\n2 matches in 1 of 1 file under 'tree'\n");
+    // The environment beats the file.
+    quickreplace()
+        .current_dir(dir.path())
+        .env("QUICKREPLACE_EXCLUDE", "")
+        .args(["--config", "quickreplace.toml", "count", "old", "tree"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4 matches in 3 of 4 files"));
+    // `backup_suffix`, for `--in-place` without one.
+    run(&["--in-place", "old", "new", "tree/main.rs"]);
+    assert_eq!(
+        read("tree/main.rs.orig"),
+        "fn old() { old() }
+"
+    );
+    run(&["--in-place=.bak", "new", "newer", "tree/main.rs"]);
+    assert_eq!(
+        read("tree/main.rs.bak"),
+        "fn new() { new() }
+"
+    );
+    run(&["--in-place=", "newer", "newest", "tree/notes.txt"]);
+    assert!(!dir.path().join("tree/notes.txt.orig").exists());
+
+    // A file named with `--config` has to be there.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--config", "missing.toml", "count", "old", "tree"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: failed to read missing.toml",
+        ));
+}
+
+#[test]
+fn speaks_spanish() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "hola mundo").unwrap();
+    let wrote = "Texto reemplazado; el resultado se escribió en 'out.txt'\n";
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--lang", "es", "mundo", "world", "in.txt", "out.txt"])
+        .assert()
+        .success()
+        .stdout(wrote);
+    // From the locale, with a territory the catalog doesn't name.
+    quickreplace()
+        .current_dir(dir.path())
+        .env("LANG", "es_MX.UTF-8")
+        .args(["mundo", "world", "missing.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: no se pudo leer el archivo 'missing.txt': ",
+        ));
+    // From the environment, and counted in the plural.
+    quickreplace()
+        .env("QUICKREPLACE_LANG", "es")
+        .args(["a", "b", "c"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: número incorrecto de argumentos: se esperaban 4 y se recibieron 3.\n",
+        ));
+    // --lang beats the locale, and a language with no catalog gets English.
+    quickreplace()
+        .current_dir(dir.path())
+        .env("LANG", "es_ES.UTF-8")
+        .args(["--lang=fr", "mundo", "world", "in.txt", "out.txt"])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text and wrote output to 'out.txt'\n");
+}
+
+/// A small source tree: two Rust files, one in a subdirectory, a text
+/// file, and a hidden directory.
+fn source_tree() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for (file, text) in [
+        ("tree/main.rs", "fn old() { old() }\n"),
+        ("tree/src/lib.rs", "pub fn old() {}\n"),
+        ("tree/src/unchanged.rs", "pub fn new() {}\n"),
+        ("tree/notes.txt", "old notes\n"),
+        ("tree/.git/HEAD.rs", "old\n"),
+    ] {
+        let path = dir.path().join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn rewrites_a_directory_in_place() {
+    let dir = source_tree();
+    let read = |file| fs::read_to_string(dir.path().join("tree").join(file)).unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--glob", "*.rs", "--in-place", "old", "new", "tree"])
+        .assert()
+        .success()
+        .stdout(
+            "tree/main.rs: 2 replacements\n\
+             tree/src/lib.rs: 1 replacement\n\
+             Changed 2 of 3 files under 'tree'\n",
+        )
+        .stderr("");
+    assert_eq!(read("main.rs"), "fn new() { new() }\n");
+    assert_eq!(read("src/lib.rs"), "pub fn new() {}\n");
+    // Neither the text file nor the hidden directory was touched.
+    assert_eq!(read("notes.txt"), "old notes\n");
+    assert_eq!(read(".git/HEAD.rs"), "old\n");
+}
+
+#[test]
+fn files_from_a_list() {
+    let dir = source_tree();
+    let read = |file| fs::read_to_string(dir.path().join("tree").join(file)).unwrap();
+    // NUL-separated on standard input, as `find -print0` writes it, with
+    // a name given twice.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--files-from", "-", "-0", "--in-place", "old", "new"])
+        .write_stdin("tree/main.rs\0tree/notes.txt\0tree/main.rs\0")
+        .assert()
+        .success()
+        .stdout(
+            "tree/main.rs: 2 replacements\n\
+             tree/notes.txt: 1 replacement\n\
+             Changed 2 of 2 files listed on standard input\n",
+        );
+    assert_eq!(read("main.rs"), "fn new() { new() }\n");
+    assert_eq!(read("notes.txt"), "new notes\n");
+    assert_eq!(read("src/lib.rs"), "pub fn old() {}\n");
+    // A line at a time from a file, with one that isn't there.
+    fs::write(dir.path().join("list"), "tree/src/lib.rs\nmissing.rs\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "--files-from", "list", "old"])
+        .assert()
+        .code(2)
+        .stdout(
+            "tree/src/lib.rs: 1 match\n\
+             1 match in 1 of 2 files listed in 'list'\n",
+        )
+        .stderr(predicate::str::contains("'missing.rs'"));
+    // The files are only rewritten in place, and there's no input to give.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--files-from", "list", "old", "new"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--in-place"));
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--files-from", "list", "--in-place", "old", "new", "tree"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn several_files_named() {
+    let dir = source_tree();
+    let read = |file| fs::read_to_string(dir.path().join("tree").join(file)).unwrap();
+    // Rewritten on the thread pool, as a list's are, with a name given
+    // twice done once.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--jobs", "2", "--in-place", "old", "new"])
+        .args(["tree/main.rs", "tree/notes.txt", "tree/main.rs"])
+        .assert()
+        .success()
+        .stdout(
+            "tree/main.rs: 2 replacements\n\
+             tree/notes.txt: 1 replacement\n\
+             Changed 2 of 2 files named on the command line\n",
+        );
+    assert_eq!(read("main.rs"), "fn new() { new() }\n");
+    assert_eq!(read("notes.txt"), "new notes\n");
+    assert_eq!(read("src/lib.rs"), "pub fn old() {}\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "new", "tree/main.rs", "tree/src/lib.rs"])
+        .assert()
+        .success()
+        .stdout(
+            "tree/main.rs: 2 matches\n\
+             2 matches in 1 of 2 files named on the command line\n",
+        );
+    // With an output file, there's only one input.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["old", "new", "tree/main.rs", "tree/notes.txt", "out"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: wrong number of arguments: expected 4, got 5.\n",
+        ));
+}
+
+#[test]
+fn stats_table() {
+    let dir = source_tree();
+    let time = r"[\d.]+(ns|µs|ms|s)";
+    // `new` replaced with itself is a match, but no replacement.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stats", "--in-place", r"\b(old|new)\b", "new", "tree"])
+        .args(["--skip-lines-matching", "notes"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(format!(
+                "^tree/main.rs: 2 replacements\n\
+                 tree/src/lib.rs: 1 replacement\n\
+                 tree/src/unchanged.rs: 1 replacement\n\
+                 Changed 3 of 4 files under 'tree'\n\
+                 File                   Matches  Replacements  Bytes changed +Time\n\
+                 -+\n\
+                 tree/main.rs                 2             2          -6 \\+6 +{time}\n\
+                 tree/src/lib.rs              1             1          -3 \\+3 +{time}\n\
+                 tree/src/unchanged.rs        1             0          -0 \\+0 +{time}\n\
+                 Total, 3 files               4             3          -9 \\+9 +{time}\n$"
+            ))
+            .unwrap(),
+        );
+    // With a report on standard output, the table goes to standard error.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stats", "--in-place", "--dry-run", "--report", "json"])
+        .args(["new", "new", "tree"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{"))
+        .stderr(
+            predicate::str::is_match(format!("\nTotal, 3 files +4 +0 +-0 \\+0 +{time}\n$"))
+                .unwrap(),
+        );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--stats", "old", "new", "tree/main.rs", "-"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: --stats only applies to a directory or a list of files\n",
+        ));
+}
+
+#[test]
+fn copies_a_directory_rewritten() {
+    let dir = source_tree();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["old", "new", "tree", "tree/out"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "Changed 3 of 4 files under 'tree'\n",
+        ));
+    let read = |file| fs::read_to_string(dir.path().join("tree/out").join(file)).unwrap();
+    assert_eq!(read("notes.txt"), "new notes\n");
+    assert_eq!(read("src/lib.rs"), "pub fn new() {}\n");
+    assert_eq!(read("src/unchanged.rs"), "pub fn new() {}\n");
+    // The originals are as they were, and the copies weren't walked as
+    // input: a second run finds the same four files.
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/notes.txt")).unwrap(),
+        "old notes\n"
+    );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["old", "new", "tree", "tree/out"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("of 4 files under 'tree'\n"));
+}
+
+#[test]
+fn leaves_out_what_gitignore_says_to() {
+    let dir = source_tree();
+    fs::write(dir.path().join("tree/.gitignore"), "*.txt\n/src/\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "old", "tree"])
+        .assert()
+        .success()
+        .stdout("tree/main.rs: 2 matches\n2 matches in 1 of 1 file under 'tree'\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "--no-ignore", "old", "tree"])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("of 4 files under 'tree'\n"));
+}
+
+#[test]
+fn undoes_what_a_journal_wrote_down() {
+    let dir = source_tree();
+    let read = |file| fs::read_to_string(dir.path().join("tree").join(file)).unwrap();
+    let before = [read("main.rs"), read("src/lib.rs"), read("notes.txt")];
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--journal",
+            "undo.json",
+            "--in-place",
+            "ol(d)",
+            "$1$1-longer",
+            "tree",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "Changed 3 of 4 files under 'tree'\n",
+        ));
+    assert_eq!(read("main.rs"), "fn dd-longer() { dd-longer() }\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["undo", "undo.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored '").count(3));
+    assert_eq!(
+        [read("main.rs"), read("src/lib.rs"), read("notes.txt")],
+        before
+    );
+
+    // A file edited since is left alone, and the others still restored.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--journal=undo.json", "--in-place", "old", "new", "tree"])
+        .assert()
+        .success();
+    fs::write(dir.path().join("tree/main.rs"), "fn edited() {}\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["undo", "undo.json"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("Restored '").count(2))
+        .stderr(predicate::str::contains("main.rs' has changed since"));
+    assert_eq!(read("main.rs"), "fn edited() {}\n");
+    assert_eq!(read("notes.txt"), "old notes\n");
+
+    // It's only for --in-place.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--journal", "undo.json", "old", "new", "tree", "out"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--in-place"));
+}
+
+#[test]
+fn reports_each_replacement_as_json() {
+    let dir = source_tree();
+    quickreplace()
+        .args(["--report", "json", "colou?r", "hue", "-", "-"])
+        .write_stdin("red\nno colour, one color\n")
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicate::str::contains("--report-file"));
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--report=json", "--glob", "*.rs", "--in-place", "old", "new", "tree"])
+        .assert()
+        .success()
+        .stdout(
+            r#"{"files":[{"file":"tree/main.rs","count":2,"replacements":[{"line":1,"column":4,"matched":"old","replacement":"new"},{"line":1,"column":12,"matched":"old","replacement":"new"}]},{"file":"tree/src/lib.rs","count":1,"replacements":[{"line":1,"column":8,"matched":"old","replacement":"new"}]}],"count":3}"#.to_string() + "\n",
+        );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/main.rs")).unwrap(),
+        "fn new() { new() }\n"
+    );
+    // To a file, with the usual messages, and a match across lines.
+    fs::write(dir.path().join("in.txt"), "a-\nb\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--report",
+            "json",
+            "--report-file",
+            "report.json",
+            "-\n",
+            "",
+            "in.txt",
+            "out.txt",
+        ])
+        .assert()
+        .success()
+        .stdout("Successfully replaced text and wrote output to 'out.txt'\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "ab\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("report.json")).unwrap(),
+        r#"{"files":[{"file":"in.txt","count":1,"replacements":[{"line":1,"column":2,"matched":"-\n","replacement":""}]}],"count":1}"#.to_string() + "\n"
+    );
+}
+
+#[test]
+fn multiline_and_dotall() {
+    let text = "/* Old\n * License\n */\nfn main() {}\n";
+    quickreplace()
+        .args(["--dotall", r"\A/\*.*?\*/\n", "// New License\n", "-", "-"])
+        .write_stdin(text)
+        .assert()
+        .success()
+        .stdout("// New License\nfn main() {}\n");
+    // Without --dotall, `.` stops at a newline, so nothing matches.
+    quickreplace()
+        .args(["count", r"/\*.*\*/", "-"])
+        .write_stdin(text)
+        .assert()
+        .success()
+        .stdout("-: 0 matches\n");
+    quickreplace()
+        .args(["--multiline", "^", "> ", "-", "-"])
+        .write_stdin("a\nb")
+        .assert()
+        .success()
+        .stdout("> a\n> b");
+}
+
+#[test]
+fn replaces_only_on_the_lines_asked_for() {
+    let text = "let x = 1; // x\nfn x() {}\n";
+    quickreplace()
+        .args(["--only-lines-matching", "//", "x", "y", "-", "-"])
+        .write_stdin(text)
+        .assert()
+        .success()
+        .stdout("let y = 1; // y\nfn x() {}\n");
+    quickreplace()
+        .args(["count", "--skip-lines-matching", "^LET", "-i", "x", "-"])
+        .write_stdin(text)
+        .assert()
+        .success()
+        .stdout("-: 1 match\n");
+    quickreplace()
+        .args(["--only-lines-matching", "(", "x", "y", "-", "-"])
+        .write_stdin(text)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: invalid line pattern '(': regex parse error:",
+        ));
+}
+
+#[test]
+fn max_replaces_only_the_first_matches_in_each_file() {
+    let dir = source_tree();
+    quickreplace()
+        .args(["-n", "1", "x", "y", "-", "-"])
+        .write_stdin("x x\nx\n")
+        .assert()
+        .success()
+        .stdout("y x\nx\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--max=1",
+            "--glob",
+            "*.rs",
+            "--in-place",
+            "old",
+            "new",
+            "tree",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            "tree/main.rs: 1 replacement\n\
+             tree/src/lib.rs: 1 replacement\n\
+             Changed 2 of 3 files under 'tree'\n",
+        );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/main.rs")).unwrap(),
+        "fn new() { old() }\n"
+    );
+    quickreplace()
+        .args(["--max", "0", "x", "y", "-", "-"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--max <N>"));
+}
+
+#[test]
+fn carries_on_past_a_file_that_fails() {
+    let dir = source_tree();
+    // A directory where main.rs's backup should go makes that one fail.
+    fs::create_dir(dir.path().join("tree/main.rs.bak")).unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--glob=*.rs", "--in-place=.bak", "old", "new", "tree"])
+        .assert()
+        .code(2)
+        .stdout(
+            "tree/src/lib.rs: 1 replacement\n\
+             Changed 1 of 3 files under 'tree'\n",
+        )
+        .stderr(predicate::str::starts_with(
+            "Error: failed to back up 'tree/main.rs' to 'tree/main.rs.bak': ",
+        ))
+        .stderr(predicate::str::ends_with(
+            "Error: 1 file couldn't be processed\n",
+        ));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/src/lib.rs")).unwrap(),
+        "pub fn new() {}\n"
+    );
+}
+
+#[test]
+fn directory_usage_errors() {
+    let dir = source_tree();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command.current_dir(dir.path()).args(args);
+        command
+    };
+    run(&["--glob", "[rs", "--in-place", "old", "new", "tree"])
+        .assert()
+        .code(2)
+        .stderr("Error: invalid glob '[rs': unclosed [\n");
+    run(&["old", "new", "tree", "-"])
+        .assert()
+        .code(2)
+        .stderr("Error: a directory's files can't all be written to standard output\n");
+    run(&["--glob", "*.rs", "old", "new", "tree/notes.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: --glob only applies when the input is a directory\n",
+        ));
+}
+
+#[test]
+fn dry_run_shows_changes_and_writes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("in.txt"),
+        "hello world\nunchanged\nworld, world\n",
+    )
+    .unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--dry-run", "world", "Rust", "in.txt", "out.txt"])
+        .assert()
+        .success()
+        .stdout(
+            "in.txt:1\n\
+             - hello world\n\
+             + hello Rust\n\
+             in.txt:3\n\
+             - world, world\n\
+             + Rust, Rust\n\
+             Would make 3 replacements; nothing was written\n",
+        )
+        .stderr("");
+    assert!(!dir.path().join("out.txt").exists());
+    // Nor in place, and a match across lines shows every line it takes.
+    fs::write(dir.path().join("in.txt"), "hyphen-\nated\n").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--in-place=.bak", "--dry-run", "-\\n", "", "in.txt"])
+        .assert()
+        .success()
+        .stdout("in.txt:1\n- hyphen-\n- ated\n+ hyphenated\nWould make 1 replacement; nothing was written\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "hyphen-\nated\n"
+    );
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn dry_run_over_a_directory() {
+    let dir = source_tree();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--dry-run", "--glob", "*.rs", "old", "new", "tree", "-"])
+        .assert()
+        .success()
+        .stdout(
+            "tree/main.rs:1\n\
+             - fn old() { old() }\n\
+             + fn new() { new() }\n\
+             tree/main.rs: 2 replacements\n\
+             tree/src/lib.rs:1\n\
+             - pub fn old() {}\n\
+             + pub fn new() {}\n\
+             tree/src/lib.rs: 1 replacement\n\
+             Would change 2 of 3 files under 'tree'; nothing was written\n",
+        );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/main.rs")).unwrap(),
+        "fn old() { old() }\n"
+    );
+}
+
+#[test]
+fn counts_matches_and_writes_nothing() {
+    let dir = source_tree();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command.current_dir(dir.path()).arg("count").args(args);
+        command.assert().success().stderr("")
+    };
+    run(&["old", "tree/main.rs"]).stdout("tree/main.rs: 2 matches\n");
+    run(&["--stream", "old", "tree/notes.txt"]).stdout("tree/notes.txt: 1 match\n");
+    quickreplace()
+        .args(["count", "o", "-"])
+        .write_stdin("foo")
+        .assert()
+        .success()
+        .stdout("-: 2 matches\n");
+    // Under a directory, only the files with matches are listed.
+    run(&["--glob", "*.rs", "old", "tree"]).stdout(
+        "tree/main.rs: 2 matches\n\
+         tree/src/lib.rs: 1 match\n\
+         3 matches in 2 of 3 files under 'tree'\n",
+    );
+    run(&["nothing", "tree"]).stdout("0 matches in 0 of 4 files under 'tree'\n");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/main.rs")).unwrap(),
+        "fn old() { old() }\n"
+    );
+}
+
+#[test]
+fn exits_with_1_only_for_no_match() {
+    let dir = source_tree();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command
+            .current_dir(dir.path())
+            .args(args)
+            .arg("--fail-if-no-match");
+        command.assert()
+    };
+    // Something matched, in each way of looking.
+    run(&["old", "new", "tree/notes.txt", "out.txt"]).success();
+    run(&["--dry-run", "--in-place", "old", "new", "tree"]).success();
+    run(&["--diff", "old", "new", "tree/main.rs"]).success();
+    run(&["count", "old", "tree"]).success();
+    run(&["find", "--stream", "old", "tree/notes.txt"]).success();
+    // Nothing did, which isn't an error to report.
+    run(&["gone", "new", "tree/notes.txt", "out.txt"])
+        .code(1)
+        .stdout("Successfully replaced text and wrote output to 'out.txt'\n")
+        .stderr("");
+    run(&["--in-place", "gone", "new", "tree"])
+        .code(1)
+        .stderr("");
+    run(&["count", "gone", "tree"])
+        .code(1)
+        .stdout("0 matches in 0 of 4 files under 'tree'\n");
+    run(&["find", "--stream", "gone", "tree/notes.txt"])
+        .code(1)
+        .stdout("");
+    // Without the flag, finding nothing is fine.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "gone", "tree"])
+        .assert()
+        .success();
+    // Every error is 2, whether or not anything matched: a bad regex, a
+    // missing input, and an output that can't be written alike.
+    run(&["(gone", "new", "tree/notes.txt", "out.txt"]).code(2);
+    run(&["gone", "new", "missing.txt", "out.txt"]).code(2);
+    run(&["old", "new", "tree/notes.txt", "tree"]).code(2);
+}
+
+#[test]
+fn finds_lines_with_a_match() {
+    let dir = source_tree();
+    fs::write(dir.path().join("tree/notes.txt"), "old\nnew\nold, old\n").unwrap();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command.current_dir(dir.path()).arg("find").args(args);
+        command.assert().success().stderr("")
+    };
+    let found = "tree/notes.txt:1:old\ntree/notes.txt:3:old, old\n";
+    run(&["old", "tree/notes.txt"]).stdout(found);
+    run(&["--stream", "old", "tree/notes.txt"]).stdout(found);
+    run(&["-F", "-i", "FN OLD(", "tree"]).stdout(
+        "tree/main.rs:1:fn old() { old() }\n\
+         tree/src/lib.rs:1:pub fn old() {}\n",
+    );
+    // A match across lines shows them all.
+    run(&["d\\nn", "tree/notes.txt"]).stdout("tree/notes.txt:1:old\ntree/notes.txt:2:new\n");
+    run(&["nothing", "tree"]).stdout("");
+}
+
+#[test]
+fn subcommands() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "hello world").unwrap();
+    // `replace` is the default, but can be named, as it must be for a
+    // target that's the name of a subcommand.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["replace", "world", "Rust", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("hello Rust");
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["replace", "-i", "count", "n", "-", "-"])
+        .write_stdin("Count")
+        .assert()
+        .success()
+        .stdout("n");
+    // Each has its own help, on stdout.
+    quickreplace()
+        .args(["count", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Usage: quickreplace count [OPTIONS] <target> [input_filename]",
+        ))
+        .stdout(predicate::str::contains("--plugin").not());
+    quickreplace()
+        .args(["--lang", "es", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "find     Muestra las líneas que coinciden con un patrón",
+        ));
+    // Without one, the help goes to stderr, as a usage error.
+    quickreplace()
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Commands:"));
+    quickreplace()
+        .args(["replace", "--rules", "rules.toml", "a", "b", "c"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::ends_with(
+            "Error: wrong number of arguments: expected 2, got 3.\n",
+        ));
+    quickreplace()
+        .args(["find", "a", "in.txt", "--plugin", "x.so"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with("error: unexpected argument"));
+}
+
+#[test]
+fn shows_a_unified_diff() {
+    let dir = source_tree();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command.current_dir(dir.path()).arg("--diff").args(args);
+        command.assert()
+    };
+    run(&["old", "new", "tree/main.rs"]).success().stdout(
+        "--- tree/main.rs\n\
+         +++ tree/main.rs\n\
+         @@ -1 +1 @@\n\
+         -fn old() { old() }\n\
+         +fn new() { new() }\n",
+    );
+    // Under a directory, the diffs are all there is, one file after
+    // another, and nothing is written.
+    run(&["--glob", "*.rs", "old", "new", "tree"])
+        .success()
+        .stdout(
+            "--- tree/main.rs\n\
+             +++ tree/main.rs\n\
+             @@ -1 +1 @@\n\
+             -fn old() { old() }\n\
+             +fn new() { new() }\n\
+             --- tree/src/lib.rs\n\
+             +++ tree/src/lib.rs\n\
+             @@ -1 +1 @@\n\
+             -pub fn old() {}\n\
+             +pub fn new() {}\n",
+        )
+        .stderr("");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("tree/main.rs")).unwrap(),
+        "fn old() { old() }\n"
+    );
+    run(&["nothing", "x", "tree"]).success().stdout("");
+    // A diff is a dry run already, and needs the whole file.
+    for other in ["--in-place", "--dry-run", "--stream"] {
+        run(&[other, "old", "new", "tree/main.rs"])
+            .code(2)
+            .stderr(predicate::str::starts_with(format!(
+                "error: the argument '--diff' cannot be used with '{}",
+                other
+            )));
+    }
+}
+
+#[test]
+fn keeps_the_input_in_its_encoding() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = |file: &str| dir.path().join(file);
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command.current_dir(dir.path()).args(args);
+        command.assert()
+    };
+    let utf16 = |text: &str| -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    };
+    fs::write(path("latin1.txt"), b"caf\xE9 au lait\n").unwrap();
+    fs::write(path("utf16.txt"), utf16("café au lait\n")).unwrap();
+
+    // As UTF-8, the Latin-1 file isn't text.
+    run(&["lait", "milk", "latin1.txt", "out.txt"])
+        .code(2)
+        .stderr("Error: failed to read from file 'latin1.txt': invalid UTF-8 at byte 3\n");
+    // Named, or told from the bytes, it's written back as it was read.
+    run(&["--encoding", "latin1", "é", "e", "latin1.txt", "out.txt"]).success();
+    assert_eq!(fs::read(path("out.txt")).unwrap(), b"cafe au lait\n");
+    run(&[
+        "--encoding=auto",
+        "--in-place",
+        "lait",
+        "crème",
+        "latin1.txt",
+    ])
+    .success();
+    assert_eq!(
+        fs::read(path("latin1.txt")).unwrap(),
+        b"caf\xE9 au cr\xE8me\n"
+    );
+    run(&[
+        "--encoding=auto",
+        "--in-place",
+        "lait",
+        "crème",
+        "utf16.txt",
+    ])
+    .success();
+    assert_eq!(
+        fs::read(path("utf16.txt")).unwrap(),
+        utf16("café au crème\n")
+    );
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["count", "[éè]", "utf16.txt", "--encoding", "UTF-16LE"])
+        .assert()
+        .success()
+        .stdout("utf16.txt: 2 matches\n");
+
+    // What Latin-1 can't hold is an error, and the file is left alone.
+    run(&[
+        "--encoding=latin1",
+        "--in-place",
+        "café",
+        "1€",
+        "latin1.txt",
+    ])
+    .code(2)
+    .stderr("Error: failed to write to file 'latin1.txt': '€' can't be written in Latin-1\n");
+    assert_eq!(
+        fs::read(path("latin1.txt")).unwrap(),
+        b"caf\xE9 au cr\xE8me\n"
+    );
+    run(&["--encoding=ebcdic", "a", "b", "latin1.txt", "out.txt"])
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "error: invalid value 'ebcdic' for '--encoding <NAME>'",
+        ));
+}
+
+#[test]
+fn highlights_changes_among_their_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let text: String = (1..=9).map(|i| format!("line {}\n", i)).collect();
+    fs::write(dir.path().join("in.txt"), &text).unwrap();
+    let run = |args: &[&str]| {
+        let mut command = quickreplace();
+        command
+            .current_dir(dir.path())
+            .arg("--highlight")
+            .args(args);
+        command.assert()
+    };
+    // Without color, each match and its replacement are marked, with two
+    // lines around each change, and `--` where lines are left out.
+    run(&["(?m)([28])$", "#$1", "in.txt"])
+        .success()
+        .stdout(
+            "in.txt-1-line 1\n\
+             in.txt:2:line [-2-]{+#2+}\n\
+             in.txt-3-line 3\n\
+             in.txt-4-line 4\n\
+             --\n\
+             in.txt-6-line 6\n\
+             in.txt-7-line 7\n\
+             in.txt:8:line [-8-]{+#8+}\n\
+             in.txt-9-line 9\n",
+        )
+        .stderr("");
+    // A match across lines shows them all, and an inserted newline as \n.
+    run(&["4\nline", "four\\n", "in.txt"]).success().stdout(
+        "in.txt-2-line 2\n\
+         in.txt-3-line 3\n\
+         in.txt:4:line [-4\n\
+         in.txt:5:line-]{+four\\n+} 5\n\
+         in.txt-6-line 6\n\
+         in.txt-7-line 7\n",
+    );
+    // In color, there are no marks.
+    quickreplace()
+        .current_dir(dir.path())
+        .env_remove("NO_COLOR")
+        .env("CLICOLOR_FORCE", "1")
+        .args(["--highlight", "line 1\n", "", "in.txt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "in.txt:1:\x1b[9;31mline 1\x1b[0m\nin.txt:2:line 2\n",
+        ));
+    assert_eq!(fs::read_to_string(dir.path().join("in.txt")).unwrap(), text);
+    run(&["nothing", "x", "in.txt"]).success().stdout("");
+    for other in ["--in-place", "--dry-run", "--diff", "--stream"] {
+        run(&[other, "line", "x", "in.txt"])
+            .code(2)
+            .stderr(predicate::str::starts_with(
+                "error: the argument '--highlight' cannot be used with",
+            ));
+    }
+}
+
+#[test]
+fn jobs_change_nothing_but_speed() {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..200 {
+        let text = "old\n".repeat(i % 4);
+        let file = dir.path().join(format!("tree/{}/{}.txt", i % 7, i));
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, text).unwrap();
+    }
+    let run = |jobs: &str, out: &str| {
+        let output = quickreplace()
+            .current_dir(dir.path())
+            .args(["--jobs", jobs, "old", "new", "tree", out])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let one = run("1", "one");
+    assert!(one.ends_with("Changed 150 of 200 files under 'tree'\n"));
+    assert_eq!(run("4", "four"), one);
+    for i in 0..200 {
+        let file = format!("{}/{}.txt", i % 7, i);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("four").join(&file)).unwrap(),
+            "new\n".repeat(i % 4)
+        );
+    }
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--jobs=0", "old", "new", "tree", "out"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "error: invalid value '0' for '--jobs <N>'",
+        ));
+}
+
+#[test]
+fn plugins_rework_each_replacement() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "// fixme: fixme later\n").unwrap();
+    let plugin = upper_plugin();
+    quickreplace()
+        .current_dir(dir.path())
+        .arg("--plugin")
+        .arg(&plugin)
+        .args(["--in-place", r"\bfixme\b", "$0!", "in.txt"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "// FIXME!: FIXME! later\n"
+    );
+    // A dry run shows what the plugin would make of it.
+    quickreplace()
+        .current_dir(dir.path())
+        .arg(format!("--plugin={}", plugin.display()))
+        .args(["--dry-run", "later", "soon", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+ // FIXME!: FIXME! SOON\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_works_out_each_replacement() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "user=ann id=7\nuser=bob id=42\n").unwrap();
+    // The match on standard input, and the groups in the environment, by
+    // number and by name, with the command's last newline taken off.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--exec", "tr a-z A-Z", r"user=\w+", "in.txt", "-"])
+        .assert()
+        .success()
+        .stdout("USER=ANN id=7\nUSER=BOB id=42\n");
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--exec",
+            r#"echo "$QUICKREPLACE_GROUP_key#$QUICKREPLACE_GROUP_2""#,
+        ])
+        .args(["--in-place", r"(?<key>\w+)=(\d+)", "in.txt"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("in.txt")).unwrap(),
+        "user=ann id#7\nuser=bob id#42\n"
+    );
+    // After the plugins' turn, so they rework what it prints.
+    quickreplace()
+        .current_dir(dir.path())
+        .arg("--plugin")
+        .arg(upper_plugin())
+        .args([
+            "--exec",
+            r#"printf '%s!' "$QUICKREPLACE_MATCH""#,
+            "ann",
+            "in.txt",
+            "-",
+        ])
+        .assert()
+        .success()
+        .stdout("user=ANN! id#7\nuser=bob id#42\n");
+
+    // A command that fails is an error, and nothing is written. Without
+    // a replacement to give, one is a usage error.
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--exec", "echo no >&2; exit 3", "ann", "in.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(
+            "no\nError: failed to replace text: 'echo no >&2; exit 3' failed on 'ann': exit \
+             status: 3\n",
+        );
+    assert!(!dir.path().join("out.txt").exists());
+    quickreplace()
+        .current_dir(dir.path())
+        .args([
+            "--exec",
+            "cat",
+            "--rules",
+            "rules.toml",
+            "in.txt",
+            "out.txt",
+        ])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn plugin_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in.txt"), "text").unwrap();
+    fs::write(dir.path().join("fake.so"), "not a library").unwrap();
+    quickreplace()
+        .current_dir(dir.path())
+        .args(["--plugin", "fake.so", "text", "b", "in.txt", "out.txt"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Error: failed to load plugin 'fake.so': couldn't load the library: ",
+        ));
+    assert!(!dir.path().join("out.txt").exists());
+    quickreplace()
+        .args(["a", "b", "in.txt", "out.txt", "--plugin"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "error: a value is required for '--plugin <LIBRARY>'",
+        ));
+}
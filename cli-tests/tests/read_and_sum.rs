@@ -0,0 +1,104 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use cli_tests::{golden, read_and_sum, read_and_sum_path};
+use predicates::prelude::*;
+
+#[test]
+fn sums_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("numbers.txt"), golden("numbers.txt")).unwrap();
+    read_and_sum()
+        .current_dir(dir.path())
+        .arg("numbers.txt")
+        .assert()
+        .success()
+        .stdout(golden("numbers.stdout"));
+}
+
+#[test]
+fn sums_a_large_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let numbers = fixtures::numbers(50_000, 0.0, 3);
+    fs::write(dir.path().join("numbers.txt"), &numbers.text).unwrap();
+    read_and_sum()
+        .current_dir(dir.path())
+        .arg("numbers.txt")
+        .assert()
+        .success()
+        .stdout(format!("Sum of numbers in file: {}\n", numbers.sum));
+}
+
+#[test]
+fn bad_numbers_are_data_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("numbers.txt"), "1\ntwo\n").unwrap();
+    read_and_sum()
+        .current_dir(dir.path())
+        .arg("numbers.txt")
+        .assert()
+        .code(65)
+        .stderr("Error: failed to sum the numbers in numbers.txt: invalid digit found in string\n");
+}
+
+#[test]
+fn missing_file() {
+    read_and_sum()
+        .arg("/no/such/numbers.txt")
+        .assert()
+        .code(66)
+        .stderr(predicate::str::contains("No such file"));
+}
+
+#[test]
+fn usage() {
+    read_and_sum()
+        .assert()
+        .code(2)
+        .stderr(predicate::str::starts_with(
+            "Usage: read_and_sum FILE [--follow]\n",
+        ));
+    read_and_sum()
+        .args(["numbers.txt", "--fellow"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn follows_a_growing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    fs::write(&path, "10\n").unwrap();
+
+    let mut child = Command::new(read_and_sum_path())
+        .arg(&path)
+        .arg("--follow")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (lines, received) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if lines.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let next = || received.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    assert_eq!(next(), "+10");
+    assert_eq!(next(), "sum = 10");
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, "-3").unwrap();
+    assert_eq!(next(), "-3");
+    assert_eq!(next(), "sum = 7");
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
@@ -0,0 +1,285 @@
+// Settings that can come from three places, with one rule for which wins:
+// the command line over the environment, and the environment over a
+// configuration file. Whatever none of them sets keeps its default.
+//
+// A program describes its settings as a struct that serde can serialize
+// and deserialize, with a `Default`:
+//
+//     #[derive(Default, Serialize, Deserialize)]
+//     #[serde(default)]
+//     struct Settings {
+//         verbosity: u8,
+//         log_format: LogFormat,
+//     }
+//
+//     let settings: Settings = Loader::new("QUICKREPLACE")
+//         .file("~/.quickreplace.toml", false)
+//         .env()
+//         .set("verbosity", "2")
+//         .load()?;
+//
+// The file is TOML, with one key per field: `log_format = "json"`. An
+// environment variable is the prefix and the field's name in capitals,
+// `QUICKREPLACE_LOG_FORMAT=json`. Values from the environment and the
+// command line are strings, and are converted to the type of the field's
+// default: `true` or `false` for a `bool`, a number for a number, and a
+// comma-separated list for a list.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{exit, Context, Error};
+
+/// Where a setting came from, lowest precedence first.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    /// The name of the environment variable.
+    Env(String),
+    CommandLine,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "the default"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env(var) => write!(f, "${}", var),
+            Source::CommandLine => write!(f, "the command line"),
+        }
+    }
+}
+
+/// A layer of settings, not yet checked against the program's.
+enum Layer {
+    File { path: PathBuf, required: bool },
+    Env(Vec<(String, String)>),
+    CommandLine(Vec<(String, String)>),
+}
+
+/// Gathers settings of type `T` from each source, and merges them.
+pub struct Loader<T> {
+    prefix: String,
+    layers: Vec<Layer>,
+    _settings: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Default> Loader<T> {
+    /// A loader that reads environment variables starting with `prefix`
+    /// and an underscore.
+    pub fn new(prefix: &str) -> Loader<T> {
+        Loader {
+            prefix: prefix.to_string(),
+            layers: Vec::new(),
+            _settings: std::marker::PhantomData,
+        }
+    }
+
+    /// Read settings from the TOML file at `path`, where a leading `~/`
+    /// stands for the home directory. If the file isn't there, that's an
+    /// error only if it's `required`: a file named on the command line
+    /// must exist, but one in a standard place needn't.
+    pub fn file(mut self, path: impl AsRef<Path>, required: bool) -> Loader<T> {
+        self.layers.push(Layer::File {
+            path: expand_home(path.as_ref()),
+            required,
+        });
+        self
+    }
+
+    /// Read settings from the process's environment.
+    pub fn env(self) -> Loader<T> {
+        self.env_from(std::env::vars())
+    }
+
+    /// Read settings from `vars`, as if they were the environment.
+    pub fn env_from(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Loader<T> {
+        self.layers.push(Layer::Env(vars.into_iter().collect()));
+        self
+    }
+
+    /// Set `key` from the command line.
+    pub fn set(mut self, key: &str, value: impl Into<String>) -> Loader<T> {
+        let pair = (key.to_string(), value.into());
+        match self.layers.last_mut() {
+            Some(Layer::CommandLine(pairs)) => pairs.push(pair),
+            _ => self.layers.push(Layer::CommandLine(vec![pair])),
+        }
+        self
+    }
+
+    /// Merge the layers and deserialize the result. Which order the
+    /// layers were added in doesn't matter: the command line always wins
+    /// over the environment, and the environment over files. Of two files,
+    /// the one added later wins.
+    pub fn load(self) -> Result<T, Error> {
+        Ok(self.load_with_sources()?.0)
+    }
+
+    /// `load`, also returning where each setting's value came from.
+    pub fn load_with_sources(self) -> Result<(T, Vec<(String, Source)>), Error> {
+        let defaults = match serde_json::to_value(T::default()) {
+            Ok(Value::Object(map)) => map,
+            _ => panic!("settings must serialize as a struct"),
+        };
+        let mut merged = defaults.clone();
+        let mut sources: Vec<(String, Source)> = defaults
+            .keys()
+            .map(|key| (key.clone(), Source::Default))
+            .collect();
+        let mut set = |key: &str, value: Value, source: Source| {
+            merged.insert(key.to_string(), value);
+            if let Some(entry) = sources.iter_mut().find(|(k, _)| k == key) {
+                entry.1 = source;
+            }
+        };
+
+        let mut layers = self.layers;
+        layers.sort_by_key(|layer| match layer {
+            Layer::File { .. } => 0,
+            Layer::Env(_) => 1,
+            Layer::CommandLine(_) => 2,
+        });
+        for layer in layers {
+            match layer {
+                Layer::File { path, required } => {
+                    let Some(table) = read_file(&path, required)? else {
+                        continue;
+                    };
+                    for (key, value) in table {
+                        if !defaults.contains_key(&key) {
+                            return Err(invalid(format!(
+                                "unknown setting '{}' in {}",
+                                key,
+                                path.display()
+                            )));
+                        }
+                        let value = serde_json::to_value(value).expect("TOML is valid JSON");
+                        set(&key, value, Source::File(path.clone()));
+                    }
+                }
+                Layer::Env(vars) => {
+                    let prefix = format!("{}_", self.prefix);
+                    for (var, text) in vars {
+                        let Some(name) = var.strip_prefix(&prefix) else {
+                            continue;
+                        };
+                        // Other variables may share the prefix, so names
+                        // that aren't settings are skipped, not errors.
+                        let key = name.to_lowercase();
+                        if let Some(default) = defaults.get(&key) {
+                            let source = Source::Env(var.clone());
+                            set(&key, coerce(&text, default, &source)?, source);
+                        }
+                    }
+                }
+                Layer::CommandLine(pairs) => {
+                    for (key, text) in pairs {
+                        let default = defaults
+                            .get(&key)
+                            .unwrap_or_else(|| panic!("no setting named '{}'", key));
+                        let value = coerce(&text, default, &Source::CommandLine)?;
+                        set(&key, value, Source::CommandLine);
+                    }
+                }
+            }
+        }
+
+        // A value can have the right JSON type and still be rejected, as
+        // an enum rejects a name it doesn't have. Trying each setting on
+        // its own, among the defaults, finds which one, and so where the
+        // mistake was made.
+        for (key, source) in &sources {
+            if *source == Source::Default {
+                continue;
+            }
+            let mut one: Map<String, Value> = defaults.clone();
+            one.insert(key.clone(), merged[key].clone());
+            if let Err(e) = serde_json::from_value::<T>(Value::Object(one)) {
+                return Err(invalid(format!(
+                    "bad value for '{}' from {}: {}",
+                    key, source, e
+                )));
+            }
+        }
+        let settings = serde_json::from_value(Value::Object(merged))
+            .map_err(|e| invalid(format!("invalid settings: {}", e)))?;
+        Ok((settings, sources))
+    }
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn read_file(path: &Path, required: bool) -> Result<Option<toml::Table>, Error> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    toml::from_str(&text).map(Some).map_err(|e| {
+        invalid(format!(
+            "{} isn't valid TOML: {}",
+            path.display(),
+            e.message()
+        ))
+    })
+}
+
+/// Convert `text` to the JSON type of `default`.
+fn coerce(text: &str, default: &Value, source: &Source) -> Result<Value, Error> {
+    let bad = |what: &str| {
+        invalid(format!(
+            "{} from {}: expected {}",
+            quote(text),
+            source,
+            what
+        ))
+    };
+    Ok(match default {
+        Value::Bool(_) => match text {
+            "true" | "1" | "yes" => Value::Bool(true),
+            "false" | "0" | "no" => Value::Bool(false),
+            _ => return Err(bad("true or false")),
+        },
+        Value::Number(n) if n.is_f64() => {
+            let x: f64 = text.parse().map_err(|_| bad("a number"))?;
+            serde_json::Number::from_f64(x)
+                .map(Value::Number)
+                .ok_or_else(|| bad("a finite number"))?
+        }
+        Value::Number(_) => {
+            if let Ok(n) = text.parse::<u64>() {
+                Value::from(n)
+            } else {
+                Value::from(text.parse::<i64>().map_err(|_| bad("a whole number"))?)
+            }
+        }
+        Value::Array(_) => Value::Array(
+            text.split(',')
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ),
+        // An unset `Option`: guess from the text itself.
+        Value::Null => serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.into())),
+        _ => Value::String(text.to_string()),
+    })
+}
+
+fn quote(text: &str) -> String {
+    format!("{:?}", text)
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(message).with_code(exit::CONFIG)
+}
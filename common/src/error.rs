@@ -0,0 +1,154 @@
+// The `CliError` trait that `run` reports through, and `Error`, a
+// ready-made type for programs that don't need their own.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use crate::exit;
+
+/// An error a command-line program can report: a message, the errors
+/// underneath it, and the exit code it calls for.
+pub trait CliError: fmt::Display {
+    fn exit_code(&self) -> u8 {
+        exit::FAILURE
+    }
+
+    /// The error that caused this one, if any. `render` follows the chain
+    /// through each one's `source`.
+    fn cause(&self) -> Option<&(dyn StdError + 'static)> {
+        None
+    }
+}
+
+/// A message, perhaps with an error underneath it.
+///
+/// Any standard error converts to an `Error` with `?`, taking its exit code
+/// from `exit::for_error`. `Context::context` wraps one in a message saying
+/// what was being attempted.
+pub struct Error {
+    /// What to say first. If this is `None`, the error came straight from
+    /// `cause`, which speaks for itself.
+    message: Option<String>,
+    cause: Option<Box<dyn StdError + Send + Sync>>,
+    code: u8,
+}
+
+impl Error {
+    pub fn new(message: impl fmt::Display) -> Error {
+        Error {
+            message: Some(message.to_string()),
+            cause: None,
+            code: exit::FAILURE,
+        }
+    }
+
+    /// An error in the program's arguments. Print the usage message first.
+    pub fn usage(message: impl fmt::Display) -> Error {
+        Error::new(message).with_code(exit::USAGE)
+    }
+
+    /// An already boxed error, like the `GenericError` of chapter 7. A
+    /// `Box<dyn Error>` isn't itself an `Error`, so `?` can't convert it.
+    pub fn from_boxed(e: Box<dyn StdError + Send + Sync>) -> Error {
+        Error {
+            message: None,
+            code: exit::for_error(&*e),
+            cause: Some(e),
+        }
+    }
+
+    pub fn with_code(mut self, code: u8) -> Error {
+        self.code = code;
+        self
+    }
+}
+
+impl CliError for Error {
+    fn exit_code(&self) -> u8 {
+        self.code
+    }
+
+    fn cause(&self) -> Option<&(dyn StdError + 'static)> {
+        let cause: &(dyn StdError + 'static) = self.cause.as_deref()?;
+        match self.message {
+            Some(_) => Some(cause),
+            None => cause.source(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.message, &self.cause) {
+            (Some(message), _) => f.write_str(message),
+            (None, Some(cause)) => fmt::Display::fmt(cause, f),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&crate::render(self))
+    }
+}
+
+/// `Error` doesn't implement `std::error::Error` itself, or this would
+/// overlap with the standard library's `impl<T> From<T> for T`.
+impl<E: StdError + Send + Sync + 'static> From<E> for Error {
+    fn from(e: E) -> Error {
+        Error {
+            message: None,
+            code: exit::for_error(&e),
+            cause: Some(Box::new(e)),
+        }
+    }
+}
+
+/// Say what was being attempted when an error happened:
+///
+/// ```no_run
+/// use common::Context;
+/// # fn f() -> Result<(), common::Error> {
+/// let text = std::fs::read_to_string("in.txt")
+///     .with_context(|| "failed to read 'in.txt'")?;
+/// # Ok(()) }
+/// ```
+pub trait Context<T> {
+    fn context(self, message: impl fmt::Display) -> Result<T, Error>;
+
+    /// Like `context`, but only builds the message if there's an error.
+    fn with_context<M: fmt::Display>(self, message: impl FnOnce() -> M) -> Result<T, Error>;
+}
+
+impl<T, E: StdError + Send + Sync + 'static> Context<T> for Result<T, E> {
+    fn context(self, message: impl fmt::Display) -> Result<T, Error> {
+        self.with_context(|| message)
+    }
+
+    fn with_context<M: fmt::Display>(self, message: impl FnOnce() -> M) -> Result<T, Error> {
+        self.map_err(|e| Error {
+            message: Some(message().to_string()),
+            code: exit::for_error(&e),
+            cause: Some(Box::new(e)),
+        })
+    }
+}
+
+impl<T> Context<T> for Result<T, Error> {
+    fn context(self, message: impl fmt::Display) -> Result<T, Error> {
+        self.with_context(|| message)
+    }
+
+    fn with_context<M: fmt::Display>(self, message: impl FnOnce() -> M) -> Result<T, Error> {
+        self.map_err(|e| Error {
+            message: Some(match e.message {
+                Some(inner) => format!("{}: {}", message(), inner),
+                // `e` was only its cause, which now follows our message.
+                None => message().to_string(),
+            }),
+            code: e.code,
+            cause: e.cause,
+        })
+    }
+}
@@ -0,0 +1,58 @@
+// Exit codes. 0 is success and 1 a failure with no more specific code. 2
+// means the program was run wrong, as for most Unix tools. The rest are
+// from BSD's `sysexits.h`, for the failures common enough to be worth
+// telling apart in a script.
+
+use std::error::Error;
+use std::io;
+use std::num::{ParseFloatError, ParseIntError};
+use std::str::Utf8Error;
+use std::string::FromUtf8Error;
+
+pub const SUCCESS: u8 = 0;
+pub const FAILURE: u8 = 1;
+/// Bad arguments: the usage message has been printed.
+pub const USAGE: u8 = 2;
+/// The input was read, but it's malformed.
+pub const DATA_ERR: u8 = 65;
+/// An input file doesn't exist.
+pub const NO_INPUT: u8 = 66;
+/// Some other input or output failed.
+pub const IO_ERR: u8 = 74;
+/// Permission denied.
+pub const NO_PERM: u8 = 77;
+/// A configuration file or setting is wrong.
+pub const CONFIG: u8 = 78;
+
+/// The exit code for `error`, judged by the first error in its chain of
+/// sources that says anything more specific than `FAILURE`.
+pub fn for_error(error: &(dyn Error + 'static)) -> u8 {
+    let mut next = Some(error);
+    while let Some(e) = next {
+        let code = for_one(e);
+        if code != FAILURE {
+            return code;
+        }
+        next = e.source();
+    }
+    FAILURE
+}
+
+fn for_one(e: &(dyn Error + 'static)) -> u8 {
+    if let Some(e) = e.downcast_ref::<io::Error>() {
+        return match e.kind() {
+            io::ErrorKind::NotFound => NO_INPUT,
+            io::ErrorKind::PermissionDenied => NO_PERM,
+            io::ErrorKind::InvalidData => DATA_ERR,
+            _ => IO_ERR,
+        };
+    }
+    if e.is::<ParseIntError>()
+        || e.is::<ParseFloatError>()
+        || e.is::<Utf8Error>()
+        || e.is::<FromUtf8Error>()
+    {
+        return DATA_ERR;
+    }
+    FAILURE
+}
@@ -0,0 +1,181 @@
+// Messages for people, in their language.
+//
+// A program keeps what it says in Fluent files, one per language, and
+// embeds them with `include_str!`:
+//
+//     # locales/en/quickreplace.ftl
+//     wrote-output = Successfully replaced text and wrote output to '{ $file }'
+//
+// At startup it picks a language, from `--lang` if it was given and from
+// the locale environment variables if not, and gets a `Messages` for it.
+// `tr!` looks a message up and fills in its arguments:
+//
+//     let messages = Messages::new(CATALOGS, lang.as_deref());
+//     println!("{}", tr!(messages, "wrote-output", file = "out.txt"));
+//
+// English is always the fallback: for a language with no catalog, and
+// for a message a catalog doesn't have yet.
+
+use std::env;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::FluentResource;
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::{FluentArgs, FluentValue};
+
+use crate::Error;
+
+/// The language used when nothing better is available.
+pub const FALLBACK: &str = "en";
+
+/// A program's catalogs: each language's tag, like `"es"`, and its Fluent
+/// source. One of them must be English.
+pub type Catalogs = &'static [(&'static str, &'static str)];
+
+/// The messages of one language, backed by English. Fluent's concurrent
+/// bundles make them `Sync`, so threads working in parallel can share one.
+pub struct Messages {
+    lang: LanguageIdentifier,
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Messages {
+    /// The messages from `catalogs` that best suit `requested`, or the
+    /// locale's language when it's `None`. A catalog that doesn't parse is
+    /// a bug in the program, not the user's input, so it panics.
+    pub fn new(catalogs: Catalogs, requested: Option<&str>) -> Messages {
+        let wanted = match requested {
+            Some(tag) => tag.parse().ok(),
+            None => locale(),
+        };
+        let lang = wanted
+            .and_then(|wanted| choose(catalogs, &wanted))
+            .unwrap_or_else(|| FALLBACK.parse().unwrap());
+        let mut bundles = vec![bundle(catalogs, &lang)];
+        if lang.language.as_str() != FALLBACK {
+            bundles.push(bundle(catalogs, &FALLBACK.parse().unwrap()));
+        }
+        Messages { lang, bundles }
+    }
+
+    /// The language chosen.
+    pub fn lang(&self) -> &LanguageIdentifier {
+        &self.lang
+    }
+
+    /// The message `id`, with `args` filled in. A message no catalog has
+    /// comes back as its id, which is easy to spot and to search for.
+    pub fn format(&self, id: &str, args: &FluentArgs) -> String {
+        for bundle in &self.bundles {
+            let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+            debug_assert!(errors.is_empty(), "formatting {}: {:?}", id, errors);
+            return text.into_owned();
+        }
+        debug_assert!(false, "no message {}", id);
+        id.to_string()
+    }
+}
+
+/// Look up a message and format it with named arguments:
+/// `tr!(messages, "read-failed", file = name)`. Arguments can be strings
+/// or numbers; numbers let a catalog pick plural forms.
+#[macro_export]
+macro_rules! tr {
+    ($messages:expr, $id:expr $(, $name:ident = $value:expr)* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut args = $crate::i18n::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)*
+        $messages.format($id, &args)
+    }};
+}
+
+/// Remove `--lang LANG` or `--lang=LANG` from `args`, the way
+/// `LogOptions::take_from` removes the logging flags, and return `LANG`.
+pub fn take_lang(args: &mut Vec<String>) -> Result<Option<String>, Error> {
+    let mut lang = None;
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            break;
+        }
+        if let Some(tag) = arg.strip_prefix("--lang=") {
+            lang = Some(tag.to_string());
+        } else if arg == "--lang" {
+            let tag = args
+                .get(i + 1)
+                .ok_or_else(|| Error::usage("--lang needs a language, like en or es"))?;
+            lang = Some(tag.clone());
+            args.remove(i + 1);
+        } else {
+            i += 1;
+            continue;
+        }
+        args.remove(i);
+    }
+    if let Some(tag) = &lang {
+        tag.parse::<LanguageIdentifier>()
+            .map_err(|_| Error::usage(format!("{:?} isn't a language tag", tag)))?;
+    }
+    Ok(lang)
+}
+
+/// The language the environment asks for, checking the variables in the
+/// order POSIX does: `LC_ALL`, then `LC_MESSAGES`, then `LANG`. GNU's
+/// `LANGUAGE`, a list like `es:en`, comes first when it's set. The `C` and
+/// `POSIX` locales ask for nothing in particular.
+pub fn locale() -> Option<LanguageIdentifier> {
+    let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+    let value = var("LANGUAGE")
+        .and_then(|list| list.split(':').next().map(str::to_string))
+        .or_else(|| var("LC_ALL"))
+        .or_else(|| var("LC_MESSAGES"))
+        .or_else(|| var("LANG"))?;
+    parse_locale(&value)
+}
+
+/// Turn a POSIX locale name, `language_TERRITORY.codeset@modifier`, into a
+/// language tag: `es_MX.UTF-8` is `es-MX`.
+pub fn parse_locale(value: &str) -> Option<LanguageIdentifier> {
+    let name = value.split(['.', '@']).next()?;
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    name.replace('_', "-").parse().ok()
+}
+
+/// The catalog's tag that best matches `wanted`: the same tag, or failing
+/// that, the same language, so `es-MX` gets `es`.
+fn choose(catalogs: Catalogs, wanted: &LanguageIdentifier) -> Option<LanguageIdentifier> {
+    let tags: Vec<LanguageIdentifier> = catalogs
+        .iter()
+        .map(|(tag, _)| tag.parse().expect("bad language tag in catalog"))
+        .collect();
+    tags.iter()
+        .find(|tag| *tag == wanted)
+        .or_else(|| tags.iter().find(|tag| tag.language == wanted.language))
+        .cloned()
+}
+
+fn bundle(catalogs: Catalogs, lang: &LanguageIdentifier) -> FluentBundle<FluentResource> {
+    let source = catalogs
+        .iter()
+        .find(|(tag, _)| tag.parse::<LanguageIdentifier>().as_ref() == Ok(lang))
+        .map(|(_, source)| *source)
+        .unwrap_or_else(|| panic!("no catalog for {}", lang));
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("catalog {} doesn't parse: {:?}", lang, errors));
+    let mut bundle = FluentBundle::new_concurrent(vec![lang.clone()]);
+    // Fluent wraps arguments in Unicode isolation marks, for mixing
+    // left-to-right and right-to-left text. A terminal shows them as junk.
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|errors| panic!("catalog {}: {:?}", lang, errors));
+    bundle
+}
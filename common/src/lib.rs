@@ -0,0 +1,83 @@
+// What the workspace's command-line programs share: one way to report an
+// error, and one set of exit codes.
+//
+// A program's `main` hands its real work to `run`, which returns an
+// `ExitCode`. Anything that goes wrong comes back as an error, and `run`
+// prints it as `Error: ...` on stderr, in red on a terminal, and exits with
+// the code the error calls for. No more `eprintln!` and `exit(1)` at every
+// `match`. For what a program has to say on its way to succeeding, there's
+// `logging`, for settings that come from more than the command line,
+// `config`, and for saying it in the user's language, `i18n`.
+
+pub mod config;
+pub mod exit;
+pub mod i18n;
+pub mod logging;
+
+mod error;
+
+pub use error::{CliError, Context, Error};
+pub use i18n::Messages;
+pub use logging::{init_logging, LogFormat, LogOptions};
+
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+
+use colored::Colorize;
+
+/// Run a program's `main`, report its error if it fails, and return the
+/// exit code to use:
+///
+/// ```no_run
+/// fn main() -> std::process::ExitCode {
+///     common::run(|| {
+///         let text = std::fs::read_to_string("input.txt")?;
+///         println!("{}", text.len());
+///         Ok::<(), common::Error>(())
+///     })
+/// }
+/// ```
+pub fn run<E: CliError>(main: impl FnOnce() -> Result<(), E>) -> ExitCode {
+    match main() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            report(&e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+/// Print `error` to stderr the way `run` does.
+pub fn report(error: &dyn CliError) {
+    eprintln!("{} {}", prefix(), render(error));
+}
+
+/// `error`'s message, followed by its causes, separated by colons:
+/// `failed to read 'in.txt': No such file or directory (os error 2)`.
+pub fn render(error: &dyn CliError) -> String {
+    let mut text = error.to_string();
+    let mut cause = error.cause();
+    while let Some(e) = cause {
+        text.push_str(": ");
+        text.push_str(&e.to_string());
+        cause = e.source();
+    }
+    text
+}
+
+/// `Error:`, in bold red if stderr is a terminal. Piped into a file or
+/// another program, color codes are just noise. `colored` also honors the
+/// `NO_COLOR` and `CLICOLOR_FORCE` variables.
+fn prefix() -> String {
+    if color() {
+        "Error:".red().bold().to_string()
+    } else {
+        "Error:".to_string()
+    }
+}
+
+/// Whether to color what goes to stderr.
+fn color() -> bool {
+    let wanted = io::stderr().is_terminal() || std::env::var_os("CLICOLOR_FORCE").is_some();
+    wanted && std::env::var_os("NO_COLOR").is_none()
+}
@@ -0,0 +1,150 @@
+// Logging, set up the same way in every program.
+//
+// Programs log with `tracing`'s macros, `tracing::info!` and the rest, and
+// call `init_logging` once at the start to say where the events go: to
+// stderr, so they never mix with a program's output, as text or as one
+// JSON object per line. `-v` on the command line shows more; `RUST_LOG`,
+// when it's set, overrides the flags with its own filter, like
+// `RUST_LOG=ch_02=debug,regex=warn`.
+
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::time;
+use tracing_subscriber::EnvFilter;
+
+use crate::Error;
+
+/// How log events are written. In configuration files, it's `"text"` or
+/// `"json"`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// One line per event for people to read, with the time since the
+    /// program started.
+    #[default]
+    Text,
+    /// One JSON object per line, for programs to read.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<LogFormat, Error> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(Error::usage(format!(
+                "unknown log format {:?}: expected text or json",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// The logging flags from a command line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// How many times `-v` was given.
+    pub verbosity: u8,
+    /// `None` if `--log-format` wasn't given.
+    pub format: Option<LogFormat>,
+}
+
+impl LogOptions {
+    /// Remove the logging flags from `args` and return what they asked
+    /// for, leaving everything else, in order, for the program's own
+    /// parser. The flags are `-v` or `--verbose`, which may be repeated or
+    /// run together as `-vv`, and `--log-format FORMAT` or
+    /// `--log-format=FORMAT`. Nothing after a `--` is touched.
+    pub fn take_from(args: &mut Vec<String>) -> Result<LogOptions, Error> {
+        let mut options = LogOptions::default();
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i].as_str();
+            if arg == "--" {
+                break;
+            }
+            if arg == "--verbose" {
+                options.verbosity = options.verbosity.saturating_add(1);
+            } else if arg.len() > 1 && arg.starts_with('-') && arg[1..].bytes().all(|b| b == b'v') {
+                let count = u8::try_from(arg.len() - 1).unwrap_or(u8::MAX);
+                options.verbosity = options.verbosity.saturating_add(count);
+            } else if let Some(format) = arg.strip_prefix("--log-format=") {
+                options.format = Some(format.parse()?);
+            } else if arg == "--log-format" {
+                let format = args
+                    .get(i + 1)
+                    .ok_or_else(|| Error::usage("--log-format needs text or json"))?;
+                options.format = Some(format.parse()?);
+                args.remove(i + 1);
+            } else {
+                i += 1;
+                continue;
+            }
+            args.remove(i);
+        }
+        Ok(options)
+    }
+
+    /// Start logging as these options say. See `init_logging`.
+    pub fn init(self) {
+        init_logging(self.verbosity, self.format.unwrap_or_default());
+    }
+}
+
+/// The most detailed level shown at `verbosity`, unless `RUST_LOG` says
+/// otherwise: warnings and errors by default, then info, debug, and trace
+/// for each `-v`.
+pub fn level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Send `tracing` events to stderr, in `format`, filtered by `RUST_LOG`
+/// if it's set and by `verbosity` if not. Directives in `RUST_LOG` that
+/// don't parse are ignored. Calling this again does nothing, so tests can
+/// call it freely.
+pub fn init_logging(verbosity: u8, format: LogFormat) {
+    let filter = EnvFilter::builder()
+        .with_default_directive(level(verbosity).into())
+        .from_env_lossy();
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr);
+    // Either way, a second subscriber can't be installed; that's fine.
+    let _ = match format {
+        LogFormat::Text => builder
+            .with_timer(time::uptime())
+            .with_target(false)
+            .with_ansi(crate::color())
+            .try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    };
+}
+
+/// The command-line arguments after the program's name, less any logging
+/// flags, with logging started as those flags say. For programs that
+/// parse their own arguments, this is the first line of `main`.
+pub fn args() -> Result<Vec<String>, Error> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    LogOptions::take_from(&mut args)?.init();
+    Ok(args)
+}
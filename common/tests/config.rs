@@ -0,0 +1,147 @@
+use std::fs;
+
+use common::config::{Loader, Source};
+use common::{exit, CliError, LogFormat};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct Settings {
+    verbosity: u8,
+    log_format: LogFormat,
+    ignore_case: bool,
+    ratio: f64,
+    excludes: Vec<String>,
+    backup: Option<String>,
+}
+
+fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn loader(file: &str) -> (tempfile::TempDir, Loader<Settings>) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("settings.toml");
+    fs::write(&path, file).unwrap();
+    let loader = Loader::new("TOOL").file(&path, true);
+    (dir, loader)
+}
+
+#[test]
+fn defaults_when_nothing_is_set() {
+    let settings: Settings = Loader::new("TOOL")
+        .file("/no/such/file.toml", false)
+        .env_from(env(&[("PATH", "/bin"), ("TOOL_UNRELATED", "x")]))
+        .load()
+        .unwrap();
+    assert_eq!(settings, Settings::default());
+}
+
+#[test]
+fn file_over_defaults() {
+    let (_dir, loader) = loader("verbosity = 1\nlog_format = \"json\"\nexcludes = [\"target\"]\n");
+    let settings = loader.load().unwrap();
+    assert_eq!(settings.verbosity, 1);
+    assert_eq!(settings.log_format, LogFormat::Json);
+    assert_eq!(settings.excludes, ["target"]);
+    assert!(!settings.ignore_case);
+}
+
+#[test]
+fn env_over_file() {
+    let (_dir, loader) = loader("verbosity = 1\nignore_case = false\n");
+    let settings = loader
+        .env_from(env(&[
+            ("TOOL_VERBOSITY", "3"),
+            ("TOOL_IGNORE_CASE", "true"),
+        ]))
+        .load()
+        .unwrap();
+    assert_eq!(settings.verbosity, 3);
+    assert!(settings.ignore_case);
+}
+
+#[test]
+fn command_line_over_env_and_file() {
+    let (_dir, loader) = loader("verbosity = 1\nlog_format = \"json\"\n");
+    let (settings, sources) = loader
+        .set("verbosity", "2")
+        .env_from(env(&[("TOOL_VERBOSITY", "3"), ("TOOL_LOG_FORMAT", "text")]))
+        .load_with_sources()
+        .unwrap();
+    assert_eq!(settings.verbosity, 2);
+    assert_eq!(settings.log_format, LogFormat::Text);
+
+    let source = |key: &str| sources.iter().find(|(k, _)| k == key).unwrap().1.clone();
+    assert_eq!(source("verbosity"), Source::CommandLine);
+    assert_eq!(
+        source("log_format"),
+        Source::Env("TOOL_LOG_FORMAT".to_string())
+    );
+    assert_eq!(source("ratio"), Source::Default);
+}
+
+#[test]
+fn later_files_win() {
+    let (dir, loader) = loader("verbosity = 1\nratio = 0.5\n");
+    let second = dir.path().join("second.toml");
+    fs::write(&second, "verbosity = 2\n").unwrap();
+    let settings = loader.file(&second, true).load().unwrap();
+    assert_eq!(settings.verbosity, 2);
+    assert_eq!(settings.ratio, 0.5);
+}
+
+#[test]
+fn strings_take_the_type_of_the_setting() {
+    let settings: Settings = Loader::new("TOOL")
+        .set("ratio", "2.5")
+        .set("excludes", "target,*.lock")
+        .set("backup", ".bak")
+        .load()
+        .unwrap();
+    assert_eq!(settings.ratio, 2.5);
+    assert_eq!(settings.excludes, ["target", "*.lock"]);
+    assert_eq!(settings.backup.as_deref(), Some(".bak"));
+}
+
+#[test]
+fn errors_say_where_the_value_came_from() {
+    let message = |loader: Loader<Settings>| {
+        let e = loader.load().unwrap_err();
+        assert_eq!(e.exit_code(), exit::CONFIG);
+        e.to_string()
+    };
+    let e = message(Loader::new("TOOL").env_from(env(&[("TOOL_VERBOSITY", "loud")])));
+    assert_eq!(e, r#""loud" from $TOOL_VERBOSITY: expected a whole number"#);
+
+    let e = message(Loader::new("TOOL").set("log_format", "xml"));
+    assert!(
+        e.starts_with("bad value for 'log_format' from the command line: unknown variant `xml`"),
+        "{}",
+        e
+    );
+
+    let (_dir, file) = loader("colour = \"red\"\n");
+    assert!(message(file).starts_with("unknown setting 'colour' in "));
+    let (_dir, file) = loader("verbosity = \n");
+    assert!(message(file).contains("isn't valid TOML"));
+    let (_dir, file) = loader("verbosity = \"high\"\n");
+    assert!(message(file).starts_with("bad value for 'verbosity' from "));
+}
+
+#[test]
+fn a_required_file_must_exist() {
+    let e = Loader::<Settings>::new("TOOL")
+        .file("/no/such/file.toml", true)
+        .load()
+        .unwrap_err();
+    assert_eq!(e.exit_code(), exit::NO_INPUT);
+    assert!(
+        e.to_string()
+            .starts_with("failed to read /no/such/file.toml"),
+        "{}",
+        e
+    );
+}
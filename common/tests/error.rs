@@ -0,0 +1,103 @@
+use std::fs;
+use std::io;
+use std::process::ExitCode;
+
+use common::{exit, render, run, CliError, Context, Error};
+
+fn read(path: &str) -> Result<String, Error> {
+    let text = fs::read_to_string(path).with_context(|| format!("failed to read '{}'", path))?;
+    Ok(text)
+}
+
+#[test]
+fn context_comes_first() {
+    let e = read("/no/such/file").unwrap_err();
+    assert_eq!(
+        render(&e),
+        "failed to read '/no/such/file': No such file or directory (os error 2)"
+    );
+    assert_eq!(e.exit_code(), exit::NO_INPUT);
+
+    // Context on context reads outward in.
+    let e = read("/no/such/file").context("loading config").unwrap_err();
+    assert_eq!(
+        render(&e),
+        "loading config: failed to read '/no/such/file': No such file or directory (os error 2)"
+    );
+    assert_eq!(e.exit_code(), exit::NO_INPUT);
+}
+
+#[test]
+fn question_mark_converts() {
+    fn parse(s: &str) -> Result<i64, Error> {
+        Ok(s.trim().parse::<i64>()?)
+    }
+    let e = parse("forty").unwrap_err();
+    assert_eq!(render(&e), "invalid digit found in string");
+    assert_eq!(e.exit_code(), exit::DATA_ERR);
+
+    let e = parse("forty").context("line 3").unwrap_err();
+    assert_eq!(render(&e), "line 3: invalid digit found in string");
+}
+
+#[test]
+fn exit_codes_follow_the_source_chain() {
+    #[derive(Debug)]
+    struct Wrapper(io::Error);
+    impl std::fmt::Display for Wrapper {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "wrapped")
+        }
+    }
+    impl std::error::Error for Wrapper {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let denied = Wrapper(io::Error::from(io::ErrorKind::PermissionDenied));
+    assert_eq!(exit::for_error(&denied), exit::NO_PERM);
+    let e = Error::from(denied);
+    assert_eq!(render(&e), "wrapped: permission denied");
+    assert_eq!(e.exit_code(), exit::NO_PERM);
+
+    let other = io::Error::other("disk on fire");
+    assert_eq!(exit::for_error(&other), exit::IO_ERR);
+    assert_eq!(exit::for_error(&std::fmt::Error), exit::FAILURE);
+}
+
+#[test]
+fn plain_and_usage_errors() {
+    let e = Error::new("nothing to do");
+    assert_eq!(render(&e), "nothing to do");
+    assert_eq!(e.exit_code(), exit::FAILURE);
+    assert_eq!(Error::usage("missing FILE").exit_code(), exit::USAGE);
+    assert_eq!(Error::new("x").with_code(42).exit_code(), 42);
+}
+
+#[test]
+fn run_returns_the_code() {
+    assert_eq!(run(|| Ok::<(), Error>(())), ExitCode::SUCCESS);
+    assert_eq!(
+        run(|| Err(Error::usage("bad"))),
+        ExitCode::from(exit::USAGE)
+    );
+}
+
+/// A program's own error type only needs `Display` and, optionally, a code.
+#[test]
+fn custom_error_types() {
+    struct Lost;
+    impl std::fmt::Display for Lost {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "lost")
+        }
+    }
+    impl CliError for Lost {
+        fn exit_code(&self) -> u8 {
+            3
+        }
+    }
+    assert_eq!(render(&Lost), "lost");
+    assert_eq!(run(|| Err(Lost)), ExitCode::from(3));
+}
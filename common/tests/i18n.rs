@@ -0,0 +1,108 @@
+use common::i18n::{parse_locale, take_lang, Catalogs};
+use common::{exit, tr, CliError, Messages};
+
+const CATALOGS: Catalogs = &[
+    (
+        "en",
+        "hello = Hello, { $name }!\nonly-english = Just English\n\
+         files = { $count ->\n    [one] one file\n   *[other] { $count } files\n}\n",
+    ),
+    (
+        "es",
+        "hello = ¡Hola, { $name }!\n\
+         files = { $count ->\n    [one] un archivo\n   *[other] { $count } archivos\n}\n",
+    ),
+    ("pt-BR", "hello = Olá, { $name }!\n"),
+];
+
+#[test]
+fn formats_in_the_chosen_language() {
+    let en = Messages::new(CATALOGS, Some("en"));
+    assert_eq!(tr!(en, "hello", name = "Ana"), "Hello, Ana!");
+    let es = Messages::new(CATALOGS, Some("es"));
+    assert_eq!(es.lang().to_string(), "es");
+    // No isolation marks around the name.
+    assert_eq!(tr!(es, "hello", name = "Ana"), "¡Hola, Ana!");
+}
+
+#[test]
+fn numbers_choose_plural_forms() {
+    let en = Messages::new(CATALOGS, Some("en"));
+    assert_eq!(tr!(en, "files", count = 1), "one file");
+    assert_eq!(tr!(en, "files", count = 3), "3 files");
+    let es = Messages::new(CATALOGS, Some("es"));
+    assert_eq!(tr!(es, "files", count = 1), "un archivo");
+    assert_eq!(tr!(es, "files", count = 0), "0 archivos");
+}
+
+#[test]
+fn falls_back_to_english() {
+    // A message the catalog lacks.
+    let es = Messages::new(CATALOGS, Some("es"));
+    assert_eq!(tr!(es, "only-english"), "Just English");
+    // A language with no catalog.
+    let fr = Messages::new(CATALOGS, Some("fr"));
+    assert_eq!(fr.lang().to_string(), "en");
+    // A territory with no catalog of its own gets its language's.
+    let mx = Messages::new(CATALOGS, Some("es-MX"));
+    assert_eq!(mx.lang().to_string(), "es");
+    let br = Messages::new(CATALOGS, Some("pt-BR"));
+    assert_eq!(tr!(br, "hello", name = "Ana"), "Olá, Ana!");
+}
+
+#[test]
+fn locale_names_become_language_tags() {
+    let tag = |s| parse_locale(s).map(|id| id.to_string());
+    assert_eq!(tag("es_MX.UTF-8"), Some("es-MX".to_string()));
+    assert_eq!(tag("de_DE@euro"), Some("de-DE".to_string()));
+    assert_eq!(tag("fr"), Some("fr".to_string()));
+    assert_eq!(tag("C.UTF-8"), None);
+    assert_eq!(tag("POSIX"), None);
+    assert_eq!(tag(""), None);
+}
+
+#[test]
+fn takes_the_lang_flag() {
+    let take = |args: &[&str]| {
+        let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        take_lang(&mut args).map(|lang| (lang, args))
+    };
+    assert_eq!(
+        take(&["--lang", "es", "a", "b"]).unwrap(),
+        (
+            Some("es".to_string()),
+            vec!["a".to_string(), "b".to_string()]
+        )
+    );
+    assert_eq!(
+        take(&["a", "--lang=pt-BR"]).unwrap(),
+        (Some("pt-BR".to_string()), vec!["a".to_string()])
+    );
+    assert_eq!(
+        take(&["--", "--lang", "es"]).unwrap().0,
+        None,
+        "nothing after -- is a flag"
+    );
+    assert_eq!(take(&["--lang"]).unwrap_err().exit_code(), exit::USAGE);
+    assert_eq!(
+        take(&["--lang", "not a tag"]).unwrap_err().exit_code(),
+        exit::USAGE
+    );
+}
+
+#[test]
+fn messages_are_shared_between_threads() {
+    fn sync<T: Send + Sync>(_: &T) {}
+    let messages = Messages::new(CATALOGS, Some("es"));
+    sync(&messages);
+    let plurals: Vec<String> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (1..=3)
+            .map(|count| {
+                let messages = &messages;
+                scope.spawn(move || tr!(messages, "files", count = count))
+            })
+            .collect();
+        threads.into_iter().map(|t| t.join().unwrap()).collect()
+    });
+    assert_eq!(plurals, ["un archivo", "2 archivos", "3 archivos"]);
+}
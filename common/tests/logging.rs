@@ -0,0 +1,66 @@
+use common::exit;
+use common::logging::level;
+use common::{CliError, LogFormat, LogOptions};
+use tracing::level_filters::LevelFilter;
+
+fn take(args: &[&str]) -> (LogOptions, Vec<String>) {
+    let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    let options = LogOptions::take_from(&mut args).unwrap();
+    (options, args)
+}
+
+#[test]
+fn leaves_other_arguments_alone() {
+    let (options, rest) = take(&["-e", "1 + 2", "file.txt"]);
+    assert_eq!(options, LogOptions::default());
+    assert_eq!(rest, ["-e", "1 + 2", "file.txt"]);
+}
+
+#[test]
+fn counts_verbosity() {
+    let (options, rest) = take(&["-v", "a", "--verbose", "-vv", "b"]);
+    assert_eq!(options.verbosity, 4);
+    assert_eq!(rest, ["a", "b"]);
+    // `-` alone usually means stdin, and isn't a flag.
+    let (options, rest) = take(&["-", "-verbose"]);
+    assert_eq!(options.verbosity, 0);
+    assert_eq!(rest, ["-", "-verbose"]);
+}
+
+#[test]
+fn reads_the_format() {
+    let (options, rest) = take(&["--log-format", "json", "x"]);
+    assert_eq!(options.format, Some(LogFormat::Json));
+    assert_eq!(rest, ["x"]);
+    let (options, rest) = take(&["x", "--log-format=text"]);
+    assert_eq!(options.format, Some(LogFormat::Text));
+    assert_eq!(rest, ["x"]);
+}
+
+#[test]
+fn stops_at_double_dash() {
+    let (options, rest) = take(&["-v", "--", "-v", "--log-format", "yaml"]);
+    assert_eq!(options.verbosity, 1);
+    assert_eq!(rest, ["--", "-v", "--log-format", "yaml"]);
+}
+
+#[test]
+fn bad_formats_are_usage_errors() {
+    for args in [
+        &["--log-format", "yaml"][..],
+        &["--log-format"],
+        &["--log-format="],
+    ] {
+        let mut args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let e = LogOptions::take_from(&mut args).unwrap_err();
+        assert_eq!(e.exit_code(), exit::USAGE, "{}", e);
+    }
+}
+
+#[test]
+fn verbosity_levels() {
+    assert_eq!(level(0), LevelFilter::WARN);
+    assert_eq!(level(1), LevelFilter::INFO);
+    assert_eq!(level(2), LevelFilter::DEBUG);
+    assert_eq!(level(9), LevelFilter::TRACE);
+}
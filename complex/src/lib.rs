@@ -0,0 +1,284 @@
+// The book's operator-overloading example grown into a small library: a
+// generic `Complex<T>` supporting the arithmetic, compound-assignment,
+// negation, equality, and formatting traits.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, Default, Hash)]
+pub struct Complex<T> {
+    /// Real portion of the complex number
+    pub re: T,
+
+    /// Imaginary portion of the complex number
+    pub im: T,
+}
+
+impl<T> Complex<T> {
+    pub const fn new(re: T, im: T) -> Complex<T> {
+        Complex { re, im }
+    }
+}
+
+impl<T: Neg<Output = T>> Complex<T> {
+    /// The complex conjugate: the same real part, negated imaginary part.
+    pub fn conj(self) -> Complex<T> {
+        Complex {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Mul<Output = T>> Complex<T> {
+    /// The squared magnitude, `re² + im²`. Unlike `norm`, this needs no
+    /// square root, so it works for integer components too.
+    pub fn norm_sqr(&self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+// Operator traits take their operands by value. Each impl only asks for the
+// capabilities of `T` that its formula actually uses.
+
+impl<T> Add for Complex<T>
+where
+    T: Add<Output = T>,
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Complex {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl<T> Sub for Complex<T>
+where
+    T: Sub<Output = T>,
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Complex {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl<T> Mul for Complex<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    type Output = Self;
+    /// `(a + bi)(c + di) = (ac - bd) + (ad + bc)i`
+    fn mul(self, rhs: Self) -> Self {
+        Complex {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+impl<T> Div for Complex<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    type Output = Self;
+    /// Multiply numerator and denominator by the denominator's conjugate:
+    /// `(a + bi)/(c + di) = ((ac + bd) + (bc - ad)i) / (c² + d²)`
+    fn div(self, rhs: Self) -> Self {
+        let denominator = rhs.norm_sqr();
+        Complex {
+            re: (self.re * rhs.re + self.im * rhs.im) / denominator,
+            im: (self.im * rhs.re - self.re * rhs.im) / denominator,
+        }
+    }
+}
+
+// Scaling by a value of the component type: `z * 2.0`, `z / 2.0`.
+
+impl<T> Mul<T> for Complex<T>
+where
+    T: Copy + Mul<Output = T>,
+{
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        Complex {
+            re: self.re * rhs,
+            im: self.im * rhs,
+        }
+    }
+}
+
+impl<T> Div<T> for Complex<T>
+where
+    T: Copy + Div<Output = T>,
+{
+    type Output = Self;
+    fn div(self, rhs: T) -> Self {
+        Complex {
+            re: self.re / rhs,
+            im: self.im / rhs,
+        }
+    }
+}
+
+impl<T> Neg for Complex<T>
+where
+    T: Neg<Output = T>,
+{
+    type Output = Complex<T>;
+    fn neg(self) -> Complex<T> {
+        Complex {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
+
+// Borrowed operands: `&a + &b` and friends, so callers needn't copy values
+// they want to keep using. These forward to the by-value impls.
+macro_rules! forward_ref_binop {
+    ( $( $imp:ident $method:ident ),* ) => {
+        $(
+            impl<'a, T> $imp<&'a Complex<T>> for &'a Complex<T>
+            where
+                T: Copy,
+                Complex<T>: $imp<Output = Complex<T>>,
+            {
+                type Output = Complex<T>;
+                fn $method(self, rhs: &'a Complex<T>) -> Complex<T> {
+                    $imp::$method(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+forward_ref_binop!(Add add, Sub sub, Mul mul, Div div);
+
+// Compound assignment operators. `+=` and `-=` can work component-wise
+// using `T`'s own compound assignment; `*=` and `/=` need the whole old
+// value, so they go through the binary operators.
+
+impl<T> AddAssign for Complex<T>
+where
+    T: AddAssign<T>,
+{
+    fn add_assign(&mut self, rhs: Complex<T>) {
+        self.re += rhs.re;
+        self.im += rhs.im;
+    }
+}
+
+impl<T> SubAssign for Complex<T>
+where
+    T: SubAssign<T>,
+{
+    fn sub_assign(&mut self, rhs: Complex<T>) {
+        self.re -= rhs.re;
+        self.im -= rhs.im;
+    }
+}
+
+impl<T> MulAssign for Complex<T>
+where
+    Complex<T>: Mul<Output = Complex<T>> + Copy,
+{
+    fn mul_assign(&mut self, rhs: Complex<T>) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T> DivAssign for Complex<T>
+where
+    Complex<T>: Div<Output = Complex<T>> + Copy,
+{
+    fn div_assign(&mut self, rhs: Complex<T>) {
+        *self = *self / rhs;
+    }
+}
+
+// Equality between complex numbers whose components can be compared, even
+// if their component types differ.
+impl<L, R> PartialEq<Complex<R>> for Complex<L>
+where
+    L: PartialEq<R>,
+{
+    fn eq(&self, other: &Complex<R>) -> bool {
+        self.re == other.re && self.im == other.im
+    }
+}
+
+impl<T: Eq> Eq for Complex<T> {}
+
+/// Formats as `a+bi` or `a-bi`. A precision (`{:.2}`) applies to both parts.
+impl<T> fmt::Display for Complex<T>
+where
+    T: fmt::Display + Copy + PartialOrd + Default + Neg<Output = T>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (sign, im) = if self.im < T::default() {
+            ('-', -self.im)
+        } else {
+            ('+', self.im)
+        };
+        match f.precision() {
+            Some(p) => write!(f, "{:.*}{}{:.*}i", p, self.re, sign, p, im),
+            None => write!(f, "{}{}{}i", self.re, sign, im),
+        }
+    }
+}
+
+/// The floating-point operations polar form needs. Implemented for `f32`
+/// and `f64`.
+pub trait Float: Copy + Mul<Output = Self> {
+    fn hypot(self, other: Self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+}
+
+macro_rules! impl_float {
+    ( $( $float:ident )* ) => {
+        $(
+            impl Float for $float {
+                fn hypot(self, other: Self) -> Self { $float::hypot(self, other) }
+                fn atan2(self, other: Self) -> Self { $float::atan2(self, other) }
+                fn sin(self) -> Self { $float::sin(self) }
+                fn cos(self) -> Self { $float::cos(self) }
+            }
+        )*
+    };
+}
+
+impl_float!(f32 f64);
+
+// Polar form only makes sense for floating-point components, which provide
+// the square roots and trigonometry it needs.
+impl<T: Float> Complex<T> {
+    /// The magnitude `|z|`: the distance from the origin.
+    pub fn norm(&self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// The angle from the positive real axis, in radians, in `(-π, π]`.
+    pub fn arg(&self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    /// Convert to `(r, θ)` polar coordinates.
+    pub fn to_polar(&self) -> (T, T) {
+        (self.norm(), self.arg())
+    }
+
+    /// Build a complex number from polar coordinates.
+    pub fn from_polar(r: T, theta: T) -> Complex<T> {
+        Complex {
+            re: r * theta.cos(),
+            im: r * theta.sin(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use complex::Complex;
+
+fn main() {
+    let x = Complex { re: 5, im: 2 };
+    let y = Complex { re: 2, im: 5 };
+    println!("({}) * ({}) = {}", x, y, x * y);
+    println!("({}) - ({}) = {}", x, y, x - y);
+    println!("-({}) = {}", x, -x);
+
+    let mut z = Complex::new(1.0, 1.0);
+    z *= Complex::new(0.0, 1.0);
+    println!("(1+1i) * i = {}", z);
+    z += Complex::new(1.0, 0.5);
+    println!("... + (1+0.5i) = {}", z);
+    println!("... / 2 = {}", z / 2.0);
+
+    let i = Complex::new(0.0_f64, 1.0);
+    let (r, theta) = i.to_polar();
+    println!("i in polar form: r = {}, θ = {:.4} rad", r, theta);
+    let back = Complex::from_polar(r, theta);
+    println!("and back again: {:.3}", back);
+
+    // Mixed component types compare through `PartialEq<Complex<R>>`.
+    let owned = Complex::new(String::from("x"), String::from("y"));
+    println!(
+        "Complex<String> == Complex<&str>: {}",
+        owned == Complex::new("x", "y")
+    );
+}
@@ -0,0 +1,156 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use complex::Complex;
+use proptest::prelude::*;
+
+type Reference<T> = num_complex::Complex<T>;
+
+fn reference<T: Copy>(z: Complex<T>) -> Reference<T> {
+    Reference::new(z.re, z.im)
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * (1.0 + a.abs().max(b.abs()))
+}
+
+#[test]
+fn book_examples() {
+    let x = Complex { re: 5, im: 2 };
+    let y = Complex { re: 2, im: 5 };
+    assert_eq!(x * y, Complex { re: 0, im: 29 });
+    assert_eq!(x + y, Complex { re: 7, im: 7 });
+    assert_eq!(-x, Complex { re: -5, im: -2 });
+
+    let mut z = Complex { re: 1, im: 2 };
+    z += Complex { re: 3, im: 4 };
+    assert_eq!(z, Complex { re: 4, im: 6 });
+}
+
+#[test]
+fn compound_assignment_matches_binary_operators() {
+    let a = Complex::new(3.0, -1.5);
+    let b = Complex::new(-0.5, 2.0);
+    let mut z = a;
+    z += b;
+    assert_eq!(z, a + b);
+    z = a;
+    z -= b;
+    assert_eq!(z, a - b);
+    z = a;
+    z *= b;
+    assert_eq!(z, a * b);
+    z = a;
+    z /= b;
+    assert_eq!(z, a / b);
+}
+
+#[test]
+// The references are the point: this exercises the `&a + &b` impls.
+#[allow(clippy::op_ref)]
+fn reference_operands() {
+    let a = Complex::new(1, 2);
+    let b = Complex::new(3, 4);
+    assert_eq!(&a + &b, a + b);
+    assert_eq!(&a - &b, a - b);
+    assert_eq!(&a * &b, a * b);
+    // a and b are still usable.
+    assert_eq!(a.norm_sqr() + b.norm_sqr(), 30);
+}
+
+#[test]
+fn scalar_operations_and_conjugate() {
+    let z = Complex::new(2.0, -4.0);
+    assert_eq!(z * 0.5, Complex::new(1.0, -2.0));
+    assert_eq!(z / 2.0, Complex::new(1.0, -2.0));
+    assert_eq!(z.conj(), Complex::new(2.0, 4.0));
+    assert_eq!(z * z.conj(), Complex::new(z.norm_sqr(), 0.0));
+}
+
+#[test]
+fn equality_across_component_types() {
+    // `String: PartialEq<&str>`, so the impl lets these compare directly.
+    let owned = Complex::new(String::from("1"), String::from("2"));
+    assert!(owned == Complex::new("1", "2"));
+}
+
+#[test]
+fn display_forms() {
+    assert_eq!(Complex::new(3, 4).to_string(), "3+4i");
+    assert_eq!(Complex::new(3, -4).to_string(), "3-4i");
+    assert_eq!(Complex::new(-0.5, 0.0).to_string(), "-0.5+0i");
+    assert_eq!(
+        format!("{:.2}", Complex::new(1.0, -1.0 / 3.0)),
+        "1.00-0.33i"
+    );
+}
+
+#[test]
+fn polar_round_trip() {
+    let i = Complex::new(0.0, 1.0);
+    assert!(close(i.norm(), 1.0));
+    assert!(close(i.arg(), FRAC_PI_2));
+    assert!(close(Complex::new(-1.0, 0.0).arg(), PI));
+
+    let z = Complex::from_polar(2.0, PI / 3.0);
+    let (r, theta) = z.to_polar();
+    assert!(close(r, 2.0) && close(theta, PI / 3.0));
+
+    // Multiplying multiplies magnitudes and adds angles.
+    let w = Complex::from_polar(3.0, PI / 6.0);
+    let product = z * w;
+    assert!(close(product.norm(), 6.0));
+    assert!(close(product.arg(), FRAC_PI_2));
+}
+
+fn small_int() -> impl Strategy<Value = i64> {
+    -10_000i64..10_000
+}
+
+fn finite() -> impl Strategy<Value = f64> {
+    -1e6f64..1e6
+}
+
+proptest! {
+    #[test]
+    fn integer_arithmetic_matches_num_complex(
+        a in (small_int(), small_int()), b in (small_int(), small_int())
+    ) {
+        let (x, y) = (Complex::new(a.0, a.1), Complex::new(b.0, b.1));
+        let (rx, ry) = (reference(x), reference(y));
+        prop_assert_eq!(reference(x + y), rx + ry);
+        prop_assert_eq!(reference(x - y), rx - ry);
+        prop_assert_eq!(reference(x * y), rx * ry);
+        prop_assert_eq!(reference(-x), -rx);
+        prop_assert_eq!(x.norm_sqr(), rx.norm_sqr());
+        if y.norm_sqr() != 0 {
+            prop_assert_eq!(reference(x / y), rx / ry);
+        }
+        prop_assert_eq!(x.to_string(), rx.to_string());
+    }
+
+    #[test]
+    fn float_arithmetic_matches_num_complex(
+        a in (finite(), finite()), b in (finite(), finite())
+    ) {
+        let (x, y) = (Complex::new(a.0, a.1), Complex::new(b.0, b.1));
+        let (rx, ry) = (reference(x), reference(y));
+        prop_assert_eq!(reference(x + y), rx + ry);
+        prop_assert_eq!(reference(x - y), rx - ry);
+        prop_assert_eq!(reference(x * y), rx * ry);
+        prop_assert_eq!(reference(x * 2.5), rx * 2.5);
+        prop_assume!(y.norm_sqr() > 1e-6);
+        let (q, rq) = (x / y, rx / ry);
+        prop_assert!(close(q.re, rq.re) && close(q.im, rq.im), "{} vs {}", q, rq);
+    }
+
+    #[test]
+    fn polar_conversions_match_num_complex(a in (finite(), finite())) {
+        let z = Complex::new(a.0, a.1);
+        let (r, theta) = z.to_polar();
+        let (rr, rtheta) = reference(z).to_polar();
+        prop_assert!(close(r, rr) && close(theta, rtheta));
+        let back = Complex::from_polar(r, theta);
+        let rback = Reference::from_polar(rr, rtheta);
+        prop_assert!(close(back.re, rback.re) && close(back.im, rback.im));
+    }
+}
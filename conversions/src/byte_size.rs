@@ -0,0 +1,145 @@
+// `ByteSize`: a number of bytes written with an optional unit suffix, like
+// `512`, `64KiB`, or `1.5 GB`.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ByteSizeError {
+    Empty,
+    NotANumber(String),
+    UnknownUnit(String),
+    /// The value works out to a fraction of a byte, like `1.5B`.
+    FractionalBytes(String),
+    Overflow,
+}
+
+impl fmt::Display for ByteSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ByteSizeError::Empty => write!(f, "size is empty"),
+            ByteSizeError::NotANumber(s) => write!(f, "size {:?} doesn't start with a number", s),
+            ByteSizeError::UnknownUnit(u) => write!(
+                f,
+                "unknown size unit {:?}: expected B, KB, MB, GB, TB, KiB, MiB, GiB, or TiB",
+                u
+            ),
+            ByteSizeError::FractionalBytes(s) => {
+                write!(f, "size {:?} is not a whole number of bytes", s)
+            }
+            ByteSizeError::Overflow => write!(f, "size is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ByteSizeError {}
+
+const KIB: u64 = 1 << 10;
+const MIB: u64 = 1 << 20;
+const GIB: u64 = 1 << 30;
+const TIB: u64 = 1 << 40;
+
+/// Units, largest first within each family. Binary units come first so that
+/// `Display` prefers them.
+const UNITS: [(&str, u64); 9] = [
+    ("TiB", TIB),
+    ("GiB", GIB),
+    ("MiB", MIB),
+    ("KiB", KIB),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+impl TryFrom<&str> for ByteSize {
+    type Error = ByteSizeError;
+
+    fn try_from(s: &str) -> Result<ByteSize, ByteSizeError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ByteSizeError::Empty);
+        }
+        let number_end = s
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(number_end);
+        let unit = unit.trim_start();
+
+        // Work in integers, so that "0.1 KB" is exactly 100 bytes.
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+            return Err(ByteSizeError::NotANumber(s.to_string()));
+        }
+
+        let multiplier = if unit.is_empty() {
+            1
+        } else {
+            UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+                .map(|&(_, multiplier)| multiplier)
+                .ok_or_else(|| ByteSizeError::UnknownUnit(unit.to_string()))?
+        };
+
+        let parse = |digits: &str| -> Result<u64, ByteSizeError> {
+            if digits.is_empty() {
+                Ok(0)
+            } else {
+                digits.parse().map_err(|_| ByteSizeError::Overflow)
+            }
+        };
+        let bytes = parse(whole)?
+            .checked_mul(multiplier)
+            .ok_or(ByteSizeError::Overflow)?;
+        let fraction_bytes = if fraction.is_empty() {
+            0
+        } else {
+            let scale = u32::try_from(fraction.len())
+                .ok()
+                .and_then(|digits| 10u128.checked_pow(digits))
+                .ok_or_else(|| ByteSizeError::FractionalBytes(s.to_string()))?;
+            let numerator = u128::from(parse(fraction)?) * u128::from(multiplier);
+            if numerator % scale != 0 {
+                return Err(ByteSizeError::FractionalBytes(s.to_string()));
+            }
+            // numerator / scale < multiplier, so this fits.
+            (numerator / scale) as u64
+        };
+        bytes
+            .checked_add(fraction_bytes)
+            .map(ByteSize)
+            .ok_or(ByteSizeError::Overflow)
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> ByteSize {
+        ByteSize(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> u64 {
+        size.0
+    }
+}
+
+/// Formats with the largest unit that divides the size exactly, so the
+/// output parses back to the same value: `1536` displays as `1536B`, but
+/// `2048` as `2KiB`.
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 == 0 {
+            return f.write_str("0B");
+        }
+        let (name, multiplier) = UNITS
+            .iter()
+            .find(|&&(_, multiplier)| self.0.is_multiple_of(multiplier))
+            .expect("every size is a whole number of bytes");
+        write!(f, "{}{}", self.0 / multiplier, name)
+    }
+}
@@ -0,0 +1,299 @@
+// Turning `key = value` text into a validated `Config`, reporting every
+// problem at once rather than stopping at the first.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{ByteSize, Duration, Hostname, Port, ValueError};
+
+/// Configuration text split into keys and raw string values, not yet
+/// validated.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RawConfig {
+    values: BTreeMap<String, String>,
+}
+
+impl RawConfig {
+    /// Parse `key = value` lines. Blank lines and lines starting with `#`
+    /// are ignored.
+    ///
+    /// Takes any `AsRef<str>`, so callers can pass a `&str`, a `String`, or
+    /// anything else that can lend out a string slice.
+    pub fn parse<S: AsRef<str>>(text: S) -> Result<RawConfig, ConfigError> {
+        let mut raw = RawConfig::default();
+        let mut problems = Vec::new();
+        for (index, line) in text.as_ref().lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    match raw.values.entry(key.trim().to_string()) {
+                        Entry::Occupied(entry) => problems.push(Problem::Duplicate {
+                            line: index + 1,
+                            key: entry.key().clone(),
+                        }),
+                        Entry::Vacant(entry) => {
+                            entry.insert(value.trim().to_string());
+                        }
+                    }
+                }
+                _ => problems.push(Problem::Syntax {
+                    line: index + 1,
+                    text: line.to_string(),
+                }),
+            }
+        }
+        if problems.is_empty() {
+            Ok(raw)
+        } else {
+            Err(ConfigError { problems })
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn set<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+}
+
+/// Build a raw configuration from pairs, as in
+/// `RawConfig::from([("host", "example.com"), ("port", "80")])`.
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]> for RawConfig {
+    fn from(pairs: [(K, V); N]) -> RawConfig {
+        let mut raw = RawConfig::default();
+        for (key, value) in pairs {
+            raw.set(key, value);
+        }
+        raw
+    }
+}
+
+/// A validated configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub host: Hostname,
+    pub port: Port,
+    pub timeout: Duration,
+    pub max_body: ByteSize,
+}
+
+pub const KEYS: [&str; 4] = ["host", "port", "timeout", "max_body"];
+
+impl Config {
+    /// Read and validate a configuration file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, LoadError> {
+        let text = fs::read_to_string(path)?;
+        Ok(text.parse()?)
+    }
+}
+
+/// Validate every field, collecting all the problems.
+impl TryFrom<&RawConfig> for Config {
+    type Error = ConfigError;
+
+    fn try_from(raw: &RawConfig) -> Result<Config, ConfigError> {
+        let mut problems = Vec::new();
+
+        let host = required(raw, "host", &mut problems);
+        let port = required(raw, "port", &mut problems);
+        let timeout = optional(
+            raw,
+            "timeout",
+            Duration::from(std::time::Duration::from_secs(30)),
+            &mut problems,
+        );
+        let max_body = optional(raw, "max_body", ByteSize(1 << 20), &mut problems);
+        for key in raw.keys() {
+            if !KEYS.contains(&key) {
+                problems.push(Problem::Unknown(key.to_string()));
+            }
+        }
+
+        match (host, port, timeout, max_body) {
+            (Some(host), Some(port), Some(timeout), Some(max_body)) if problems.is_empty() => {
+                Ok(Config {
+                    host,
+                    port,
+                    timeout,
+                    max_body,
+                })
+            }
+            _ => Err(ConfigError { problems }),
+        }
+    }
+}
+
+impl TryFrom<RawConfig> for Config {
+    type Error = ConfigError;
+
+    fn try_from(raw: RawConfig) -> Result<Config, ConfigError> {
+        Config::try_from(&raw)
+    }
+}
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    fn from_str(text: &str) -> Result<Config, ConfigError> {
+        Config::try_from(&RawConfig::parse(text)?)
+    }
+}
+
+/// Convert the value at `key` to a `T`, recording a problem if it's missing
+/// or invalid.
+///
+/// The bound says: for any lifetime `'a`, `T` can be made from a `&'a str`,
+/// and the conversion's error type converts into `ValueError`.
+fn required<T>(raw: &RawConfig, key: &'static str, problems: &mut Vec<Problem>) -> Option<T>
+where
+    T: for<'a> TryFrom<&'a str>,
+    for<'a> <T as TryFrom<&'a str>>::Error: Into<ValueError>,
+{
+    match raw.get(key) {
+        None => {
+            problems.push(Problem::Missing(key));
+            None
+        }
+        Some(value) => convert(key, value, problems),
+    }
+}
+
+/// Like `required`, but uses `default` if `key` is absent.
+fn optional<T>(
+    raw: &RawConfig,
+    key: &'static str,
+    default: T,
+    problems: &mut Vec<Problem>,
+) -> Option<T>
+where
+    T: for<'a> TryFrom<&'a str>,
+    for<'a> <T as TryFrom<&'a str>>::Error: Into<ValueError>,
+{
+    match raw.get(key) {
+        None => Some(default),
+        Some(value) => convert(key, value, problems),
+    }
+}
+
+fn convert<T>(key: &'static str, value: &str, problems: &mut Vec<Problem>) -> Option<T>
+where
+    T: for<'a> TryFrom<&'a str>,
+    for<'a> <T as TryFrom<&'a str>>::Error: Into<ValueError>,
+{
+    match T::try_from(value) {
+        Ok(converted) => Some(converted),
+        Err(error) => {
+            problems.push(Problem::Invalid {
+                key,
+                value: value.to_string(),
+                error: error.into(),
+            });
+            None
+        }
+    }
+}
+
+/// One thing wrong with a configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    Syntax {
+        line: usize,
+        text: String,
+    },
+    Duplicate {
+        line: usize,
+        key: String,
+    },
+    Missing(&'static str),
+    Unknown(String),
+    Invalid {
+        key: &'static str,
+        value: String,
+        error: ValueError,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::Syntax { line, text } => {
+                write!(f, "line {}: expected `key = value`, found {:?}", line, text)
+            }
+            Problem::Duplicate { line, key } => {
+                write!(f, "line {}: {} is set more than once", line, key)
+            }
+            Problem::Missing(key) => write!(f, "{} is required", key),
+            Problem::Unknown(key) => write!(f, "unknown setting {}", key),
+            Problem::Invalid { key, value, error } => write!(f, "{} = {:?}: {}", key, value, error),
+        }
+    }
+}
+
+/// Every problem found in a configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    pub problems: Vec<Problem>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid configuration:")?;
+        for problem in &self.problems {
+            write!(f, "\n  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// An error from `Config::load`.
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Config(ConfigError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "failed to read configuration: {}", e),
+            LoadError::Config(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Config(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl From<ConfigError> for LoadError {
+    fn from(e: ConfigError) -> Self {
+        LoadError::Config(e)
+    }
+}
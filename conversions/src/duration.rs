@@ -0,0 +1,130 @@
+// `Duration`: a span of time written with unit suffixes, like `250ms`,
+// `30s`, or `1h30m`.
+
+use std::fmt;
+use std::time;
+
+/// A duration parsed from a human-friendly string. Converts to and from
+/// `std::time::Duration`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Duration(time::Duration);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DurationError {
+    Empty,
+    /// A number with no unit after it: "30" could mean seconds or minutes.
+    MissingUnit(String),
+    UnknownUnit(String),
+    /// A unit with no number before it.
+    MissingNumber(String),
+    Overflow,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DurationError::Empty => write!(f, "duration is empty"),
+            DurationError::MissingUnit(n) => {
+                write!(f, "duration {:?} needs a unit: ms, s, m, h, or d", n)
+            }
+            DurationError::UnknownUnit(u) => {
+                write!(
+                    f,
+                    "unknown duration unit {:?}: expected ms, s, m, h, or d",
+                    u
+                )
+            }
+            DurationError::MissingNumber(u) => write!(f, "duration unit {:?} has no number", u),
+            DurationError::Overflow => write!(f, "duration is too long"),
+        }
+    }
+}
+
+impl std::error::Error for DurationError {}
+
+/// Units, largest first, with their lengths in milliseconds.
+const UNITS: [(&str, u64); 5] = [
+    ("d", 24 * 60 * 60 * 1000),
+    ("h", 60 * 60 * 1000),
+    ("m", 60 * 1000),
+    ("s", 1000),
+    ("ms", 1),
+];
+
+impl TryFrom<&str> for Duration {
+    type Error = DurationError;
+
+    fn try_from(s: &str) -> Result<Duration, DurationError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(DurationError::Empty);
+        }
+        let mut total_ms: u64 = 0;
+        let mut rest = s;
+        while !rest.is_empty() {
+            let digits_end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (digits, after) = rest.split_at(digits_end);
+            let unit_end = after
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(after.len());
+            let (unit, after) = after.split_at(unit_end);
+            if digits.is_empty() {
+                return Err(DurationError::MissingNumber(unit.to_string()));
+            }
+            if unit.is_empty() {
+                return Err(DurationError::MissingUnit(digits.to_string()));
+            }
+            let (_, unit_ms) = UNITS
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .ok_or_else(|| DurationError::UnknownUnit(unit.to_string()))?;
+            let n: u64 = digits.parse().map_err(|_| DurationError::Overflow)?;
+            total_ms = n
+                .checked_mul(*unit_ms)
+                .and_then(|ms| total_ms.checked_add(ms))
+                .ok_or(DurationError::Overflow)?;
+            rest = after;
+        }
+        Ok(Duration(time::Duration::from_millis(total_ms)))
+    }
+}
+
+impl From<time::Duration> for Duration {
+    fn from(d: time::Duration) -> Duration {
+        Duration(d)
+    }
+}
+
+impl From<Duration> for time::Duration {
+    fn from(d: Duration) -> time::Duration {
+        d.0
+    }
+}
+
+impl AsRef<time::Duration> for Duration {
+    fn as_ref(&self) -> &time::Duration {
+        &self.0
+    }
+}
+
+/// Formats in the same syntax `TryFrom<&str>` accepts, using the largest
+/// units possible: `90s` displays as `1m30s`. Sub-millisecond precision is
+/// dropped.
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ms = self.0.as_millis();
+        if ms == 0 {
+            return f.write_str("0s");
+        }
+        for (name, unit_ms) in UNITS {
+            let unit_ms = u128::from(unit_ms);
+            if ms >= unit_ms {
+                write!(f, "{}{}", ms / unit_ms, name)?;
+                ms %= unit_ms;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+// `Hostname`: a DNS host name following RFC 1123: dot-separated labels of
+// letters, digits, and hyphens.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+/// A valid host name, normalized to lowercase.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hostname(String);
+
+pub const MAX_LENGTH: usize = 253;
+pub const MAX_LABEL_LENGTH: usize = 63;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostnameError {
+    Empty,
+    TooLong(usize),
+    EmptyLabel,
+    LabelTooLong(String),
+    InvalidChar(char),
+    HyphenAtLabelEdge(String),
+}
+
+impl fmt::Display for HostnameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostnameError::Empty => write!(f, "host name is empty"),
+            HostnameError::TooLong(len) => {
+                write!(
+                    f,
+                    "host name is {} characters long; the limit is {}",
+                    len, MAX_LENGTH
+                )
+            }
+            HostnameError::EmptyLabel => {
+                write!(f, "host name has an empty label (two dots in a row?)")
+            }
+            HostnameError::LabelTooLong(label) => write!(
+                f,
+                "host name label {:?} is longer than {} characters",
+                label, MAX_LABEL_LENGTH
+            ),
+            HostnameError::InvalidChar(c) => {
+                write!(f, "host name contains invalid character {:?}", c)
+            }
+            HostnameError::HyphenAtLabelEdge(label) => {
+                write!(
+                    f,
+                    "host name label {:?} starts or ends with a hyphen",
+                    label
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HostnameError {}
+
+impl TryFrom<&str> for Hostname {
+    type Error = HostnameError;
+
+    fn try_from(s: &str) -> Result<Hostname, HostnameError> {
+        let s = s.trim();
+        // A single trailing dot marks a fully qualified name; it isn't part
+        // of any label.
+        let name = s.strip_suffix('.').unwrap_or(s);
+        if name.is_empty() {
+            return Err(HostnameError::Empty);
+        }
+        if name.len() > MAX_LENGTH {
+            return Err(HostnameError::TooLong(name.len()));
+        }
+        for label in name.split('.') {
+            if label.is_empty() {
+                return Err(HostnameError::EmptyLabel);
+            }
+            if label.len() > MAX_LABEL_LENGTH {
+                return Err(HostnameError::LabelTooLong(label.to_string()));
+            }
+            if let Some(c) = label
+                .chars()
+                .find(|&c| !(c.is_ascii_alphanumeric() || c == '-'))
+            {
+                return Err(HostnameError::InvalidChar(c));
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err(HostnameError::HyphenAtLabelEdge(label.to_string()));
+            }
+        }
+        Ok(Hostname(name.to_ascii_lowercase()))
+    }
+}
+
+/// Converting from an owned `String` can reuse its buffer when the name is
+/// already lowercase.
+impl TryFrom<String> for Hostname {
+    type Error = HostnameError;
+
+    fn try_from(s: String) -> Result<Hostname, HostnameError> {
+        let validated = Hostname::try_from(s.as_str())?;
+        if validated.0 == s {
+            Ok(Hostname(s))
+        } else {
+            Ok(validated)
+        }
+    }
+}
+
+impl Hostname {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.')
+    }
+}
+
+impl AsRef<str> for Hostname {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// `Hostname` hashes and compares exactly like its string, so collections
+/// keyed by `Hostname` can be searched with a `&str`.
+impl Borrow<str> for Hostname {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Hostname {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Hostname> for String {
+    fn from(host: Hostname) -> String {
+        host.0
+    }
+}
+
+impl fmt::Display for Hostname {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,75 @@
+// A configuration loader built on the standard conversion traits. Raw
+// `key = value` text is split into strings, and each string is converted
+// with `TryFrom` into a domain type that can only hold valid values: once
+// you have a `Port`, you know it's a usable port number.
+
+mod byte_size;
+mod config;
+mod duration;
+mod hostname;
+mod port;
+
+pub use byte_size::{ByteSize, ByteSizeError};
+pub use config::{Config, ConfigError, LoadError, Problem, RawConfig};
+pub use duration::{Duration, DurationError};
+pub use hostname::{Hostname, HostnameError};
+pub use port::{Port, PortError};
+
+use std::fmt;
+
+/// Any error from converting a single configuration value. Each domain
+/// type's error converts into this with `From`, so `?` can propagate them
+/// all from one function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueError {
+    Port(PortError),
+    Hostname(HostnameError),
+    Duration(DurationError),
+    ByteSize(ByteSizeError),
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueError::Port(e) => e.fmt(f),
+            ValueError::Hostname(e) => e.fmt(f),
+            ValueError::Duration(e) => e.fmt(f),
+            ValueError::ByteSize(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ValueError::Port(e) => Some(e),
+            ValueError::Hostname(e) => Some(e),
+            ValueError::Duration(e) => Some(e),
+            ValueError::ByteSize(e) => Some(e),
+        }
+    }
+}
+
+impl From<PortError> for ValueError {
+    fn from(e: PortError) -> Self {
+        ValueError::Port(e)
+    }
+}
+
+impl From<HostnameError> for ValueError {
+    fn from(e: HostnameError) -> Self {
+        ValueError::Hostname(e)
+    }
+}
+
+impl From<DurationError> for ValueError {
+    fn from(e: DurationError) -> Self {
+        ValueError::Duration(e)
+    }
+}
+
+impl From<ByteSizeError> for ValueError {
+    fn from(e: ByteSizeError) -> Self {
+        ValueError::ByteSize(e)
+    }
+}
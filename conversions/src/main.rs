@@ -0,0 +1,53 @@
+use conversions::{ByteSize, Config, Duration, Port};
+
+const GOOD: &str = "
+# Where to listen.
+host = API.Example.com
+port = 8443
+timeout = 1m30s
+max_body = 2.5MB
+";
+
+const BAD: &str = "
+host = -bad-.example.com
+port = 70000
+timeout = 30
+max_body = 1.5B
+colour = blue
+";
+
+fn main() {
+    match GOOD.parse::<Config>() {
+        Ok(config) => {
+            println!("host:     {}", config.host);
+            println!("port:     {}", config.port);
+            println!(
+                "timeout:  {} ({:?})",
+                config.timeout,
+                std::time::Duration::from(config.timeout)
+            );
+            println!(
+                "max_body: {} ({} bytes)",
+                config.max_body,
+                u64::from(config.max_body)
+            );
+        }
+        Err(e) => println!("{}", e),
+    }
+
+    println!();
+    if let Err(e) = BAD.parse::<Config>() {
+        println!("{}", e);
+    }
+
+    println!();
+    let port: Result<Port, _> = "0".try_into();
+    println!("\"0\" as a port: {:?}", port);
+    let timeout = Duration::try_from("2h").unwrap();
+    let size = ByteSize::try_from("64 KiB").unwrap();
+    println!(
+        "2h = {:?}, 64 KiB = {} bytes",
+        std::time::Duration::from(timeout),
+        size.0
+    );
+}
@@ -0,0 +1,82 @@
+// `Port`: a TCP or UDP port number. Port 0 means "any port" to the
+// operating system, which is never what a configuration file means.
+
+use std::fmt;
+use std::num::NonZeroU16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Port(NonZeroU16);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PortError {
+    Empty,
+    NotANumber(String),
+    Zero,
+    OutOfRange(i64),
+}
+
+impl fmt::Display for PortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PortError::Empty => write!(f, "port number is empty"),
+            PortError::NotANumber(s) => write!(f, "port {:?} is not a number", s),
+            PortError::Zero => write!(f, "port 0 is reserved"),
+            PortError::OutOfRange(n) => write!(f, "port {} is out of range 1-65535", n),
+        }
+    }
+}
+
+impl std::error::Error for PortError {}
+
+impl Port {
+    pub fn get(self) -> u16 {
+        self.0.get()
+    }
+}
+
+/// Any integer is a candidate; only 1 through 65535 are ports.
+impl TryFrom<i64> for Port {
+    type Error = PortError;
+
+    fn try_from(n: i64) -> Result<Port, PortError> {
+        let n = u16::try_from(n).map_err(|_| PortError::OutOfRange(n))?;
+        NonZeroU16::new(n).map(Port).ok_or(PortError::Zero)
+    }
+}
+
+impl TryFrom<u16> for Port {
+    type Error = PortError;
+
+    fn try_from(n: u16) -> Result<Port, PortError> {
+        Port::try_from(i64::from(n))
+    }
+}
+
+impl TryFrom<&str> for Port {
+    type Error = PortError;
+
+    fn try_from(s: &str) -> Result<Port, PortError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(PortError::Empty);
+        }
+        // Parse as i64 so that "-1" and "70000" report their value.
+        let n: i64 = s
+            .parse()
+            .map_err(|_| PortError::NotANumber(s.to_string()))?;
+        Port::try_from(n)
+    }
+}
+
+/// Widening out of a `Port` can't fail, so it's plain `From`.
+impl From<Port> for u16 {
+    fn from(port: Port) -> u16 {
+        port.get()
+    }
+}
+
+impl fmt::Display for Port {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::time;
+
+use conversions::{
+    ByteSize, Config, ConfigError, DurationError, HostnameError, LoadError, PortError, Problem,
+    RawConfig, ValueError,
+};
+
+#[test]
+fn a_valid_config() {
+    let config: Config = "
+        # comment
+        host = Example.ORG
+        port = 8080
+
+        timeout = 2m
+        max_body = 10MiB
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(config.host.as_str(), "example.org");
+    assert_eq!(config.port.get(), 8080);
+    assert_eq!(
+        time::Duration::from(config.timeout),
+        time::Duration::from_secs(120)
+    );
+    assert_eq!(config.max_body, ByteSize(10 << 20));
+}
+
+#[test]
+fn optional_settings_have_defaults() {
+    let raw = RawConfig::from([("host", "localhost"), ("port", "80")]);
+    let config = Config::try_from(&raw).unwrap();
+    assert_eq!(
+        time::Duration::from(config.timeout),
+        time::Duration::from_secs(30)
+    );
+    assert_eq!(config.max_body, ByteSize(1 << 20));
+    assert_eq!(Config::try_from(raw), Ok(config));
+}
+
+#[test]
+fn every_problem_is_reported() {
+    let error = "
+        port = 0
+        timeout = 30
+        max_body = lots
+        color = blue
+    "
+    .parse::<Config>()
+    .unwrap_err();
+    assert_eq!(
+        error.problems,
+        [
+            Problem::Missing("host"),
+            Problem::Invalid {
+                key: "port",
+                value: "0".into(),
+                error: ValueError::Port(PortError::Zero),
+            },
+            Problem::Invalid {
+                key: "timeout",
+                value: "30".into(),
+                error: ValueError::Duration(DurationError::MissingUnit("30".into())),
+            },
+            Problem::Invalid {
+                key: "max_body",
+                value: "lots".into(),
+                error: ValueError::ByteSize(conversions::ByteSizeError::NotANumber("lots".into())),
+            },
+            Problem::Unknown("color".into()),
+        ]
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid configuration:\n  host is required\n  port = \"0\": port 0 is reserved\n  \
+         timeout = \"30\": duration \"30\" needs a unit: ms, s, m, h, or d\n  \
+         max_body = \"lots\": size \"lots\" doesn't start with a number\n  unknown setting color"
+    );
+}
+
+#[test]
+fn syntax_errors_are_reported_by_line() {
+    let error = RawConfig::parse("host = a\njust some words\nhost = b\n= value\n").unwrap_err();
+    assert_eq!(
+        error,
+        ConfigError {
+            problems: vec![
+                Problem::Syntax {
+                    line: 2,
+                    text: "just some words".into()
+                },
+                Problem::Duplicate {
+                    line: 3,
+                    key: "host".into()
+                },
+                Problem::Syntax {
+                    line: 4,
+                    text: "= value".into()
+                },
+            ]
+        }
+    );
+}
+
+#[test]
+fn values_may_contain_equals_signs_and_be_empty() {
+    let raw = RawConfig::parse(String::from("a = b = c\nempty =")).unwrap();
+    assert_eq!(raw.get("a"), Some("b = c"));
+    assert_eq!(raw.get("empty"), Some(""));
+    assert_eq!(raw.keys().collect::<Vec<_>>(), ["a", "empty"]);
+
+    let error = Config::try_from(&RawConfig::from([("host", ""), ("port", "1")])).unwrap_err();
+    assert_eq!(
+        error.problems,
+        [Problem::Invalid {
+            key: "host",
+            value: "".into(),
+            error: ValueError::Hostname(HostnameError::Empty),
+        }]
+    );
+}
+
+#[test]
+fn loading_from_a_file() {
+    let dir = std::env::temp_dir().join(format!("conversions-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("server.conf");
+    fs::write(&path, "host = example.net\nport = 443\n").unwrap();
+    assert_eq!(Config::load(&path).unwrap().port.get(), 443);
+
+    fs::write(&path, "port = 443\n").unwrap();
+    assert!(matches!(Config::load(&path), Err(LoadError::Config(_))));
+    assert!(matches!(
+        Config::load(dir.join("missing.conf")),
+        Err(LoadError::Io(_))
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+}
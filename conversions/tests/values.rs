@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+use std::time;
+
+use conversions::{
+    ByteSize, ByteSizeError, Duration, DurationError, Hostname, HostnameError, Port, PortError,
+    ValueError,
+};
+
+#[test]
+fn ports() {
+    assert_eq!(Port::try_from("80").map(u16::from), Ok(80));
+    assert_eq!(Port::try_from(" 65535 ").map(Port::get), Ok(65535));
+    assert_eq!(Port::try_from(1u16).map(Port::get), Ok(1));
+    assert_eq!(Port::try_from(443i64).unwrap().to_string(), "443");
+}
+
+#[test]
+fn port_errors() {
+    assert_eq!(Port::try_from(""), Err(PortError::Empty));
+    assert_eq!(Port::try_from("   "), Err(PortError::Empty));
+    assert_eq!(
+        Port::try_from("http"),
+        Err(PortError::NotANumber("http".into()))
+    );
+    assert_eq!(
+        Port::try_from("80.5"),
+        Err(PortError::NotANumber("80.5".into()))
+    );
+    assert_eq!(Port::try_from("0"), Err(PortError::Zero));
+    assert_eq!(Port::try_from(0u16), Err(PortError::Zero));
+    assert_eq!(Port::try_from("65536"), Err(PortError::OutOfRange(65536)));
+    assert_eq!(Port::try_from("-1"), Err(PortError::OutOfRange(-1)));
+    assert_eq!(
+        Port::try_from(i64::MAX),
+        Err(PortError::OutOfRange(i64::MAX))
+    );
+    assert_eq!(
+        Port::try_from("99999999999999999999"),
+        Err(PortError::NotANumber("99999999999999999999".into()))
+    );
+    assert_eq!(
+        PortError::OutOfRange(70000).to_string(),
+        "port 70000 is out of range 1-65535"
+    );
+}
+
+#[test]
+fn hostnames() {
+    let host = Hostname::try_from("API.Example.COM").unwrap();
+    assert_eq!(host.as_str(), "api.example.com");
+    assert_eq!(host.labels().collect::<Vec<_>>(), ["api", "example", "com"]);
+    assert_eq!(
+        Hostname::try_from("example.com.").unwrap().as_ref(),
+        "example.com"
+    );
+    assert_eq!(
+        Hostname::try_from("localhost").unwrap().to_string(),
+        "localhost"
+    );
+    assert!(Hostname::try_from("xn--bcher-kva.example").is_ok());
+    assert!(Hostname::try_from("123.456").is_ok());
+
+    // `Deref<Target = str>` gives us all of `str`'s methods.
+    assert!(host.ends_with(".com"));
+    // `Borrow<str>` lets sets of hostnames be searched with `&str`.
+    let allowed: HashSet<Hostname> = [host].into_iter().collect();
+    assert!(allowed.contains("api.example.com"));
+
+    let owned = String::from("already.lowercase");
+    let ptr = owned.as_ptr();
+    let host = Hostname::try_from(owned).unwrap();
+    let back = String::from(host);
+    assert_eq!(back.as_ptr(), ptr);
+}
+
+#[test]
+fn hostname_errors() {
+    assert_eq!(Hostname::try_from(""), Err(HostnameError::Empty));
+    assert_eq!(Hostname::try_from("."), Err(HostnameError::Empty));
+    let long = [
+        "a".repeat(63),
+        "b".repeat(63),
+        "c".repeat(63),
+        "d".repeat(63),
+    ]
+    .join(".");
+    assert_eq!(
+        Hostname::try_from(long.as_str()),
+        Err(HostnameError::TooLong(255))
+    );
+    assert_eq!(Hostname::try_from("a..b"), Err(HostnameError::EmptyLabel));
+    assert_eq!(Hostname::try_from(".a"), Err(HostnameError::EmptyLabel));
+    let label = "x".repeat(64);
+    assert_eq!(
+        Hostname::try_from(format!("{}.com", label).as_str()),
+        Err(HostnameError::LabelTooLong(label))
+    );
+    assert_eq!(
+        Hostname::try_from("under_score.com"),
+        Err(HostnameError::InvalidChar('_'))
+    );
+    assert_eq!(
+        Hostname::try_from("bücher.de"),
+        Err(HostnameError::InvalidChar('ü'))
+    );
+    assert_eq!(
+        Hostname::try_from("a b"),
+        Err(HostnameError::InvalidChar(' '))
+    );
+    assert_eq!(
+        Hostname::try_from("-lead.com"),
+        Err(HostnameError::HyphenAtLabelEdge("-lead".into()))
+    );
+    assert_eq!(
+        Hostname::try_from("example.trail-"),
+        Err(HostnameError::HyphenAtLabelEdge("trail-".into()))
+    );
+}
+
+#[test]
+fn durations() {
+    let ms = |s: &str| time::Duration::from(Duration::try_from(s).unwrap()).as_millis();
+    assert_eq!(ms("250ms"), 250);
+    assert_eq!(ms("30s"), 30_000);
+    assert_eq!(ms("1m30s"), 90_000);
+    assert_eq!(ms("2h"), 7_200_000);
+    assert_eq!(ms("1d1ms"), 86_400_001);
+    assert_eq!(ms("0s"), 0);
+    // Units needn't be in order, and may repeat.
+    assert_eq!(ms("30s1m30s"), 120_000);
+
+    assert_eq!(Duration::try_from("90s").unwrap().to_string(), "1m30s");
+    assert_eq!(Duration::try_from("0ms").unwrap().to_string(), "0s");
+    assert_eq!(
+        Duration::from(time::Duration::from_millis(93_784_005)).to_string(),
+        "1d2h3m4s5ms"
+    );
+    assert_eq!(
+        Duration::try_from("1500ms").unwrap().as_ref().as_secs_f64(),
+        1.5
+    );
+}
+
+#[test]
+fn duration_errors() {
+    assert_eq!(Duration::try_from(""), Err(DurationError::Empty));
+    assert_eq!(
+        Duration::try_from("30"),
+        Err(DurationError::MissingUnit("30".into()))
+    );
+    assert_eq!(
+        Duration::try_from("1m30"),
+        Err(DurationError::MissingUnit("30".into()))
+    );
+    assert_eq!(
+        Duration::try_from("3 weeks"),
+        Err(DurationError::UnknownUnit(" weeks".into()))
+    );
+    assert_eq!(
+        Duration::try_from("1.5s"),
+        Err(DurationError::UnknownUnit(".".into()))
+    );
+    assert_eq!(
+        Duration::try_from("5S"),
+        Err(DurationError::UnknownUnit("S".into()))
+    );
+    assert_eq!(
+        Duration::try_from("s"),
+        Err(DurationError::MissingNumber("s".into()))
+    );
+    assert_eq!(
+        Duration::try_from("99999999999999999999d"),
+        Err(DurationError::Overflow)
+    );
+    assert_eq!(
+        Duration::try_from("999999999999999d"),
+        Err(DurationError::Overflow)
+    );
+}
+
+#[test]
+fn byte_sizes() {
+    let bytes = |s: &str| u64::from(ByteSize::try_from(s).unwrap());
+    assert_eq!(bytes("512"), 512);
+    assert_eq!(bytes("512B"), 512);
+    assert_eq!(bytes("64KiB"), 65_536);
+    assert_eq!(bytes("64 kib"), 65_536);
+    assert_eq!(bytes("64KB"), 64_000);
+    assert_eq!(bytes("1.5 GiB"), 1_610_612_736);
+    assert_eq!(bytes("0.1KB"), 100);
+    assert_eq!(bytes(".5MiB"), 524_288);
+    assert_eq!(bytes("2.TB"), 2_000_000_000_000);
+    assert_eq!(bytes("16EiB".replace("EiB", "TiB").as_str()), 16 << 40);
+
+    assert_eq!(ByteSize(1536).to_string(), "1536B");
+    assert_eq!(ByteSize(2048).to_string(), "2KiB");
+    assert_eq!(ByteSize(3_000_000).to_string(), "3MB");
+    assert_eq!(ByteSize(0).to_string(), "0B");
+    for n in [1, 1000, 1024, 1_500_000, 5 << 40, u64::MAX] {
+        let size = ByteSize::from(n);
+        assert_eq!(ByteSize::try_from(size.to_string().as_str()), Ok(size));
+    }
+}
+
+#[test]
+fn byte_size_errors() {
+    assert_eq!(ByteSize::try_from(" "), Err(ByteSizeError::Empty));
+    assert_eq!(
+        ByteSize::try_from("MB"),
+        Err(ByteSizeError::NotANumber("MB".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from(".KB"),
+        Err(ByteSizeError::NotANumber(".KB".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from("1.2.3MB"),
+        Err(ByteSizeError::NotANumber("1.2.3MB".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from("5 PB"),
+        Err(ByteSizeError::UnknownUnit("PB".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from("5 bytes"),
+        Err(ByteSizeError::UnknownUnit("bytes".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from("1.5B"),
+        Err(ByteSizeError::FractionalBytes("1.5B".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from("0.0001KB"),
+        Err(ByteSizeError::FractionalBytes("0.0001KB".into()))
+    );
+    assert_eq!(
+        ByteSize::try_from("16777216TiB"),
+        Err(ByteSizeError::Overflow)
+    );
+    assert_eq!(
+        ByteSize::try_from("18446744073709551616"),
+        Err(ByteSizeError::Overflow)
+    );
+}
+
+#[test]
+fn errors_convert_into_value_error() {
+    fn port_and_size(port: &str, size: &str) -> Result<(Port, ByteSize), ValueError> {
+        // `?` applies `From` to each error.
+        Ok((Port::try_from(port)?, ByteSize::try_from(size)?))
+    }
+    assert!(port_and_size("80", "1KB").is_ok());
+    assert_eq!(
+        port_and_size("0", "1KB"),
+        Err(ValueError::Port(PortError::Zero))
+    );
+    assert_eq!(
+        port_and_size("80", "1.5B"),
+        Err(ValueError::ByteSize(ByteSizeError::FractionalBytes(
+            "1.5B".into()
+        )))
+    );
+    let error: ValueError = DurationError::Empty.into();
+    assert_eq!(error.to_string(), "duration is empty");
+    assert!(std::error::Error::source(&error).is_some());
+}
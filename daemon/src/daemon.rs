@@ -0,0 +1,211 @@
+// The tick loop, the jobs it starts, and the drain at shutdown.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::shutdown::{Reason, Shutdown};
+
+/// Work started by a tick, run on a thread of its own. A long job should
+/// check `Shutdown::is_triggered` now and then, and wrap up early if so.
+pub type Job = Box<dyn FnOnce(&Shutdown) + Send>;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// How often to tick.
+    pub tick: Duration,
+    /// The most jobs to run at once. A tick whose job would exceed this
+    /// skips it, rather than letting jobs pile up behind a slow one.
+    pub workers: usize,
+    /// How long to wait, at shutdown, for jobs still running.
+    pub drain_timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            tick: Duration::from_secs(1),
+            workers: 4,
+            drain_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+pub struct Daemon {
+    config: Config,
+    shutdown: Shutdown,
+}
+
+impl Daemon {
+    pub fn new(config: Config) -> Daemon {
+        Daemon {
+            config,
+            shutdown: Shutdown::default(),
+        }
+    }
+
+    /// A handle that stops this daemon.
+    pub fn shutdown(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
+    /// Call `on_tick` once per tick, numbering the ticks from 1, and run
+    /// any job it returns. Returns once shutdown has been triggered and
+    /// the jobs have drained.
+    ///
+    /// Ticks are scheduled from the start time, so a slow `on_tick`
+    /// doesn't make every later tick late. If the loop falls more than a
+    /// whole tick behind, the missed ticks are dropped rather than run in
+    /// a burst.
+    pub fn run<F>(self, mut on_tick: F) -> Report
+    where
+        F: FnMut(u64) -> Option<Job>,
+    {
+        let control = &self.shutdown.control;
+        let start = Instant::now();
+        let mut report = Report {
+            reason: Reason::Requested,
+            forced: false,
+            uptime: Duration::ZERO,
+            ticks: 0,
+            jobs_started: 0,
+            jobs_completed: 0,
+            jobs_failed: 0,
+            jobs_skipped: 0,
+            jobs_abandoned: 0,
+            drain_time: Duration::ZERO,
+        };
+
+        let mut next = start + self.config.tick;
+        report.reason = loop {
+            let state = control.wait_until(next, |state| !state.reasons.is_empty());
+            if let Some(reason) = state.reasons.first() {
+                break reason.clone();
+            }
+            drop(state);
+
+            report.ticks += 1;
+            if let Some(job) = on_tick(report.ticks) {
+                if self.start(job) {
+                    report.jobs_started += 1;
+                } else {
+                    report.jobs_skipped += 1;
+                }
+            }
+
+            next += self.config.tick;
+            let now = Instant::now();
+            if next <= now {
+                next = now + self.config.tick;
+            }
+        };
+
+        let drain_start = Instant::now();
+        let state = control.wait_until(drain_start + self.config.drain_timeout, |state| {
+            state.in_flight == 0 || state.reasons.len() > 1
+        });
+        report.forced = state.in_flight > 0 && state.reasons.len() > 1;
+        report.jobs_abandoned = state.in_flight as u64;
+        report.jobs_completed = state.completed;
+        report.jobs_failed = state.failed;
+        report.drain_time = drain_start.elapsed();
+        report.uptime = start.elapsed();
+        report
+    }
+
+    /// Start `job` on a new thread, unless `workers` jobs are already
+    /// running or the thread can't be created. Returns whether it started.
+    fn start(&self, job: Job) -> bool {
+        let control = &self.shutdown.control;
+        {
+            let mut state = control.lock();
+            if state.in_flight >= self.config.workers {
+                return false;
+            }
+            state.in_flight += 1;
+        }
+        let shutdown = self.shutdown.clone();
+        let spawned = thread::Builder::new()
+            .name("daemon-job".to_string())
+            .spawn(move || {
+                // A panicking job is counted as failed; the daemon carries
+                // on.
+                let ok = panic::catch_unwind(AssertUnwindSafe(|| job(&shutdown))).is_ok();
+                finish(&shutdown, ok);
+            });
+        match spawned {
+            Ok(_) => true,
+            Err(e) => {
+                // The job never ran, so it's skipped, not failed.
+                eprintln!("daemon: failed to start a job: {}", e);
+                let mut state = control.lock();
+                state.in_flight -= 1;
+                drop(state);
+                control.notify();
+                false
+            }
+        }
+    }
+}
+
+fn finish(shutdown: &Shutdown, ok: bool) {
+    let mut state = shutdown.control.lock();
+    state.in_flight -= 1;
+    if ok {
+        state.completed += 1;
+    } else {
+        state.failed += 1;
+    }
+    drop(state);
+    shutdown.control.notify();
+}
+
+/// What happened over a daemon's lifetime, as of the end of the drain.
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// The first shutdown request.
+    pub reason: Reason,
+    /// Whether a second request cut the drain short.
+    pub forced: bool,
+    pub uptime: Duration,
+    pub ticks: u64,
+    pub jobs_started: u64,
+    pub jobs_completed: u64,
+    /// Jobs that panicked.
+    pub jobs_failed: u64,
+    /// Ticks whose jobs weren't run because every worker was busy, or a
+    /// thread couldn't be started for them.
+    pub jobs_skipped: u64,
+    /// Jobs still running when the drain ended.
+    pub jobs_abandoned: u64,
+    pub drain_time: Duration,
+}
+
+impl Report {
+    /// True if every job that started had finished by the end.
+    pub fn is_clean(&self) -> bool {
+        self.jobs_abandoned == 0
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "shutdown: {}", self.reason)?;
+        writeln!(f, "uptime: {:.1?}, {} ticks", self.uptime, self.ticks)?;
+        writeln!(
+            f,
+            "jobs: {} started, {} completed, {} failed, {} skipped, {} abandoned",
+            self.jobs_started,
+            self.jobs_completed,
+            self.jobs_failed,
+            self.jobs_skipped,
+            self.jobs_abandoned
+        )?;
+        write!(f, "drain: {:.1?}", self.drain_time)?;
+        if self.forced {
+            write!(f, " (cut short by a second request)")?;
+        }
+        Ok(())
+    }
+}
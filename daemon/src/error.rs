@@ -0,0 +1,53 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// Another process holds the PID file. `pid` is the ID it wrote
+    /// there, if it had written it yet.
+    AlreadyRunning {
+        pid: Option<u32>,
+        path: PathBuf,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::AlreadyRunning {
+                pid: Some(pid),
+                path,
+            } => {
+                write!(
+                    f,
+                    "already running as process {} (see {})",
+                    pid,
+                    path.display()
+                )
+            }
+            Error::AlreadyRunning { pid: None, path } => {
+                write!(f, "already running (see {})", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::AlreadyRunning { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
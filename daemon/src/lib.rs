@@ -0,0 +1,22 @@
+// The skeleton of a long-running service.
+//
+// A `Daemon` wakes once per tick and may hand a job to a background
+// thread. It runs until its `Shutdown` is triggered, by SIGINT or SIGTERM
+// once `forward_signals` is installed, or by any code holding a clone of
+// the handle. Then it stops ticking, gives the jobs still running a
+// deadline to finish, and returns a `Report` of what happened. A
+// `PidFile` keeps two copies from running at once.
+
+mod daemon;
+mod error;
+mod pidfile;
+mod shutdown;
+#[cfg(unix)]
+mod signals;
+
+pub use daemon::{Config, Daemon, Job, Report};
+pub use error::{Error, Result};
+pub use pidfile::PidFile;
+pub use shutdown::{Reason, Shutdown};
+#[cfg(unix)]
+pub use signals::forward_signals;
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use daemon::{Config, Daemon, Error, Job, PidFile};
+
+#[derive(Debug)]
+struct Arguments {
+    config: Config,
+    job: Duration,
+    pid_file: Option<PathBuf>,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            config: Config::default(),
+            job: Duration::from_millis(2500),
+            pid_file: None,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = |what: &str| {
+                args.next()
+                    .unwrap_or_else(|| usage_error(&format!("{} needs {}", arg, what)))
+            };
+            match arg.as_str() {
+                "--tick" => arguments.config.tick = millis(&value("a duration")),
+                "--job" => arguments.job = millis(&value("a duration")),
+                "--drain-timeout" => arguments.config.drain_timeout = millis(&value("a duration")),
+                "--workers" => {
+                    let n = value("a count");
+                    arguments.config.workers = n
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .unwrap_or_else(|| usage_error(&format!("bad worker count {:?}", n)));
+                }
+                "--pid-file" => arguments.pid_file = Some(PathBuf::from(value("a path"))),
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        arguments
+    }
+}
+
+fn millis(s: &str) -> Duration {
+    s.parse()
+        .map(Duration::from_millis)
+        .unwrap_or_else(|_| usage_error(&format!("bad duration {:?}: expected milliseconds", s)))
+}
+
+fn print_usage() {
+    eprintln!("daemon - tick, run jobs, and shut down cleanly on SIGINT or SIGTERM");
+    eprintln!("Usage: daemon [options]");
+    eprintln!("  --tick MS            time between ticks (default 1000)");
+    eprintln!("  --job MS             how long each tick's job takes (default 2500)");
+    eprintln!("  --workers N          most jobs to run at once (default 4)");
+    eprintln!("  --drain-timeout MS   how long to wait for jobs at shutdown (default 10000)");
+    eprintln!("  --pid-file PATH      write the process ID to PATH while running");
+    eprintln!("A second signal abandons any jobs still running.");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn main() {
+    let args = Arguments::parse();
+
+    let pid_file = args.pid_file.map(|path| match PidFile::create(path) {
+        Ok(pid_file) => pid_file,
+        Err(e @ Error::AlreadyRunning { .. }) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: failed to write PID file: {}", e);
+            process::exit(1);
+        }
+    });
+
+    let daemon = Daemon::new(args.config);
+    #[cfg(unix)]
+    if let Err(e) = daemon::forward_signals(&daemon.shutdown()) {
+        eprintln!("Error: failed to install signal handlers: {}", e);
+        process::exit(1);
+    }
+
+    let start = Instant::now();
+    let log =
+        move |message: String| eprintln!("[{:>8.3}s] {}", start.elapsed().as_secs_f64(), message);
+    log(format!("started as process {}", process::id()));
+
+    let job_length = args.job;
+    let report = daemon.run(|tick| {
+        log(format!("tick {}", tick));
+        let job: Job = Box::new(move |_shutdown| {
+            log(format!("job {} started", tick));
+            thread::sleep(job_length);
+            log(format!("job {} finished", tick));
+        });
+        Some(job)
+    });
+
+    log("stopped".to_string());
+    println!("{}", report);
+    // `process::exit` skips destructors, so remove the PID file first.
+    drop(pid_file);
+    if !report.is_clean() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,112 @@
+// A PID file: the running daemon's process ID, at a well-known path.
+//
+// The file is locked for as long as the daemon runs, and the lock is what
+// says a daemon is running, not the ID inside. The kernel releases a lock
+// when its process exits, however it exits, so a file left behind by a
+// daemon that crashed is simply locked and reused by the next one. There's
+// no window between checking for a running daemon and taking its place:
+// taking the lock is both at once. The file is removed when the `PidFile`
+// is dropped, but only if it still holds this process's ID.
+
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::error::{Error, Result};
+
+pub struct PidFile {
+    path: PathBuf,
+    pid: u32,
+    /// Holds the lock. Dropped after the file is removed, so that no other
+    /// daemon can lock the file in between and then lose it.
+    _file: File,
+}
+
+impl PidFile {
+    /// Write this process's ID to `path`, failing with
+    /// `Error::AlreadyRunning` if another process holds the file.
+    pub fn create(path: impl Into<PathBuf>) -> Result<PidFile> {
+        let path = path.into();
+        let pid = process::id();
+        loop {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    // The owner may not have written its ID yet, and on
+                    // some systems the lock keeps us from reading it.
+                    let other = read_pid(&path).ok().flatten();
+                    return Err(Error::AlreadyRunning { pid: other, path });
+                }
+                Err(TryLockError::Error(e)) => return Err(e.into()),
+            }
+            // The daemon that held the lock may have removed the file
+            // between our opening it and locking it. A lock on a file
+            // that's no longer at `path` protects nothing, so start over.
+            if !still_at(&file, &path)? {
+                continue;
+            }
+            file.set_len(0)?;
+            writeln!(file, "{}", pid)?;
+            file.sync_all()?;
+            return Ok(PidFile {
+                path,
+                pid,
+                _file: file,
+            });
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Ok(Some(pid)) = read_pid(&self.path) {
+            if pid == self.pid {
+                let _ = fs::remove_file(&self.path);
+            }
+        }
+    }
+}
+
+/// The ID in the file at `path`, or `None` if it's gone or doesn't hold
+/// one.
+fn read_pid(path: &Path) -> Result<Option<u32>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text.trim().parse().ok()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// True if `path` still names the open `file`.
+#[cfg(unix)]
+fn still_at(file: &File, path: &Path) -> Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let open = file.metadata()?;
+    match fs::metadata(path) {
+        Ok(named) => Ok(open.dev() == named.dev() && open.ino() == named.ino()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Elsewhere, an open file can't be removed, so it's always still there.
+#[cfg(not(unix))]
+fn still_at(_file: &File, _path: &Path) -> Result<bool> {
+    Ok(true)
+}
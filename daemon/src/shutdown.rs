@@ -0,0 +1,94 @@
+// The shutdown trigger, and the state the daemon's threads share.
+//
+// Everything the tick loop and the drain wait for lives behind one mutex
+// with one condition variable: shutdown requests, and the count of jobs
+// in flight. So a single wait covers "the next tick is due", "a signal
+// arrived", and "the last job finished", whichever comes first.
+
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Instant;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// SIGINT, as sent by Ctrl-C.
+    Interrupt,
+    /// SIGTERM, as sent by `kill` or a service manager.
+    Terminate,
+    /// `Shutdown::trigger` called by the program itself.
+    Requested,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Reason::Interrupt => "SIGINT",
+            Reason::Terminate => "SIGTERM",
+            Reason::Requested => "requested",
+        })
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct State {
+    /// Every shutdown request so far. The first says why the daemon
+    /// stopped; a second cuts the drain short.
+    pub(crate) reasons: Vec<Reason>,
+    pub(crate) in_flight: usize,
+    pub(crate) completed: u64,
+    pub(crate) failed: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct Control {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl Control {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    pub(crate) fn notify(&self) {
+        self.changed.notify_all();
+    }
+
+    /// Wait until `done` holds or `deadline` passes, and return the state
+    /// either way.
+    pub(crate) fn wait_until(
+        &self,
+        deadline: Instant,
+        mut done: impl FnMut(&State) -> bool,
+    ) -> MutexGuard<'_, State> {
+        let mut state = self.lock();
+        while !done(&state) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = self.changed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+        state
+    }
+}
+
+/// A handle for stopping a `Daemon`. Clone it freely; any clone can stop
+/// the daemon, and jobs get one to check whether they should wrap up.
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    pub(crate) control: Arc<Control>,
+}
+
+impl Shutdown {
+    /// Ask the daemon to stop. The first call ends the tick loop and starts
+    /// the drain; a second one, like a second Ctrl-C, abandons the drain.
+    pub fn trigger(&self, reason: Reason) {
+        self.control.lock().reasons.push(reason);
+        self.control.notify();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        !self.control.lock().reasons.is_empty()
+    }
+}
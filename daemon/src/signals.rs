@@ -0,0 +1,138 @@
+// Turning SIGINT and SIGTERM into shutdown requests.
+//
+// A signal handler interrupts whatever the thread it lands on was doing,
+// so it may only call async-signal-safe functions: no locks, no
+// allocation, nothing from std. This one writes the signal's number to a
+// pipe, which is safe, and a thread reading the other end does the real
+// work. That's the classic self-pipe trick.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::FromRawFd;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+
+use crate::shutdown::{Reason, Shutdown};
+
+/// The pipe's write end, for the handler.
+static PIPE: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn on_signal(signal: libc::c_int) {
+    // The interrupted code may be about to read `errno`, and `write` can
+    // change it.
+    // SAFETY: `errno_location` points at this thread's `errno`.
+    let errno = unsafe { *errno_location() };
+    let fd = PIPE.load(Ordering::Relaxed);
+    let byte = signal as u8;
+    // SAFETY: `write` is async-signal-safe, and `byte` outlives the call.
+    // The write end is non-blocking, so if the pipe is full, the signal is
+    // dropped, but so many are already waiting that it makes no difference.
+    unsafe {
+        libc::write(fd, &byte as *const u8 as *const libc::c_void, 1);
+        *errno_location() = errno;
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__errno_location()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__error()
+}
+
+/// Add `flag` to the file descriptor flags (`F_GETFD`/`F_SETFD`) or the
+/// file status flags (`F_GETFL`/`F_SETFL`) of `fd`.
+fn add_flag(
+    fd: libc::c_int,
+    get: libc::c_int,
+    set: libc::c_int,
+    flag: libc::c_int,
+) -> io::Result<()> {
+    // SAFETY: `fcntl` with these commands only reads and writes flags.
+    unsafe {
+        let flags = libc::fcntl(fd, get);
+        if flags < 0 || libc::fcntl(fd, set, flags | flag) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Trigger `shutdown` on each SIGINT or SIGTERM from now on. Signal
+/// handlers belong to the whole process, so this can only be called once.
+pub fn forward_signals(shutdown: &Shutdown) -> io::Result<()> {
+    let mut fds = [0; 2];
+    // SAFETY: `pipe` writes two file descriptors into the array.
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let [read_end, write_end] = fds;
+    // Neither end should leak into child processes. The handler mustn't
+    // block in `write` if the pipe fills up, but the reading thread is
+    // meant to sleep until there's something to read.
+    let flags = add_flag(read_end, libc::F_GETFD, libc::F_SETFD, libc::FD_CLOEXEC)
+        .and_then(|()| add_flag(write_end, libc::F_GETFD, libc::F_SETFD, libc::FD_CLOEXEC))
+        .and_then(|()| add_flag(write_end, libc::F_GETFL, libc::F_SETFL, libc::O_NONBLOCK));
+    if let Err(e) = flags {
+        // SAFETY: Both descriptors are ours, and unused.
+        unsafe {
+            libc::close(read_end);
+            libc::close(write_end);
+        }
+        return Err(e);
+    }
+    if PIPE
+        .compare_exchange(-1, write_end, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        // SAFETY: Both descriptors are ours, and unused.
+        unsafe {
+            libc::close(read_end);
+            libc::close(write_end);
+        }
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "signals are already being forwarded",
+        ));
+    }
+
+    // SAFETY: `read_end` is open, and nothing else owns it.
+    let mut pipe = unsafe { File::from_raw_fd(read_end) };
+    let shutdown = shutdown.clone();
+    thread::Builder::new()
+        .name("daemon-signals".to_string())
+        .spawn(move || {
+            let mut byte = [0];
+            loop {
+                match pipe.read(&mut byte) {
+                    Ok(0) => return,
+                    Ok(_) => {}
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(_) => return,
+                }
+                match byte[0] as libc::c_int {
+                    libc::SIGINT => shutdown.trigger(Reason::Interrupt),
+                    libc::SIGTERM => shutdown.trigger(Reason::Terminate),
+                    _ => {}
+                }
+            }
+        })?;
+
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: A zeroed `sigaction` is a valid starting point; the
+        // handler only touches the pipe, which stays open from here on.
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_signal as *const () as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            if libc::sigaction(signal, &action, std::ptr::null_mut()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use daemon::{Config, Daemon, Job, Reason};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+fn config(tick: u64, workers: usize, drain_timeout: u64) -> Config {
+    Config {
+        tick: ms(tick),
+        workers,
+        drain_timeout: ms(drain_timeout),
+    }
+}
+
+fn sleeper(length: u64) -> Option<Job> {
+    Some(Box::new(move |_| thread::sleep(ms(length))))
+}
+
+#[test]
+fn ticks_until_triggered() {
+    let daemon = Daemon::new(config(5, 1, 1000));
+    let shutdown = daemon.shutdown();
+    let start = Instant::now();
+    let report = daemon.run(|tick| {
+        if tick == 3 {
+            shutdown.trigger(Reason::Requested);
+        }
+        None
+    });
+    assert_eq!(report.ticks, 3);
+    assert_eq!(report.reason, Reason::Requested);
+    assert!(start.elapsed() >= ms(15));
+    assert!(report.is_clean());
+    assert_eq!(report.jobs_started, 0);
+}
+
+#[test]
+fn trigger_wakes_the_loop_between_ticks() {
+    let daemon = Daemon::new(config(10_000, 1, 1000));
+    let shutdown = daemon.shutdown();
+    let trigger = thread::spawn(move || {
+        thread::sleep(ms(20));
+        shutdown.trigger(Reason::Terminate);
+    });
+    let start = Instant::now();
+    let report = daemon.run(|_| None);
+    assert!(start.elapsed() < ms(1000), "waited for the tick");
+    assert_eq!(report.ticks, 0);
+    assert_eq!(report.reason, Reason::Terminate);
+    trigger.join().unwrap();
+}
+
+#[test]
+fn triggered_before_running() {
+    let daemon = Daemon::new(config(1, 1, 1000));
+    daemon.shutdown().trigger(Reason::Interrupt);
+    let report = daemon.run(|_| panic!("ticked after shutdown"));
+    assert_eq!(report.ticks, 0);
+    assert_eq!(report.reason, Reason::Interrupt);
+}
+
+#[test]
+fn drain_waits_for_jobs_in_flight() {
+    let daemon = Daemon::new(config(5, 4, 5000));
+    let shutdown = daemon.shutdown();
+    let report = daemon.run(|tick| {
+        if tick == 2 {
+            shutdown.trigger(Reason::Requested);
+            return None;
+        }
+        sleeper(60)
+    });
+    assert_eq!(report.jobs_started, 1);
+    assert_eq!(report.jobs_completed, 1);
+    assert!(report.drain_time >= ms(40), "{:?}", report.drain_time);
+    assert!(report.is_clean());
+}
+
+#[test]
+fn drain_times_out() {
+    let daemon = Daemon::new(config(5, 4, 30));
+    let shutdown = daemon.shutdown();
+    let report = daemon.run(|tick| {
+        if tick == 2 {
+            shutdown.trigger(Reason::Requested);
+        }
+        sleeper(1000)
+    });
+    assert_eq!(report.jobs_started, 2);
+    assert_eq!(report.jobs_abandoned, 2);
+    assert!(!report.forced);
+    assert!(report.drain_time >= ms(30) && report.drain_time < ms(500));
+    assert!(!report.is_clean());
+}
+
+#[test]
+fn jobs_can_wrap_up_early() {
+    let daemon = Daemon::new(config(5, 4, 5000));
+    let shutdown = daemon.shutdown();
+    let report = daemon.run(|tick| {
+        if tick == 2 {
+            shutdown.trigger(Reason::Requested);
+            return None;
+        }
+        Some(Box::new(|shutdown| {
+            while !shutdown.is_triggered() {
+                thread::sleep(ms(1));
+            }
+        }))
+    });
+    assert_eq!(report.jobs_completed, 1);
+    assert!(report.drain_time < ms(1000));
+}
+
+#[test]
+fn second_trigger_abandons_the_drain() {
+    let daemon = Daemon::new(config(5, 4, 10_000));
+    let shutdown = daemon.shutdown();
+    let report = daemon.run(|tick| {
+        if tick == 2 {
+            shutdown.trigger(Reason::Interrupt);
+            let shutdown = shutdown.clone();
+            thread::spawn(move || {
+                thread::sleep(ms(30));
+                shutdown.trigger(Reason::Interrupt);
+            });
+            return None;
+        }
+        sleeper(5000)
+    });
+    assert!(report.forced);
+    assert_eq!(report.jobs_abandoned, 1);
+    assert!(report.drain_time < ms(1000), "{:?}", report.drain_time);
+    assert!(report.to_string().contains("cut short"));
+}
+
+#[test]
+fn busy_workers_skip_ticks() {
+    let daemon = Daemon::new(config(5, 1, 5000));
+    let shutdown = daemon.shutdown();
+    let report = daemon.run(|tick| {
+        if tick == 6 {
+            shutdown.trigger(Reason::Requested);
+        }
+        sleeper(200)
+    });
+    assert_eq!(report.ticks, 6);
+    assert_eq!(report.jobs_started, 1);
+    assert_eq!(report.jobs_skipped, 5);
+    assert_eq!(report.jobs_completed, 1);
+}
+
+#[test]
+fn panicking_jobs_are_counted_as_failed() {
+    let daemon = Daemon::new(config(5, 4, 5000));
+    let shutdown = daemon.shutdown();
+    let ran_after = Arc::new(AtomicBool::new(false));
+    let report = daemon.run(|tick| match tick {
+        1 => Some(Box::new(|_| panic!("job failed"))),
+        2 => {
+            let ran_after = ran_after.clone();
+            Some(Box::new(move |_| ran_after.store(true, Ordering::SeqCst)))
+        }
+        _ => {
+            shutdown.trigger(Reason::Requested);
+            None
+        }
+    });
+    assert_eq!(report.jobs_failed, 1);
+    assert_eq!(report.jobs_completed, 1);
+    assert!(ran_after.load(Ordering::SeqCst));
+    assert!(report.is_clean());
+}
+
+#[test]
+fn report_format() {
+    let daemon = Daemon::new(config(1, 1, 1000));
+    daemon.shutdown().trigger(Reason::Terminate);
+    let report = daemon.run(|_| None).to_string();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines[0], "shutdown: SIGTERM");
+    assert!(lines[1].starts_with("uptime: ") && lines[1].ends_with(", 0 ticks"));
+    assert_eq!(
+        lines[2],
+        "jobs: 0 started, 0 completed, 0 failed, 0 skipped, 0 abandoned"
+    );
+    assert!(lines[3].starts_with("drain: "));
+}
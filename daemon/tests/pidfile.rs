@@ -0,0 +1,101 @@
+use std::fs;
+use std::process;
+use std::sync::Barrier;
+use std::thread;
+
+use daemon::{Error, PidFile};
+
+#[test]
+fn written_and_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("daemon.pid");
+    let pid_file = PidFile::create(&path).unwrap();
+    assert_eq!(pid_file.pid(), process::id());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("{}\n", process::id())
+    );
+    drop(pid_file);
+    assert!(!path.exists());
+}
+
+#[test]
+fn refuses_while_the_owner_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("daemon.pid");
+    let _first = PidFile::create(&path).unwrap();
+    match PidFile::create(&path) {
+        Err(Error::AlreadyRunning { pid, .. }) => assert_eq!(pid, Some(process::id())),
+        Err(e) => panic!("unexpected error {}", e),
+        Ok(_) => panic!("created a second PID file"),
+    }
+    assert!(path.exists(), "the failed attempt removed the file");
+}
+
+#[test]
+fn replaces_stale_and_garbled_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("daemon.pid");
+    // Beyond any system's largest process ID.
+    for contents in ["999999999\n", "not a pid", ""] {
+        fs::write(&path, contents).unwrap();
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            process::id().to_string()
+        );
+        drop(pid_file);
+    }
+}
+
+#[test]
+fn leaves_a_file_it_no_longer_owns() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("daemon.pid");
+    let pid_file = PidFile::create(&path).unwrap();
+    fs::write(&path, "1\n").unwrap();
+    drop(pid_file);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "1\n");
+}
+
+#[test]
+fn concurrent_creators() {
+    // Many starters at once, some finding a stale file from a daemon that
+    // crashed: exactly one may win each round.
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("daemon.pid");
+    for round in 0..50 {
+        if round % 2 == 1 {
+            fs::write(&path, "999999999\n").unwrap();
+        }
+        let barrier = Barrier::new(8);
+        let results: Vec<_> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    s.spawn(|| {
+                        barrier.wait();
+                        PidFile::create(&path)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let mut winners = 0;
+        for result in &results {
+            match result {
+                Ok(_) => winners += 1,
+                Err(Error::AlreadyRunning { pid, .. }) => {
+                    assert!(pid.is_none() || *pid == Some(process::id()))
+                }
+                Err(e) => panic!("unexpected error {}", e),
+            }
+        }
+        assert_eq!(winners, 1, "round {}", round);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n", process::id())
+        );
+        drop(results);
+        assert!(!path.exists());
+    }
+}
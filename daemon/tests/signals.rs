@@ -0,0 +1,71 @@
+// Signal handlers belong to the whole process, so these tests get a test
+// binary of their own.
+
+#![cfg(unix)]
+
+use std::io;
+use std::time::Duration;
+
+use daemon::{Config, Daemon, Reason};
+
+/// The pipes this process has open.
+fn pipes() -> Vec<libc::c_int> {
+    (0..1024)
+        .filter(|&fd| {
+            // SAFETY: `fstat` on a closed descriptor just fails.
+            let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+            let open = unsafe { libc::fstat(fd, &mut stat) } == 0;
+            open && stat.st_mode & libc::S_IFMT == libc::S_IFIFO
+        })
+        .collect()
+}
+
+#[test]
+fn signals_trigger_shutdown() {
+    let daemon = Daemon::new(Config {
+        tick: Duration::from_millis(5),
+        ..Config::default()
+    });
+    let before = pipes();
+    daemon::forward_signals(&daemon.shutdown()).unwrap();
+    let new: Vec<_> = pipes()
+        .into_iter()
+        .filter(|fd| !before.contains(fd))
+        .collect();
+    assert_eq!(new.len(), 2, "{:?}", new);
+    for fd in new {
+        // SAFETY: `fcntl` with `F_GETFD` and `F_GETFL` only reads flags.
+        let (fd_flags, status_flags) = unsafe {
+            (
+                libc::fcntl(fd, libc::F_GETFD),
+                libc::fcntl(fd, libc::F_GETFL),
+            )
+        };
+        assert_ne!(
+            fd_flags & libc::FD_CLOEXEC,
+            0,
+            "fd {} leaks into children",
+            fd
+        );
+        // The handler must never block on the write end.
+        if status_flags & libc::O_ACCMODE == libc::O_WRONLY {
+            assert_ne!(status_flags & libc::O_NONBLOCK, 0);
+        }
+    }
+
+    let report = daemon.run(|tick| {
+        if tick == 2 {
+            // A failed call leaves `errno` set, and the handler mustn't
+            // change it behind this code's back.
+            // SAFETY: Closing an invalid descriptor just fails.
+            unsafe { libc::close(-1) };
+            // SAFETY: The handler is installed; `raise` runs it on this
+            // thread before returning.
+            unsafe { libc::raise(libc::SIGTERM) };
+            assert_eq!(io::Error::last_os_error().raw_os_error(), Some(libc::EBADF));
+        }
+        None
+    });
+    assert_eq!(report.reason, Reason::Terminate);
+    assert_eq!(report.ticks, 2);
+}
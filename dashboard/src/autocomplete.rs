@@ -0,0 +1,49 @@
+// The autocomplete pane: what's typed so far, and the words in the
+// `StringTable` it could be the start of.
+
+use ch_05::StringTable;
+use ratatui::crossterm::event::KeyCode;
+
+pub(crate) struct Autocomplete {
+    pub(crate) table: StringTable,
+    pub(crate) input: String,
+    /// The highlighted completion, counting from 0.
+    pub(crate) selected: usize,
+}
+
+impl Autocomplete {
+    pub(crate) fn new(table: StringTable) -> Autocomplete {
+        Autocomplete {
+            table,
+            input: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn completions(&self) -> Vec<&str> {
+        self.table.completions(&self.input).collect()
+    }
+
+    pub(crate) fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.selected = 0;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.selected = 0;
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down if self.selected + 1 < self.completions().len() => self.selected += 1,
+            // Accept the highlighted completion.
+            KeyCode::Enter | KeyCode::Right => {
+                if let Some(word) = self.completions().get(self.selected) {
+                    self.input = word.to_string();
+                    self.selected = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// The calculator pane: an input line, and the expressions evaluated so far.
+
+use ratatui::crossterm::event::KeyCode;
+
+pub(crate) struct Entry {
+    pub(crate) expr: String,
+    pub(crate) result: Result<f64, String>,
+}
+
+#[derive(Default)]
+pub(crate) struct Calculator {
+    pub(crate) input: String,
+    pub(crate) history: Vec<Entry>,
+    /// While paging through history with Up and Down, the entry shown.
+    recalled: Option<usize>,
+}
+
+impl Calculator {
+    pub(crate) fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.recalled = None;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.recalled = None;
+            }
+            KeyCode::Enter => self.evaluate(),
+            KeyCode::Up => self.recall(|i, _| i.checked_sub(1)),
+            KeyCode::Down => self.recall(|i, len| (i + 1 < len).then_some(i + 1)),
+            _ => {}
+        }
+    }
+
+    fn evaluate(&mut self) {
+        let expr = self.input.trim().to_string();
+        if expr.is_empty() {
+            return;
+        }
+        let result = ch_03::eval(&expr).map_err(|e| e.to_string());
+        self.history.push(Entry { expr, result });
+        self.input.clear();
+        self.recalled = None;
+    }
+
+    /// Show another history entry in the input line. `step` gets the
+    /// current position and the history's length, and returns the new
+    /// position, if there is one. Before the first Up, the position is
+    /// just past the end.
+    fn recall(&mut self, step: impl Fn(usize, usize) -> Option<usize>) {
+        let len = self.history.len();
+        let current = self.recalled.unwrap_or(len);
+        if let Some(i) = step(current, len) {
+            self.recalled = Some(i);
+            self.input = self.history[i].expr.clone();
+        }
+    }
+}
@@ -0,0 +1,115 @@
+// A terminal dashboard putting three earlier chapters side by side: the
+// chapter 3 calculator, the chapter 5 `StringTable` as an autocomplete
+// box, and the chapter 7 running sum following a file as it grows.
+//
+// `App` holds all the state and reacts to keys and clock ticks; `ui` draws
+// it. Neither knows about the real terminal, which only `main` touches, so
+// the tests can drive an `App` with synthetic key presses and check what
+// it draws on ratatui's in-memory `TestBackend`.
+
+mod autocomplete;
+mod calculator;
+mod sum;
+mod ui;
+
+use std::path::PathBuf;
+
+use ch_05::StringTable;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+
+use autocomplete::Autocomplete;
+use calculator::Calculator;
+use sum::SumPane;
+
+/// Words to complete when no `--words` file is given: Rust's keywords and
+/// some names from the standard library.
+pub fn default_words() -> StringTable {
+    [
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+        "type", "unsafe", "use", "where", "while", "Arc", "Box", "BTreeMap", "Cell", "Clone",
+        "Copy", "Debug", "Default", "Display", "HashMap", "HashSet", "Iterator", "Mutex", "Option",
+        "Rc", "RefCell", "Result", "String", "Vec", "VecDeque",
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// The pane that keys go to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pane {
+    Calculator,
+    Autocomplete,
+    Sum,
+}
+
+impl Pane {
+    const ALL: [Pane; 3] = [Pane::Calculator, Pane::Autocomplete, Pane::Sum];
+
+    fn next(self) -> Pane {
+        let i = Pane::ALL.iter().position(|&p| p == self).unwrap();
+        Pane::ALL[(i + 1) % Pane::ALL.len()]
+    }
+
+    fn previous(self) -> Pane {
+        let i = Pane::ALL.iter().position(|&p| p == self).unwrap();
+        Pane::ALL[(i + Pane::ALL.len() - 1) % Pane::ALL.len()]
+    }
+}
+
+pub struct App {
+    focus: Pane,
+    calculator: Calculator,
+    autocomplete: Autocomplete,
+    sum: SumPane,
+    quit: bool,
+}
+
+impl App {
+    /// A dashboard completing from `words`, and following the numbers in
+    /// `follow`, if given.
+    pub fn new(words: StringTable, follow: Option<PathBuf>) -> App {
+        App {
+            focus: Pane::Calculator,
+            calculator: Calculator::default(),
+            autocomplete: Autocomplete::new(words),
+            sum: SumPane::new(follow),
+            quit: false,
+        }
+    }
+
+    pub fn focus(&self) -> Pane {
+        self.focus
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Tab and Shift-Tab move between panes, and Esc or Ctrl-C quits.
+    /// Every other key goes to the focused pane.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.previous(),
+            code => match self.focus {
+                Pane::Calculator => self.calculator.handle_key(code),
+                Pane::Autocomplete => self.autocomplete.handle_key(code),
+                Pane::Sum => self.sum.handle_key(code),
+            },
+        }
+    }
+
+    /// Called on every clock tick: catch up with the followed file.
+    pub fn tick(&mut self) {
+        self.sum.poll();
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        ui::draw(self, frame);
+    }
+}
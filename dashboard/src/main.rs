@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use ch_05::StringTable;
+use dashboard::{default_words, App};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+/// How often to poll the followed file.
+const TICK: Duration = Duration::from_millis(250);
+
+#[derive(Debug)]
+struct Arguments {
+    follow: Option<PathBuf>,
+    words: Option<PathBuf>,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            follow: None,
+            words: None,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = |what: &str| {
+                args.next()
+                    .unwrap_or_else(|| usage_error(&format!("{} needs {}", arg, what)))
+            };
+            match arg.as_str() {
+                "--follow" => arguments.follow = Some(PathBuf::from(value("a path"))),
+                "--words" => arguments.words = Some(PathBuf::from(value("a path"))),
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        arguments
+    }
+}
+
+fn print_usage() {
+    eprintln!("dashboard - the calculator, autocomplete, and a running sum in one terminal");
+    eprintln!("Usage: dashboard [options]");
+    eprintln!("  --follow PATH   keep a running sum of the numbers in PATH as it grows");
+    eprintln!("  --words PATH    complete from the words in PATH, one per line");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    process::exit(1);
+}
+
+fn main() {
+    let args = Arguments::parse();
+    let words = match &args.words {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(text) => text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<StringTable>(),
+            Err(e) => fail(&format!("failed to read {}: {}", path.display(), e)),
+        },
+        None => default_words(),
+    };
+
+    let mut app = App::new(words, args.follow);
+    app.tick();
+    let mut terminal = ratatui::init();
+    let result = (|| -> std::io::Result<()> {
+        let mut last_tick = Instant::now();
+        while !app.should_quit() {
+            terminal.draw(|frame| app.draw(frame))?;
+            let timeout = TICK.saturating_sub(last_tick.elapsed());
+            if event::poll(timeout)? {
+                // Only presses: some terminals report releases as well.
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        app.handle_key(key);
+                    }
+                }
+            }
+            if last_tick.elapsed() >= TICK {
+                app.tick();
+                last_tick = Instant::now();
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    if let Err(e) = result {
+        fail(&e.to_string());
+    }
+}
@@ -0,0 +1,57 @@
+// The running-sum pane: chapter 7's `SumFollower` polled on every tick,
+// with a log of what it found.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use ch_07::{SumFollower, Update};
+use ratatui::crossterm::event::KeyCode;
+
+/// How many log lines to keep.
+const LOG_LINES: usize = 200;
+
+pub(crate) struct SumPane {
+    pub(crate) follower: Option<SumFollower>,
+    pub(crate) log: VecDeque<String>,
+    pub(crate) error: Option<String>,
+}
+
+impl SumPane {
+    pub(crate) fn new(path: Option<PathBuf>) -> SumPane {
+        SumPane {
+            follower: path.map(SumFollower::new),
+            log: VecDeque::new(),
+            error: None,
+        }
+    }
+
+    pub(crate) fn poll(&mut self) {
+        let Some(follower) = &mut self.follower else {
+            return;
+        };
+        match follower.poll() {
+            Ok(updates) => {
+                self.error = None;
+                for update in updates {
+                    let line = match update {
+                        Update::Number(n) => format!("{:+}", n),
+                        Update::Skipped { line, error } => format!("skipped {:?}: {}", line, error),
+                        Update::Restarted => "file truncated; starting over".to_string(),
+                    };
+                    if self.log.len() == LOG_LINES {
+                        self.log.pop_front();
+                    }
+                    self.log.push_back(line);
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// `c` clears the log; the sum carries on.
+    pub(crate) fn handle_key(&mut self, code: KeyCode) {
+        if code == KeyCode::Char('c') {
+            self.log.clear();
+        }
+    }
+}
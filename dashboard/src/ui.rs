@@ -0,0 +1,144 @@
+// Drawing the dashboard: the calculator down the left, autocomplete and
+// the running sum on the right, and a line of key help along the bottom.
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::{App, Pane};
+
+pub(crate) fn draw(app: &App, frame: &mut Frame) {
+    let [main, help] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [left, right] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+    let [top, bottom] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+
+    calculator(app, frame, left);
+    autocomplete(app, frame, top);
+    sum(app, frame, bottom);
+    frame.render_widget(
+        Paragraph::new("Tab/Shift-Tab: switch pane   Esc/Ctrl-C: quit")
+            .style(Style::default().fg(Color::DarkGray)),
+        help,
+    );
+}
+
+/// A bordered block, highlighted if `pane` has the focus.
+fn block(app: &App, pane: Pane, title: &str) -> Block<'static> {
+    let style = if app.focus == pane {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    Block::bordered()
+        .title(format!(" {} ", title))
+        .border_style(style)
+}
+
+/// An input line, showing a cursor while its pane has the focus.
+fn prompt(app: &App, pane: Pane, input: &str) -> Line<'static> {
+    let cursor = if app.focus == pane { "_" } else { "" };
+    Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Cyan)),
+        Span::raw(format!("{}{}", input, cursor)),
+    ])
+}
+
+fn calculator(app: &App, frame: &mut Frame, area: Rect) {
+    let block = block(app, Pane::Calculator, "Calculator");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [history, input] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+
+    // The most recent entries that fit, oldest at the top.
+    let lines: Vec<Line> = app
+        .calculator
+        .history
+        .iter()
+        .rev()
+        .take(history.height as usize)
+        .rev()
+        .map(|entry| match &entry.result {
+            Ok(value) => Line::from(format!("{} = {}", entry.expr, value)),
+            Err(e) => Line::from(vec![
+                Span::raw(format!("{}: ", entry.expr)),
+                Span::styled(e.clone(), Style::default().fg(Color::Red)),
+            ]),
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), history);
+    frame.render_widget(
+        Paragraph::new(prompt(app, Pane::Calculator, &app.calculator.input)),
+        input,
+    );
+}
+
+fn autocomplete(app: &App, frame: &mut Frame, area: Rect) {
+    let completions = app.autocomplete.completions();
+    let title = format!(
+        "Autocomplete ({} of {})",
+        completions.len(),
+        app.autocomplete.table.len()
+    );
+    let block = block(app, Pane::Autocomplete, &title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [input, list] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+
+    frame.render_widget(
+        Paragraph::new(prompt(app, Pane::Autocomplete, &app.autocomplete.input)),
+        input,
+    );
+    let items: Vec<ListItem> = completions
+        .iter()
+        .map(|&word| ListItem::new(word))
+        .collect();
+    let list_widget = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("» ");
+    let mut state = ListState::default().with_selected(Some(app.autocomplete.selected));
+    frame.render_stateful_widget(list_widget, list, &mut state);
+}
+
+fn sum(app: &App, frame: &mut Frame, area: Rect) {
+    let pane = &app.sum;
+    let title = match &pane.follower {
+        Some(follower) => format!(
+            "Sum of {}: {} ({} numbers)",
+            follower.path().display(),
+            follower.sum(),
+            follower.count()
+        ),
+        None => "Sum".to_string(),
+    };
+    let block = block(app, Pane::Sum, &title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    if pane.follower.is_none() {
+        lines.push(Line::from("Not following a file: start with --follow PATH"));
+    }
+    if let Some(e) = &pane.error {
+        lines.push(Line::styled(
+            format!("Error: {}", e),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    let room = (inner.height as usize).saturating_sub(lines.len());
+    let skip = pane.log.len().saturating_sub(room);
+    lines.extend(
+        pane.log
+            .iter()
+            .skip(skip)
+            .map(|line| Line::from(line.clone())),
+    );
+    frame.render_widget(Paragraph::new(lines), inner);
+}
@@ -0,0 +1,110 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use ch_05::StringTable;
+use dashboard::{default_words, App, Pane};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn type_str(app: &mut App, s: &str) {
+    for c in s.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+/// What `app` draws on a 100x30 screen, as text.
+fn screen(app: &App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let mut text = String::new();
+    for y in 0..buffer.area.height {
+        for x in 0..buffer.area.width {
+            text.push_str(buffer[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[test]
+fn calculator_shows_results_and_errors() {
+    let mut app = App::new(default_words(), None);
+    type_str(&mut app, "2 ^ 3 ^ 2");
+    press(&mut app, KeyCode::Enter);
+    type_str(&mut app, "1 / 0");
+    press(&mut app, KeyCode::Enter);
+    let text = screen(&app);
+    assert!(text.contains("2 ^ 3 ^ 2 = 512"), "{}", text);
+    assert!(text.contains("1 / 0: division by zero"), "{}", text);
+
+    // Up recalls the last expression.
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Up);
+    assert!(screen(&app).contains("> 2 ^ 3 ^ 2_"));
+}
+
+#[test]
+fn tab_moves_the_focus() {
+    let mut app = App::new(default_words(), None);
+    assert_eq!(app.focus(), Pane::Calculator);
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.focus(), Pane::Autocomplete);
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.focus(), Pane::Sum);
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.focus(), Pane::Calculator);
+    press(&mut app, KeyCode::BackTab);
+    assert_eq!(app.focus(), Pane::Sum);
+}
+
+#[test]
+fn autocomplete_filters_and_accepts() {
+    let words: StringTable = ["map", "match", "matches", "mutex"].into_iter().collect();
+    let mut app = App::new(words, None);
+    press(&mut app, KeyCode::Tab);
+    type_str(&mut app, "mat");
+    let text = screen(&app);
+    assert!(text.contains("Autocomplete (2 of 4)"), "{}", text);
+    assert!(!text.contains("mutex"));
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Enter);
+    assert!(screen(&app).contains("> matches_"));
+}
+
+#[test]
+fn sum_follows_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("numbers.txt");
+    fs::write(&path, "10\n20\n").unwrap();
+
+    let mut app = App::new(default_words(), Some(path.clone()));
+    app.tick();
+    assert!(screen(&app).contains(": 30 (2 numbers)"));
+
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, "-5\nseven").unwrap();
+    app.tick();
+    let text = screen(&app);
+    assert!(text.contains(": 25 (3 numbers)"), "{}", text);
+    assert!(text.contains("skipped \"seven\""), "{}", text);
+}
+
+#[test]
+fn quit_keys() {
+    let mut app = App::new(default_words(), None);
+    press(&mut app, KeyCode::Char('c'));
+    assert!(!app.should_quit());
+    app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert!(app.should_quit());
+
+    let mut app = App::new(default_words(), None);
+    press(&mut app, KeyCode::Esc);
+    assert!(app.should_quit());
+}
@@ -0,0 +1,55 @@
+// Every error is a `Box<dyn Error>`, and `?` converts anything into one.
+// Saying which file or line failed takes an error type of our own,
+// `Context`: it's most of what the libraries in the other modules write
+// for you.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::num::ParseIntError;
+use std::path::Path;
+
+pub type GenericError = Box<dyn Error + Send + Sync + 'static>;
+
+#[derive(Debug)]
+struct Context {
+    message: String,
+    source: Option<GenericError>,
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for Context {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+/// Sum the integers in a file, one per line, skipping blank lines. A
+/// caller can only tell failures apart by downcasting the source.
+pub fn sum_file(path: &Path) -> Result<i64, GenericError> {
+    let text = fs::read_to_string(path).map_err(|e| Context {
+        message: format!("couldn't read {}", path.display()),
+        source: Some(e.into()),
+    })?;
+    let mut sum: i64 = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let n: i64 = line.parse().map_err(|e: ParseIntError| Context {
+            message: format!("line {}: {:?} isn't a number", index + 1, line),
+            source: Some(e.into()),
+        })?;
+        sum = sum.checked_add(n).ok_or_else(|| Context {
+            message: format!("the sum overflows at line {}", index + 1),
+            source: None,
+        })?;
+    }
+    Ok(sum)
+}
@@ -0,0 +1,24 @@
+// Chapter 7's `read_and_sum`, written four ways: with `Box<dyn Error>` as
+// the chapter does, with an enum derived by thiserror, with anyhow and its
+// `context`, and with snafu's context selectors. Each module is the whole
+// pipeline, so any two can be diffed, and they report the same failures
+// in the same words; the tests hold them to that.
+
+pub mod boxed;
+pub mod with_anyhow;
+pub mod with_snafu;
+pub mod with_thiserror;
+
+use std::error::Error;
+
+/// An error's message, then its source's, and so on down: what a program
+/// would show its user.
+pub fn chain(error: &(dyn Error + 'static)) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut next = Some(error);
+    while let Some(error) = next {
+        messages.push(error.to_string());
+        next = error.source();
+    }
+    messages
+}
@@ -0,0 +1,30 @@
+// One error type for everything, `anyhow::Error`, with `context` wrapping
+// an error in a message, as `boxed`'s `Context` does by hand. `context`
+// works on an `Option` too, so the overflow needs no error to wrap.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Sum the integers in a file, one per line, skipping blank lines. A
+/// caller can tell failures apart with `downcast_ref`, which sees through
+/// the context to the error inside.
+pub fn sum_file(path: &Path) -> Result<i64> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("couldn't read {}", path.display()))?;
+    let mut sum: i64 = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let n: i64 = line
+            .parse()
+            .with_context(|| format!("line {}: {:?} isn't a number", index + 1, line))?;
+        sum = sum
+            .checked_add(n)
+            .with_context(|| format!("the sum overflows at line {}", index + 1))?;
+    }
+    Ok(sum)
+}
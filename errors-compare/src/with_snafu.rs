@@ -0,0 +1,45 @@
+// The same enum as `with_thiserror`, but snafu also derives a context
+// selector for each variant, `ReadSnafu` and so on, holding the fields
+// that aren't the source. `context` fills in the source, so the call sites
+// read like anyhow's while the error stays an enum.
+
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+
+use snafu::{OptionExt, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+pub enum SumError {
+    #[snafu(display("couldn't read {}", path.display()))]
+    Read { path: PathBuf, source: io::Error },
+    #[snafu(display("line {line}: {text:?} isn't a number"))]
+    Parse {
+        line: usize,
+        text: String,
+        source: ParseIntError,
+    },
+    #[snafu(display("the sum overflows at line {line}"))]
+    Overflow { line: usize },
+}
+
+/// Sum the integers in a file, one per line, skipping blank lines.
+pub fn sum_file(path: &Path) -> Result<i64, SumError> {
+    let text = fs::read_to_string(path).context(ReadSnafu { path })?;
+    let mut sum: i64 = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let n: i64 = line.parse().context(ParseSnafu {
+            line: index + 1,
+            text: line,
+        })?;
+        sum = sum
+            .checked_add(n)
+            .context(OverflowSnafu { line: index + 1 })?;
+    }
+    Ok(sum)
+}
@@ -0,0 +1,46 @@
+// An enum with a variant for each way the pipeline fails, and thiserror
+// deriving `Display` and `Error` from the attributes. A field named
+// `source` is the variant's source. Callers can `match` on the variants.
+
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SumError {
+    #[error("couldn't read {}", .path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("line {line}: {text:?} isn't a number")]
+    Parse {
+        line: usize,
+        text: String,
+        source: ParseIntError,
+    },
+    #[error("the sum overflows at line {line}")]
+    Overflow { line: usize },
+}
+
+/// Sum the integers in a file, one per line, skipping blank lines.
+pub fn sum_file(path: &Path) -> Result<i64, SumError> {
+    let text = fs::read_to_string(path).map_err(|source| SumError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut sum: i64 = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let n: i64 = line.parse().map_err(|source| SumError::Parse {
+            line: index + 1,
+            text: line.to_string(),
+            source,
+        })?;
+        sum = sum
+            .checked_add(n)
+            .ok_or(SumError::Overflow { line: index + 1 })?;
+    }
+    Ok(sum)
+}
@@ -0,0 +1,91 @@
+// One set of tests, run against each approach's `sum_file`: the same sums,
+// and the same messages, source by source, for each failure.
+
+use std::fs;
+use std::path::Path;
+
+use errors_compare::boxed::GenericError;
+
+/// `sum_file`'s result, with any error turned into its chain of messages.
+fn messages<E: Into<GenericError>>(result: Result<i64, E>) -> Result<i64, Vec<String>> {
+    result.map_err(|e| errors_compare::chain(&*e.into()))
+}
+
+macro_rules! same_behavior {
+    ($($approach:ident),*) => {
+        $(
+            mod $approach {
+                use super::*;
+
+                fn sum(path: &Path) -> Result<i64, Vec<String>> {
+                    messages(errors_compare::$approach::sum_file(path))
+                }
+
+                #[test]
+                fn sums_numbers() {
+                    let dir = tempfile::tempdir().unwrap();
+                    let path = dir.path().join("numbers.txt");
+                    fs::write(&path, "1\n\n 2 \n39\n").unwrap();
+                    assert_eq!(sum(&path), Ok(42));
+                    fs::write(&path, "").unwrap();
+                    assert_eq!(sum(&path), Ok(0));
+
+                    let numbers = fixtures::numbers(10_000, 0.0, 7);
+                    fs::write(&path, &numbers.text).unwrap();
+                    assert_eq!(sum(&path), Ok(numbers.sum));
+                }
+
+                #[test]
+                fn a_missing_file() {
+                    let dir = tempfile::tempdir().unwrap();
+                    let path = dir.path().join("missing.txt");
+                    let io_error = fs::read_to_string(&path).unwrap_err();
+                    assert_eq!(
+                        sum(&path),
+                        Err(vec![
+                            format!("couldn't read {}", path.display()),
+                            io_error.to_string(),
+                        ])
+                    );
+                }
+
+                #[test]
+                fn a_bad_line() {
+                    let dir = tempfile::tempdir().unwrap();
+                    let path = dir.path().join("numbers.txt");
+                    fs::write(&path, "12\n\ntwelve\n").unwrap();
+                    assert_eq!(
+                        sum(&path),
+                        Err(vec![
+                            r#"line 3: "twelve" isn't a number"#.to_string(),
+                            "invalid digit found in string".to_string(),
+                        ])
+                    );
+
+                    let numbers = fixtures::numbers(10_000, 0.01, 7);
+                    fs::write(&path, &numbers.text).unwrap();
+                    let first = numbers.bad_lines[0];
+                    let error = sum(&path).unwrap_err();
+                    assert!(
+                        error[0].starts_with(&format!("line {}: ", first)),
+                        "{:?}",
+                        error
+                    );
+                }
+
+                #[test]
+                fn an_overflow() {
+                    let dir = tempfile::tempdir().unwrap();
+                    let path = dir.path().join("numbers.txt");
+                    fs::write(&path, format!("{}\n-1\n1\n1\n", i64::MAX)).unwrap();
+                    assert_eq!(
+                        sum(&path),
+                        Err(vec!["the sum overflows at line 4".to_string()])
+                    );
+                }
+            }
+        )*
+    };
+}
+
+same_behavior!(boxed, with_thiserror, with_anyhow, with_snafu);
@@ -0,0 +1,59 @@
+// Where the approaches differ: how a caller finds out that the file was
+// missing, rather than that one of its lines was bad.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+
+use errors_compare::{boxed, with_anyhow, with_snafu, with_thiserror};
+
+fn missing() -> (tempfile::TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("missing.txt");
+    (dir, path)
+}
+
+#[test]
+fn boxed_by_downcasting_the_source() {
+    let (_dir, path) = missing();
+    let error = boxed::sum_file(&path).unwrap_err();
+    // The box holds a `Context`, which is private; the `io::Error` is its
+    // source.
+    assert!(error.downcast_ref::<io::Error>().is_none());
+    let source = error.source().and_then(|e| e.downcast_ref::<io::Error>());
+    assert_eq!(source.map(io::Error::kind), Some(ErrorKind::NotFound));
+}
+
+#[test]
+fn thiserror_by_matching() {
+    let (_dir, path) = missing();
+    let error = with_thiserror::sum_file(&path).unwrap_err();
+    assert!(matches!(
+        error,
+        with_thiserror::SumError::Read { source, .. } if source.kind() == ErrorKind::NotFound
+    ));
+}
+
+#[test]
+fn anyhow_by_downcasting_through_the_context() {
+    let (_dir, path) = missing();
+    let error = with_anyhow::sum_file(&path).unwrap_err();
+    let io_error = error.downcast_ref::<io::Error>();
+    assert_eq!(io_error.map(io::Error::kind), Some(ErrorKind::NotFound));
+}
+
+#[test]
+fn snafu_by_matching() {
+    let (dir, path) = missing();
+    let error = with_snafu::sum_file(&path).unwrap_err();
+    assert!(matches!(
+        error,
+        with_snafu::SumError::Read { source, .. } if source.kind() == ErrorKind::NotFound
+    ));
+
+    // A bad line is a different variant, carrying the line.
+    let bad = dir.path().join("bad.txt");
+    fs::write(&bad, "1\nx\n").unwrap();
+    let error = with_snafu::sum_file(&bad).unwrap_err();
+    assert!(matches!(error, with_snafu::SumError::Parse { line: 2, .. }));
+}
@@ -0,0 +1,15 @@
+//! Simulate the growth of ferns, from the level of
+//! individual cells on up.
+//!
+//! The crate is organized the way the book's `fern_sim` example is:
+//! `plant_structures` describes ferns, `spores` how they reproduce, and
+//! `simulation` the terrarium they live in, which can be saved to disk and
+//! picked up again later.
+
+pub mod plant_structures;
+pub mod random;
+pub mod simulation;
+pub mod spores;
+
+pub use plant_structures::{Fern, FernType};
+pub use simulation::{SnapshotError, Terrarium};
@@ -0,0 +1,26 @@
+use fern_sim::{Fern, FernType, Terrarium};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut fern = Fern::new(FernType::Fiddlehead);
+    fern_sim::plant_structures::run_simulation(&mut fern, 30);
+    println!("final fern size: {:.2}", fern.size);
+
+    let mut terrarium = Terrarium::with_ferns(2017);
+    terrarium.run(20);
+    terrarium.apply_sunlight(0.5);
+    terrarium.run(20);
+
+    let path = std::env::temp_dir().join("fern-sim-snapshot.json");
+    terrarium.save_json(&path)?;
+    let restored = Terrarium::load_json(&path)?;
+    assert_eq!(restored, terrarium);
+
+    println!(
+        "day {}: {} ferns, {} spores (snapshot saved to {})",
+        restored.day(),
+        restored.ferns().len(),
+        restored.spores().len(),
+        path.display()
+    );
+    Ok(())
+}
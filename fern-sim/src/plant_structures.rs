@@ -0,0 +1,95 @@
+// Ferns themselves: what kind they are, how big, and how they grow.
+
+use serde::{Deserialize, Serialize};
+
+use crate::random::Rng;
+use crate::spores::Spore;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FernType {
+    Fiddlehead,
+    Maidenhair,
+    Staghorn,
+}
+
+impl FernType {
+    pub const ALL: [FernType; 3] = [
+        FernType::Fiddlehead,
+        FernType::Maidenhair,
+        FernType::Staghorn,
+    ];
+
+    /// The fraction of its size a well-lit fern of this type adds per day.
+    pub fn base_growth_rate(self) -> f64 {
+        match self {
+            FernType::Fiddlehead => 0.08,
+            FernType::Maidenhair => 0.05,
+            FernType::Staghorn => 0.03,
+        }
+    }
+
+    /// The size at which a fern of this type starts releasing spores.
+    pub fn mature_size(self) -> f64 {
+        match self {
+            FernType::Fiddlehead => 4.0,
+            FernType::Maidenhair => 6.0,
+            FernType::Staghorn => 10.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fern {
+    pub kind: FernType,
+    pub size: f64,
+    pub growth_rate: f64,
+    pub age: u32,
+}
+
+/// No fern grows past this, no matter how much light it gets.
+pub const MAX_SIZE: f64 = 50.0;
+
+impl Fern {
+    /// A newly sprouted fern of the given type.
+    pub fn new(kind: FernType) -> Fern {
+        Fern {
+            kind,
+            size: 1.0,
+            growth_rate: kind.base_growth_rate(),
+            age: 0,
+        }
+    }
+
+    pub fn is_mature(&self) -> bool {
+        self.size >= self.kind.mature_size()
+    }
+
+    /// Grow for one day, given the fraction of full sunlight (`0.0..=1.0`)
+    /// the fern received.
+    pub fn grow(&mut self, sunlight: f64) {
+        let sunlight = sunlight.clamp(0.0, 1.0);
+        self.size = (self.size * (1.0 + self.growth_rate * sunlight)).min(MAX_SIZE);
+        self.age += 1;
+    }
+
+    /// A mature fern sometimes releases a spore. The bigger it is, the more
+    /// likely.
+    pub fn sporulate(&self, rng: &mut Rng) -> Option<Spore> {
+        if !self.is_mature() {
+            return None;
+        }
+        let p = (self.size / MAX_SIZE).min(1.0) * 0.5;
+        if rng.chance(p) {
+            Some(Spore::new(self.kind))
+        } else {
+            None
+        }
+    }
+}
+
+/// Run a single fern for `days` days of full sunlight.
+pub fn run_simulation(fern: &mut Fern, days: usize) {
+    for _ in 0..days {
+        fern.grow(1.0);
+    }
+}
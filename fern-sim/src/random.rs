@@ -0,0 +1,48 @@
+// A small seeded pseudo-random number generator.
+//
+// The simulation's only source of randomness lives inside the `Terrarium`
+// and is saved along with it, so a restored snapshot makes exactly the same
+// "random" choices an uninterrupted run would have.
+
+use serde::{Deserialize, Serialize};
+
+/// SplitMix64: fast, tiny state, and good enough for a simulation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A float uniformly distributed in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill an f64's mantissa exactly.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A float uniformly distributed in `low..high`.
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// Return true with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Pick an index in `0..len`. `len` must be nonzero.
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
@@ -0,0 +1,208 @@
+// Overall simulation control, plus saving and restoring snapshots.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::plant_structures::{Fern, FernType};
+use crate::random::Rng;
+use crate::spores::{Fate, Spore};
+
+/// The terrarium holds no more ferns than this. Spores that germinate when
+/// it's full die instead.
+pub const CAPACITY: usize = 64;
+
+/// The simulated universe: some ferns, their spores, and the random number
+/// generator that decides what happens to them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Terrarium {
+    day: u64,
+    sunlight: f64,
+    ferns: Vec<Fern>,
+    spores: Vec<Spore>,
+    rng: Rng,
+}
+
+impl Terrarium {
+    /// Create an empty terrarium in full sunlight. The seed determines every
+    /// random event in the simulation.
+    pub fn new(seed: u64) -> Terrarium {
+        Terrarium {
+            day: 0,
+            sunlight: 1.0,
+            ferns: Vec::new(),
+            spores: Vec::new(),
+            rng: Rng::seeded(seed),
+        }
+    }
+
+    /// Create a terrarium with one young fern of each type.
+    pub fn with_ferns(seed: u64) -> Terrarium {
+        let mut terrarium = Terrarium::new(seed);
+        for kind in FernType::ALL {
+            terrarium.plant(Fern::new(kind));
+        }
+        terrarium
+    }
+
+    pub fn plant(&mut self, fern: Fern) {
+        self.ferns.push(fern);
+    }
+
+    pub fn day(&self) -> u64 {
+        self.day
+    }
+
+    pub fn ferns(&self) -> &[Fern] {
+        &self.ferns
+    }
+
+    /// Access a single fern in the simulation.
+    pub fn fern(&self, index: usize) -> &Fern {
+        &self.ferns[index]
+    }
+
+    pub fn spores(&self) -> &[Spore] {
+        &self.spores
+    }
+
+    /// Set the fraction of full sunlight (`0.0..=1.0`) the terrarium gets
+    /// from now on.
+    pub fn apply_sunlight(&mut self, sunlight: f64) {
+        self.sunlight = sunlight.clamp(0.0, 1.0);
+    }
+
+    /// Run the simulation for one day: ferns grow and release spores, and
+    /// the spores released on earlier days drift, germinate, or die.
+    pub fn tick(&mut self) {
+        self.day += 1;
+
+        let mut germinated = Vec::new();
+        for spore in std::mem::take(&mut self.spores) {
+            match spore.tick(&mut self.rng) {
+                Fate::Drifting(spore) => self.spores.push(spore),
+                Fate::Germinated(fern) => germinated.push(fern),
+                Fate::Died => {}
+            }
+        }
+
+        for fern in &mut self.ferns {
+            // Ferns in the shade of their neighbors get a little less light.
+            let shade = self.rng.range(0.0, 0.2);
+            fern.grow(self.sunlight * (1.0 - shade));
+            if let Some(spore) = fern.sporulate(&mut self.rng) {
+                self.spores.push(spore);
+            }
+        }
+
+        for fern in germinated {
+            if self.ferns.len() < CAPACITY {
+                self.ferns.push(fern);
+            }
+        }
+    }
+
+    /// Run the simulation for `days` days.
+    pub fn run(&mut self, days: u64) {
+        for _ in 0..days {
+            self.tick();
+        }
+    }
+
+    /// Write a snapshot of the simulation as JSON.
+    pub fn to_json_writer<W: Write>(&self, writer: W) -> Result<(), SnapshotError> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by `to_json_writer`.
+    pub fn from_json_reader<R: Read>(reader: R) -> Result<Terrarium, SnapshotError> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Write a snapshot of the simulation in the compact bincode format.
+    pub fn to_bincode_writer<W: Write>(&self, writer: W) -> Result<(), SnapshotError> {
+        bincode::serialize_into(writer, self)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by `to_bincode_writer`.
+    pub fn from_bincode_reader<R: Read>(reader: R) -> Result<Terrarium, SnapshotError> {
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    /// Save a JSON snapshot to the file at `path`.
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_json_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a JSON snapshot from the file at `path`.
+    pub fn load_json<P: AsRef<Path>>(path: P) -> Result<Terrarium, SnapshotError> {
+        Terrarium::from_json_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Save a bincode snapshot to the file at `path`.
+    pub fn save_bincode<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_bincode_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Load a bincode snapshot from the file at `path`.
+    pub fn load_bincode<P: AsRef<Path>>(path: P) -> Result<Terrarium, SnapshotError> {
+        Terrarium::from_bincode_reader(BufReader::new(File::open(path)?))
+    }
+}
+
+/// An error saving or loading a snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Bincode(bincode::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "snapshot I/O error: {}", err),
+            SnapshotError::Json(err) => write!(f, "bad JSON snapshot: {}", err),
+            SnapshotError::Bincode(err) => write!(f, "bad bincode snapshot: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(err) => Some(err),
+            SnapshotError::Json(err) => Some(err),
+            SnapshotError::Bincode(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> SnapshotError {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> SnapshotError {
+        SnapshotError::Json(err)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(err: bincode::Error) -> SnapshotError {
+        SnapshotError::Bincode(err)
+    }
+}
@@ -0,0 +1,48 @@
+// Fern reproduction. A spore drifts around the terrarium until it either
+// germinates into a new fern or dies.
+
+use serde::{Deserialize, Serialize};
+
+use crate::plant_structures::{Fern, FernType};
+use crate::random::Rng;
+
+/// Spores that haven't germinated after this many days die.
+pub const SPORE_LIFETIME: u32 = 10;
+
+/// The chance, each day, that a spore germinates.
+pub const GERMINATION_CHANCE: f64 = 0.1;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spore {
+    pub kind: FernType,
+    pub age: u32,
+}
+
+/// What became of a spore after a day in the terrarium.
+#[derive(Debug, PartialEq)]
+pub enum Fate {
+    Drifting(Spore),
+    Germinated(Fern),
+    Died,
+}
+
+impl Spore {
+    pub fn new(kind: FernType) -> Spore {
+        Spore { kind, age: 0 }
+    }
+
+    /// Age the spore by one day. A germinating spore's fern gets a growth
+    /// rate within 20% either way of its type's usual rate.
+    pub fn tick(mut self, rng: &mut Rng) -> Fate {
+        self.age += 1;
+        if rng.chance(GERMINATION_CHANCE) {
+            let mut fern = Fern::new(self.kind);
+            fern.growth_rate *= rng.range(0.8, 1.2);
+            Fate::Germinated(fern)
+        } else if self.age >= SPORE_LIFETIME {
+            Fate::Died
+        } else {
+            Fate::Drifting(self)
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use fern_sim::{Fern, FernType, Terrarium};
+
+const SEEDS: [u64; 4] = [0, 1, 2017, 0xdead_beef];
+const DAYS: u64 = 120;
+
+/// Run `DAYS` days straight through.
+fn uninterrupted(seed: u64) -> Terrarium {
+    let mut terrarium = Terrarium::with_ferns(seed);
+    terrarium.run(DAYS);
+    terrarium
+}
+
+/// Run `split` days, pass the terrarium through `save_and_restore`, then run
+/// the remaining days.
+fn interrupted(
+    seed: u64,
+    split: u64,
+    save_and_restore: impl Fn(&Terrarium) -> Terrarium,
+) -> Terrarium {
+    let mut terrarium = Terrarium::with_ferns(seed);
+    terrarium.run(split);
+    let mut restored = save_and_restore(&terrarium);
+    assert_eq!(restored, terrarium);
+    restored.run(DAYS - split);
+    restored
+}
+
+fn through_json(terrarium: &Terrarium) -> Terrarium {
+    let mut buffer = Vec::new();
+    terrarium.to_json_writer(&mut buffer).unwrap();
+    Terrarium::from_json_reader(&buffer[..]).unwrap()
+}
+
+fn through_bincode(terrarium: &Terrarium) -> Terrarium {
+    let mut buffer = Vec::new();
+    terrarium.to_bincode_writer(&mut buffer).unwrap();
+    Terrarium::from_bincode_reader(&buffer[..]).unwrap()
+}
+
+#[test]
+fn simulation_is_deterministic() {
+    for seed in SEEDS {
+        assert_eq!(uninterrupted(seed), uninterrupted(seed));
+    }
+    assert_ne!(uninterrupted(1), uninterrupted(2));
+}
+
+#[test]
+fn simulation_does_something() {
+    let terrarium = uninterrupted(2017);
+    assert_eq!(terrarium.day(), DAYS);
+    assert!(terrarium.ferns().len() > FernType::ALL.len());
+    assert!(terrarium.fern(0).is_mature());
+}
+
+#[test]
+fn json_restore_never_diverges() {
+    for seed in SEEDS {
+        let expected = uninterrupted(seed);
+        for split in [0, 1, 17, 60, 119, DAYS] {
+            assert_eq!(
+                interrupted(seed, split, through_json),
+                expected,
+                "seed {} split at day {}",
+                seed,
+                split
+            );
+        }
+    }
+}
+
+#[test]
+fn bincode_restore_never_diverges() {
+    for seed in SEEDS {
+        let expected = uninterrupted(seed);
+        for split in [0, 1, 17, 60, 119, DAYS] {
+            assert_eq!(
+                interrupted(seed, split, through_bincode),
+                expected,
+                "seed {} split at day {}",
+                seed,
+                split
+            );
+        }
+    }
+}
+
+#[test]
+fn save_and_load_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let json = dir.path().join("terrarium.json");
+    let bin = dir.path().join("terrarium.bin");
+
+    let mut terrarium = Terrarium::with_ferns(7);
+    terrarium.apply_sunlight(0.6);
+    terrarium.run(45);
+    terrarium.save_json(&json).unwrap();
+    terrarium.save_bincode(&bin).unwrap();
+
+    assert_eq!(Terrarium::load_json(&json).unwrap(), terrarium);
+    assert_eq!(Terrarium::load_bincode(&bin).unwrap(), terrarium);
+    assert!(std::fs::metadata(&bin).unwrap().len() < std::fs::metadata(&json).unwrap().len());
+}
+
+#[test]
+fn loading_bad_snapshots_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.json");
+    assert!(matches!(
+        Terrarium::load_json(&missing),
+        Err(fern_sim::SnapshotError::Io(_))
+    ));
+    assert!(matches!(
+        Terrarium::from_json_reader(&b"{\"day\": 3}"[..]),
+        Err(fern_sim::SnapshotError::Json(_))
+    ));
+    assert!(matches!(
+        Terrarium::from_bincode_reader(&[1, 2, 3][..]),
+        Err(fern_sim::SnapshotError::Bincode(_))
+    ));
+}
+
+#[test]
+fn ferns_grow_toward_their_limit() {
+    let mut fern = Fern::new(FernType::Staghorn);
+    fern_sim::plant_structures::run_simulation(&mut fern, 10);
+    assert!(fern.size > 1.0);
+    assert_eq!(fern.age, 10);
+
+    let mut dark = Fern::new(FernType::Staghorn);
+    dark.grow(0.0);
+    assert_eq!(dark.size, 1.0);
+
+    fern_sim::plant_structures::run_simulation(&mut fern, 10_000);
+    assert_eq!(fern.size, fern_sim::plant_structures::MAX_SIZE);
+}
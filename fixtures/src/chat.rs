@@ -0,0 +1,51 @@
+// Chat transcripts: who said what, in order. The workspace has no chat
+// server yet, but messages from several senders are what channels carry.
+
+use std::fmt;
+
+use crate::text::word;
+use crate::Rng;
+
+const NAMES: &[&str] = &["ana", "bo", "chidi", "dana", "emeka", "fen", "gus", "hana"];
+
+/// One line of a chat.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub from: String,
+    pub text: String,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.from, self.text)
+    }
+}
+
+/// `count` messages between up to `senders` people, at most eight. Now
+/// and then a message is long, or mentions someone, or repeats the one
+/// before it.
+pub fn transcript(count: usize, senders: usize, seed: u64) -> Vec<Message> {
+    let mut rng = Rng::new(seed);
+    let names = &NAMES[..senders.clamp(1, NAMES.len())];
+    let mut messages: Vec<Message> = Vec::with_capacity(count);
+    while messages.len() < count {
+        let from = rng.pick(names).to_string();
+        let text = match (rng.below(10), messages.last()) {
+            (0, Some(last)) => last.text.clone(),
+            (1, _) => format!("@{} {}", rng.pick(names), word(&mut rng)),
+            (2, _) => (0..40)
+                .map(|_| word(&mut rng))
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => {
+                let length = 1 + rng.below(8);
+                (0..length)
+                    .map(|_| word(&mut rng))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+        };
+        messages.push(Message { from, text });
+    }
+    messages
+}
@@ -0,0 +1,32 @@
+// Expressions for the chapter 3 calculator.
+
+use crate::Rng;
+
+/// An arithmetic expression in the calculator's syntax with `operators`
+/// binary operators, parenthesized now and then. Division is avoided so
+/// that every expression evaluates.
+pub fn expression(operators: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut expr = number(&mut rng);
+    for _ in 0..operators {
+        let op = rng.pick(&["+", "-", "*", "^", "%"]);
+        let operand = match rng.below(6) {
+            0 => format!("sqrt({})", number(&mut rng)),
+            1 => format!("({} + {})", number(&mut rng), number(&mut rng)),
+            2 => "pi".to_string(),
+            _ => number(&mut rng),
+        };
+        // `^` binds tightest, so raising only a number to a small power
+        // keeps the value finite however long the expression grows.
+        if op == "^" {
+            expr = format!("{} + {} ^ 2", expr, number(&mut rng));
+        } else {
+            expr = format!("{} {} {}", expr, op, operand);
+        }
+    }
+    expr
+}
+
+fn number(rng: &mut Rng) -> String {
+    format!("{}.{}", 1 + rng.below(99), rng.below(10))
+}
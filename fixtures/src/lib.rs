@@ -0,0 +1,55 @@
+// Seeded inputs for tests and benchmarks: prose with a pattern planted in
+// it, number files with some bad lines, dictionaries, chat transcripts,
+// and calculator expressions.
+//
+// Every generator takes a seed and gives the same output for it on any
+// machine, so a failing test fails the same way twice, and benchmarks
+// comparing alternatives feed each one the same bytes. Each also says
+// what a correct program should make of its output, where that isn't
+// obvious: how many copies of the pattern were planted, what the numbers
+// add up to, which lines are bad. A test can then check a result against
+// what was generated, at any size, instead of against a file someone
+// wrote by hand.
+
+mod chat;
+mod expression;
+mod numbers;
+mod text;
+
+pub use chat::{transcript, Message};
+pub use expression::expression;
+pub use numbers::{numbers, Numbers};
+pub use text::{planted, text, words, Planted};
+
+/// A xorshift generator, as in serde-bench: varied enough to keep branch
+/// predictors honest, and repeatable. A seed of 0, which is a fixed point,
+/// is taken as 1.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < p
+    }
+
+    pub fn pick<'a>(&mut self, choices: &[&'a str]) -> &'a str {
+        choices[self.below(choices.len() as u64) as usize]
+    }
+}
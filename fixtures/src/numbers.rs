@@ -0,0 +1,44 @@
+// Files of numbers, one per line, for chapter 7's `read_and_sum` and
+// `SumFollower`.
+
+use crate::Rng;
+
+/// Lines that aren't integers, each for a different reason.
+const BAD: &[&str] = &["twelve", "1.5", "0x10", "--3", "4 5", "9223372036854775808"];
+
+/// A file of numbers, and what's in it.
+#[derive(Clone, Debug)]
+pub struct Numbers {
+    pub text: String,
+    /// The sum of the lines that are numbers.
+    pub sum: i64,
+    /// The lines that aren't, counting from 1.
+    pub bad_lines: Vec<usize>,
+}
+
+/// `lines` lines, each an integer between -1000 and 1000, some with spaces
+/// around it, except that each line is something else with probability
+/// `error_rate`.
+pub fn numbers(lines: usize, error_rate: f64, seed: u64) -> Numbers {
+    let mut rng = Rng::new(seed);
+    let mut numbers = Numbers {
+        text: String::new(),
+        sum: 0,
+        bad_lines: Vec::new(),
+    };
+    for line in 1..=lines {
+        if rng.chance(error_rate) {
+            numbers.text.push_str(rng.pick(BAD));
+            numbers.bad_lines.push(line);
+        } else {
+            let n = rng.below(2001) as i64 - 1000;
+            let padding = if rng.below(10) == 0 { " " } else { "" };
+            numbers
+                .text
+                .push_str(&format!("{}{}{}", padding, n, padding));
+            numbers.sum += n;
+        }
+        numbers.text.push('\n');
+    }
+    numbers
+}
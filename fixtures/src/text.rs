@@ -0,0 +1,85 @@
+// Words, and prose made of them.
+
+use std::collections::HashSet;
+
+use crate::Rng;
+
+const SYLLABLES: &[&str] = &[
+    "ka", "lo", "mi", "ne", "ru", "sa", "ti", "vo", "zen", "qua", "str", "ing",
+];
+
+/// A made-up word of one to four syllables, in lowercase ASCII letters.
+pub(crate) fn word(rng: &mut Rng) -> String {
+    let syllables = 1 + rng.below(4);
+    (0..syllables).map(|_| rng.pick(SYLLABLES)).collect()
+}
+
+/// `count` distinct words, in no particular order: a dictionary for a
+/// `StringTable` or an autocomplete box.
+pub fn words(count: usize, seed: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    let mut seen = HashSet::new();
+    let mut words = Vec::with_capacity(count);
+    while words.len() < count {
+        // Numbering the word keeps the loop finite once the syllables
+        // run out of combinations.
+        let w = format!("{}{}", word(&mut rng), words.len() % 100);
+        if seen.insert(w.clone()) {
+            words.push(w);
+        }
+    }
+    words
+}
+
+/// Lines of up to a dozen words, until there are at least `bytes` bytes,
+/// with `extra` deciding now and then to put something else in a word's
+/// place.
+fn prose(bytes: usize, rng: &mut Rng, mut extra: impl FnMut(&mut Rng) -> Option<String>) -> String {
+    let mut text = String::with_capacity(bytes + 80);
+    while text.len() < bytes {
+        let length = 1 + rng.below(12);
+        for i in 0..length {
+            if i > 0 {
+                text.push(' ');
+            }
+            match extra(rng) {
+                Some(other) => text.push_str(&other),
+                None => text.push_str(&word(rng)),
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// About `bytes` bytes of prose-like text, with an email address now and
+/// then for patterns to find.
+pub fn text(bytes: usize, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    prose(bytes, &mut rng, |rng| {
+        (rng.below(20) == 0).then(|| format!("{}@{}.org", word(rng), word(rng)))
+    })
+}
+
+/// Text with a pattern planted in it, and how many times.
+#[derive(Clone, Debug)]
+pub struct Planted {
+    pub text: String,
+    pub count: usize,
+}
+
+/// About `bytes` bytes of prose-like text with `needle` in place of one
+/// word in `every`, on average. The other words are lowercase letters, so
+/// a needle with anything else in it, like `TODO` or `a.b(c)`, appears
+/// exactly `count` times.
+pub fn planted(bytes: usize, needle: &str, every: u64, seed: u64) -> Planted {
+    let mut rng = Rng::new(seed);
+    let mut count = 0;
+    let text = prose(bytes, &mut rng, |rng| {
+        (rng.below(every) == 0).then(|| {
+            count += 1;
+            needle.to_string()
+        })
+    });
+    Planted { text, count }
+}
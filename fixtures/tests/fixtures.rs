@@ -0,0 +1,80 @@
+use std::io::Cursor;
+
+use fixtures::{expression, numbers, planted, text, transcript, words, Rng};
+
+#[test]
+fn the_same_seed_gives_the_same_output() {
+    assert_eq!(words(500, 7), words(500, 7));
+    assert_eq!(text(4096, 7), text(4096, 7));
+    assert_eq!(expression(50, 7), expression(50, 7));
+    assert_eq!(numbers(100, 0.1, 7).text, numbers(100, 0.1, 7).text);
+    assert_eq!(transcript(100, 4, 7), transcript(100, 4, 7));
+    assert_ne!(text(4096, 7), text(4096, 8));
+    // Including 0, which xorshift can't start from.
+    assert_eq!(Rng::new(0).next_u64(), Rng::new(1).next_u64());
+}
+
+#[test]
+fn words_are_distinct() {
+    let mut list = words(2000, 1);
+    list.sort();
+    list.dedup();
+    assert_eq!(list.len(), 2000);
+}
+
+#[test]
+fn text_has_addresses() {
+    let text = text(64 * 1024, 3);
+    assert!(text.len() >= 64 * 1024);
+    assert!(text.contains('@'));
+    assert!(text.ends_with('\n'));
+}
+
+#[test]
+fn planted_counts_are_exact() {
+    for (needle, every) in [("TODO", 10), ("a.b(c)", 50), ("x@y", 3)] {
+        let planted = planted(32 * 1024, needle, every, 11);
+        assert!(planted.count > 0);
+        assert_eq!(planted.text.matches(needle).count(), planted.count);
+    }
+}
+
+#[test]
+fn numbers_add_up() {
+    let good = numbers(1000, 0.0, 5);
+    assert!(good.bad_lines.is_empty());
+    assert_eq!(good.text.lines().count(), 1000);
+    assert_eq!(ch_07::sum_lines(Cursor::new(&good.text)).unwrap(), good.sum);
+
+    let bad = numbers(1000, 0.05, 5);
+    assert!(!bad.bad_lines.is_empty());
+    for (i, line) in bad.text.lines().enumerate() {
+        let parses = line.trim().parse::<i64>().is_ok();
+        assert_eq!(parses, !bad.bad_lines.contains(&(i + 1)), "{:?}", line);
+    }
+    assert!(ch_07::sum_lines(Cursor::new(&bad.text)).is_err());
+}
+
+#[test]
+fn transcripts_stay_within_their_senders() {
+    let chat = transcript(500, 3, 9);
+    assert_eq!(chat.len(), 500);
+    let mut senders: Vec<&str> = chat.iter().map(|m| m.from.as_str()).collect();
+    senders.sort();
+    senders.dedup();
+    assert_eq!(senders, ["ana", "bo", "chidi"]);
+    assert!(chat.iter().all(|m| !m.text.is_empty()));
+    assert_eq!(
+        chat[0].to_string(),
+        format!("{}: {}", chat[0].from, chat[0].text)
+    );
+}
+
+#[test]
+fn expressions_evaluate() {
+    for seed in 1..50 {
+        let expr = expression(20 * seed as usize, seed);
+        let value = ch_03::eval(&expr).unwrap_or_else(|e| panic!("{}: {}", expr, e));
+        assert!(value.is_finite(), "{} = {}", expr, value);
+    }
+}
@@ -0,0 +1,10 @@
+// Chapter 3's evaluator, on any text at all. It may reject the input, but
+// it mustn't panic or overflow the stack.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = ch_03::eval(input);
+});
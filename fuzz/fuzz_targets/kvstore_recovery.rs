@@ -0,0 +1,39 @@
+// kvstore's recovery, on a log file of any bytes: what a crash, a bad
+// disk, or a stray write could leave behind. Opening the store must either
+// fail cleanly or give a store that can read back every key it lists,
+// take new writes, and open again to the same contents.
+
+#![no_main]
+
+use std::fs;
+
+use kvstore::KvStore;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|log: &[u8]| {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("1.log"), log).unwrap();
+
+    let Ok(store) = KvStore::open(dir.path()) else {
+        return;
+    };
+    let mut contents = Vec::new();
+    for key in store.keys() {
+        let value = store.get(&key).unwrap().expect("listed key has no value");
+        contents.push((key, value));
+    }
+    contents.sort();
+    store.put(b"after recovery", b"still writable").unwrap();
+    drop(store);
+
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(
+        store.get(b"after recovery").unwrap().as_deref(),
+        Some(&b"still writable"[..])
+    );
+    for (key, value) in contents {
+        if key != b"after recovery" {
+            assert_eq!(store.get(&key).unwrap(), Some(value));
+        }
+    }
+});
@@ -0,0 +1,34 @@
+// quickreplace's rules files, of any bytes: what `--rules` could be
+// pointed at by mistake, or written by hand with a typo. Loading one must
+// fail cleanly or give at least one rule, and building a replacer from
+// the rules must fail cleanly or give one that can replace.
+
+#![no_main]
+
+use std::fs;
+
+use ch_02::quickreplace::CATALOGS;
+use ch_02::{rules, Replacer};
+use common::Messages;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|file: &[u8]| {
+    let messages = Messages::new(CATALOGS, Some("en"));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rules.toml");
+    fs::write(&path, file).unwrap();
+
+    let Ok(rules) = rules::load(path.to_str().unwrap(), &messages) else {
+        return;
+    };
+    assert!(!rules.rule.is_empty());
+    // A pattern is whatever the file says, so it's kept small enough to
+    // compile quickly.
+    let mut builder = Replacer::builder().size_limit(1 << 20);
+    for rule in rules.rule {
+        builder = builder.pattern(rule.pattern).replacement(rule.replacement);
+    }
+    if let Ok(replacer) = builder.build() {
+        let _ = replacer.replace("colour me@home\n");
+    }
+});
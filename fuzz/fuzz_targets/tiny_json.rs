@@ -0,0 +1,13 @@
+// tiny-json's parser, on any bytes. Whatever it accepts must print as
+// JSON that parses back to the same value.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &[u8]| {
+    if let Ok(value) = tiny_json::parse_bytes(input) {
+        let printed = value.to_string();
+        assert_eq!(tiny_json::parse(&printed).unwrap(), value, "{}", printed);
+    }
+});
@@ -0,0 +1,370 @@
+// The book's `GapBuffer<T>`: a sequence with a movable "gap" of unused
+// space, so that insertions and deletions at the gap cost O(1) no matter how
+// long the sequence is. Text editors use this structure for the text around
+// the cursor.
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::Range;
+use std::slice;
+
+/// A `GapBuffer<T>` is a sequence of elements of type `T` that can insert and
+/// remove elements at any position in constant time. Moving the position
+/// takes time proportional to the distance it is moved.
+pub struct GapBuffer<T> {
+    // Storage for elements. This has the capacity we need, but its length
+    // always remains zero. GapBuffer puts its elements and the gap in this
+    // `Vec`'s "unused" capacity.
+    storage: Vec<T>,
+
+    // Range of uninitialized elements in the middle of `storage`.
+    // Elements before and after this range are always initialized.
+    gap: Range<usize>,
+}
+
+impl<T> GapBuffer<T> {
+    pub fn new() -> GapBuffer<T> {
+        let storage = Vec::new();
+        // For zero-sized types, an empty `Vec` already reports a capacity of
+        // `usize::MAX`; all of it starts out as gap.
+        let gap = 0..storage.capacity();
+        GapBuffer { storage, gap }
+    }
+
+    /// Return the number of elements this GapBuffer could hold without
+    /// reallocation.
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    /// Return the number of elements this GapBuffer currently holds.
+    pub fn len(&self) -> usize {
+        self.capacity() - self.gap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the current insertion position.
+    pub fn position(&self) -> usize {
+        self.gap.start
+    }
+
+    /// Return a pointer to the `index`'th element of the underlying storage,
+    /// regardless of the gap.
+    ///
+    /// Safety: `index` must be a valid index into `self.storage`.
+    unsafe fn space(&self, index: usize) -> *const T {
+        self.storage.as_ptr().add(index)
+    }
+
+    /// Return a mutable pointer to the `index`'th element of the underlying
+    /// storage, regardless of the gap.
+    ///
+    /// Safety: `index` must be a valid index into `self.storage`.
+    unsafe fn space_mut(&mut self, index: usize) -> *mut T {
+        self.storage.as_mut_ptr().add(index)
+    }
+
+    /// Return the offset in the buffer of the `index`'th element, taking
+    /// the gap into account. This does not check whether index is in range,
+    /// but it never returns an index in the gap.
+    fn index_to_raw(&self, index: usize) -> usize {
+        if index < self.gap.start {
+            index
+        } else {
+            index + self.gap.len()
+        }
+    }
+
+    /// Return a reference to the `index`'th element,
+    /// or `None` if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        let raw = self.index_to_raw(index);
+        // We just checked `index` against self.len(), so `raw` is in bounds
+        // and outside the gap: the element there is initialized.
+        unsafe { Some(&*self.space(raw)) }
+    }
+
+    /// Return a mutable reference to the `index`'th element,
+    /// or `None` if `index` is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+        let raw = self.index_to_raw(index);
+        unsafe { Some(&mut *self.space_mut(raw)) }
+    }
+
+    /// Set the current insertion position to `pos`.
+    /// If `pos` is out of bounds, panic.
+    pub fn set_position(&mut self, pos: usize) {
+        if pos > self.len() {
+            panic!("index {} out of range for GapBuffer", pos);
+        }
+
+        unsafe {
+            let gap = self.gap.clone();
+            if pos > gap.start {
+                // `pos` falls after the gap. Move the gap right
+                // by shifting elements after the gap to before it.
+                let distance = pos - gap.start;
+                std::ptr::copy(self.space(gap.end), self.space_mut(gap.start), distance);
+            } else if pos < gap.start {
+                // `pos` falls before the gap. Move the gap left
+                // by shifting elements before the gap to after it.
+                let distance = gap.start - pos;
+                std::ptr::copy(
+                    self.space(pos),
+                    self.space_mut(gap.end - distance),
+                    distance,
+                );
+            }
+
+            self.gap = pos..pos + gap.len();
+        }
+    }
+
+    /// Insert `elt` at the current insertion position,
+    /// and leave the insertion position after it.
+    pub fn insert(&mut self, elt: T) {
+        if self.gap.is_empty() {
+            self.enlarge_gap();
+        }
+
+        unsafe {
+            let index = self.gap.start;
+            std::ptr::write(self.space_mut(index), elt);
+        }
+        self.gap.start += 1;
+    }
+
+    /// Insert the elements produced by `iter` at the current insertion
+    /// position, and leave the insertion position after them.
+    pub fn insert_iter<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.insert(item)
+        }
+    }
+
+    /// Remove the element just after the insertion position
+    /// and return it, or return `None` if the insertion position
+    /// is at the end of the GapBuffer.
+    pub fn remove(&mut self) -> Option<T> {
+        if self.gap.end == self.capacity() {
+            return None;
+        }
+
+        let element = unsafe { std::ptr::read(self.space(self.gap.end)) };
+        self.gap.end += 1;
+        Some(element)
+    }
+
+    /// Remove the element just before the insertion position and return it,
+    /// moving the insertion position back by one, or return `None` if the
+    /// insertion position is at the start. This is a text editor's
+    /// backspace.
+    pub fn remove_before(&mut self) -> Option<T> {
+        if self.gap.start == 0 {
+            return None;
+        }
+
+        self.gap.start -= 1;
+        Some(unsafe { std::ptr::read(self.space(self.gap.start)) })
+    }
+
+    /// Return the elements before and after the gap as two slices.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        unsafe {
+            let before = slice::from_raw_parts(self.space(0), self.gap.start);
+            let after =
+                slice::from_raw_parts(self.space(self.gap.end), self.capacity() - self.gap.end);
+            (before, after)
+        }
+    }
+
+    /// Return an iterator over the elements in order, skipping the gap.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (before, after) = self.as_slices();
+        Iter {
+            before: before.iter(),
+            after: after.iter(),
+        }
+    }
+
+    /// Double the capacity of `self.storage`.
+    fn enlarge_gap(&mut self) {
+        let mut new_capacity = self.capacity() * 2;
+        if new_capacity == 0 {
+            // The existing vector is empty.
+            // Choose a reasonable starting capacity.
+            new_capacity = 4;
+        }
+
+        // We have no idea what resizing a Vec does with its "unused"
+        // capacity. So just create a new vector and move over the elements.
+        let mut new = Vec::with_capacity(new_capacity);
+        let after_gap = self.capacity() - self.gap.end;
+        let new_gap = self.gap.start..new.capacity() - after_gap;
+
+        unsafe {
+            // Move elements that fall before the gap.
+            std::ptr::copy_nonoverlapping(self.space(0), new.as_mut_ptr(), self.gap.start);
+
+            // Move elements that fall after the gap.
+            let new_gap_end = new.as_mut_ptr().add(new_gap.end);
+            std::ptr::copy_nonoverlapping(self.space(self.gap.end), new_gap_end, after_gap);
+        }
+
+        // This frees the old Vec, but drops no elements,
+        // because the Vec's length is zero.
+        self.storage = new;
+        self.gap = new_gap;
+    }
+}
+
+impl<T> Drop for GapBuffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            for i in 0..self.gap.start {
+                std::ptr::drop_in_place(self.space_mut(i));
+            }
+            for i in self.gap.end..self.capacity() {
+                std::ptr::drop_in_place(self.space_mut(i));
+            }
+        }
+    }
+}
+
+impl<T> Default for GapBuffer<T> {
+    fn default() -> Self {
+        GapBuffer::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GapBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone> Clone for GapBuffer<T> {
+    fn clone(&self) -> Self {
+        let mut copy: GapBuffer<T> = self.iter().cloned().collect();
+        copy.set_position(self.position());
+        copy
+    }
+}
+
+impl<T: PartialEq> PartialEq for GapBuffer<T> {
+    /// Two buffers are equal if they hold equal elements in the same order;
+    /// the position of the gap doesn't matter.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for GapBuffer<T> {}
+
+impl<T> Extend<T> for GapBuffer<T> {
+    /// Insert the items at the current position, like `insert_iter`.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.insert_iter(iter);
+    }
+}
+
+impl<T> FromIterator<T> for GapBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut buffer = GapBuffer::new();
+        buffer.insert_iter(iter);
+        buffer
+    }
+}
+
+impl fmt::Display for GapBuffer<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.iter() {
+            fmt::Write::write_char(f, *ch)?;
+        }
+        Ok(())
+    }
+}
+
+/// A borrowing iterator over a `GapBuffer`'s elements, in order.
+pub struct Iter<'a, T> {
+    before: slice::Iter<'a, T>,
+    after: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.before.next().or_else(|| self.after.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.before.len() + self.after.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.after.next_back().or_else(|| self.before.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a GapBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An owning iterator that moves elements out of a `GapBuffer`, in order.
+pub struct IntoIter<T> {
+    // All remaining elements sit after the gap; `next` removes them one at a
+    // time, and the buffer's own `Drop` cleans up whatever is left.
+    buffer: GapBuffer<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.buffer.remove()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buffer.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for GapBuffer<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> IntoIter<T> {
+        self.set_position(0);
+        IntoIter { buffer: self }
+    }
+}
@@ -0,0 +1,26 @@
+use gap_buffer::GapBuffer;
+
+fn main() {
+    let mut buf = GapBuffer::new();
+    buf.insert_iter("Lord of the Rings".chars());
+    println!("{} (position {})", buf, buf.position());
+
+    buf.set_position(12);
+    buf.insert_iter("Onion ".chars());
+    println!("{} (position {})", buf, buf.position());
+
+    // Backspace over "Onion " and type something else.
+    for _ in 0.."Onion ".len() {
+        buf.remove_before();
+    }
+    buf.insert_iter("Two ".chars());
+    println!("{} (position {})", buf, buf.position());
+
+    let (before, after) = buf.as_slices();
+    println!(
+        "before gap: {:?}, after gap: {:?}, capacity: {}",
+        before.iter().collect::<String>(),
+        after.iter().collect::<String>(),
+        buf.capacity()
+    );
+}
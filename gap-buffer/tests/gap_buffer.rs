@@ -0,0 +1,244 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gap_buffer::GapBuffer;
+use proptest::prelude::*;
+
+fn contents<T: Clone>(buf: &GapBuffer<T>) -> Vec<T> {
+    buf.iter().cloned().collect()
+}
+
+#[test]
+fn insert_and_move_position() {
+    let mut buf = GapBuffer::new();
+    buf.insert_iter("Lord of the Rings".chars());
+    buf.set_position(12);
+    buf.insert_iter("Onion ".chars());
+    assert_eq!(buf.to_string(), "Lord of the Onion Rings");
+    assert_eq!(buf.position(), 18);
+    assert_eq!(buf.len(), 23);
+}
+
+#[test]
+fn get_skips_the_gap() {
+    let mut buf: GapBuffer<i32> = (0..10).collect();
+    buf.set_position(3);
+    for i in 0..10 {
+        assert_eq!(buf.get(i as usize), Some(&i));
+    }
+    assert_eq!(buf.get(10), None);
+    *buf.get_mut(4).unwrap() = 40;
+    assert_eq!(contents(&buf), [0, 1, 2, 3, 40, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn remove_after_and_before_position() {
+    let mut buf: GapBuffer<char> = "abcd".chars().collect();
+    buf.set_position(2);
+    assert_eq!(buf.remove(), Some('c'));
+    assert_eq!(buf.remove_before(), Some('b'));
+    assert_eq!(buf.position(), 1);
+    assert_eq!(buf.to_string(), "ad");
+    buf.set_position(2);
+    assert_eq!(buf.remove(), None);
+    buf.set_position(0);
+    assert_eq!(buf.remove_before(), None);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn set_position_past_end_panics() {
+    let mut buf: GapBuffer<u8> = GapBuffer::new();
+    buf.insert(1);
+    buf.set_position(2);
+}
+
+#[test]
+fn iterators_in_both_directions() {
+    let mut buf: GapBuffer<u32> = (1..=6).collect();
+    buf.set_position(2);
+    let iter = buf.iter();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(
+        buf.iter().rev().copied().collect::<Vec<_>>(),
+        [6, 5, 4, 3, 2, 1]
+    );
+
+    let mut iter = buf.iter();
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next_back(), Some(&6));
+    assert_eq!(iter.size_hint(), (4, Some(4)));
+    assert_eq!(iter.copied().collect::<Vec<_>>(), [2, 3, 4, 5]);
+
+    let (before, after) = buf.as_slices();
+    assert_eq!((before, after), (&[1, 2][..], &[3, 4, 5, 6][..]));
+
+    let mut sum = 0;
+    for x in &buf {
+        sum += x;
+    }
+    assert_eq!(sum, 21);
+}
+
+#[test]
+fn owning_iterator_moves_elements_out() {
+    let mut buf: GapBuffer<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    buf.set_position(1);
+    let mut iter = buf.into_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next().as_deref(), Some("a"));
+    // Dropping a partly consumed iterator drops the rest.
+    drop(iter);
+}
+
+#[test]
+fn clone_eq_and_debug() {
+    let mut buf: GapBuffer<i32> = vec![1, 2, 3].into_iter().collect();
+    buf.set_position(1);
+    let copy = buf.clone();
+    assert_eq!(copy.position(), 1);
+    assert_eq!(copy, buf);
+
+    let mut other: GapBuffer<i32> = vec![1, 2, 3].into_iter().collect();
+    other.set_position(3);
+    assert_eq!(other, buf, "gap position doesn't affect equality");
+    assert_eq!(format!("{:?}", buf), "[1, 2, 3]");
+}
+
+#[test]
+fn extend_inserts_at_position() {
+    let mut buf: GapBuffer<char> = "ad".chars().collect();
+    buf.set_position(1);
+    buf.extend("bc".chars());
+    assert_eq!(buf.to_string(), "abcd");
+}
+
+#[test]
+fn zero_sized_elements() {
+    let mut buf = GapBuffer::new();
+    assert!(buf.is_empty());
+    for _ in 0..5 {
+        buf.insert(());
+    }
+    buf.set_position(2);
+    assert_eq!(buf.len(), 5);
+    assert_eq!(buf.remove(), Some(()));
+    assert_eq!(buf.len(), 4);
+    assert_eq!(buf.iter().count(), 4);
+}
+
+/// Counts how many times values have been dropped.
+#[derive(Clone)]
+struct DropCounter(Rc<Cell<usize>>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn drop_drops_every_element_exactly_once() {
+    let drops = Rc::new(Cell::new(0));
+    {
+        let mut buf = GapBuffer::new();
+        for _ in 0..20 {
+            buf.insert(DropCounter(drops.clone()));
+        }
+        // Move the gap into the middle so elements sit on both sides of it,
+        // and force a few reallocations along the way.
+        buf.set_position(7);
+        for _ in 0..20 {
+            buf.insert(DropCounter(drops.clone()));
+        }
+        drop(buf.remove());
+        drop(buf.remove_before());
+        assert_eq!(drops.get(), 2);
+    }
+    assert_eq!(drops.get(), 40);
+}
+
+#[test]
+fn partially_consumed_into_iter_drops_the_rest() {
+    let drops = Rc::new(Cell::new(0));
+    let mut buf = GapBuffer::new();
+    for _ in 0..10 {
+        buf.insert(DropCounter(drops.clone()));
+    }
+    buf.set_position(5);
+    let mut iter = buf.into_iter();
+    drop(iter.next());
+    drop(iter.next());
+    assert_eq!(drops.get(), 2);
+    drop(iter);
+    assert_eq!(drops.get(), 10);
+}
+
+/// Operations applied both to a `GapBuffer` and to a `Vec` + cursor model.
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u16),
+    Remove,
+    RemoveBefore,
+    SetPosition(usize),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => any::<u16>().prop_map(Op::Insert),
+        1 => Just(Op::Remove),
+        1 => Just(Op::RemoveBefore),
+        1 => any::<usize>().prop_map(Op::SetPosition),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(if cfg!(miri) { 4 } else { 256 }))]
+
+    #[test]
+    fn behaves_like_a_vec_with_a_cursor(ops in prop::collection::vec(op(), 0..200)) {
+        let mut buf = GapBuffer::new();
+        let mut model: Vec<String> = Vec::new();
+        let mut cursor = 0;
+
+        for op in ops {
+            match op {
+                Op::Insert(n) => {
+                    // Use a heap-allocated type so leaks and double drops
+                    // would show up under Miri.
+                    buf.insert(n.to_string());
+                    model.insert(cursor, n.to_string());
+                    cursor += 1;
+                }
+                Op::Remove => {
+                    let expected = (cursor < model.len()).then(|| model.remove(cursor));
+                    prop_assert_eq!(buf.remove(), expected);
+                }
+                Op::RemoveBefore => {
+                    let expected = (cursor > 0).then(|| {
+                        cursor -= 1;
+                        model.remove(cursor)
+                    });
+                    prop_assert_eq!(buf.remove_before(), expected);
+                }
+                Op::SetPosition(pos) => {
+                    let pos = pos % (model.len() + 1);
+                    buf.set_position(pos);
+                    cursor = pos;
+                }
+            }
+
+            prop_assert_eq!(buf.len(), model.len());
+            prop_assert_eq!(buf.position(), cursor);
+            prop_assert!(buf.capacity() >= buf.len());
+        }
+
+        prop_assert_eq!(contents(&buf), model.clone());
+        prop_assert_eq!(buf.iter().rev().cloned().collect::<Vec<_>>(),
+                        model.iter().rev().cloned().collect::<Vec<_>>());
+        for (i, expected) in model.iter().enumerate() {
+            prop_assert_eq!(buf.get(i), Some(expected));
+        }
+        prop_assert_eq!(buf.into_iter().collect::<Vec<_>>(), model);
+    }
+}
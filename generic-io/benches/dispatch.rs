@@ -0,0 +1,75 @@
+// Static versus dynamic dispatch.
+//
+// `write_numbers_generic::<Vec<u8>>` is compiled knowing its writer is a
+// `Vec<u8>`, so each one-byte `write_all` can be inlined down to a push.
+// `write_numbers_dyn` makes every one of those writes an indirect call
+// through the vtable, which also prevents inlining. Against a slower sink,
+// like a `BufWriter<File>`, the difference shrinks into the noise.
+
+use std::io::{self, Write};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use generic_io::{write_numbers_dyn, write_numbers_generic, MeasureDistance, Point};
+
+const COUNTS: [u32; 2] = [1_000, 100_000];
+
+fn write_numbers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write_numbers");
+    for n in COUNTS {
+        group.bench_with_input(BenchmarkId::new("generic", n), &n, |b, &n| {
+            let mut out = Vec::with_capacity(1 << 20);
+            b.iter(|| {
+                out.clear();
+                write_numbers_generic(&mut out, n).unwrap();
+                black_box(out.len())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dyn", n), &n, |b, &n| {
+            let mut out = Vec::with_capacity(1 << 20);
+            b.iter(|| {
+                out.clear();
+                let sink: &mut dyn Write = &mut out;
+                write_numbers_dyn(sink, n).unwrap();
+                black_box(out.len())
+            })
+        });
+        // For comparison: a sink that does nothing, so all that's left is
+        // the cost of the calls.
+        group.bench_with_input(BenchmarkId::new("dyn-sink", n), &n, |b, &n| {
+            b.iter(|| write_numbers_dyn(&mut io::sink(), n).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// The same sum of distances, with the distance function chosen at compile
+/// time versus behind a `&dyn Fn`.
+fn path_length(c: &mut Criterion) {
+    let points: Vec<Point> = (0..10_000)
+        .map(|i| {
+            let t = f64::from(i) * 0.01;
+            Point {
+                x: t.cos() * t,
+                y: t.sin() * t,
+            }
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("path_length");
+    group.bench_function("generic", |b| {
+        b.iter(|| Point::path_length(black_box(&points)))
+    });
+    group.bench_function("dyn", |b| {
+        let distance: &dyn Fn(&Point, &Point) -> f64 = &|a, b| a.distance_to(b);
+        b.iter(|| {
+            black_box(&points)
+                .windows(2)
+                .map(|pair| distance(&pair[0], &pair[1]))
+                .sum::<f64>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, write_numbers, path_length);
+criterion_main!(benches);
@@ -0,0 +1,81 @@
+// A trait with one required method and several default methods built on
+// it. Implementors write `distance_to`, and get the rest for free.
+
+/// Types with a notion of distance between two values.
+pub trait MeasureDistance {
+    /// The distance from `self` to `other`. Should be non-negative and
+    /// symmetric.
+    fn distance_to(&self, other: &Self) -> f64;
+
+    /// True if `other` is no more than `radius` away.
+    fn is_within(&self, other: &Self, radius: f64) -> bool {
+        self.distance_to(other) <= radius
+    }
+
+    /// The closest of `candidates`, or `None` if there are none.
+    fn nearest<'a>(&self, candidates: &'a [Self]) -> Option<&'a Self>
+    where
+        Self: Sized,
+    {
+        candidates
+            .iter()
+            .min_by(|a, b| self.distance_to(a).total_cmp(&self.distance_to(b)))
+    }
+
+    /// The total length of the path visiting `points` in order.
+    ///
+    /// An associated function with no `self`: call it as
+    /// `Point::path_length(&points)`.
+    fn path_length(points: &[Self]) -> f64
+    where
+        Self: Sized,
+    {
+        points
+            .windows(2)
+            .map(|pair| pair[0].distance_to(&pair[1]))
+            .sum()
+    }
+}
+
+/// A point in the plane.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl MeasureDistance for Point {
+    fn distance_to(&self, other: &Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// A position on the Earth's surface, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// The Earth's mean radius, in kilometers.
+    pub const EARTH_RADIUS_KM: f64 = 6371.0;
+}
+
+impl MeasureDistance for GeoPoint {
+    /// Great-circle distance in kilometers, by the haversine formula.
+    fn distance_to(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.longitude - self.longitude).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * Self::EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+    }
+}
+
+/// Numbers are one-dimensional points.
+impl MeasureDistance for f64 {
+    fn distance_to(&self, other: &f64) -> f64 {
+        (self - other).abs()
+    }
+}
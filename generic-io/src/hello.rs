@@ -0,0 +1,112 @@
+// Writing to "any sink" two ways.
+//
+// `say_hello` takes a trait object: one compiled function that calls
+// `write_all` through a vtable, whatever the writer is. `say_hello_generic`
+// takes a type parameter: the compiler stamps out a copy for each writer
+// type it's used with, and each copy calls that type's `write_all`
+// directly, where it can be inlined.
+
+use std::io::{self, Write};
+
+/// Write a greeting to any writer, through a trait object.
+pub fn say_hello(out: &mut dyn Write) -> io::Result<()> {
+    out.write_all(b"hello world\n")?;
+    out.flush()
+}
+
+/// The same thing, generically. `?Sized` lets `W` itself be `dyn Write`, so
+/// callers holding a trait object can still use this function.
+pub fn say_hello_generic<W: Write + ?Sized>(out: &mut W) -> io::Result<()> {
+    out.write_all(b"hello world\n")?;
+    out.flush()
+}
+
+/// Taking ownership of a boxed writer. A `Box<dyn Write>` is how you store
+/// a writer whose type is only known at run time, say stdout or a file
+/// depending on a command-line flag.
+pub fn say_hello_boxed(mut out: Box<dyn Write>) -> io::Result<Box<dyn Write>> {
+    say_hello(&mut out)?;
+    Ok(out)
+}
+
+/// Greet every writer in a collection of different types. This is where
+/// trait objects are the only choice: a `Vec` can't hold a `File`, a
+/// `Vec<u8>`, and `Stdout` unless they're all behind `dyn Write`.
+pub fn say_hello_to_all(outs: &mut [&mut dyn Write]) -> io::Result<()> {
+    for out in outs.iter_mut() {
+        say_hello(&mut **out)?;
+    }
+    Ok(())
+}
+
+/// A writer that copies everything to two other writers.
+///
+/// Generic over both, so a `Tee<Vec<u8>, File>` costs nothing over writing
+/// to each by hand; but since `&mut dyn Write` implements `Write` too,
+/// `Tee<&mut dyn Write, &mut dyn Write>` works when the types vary.
+pub struct Tee<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Write, B: Write> Tee<A, B> {
+    pub fn new(first: A, second: B) -> Tee<A, B> {
+        Tee { first, second }
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Write everything to both, so they never disagree about how much
+        // was written.
+        self.first.write_all(buf)?;
+        self.second.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.first.flush()?;
+        self.second.flush()
+    }
+}
+
+/// Write the numbers `0..n`, one per line, through a trait object. Lots of
+/// tiny writes, so the cost of each call dominates; see
+/// `benches/dispatch.rs`.
+pub fn write_numbers_dyn(out: &mut dyn Write, n: u32) -> io::Result<()> {
+    for i in 0..n {
+        write_decimal(out, i)?;
+    }
+    Ok(())
+}
+
+/// `write_numbers_dyn`, monomorphized.
+pub fn write_numbers_generic<W: Write + ?Sized>(out: &mut W, n: u32) -> io::Result<()> {
+    for i in 0..n {
+        write_decimal(out, i)?;
+    }
+    Ok(())
+}
+
+/// Write `i` and a newline, one byte at a time.
+#[inline(always)]
+fn write_decimal<W: Write + ?Sized>(out: &mut W, mut i: u32) -> io::Result<()> {
+    let mut digits = [0; 10];
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (i % 10) as u8;
+        len += 1;
+        i /= 10;
+        if i == 0 {
+            break;
+        }
+    }
+    for &digit in digits[..len].iter().rev() {
+        out.write_all(&[digit])?;
+    }
+    out.write_all(b"\n")
+}
@@ -0,0 +1,14 @@
+// Traits and generics: the same code written once against trait objects
+// (`&mut dyn Write`) and once against type parameters (`W: Write`), a
+// generic statistics function, and a trait with default methods.
+
+mod distance;
+mod hello;
+mod stats;
+
+pub use distance::{GeoPoint, MeasureDistance, Point};
+pub use hello::{
+    say_hello, say_hello_boxed, say_hello_generic, say_hello_to_all, write_numbers_dyn,
+    write_numbers_generic, Tee,
+};
+pub use stats::{top_ten, write_top_ten};
@@ -0,0 +1,55 @@
+use std::io::{self, Write};
+
+use generic_io::{
+    say_hello, say_hello_generic, say_hello_to_all, write_top_ten, GeoPoint, MeasureDistance, Tee,
+};
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog and the dog sleeps \
+                    while the fox runs over the hill and over the river";
+
+fn main() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    say_hello(&mut stdout)?;
+    say_hello_generic(&mut stdout)?;
+
+    // One call, three different writer types.
+    let mut buffer = Vec::new();
+    let mut sink = io::sink();
+    say_hello_to_all(&mut [&mut stdout, &mut buffer, &mut sink])?;
+    println!("buffer holds {:?}", String::from_utf8_lossy(&buffer));
+
+    // Top ten words, written to stdout and captured at the same time.
+    println!();
+    let words: Vec<&str> = TEXT.split_whitespace().collect();
+    let mut tee = Tee::new(io::stdout(), Vec::new());
+    write_top_ten(&mut tee, &words)?;
+    let (_, captured) = tee.into_inner();
+    println!("({} bytes of report)", captured.len());
+
+    println!();
+    let stops = [
+        GeoPoint {
+            latitude: 51.5074,
+            longitude: -0.1278,
+        }, // London
+        GeoPoint {
+            latitude: 48.8566,
+            longitude: 2.3522,
+        }, // Paris
+        GeoPoint {
+            latitude: 52.5200,
+            longitude: 13.4050,
+        }, // Berlin
+    ];
+    writeln!(
+        stdout,
+        "London -> Paris -> Berlin: {:.0} km",
+        GeoPoint::path_length(&stops)
+    )?;
+    let rome = GeoPoint {
+        latitude: 41.9028,
+        longitude: 12.4964,
+    };
+    writeln!(stdout, "closest to Rome: {:?}", rome.nearest(&stops))?;
+    Ok(())
+}
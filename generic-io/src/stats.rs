@@ -0,0 +1,57 @@
+// A generic statistics function. The bounds say exactly what `top_ten`
+// needs from `T`: `Hash + Eq` to count values in a `HashMap`, and `Debug`
+// to print them.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io::{self, Write};
+
+/// The ten most common values, with their counts, most common first. Values
+/// with equal counts come in the order they first appeared, so the result
+/// is deterministic.
+pub fn top_ten<T: Debug + Hash + Eq>(values: &[T]) -> Vec<(&T, usize)> {
+    // For each distinct value: its count and where it first appeared.
+    let mut counts: HashMap<&T, (usize, usize)> = HashMap::new();
+    for (i, value) in values.iter().enumerate() {
+        counts.entry(value).or_insert((0, i)).0 += 1;
+    }
+
+    let mut ranked: Vec<_> = counts.into_iter().collect();
+    ranked.sort_by_key(|&(_, (count, first))| (std::cmp::Reverse(count), first));
+    ranked
+        .into_iter()
+        .take(10)
+        .map(|(value, (count, _))| (value, count))
+        .collect()
+}
+
+/// Print `top_ten(values)` as a table, one value per line with its count.
+///
+/// Generic in both the values and the sink: `write_top_ten(&mut stdout,
+/// &words)` and `write_top_ten(&mut vec, &ports)` are separate
+/// instantiations.
+pub fn write_top_ten<T, W>(out: &mut W, values: &[T]) -> io::Result<()>
+where
+    T: Debug + Hash + Eq,
+    W: Write + ?Sized,
+{
+    let top = top_ten(values);
+    let width = top
+        .iter()
+        .map(|(value, _)| format!("{:?}", value).len())
+        .max()
+        .unwrap_or(0);
+    for (value, count) in top {
+        // Pad the `Debug` text, not the value: `Debug` impls generally
+        // ignore width.
+        writeln!(
+            out,
+            "{:<width$}  {}",
+            format!("{:?}", value),
+            count,
+            width = width
+        )?;
+    }
+    Ok(())
+}
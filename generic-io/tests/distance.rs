@@ -0,0 +1,84 @@
+use generic_io::{GeoPoint, MeasureDistance, Point};
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance
+}
+
+#[test]
+fn points() {
+    let origin = Point { x: 0.0, y: 0.0 };
+    let p = Point { x: 3.0, y: 4.0 };
+    assert_eq!(origin.distance_to(&p), 5.0);
+    assert_eq!(p.distance_to(&origin), 5.0);
+    assert!(origin.is_within(&p, 5.0));
+    assert!(!origin.is_within(&p, 4.9));
+}
+
+#[test]
+fn default_methods() {
+    let path = [
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 3.0, y: 4.0 },
+        Point { x: 3.0, y: 10.0 },
+    ];
+    assert_eq!(Point::path_length(&path), 11.0);
+    assert_eq!(Point::path_length(&path[..1]), 0.0);
+    assert_eq!(Point::path_length(&[]), 0.0);
+
+    let target = Point { x: 2.0, y: 9.0 };
+    assert_eq!(target.nearest(&path), Some(&path[2]));
+    assert_eq!(target.nearest(&[]), None);
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(2.5.distance_to(&-1.0), 3.5);
+    assert_eq!(f64::path_length(&[0.0, 10.0, 4.0]), 16.0);
+    assert_eq!(7.0.nearest(&[1.0, 6.0, 9.0]), Some(&6.0));
+}
+
+#[test]
+fn great_circle() {
+    let london = GeoPoint {
+        latitude: 51.5074,
+        longitude: -0.1278,
+    };
+    let paris = GeoPoint {
+        latitude: 48.8566,
+        longitude: 2.3522,
+    };
+    assert!(close(london.distance_to(&paris), 344.0, 1.0));
+    assert_eq!(london.distance_to(&london), 0.0);
+
+    // Antipodes are half the Earth's circumference apart.
+    let north = GeoPoint {
+        latitude: 90.0,
+        longitude: 0.0,
+    };
+    let south = GeoPoint {
+        latitude: -90.0,
+        longitude: 0.0,
+    };
+    let half = std::f64::consts::PI * GeoPoint::EARTH_RADIUS_KM;
+    assert!(close(north.distance_to(&south), half, 1e-6));
+}
+
+/// A generic function bounded by the trait works with every implementor.
+fn farthest_pair<T: MeasureDistance>(points: &[T]) -> f64 {
+    let mut best = 0.0_f64;
+    for a in points {
+        for b in points {
+            best = best.max(a.distance_to(b));
+        }
+    }
+    best
+}
+
+#[test]
+fn generic_over_implementors() {
+    assert_eq!(farthest_pair(&[1.0, -4.0, 3.0]), 7.0);
+    assert_eq!(
+        farthest_pair(&[Point { x: 0.0, y: 0.0 }, Point { x: 0.0, y: 2.0 }]),
+        2.0
+    );
+}
@@ -0,0 +1,80 @@
+use std::io::{self, Write};
+
+use generic_io::{
+    say_hello, say_hello_boxed, say_hello_generic, say_hello_to_all, write_numbers_dyn,
+    write_numbers_generic, Tee,
+};
+
+#[test]
+fn dyn_and_generic_agree() {
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    say_hello(&mut a).unwrap();
+    say_hello_generic(&mut b).unwrap();
+    assert_eq!(a, b"hello world\n");
+    assert_eq!(a, b);
+
+    // A trait object can be passed to the generic version too.
+    let mut c = Vec::new();
+    let dynamic: &mut dyn Write = &mut c;
+    say_hello_generic(dynamic).unwrap();
+    assert_eq!(c, a);
+}
+
+#[test]
+fn boxed_writer() {
+    let out: Box<dyn Write> = Box::new(io::Cursor::new(Vec::new()));
+    say_hello_boxed(out).unwrap();
+}
+
+#[test]
+fn heterogeneous_writers() {
+    let mut vec = Vec::new();
+    let mut cursor = io::Cursor::new([0u8; 32]);
+    let mut sink = io::sink();
+    say_hello_to_all(&mut [&mut vec, &mut cursor, &mut sink]).unwrap();
+    assert_eq!(vec, b"hello world\n");
+    assert_eq!(cursor.position(), 12);
+    assert_eq!(&cursor.get_ref()[..12], b"hello world\n");
+}
+
+#[test]
+fn errors_propagate() {
+    // Too small to hold the greeting.
+    let mut small = io::Cursor::new([0u8; 4]);
+    let err = say_hello(&mut small).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn tee_copies_to_both() {
+    let mut tee = Tee::new(Vec::new(), io::Cursor::new(Vec::new()));
+    say_hello_generic(&mut tee).unwrap();
+    write!(tee, "{}", 42).unwrap();
+    let (first, second) = tee.into_inner();
+    assert_eq!(first, b"hello world\n42");
+    assert_eq!(second.into_inner(), first);
+}
+
+#[test]
+fn tee_of_trait_objects() {
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    {
+        let (x, y): (&mut dyn Write, &mut dyn Write) = (&mut a, &mut b);
+        say_hello(&mut Tee::new(x, y)).unwrap();
+    }
+    assert_eq!(a, b);
+    assert_eq!(a, b"hello world\n");
+}
+
+#[test]
+fn numbers() {
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    write_numbers_dyn(&mut a, 12).unwrap();
+    write_numbers_generic(&mut b, 12).unwrap();
+    let expected: String = (0..12).map(|i| format!("{}\n", i)).collect();
+    assert_eq!(String::from_utf8(a).unwrap(), expected);
+    assert_eq!(String::from_utf8(b).unwrap(), expected);
+}
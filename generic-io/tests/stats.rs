@@ -0,0 +1,56 @@
+use generic_io::{top_ten, write_top_ten};
+
+#[test]
+fn counts_and_order() {
+    let words = "b a c a b a d".split(' ').collect::<Vec<_>>();
+    assert_eq!(
+        top_ten(&words),
+        vec![(&"a", 3), (&"b", 2), (&"c", 1), (&"d", 1)]
+    );
+}
+
+#[test]
+fn at_most_ten() {
+    let values: Vec<u32> = (0..100)
+        .flat_map(|i| std::iter::repeat_n(i, i as usize % 7 + 1))
+        .collect();
+    let top = top_ten(&values);
+    assert_eq!(top.len(), 10);
+    assert!(top.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    // 6, 13, 20, ... each appear seven times; ties keep first-seen order.
+    assert_eq!(top[0], (&6, 7));
+    assert_eq!(top[1], (&13, 7));
+}
+
+#[test]
+fn empty() {
+    let none: [char; 0] = [];
+    assert!(top_ten(&none).is_empty());
+}
+
+#[test]
+fn any_hashable_type() {
+    #[derive(Debug, Hash, PartialEq, Eq)]
+    enum Status {
+        Ok,
+        NotFound,
+    }
+    let log = [Status::Ok, Status::NotFound, Status::Ok];
+    assert_eq!(
+        top_ten(&log),
+        vec![(&Status::Ok, 2), (&Status::NotFound, 1)]
+    );
+
+    let pairs = [(1, 'a'), (2, 'b'), (1, 'a')];
+    assert_eq!(top_ten(&pairs)[0], (&(1, 'a'), 2));
+}
+
+#[test]
+fn report() {
+    let mut out = Vec::new();
+    write_top_ten(&mut out, &["apple", "fig", "apple"]).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "\"apple\"  2\n\"fig\"    1\n"
+    );
+}
@@ -0,0 +1,33 @@
+// Tell Cargo how to link libgit2.
+//
+// With the default `vendored` feature, the libgit2-sys crate builds the
+// library from source and emits the link directives itself, so there is
+// nothing to do here. Otherwise we link against an installed libgit2: set
+// LIBGIT2_LIB_DIR to the directory containing it, or let pkg-config find it.
+
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=LIBGIT2_LIB_DIR");
+
+    if env::var_os("CARGO_FEATURE_VENDORED").is_some() {
+        return;
+    }
+
+    if let Some(dir) = env::var_os("LIBGIT2_LIB_DIR") {
+        println!("cargo:rustc-link-search=native={}", dir.to_string_lossy());
+        println!("cargo:rustc-link-lib=dylib=git2");
+        return;
+    }
+
+    if let Err(e) = pkg_config::Config::new()
+        .atleast_version("1.0.0")
+        .probe("libgit2")
+    {
+        panic!(
+            "couldn't find libgit2 ({}); install it, set LIBGIT2_LIB_DIR, \
+             or enable the `vendored` feature",
+            e
+        );
+    }
+}
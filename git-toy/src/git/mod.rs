@@ -0,0 +1,367 @@
+// A safe interface to the raw libgit2 bindings.
+//
+// Every pointer libgit2 hands us is owned by exactly one Rust value, freed in
+// that value's `Drop` impl, and borrowed values carry lifetimes tying them to
+// their owner, so the compiler rules out use-after-free and double frees.
+
+use std::error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::{c_char, c_int};
+use std::path::Path;
+use std::ptr;
+use std::result;
+use std::sync::Once;
+
+use crate::raw;
+
+/// The category of a libgit2 error, from `git_error_t` in `errors.h`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    None,
+    NoMemory,
+    Os,
+    Invalid,
+    Reference,
+    Zlib,
+    Repository,
+    Config,
+    Regex,
+    Odb,
+    Index,
+    Object,
+    Net,
+    Tag,
+    Tree,
+    Indexer,
+    Ssl,
+    Submodule,
+    Thread,
+    Stash,
+    Checkout,
+    FetchHead,
+    Merge,
+    Ssh,
+    Filter,
+    Revert,
+    Callback,
+    CherryPick,
+    Describe,
+    Rebase,
+    Filesystem,
+    Patch,
+    Worktree,
+    Sha,
+    Http,
+    Internal,
+    Grafts,
+    /// A class this wrapper doesn't know about, from a newer libgit2.
+    Other(c_int),
+}
+
+impl ErrorClass {
+    fn from_raw(klass: c_int) -> ErrorClass {
+        use ErrorClass::*;
+        const CLASSES: [ErrorClass; 37] = [
+            None, NoMemory, Os, Invalid, Reference, Zlib, Repository, Config, Regex, Odb, Index,
+            Object, Net, Tag, Tree, Indexer, Ssl, Submodule, Thread, Stash, Checkout, FetchHead,
+            Merge, Ssh, Filter, Revert, Callback, CherryPick, Describe, Rebase, Filesystem, Patch,
+            Worktree, Sha, Http, Internal, Grafts,
+        ];
+        usize::try_from(klass)
+            .ok()
+            .and_then(|i| CLASSES.get(i).copied())
+            .unwrap_or(Other(klass))
+    }
+}
+
+/// The return code libgit2 uses for "the thing you asked for doesn't exist".
+pub const GIT_ENOTFOUND: c_int = -3;
+
+#[derive(Debug)]
+pub struct Error {
+    code: i32,
+    message: String,
+    class: ErrorClass,
+}
+
+impl Error {
+    /// The negative status code returned by the failing libgit2 call, or -1
+    /// for errors detected on the Rust side.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        self.class
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.code == GIT_ENOTFOUND
+    }
+
+    fn invalid(message: &str) -> Error {
+        Error {
+            code: -1,
+            message: message.to_string(),
+            class: ErrorClass::Invalid,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Displaying an `Error` simply displays the message from libgit2.
+        self.message.fmt(f)
+    }
+}
+
+impl error::Error for Error {}
+
+pub type Result<T> = result::Result<T, Error>;
+
+fn check(code: c_int) -> Result<c_int> {
+    if code >= 0 {
+        return Ok(code);
+    }
+
+    unsafe {
+        let error = raw::git_error_last();
+
+        // libgit2 ensures that (*error).message is always non-null and null
+        // terminated, so this call is safe. Older versions could return a
+        // null error pointer, so guard against that too.
+        let (message, class) = if error.is_null() {
+            ("unknown libgit2 error".to_string(), ErrorClass::None)
+        } else {
+            (
+                CStr::from_ptr((*error).message)
+                    .to_string_lossy()
+                    .into_owned(),
+                ErrorClass::from_raw((*error).klass),
+            )
+        };
+
+        Err(Error {
+            code,
+            message,
+            class,
+        })
+    }
+}
+
+/// Initialize libgit2 the first time any repository is opened, and arrange
+/// for it to be shut down when the process exits. Everything that calls into
+/// libgit2 goes through a `Repository`, so this is the only entry point that
+/// needs the check.
+fn ensure_initialized() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| unsafe {
+        check(raw::git_libgit2_init()).expect("initializing libgit2 failed");
+        assert_eq!(libc::atexit(shutdown), 0);
+    });
+}
+
+extern "C" fn shutdown() {
+    unsafe {
+        if let Err(e) = check(raw::git_libgit2_shutdown()) {
+            eprintln!("shutting down libgit2 failed: {}", e);
+            std::process::abort();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn path_to_cstring(path: &Path) -> Result<CString> {
+    // The `as_bytes` method exists only on Unix-like systems.
+    use std::os::unix::ffi::OsStrExt;
+
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| Error::invalid("path contains null characters"))
+}
+
+#[cfg(windows)]
+fn path_to_cstring(path: &Path) -> Result<CString> {
+    // Try to convert to UTF-8. If this fails, libgit2 can't handle the path
+    // anyway.
+    match path.to_str() {
+        Some(s) => CString::new(s).map_err(|_| Error::invalid("path contains null characters")),
+        None => Err(Error::invalid("couldn't convert path to UTF-8")),
+    }
+}
+
+/// Try to borrow a `&str` from `ptr`, given that `ptr` may be null or refer
+/// to ill-formed UTF-8. Give the result a lifetime as if it were borrowed
+/// from `_owner`.
+///
+/// Safety: if `ptr` is non-null, it must point to a null-terminated C string
+/// that is safe to access for at least as long as the lifetime of `_owner`.
+unsafe fn char_ptr_to_str<T>(_owner: &T, ptr: *const c_char) -> Option<&str> {
+    if ptr.is_null() {
+        None
+    } else {
+        CStr::from_ptr(ptr).to_str().ok()
+    }
+}
+
+/// A Git repository.
+pub struct Repository {
+    // This must always be a pointer to a live `git_repository` structure.
+    // No other `Repository` may point to it.
+    raw: *mut raw::git_repository,
+}
+
+impl Repository {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Repository> {
+        ensure_initialized();
+
+        let path = path_to_cstring(path.as_ref())?;
+        let mut repo = ptr::null_mut();
+        unsafe {
+            check(raw::git_repository_open(&mut repo, path.as_ptr()))?;
+        }
+
+        Ok(Repository { raw: repo })
+    }
+
+    /// Resolve a reference such as `HEAD` or `refs/heads/main` to the id of
+    /// the object it ultimately points at.
+    pub fn reference_name_to_id(&self, name: &str) -> Result<Oid> {
+        let name =
+            CString::new(name).map_err(|_| Error::invalid("name contains null characters"))?;
+        unsafe {
+            let oid = {
+                let mut oid = mem::MaybeUninit::uninit();
+                check(raw::git_reference_name_to_id(
+                    oid.as_mut_ptr(),
+                    self.raw,
+                    name.as_ptr(),
+                ))?;
+                oid.assume_init()
+            };
+            Ok(Oid { raw: oid })
+        }
+    }
+
+    pub fn find_commit(&self, oid: &Oid) -> Result<Commit<'_>> {
+        let mut commit = ptr::null_mut();
+        unsafe {
+            check(raw::git_commit_lookup(&mut commit, self.raw, &oid.raw))?;
+        }
+        Ok(Commit {
+            raw: commit,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl Drop for Repository {
+    fn drop(&mut self) {
+        unsafe {
+            raw::git_repository_free(self.raw);
+        }
+    }
+}
+
+/// The identifier of an object in a repository's object database: the SHA-1
+/// hash of its contents.
+#[derive(Clone, Copy)]
+pub struct Oid {
+    raw: raw::git_oid,
+}
+
+impl Oid {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.raw.id
+    }
+}
+
+impl PartialEq for Oid {
+    fn eq(&self, other: &Oid) -> bool {
+        self.raw.id == other.raw.id
+    }
+}
+
+impl Eq for Oid {}
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.raw.id {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Oid({})", self)
+    }
+}
+
+/// A commit, borrowed from the `Repository` it was found in. libgit2 requires
+/// the repository to outlive its commits, which the `'repo` lifetime enforces.
+pub struct Commit<'repo> {
+    // This must always be a pointer to a usable `git_commit` structure.
+    raw: *mut raw::git_commit,
+    _marker: PhantomData<&'repo Repository>,
+}
+
+impl Commit<'_> {
+    pub fn author(&self) -> Signature<'_> {
+        unsafe {
+            Signature {
+                raw: raw::git_commit_author(self.raw),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// The full commit message, or `None` if it isn't valid UTF-8.
+    pub fn message(&self) -> Option<&str> {
+        unsafe {
+            let message = raw::git_commit_message(self.raw);
+            char_ptr_to_str(self, message)
+        }
+    }
+}
+
+impl Drop for Commit<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            raw::git_commit_free(self.raw);
+        }
+    }
+}
+
+/// A commit's author or committer. The strings live inside the commit, so a
+/// `Signature` can't outlive the `Commit` it came from.
+pub struct Signature<'text> {
+    raw: *const raw::git_signature,
+    _marker: PhantomData<&'text str>,
+}
+
+impl Signature<'_> {
+    /// Return the author's name as a `&str`, or `None` if it is not
+    /// well-formed UTF-8.
+    pub fn name(&self) -> Option<&str> {
+        unsafe { char_ptr_to_str(self, (*self.raw).name) }
+    }
+
+    /// Return the author's email as a `&str`, or `None` if it is not
+    /// well-formed UTF-8.
+    pub fn email(&self) -> Option<&str> {
+        unsafe { char_ptr_to_str(self, (*self.raw).email) }
+    }
+
+    /// Seconds since the Unix epoch at which the signature was made.
+    pub fn time(&self) -> i64 {
+        unsafe { (*self.raw).when.time }
+    }
+}
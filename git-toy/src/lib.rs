@@ -0,0 +1,10 @@
+// The FFI chapter's libgit2 bindings: `raw` declares the C interface as-is,
+// and `git` wraps it in a safe API whose types enforce libgit2's ownership
+// and lifetime rules.
+
+pub mod git;
+pub mod raw;
+
+// Link the vendored copy of libgit2 when the feature is enabled.
+#[cfg(feature = "vendored")]
+use libgit2_sys as _;
@@ -0,0 +1,26 @@
+// Print the author and message of a repository's HEAD commit, this time
+// through the safe `git` wrapper rather than the raw bindings.
+
+use git_toy::git;
+
+fn main() {
+    let path = std::env::args_os().nth(1).expect("usage: git-toy PATH");
+
+    let repo = git::Repository::open(&path).expect("opening repository");
+
+    let commit_oid = repo
+        .reference_name_to_id("HEAD")
+        .expect("looking up 'HEAD' reference");
+
+    let commit = repo.find_commit(&commit_oid).expect("looking up commit");
+
+    let author = commit.author();
+    println!("commit {}", commit_oid);
+    println!(
+        "{} <{}>\n",
+        author.name().unwrap_or("(none)"),
+        author.email().unwrap_or("none")
+    );
+
+    println!("{}", commit.message().unwrap_or("(none)"));
+}
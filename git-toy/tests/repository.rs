@@ -0,0 +1,124 @@
+use std::path::Path;
+use std::process::Command;
+
+use git_toy::git::{ErrorClass, Repository};
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("HOME", dir)
+        .output()
+        .expect("running git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Create a repository with a single commit and return it with the commit's
+/// hex id.
+fn repo_with_commit(message: &str) -> (TempDir, String) {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "--quiet"]);
+    std::fs::write(dir.path().join("README"), "hello\n").unwrap();
+    git(dir.path(), &["add", "README"]);
+    git(
+        dir.path(),
+        &[
+            "-c",
+            "user.name=Ferris Crab",
+            "-c",
+            "user.email=ferris@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            message,
+        ],
+    );
+    let head = git(dir.path(), &["rev-parse", "HEAD"]).trim().to_string();
+    (dir, head)
+}
+
+#[test]
+fn head_commit_author_and_message() {
+    let (dir, head) = repo_with_commit("Initial commit\n\nWith a body.");
+    let repo = Repository::open(dir.path()).unwrap();
+
+    let oid = repo.reference_name_to_id("HEAD").unwrap();
+    assert_eq!(oid.to_string(), head);
+    assert_eq!(oid.as_bytes().len(), 20);
+
+    let commit = repo.find_commit(&oid).unwrap();
+    let author = commit.author();
+    assert_eq!(author.name(), Some("Ferris Crab"));
+    assert_eq!(author.email(), Some("ferris@example.com"));
+    assert!(author.time() > 0);
+    assert_eq!(commit.message(), Some("Initial commit\n\nWith a body.\n"));
+}
+
+#[test]
+fn same_reference_resolves_to_equal_oids() {
+    let (dir, _) = repo_with_commit("one");
+    let repo = Repository::open(dir.path()).unwrap();
+    let branch = git(dir.path(), &["symbolic-ref", "HEAD"]);
+    assert_eq!(
+        repo.reference_name_to_id("HEAD").unwrap(),
+        repo.reference_name_to_id(branch.trim()).unwrap()
+    );
+}
+
+#[test]
+fn opening_a_non_repository_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let error = match Repository::open(dir.path().join("missing")) {
+        Ok(_) => panic!("opened a repository that doesn't exist"),
+        Err(e) => e,
+    };
+    assert!(error.is_not_found());
+    assert!(!error.message().is_empty());
+    assert_eq!(error.to_string(), error.message());
+}
+
+#[test]
+fn missing_reference_reports_reference_class() {
+    let (dir, _) = repo_with_commit("one");
+    let repo = Repository::open(dir.path()).unwrap();
+    let error = repo.reference_name_to_id("refs/heads/nope").unwrap_err();
+    assert!(error.is_not_found());
+    assert_eq!(error.class(), ErrorClass::Reference);
+}
+
+#[test]
+fn interior_nul_is_rejected_before_calling_libgit2() {
+    let (dir, _) = repo_with_commit("one");
+    let repo = Repository::open(dir.path()).unwrap();
+    let error = repo.reference_name_to_id("HE\0AD").unwrap_err();
+    assert_eq!(error.class(), ErrorClass::Invalid);
+    assert_eq!(error.code(), -1);
+}
+
+#[test]
+fn repositories_can_be_used_from_several_threads() {
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let message = format!("commit {}", i);
+                let (dir, head) = repo_with_commit(&message);
+                let repo = Repository::open(dir.path()).unwrap();
+                let oid = repo.reference_name_to_id("HEAD").unwrap();
+                assert_eq!(oid.to_string(), head);
+                let commit = repo.find_commit(&oid).unwrap();
+                assert_eq!(commit.message().unwrap().trim_end(), message);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+// The book's partial-ordering example: half-open intervals, where one
+// interval is less than another only if it lies entirely before it.
+// Overlapping intervals are unordered.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+/// The half-open interval `lower..upper`: it contains every value `x` with
+/// `lower <= x < upper`. An interval whose bounds are equal is empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub lower: T, // inclusive
+    pub upper: T, // exclusive
+}
+
+impl<T: PartialOrd> Interval<T> {
+    /// Return the interval `lower..upper`, or `None` if `upper` is below
+    /// `lower` (or the bounds can't be compared at all, like NaN).
+    pub fn new(lower: T, upper: T) -> Option<Interval<T>> {
+        if lower <= upper {
+            Some(Interval { lower, upper })
+        } else {
+            None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lower >= self.upper
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.lower <= *value && *value < self.upper
+    }
+
+    /// True if the intervals share at least one value.
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.lower < other.upper
+            && other.lower < self.upper
+    }
+}
+
+impl<T: PartialOrd + Copy> Interval<T> {
+    /// The values in both intervals, or `None` if there are none.
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        let lower = max(self.lower, other.lower);
+        let upper = min(self.upper, other.upper);
+        Some(Interval { lower, upper })
+    }
+
+    /// The values in either interval, as a single interval. Returns `None` if
+    /// there is a gap between them, since the union then isn't an interval.
+    /// Empty intervals contribute nothing.
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if self.is_empty() {
+            return Some(*other);
+        }
+        if other.is_empty() {
+            return Some(*self);
+        }
+        // Touching intervals, like 0..2 and 2..4, have no gap between them.
+        if self.upper < other.lower || other.upper < self.lower {
+            return None;
+        }
+        Some(Interval {
+            lower: min(self.lower, other.lower),
+            upper: max(self.upper, other.upper),
+        })
+    }
+
+    /// The smallest interval containing both, gaps and all.
+    pub fn hull(&self, other: &Interval<T>) -> Interval<T> {
+        Interval {
+            lower: min(self.lower, other.lower),
+            upper: max(self.upper, other.upper),
+        }
+    }
+}
+
+// `std::cmp::{min, max}` require `Ord`, which floating-point bounds lack.
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+impl<T: PartialOrd> PartialOrd<Interval<T>> for Interval<T> {
+    fn partial_cmp(&self, other: &Interval<T>) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.lower >= other.upper {
+            Some(Ordering::Greater)
+        } else if self.upper <= other.lower {
+            Some(Ordering::Less)
+        } else {
+            None
+        }
+    }
+}
+
+/// Shift an interval by an offset: `(10..20) + 5` is `15..25`.
+impl<T: Add<Output = T> + Copy> Add<T> for Interval<T> {
+    type Output = Interval<T>;
+
+    fn add(self, offset: T) -> Interval<T> {
+        Interval {
+            lower: self.lower + offset,
+            upper: self.upper + offset,
+        }
+    }
+}
+
+/// Shift an interval down by an offset.
+impl<T: Sub<Output = T> + Copy> Sub<T> for Interval<T> {
+    type Output = Interval<T>;
+
+    fn sub(self, offset: T) -> Interval<T> {
+        Interval {
+            lower: self.lower - offset,
+            upper: self.upper - offset,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {})", self.lower, self.upper)
+    }
+}
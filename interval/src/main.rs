@@ -0,0 +1,40 @@
+use interval::Interval;
+
+fn main() {
+    let a = Interval {
+        lower: 10,
+        upper: 20,
+    };
+    let b = Interval {
+        lower: 20,
+        upper: 40,
+    };
+    let c = Interval {
+        lower: 15,
+        upper: 25,
+    };
+
+    println!("{} < {}: {}", a, b, a < b);
+    println!("{} >= {}: {}", b, a, b >= a);
+    println!("{} vs {}: {:?}", a, c, a.partial_cmp(&c));
+    println!("{} < {}: {}, {} > {}: {}", a, c, a < c, a, c, a > c);
+
+    println!("{} ∩ {} = {:?}", a, c, a.intersection(&c));
+    println!("{} ∪ {} = {:?}", a, b, a.union(&b));
+    println!("{} + 5 = {}", a, a + 5);
+
+    let mut intervals = vec![
+        Interval {
+            lower: 30,
+            upper: 35,
+        },
+        Interval { lower: 0, upper: 5 },
+        Interval {
+            lower: 10,
+            upper: 12,
+        },
+    ];
+    // Disjoint intervals are totally ordered, so sorting them is meaningful.
+    intervals.sort_by(|x, y| x.partial_cmp(y).expect("intervals overlap"));
+    println!("sorted: {:?}", intervals);
+}
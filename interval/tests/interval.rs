@@ -0,0 +1,187 @@
+use std::cmp::Ordering;
+
+use interval::Interval;
+
+fn iv(lower: i32, upper: i32) -> Interval<i32> {
+    Interval::new(lower, upper).unwrap()
+}
+
+/// Every interval with bounds in `0..=4`, including empty ones.
+fn all_intervals() -> Vec<Interval<i32>> {
+    let mut all = Vec::new();
+    for lower in 0..=4 {
+        for upper in lower..=4 {
+            all.push(iv(lower, upper));
+        }
+    }
+    all
+}
+
+#[test]
+// Negated comparisons are exactly what this test is about: for overlapping
+// intervals, `!(a < b)` does not imply `a >= b`.
+#[allow(clippy::neg_cmp_op_on_partial_ord)]
+fn book_examples() {
+    assert!(
+        Interval {
+            lower: 10,
+            upper: 20
+        } < Interval {
+            lower: 20,
+            upper: 40
+        }
+    );
+    assert!(Interval { lower: 7, upper: 8 } >= Interval { lower: 0, upper: 1 });
+    assert!(Interval { lower: 7, upper: 8 } <= Interval { lower: 7, upper: 8 });
+
+    // Overlapping intervals aren't ordered with respect to each other.
+    let left = Interval {
+        lower: 10,
+        upper: 30,
+    };
+    let right = Interval {
+        lower: 20,
+        upper: 40,
+    };
+    assert!(!(left < right));
+    assert!(!(left >= right));
+}
+
+#[test]
+fn new_rejects_inverted_bounds() {
+    assert_eq!(Interval::new(3, 1), None);
+    assert_eq!(Interval::new(1.0, f64::NAN), None);
+    assert!(Interval::new(2, 2).unwrap().is_empty());
+}
+
+#[test]
+fn partial_cmp_matches_its_definition_exhaustively() {
+    for a in all_intervals() {
+        for b in all_intervals() {
+            let expected = if a == b {
+                Some(Ordering::Equal)
+            } else if a.lower >= b.upper {
+                Some(Ordering::Greater)
+            } else if a.upper <= b.lower {
+                Some(Ordering::Less)
+            } else {
+                None
+            };
+            assert_eq!(a.partial_cmp(&b), expected, "{} vs {}", a, b);
+
+            // The comparison operators must agree with partial_cmp.
+            assert_eq!(a < b, expected == Some(Ordering::Less));
+            assert_eq!(a > b, expected == Some(Ordering::Greater));
+            assert_eq!(
+                a <= b,
+                matches!(expected, Some(Ordering::Less | Ordering::Equal))
+            );
+            assert_eq!(
+                a >= b,
+                matches!(expected, Some(Ordering::Greater | Ordering::Equal))
+            );
+        }
+    }
+}
+
+#[test]
+fn non_empty_intervals_are_ordered_exactly_when_disjoint() {
+    for a in all_intervals().into_iter().filter(|i| !i.is_empty()) {
+        for b in all_intervals().into_iter().filter(|i| !i.is_empty()) {
+            let ordered = a.partial_cmp(&b).is_some();
+            assert_eq!(ordered, a == b || !a.overlaps(&b), "{} vs {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn ordering_is_antisymmetric_and_transitive() {
+    let all = all_intervals();
+    for a in &all {
+        for b in &all {
+            // Antisymmetry: a < b exactly when b > a.
+            assert_eq!(a < b, b > a, "{} vs {}", a, b);
+            assert_eq!(a.partial_cmp(b), b.partial_cmp(a).map(Ordering::reverse));
+            for c in &all {
+                if a < b && b < c {
+                    assert!(a < c, "{} < {} < {} but not {} < {}", a, b, c, a, c);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn contains_exhaustively() {
+    for i in all_intervals() {
+        for x in -1..=5 {
+            assert_eq!(i.contains(&x), i.lower <= x && x < i.upper);
+        }
+    }
+}
+
+#[test]
+fn intersection_holds_exactly_the_common_values() {
+    for a in all_intervals() {
+        for b in all_intervals() {
+            let common: Vec<i32> = (0..=4).filter(|x| a.contains(x) && b.contains(x)).collect();
+            match a.intersection(&b) {
+                Some(i) => {
+                    let got: Vec<i32> = (0..=4).filter(|x| i.contains(x)).collect();
+                    assert_eq!(got, common, "{} ∩ {}", a, b);
+                    assert!(!i.is_empty());
+                }
+                None => assert!(common.is_empty(), "{} ∩ {}", a, b),
+            }
+        }
+    }
+}
+
+#[test]
+fn union_holds_exactly_the_values_of_either() {
+    for a in all_intervals() {
+        for b in all_intervals() {
+            let either: Vec<i32> = (0..=4).filter(|x| a.contains(x) || b.contains(x)).collect();
+            match a.union(&b) {
+                Some(u) => {
+                    let got: Vec<i32> = (0..=4).filter(|x| u.contains(x)).collect();
+                    assert_eq!(got, either, "{} ∪ {}", a, b);
+                }
+                None => {
+                    // There must be a gap: some value between the two
+                    // intervals that neither contains.
+                    let hull = a.hull(&b);
+                    assert!((hull.lower..hull.upper).any(|x| !either.contains(&x)));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn offset_operators() {
+    assert_eq!(iv(10, 20) + 5, iv(15, 25));
+    assert_eq!(iv(10, 20) - 10, iv(0, 10));
+    let shifted = Interval {
+        lower: 0.5,
+        upper: 1.5,
+    } + 0.25;
+    assert_eq!(
+        shifted,
+        Interval {
+            lower: 0.75,
+            upper: 1.75
+        }
+    );
+    for a in all_intervals() {
+        for b in all_intervals() {
+            // Shifting both sides by the same amount preserves ordering.
+            assert_eq!(a.partial_cmp(&b), (a + 3).partial_cmp(&(b + 3)));
+        }
+    }
+}
+
+#[test]
+fn display() {
+    assert_eq!(iv(1, 3).to_string(), "[1, 3)");
+}
@@ -0,0 +1,83 @@
+// `Sha256Tee`: pass data through unchanged while computing its SHA-256.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use sha2::{Digest as _, Sha256};
+
+/// A SHA-256 digest. Displays as lowercase hex, like `sha256sum`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Digest(pub [u8; 32]);
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a reader or writer, hashing every byte that passes through.
+///
+/// Only bytes actually transferred are hashed: if the inner writer accepts
+/// part of a buffer, only that part counts.
+pub struct Sha256Tee<T> {
+    inner: T,
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl<T> Sha256Tee<T> {
+    pub fn new(inner: T) -> Self {
+        Sha256Tee {
+            inner,
+            hasher: Sha256::new(),
+            bytes: 0,
+        }
+    }
+
+    /// The number of bytes hashed so far.
+    pub fn byte_count(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Return the wrapped stream and the digest of everything that passed
+    /// through. A writer is not flushed first.
+    pub fn finish(self) -> (T, Digest) {
+        (self.inner, Digest(self.hasher.finalize().into()))
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.bytes += data.len() as u64;
+    }
+}
+
+impl<R: Read> Read for Sha256Tee<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<W: Write> Write for Sha256Tee<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
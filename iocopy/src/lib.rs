@@ -0,0 +1,15 @@
+// I/O adapters that wrap any reader or writer, and a copy pipeline built by
+// stacking them. Each adapter is generic over the stream it wraps and
+// implements `Read` when wrapping a reader and `Write` when wrapping a
+// writer, so they compose freely with each other and with `flate2`'s gzip
+// streams.
+
+pub mod hash;
+pub mod pipeline;
+pub mod progress;
+pub mod throttle;
+
+pub use hash::{Digest, Sha256Tee};
+pub use pipeline::{copy, Codec, Options, Summary};
+pub use progress::Progress;
+pub use throttle::Throttle;
@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use iocopy::{Codec, Options};
+
+#[derive(Debug)]
+struct Arguments {
+    options: Options,
+    sha256: bool,
+    progress: bool,
+    input: Option<String>,
+    output: Option<String>,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            options: Options::default(),
+            sha256: false,
+            progress: false,
+            input: None,
+            output: None,
+        };
+        let mut paths = Vec::new();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-z" | "--compress" => arguments.options.codec = Codec::Compress,
+                "-d" | "--decompress" => arguments.options.codec = Codec::Decompress,
+                "--sha256" => arguments.sha256 = true,
+                "--progress" => arguments.progress = true,
+                "--limit" => {
+                    let rate = args
+                        .next()
+                        .unwrap_or_else(|| usage_error("--limit needs a rate"));
+                    let rate = parse_rate(&rate).unwrap_or_else(|| {
+                        usage_error(&format!(
+                            "bad rate {:?}: expected e.g. 500, 64k, or 2m",
+                            rate
+                        ))
+                    });
+                    arguments.options.bytes_per_second = Some(rate);
+                }
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                "-" => paths.push(arg),
+                _ if arg.starts_with('-') => usage_error(&format!("unknown option {}", arg)),
+                _ => paths.push(arg),
+            }
+        }
+        if paths.len() > 2 {
+            usage_error(&format!("expected at most 2 paths, got {}", paths.len()));
+        }
+        let mut paths = paths
+            .into_iter()
+            .map(|p| if p == "-" { None } else { Some(p) });
+        arguments.input = paths.next().flatten();
+        arguments.output = paths.next().flatten();
+        arguments
+    }
+}
+
+/// Parse a byte rate with an optional `k` or `m` suffix (powers of 1024).
+fn parse_rate(s: &str) -> Option<u64> {
+    let lower = s.to_ascii_lowercase();
+    let (digits, multiplier) = match lower.as_bytes().last()? {
+        b'k' => (&lower[..lower.len() - 1], 1024),
+        b'm' => (&lower[..lower.len() - 1], 1024 * 1024),
+        _ => (&lower[..], 1),
+    };
+    let rate = digits.parse::<u64>().ok()?.checked_mul(multiplier)?;
+    (rate > 0).then_some(rate)
+}
+
+fn print_usage() {
+    eprintln!("iocopy - copy bytes, optionally compressing, hashing, and rate-limiting");
+    eprintln!("Usage: iocopy [options] [input] [output]");
+    eprintln!("Input and output default to stdin and stdout; '-' also means them.");
+    eprintln!("  -z, --compress     gzip the input");
+    eprintln!("  -d, --decompress   gunzip the input");
+    eprintln!("      --sha256       print the SHA-256 of the output to stderr");
+    eprintln!("      --limit RATE   copy at most RATE bytes per second (suffixes k, m)");
+    eprintln!("      --progress     show a running byte count on stderr");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn fail(message: &str, e: io::Error) -> ! {
+    eprintln!("Error: {}: {}", message, e);
+    process::exit(1);
+}
+
+fn main() {
+    let args = Arguments::parse();
+
+    let input: Box<dyn Read> = match &args.input {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(e) => fail(&format!("failed to open '{}'", path), e),
+        },
+        None => Box::new(io::stdin().lock()),
+    };
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => fail(&format!("failed to create '{}'", path), e),
+        },
+        None => {
+            if args.options.codec == Codec::Compress && io::stdout().is_terminal() {
+                eprintln!("Error: refusing to write compressed data to a terminal");
+                process::exit(1);
+            }
+            Box::new(io::stdout().lock())
+        }
+    };
+
+    let mut last_report = Instant::now();
+    let report = |bytes: u64| {
+        if args.progress && last_report.elapsed() >= Duration::from_millis(100) {
+            eprint!("\r{} bytes read", bytes);
+            last_report = Instant::now();
+        }
+    };
+
+    let summary = match iocopy::copy(input, output, &args.options, report) {
+        Ok(summary) => summary,
+        Err(e) => fail("copy failed", e),
+    };
+    if args.progress {
+        eprintln!(
+            "\r{} bytes read, {} bytes written in {:.2?}",
+            summary.bytes_read, summary.bytes_written, summary.elapsed
+        );
+    }
+    if args.sha256 {
+        eprintln!(
+            "{}  {}",
+            summary.sha256,
+            args.output.as_deref().unwrap_or("-")
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// Stack the adapters into a copy pipeline:
+//
+//     input → progress → [gunzip] → [throttle] → io::copy → [gzip] → SHA-256 → output
+//
+// Progress counts bytes as read from the input, and the digest covers bytes
+// as written to the output, so both describe the files on disk.
+
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::hash::{Digest, Sha256Tee};
+use crate::progress::Progress;
+use crate::throttle::Throttle;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    /// Copy the bytes as they are.
+    #[default]
+    Plain,
+    /// Gzip-compress the input.
+    Compress,
+    /// Decompress gzipped input.
+    Decompress,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    pub codec: Codec,
+    /// Limit the rate at which (decompressed) data is copied.
+    pub bytes_per_second: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// The SHA-256 of the bytes written.
+    pub sha256: Digest,
+    pub elapsed: Duration,
+}
+
+/// Copy everything from `input` to `output` according to `options`,
+/// calling `progress` with the total number of bytes read so far.
+pub fn copy<R, W, F>(input: R, output: W, options: &Options, mut progress: F) -> io::Result<Summary>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(u64),
+{
+    let start = Instant::now();
+    let mut bytes_read = 0;
+    let counted = Progress::new(input, |n| {
+        bytes_read = n;
+        progress(n)
+    });
+    let decoded: Box<dyn Read + '_> = match options.codec {
+        Codec::Decompress => Box::new(MultiGzDecoder::new(counted)),
+        Codec::Plain | Codec::Compress => Box::new(counted),
+    };
+    let mut source: Box<dyn Read + '_> = match options.bytes_per_second {
+        Some(rate) => Box::new(Throttle::new(decoded, rate)),
+        None => decoded,
+    };
+
+    let mut hashed = Sha256Tee::new(output);
+    match options.codec {
+        Codec::Compress => {
+            let mut encoder = GzEncoder::new(&mut hashed, Compression::default());
+            io::copy(&mut source, &mut encoder)?;
+            encoder.finish()?;
+        }
+        Codec::Plain | Codec::Decompress => {
+            io::copy(&mut source, &mut hashed)?;
+        }
+    }
+    hashed.flush()?;
+    drop(source);
+
+    let bytes_written = hashed.byte_count();
+    let (_, sha256) = hashed.finish();
+    Ok(Summary {
+        bytes_read,
+        bytes_written,
+        sha256,
+        elapsed: start.elapsed(),
+    })
+}
@@ -0,0 +1,56 @@
+// `Progress`: count the bytes passing through, and report as they go.
+
+use std::io::{self, Read, Write};
+
+/// Wraps a reader or writer, calling `report` with the running total of
+/// bytes transferred after each successful nonempty read or write.
+pub struct Progress<T, F> {
+    inner: T,
+    report: F,
+    bytes: u64,
+}
+
+impl<T, F: FnMut(u64)> Progress<T, F> {
+    pub fn new(inner: T, report: F) -> Self {
+        Progress {
+            inner,
+            report,
+            bytes: 0,
+        }
+    }
+
+    pub fn byte_count(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn advance(&mut self, n: usize) {
+        if n > 0 {
+            self.bytes += n as u64;
+            (self.report)(self.bytes);
+        }
+    }
+}
+
+impl<R: Read, F: FnMut(u64)> Read for Progress<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.advance(n);
+        Ok(n)
+    }
+}
+
+impl<W: Write, F: FnMut(u64)> Write for Progress<W, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.advance(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,70 @@
+// `Throttle`: limit the rate at which data passes through.
+
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Wraps a reader or writer, sleeping as needed to keep the average
+/// transfer rate at or below `bytes_per_second`.
+///
+/// Each read or write is capped at a tenth of a second's worth of data, so
+/// that the stream moves steadily rather than in bursts.
+pub struct Throttle<T> {
+    inner: T,
+    bytes_per_second: u64,
+    /// When the first transfer happened.
+    start: Option<Instant>,
+    bytes: u64,
+}
+
+impl<T> Throttle<T> {
+    /// Panics if `bytes_per_second` is zero.
+    pub fn new(inner: T, bytes_per_second: u64) -> Self {
+        assert!(bytes_per_second > 0, "rate limit must be positive");
+        Throttle {
+            inner,
+            bytes_per_second,
+            start: None,
+            bytes: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Wait until we're allowed to transfer more, then return how many
+    /// bytes of a `wanted`-byte transfer to attempt.
+    fn admit(&mut self, wanted: usize) -> usize {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let due = Duration::from_secs_f64(self.bytes as f64 / self.bytes_per_second as f64);
+        let elapsed = start.elapsed();
+        if due > elapsed {
+            thread::sleep(due - elapsed);
+        }
+        let chunk = (self.bytes_per_second / 10).max(1);
+        wanted.min(usize::try_from(chunk).unwrap_or(usize::MAX))
+    }
+}
+
+impl<R: Read> Read for Throttle<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.admit(buf.len());
+        let n = self.inner.read(&mut buf[..len])?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+}
+
+impl<W: Write> Write for Throttle<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.admit(buf.len());
+        let n = self.inner.write(&buf[..len])?;
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
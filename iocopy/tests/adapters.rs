@@ -0,0 +1,138 @@
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+use iocopy::{Digest, Progress, Sha256Tee, Throttle};
+
+/// A writer that accepts at most `limit` bytes per call, to check that
+/// adapters account for partial writes.
+struct Trickle {
+    data: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.limit);
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn hex(digest: Digest) -> String {
+    digest.to_string()
+}
+
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+#[test]
+fn sha256_of_known_strings() {
+    let (_, digest) = Sha256Tee::new(io::sink()).finish();
+    assert_eq!(hex(digest), EMPTY_SHA256);
+
+    let mut tee = Sha256Tee::new(Vec::new());
+    tee.write_all(b"a").unwrap();
+    tee.write_all(b"bc").unwrap();
+    assert_eq!(tee.byte_count(), 3);
+    let (written, digest) = tee.finish();
+    assert_eq!(written, b"abc");
+    assert_eq!(hex(digest), ABC_SHA256);
+}
+
+#[test]
+fn sha256_tee_on_the_read_side() {
+    let mut tee = Sha256Tee::new(&b"abc"[..]);
+    let mut out = String::new();
+    tee.read_to_string(&mut out).unwrap();
+    assert_eq!(out, "abc");
+    assert_eq!(hex(tee.finish().1), ABC_SHA256);
+}
+
+#[test]
+fn sha256_tee_hashes_only_what_was_written() {
+    let mut tee = Sha256Tee::new(Trickle {
+        data: Vec::new(),
+        limit: 2,
+    });
+    assert_eq!(tee.write(b"abcdef").unwrap(), 2);
+    tee.write_all(b"cdef").unwrap();
+    let (trickle, digest) = tee.finish();
+    assert_eq!(trickle.data, b"abcdef");
+
+    let mut whole = Sha256Tee::new(io::sink());
+    whole.write_all(b"abcdef").unwrap();
+    assert_eq!(digest, whole.finish().1);
+}
+
+#[test]
+fn progress_reports_running_totals() {
+    let reports = RefCell::new(Vec::new());
+    let data = [7u8; 10];
+    let mut reader = Progress::new(&data[..], |n| reports.borrow_mut().push(n));
+    let mut buf = [0; 4];
+    while reader.read(&mut buf).unwrap() > 0 {}
+    assert_eq!(reader.byte_count(), 10);
+    assert_eq!(*reports.borrow(), [4, 8, 10]);
+}
+
+#[test]
+fn progress_on_the_write_side_counts_partial_writes() {
+    let mut reports = Vec::new();
+    let mut writer = Progress::new(
+        Trickle {
+            data: Vec::new(),
+            limit: 3,
+        },
+        |n| reports.push(n),
+    );
+    writer.write_all(b"abcdefgh").unwrap();
+    let trickle = writer.into_inner();
+    assert_eq!(trickle.data, b"abcdefgh");
+    assert_eq!(reports, [3, 6, 8]);
+}
+
+#[test]
+fn throttle_limits_the_rate() {
+    let data: Vec<u8> = (0..=255).cycle().take(40_000).collect();
+    let start = Instant::now();
+    let mut reader = Throttle::new(&data[..], 100_000);
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out).unwrap();
+    let elapsed = start.elapsed();
+    assert_eq!(out, data);
+    // Reads are capped at 10,000 bytes; the last one goes out 0.3s after
+    // the first.
+    assert!(elapsed.as_secs_f64() >= 0.29, "took only {:?}", elapsed);
+    assert!(elapsed.as_secs_f64() < 2.0, "took {:?}", elapsed);
+}
+
+#[test]
+fn throttle_on_the_write_side() {
+    let start = Instant::now();
+    let mut writer = Throttle::new(Vec::new(), 50_000);
+    writer.write_all(&[1; 20_000]).unwrap();
+    assert!(start.elapsed().as_secs_f64() >= 0.29);
+    assert_eq!(writer.into_inner(), vec![1; 20_000]);
+}
+
+#[test]
+#[should_panic(expected = "rate limit must be positive")]
+fn throttle_rejects_zero() {
+    let _ = Throttle::new(io::empty(), 0);
+}
+
+#[test]
+fn adapters_stack() {
+    let mut out = Vec::new();
+    {
+        let counted = Progress::new(&b"abc"[..], |_| {});
+        let mut hashed = Sha256Tee::new(Throttle::new(counted, 1_000_000));
+        io::copy(&mut hashed, &mut out).unwrap();
+        assert_eq!(hex(hashed.finish().1), ABC_SHA256);
+    }
+    assert_eq!(out, b"abc");
+}
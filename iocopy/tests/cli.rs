@@ -0,0 +1,66 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn iocopy() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_iocopy"))
+}
+
+#[test]
+fn files_round_trip_through_gzip() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = dir.path().join("original.txt");
+    let gz = dir.path().join("original.txt.gz");
+    let restored = dir.path().join("restored.txt");
+    fs::write(&original, "all work and no play\n".repeat(1000)).unwrap();
+
+    let status = iocopy().arg("-z").arg(&original).arg(&gz).status().unwrap();
+    assert!(status.success());
+    assert!(fs::metadata(&gz).unwrap().len() < fs::metadata(&original).unwrap().len());
+
+    let output = iocopy()
+        .args(["--decompress", "--sha256", "--progress"])
+        .arg(&gz)
+        .arg(&restored)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(fs::read(&restored).unwrap(), fs::read(&original).unwrap());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains(&format!("  {}", restored.display())),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("21000 bytes written"), "{}", stderr);
+}
+
+#[test]
+fn stdin_to_stdout() {
+    let mut child = iocopy()
+        .arg("--sha256")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"abc").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"abc");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  -\n"
+    );
+}
+
+#[test]
+fn bad_usage() {
+    let output = iocopy().arg("--limit").arg("fast").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("bad rate"));
+
+    let output = iocopy().arg("/nonexistent/input").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to open"));
+}
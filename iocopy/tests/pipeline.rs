@@ -0,0 +1,99 @@
+use std::io::{Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use iocopy::{copy, Codec, Options, Sha256Tee};
+
+fn sample() -> Vec<u8> {
+    "It was a bright cold day in April, and the clocks were striking thirteen.\n"
+        .repeat(500)
+        .into_bytes()
+}
+
+#[test]
+fn plain_copy() {
+    let data = sample();
+    let mut out = Vec::new();
+    let mut reports = 0;
+    let summary = copy(&data[..], &mut out, &Options::default(), |_| reports += 1).unwrap();
+    assert_eq!(out, data);
+    assert_eq!(summary.bytes_read, data.len() as u64);
+    assert_eq!(summary.bytes_written, data.len() as u64);
+    assert!(reports > 0);
+
+    let mut tee = Sha256Tee::new(std::io::sink());
+    tee.write_all(&data).unwrap();
+    assert_eq!(summary.sha256, tee.finish().1);
+}
+
+#[test]
+fn compress_then_decompress() {
+    let data = sample();
+    let compress = Options {
+        codec: Codec::Compress,
+        ..Options::default()
+    };
+    let mut gz = Vec::new();
+    let summary = copy(&data[..], &mut gz, &compress, |_| {}).unwrap();
+    assert_eq!(summary.bytes_read, data.len() as u64);
+    assert_eq!(summary.bytes_written, gz.len() as u64);
+    assert!(gz.len() < data.len() / 10);
+
+    // flate2 agrees it's gzip.
+    let mut check = Vec::new();
+    GzDecoder::new(&gz[..]).read_to_end(&mut check).unwrap();
+    assert_eq!(check, data);
+
+    let decompress = Options {
+        codec: Codec::Decompress,
+        ..Options::default()
+    };
+    let mut round_trip = Vec::new();
+    let summary = copy(&gz[..], &mut round_trip, &decompress, |_| {}).unwrap();
+    assert_eq!(round_trip, data);
+    assert_eq!(summary.bytes_read, gz.len() as u64);
+}
+
+#[test]
+fn decompress_concatenated_members() {
+    let mut gz = Vec::new();
+    for part in ["first ", "second"] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(part.as_bytes()).unwrap();
+        gz.extend(encoder.finish().unwrap());
+    }
+    let options = Options {
+        codec: Codec::Decompress,
+        ..Options::default()
+    };
+    let mut out = Vec::new();
+    copy(&gz[..], &mut out, &options, |_| {}).unwrap();
+    assert_eq!(out, b"first second");
+}
+
+#[test]
+fn decompressing_garbage_fails() {
+    let options = Options {
+        codec: Codec::Decompress,
+        ..Options::default()
+    };
+    assert!(copy(&b"not gzip at all"[..], Vec::new(), &options, |_| {}).is_err());
+}
+
+#[test]
+fn throttled_copy() {
+    let data = vec![0u8; 30_000];
+    let options = Options {
+        bytes_per_second: Some(100_000),
+        ..Options::default()
+    };
+    let mut out = Vec::new();
+    let summary = copy(&data[..], &mut out, &options, |_| {}).unwrap();
+    assert_eq!(out, data);
+    assert!(
+        summary.elapsed.as_secs_f64() >= 0.19,
+        "{:?}",
+        summary.elapsed
+    );
+}
@@ -0,0 +1,89 @@
+// `chunks_exact_owned`: like the slice method `chunks_exact`, but for any
+// iterator, yielding owned `Vec`s.
+
+use std::iter::FusedIterator;
+
+#[derive(Clone, Debug)]
+pub struct ChunksExactOwned<I: Iterator> {
+    iter: I,
+    size: usize,
+    /// The leftover items that didn't fill a chunk, once we've found them.
+    remainder: Option<Vec<I::Item>>,
+}
+
+impl<I: Iterator> ChunksExactOwned<I> {
+    pub(crate) fn new(iter: I, size: usize) -> Self {
+        assert!(size != 0, "chunk size must be nonzero");
+        ChunksExactOwned {
+            iter,
+            size,
+            remainder: None,
+        }
+    }
+
+    /// The items left over at the end that were too few to fill a chunk.
+    /// Returns an empty slice if the iterator hasn't found them yet.
+    pub fn remainder(&self) -> &[I::Item] {
+        self.remainder.as_deref().unwrap_or(&[])
+    }
+
+    /// Consume the adapter, returning the leftover items (if found).
+    pub fn into_remainder(self) -> Vec<I::Item> {
+        self.remainder.unwrap_or_default()
+    }
+}
+
+impl<I: Iterator> Iterator for ChunksExactOwned<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        if self.remainder.is_some() {
+            // We've already hit the end, from one side or the other. If it
+            // was from the back, everything left in `iter` is whole chunks.
+            if self.iter.size_hint().1 == Some(0) {
+                return None;
+            }
+        }
+        let mut chunk = Vec::with_capacity(self.size);
+        chunk.extend(self.iter.by_ref().take(self.size));
+        if chunk.len() == self.size {
+            Some(chunk)
+        } else {
+            if !chunk.is_empty() || self.remainder.is_none() {
+                self.remainder = Some(chunk);
+            }
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        (lower / self.size, upper.map(|upper| upper / self.size))
+    }
+}
+
+/// Iterating from the back requires knowing where the last whole chunk
+/// ends, so the underlying iterator must know its exact length.
+impl<I> DoubleEndedIterator for ChunksExactOwned<I>
+where
+    I: DoubleEndedIterator + ExactSizeIterator,
+{
+    fn next_back(&mut self) -> Option<Vec<I::Item>> {
+        if self.remainder.is_none() {
+            let leftover = self.iter.len() % self.size;
+            let mut remainder: Vec<I::Item> = self.iter.by_ref().rev().take(leftover).collect();
+            remainder.reverse();
+            self.remainder = Some(remainder);
+        }
+        if self.iter.len() < self.size {
+            return None;
+        }
+        let mut chunk: Vec<I::Item> = self.iter.by_ref().rev().take(self.size).collect();
+        chunk.reverse();
+        Some(chunk)
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for ChunksExactOwned<I> {}
+
+impl<I: FusedIterator> FusedIterator for ChunksExactOwned<I> {}
@@ -0,0 +1,90 @@
+// `dedup_by`: drop consecutive items that are "the same" as the one before.
+
+use std::fmt;
+use std::iter::FusedIterator;
+
+pub struct DedupBy<I: Iterator, F> {
+    iter: I,
+    /// The first item of the next run, if we've already pulled it from
+    /// `iter` while looking for the end of the previous run.
+    next: Option<I::Item>,
+    same: F,
+}
+
+impl<I: Iterator, F> DedupBy<I, F> {
+    pub(crate) fn new(iter: I, same: F) -> Self {
+        DedupBy {
+            iter,
+            next: None,
+            same,
+        }
+    }
+}
+
+impl<I, F> Iterator for DedupBy<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let kept = match self.next.take() {
+            Some(item) => item,
+            None => self.iter.next()?,
+        };
+        for item in self.iter.by_ref() {
+            if !(self.same)(&kept, &item) {
+                self.next = Some(item);
+                break;
+            }
+        }
+        Some(kept)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Any nonempty run of items produces at least one item, and at most
+        // one per item.
+        let pending = self.next.is_some() as usize;
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.saturating_add(pending).min(1),
+            upper.and_then(|upper| upper.checked_add(pending)),
+        )
+    }
+}
+
+impl<I, F> FusedIterator for DedupBy<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(&I::Item, &I::Item) -> bool,
+{
+}
+
+impl<I, F> Clone for DedupBy<I, F>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        DedupBy {
+            iter: self.iter.clone(),
+            next: self.next.clone(),
+            same: self.same.clone(),
+        }
+    }
+}
+
+impl<I, F> fmt::Debug for DedupBy<I, F>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DedupBy")
+            .field("iter", &self.iter)
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,114 @@
+// Iterators that generate their items from scratch.
+
+use std::iter::FusedIterator;
+
+/// The number of Fibonacci numbers, starting from F(0) = 0, that fit in a
+/// `u64`: F(93) does, F(94) doesn't.
+const FIBONACCI_COUNT: usize = 94;
+
+/// Every Fibonacci number that fits in a `u64`, in order: 0, 1, 1, 2, 3, 5, …
+pub fn fibonacci() -> Fibonacci {
+    Fibonacci {
+        front: 0,
+        front_pair: (0, 1),
+        back: FIBONACCI_COUNT,
+        // F(92) and F(93).
+        back_pair: (7_540_113_804_746_346_429, 12_200_160_415_121_876_738),
+    }
+}
+
+/// The iterator returned by `fibonacci`. Since F(n - 1) = F(n + 1) - F(n),
+/// the sequence can be walked backwards as well as forwards.
+#[derive(Clone, Debug)]
+pub struct Fibonacci {
+    /// The index of the next number `next` will return, and that number
+    /// paired with its successor.
+    front: usize,
+    front_pair: (u64, u64),
+    /// One past the index of the next number `next_back` will return, and
+    /// that number paired with its predecessor.
+    back: usize,
+    back_pair: (u64, u64),
+}
+
+impl Iterator for Fibonacci {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.front == self.back {
+            return None;
+        }
+        let (current, following) = self.front_pair;
+        // Past F(93), the sum overflows; but we'll never return it.
+        self.front_pair = (following, current.wrapping_add(following));
+        self.front += 1;
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Fibonacci {
+    fn next_back(&mut self) -> Option<u64> {
+        if self.front == self.back {
+            return None;
+        }
+        let (preceding, current) = self.back_pair;
+        // Below F(0) this wraps too, and is likewise never returned.
+        self.back_pair = (current.wrapping_sub(preceding), preceding);
+        self.back -= 1;
+        Some(current)
+    }
+}
+
+impl ExactSizeIterator for Fibonacci {}
+
+impl FusedIterator for Fibonacci {}
+
+/// All the prime numbers, in order: 2, 3, 5, 7, 11, …
+pub fn primes() -> Primes {
+    Primes { found: Vec::new() }
+}
+
+/// The iterator returned by `primes`. It tests each candidate by trial
+/// division against the primes it has already found, up to the candidate's
+/// square root.
+#[derive(Clone, Debug)]
+pub struct Primes {
+    found: Vec<u64>,
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let mut candidate = match self.found.last() {
+            None => 2,
+            Some(2) => 3,
+            Some(&last) => last + 2,
+        };
+        while !self.is_prime(candidate) {
+            candidate += 2;
+        }
+        self.found.push(candidate);
+        Some(candidate)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl Primes {
+    fn is_prime(&self, candidate: u64) -> bool {
+        self.found
+            .iter()
+            .take_while(|&&p| p * p <= candidate)
+            .all(|&p| !candidate.is_multiple_of(p))
+    }
+}
+
+impl FusedIterator for Primes {}
@@ -0,0 +1,51 @@
+// Hand-written iterator adapters and generators, in the style of the
+// standard library's own: each adapter is a struct wrapping the iterator it
+// draws from, with an extension trait method to create it.
+
+mod chunks;
+mod dedup;
+mod generators;
+mod unfold;
+mod windows;
+
+pub use chunks::ChunksExactOwned;
+pub use dedup::DedupBy;
+pub use generators::{fibonacci, primes, Fibonacci, Primes};
+pub use unfold::{unfold, Unfold};
+pub use windows::WindowsMap;
+
+/// Adapter methods available on every iterator.
+pub trait IteratorExt: Iterator + Sized {
+    /// Collapse each run of consecutive items for which `same(previous,
+    /// next)` returns true into its first item. `previous` is always the
+    /// item that was kept.
+    fn dedup_by<F>(self, same: F) -> DedupBy<Self, F>
+    where
+        F: FnMut(&Self::Item, &Self::Item) -> bool,
+    {
+        DedupBy::new(self, same)
+    }
+
+    /// Group items into `Vec`s of exactly `size` items each. Leftover items
+    /// at the end are not yielded; `remainder` returns them once the
+    /// iterator is exhausted.
+    ///
+    /// Panics if `size` is zero.
+    fn chunks_exact_owned(self, size: usize) -> ChunksExactOwned<Self> {
+        ChunksExactOwned::new(self, size)
+    }
+
+    /// Call `f` on each overlapping window of `size` consecutive items,
+    /// yielding its results. Windows are passed as slices, so the items
+    /// don't need to be `Clone`.
+    ///
+    /// Panics if `size` is zero.
+    fn windows_map<F, R>(self, size: usize, f: F) -> WindowsMap<Self, F>
+    where
+        F: FnMut(&[Self::Item]) -> R,
+    {
+        WindowsMap::new(self, size, f)
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
@@ -0,0 +1,33 @@
+use iter_tools::{fibonacci, primes, unfold, IteratorExt};
+
+fn main() {
+    let words = "the the quick brown brown brown fox".split_whitespace();
+    let unique: Vec<&str> = words.dedup_by(|a, b| a == b).collect();
+    println!("dedup_by: {:?}", unique);
+
+    let rows: Vec<Vec<u32>> = (1..=10).chunks_exact_owned(3).collect();
+    println!("chunks_exact_owned(3): {:?}", rows);
+
+    let averages: Vec<f64> = [3.0, 5.0, 4.0, 8.0, 6.0]
+        .into_iter()
+        .windows_map(3, |w| w.iter().sum::<f64>() / w.len() as f64)
+        .collect();
+    println!("moving average: {:?}", averages);
+
+    println!("fibonacci: {:?}", fibonacci().take(12).collect::<Vec<_>>());
+    println!(
+        "largest fibonacci in u64: {}",
+        fibonacci().next_back().unwrap()
+    );
+    println!("primes: {:?}", primes().take(12).collect::<Vec<_>>());
+
+    let collatz: Vec<u64> = unfold(27u64, |n| {
+        if *n == 1 {
+            return None;
+        }
+        *n = if *n % 2 == 0 { *n / 2 } else { 3 * *n + 1 };
+        Some(*n)
+    })
+    .collect();
+    println!("collatz(27) reaches 1 after {} steps", collatz.len());
+}
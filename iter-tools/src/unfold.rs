@@ -0,0 +1,47 @@
+// `unfold`: build an iterator from a state value and a closure that steps it.
+
+use std::fmt;
+
+/// Return an iterator that calls `f` on `state` to produce each item, and
+/// ends when `f` returns `None`.
+///
+///     # use iter_tools::unfold;
+///     let powers: Vec<u32> = unfold(1u32, |n| {
+///         let current = *n;
+///         *n = n.checked_mul(10)?;
+///         Some(current)
+///     })
+///     .collect();
+///     assert_eq!(powers[..3], [1, 10, 100]);
+///     assert_eq!(powers.len(), 9);
+pub fn unfold<St, F, T>(state: St, f: F) -> Unfold<St, F>
+where
+    F: FnMut(&mut St) -> Option<T>,
+{
+    Unfold { state, f }
+}
+
+#[derive(Clone)]
+pub struct Unfold<St, F> {
+    pub state: St,
+    f: F,
+}
+
+impl<St, F, T> Iterator for Unfold<St, F>
+where
+    F: FnMut(&mut St) -> Option<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        (self.f)(&mut self.state)
+    }
+}
+
+impl<St: fmt::Debug, F> fmt::Debug for Unfold<St, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Unfold")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,100 @@
+// `windows_map`: like the slice method `windows`, but for any iterator.
+// Windows borrow from a buffer the adapter reuses, so rather than yielding
+// them (which `Iterator` can't express) we pass each to a closure.
+
+use std::fmt;
+use std::iter::FusedIterator;
+
+pub struct WindowsMap<I: Iterator, F> {
+    iter: I,
+    size: usize,
+    /// The current window. Empty until the first call to `next`.
+    window: Vec<I::Item>,
+    f: F,
+}
+
+impl<I: Iterator, F> WindowsMap<I, F> {
+    pub(crate) fn new(iter: I, size: usize, f: F) -> Self {
+        assert!(size != 0, "window size must be nonzero");
+        WindowsMap {
+            iter,
+            size,
+            window: Vec::with_capacity(size),
+            f,
+        }
+    }
+}
+
+impl<I, F, R> Iterator for WindowsMap<I, F>
+where
+    I: Iterator,
+    F: FnMut(&[I::Item]) -> R,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<R> {
+        if self.window.is_empty() {
+            self.window.extend(self.iter.by_ref().take(self.size));
+            if self.window.len() < self.size {
+                // Too short for even one window. Leave the buffer nonempty
+                // so we don't try to fill it again.
+                self.window.truncate(1);
+                return None;
+            }
+        } else if self.window.len() < self.size {
+            return None;
+        } else {
+            let item = self.iter.next()?;
+            // Slide the window: the old first item moves to the end, where
+            // the assignment drops it.
+            self.window.rotate_left(1);
+            self.window[self.size - 1] = item;
+        }
+        Some((self.f)(&self.window))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        if self.window.is_empty() {
+            // The first window uses `size` items; each item after that makes
+            // one more.
+            let skipped = self.size - 1;
+            (
+                lower.saturating_sub(skipped),
+                upper.map(|upper| upper.saturating_sub(skipped)),
+            )
+        } else if self.window.len() < self.size {
+            (0, Some(0))
+        } else {
+            (lower, upper)
+        }
+    }
+}
+
+impl<I, F, R> ExactSizeIterator for WindowsMap<I, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(&[I::Item]) -> R,
+{
+}
+
+impl<I, F, R> FusedIterator for WindowsMap<I, F>
+where
+    I: FusedIterator,
+    F: FnMut(&[I::Item]) -> R,
+{
+}
+
+impl<I, F> fmt::Debug for WindowsMap<I, F>
+where
+    I: Iterator + fmt::Debug,
+    I::Item: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WindowsMap")
+            .field("iter", &self.iter)
+            .field("size", &self.size)
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,171 @@
+use iter_tools::IteratorExt;
+use proptest::prelude::*;
+
+/// Drain `iter`, checking at every step that its `size_hint` is consistent
+/// with the number of items it actually goes on to produce.
+fn collect_checking_size_hint<I: Iterator>(mut iter: I) -> Vec<I::Item> {
+    let mut hints = vec![iter.size_hint()];
+    let mut items = Vec::new();
+    while let Some(item) = iter.next() {
+        items.push(item);
+        hints.push(iter.size_hint());
+    }
+    for (taken, (lower, upper)) in hints.into_iter().enumerate() {
+        let remaining = items.len() - taken;
+        assert!(lower <= remaining, "lower bound {} > {}", lower, remaining);
+        if let Some(upper) = upper {
+            assert!(remaining <= upper, "upper bound {} < {}", upper, remaining);
+        }
+    }
+    assert_eq!(iter.next().map(|_| ()), None);
+    items
+}
+
+#[test]
+fn dedup_by_keeps_the_first_of_each_run() {
+    let v: Vec<i32> = [1, 1, 2, 3, 3, 3, 1, 4, 4]
+        .into_iter()
+        .dedup_by(|a, b| a == b)
+        .collect();
+    assert_eq!(v, [1, 2, 3, 1, 4]);
+
+    // Compares against the kept item, not the previous item.
+    let v: Vec<i32> = [1, 2, 3, 4, 5, 6]
+        .into_iter()
+        .dedup_by(|a, b| b - a < 3)
+        .collect();
+    assert_eq!(v, [1, 4]);
+
+    assert_eq!(std::iter::empty::<u8>().dedup_by(|a, b| a == b).count(), 0);
+}
+
+#[test]
+fn dedup_by_is_case_insensitive_on_request() {
+    let words = ["Apple", "APPLE", "apple", "Banana", "banana", "apple"];
+    let v: Vec<&str> =
+        IteratorExt::dedup_by(words.into_iter(), |a, b| a.eq_ignore_ascii_case(b)).collect();
+    assert_eq!(v, ["Apple", "Banana", "apple"]);
+}
+
+#[test]
+fn chunks_exact_owned_leaves_a_remainder() {
+    let mut chunks = (1..9).chunks_exact_owned(3);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks.next(), Some(vec![1, 2, 3]));
+    assert_eq!(chunks.remainder(), &[] as &[i32]);
+    assert_eq!(chunks.next(), Some(vec![4, 5, 6]));
+    assert_eq!(chunks.next(), None);
+    assert_eq!(chunks.remainder(), &[7, 8]);
+    assert_eq!(chunks.next(), None);
+    assert_eq!(chunks.into_remainder(), [7, 8]);
+}
+
+#[test]
+fn chunks_exact_owned_from_both_ends() {
+    let mut chunks = (1..12).chunks_exact_owned(3);
+    assert_eq!(chunks.next_back(), Some(vec![7, 8, 9]));
+    assert_eq!(chunks.remainder(), &[10, 11]);
+    assert_eq!(chunks.next(), Some(vec![1, 2, 3]));
+    assert_eq!(chunks.next_back(), Some(vec![4, 5, 6]));
+    assert_eq!(chunks.next(), None);
+    assert_eq!(chunks.next_back(), None);
+    assert_eq!(chunks.remainder(), &[10, 11]);
+
+    let reversed: Vec<Vec<char>> = "abcdefg"
+        .chars()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .chunks_exact_owned(2)
+        .rev()
+        .collect();
+    assert_eq!(reversed, [vec!['e', 'f'], vec!['c', 'd'], vec!['a', 'b']]);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be nonzero")]
+fn chunks_exact_owned_rejects_zero() {
+    let _ = (0..3).chunks_exact_owned(0);
+}
+
+#[test]
+fn windows_map_works_on_non_clone_items() {
+    let strings = ["a", "b", "c", "d"].map(String::from);
+    let joined: Vec<String> = strings.into_iter().windows_map(2, |w| w.concat()).collect();
+    assert_eq!(joined, ["ab", "bc", "cd"]);
+
+    assert_eq!((0..2).windows_map(3, |w| w.len()).count(), 0);
+    assert_eq!((0..3).windows_map(3, |w| w.len()).collect::<Vec<_>>(), [3]);
+}
+
+#[test]
+fn windows_map_size_hints_are_exact() {
+    let mut windows = (0..10).windows_map(4, |w| w[0]);
+    assert_eq!(windows.len(), 7);
+    windows.next();
+    assert_eq!(windows.len(), 6);
+    assert_eq!(windows.by_ref().last(), Some(6));
+    assert_eq!(windows.len(), 0);
+}
+
+proptest! {
+    #[test]
+    fn dedup_by_matches_itertools(v in prop::collection::vec(0u8..4, 0..40), modulus in 1u8..4) {
+        let same = |a: &u8, b: &u8| a % modulus == b % modulus;
+        let ours = collect_checking_size_hint(IteratorExt::dedup_by(v.iter(), |a, b| same(a, b)));
+        let theirs: Vec<&u8> = itertools::Itertools::dedup_by(v.iter(), |a, b| same(a, b)).collect();
+        prop_assert_eq!(ours, theirs);
+    }
+
+    #[test]
+    fn chunks_exact_owned_matches_slice_chunks_exact(v in prop::collection::vec(any::<i16>(), 0..40), size in 1usize..6) {
+        let expected = v.chunks_exact(size);
+        let expected_remainder = expected.remainder().to_vec();
+        let expected: Vec<Vec<i16>> = expected.map(<[i16]>::to_vec).collect();
+
+        let mut chunks = v.clone().into_iter().chunks_exact_owned(size);
+        prop_assert_eq!(chunks.len(), expected.len());
+        let ours = collect_checking_size_hint(chunks.by_ref());
+        prop_assert_eq!(&ours, &expected);
+        prop_assert_eq!(chunks.remainder(), &expected_remainder[..]);
+
+        // And itertools's lazy chunks agree, once their short last chunk is
+        // dropped.
+        let theirs: Vec<Vec<i16>> = itertools::Itertools::chunks(v.iter().copied(), size).into_iter().map(|c| c.collect::<Vec<_>>()).filter(|c| c.len() == size).collect();
+        prop_assert_eq!(ours, theirs);
+    }
+
+    #[test]
+    fn chunks_exact_owned_double_ended(v in prop::collection::vec(any::<u8>(), 0..40), size in 1usize..6, pattern in any::<u64>()) {
+        let expected: Vec<Vec<u8>> = v.chunks_exact(size).map(<[u8]>::to_vec).collect();
+        let mut chunks = v.clone().into_iter().chunks_exact_owned(size);
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        for step in 0.. {
+            let chunk = if pattern >> (step % 64) & 1 == 1 { chunks.next_back() } else { chunks.next() };
+            match chunk {
+                Some(chunk) if pattern >> (step % 64) & 1 == 1 => back.push(chunk),
+                Some(chunk) => front.push(chunk),
+                None => break,
+            }
+            prop_assert_eq!(chunks.len(), expected.len() - front.len() - back.len());
+        }
+        prop_assert_eq!(chunks.next(), None);
+        prop_assert_eq!(chunks.next_back(), None);
+        front.extend(back.into_iter().rev());
+        prop_assert_eq!(front, expected);
+        prop_assert_eq!(chunks.remainder(), v.chunks_exact(size).remainder());
+    }
+
+    #[test]
+    fn windows_map_matches_slice_windows(v in prop::collection::vec(any::<i32>(), 0..40), size in 1usize..6) {
+        let expected: Vec<Vec<i32>> = v.windows(size).map(<[i32]>::to_vec).collect();
+        let ours = collect_checking_size_hint(v.iter().copied().windows_map(size, <[i32]>::to_vec));
+        prop_assert_eq!(ours, expected);
+    }
+
+    #[test]
+    fn windows_map_pairs_match_tuple_windows(v in prop::collection::vec(any::<i32>(), 0..40)) {
+        let ours: Vec<(i32, i32)> = v.iter().copied().windows_map(2, |w| (w[0], w[1])).collect();
+        let theirs: Vec<(i32, i32)> = itertools::Itertools::tuple_windows(v.iter().copied()).collect();
+        prop_assert_eq!(ours, theirs);
+    }
+}
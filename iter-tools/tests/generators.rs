@@ -0,0 +1,97 @@
+use iter_tools::{fibonacci, primes, unfold};
+
+#[test]
+fn fibonacci_starts_right() {
+    let v: Vec<u64> = fibonacci().take(10).collect();
+    assert_eq!(v, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+}
+
+#[test]
+fn fibonacci_covers_all_of_u64() {
+    let all: Vec<u64> = fibonacci().collect();
+    assert_eq!(all.len(), 94);
+    assert_eq!(fibonacci().len(), 94);
+    for w in all.windows(3) {
+        assert_eq!(w[0] + w[1], w[2]);
+    }
+    // The next one would overflow.
+    let last = all[all.len() - 1];
+    let second_last = all[all.len() - 2];
+    assert!(last.checked_add(second_last).is_none());
+}
+
+#[test]
+fn fibonacci_backwards() {
+    let forward: Vec<u64> = fibonacci().collect();
+    let mut backward: Vec<u64> = fibonacci().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+
+    let mut fib = fibonacci();
+    assert_eq!(fib.next(), Some(0));
+    assert_eq!(fib.next_back(), Some(12_200_160_415_121_876_738));
+    assert_eq!(fib.len(), 92);
+    let middle: Vec<u64> = fib.by_ref().skip(45).collect();
+    assert_eq!(middle.len(), 47);
+    assert_eq!(fib.next(), None);
+    assert_eq!(fib.next_back(), None);
+}
+
+#[test]
+fn fibonacci_matches_unfold() {
+    // The textbook way to write it with `unfold`, which stops at overflow.
+    let theirs = itertools::unfold((Some(0u64), Some(1u64)), |state| {
+        let current = state.0?;
+        *state = (state.1, state.1.and_then(|next| next.checked_add(current)));
+        Some(current)
+    });
+    assert!(fibonacci().eq(theirs));
+}
+
+#[test]
+fn primes_are_prime() {
+    let v: Vec<u64> = primes().take(10).collect();
+    assert_eq!(v, [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+    // Compare against a sieve of Eratosthenes.
+    const LIMIT: usize = 10_000;
+    let mut composite = vec![false; LIMIT];
+    for i in 2..LIMIT {
+        if !composite[i] {
+            for multiple in (i * i..LIMIT).step_by(i) {
+                composite[multiple] = true;
+            }
+        }
+    }
+    let sieve = (2..LIMIT as u64).filter(|&n| !composite[n as usize]);
+    assert!(primes().take_while(|&p| p < LIMIT as u64).eq(sieve));
+    assert_eq!(primes().nth(999), Some(7919));
+}
+
+#[test]
+fn unfold_matches_itertools() {
+    let step = |n: &mut u32| {
+        if *n > 1000 {
+            None
+        } else {
+            *n = *n * 3 + 1;
+            Some(*n)
+        }
+    };
+    let ours: Vec<u32> = unfold(1, step).collect();
+    let theirs: Vec<u32> = itertools::unfold(1, step).collect();
+    assert_eq!(ours, theirs);
+    assert_eq!(ours, [4, 13, 40, 121, 364, 1093]);
+}
+
+#[test]
+fn unfold_exposes_its_state() {
+    let mut counter = unfold(0, |n: &mut i32| {
+        *n += 1;
+        Some(*n)
+    });
+    assert_eq!(counter.nth(4), Some(5));
+    assert_eq!(counter.state, 5);
+    counter.state = 100;
+    assert_eq!(counter.next(), Some(101));
+}
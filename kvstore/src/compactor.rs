@@ -0,0 +1,92 @@
+// The background compaction thread.
+//
+// Writers never compact the store themselves: when a write pushes the
+// stale byte count past the threshold, it sends the compactor a message
+// and carries on. The compactor holds only a weak reference to the store,
+// so it doesn't keep it alive; when the last `KvStore` handle is dropped,
+// the channel closes and the thread exits.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, Weak};
+use std::thread::{self, JoinHandle};
+
+use crate::error::Result;
+use crate::store::Inner;
+
+enum Message {
+    Compact,
+    /// Reply on the enclosed channel once every earlier message has been
+    /// handled, with the error from any compaction that failed meanwhile.
+    Barrier(Sender<Result<()>>),
+}
+
+pub(crate) struct Compactor {
+    sender: Mutex<Option<Sender<Message>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Compactor {
+    pub(crate) fn spawn(store: Weak<Inner>) -> Compactor {
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("kvstore-compactor".to_string())
+            .spawn(move || run(store, receiver))
+            .expect("failed to spawn compaction thread");
+        Compactor {
+            sender: Mutex::new(Some(sender)),
+            thread: Some(thread),
+        }
+    }
+
+    pub(crate) fn request(&self) {
+        self.send(Message::Compact);
+    }
+
+    pub(crate) fn wait(&self) -> Result<()> {
+        let (reply, result) = mpsc::channel();
+        self.send(Message::Barrier(reply));
+        // If the thread is gone, there's nothing to wait for.
+        result.recv().unwrap_or(Ok(()))
+    }
+
+    fn send(&self, message: Message) {
+        if let Some(sender) = &*self.sender.lock().unwrap() {
+            // The thread only exits once the channel is closed, so this
+            // can't fail.
+            let _ = sender.send(message);
+        }
+    }
+}
+
+impl Drop for Compactor {
+    fn drop(&mut self) {
+        // Close the channel, then wait for the thread to finish whatever
+        // it's doing. If the store is being dropped by the compactor
+        // itself, at the end of a compaction, it's about to exit anyway.
+        self.sender.lock().unwrap().take();
+        if let Some(thread) = self.thread.take() {
+            if thread.thread().id() != thread::current().id() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+fn run(store: Weak<Inner>, receiver: Receiver<Message>) {
+    let mut failure = Ok(());
+    for message in receiver {
+        match message {
+            Message::Compact => {
+                let Some(store) = store.upgrade() else {
+                    return;
+                };
+                if let Err(e) = store.compact_in_background() {
+                    failure = Err(e);
+                }
+            }
+            Message::Barrier(reply) => {
+                let _ = reply.send(std::mem::replace(&mut failure, Ok(())));
+            }
+        }
+    }
+}
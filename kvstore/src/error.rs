@@ -0,0 +1,53 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A damaged record in a log file other than the newest one. Damage at
+    /// the end of the newest log is what a crash mid-write leaves behind,
+    /// so recovery discards it; anywhere else, it means the data itself
+    /// has been corrupted, and the store refuses to guess.
+    Corrupt {
+        file: PathBuf,
+        offset: u64,
+    },
+    /// Another `KvStore`, in this process or another, already has the
+    /// store open.
+    Locked(PathBuf),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Corrupt { file, offset } => {
+                write!(
+                    f,
+                    "corrupt record in {} at offset {}",
+                    file.display(),
+                    offset
+                )
+            }
+            Error::Locked(dir) => write!(f, "{} is in use by another process", dir.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Corrupt { .. } | Error::Locked(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
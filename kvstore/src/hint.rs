@@ -0,0 +1,84 @@
+// Hint files.
+//
+// Rebuilding the keydir from a log means reading every value in it, just
+// to learn where each one is. When compaction writes a log, it also writes
+// a hint file listing each entry's key and position, so startup can read
+// that instead. A hint holds, for each entry:
+//
+//     key_len       u32, little-endian
+//     value_len     u32
+//     value_offset  u64
+//     key           key_len bytes
+//
+// followed by a CRC-32 of everything before it. A hint that fails the
+// check, or is missing, is ignored, and the log is replayed instead.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::crc::Crc32;
+
+/// One entry in a hint file.
+pub(crate) struct Hint {
+    pub(crate) key: Vec<u8>,
+    pub(crate) offset: u64,
+    pub(crate) len: u64,
+}
+
+/// The path of the hint file for log `generation` in `dir`.
+pub(crate) fn hint_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("{}.hint", generation))
+}
+
+/// Write `hints` to `path` and sync it.
+pub(crate) fn write(path: &Path, hints: &[Hint]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut crc = Crc32::new();
+    for hint in hints {
+        let mut header = [0; 16];
+        header[..4].copy_from_slice(&(hint.key.len() as u32).to_le_bytes());
+        header[4..8].copy_from_slice(&(hint.len as u32).to_le_bytes());
+        header[8..].copy_from_slice(&hint.offset.to_le_bytes());
+        for part in [&header[..], &hint.key] {
+            crc.update(part);
+            out.write_all(part)?;
+        }
+    }
+    out.write_all(&crc.finish().to_le_bytes())?;
+    out.into_inner()
+        .map_err(io::IntoInnerError::into_error)?
+        .sync_all()
+}
+
+/// Read the hints in `path`. Returns `None` if the file is missing or
+/// damaged, or mentions a value past `log_len`, the length of its log.
+pub(crate) fn read(path: &Path, log_len: u64) -> Option<Vec<Hint>> {
+    let bytes = fs::read(path).ok()?;
+    let (body, trailer) = bytes.split_at_checked(bytes.len().checked_sub(4)?)?;
+    let mut crc = Crc32::new();
+    crc.update(body);
+    if crc.finish().to_le_bytes() != trailer {
+        return None;
+    }
+
+    let mut hints = Vec::new();
+    let mut rest = body;
+    while !rest.is_empty() {
+        let (header, after) = rest.split_at_checked(16)?;
+        let key_len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let len = u64::from(u32::from_le_bytes(header[4..8].try_into().unwrap()));
+        let offset = u64::from_le_bytes(header[8..].try_into().unwrap());
+        let (key, after) = after.split_at_checked(key_len)?;
+        if offset.checked_add(len)? > log_len {
+            return None;
+        }
+        hints.push(Hint {
+            key: key.to_vec(),
+            offset,
+            len,
+        });
+        rest = after;
+    }
+    Some(hints)
+}
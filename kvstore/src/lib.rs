@@ -0,0 +1,19 @@
+// A Bitcask-style key-value store.
+//
+// Every write is appended to a log file; nothing on disk is ever modified
+// in place. An in-memory index, the *keydir*, maps each live key to the
+// file and offset of its latest value, so a read is one seek. Reopening
+// the store replays the logs to rebuild the keydir, and compaction
+// rewrites the live entries into a fresh log so the space taken by
+// overwritten and deleted values can be reclaimed. It runs on a background
+// thread, while reads and writes carry on.
+
+mod compactor;
+mod crc;
+mod error;
+mod hint;
+mod log;
+mod store;
+
+pub use error::{Error, Result};
+pub use store::{KvStore, Options};
@@ -0,0 +1,127 @@
+// The on-disk record format.
+//
+// A log file is a sequence of records, each:
+//
+//     crc32      u32, little-endian, over everything after it
+//     key_len    u32
+//     value_len  u32, or TOMBSTONE for a deletion
+//     key        key_len bytes
+//     value      value_len bytes (absent for a tombstone)
+//
+// The checksum is what makes recovery possible: a record cut short by a
+// crash, or with garbage where its end should be, fails the check.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::crc::Crc32;
+
+pub(crate) const HEADER_LEN: u64 = 12;
+const TOMBSTONE: u32 = u32::MAX;
+
+/// The length of the record for `key` with a value of `value_len` bytes
+/// (or a tombstone, for zero).
+pub(crate) fn record_len(key_len: usize, value_len: usize) -> u64 {
+    HEADER_LEN + key_len as u64 + value_len as u64
+}
+
+/// Encode a record. `None` encodes a tombstone, marking `key` deleted.
+pub(crate) fn encode(key: &[u8], value: Option<&[u8]>) -> Vec<u8> {
+    let key_len = u32::try_from(key.len()).expect("key too large");
+    let value_len = match value {
+        Some(v) => {
+            let len = u32::try_from(v.len()).expect("value too large");
+            assert!(len != TOMBSTONE, "value too large");
+            len
+        }
+        None => TOMBSTONE,
+    };
+    let value = value.unwrap_or(&[]);
+
+    let mut record = Vec::with_capacity(record_len(key.len(), value.len()) as usize);
+    record.extend_from_slice(&[0; 4]);
+    record.extend_from_slice(&key_len.to_le_bytes());
+    record.extend_from_slice(&value_len.to_le_bytes());
+    record.extend_from_slice(key);
+    record.extend_from_slice(value);
+    let mut crc = Crc32::new();
+    crc.update(&record[4..]);
+    record[..4].copy_from_slice(&crc.finish().to_le_bytes());
+    record
+}
+
+/// What `read_record` found.
+pub(crate) enum Next {
+    Record {
+        key: Vec<u8>,
+        /// `None` for a tombstone.
+        value: Option<Vec<u8>>,
+    },
+    /// A clean end of file, exactly at a record boundary.
+    End,
+    /// A truncated record or a checksum mismatch.
+    Damaged,
+}
+
+/// Read the next record from `reader`, which has `remaining` bytes left.
+/// Knowing how much is left lets us reject a damaged length field before
+/// trying to allocate gigabytes for it.
+pub(crate) fn read_record<R: Read>(reader: &mut R, remaining: u64) -> io::Result<Next> {
+    if remaining == 0 {
+        return Ok(Next::End);
+    }
+    if remaining < HEADER_LEN {
+        return Ok(Next::Damaged);
+    }
+    let mut header = [0; HEADER_LEN as usize];
+    reader.read_exact(&mut header)?;
+    let word = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+    let (expected_crc, key_len, value_len) = (word(0), word(4), word(8));
+    let body_len = u64::from(key_len)
+        + if value_len == TOMBSTONE {
+            0
+        } else {
+            u64::from(value_len)
+        };
+    if body_len > remaining - HEADER_LEN {
+        return Ok(Next::Damaged);
+    }
+
+    let mut body = vec![0; body_len as usize];
+    reader.read_exact(&mut body)?;
+    let mut crc = Crc32::new();
+    crc.update(&header[4..]);
+    crc.update(&body);
+    if crc.finish() != expected_crc {
+        return Ok(Next::Damaged);
+    }
+
+    let value = if value_len == TOMBSTONE {
+        None
+    } else {
+        Some(body.split_off(key_len as usize))
+    };
+    Ok(Next::Record { key: body, value })
+}
+
+/// The path of log file number `generation` in `dir`.
+pub(crate) fn log_path(dir: &Path, generation: u64) -> PathBuf {
+    dir.join(format!("{}.log", generation))
+}
+
+/// The generation numbers of the log files in `dir`, oldest first.
+pub(crate) fn generations(dir: &Path) -> io::Result<Vec<u64>> {
+    let mut generations = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension() != Some("log".as_ref()) {
+            continue;
+        }
+        if let Some(generation) = path.file_stem().and_then(|s| s.to_str()?.parse().ok()) {
+            generations.push(generation);
+        }
+    }
+    generations.sort_unstable();
+    Ok(generations)
+}
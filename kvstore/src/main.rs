@@ -0,0 +1,117 @@
+use std::io::{self, Write};
+use std::{env, process};
+
+use argparse_lite::{Arg, Command};
+use kvstore::KvStore;
+
+#[derive(Debug)]
+enum Action {
+    Get(String),
+    Set(String, String),
+    Remove(String),
+    List,
+    Compact,
+}
+
+#[derive(Debug)]
+struct Arguments {
+    dir: String,
+    action: Action,
+}
+
+fn command() -> Command<'static> {
+    let key = || Arg::positional("key");
+    Command::new("kvstore")
+        .about("a persistent key-value store")
+        .arg(
+            Arg::option("dir")
+                .short('C')
+                .value_name("DIR")
+                .default(".kvstore")
+                .help("the directory the store lives in"),
+        )
+        .subcommand(
+            Command::new("get")
+                .about("print the value of KEY")
+                .arg(key()),
+        )
+        .subcommand(
+            Command::new("set")
+                .about("set KEY to VALUE")
+                .arg(key())
+                .arg(Arg::positional("value")),
+        )
+        .subcommand(Command::new("rm").about("delete KEY").arg(key()))
+        .subcommand(Command::new("list").about("print every key"))
+        .subcommand(Command::new("compact").about("reclaim space from old values"))
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let command = command();
+        let matches = command
+            .parse(env::args().skip(1))
+            .unwrap_or_else(|e| e.exit());
+        let value = |m: &argparse_lite::Matches, name| m.value(name).unwrap().to_string();
+        let action = match matches.subcommand() {
+            Some(("get", m)) => Action::Get(value(m, "key")),
+            Some(("set", m)) => Action::Set(value(m, "key"), value(m, "value")),
+            Some(("rm", m)) => Action::Remove(value(m, "key")),
+            Some(("list", _)) => Action::List,
+            Some(("compact", _)) => Action::Compact,
+            _ => unreachable!("argparse-lite requires a known command"),
+        };
+        Arguments {
+            dir: value(&matches, "dir"),
+            action,
+        }
+    }
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", e);
+    process::exit(1);
+}
+
+fn main() {
+    let args = Arguments::parse();
+    let store = KvStore::open(&args.dir).unwrap_or_else(|e| fail(e));
+
+    match args.action {
+        Action::Get(key) => match store.get(key.as_bytes()).unwrap_or_else(|e| fail(e)) {
+            Some(value) => {
+                let mut stdout = io::stdout().lock();
+                stdout
+                    .write_all(&value)
+                    .and_then(|()| writeln!(stdout))
+                    .unwrap_or_else(|e| fail(e));
+            }
+            None => fail(format!("key not found: {}", key)),
+        },
+        Action::Set(key, value) => store
+            .put(key.as_bytes(), value.as_bytes())
+            .unwrap_or_else(|e| fail(e)),
+        Action::Remove(key) => {
+            if !store.delete(key.as_bytes()).unwrap_or_else(|e| fail(e)) {
+                fail(format!("key not found: {}", key));
+            }
+        }
+        Action::List => {
+            let mut keys: Vec<_> = store
+                .keys()
+                .into_iter()
+                .map(|key| String::from_utf8_lossy(&key).into_owned())
+                .collect();
+            keys.sort();
+            for key in keys {
+                println!("{}", key);
+            }
+        }
+        Action::Compact => {
+            let before = store.stale_bytes();
+            store.compact().unwrap_or_else(|e| fail(e));
+            eprintln!("reclaimed {} bytes", before);
+        }
+    }
+    store.sync().unwrap_or_else(|e| fail(e));
+}
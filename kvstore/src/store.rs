@@ -0,0 +1,559 @@
+use std::collections::{btree_map, BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::compactor::Compactor;
+use crate::error::{Error, Result};
+use crate::hint::{self, Hint};
+use crate::log::{self, Next};
+
+/// Where a key's current value lives on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ValuePos {
+    generation: u64,
+    /// The offset of the value itself, past the record's header and key.
+    offset: u64,
+    len: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    /// Compact in the background once this many bytes of the logs hold
+    /// overwritten or deleted data. `None` leaves compaction to explicit
+    /// calls to `KvStore::compact`.
+    pub compaction_threshold: Option<u64>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            compaction_threshold: Some(1024 * 1024),
+        }
+    }
+}
+
+/// A handle on an open store. Handles are cheap to clone, and all clones
+/// share the same store, so each thread can have its own.
+#[derive(Clone)]
+pub struct KvStore {
+    inner: Arc<Inner>,
+}
+
+pub(crate) struct Inner {
+    dir: PathBuf,
+    options: Options,
+    /// The keydir and the files it points into.
+    index: RwLock<Index>,
+    /// The log all writes are appended to.
+    writer: Mutex<Writer>,
+    /// Held for the duration of a compaction, so only one runs at a time.
+    compaction: Mutex<()>,
+    /// Set while a background compaction is queued or running, so that
+    /// every write past the threshold doesn't queue another.
+    compaction_requested: AtomicBool,
+    compactor: Compactor,
+    /// An advisory lock on the `LOCK` file, held as long as the store is
+    /// open, so that two processes can't append to the same logs.
+    _lock: File,
+}
+
+struct Index {
+    keydir: HashMap<Vec<u8>, ValuePos>,
+    /// An open handle on every log file, for reads. Readers clone the
+    /// `Arc` and release the lock before reading, so a compaction can
+    /// delete a log while a read of it is still in flight.
+    files: BTreeMap<u64, Arc<File>>,
+    /// For each log, the bytes in it that compaction would reclaim:
+    /// superseded values and tombstones.
+    stale: BTreeMap<u64, u64>,
+}
+
+struct Writer {
+    generation: u64,
+    file: BufWriter<File>,
+    /// The length of the active log.
+    pos: u64,
+}
+
+impl KvStore {
+    /// Open the store in `dir`, creating the directory if need be.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<KvStore> {
+        KvStore::open_with(dir, Options::default())
+    }
+
+    pub fn open_with<P: AsRef<Path>>(dir: P, options: Options) -> Result<KvStore> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let lock = lock(&dir)?;
+        remove_leftovers(&dir)?;
+
+        let mut index = Index {
+            keydir: HashMap::new(),
+            files: BTreeMap::new(),
+            stale: BTreeMap::new(),
+        };
+        let generations = log::generations(&dir)?;
+        for (i, &generation) in generations.iter().enumerate() {
+            let newest = i + 1 == generations.len();
+            load(&dir, generation, newest, &mut index)?;
+            index.files.insert(
+                generation,
+                Arc::new(File::open(log::log_path(&dir, generation))?),
+            );
+        }
+
+        // Keep appending to the newest log, unless it was written by
+        // compaction: appending would make its hint file out of date.
+        let active = match generations.last() {
+            None => 1,
+            Some(&newest) if hint::hint_path(&dir, newest).exists() => newest + 1,
+            Some(&newest) => newest,
+        };
+        let writer = open_for_append(&dir, active)?;
+        if let btree_map::Entry::Vacant(entry) = index.files.entry(active) {
+            entry.insert(Arc::new(File::open(log::log_path(&dir, active))?));
+        }
+
+        let inner = Arc::new_cyclic(|weak| Inner {
+            dir,
+            options,
+            index: RwLock::new(index),
+            writer: Mutex::new(writer),
+            compaction: Mutex::new(()),
+            compaction_requested: AtomicBool::new(false),
+            compactor: Compactor::spawn(weak.clone()),
+            _lock: lock,
+        });
+        Ok(KvStore { inner })
+    }
+
+    /// The current value for `key`, if any.
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let (pos, file) = {
+            let index = self.inner.index.read().unwrap();
+            let Some(&pos) = index.keydir.get(key) else {
+                return Ok(None);
+            };
+            (pos, Arc::clone(&index.files[&pos.generation]))
+        };
+        let mut value = vec![0; pos.len as usize];
+        read_exact_at(&file, &mut value, pos.offset)?;
+        Ok(Some(value))
+    }
+
+    /// Set `key` to `value`, replacing any previous value.
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        {
+            let mut writer = self.inner.writer.lock().unwrap();
+            let offset = writer.append(key, Some(value))?;
+            let pos = ValuePos {
+                generation: writer.generation,
+                offset: offset + log::HEADER_LEN + key.len() as u64,
+                len: value.len() as u64,
+            };
+            // Update the keydir before releasing the writer, so that the
+            // keydir's order of updates to a key matches the log's.
+            let mut index = self.inner.index.write().unwrap();
+            if let Some(old) = index.keydir.insert(key.to_vec(), pos) {
+                index.add_stale(old.generation, log::record_len(key.len(), old.len as usize));
+            }
+        }
+        self.inner.maybe_compact();
+        Ok(())
+    }
+
+    /// Delete `key`. Returns true if it was present.
+    pub fn delete(&self, key: &[u8]) -> Result<bool> {
+        {
+            let mut writer = self.inner.writer.lock().unwrap();
+            if !self.inner.index.read().unwrap().keydir.contains_key(key) {
+                return Ok(false);
+            }
+            // Write the tombstone before touching the keydir: if the write
+            // fails, the key is still there, in memory and on disk.
+            writer.append(key, None)?;
+            let mut index = self.inner.index.write().unwrap();
+            let old = index
+                .keydir
+                .remove(key)
+                .expect("key vanished while writer locked");
+            index.add_stale(old.generation, log::record_len(key.len(), old.len as usize));
+            index.add_stale(writer.generation, log::record_len(key.len(), 0));
+        }
+        self.inner.maybe_compact();
+        Ok(true)
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.inner.index.read().unwrap().keydir.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.index.read().unwrap().keydir.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All live keys, in no particular order.
+    pub fn keys(&self) -> Vec<Vec<u8>> {
+        self.inner
+            .index
+            .read()
+            .unwrap()
+            .keydir
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Bytes on disk that compaction would reclaim.
+    pub fn stale_bytes(&self) -> u64 {
+        self.inner.index.read().unwrap().stale.values().sum()
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.inner.dir
+    }
+
+    /// Make every write so far durable. Each write is handed to the
+    /// operating system as soon as it's made, so it survives the process
+    /// crashing; `sync` is needed for it to survive the machine crashing.
+    pub fn sync(&self) -> Result<()> {
+        let mut writer = self.inner.writer.lock().unwrap();
+        writer.file.flush()?;
+        writer.file.get_ref().sync_data()?;
+        Ok(())
+    }
+
+    /// Rewrite the live entries into a new log and delete the old ones, in
+    /// the calling thread. Reads and writes can go on meanwhile.
+    pub fn compact(&self) -> Result<()> {
+        self.inner.compact()
+    }
+
+    /// Wait for any queued or running background compaction to finish, and
+    /// return the error from the last one that failed since the previous
+    /// call, if any.
+    pub fn wait_for_compaction(&self) -> Result<()> {
+        self.inner.compactor.wait()
+    }
+}
+
+impl Inner {
+    /// Queue a background compaction if enough of the logs are stale.
+    fn maybe_compact(&self) {
+        let Some(threshold) = self.options.compaction_threshold else {
+            return;
+        };
+        let stale: u64 = self.index.read().unwrap().stale.values().sum();
+        if stale > threshold && !self.compaction_requested.swap(true, Ordering::AcqRel) {
+            self.compactor.request();
+        }
+    }
+
+    /// Run a compaction requested by `maybe_compact`.
+    pub(crate) fn compact_in_background(&self) -> Result<()> {
+        self.compaction_requested.store(false, Ordering::Release);
+        self.compact()
+    }
+
+    /// Compaction copies the live values out of every existing log into a
+    /// new one, in four steps:
+    ///
+    /// 1. Start a fresh active log, so that from here on, new writes land
+    ///    in a log compaction won't touch.
+    /// 2. Take a snapshot of the keydir entries pointing into older logs.
+    /// 3. Copy those values into the compacted log and write its hint
+    ///    file, without holding any locks. Both are written under
+    ///    temporary names, synced, and then renamed into place, so a crash
+    ///    never leaves a half-written compacted log.
+    /// 4. Point the keydir at the compacted log, except for keys that were
+    ///    written or deleted during step 3, and delete the old logs.
+    ///
+    /// The compacted log is numbered just below the new active log, so
+    /// replaying everything in order gives the right answer even if a
+    /// crash prevents step 4 from deleting the old logs.
+    fn compact(&self) -> Result<()> {
+        let _compaction = self.compaction.lock().unwrap();
+
+        // Step 1.
+        let (compacted, old_generations) = {
+            let mut writer = self.writer.lock().unwrap();
+            writer.file.flush()?;
+            let compacted = writer.generation + 1;
+            let active = compacted + 1;
+            let new_writer = open_for_append(&self.dir, active)?;
+            let mut index = self.index.write().unwrap();
+            index.files.insert(
+                active,
+                Arc::new(File::open(log::log_path(&self.dir, active))?),
+            );
+            *writer = new_writer;
+            let old: Vec<u64> = index
+                .files
+                .keys()
+                .copied()
+                .filter(|&g| g < compacted)
+                .collect();
+            (compacted, old)
+        };
+
+        // Step 2.
+        let (snapshot, files) = {
+            let index = self.index.read().unwrap();
+            let snapshot: Vec<(Vec<u8>, ValuePos)> = index
+                .keydir
+                .iter()
+                .filter(|(_, pos)| pos.generation < compacted)
+                .map(|(key, &pos)| (key.clone(), pos))
+                .collect();
+            (snapshot, index.files.clone())
+        };
+
+        // Step 3.
+        let log_path = log::log_path(&self.dir, compacted);
+        let hint_path = hint::hint_path(&self.dir, compacted);
+        let log_tmp = log_path.with_extension("log.tmp");
+        let hint_tmp = hint_path.with_extension("hint.tmp");
+        let mut out = BufWriter::new(File::create(&log_tmp)?);
+        let mut hints = Vec::with_capacity(snapshot.len());
+        let mut new_positions = Vec::with_capacity(snapshot.len());
+        let mut offset = 0;
+        for (key, pos) in &snapshot {
+            let mut value = vec![0; pos.len as usize];
+            read_exact_at(&files[&pos.generation], &mut value, pos.offset)?;
+            out.write_all(&log::encode(key, Some(&value)))?;
+            let new_pos = ValuePos {
+                generation: compacted,
+                offset: offset + log::HEADER_LEN + key.len() as u64,
+                len: pos.len,
+            };
+            offset += log::record_len(key.len(), value.len());
+            hints.push(Hint {
+                key: key.clone(),
+                offset: new_pos.offset,
+                len: new_pos.len,
+            });
+            new_positions.push(new_pos);
+        }
+        out.into_inner()
+            .map_err(io::IntoInnerError::into_error)?
+            .sync_all()?;
+        hint::write(&hint_tmp, &hints)?;
+        // The hint first: a hint without its log is ignored, but a log
+        // without its hint would be taken for an active log.
+        fs::rename(&hint_tmp, &hint_path)?;
+        fs::rename(&log_tmp, &log_path)?;
+        sync_dir(&self.dir)?;
+
+        // Step 4.
+        {
+            let mut index = self.index.write().unwrap();
+            index
+                .files
+                .insert(compacted, Arc::new(File::open(&log_path)?));
+            for ((key, old_pos), new_pos) in snapshot.into_iter().zip(new_positions) {
+                match index.keydir.get_mut(&key) {
+                    Some(pos) if *pos == old_pos => *pos = new_pos,
+                    // Overwritten or deleted since the snapshot, so the
+                    // copy is already stale.
+                    _ => {
+                        index.add_stale(compacted, log::record_len(key.len(), new_pos.len as usize))
+                    }
+                }
+            }
+            for generation in &old_generations {
+                index.files.remove(generation);
+                index.stale.remove(generation);
+            }
+        }
+        for generation in old_generations {
+            fs::remove_file(log::log_path(&self.dir, generation))?;
+            match fs::remove_file(hint::hint_path(&self.dir, generation)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Index {
+    fn add_stale(&mut self, generation: u64, bytes: u64) {
+        *self.stale.entry(generation).or_insert(0) += bytes;
+    }
+}
+
+impl Writer {
+    /// Append a record to the active log, returning its offset.
+    fn append(&mut self, key: &[u8], value: Option<&[u8]>) -> Result<u64> {
+        let record = log::encode(key, value);
+        let offset = self.pos;
+        self.file.write_all(&record)?;
+        self.file.flush()?;
+        self.pos += record.len() as u64;
+        Ok(offset)
+    }
+}
+
+/// Take the store's advisory lock. The operating system releases it when
+/// the file is closed, even if the process dies, so there's never a stale
+/// lock to clean up.
+fn lock(dir: &Path) -> Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join("LOCK"))?;
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) => Err(Error::Locked(dir.to_path_buf())),
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+/// Remove the temporary files of an interrupted compaction, and hint files
+/// whose logs are gone.
+fn remove_leftovers(dir: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let orphan_hint =
+            path.extension() == Some("hint".as_ref()) && !path.with_extension("log").exists();
+        if path.extension() == Some("tmp".as_ref()) || orphan_hint {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Make renames and deletions in `dir` durable.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    if cfg!(unix) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Open log `generation` for appending, creating it if necessary.
+fn open_for_append(dir: &Path, generation: u64) -> io::Result<Writer> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log::log_path(dir, generation))?;
+    let pos = file.metadata()?.len();
+    Ok(Writer {
+        generation,
+        file: BufWriter::new(file),
+        pos,
+    })
+}
+
+/// Read exactly `buf.len()` bytes at `offset`, without moving the file's
+/// cursor, so that many threads can read one `File` at once.
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+    }
+    #[cfg(windows)]
+    {
+        let mut done = 0;
+        while done < buf.len() {
+            let n = std::os::windows::fs::FileExt::seek_read(
+                file,
+                &mut buf[done..],
+                offset + done as u64,
+            )?;
+            if n == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            done += n;
+        }
+        Ok(())
+    }
+}
+
+/// Add log `generation` to `index`, from its hint file if it has a good
+/// one, or by replaying it otherwise.
+fn load(dir: &Path, generation: u64, newest: bool, index: &mut Index) -> Result<()> {
+    let log_len = fs::metadata(log::log_path(dir, generation))?.len();
+    match hint::read(&hint::hint_path(dir, generation), log_len) {
+        Some(hints) => {
+            for Hint { key, offset, len } in hints {
+                index.insert(
+                    key,
+                    ValuePos {
+                        generation,
+                        offset,
+                        len,
+                    },
+                );
+            }
+            Ok(())
+        }
+        None => replay(dir, generation, newest, index),
+    }
+}
+
+impl Index {
+    /// Point `key` at `pos`, counting its previous value, if any, as stale.
+    fn insert(&mut self, key: Vec<u8>, pos: ValuePos) {
+        let key_len = key.len();
+        if let Some(old) = self.keydir.insert(key, pos) {
+            self.add_stale(old.generation, log::record_len(key_len, old.len as usize));
+        }
+    }
+}
+
+/// Replay log `generation` into `index`. If the log is the newest one, a
+/// damaged record is taken to be the remains of an interrupted write: the
+/// log is truncated just before it.
+fn replay(dir: &Path, generation: u64, newest: bool, index: &mut Index) -> Result<()> {
+    let path = log::log_path(dir, generation);
+    let file = File::open(&path)?;
+    let len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut offset = 0;
+
+    loop {
+        match log::read_record(&mut reader, len - offset)? {
+            Next::End => return Ok(()),
+            Next::Damaged if newest => {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&path)?
+                    .set_len(offset)?;
+                return Ok(());
+            }
+            Next::Damaged => return Err(Error::Corrupt { file: path, offset }),
+            Next::Record { key, value } => {
+                let record_len = log::record_len(key.len(), value.as_ref().map_or(0, Vec::len));
+                match value {
+                    Some(value) => {
+                        let pos = ValuePos {
+                            generation,
+                            offset: offset + log::HEADER_LEN + key.len() as u64,
+                            len: value.len() as u64,
+                        };
+                        index.insert(key, pos);
+                    }
+                    None => {
+                        index.add_stale(generation, record_len);
+                        if let Some(old) = index.keydir.remove(&key) {
+                            index.add_stale(
+                                old.generation,
+                                log::record_len(key.len(), old.len as usize),
+                            );
+                        }
+                    }
+                }
+                offset += record_len;
+            }
+        }
+    }
+}
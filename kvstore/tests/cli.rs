@@ -0,0 +1,75 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn kvstore(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_kvstore"))
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn session() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(kvstore(dir.path(), &["set", "name", "Ferris"])
+        .status
+        .success());
+    assert!(kvstore(dir.path(), &["set", "lang", "Rust"])
+        .status
+        .success());
+    assert!(kvstore(dir.path(), &["set", "name", "Corro"])
+        .status
+        .success());
+
+    let get = kvstore(dir.path(), &["get", "name"]);
+    assert!(get.status.success());
+    assert_eq!(get.stdout, b"Corro\n");
+
+    let list = kvstore(dir.path(), &["list"]);
+    assert_eq!(list.stdout, b"lang\nname\n");
+
+    assert!(kvstore(dir.path(), &["rm", "lang"]).status.success());
+    let missing = kvstore(dir.path(), &["get", "lang"]);
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("key not found: lang"));
+    assert_eq!(kvstore(dir.path(), &["rm", "lang"]).status.code(), Some(1));
+
+    let compact = kvstore(dir.path(), &["compact"]);
+    assert!(compact.status.success());
+    assert!(String::from_utf8_lossy(&compact.stderr).starts_with("reclaimed "));
+    assert_eq!(kvstore(dir.path(), &["get", "name"]).stdout, b"Corro\n");
+}
+
+#[test]
+fn usage_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    for args in [&["get"][..], &["set", "k"], &["frobnicate"], &[]] {
+        let output = kvstore(dir.path(), args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn help() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kvstore(dir.path(), &["--help"]);
+    assert_eq!(output.status.code(), Some(0));
+    let help = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        help.starts_with("kvstore - a persistent key-value store\n"),
+        "{}",
+        help
+    );
+    assert!(help.contains("-C, --dir DIR"), "{}", help);
+
+    let output = kvstore(dir.path(), &["set", "k"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with("kvstore set - set KEY to VALUE\n"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.ends_with("Error: missing VALUE\n"), "{}", stderr);
+}
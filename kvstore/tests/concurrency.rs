@@ -0,0 +1,167 @@
+// The store shared between threads, and between processes.
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use kvstore::{Error, KvStore, Options};
+
+#[test]
+fn second_open_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open(dir.path()).unwrap();
+    match KvStore::open(dir.path()) {
+        Err(Error::Locked(path)) => assert_eq!(path, dir.path()),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("opened a store twice"),
+    }
+
+    // Clones share the one lock, and it's released when the last is
+    // dropped.
+    let clone = store.clone();
+    drop(store);
+    assert!(matches!(KvStore::open(dir.path()), Err(Error::Locked(_))));
+    drop(clone);
+    KvStore::open(dir.path()).unwrap();
+}
+
+#[test]
+fn locked_across_processes() {
+    let dir = tempfile::tempdir().unwrap();
+    let _store = KvStore::open(dir.path()).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_kvstore"))
+        .arg("-C")
+        .arg(dir.path())
+        .args(["set", "k", "v"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("in use by another process"));
+}
+
+/// Writers hammer their own keys with increasing counters while
+/// compaction runs in the background, and readers check that no counter
+/// ever goes backwards: a read may be out of date, but never older than a
+/// read before it.
+#[test]
+fn readers_and_writers_during_compaction() {
+    const WRITERS: u64 = 4;
+    const KEYS_PER_WRITER: u64 = 8;
+    const ROUNDS: u64 = 300;
+
+    let dir = tempfile::tempdir().unwrap();
+    let options = Options {
+        compaction_threshold: Some(4096),
+    };
+    let store = KvStore::open_with(dir.path(), options).unwrap();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let key = |writer: u64, k: u64| format!("w{}-k{}", writer, k).into_bytes();
+    let value = |round: u64| {
+        // Vary the length, so a read from a wrong offset can't pass.
+        let mut v = round.to_le_bytes().to_vec();
+        v.resize(8 + (round % 13) as usize, b'.');
+        v
+    };
+
+    let readers: Vec<_> = (0..3)
+        .map(|_| {
+            let store = store.clone();
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut seen = vec![vec![None; KEYS_PER_WRITER as usize]; WRITERS as usize];
+                while !done.load(Ordering::Acquire) {
+                    for w in 0..WRITERS {
+                        for k in 0..KEYS_PER_WRITER {
+                            if let Some(v) = store.get(&key(w, k)).unwrap() {
+                                let round = u64::from_le_bytes(v[..8].try_into().unwrap());
+                                assert_eq!(v, value(round), "torn value");
+                                let last = &mut seen[w as usize][k as usize];
+                                assert!(Some(round) >= *last, "went backwards");
+                                *last = Some(round);
+                            }
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let writers: Vec<_> = (0..WRITERS)
+        .map(|w| {
+            let store = store.clone();
+            thread::spawn(move || {
+                for round in 0..ROUNDS {
+                    for k in 0..KEYS_PER_WRITER {
+                        store.put(&key(w, k), &value(round)).unwrap();
+                    }
+                    if round % 50 == 49 {
+                        // Delete and immediately restore, to exercise
+                        // tombstones racing with compaction.
+                        store.delete(&key(w, 0)).unwrap();
+                        store.put(&key(w, 0), &value(round)).unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    done.store(true, Ordering::Release);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    store.wait_for_compaction().unwrap();
+
+    let check = |store: &KvStore| {
+        assert_eq!(store.len() as u64, WRITERS * KEYS_PER_WRITER);
+        for w in 0..WRITERS {
+            for k in 0..KEYS_PER_WRITER {
+                assert_eq!(store.get(&key(w, k)).unwrap().unwrap(), value(ROUNDS - 1));
+            }
+        }
+    };
+    check(&store);
+    // Compaction kept up: far less is on disk than was ever written.
+    let written = WRITERS * KEYS_PER_WRITER * ROUNDS * 40;
+    let on_disk: u64 = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().metadata().unwrap().len())
+        .sum();
+    assert!(on_disk < written / 10, "{} bytes on disk", on_disk);
+
+    drop(store);
+    check(&KvStore::open(dir.path()).unwrap());
+}
+
+#[test]
+fn explicit_and_background_compaction_together() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = Options {
+        compaction_threshold: Some(1024),
+    };
+    let store = KvStore::open_with(dir.path(), options).unwrap();
+    let handles: Vec<_> = (0..4u8)
+        .map(|t| {
+            let store = store.clone();
+            thread::spawn(move || {
+                for i in 0..200u32 {
+                    store.put(&[t], &i.to_le_bytes()).unwrap();
+                    if i % 40 == 0 {
+                        store.compact().unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    store.wait_for_compaction().unwrap();
+    for t in 0..4u8 {
+        assert_eq!(store.get(&[t]).unwrap().unwrap(), 199u32.to_le_bytes());
+    }
+}
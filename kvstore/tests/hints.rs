@@ -0,0 +1,84 @@
+use std::fs;
+
+use kvstore::{KvStore, Options};
+
+const MANUAL: Options = Options {
+    compaction_threshold: None,
+};
+
+fn populated() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open_with(dir.path(), MANUAL).unwrap();
+    for i in 0..50u32 {
+        store
+            .put(format!("key{}", i).as_bytes(), &i.to_be_bytes())
+            .unwrap();
+    }
+    store.delete(b"key0").unwrap();
+    store.compact().unwrap();
+    store.put(b"key1", b"after").unwrap();
+    dir
+}
+
+fn check(store: &KvStore) {
+    assert_eq!(store.len(), 49);
+    assert_eq!(store.get(b"key0").unwrap(), None);
+    assert_eq!(store.get(b"key1").unwrap().unwrap(), b"after");
+    for i in 2..50u32 {
+        assert_eq!(
+            store.get(format!("key{}", i).as_bytes()).unwrap().unwrap(),
+            i.to_be_bytes()
+        );
+    }
+}
+
+#[test]
+fn compaction_writes_a_hint() {
+    let dir = populated();
+    assert!(dir.path().join("2.hint").exists());
+    assert!(!dir.path().join("1.log").exists());
+    check(&KvStore::open(dir.path()).unwrap());
+}
+
+#[test]
+fn startup_trusts_the_hint() {
+    // Startup reads positions from the hint, not the log, so it doesn't
+    // notice a damaged value. That's the trade: startup reads a small file
+    // instead of every value.
+    let dir = populated();
+    let log = dir.path().join("2.log");
+    let mut bytes = fs::read(&log).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    fs::write(&log, bytes).unwrap();
+    assert_eq!(KvStore::open(dir.path()).unwrap().len(), 49);
+}
+
+#[test]
+fn damaged_or_missing_hint_falls_back_to_the_log() {
+    let dir = populated();
+    let hint = dir.path().join("2.hint");
+    let mut bytes = fs::read(&hint).unwrap();
+    bytes[5] ^= 1;
+    fs::write(&hint, &bytes).unwrap();
+    check(&KvStore::open(dir.path()).unwrap());
+
+    fs::write(&hint, &bytes[..bytes.len() / 2]).unwrap();
+    check(&KvStore::open(dir.path()).unwrap());
+
+    fs::remove_file(&hint).unwrap();
+    check(&KvStore::open(dir.path()).unwrap());
+}
+
+#[test]
+fn writes_after_reopening_go_to_a_new_log() {
+    let dir = populated();
+    {
+        let store = KvStore::open(dir.path()).unwrap();
+        store.put(b"key2", b"later").unwrap();
+    }
+    // The compacted log and its hint are untouched.
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.get(b"key2").unwrap().unwrap(), b"later");
+    assert_eq!(store.get(b"key3").unwrap().unwrap(), 3u32.to_be_bytes());
+}
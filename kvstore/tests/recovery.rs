@@ -0,0 +1,189 @@
+// Crash recovery. A crash can interrupt a write at any byte, so these tests
+// cut the log short everywhere and check that the store reopens with
+// exactly the records that were completely written.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use kvstore::{Error, KvStore, Options};
+
+const MANUAL: Options = Options {
+    compaction_threshold: None,
+};
+
+/// The log files in `dir`, oldest first.
+fn logs(dir: &Path) -> Vec<PathBuf> {
+    let mut logs: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension() == Some("log".as_ref()))
+        .collect();
+    logs.sort_by_key(|p| {
+        p.file_stem()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse::<u64>()
+            .unwrap()
+    });
+    logs
+}
+
+fn only_log(dir: &Path) -> PathBuf {
+    let logs = logs(dir);
+    assert_eq!(logs.len(), 1, "{:?}", logs);
+    logs.into_iter().next().unwrap()
+}
+
+#[test]
+fn truncated_at_every_offset() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut boundaries = vec![0];
+    {
+        let store = KvStore::open(dir.path()).unwrap();
+        let log = only_log(dir.path());
+        for i in 0..5u8 {
+            store.put(&[b'k', i], &vec![i; i as usize * 3]).unwrap();
+            boundaries.push(fs::metadata(&log).unwrap().len());
+        }
+    }
+    let log = only_log(dir.path());
+    let full = fs::read(&log).unwrap();
+
+    for cut in 0..=full.len() {
+        fs::write(&log, &full[..cut]).unwrap();
+        let store = KvStore::open(dir.path()).unwrap();
+        let complete = boundaries.iter().filter(|&&b| b as usize <= cut).count() - 1;
+        assert_eq!(store.len(), complete, "cut at {}", cut);
+        for i in 0..complete as u8 {
+            assert_eq!(
+                store.get(&[b'k', i]).unwrap().unwrap(),
+                vec![i; i as usize * 3]
+            );
+        }
+        // The torn record was truncated away, so new writes land on a
+        // record boundary and survive another reopen.
+        store.put(b"after", b"crash").unwrap();
+        drop(store);
+        let store = KvStore::open(dir.path()).unwrap();
+        assert_eq!(store.len(), complete + 1, "cut at {}", cut);
+        assert_eq!(store.get(b"after").unwrap().unwrap(), b"crash");
+    }
+}
+
+#[test]
+fn garbage_tail_is_discarded() {
+    let dir = tempfile::tempdir().unwrap();
+    KvStore::open(dir.path())
+        .unwrap()
+        .put(b"good", b"value")
+        .unwrap();
+    let log = only_log(dir.path());
+    let good_len = fs::metadata(&log).unwrap().len();
+    // Enough garbage to look like a header claiming a huge record.
+    OpenOptions::new()
+        .append(true)
+        .open(&log)
+        .unwrap()
+        .write_all(&[0xff; 40])
+        .unwrap();
+
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.get(b"good").unwrap().unwrap(), b"value");
+    assert_eq!(fs::metadata(&log).unwrap().len(), good_len);
+}
+
+#[test]
+fn flipped_bit_in_last_record() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let store = KvStore::open(dir.path()).unwrap();
+        store.put(b"first", b"1").unwrap();
+        store.put(b"second", b"2").unwrap();
+    }
+    let log = only_log(dir.path());
+    let mut bytes = fs::read(&log).unwrap();
+    *bytes.last_mut().unwrap() ^= 1;
+    fs::write(&log, bytes).unwrap();
+
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.get(b"first").unwrap().unwrap(), b"1");
+    assert_eq!(store.get(b"second").unwrap(), None);
+}
+
+#[test]
+fn corruption_in_an_older_log_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let store = KvStore::open_with(dir.path(), MANUAL).unwrap();
+        store.put(b"a", b"1").unwrap();
+        store.put(b"b", b"2").unwrap();
+        store.compact().unwrap();
+        store.put(b"c", b"3").unwrap();
+    }
+    // After compaction there are two logs: the compacted one and the active
+    // one. Damage the older, and remove its hint file so that opening the
+    // store has to replay it.
+    let logs = logs(dir.path());
+    assert_eq!(logs.len(), 2);
+    fs::remove_file(logs[0].with_extension("hint")).unwrap();
+    // Each record there is 14 bytes: a 12-byte header, a one-byte key, and a
+    // one-byte value. Flip a bit in the second record's checksum.
+    let mut bytes = fs::read(&logs[0]).unwrap();
+    bytes[14] ^= 0x80;
+    fs::write(&logs[0], bytes).unwrap();
+
+    match KvStore::open(dir.path()) {
+        Err(Error::Corrupt { file, offset }) => {
+            assert_eq!(file, logs[0]);
+            assert_eq!(offset, 14);
+        }
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("opened a corrupt store"),
+    }
+}
+
+#[test]
+fn interrupted_compaction() {
+    // Simulate a crash after compaction wrote its new log but before it
+    // removed the old ones: both sets of logs are present.
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let store = KvStore::open_with(dir.path(), MANUAL).unwrap();
+        store.put(b"a", b"old").unwrap();
+        store.put(b"a", b"new").unwrap();
+        store.put(b"b", b"gone").unwrap();
+        store.delete(b"b").unwrap();
+    }
+    let original = fs::read(dir.path().join("1.log")).unwrap();
+    {
+        let store = KvStore::open_with(dir.path(), MANUAL).unwrap();
+        store.compact().unwrap();
+    }
+    fs::write(dir.path().join("1.log"), original).unwrap();
+
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.get(b"a").unwrap().unwrap(), b"new");
+    assert_eq!(store.get(b"b").unwrap(), None);
+}
+
+#[test]
+fn interrupted_compaction_leaves_temporary_files() {
+    let dir = tempfile::tempdir().unwrap();
+    KvStore::open(dir.path()).unwrap().put(b"k", b"v").unwrap();
+    // A crash before the compacted log was renamed into place.
+    fs::write(dir.path().join("2.log.tmp"), b"half a log").unwrap();
+    fs::write(dir.path().join("2.hint.tmp"), b"half a hint").unwrap();
+    fs::write(dir.path().join("2.hint"), b"a hint without its log").unwrap();
+
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.get(b"k").unwrap().unwrap(), b"v");
+    let mut names: Vec<_> = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["1.log", "LOCK"]);
+}
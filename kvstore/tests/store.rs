@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+
+use kvstore::{KvStore, Options};
+
+const MANUAL: Options = Options {
+    compaction_threshold: None,
+};
+
+fn disk_usage(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .sum()
+}
+
+#[test]
+fn get_put_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open(dir.path()).unwrap();
+    assert!(store.is_empty());
+    assert_eq!(store.get(b"k").unwrap(), None);
+
+    store.put(b"k", b"one").unwrap();
+    store.put(b"other", b"").unwrap();
+    assert_eq!(store.get(b"k").unwrap().as_deref(), Some(&b"one"[..]));
+    assert_eq!(store.get(b"other").unwrap().as_deref(), Some(&b""[..]));
+
+    store.put(b"k", b"two").unwrap();
+    assert_eq!(store.get(b"k").unwrap().as_deref(), Some(&b"two"[..]));
+    assert_eq!(store.len(), 2);
+
+    assert!(store.delete(b"k").unwrap());
+    assert!(!store.delete(b"k").unwrap());
+    assert_eq!(store.get(b"k").unwrap(), None);
+    assert!(!store.contains_key(b"k"));
+    assert_eq!(store.keys(), vec![b"other".to_vec()]);
+}
+
+#[test]
+fn persists_across_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let store = KvStore::open(dir.path()).unwrap();
+        for i in 0..100u32 {
+            store
+                .put(format!("key{}", i).as_bytes(), &i.to_le_bytes())
+                .unwrap();
+        }
+        store.put(b"key7", b"seven").unwrap();
+        store.delete(b"key8").unwrap();
+    }
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.len(), 99);
+    assert_eq!(store.get(b"key7").unwrap().unwrap(), b"seven");
+    assert_eq!(store.get(b"key8").unwrap(), None);
+    assert_eq!(store.get(b"key99").unwrap().unwrap(), 99u32.to_le_bytes());
+    // The overwritten key7 and the deletion of key8 are still on disk.
+    assert!(store.stale_bytes() > 0);
+}
+
+#[test]
+fn compaction_reclaims_space() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open_with(dir.path(), MANUAL).unwrap();
+    let value = vec![b'x'; 1000];
+    for round in 0..20 {
+        for key in 0..10u8 {
+            store.put(&[key], &value[..500 + round]).unwrap();
+        }
+    }
+    store.delete(&[9]).unwrap();
+    let before = disk_usage(dir.path());
+    assert!(store.stale_bytes() > before / 2);
+
+    store.compact().unwrap();
+    assert_eq!(store.stale_bytes(), 0);
+    let after = disk_usage(dir.path());
+    assert!(after < before / 10, "{} -> {}", before, after);
+
+    for key in 0..9u8 {
+        assert_eq!(store.get(&[key]).unwrap().unwrap(), &value[..519]);
+    }
+    assert_eq!(store.get(&[9]).unwrap(), None);
+
+    // Writes after compaction, and reopening, still work.
+    store.put(&[3], b"three").unwrap();
+    drop(store);
+    let store = KvStore::open(dir.path()).unwrap();
+    assert_eq!(store.len(), 9);
+    assert_eq!(store.get(&[3]).unwrap().unwrap(), b"three");
+    assert_eq!(store.get(&[4]).unwrap().unwrap(), &value[..519]);
+    // Only the compacted value of key 3 is stale: a 12-byte header, the
+    // key, and the value.
+    assert_eq!(store.stale_bytes(), 12 + 1 + 519);
+}
+
+#[test]
+fn automatic_compaction() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = Options {
+        compaction_threshold: Some(10_000),
+    };
+    let store = KvStore::open_with(dir.path(), options).unwrap();
+    for i in 0..1000u32 {
+        store.put(b"counter", &i.to_le_bytes()).unwrap();
+    }
+    // Compaction runs in the background, so the store can briefly go past
+    // the threshold, but once it catches up, almost nothing is stale.
+    store.wait_for_compaction().unwrap();
+    assert!(store.stale_bytes() <= 10_000);
+    assert!(disk_usage(dir.path()) < 20_000);
+    assert_eq!(
+        store.get(b"counter").unwrap().unwrap(),
+        999u32.to_le_bytes()
+    );
+}
+
+#[test]
+fn compacting_an_empty_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = KvStore::open(dir.path()).unwrap();
+    store.compact().unwrap();
+    store.compact().unwrap();
+    store.put(b"a", b"b").unwrap();
+    drop(store);
+    assert_eq!(
+        KvStore::open(dir.path())
+            .unwrap()
+            .get(b"a")
+            .unwrap()
+            .unwrap(),
+        b"b"
+    );
+}
@@ -0,0 +1,171 @@
+// The calculator language: arithmetic on numbers and variables, with
+// comparisons and logic, one statement per line.
+//
+//     # The area of a circle.
+//     let r = 2.5
+//     let area = pi * r ^ 2
+//     area > 10 and not (r == 0)
+
+use crate::cursor::Cursor;
+use crate::keywords::Keywords;
+use crate::lex::{Language, LexError};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Kind {
+    Number(f64),
+    Ident,
+    // Keywords.
+    Let,
+    And,
+    Or,
+    Not,
+    True,
+    False,
+    // Operators and punctuation.
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    Assign,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    LeftParen,
+    RightParen,
+    Comma,
+    /// Ends a statement.
+    Newline,
+    Error(LexError),
+}
+
+impl From<LexError> for Kind {
+    fn from(e: LexError) -> Kind {
+        Kind::Error(e)
+    }
+}
+
+pub struct Calc {
+    keywords: Keywords<Kind>,
+}
+
+impl Calc {
+    pub fn new() -> Calc {
+        Calc {
+            keywords: Keywords::new(&[
+                ("let", Kind::Let),
+                ("and", Kind::And),
+                ("or", Kind::Or),
+                ("not", Kind::Not),
+                ("true", Kind::True),
+                ("false", Kind::False),
+            ]),
+        }
+    }
+}
+
+impl Default for Calc {
+    fn default() -> Calc {
+        Calc::new()
+    }
+}
+
+impl Language for Calc {
+    type Kind = Kind;
+
+    fn lex(&mut self, cursor: &mut Cursor<'_>) -> Option<Kind> {
+        let c = cursor.peek()?;
+        if c == '\n' {
+            cursor.bump();
+            return Some(Kind::Newline);
+        }
+        if c.is_whitespace() {
+            cursor.eat_while(|c| c.is_whitespace() && c != '\n');
+            return None;
+        }
+        if c == '#' {
+            cursor.eat_while(|c| c != '\n');
+            return None;
+        }
+        if c.is_ascii_digit() {
+            return Some(number(cursor));
+        }
+        let word = cursor.eat_identifier();
+        if !word.is_empty() {
+            return Some(self.keywords.get(word).unwrap_or(Kind::Ident));
+        }
+
+        let two = |cursor: &mut Cursor, second, long, short| {
+            if cursor.eat(second) {
+                long
+            } else {
+                short
+            }
+        };
+        let kind = match c {
+            '+' => Kind::Plus,
+            '-' => Kind::Minus,
+            '*' => Kind::Star,
+            '/' => Kind::Slash,
+            '%' => Kind::Percent,
+            '^' => Kind::Caret,
+            '(' => Kind::LeftParen,
+            ')' => Kind::RightParen,
+            ',' => Kind::Comma,
+            '=' | '<' | '>' | '!' => {
+                cursor.bump();
+                return Some(match c {
+                    '=' => two(cursor, '=', Kind::Equal, Kind::Assign),
+                    '<' => two(cursor, '=', Kind::LessEqual, Kind::Less),
+                    '>' => two(cursor, '=', Kind::GreaterEqual, Kind::Greater),
+                    _ => two(
+                        cursor,
+                        '=',
+                        Kind::NotEqual,
+                        Kind::Error(LexError::UnexpectedChar('!')),
+                    ),
+                });
+            }
+            // Leave it to the lexer to report.
+            _ => return None,
+        };
+        cursor.bump();
+        Some(kind)
+    }
+}
+
+/// Digits, with `_` allowed between them for readability, then an
+/// optional fraction and exponent: `42`, `1_000_000`, `2.5`, `6.02e23`.
+fn number(cursor: &mut Cursor) -> Kind {
+    let digits = |cursor: &mut Cursor| cursor.eat_while(|c| c.is_ascii_digit() || c == '_').len();
+    digits(cursor);
+    if cursor.peek() == Some('.') && cursor.peek_second().is_some_and(|c| c.is_ascii_digit()) {
+        cursor.bump();
+        digits(cursor);
+    }
+    let mut valid = true;
+    if cursor.eat_if(|c| c == 'e' || c == 'E').is_some() {
+        cursor.eat_if(|c| c == '+' || c == '-');
+        valid = digits(cursor) > 0;
+    }
+    // A number running straight into a letter, like `12abc`, is one bad
+    // token rather than a number and an identifier.
+    if !cursor
+        .eat_while(|c| c.is_alphanumeric() || c == '_')
+        .is_empty()
+    {
+        valid = false;
+    }
+    let text = cursor.token_text();
+    if text.contains("__") || text.contains("_.") || text.ends_with('_') {
+        valid = false;
+    }
+    match text.replace('_', "").parse() {
+        Ok(n) if valid => Kind::Number(n),
+        _ => Kind::Error(LexError::InvalidNumber),
+    }
+}
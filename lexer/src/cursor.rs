@@ -0,0 +1,111 @@
+use crate::span::Span;
+
+/// A position in the source, which a lexer moves forward a character or
+/// a run of characters at a time. It also remembers where the current
+/// token started, so the text and span of a token are always to hand.
+#[derive(Clone, Debug)]
+pub struct Cursor<'a> {
+    source: &'a str,
+    pos: usize,
+    token_start: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(source: &'a str) -> Cursor<'a> {
+        Cursor {
+            source,
+            pos: 0,
+            token_start: 0,
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The byte offset of the next character.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.pos == self.source.len()
+    }
+
+    /// Everything from the cursor on.
+    pub fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// The character after the next one, for the few tokens that need two
+    /// characters of lookahead, like a `.` that starts a fraction only if
+    /// a digit follows.
+    pub fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Consume `c` if it's next.
+    pub fn eat(&mut self, c: char) -> bool {
+        self.eat_if(|next| next == c).is_some()
+    }
+
+    pub fn eat_if(&mut self, predicate: impl FnOnce(char) -> bool) -> Option<char> {
+        let c = self.peek().filter(|&c| predicate(c))?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// Consume `s` if the rest of the source starts with it.
+    pub fn eat_str(&mut self, s: &str) -> bool {
+        let found = self.rest().starts_with(s);
+        if found {
+            self.pos += s.len();
+        }
+        found
+    }
+
+    /// Consume characters as long as `predicate` holds, and return them.
+    pub fn eat_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let start = self.pos;
+        let rest = self.rest();
+        let len = rest.find(|c: char| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &self.source[start..self.pos]
+    }
+
+    /// An identifier: a letter or `_`, then letters, digits, and `_`s.
+    /// Returns an empty string, consuming nothing, if there isn't one.
+    pub fn eat_identifier(&mut self) -> &'a str {
+        match self.peek() {
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                self.eat_while(|c| c.is_alphanumeric() || c == '_')
+            }
+            _ => "",
+        }
+    }
+
+    /// Mark the current position as the start of a token. The `Lexer`
+    /// calls this before asking the language for each token.
+    pub fn start_token(&mut self) {
+        self.token_start = self.pos;
+    }
+
+    pub fn token_span(&self) -> Span {
+        Span::new(self.token_start, self.pos)
+    }
+
+    /// The text consumed since the token started.
+    pub fn token_text(&self) -> &'a str {
+        &self.source[self.token_start..self.pos]
+    }
+}
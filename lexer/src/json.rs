@@ -0,0 +1,181 @@
+// JSON, lexed to the letter of RFC 8259, as the tiny-json chapter parses
+// it. The tiny-json parser reads bytes directly rather than tokens, so
+// this makes a useful cross-check: anything it accepts must lex without
+// errors.
+
+use crate::cursor::Cursor;
+use crate::keywords::Keywords;
+use crate::lex::{Language, LexError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
+    Comma,
+    /// A string, quotes and escapes included.
+    String,
+    Number,
+    True,
+    False,
+    Null,
+    Error(LexError),
+}
+
+impl From<LexError> for Kind {
+    fn from(e: LexError) -> Kind {
+        Kind::Error(e)
+    }
+}
+
+pub struct Json {
+    keywords: Keywords<Kind>,
+}
+
+impl Json {
+    pub fn new() -> Json {
+        Json {
+            keywords: Keywords::new(&[
+                ("true", Kind::True),
+                ("false", Kind::False),
+                ("null", Kind::Null),
+            ]),
+        }
+    }
+}
+
+impl Default for Json {
+    fn default() -> Json {
+        Json::new()
+    }
+}
+
+impl Language for Json {
+    type Kind = Kind;
+
+    fn lex(&mut self, cursor: &mut Cursor<'_>) -> Option<Kind> {
+        let c = cursor.peek()?;
+        let kind = match c {
+            // JSON allows exactly these four whitespace characters.
+            ' ' | '\t' | '\n' | '\r' => {
+                cursor.eat_while(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+                return None;
+            }
+            '{' => Kind::LeftBrace,
+            '}' => Kind::RightBrace,
+            '[' => Kind::LeftBracket,
+            ']' => Kind::RightBracket,
+            ':' => Kind::Colon,
+            ',' => Kind::Comma,
+            '"' => return Some(string(cursor)),
+            '-' | '0'..='9' => return Some(number(cursor)),
+            _ if c.is_ascii_alphabetic() => {
+                let word = cursor.eat_while(|c| c.is_ascii_alphanumeric());
+                return Some(
+                    self.keywords
+                        .get(word)
+                        .unwrap_or(Kind::Error(LexError::UnexpectedWord)),
+                );
+            }
+            _ => return None,
+        };
+        cursor.bump();
+        Some(kind)
+    }
+}
+
+/// A string runs to the next unescaped quote. After a bad escape or a raw
+/// control character, keep going to that quote anyway, so the whole string
+/// becomes one error token and lexing picks up cleanly after it.
+fn string(cursor: &mut Cursor) -> Kind {
+    cursor.bump();
+    let mut error = None;
+    loop {
+        let Some(c) = cursor.bump() else {
+            return Kind::Error(LexError::UnterminatedString);
+        };
+        match c {
+            '"' => break,
+            '\\' => {
+                if let Err(e) = escape(cursor) {
+                    error.get_or_insert(e);
+                }
+            }
+            '\n' => return Kind::Error(LexError::UnterminatedString),
+            _ if (c as u32) < 0x20 => {
+                error.get_or_insert(LexError::ControlCharacter);
+            }
+            _ => {}
+        }
+    }
+    error.map_or(Kind::String, Kind::Error)
+}
+
+fn escape(cursor: &mut Cursor) -> Result<(), LexError> {
+    match cursor.peek() {
+        Some('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't') => {
+            cursor.bump();
+            Ok(())
+        }
+        Some('u') => {
+            cursor.bump();
+            let unit = hex4(cursor)?;
+            match unit {
+                // A high surrogate must be followed by a low one.
+                0xD800..=0xDBFF => {
+                    if !cursor.eat_str("\\u") {
+                        return Err(LexError::InvalidEscape);
+                    }
+                    match hex4(cursor)? {
+                        0xDC00..=0xDFFF => Ok(()),
+                        _ => Err(LexError::InvalidEscape),
+                    }
+                }
+                0xDC00..=0xDFFF => Err(LexError::InvalidEscape),
+                _ => Ok(()),
+            }
+        }
+        _ => Err(LexError::InvalidEscape),
+    }
+}
+
+fn hex4(cursor: &mut Cursor) -> Result<u32, LexError> {
+    let mut value = 0;
+    for _ in 0..4 {
+        let digit = cursor
+            .peek()
+            .and_then(|c| c.to_digit(16))
+            .ok_or(LexError::InvalidEscape)?;
+        cursor.bump();
+        value = value * 16 + digit;
+    }
+    Ok(value)
+}
+
+/// `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`. A malformed number
+/// still swallows every character that could belong to one, so `01.e`
+/// is a single error rather than four tokens.
+fn number(cursor: &mut Cursor) -> Kind {
+    let digits = |cursor: &mut Cursor| cursor.eat_while(|c| c.is_ascii_digit()).len();
+    cursor.eat('-');
+    let mut valid = match digits(cursor) {
+        0 => false,
+        1 => true,
+        _ => !cursor.token_text().trim_start_matches('-').starts_with('0'),
+    };
+    if cursor.eat('.') {
+        valid &= digits(cursor) > 0;
+    }
+    if cursor.eat_if(|c| c == 'e' || c == 'E').is_some() {
+        cursor.eat_if(|c| c == '+' || c == '-');
+        valid &= digits(cursor) > 0;
+    }
+    let trailing = cursor.eat_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'));
+    if valid && trailing.is_empty() {
+        Kind::Number
+    } else {
+        Kind::Error(LexError::InvalidNumber)
+    }
+}
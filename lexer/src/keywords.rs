@@ -0,0 +1,33 @@
+/// A table of reserved words. Lexers scan an identifier first, then look
+/// it up here to see whether it's really a keyword; that's simpler and
+/// faster than trying every keyword at every position.
+#[derive(Clone, Debug)]
+pub struct Keywords<K> {
+    /// Sorted by word, for binary search.
+    table: Vec<(&'static str, K)>,
+}
+
+impl<K: Clone> Keywords<K> {
+    /// # Panics
+    ///
+    /// If a word appears twice.
+    pub fn new(entries: &[(&'static str, K)]) -> Keywords<K> {
+        let mut table = entries.to_vec();
+        table.sort_by_key(|&(word, _)| word);
+        if let Some(pair) = table.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            panic!("keyword {:?} appears twice", pair[0].0);
+        }
+        Keywords { table }
+    }
+
+    pub fn get(&self, word: &str) -> Option<K> {
+        self.table
+            .binary_search_by_key(&word, |&(word, _)| word)
+            .ok()
+            .map(|i| self.table[i].1.clone())
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.table.iter().map(|&(word, _)| word)
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::{self, Debug, Write};
+
+use crate::cursor::Cursor;
+use crate::span::{LineIndex, Span};
+
+/// The lexical errors common to most languages. A language's token kind
+/// wraps these in an error variant, so they appear in the token stream
+/// like any other token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LexError {
+    UnexpectedChar(char),
+    /// A word that looks like a keyword but isn't one, in a language
+    /// without identifiers.
+    UnexpectedWord,
+    UnterminatedString,
+    InvalidEscape,
+    /// A raw control character, such as a tab, inside a string.
+    ControlCharacter,
+    InvalidNumber,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LexError::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            LexError::UnexpectedWord => write!(f, "unexpected word"),
+            LexError::UnterminatedString => write!(f, "unterminated string"),
+            LexError::InvalidEscape => write!(f, "invalid escape sequence"),
+            LexError::ControlCharacter => write!(f, "control character in string"),
+            LexError::InvalidNumber => write!(f, "invalid number"),
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token<K> {
+    pub kind: K,
+    pub span: Span,
+}
+
+/// What a lexer needs to know about a language.
+pub trait Language {
+    type Kind: From<LexError>;
+
+    /// Lex the token at the cursor, which is never at the end of the
+    /// source. Return `None` after consuming something that isn't a token,
+    /// like whitespace or a comment.
+    ///
+    /// If this consumes nothing, the `Lexer` consumes one character itself
+    /// and reports it as unexpected, so a language need only handle the
+    /// characters it knows about.
+    fn lex(&mut self, cursor: &mut Cursor<'_>) -> Option<Self::Kind>;
+}
+
+/// An iterator over the tokens in a source, for any language.
+pub struct Lexer<'a, L> {
+    language: L,
+    cursor: Cursor<'a>,
+}
+
+impl<'a, L: Language> Lexer<'a, L> {
+    pub fn new(language: L, source: &'a str) -> Lexer<'a, L> {
+        Lexer {
+            language,
+            cursor: Cursor::new(source),
+        }
+    }
+}
+
+impl<L: Language> Iterator for Lexer<'_, L> {
+    type Item = Token<L::Kind>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.cursor.is_eof() {
+            self.cursor.start_token();
+            let kind = self.language.lex(&mut self.cursor);
+            // Insist on progress, so a language bug can't loop forever.
+            if self.cursor.token_span().is_empty() {
+                let c = self.cursor.bump().unwrap();
+                return Some(Token {
+                    kind: LexError::UnexpectedChar(c).into(),
+                    span: self.cursor.token_span(),
+                });
+            }
+            if let Some(kind) = kind {
+                return Some(Token {
+                    kind,
+                    span: self.cursor.token_span(),
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Lex all of `source`.
+pub fn tokenize<L: Language>(language: L, source: &str) -> Vec<Token<L::Kind>> {
+    Lexer::new(language, source).collect()
+}
+
+/// Describe a token stream one token per line, with its position, kind,
+/// and text, as in snapshot tests:
+///
+/// ```text
+/// 1:1     Let  "let"
+/// 1:5     Ident  "x"
+/// ```
+pub fn dump<K: Debug>(source: &str, tokens: &[Token<K>]) -> String {
+    let lines = LineIndex::new(source);
+    let mut out = String::new();
+    for token in tokens {
+        let position = lines.position(token.span.start).to_string();
+        writeln!(
+            out,
+            "{:<7} {:?}  {:?}",
+            position,
+            token.kind,
+            token.span.text(source)
+        )
+        .unwrap();
+    }
+    out
+}
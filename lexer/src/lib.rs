@@ -0,0 +1,21 @@
+// A toolkit for writing lexers, and two languages lexed with it.
+//
+// The toolkit supplies the parts every hand-written lexer needs: a
+// `Cursor` that steps through the source a character at a time, `Span`s
+// recording where each token came from, a `Keywords` table, and a
+// `Lexer` driver that turns anything it can't make sense of into an
+// error token and carries on, so that one typo doesn't hide the rest of
+// a file's problems. A language only has to say how to lex one token.
+
+mod cursor;
+mod keywords;
+mod lex;
+mod span;
+
+pub mod calc;
+pub mod json;
+
+pub use cursor::Cursor;
+pub use keywords::Keywords;
+pub use lex::{dump, tokenize, Language, LexError, Lexer, Token};
+pub use span::{LineIndex, Position, Span};
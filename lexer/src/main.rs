@@ -0,0 +1,50 @@
+use std::fs;
+use std::io::{self, Read};
+use std::{env, process};
+
+use lexer::calc::Calc;
+use lexer::json::Json;
+
+fn print_usage() {
+    eprintln!("lexer - print the tokens of a file");
+    eprintln!("Usage: lexer calc|json [FILE]");
+    eprintln!("FILE defaults to stdin.");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print_usage();
+        process::exit(0);
+    }
+    let (language, path) = match args.as_slice() {
+        [language] => (language, None),
+        [language, path] => (language, Some(path)),
+        _ => usage_error("expected a language and at most one file"),
+    };
+
+    let source = match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        }
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    });
+
+    let dump = match language.as_str() {
+        "calc" => lexer::dump(&source, &lexer::tokenize(Calc::new(), &source)),
+        "json" => lexer::dump(&source, &lexer::tokenize(Json::new(), &source)),
+        other => usage_error(&format!("unknown language {}", other)),
+    };
+    print!("{}", dump);
+}
@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::Path;
+
+use lexer::json::{Json, Kind};
+use lexer::{tokenize, Lexer};
+use proptest::prelude::*;
+
+fn has_errors(source: &str) -> bool {
+    Lexer::new(Json::new(), source).any(|token| matches!(token.kind, Kind::Error(_)))
+}
+
+/// The tiny-json chapter's conformance files. Whatever its parser accepts
+/// must lex cleanly; and where the lexer finds an error, the parser must
+/// have rejected the input too. (The converse doesn't hold: `[1,]` lexes
+/// fine but doesn't parse.)
+#[test]
+fn agrees_with_tiny_json() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tiny-json/tests/suite");
+    let mut checked = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let Ok(source) = fs::read_to_string(&path) else {
+            // The lexer works on `str`s; invalid UTF-8 is rejected before
+            // it gets a look.
+            continue;
+        };
+        let parsed = tiny_json::parse(&source).is_ok();
+        let lexed = !has_errors(&source);
+        if parsed {
+            assert!(lexed, "{} parses but doesn't lex", name);
+        }
+        if !lexed {
+            assert!(!parsed, "{} lexes with errors but parses", name);
+        }
+        if name.starts_with("y_") {
+            assert!(lexed, "{} should lex", name);
+        }
+        checked += 1;
+    }
+    assert!(checked > 50, "only {} suite files found", checked);
+}
+
+/// Numbers the lexer accepts are numbers tiny-json accepts, and vice versa.
+#[test]
+fn numbers_agree() {
+    for text in [
+        "0", "-0", "1", "-1", "10", "1.5", "0.5e10", "1E+2", "1e-2", "-0.0e0", "01", "-01", "1.",
+        ".5", "+1", "1e", "1e+", "0x1", "1.5.5", "--1", "-",
+    ] {
+        let tokens = tokenize(Json::new(), text);
+        let lexed = tokens.len() == 1 && tokens[0].kind == Kind::Number;
+        assert_eq!(lexed, tiny_json::parse(text).is_ok(), "{:?}", text);
+    }
+}
+
+proptest! {
+    /// Tokens come out in order, don't overlap, start and end on character
+    /// boundaries, and between them leave only whitespace.
+    #[test]
+    fn tokens_tile_the_source(source in r#"[\[\]{}:,"\\a-z0-9.eE+\- \n\tπ'u]{0,60}"#) {
+        let mut end = 0;
+        for token in tokenize(Json::new(), &source) {
+            prop_assert!(token.span.start >= end);
+            prop_assert!(!token.span.is_empty());
+            prop_assert!(source.is_char_boundary(token.span.start));
+            prop_assert!(source.is_char_boundary(token.span.end));
+            prop_assert!(source[end..token.span.start].chars().all(|c| " \t\n\r".contains(c)));
+            end = token.span.end;
+        }
+        prop_assert!(source[end..].chars().all(|c| " \t\n\r".contains(c)));
+    }
+}
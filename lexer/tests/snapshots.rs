@@ -0,0 +1,50 @@
+// Each file in `tests/snapshots` is lexed as the language its extension
+// names, and the token stream compared with `FILE.tokens`. After a
+// deliberate change to a lexer, run the tests with `UPDATE_SNAPSHOTS=1` to
+// rewrite the expected streams, and review the diff.
+
+use std::fs;
+use std::path::Path;
+
+use lexer::calc::Calc;
+use lexer::json::Json;
+
+#[test]
+fn snapshots() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut inputs: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext != "tokens"))
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+
+    let mut failures = Vec::new();
+    for input in inputs {
+        let source = fs::read_to_string(&input).unwrap();
+        let dump = match input.extension().unwrap().to_str().unwrap() {
+            "calc" => lexer::dump(&source, &lexer::tokenize(Calc::new(), &source)),
+            "json" => lexer::dump(&source, &lexer::tokenize(Json::new(), &source)),
+            other => panic!("no language for .{} files", other),
+        };
+        let mut expected_path = input.clone().into_os_string();
+        expected_path.push(".tokens");
+        if update {
+            fs::write(&expected_path, &dump).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if dump != expected {
+            eprintln!(
+                "--- {}\n--- expected:\n{}--- got:\n{}",
+                input.display(),
+                expected,
+                dump
+            );
+            failures.push(input);
+        }
+    }
+    assert!(failures.is_empty(), "mismatched tokens for {:?}", failures);
+}
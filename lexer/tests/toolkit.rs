@@ -0,0 +1,114 @@
+use lexer::{tokenize, Cursor, Keywords, Language, LexError, LineIndex, Position, Span, Token};
+
+#[test]
+fn cursor_steps_through_characters() {
+    let mut cursor = Cursor::new("héllo, wörld");
+    assert_eq!(cursor.peek(), Some('h'));
+    assert_eq!(cursor.peek_second(), Some('é'));
+    cursor.start_token();
+    assert_eq!(cursor.eat_identifier(), "héllo");
+    assert_eq!(cursor.token_text(), "héllo");
+    assert_eq!(cursor.token_span(), Span::new(0, 6));
+
+    assert!(!cursor.eat('x'));
+    assert!(cursor.eat(','));
+    assert!(cursor.eat_str(" w"));
+    assert_eq!(cursor.eat_while(|c| c != 'l'), "ör");
+    assert_eq!(cursor.bump(), Some('l'));
+    assert_eq!(cursor.eat_if(|c| c.is_ascii_digit()), None);
+    assert_eq!(cursor.rest(), "d");
+    assert_eq!(cursor.bump(), Some('d'));
+    assert!(cursor.is_eof());
+    assert_eq!(cursor.bump(), None);
+    assert_eq!(cursor.eat_identifier(), "");
+}
+
+#[test]
+fn spans_and_positions() {
+    let source = "ab\ncdé\n\nf";
+    let lines = LineIndex::new(source);
+    let at = |offset| lines.position(offset);
+    assert_eq!(at(0), Position { line: 1, column: 1 });
+    assert_eq!(at(2), Position { line: 1, column: 3 });
+    assert_eq!(at(3), Position { line: 2, column: 1 });
+    // `é` is two bytes, but one column.
+    assert_eq!(at(7), Position { line: 2, column: 4 });
+    assert_eq!(at(8), Position { line: 3, column: 1 });
+    assert_eq!(at(9), Position { line: 4, column: 1 });
+    assert_eq!(at(9).to_string(), "4:1");
+
+    let span = Span::new(3, 5).to(Span::new(0, 1));
+    assert_eq!(span, Span::new(0, 5));
+    assert_eq!(span.text(source), "ab\ncd");
+    assert_eq!(span.to_string(), "0..5");
+}
+
+#[test]
+fn keyword_table() {
+    let keywords = Keywords::new(&[("while", 3), ("if", 1), ("else", 2)]);
+    assert_eq!(keywords.get("if"), Some(1));
+    assert_eq!(keywords.get("while"), Some(3));
+    assert_eq!(keywords.get("whilst"), None);
+    assert_eq!(
+        keywords.words().collect::<Vec<_>>(),
+        ["else", "if", "while"]
+    );
+}
+
+#[test]
+#[should_panic(expected = "appears twice")]
+fn duplicate_keywords() {
+    Keywords::new(&[("if", 1), ("if", 2)]);
+}
+
+/// A language that only knows the letter `a` and spaces.
+struct JustA;
+
+#[derive(Debug, PartialEq)]
+enum A {
+    A,
+    Error(LexError),
+}
+
+impl From<LexError> for A {
+    fn from(e: LexError) -> A {
+        A::Error(e)
+    }
+}
+
+impl Language for JustA {
+    type Kind = A;
+
+    fn lex(&mut self, cursor: &mut Cursor<'_>) -> Option<A> {
+        if cursor.eat(' ') {
+            return None;
+        }
+        cursor.eat('a').then_some(A::A)
+    }
+}
+
+#[test]
+fn unknown_characters_become_error_tokens() {
+    let tokens = tokenize(JustA, "a bé a");
+    assert_eq!(
+        tokens,
+        vec![
+            Token {
+                kind: A::A,
+                span: Span::new(0, 1)
+            },
+            Token {
+                kind: A::Error(LexError::UnexpectedChar('b')),
+                span: Span::new(2, 3)
+            },
+            Token {
+                kind: A::Error(LexError::UnexpectedChar('é')),
+                span: Span::new(3, 5)
+            },
+            Token {
+                kind: A::A,
+                span: Span::new(6, 7)
+            },
+        ]
+    );
+}
@@ -0,0 +1,248 @@
+// `#[derive(ToJson)]`: a procedural macro that generates `macros::ToJson`
+// impls, converting a struct or enum into the `macros::Json` value type.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, Ident, LitStr, Result};
+
+/// Derive `macros::ToJson`.
+///
+/// * Structs with named fields become JSON objects; tuple structs become
+///   arrays, except a single-field "newtype" which serializes as its field;
+///   unit structs become `null`.
+/// * Unit enum variants become strings holding the variant name. Variants
+///   with data become a single-entry object `{ "Variant": ... }`.
+/// * `#[json(rename = "name")]` changes the key used for a field or variant.
+/// * `#[json(skip)]` leaves a field out of the output.
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => expand_struct(&data.fields)?,
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    expand_variant(name, &variant.ident, &variant.attrs, &variant.fields)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if arms.is_empty() {
+                // `match self {}` isn't exhaustive, because `&Never` could
+                // be a dangling reference; matching the value itself says
+                // that there can't be one.
+                quote! { match *self {} }
+            } else {
+                quote! {
+                    match self {
+                        #( #arms )*
+                    }
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "ToJson cannot be derived for unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::macros::ToJson));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::macros::ToJson for #name #ty_generics #where_clause {
+            fn to_json(&self) -> ::macros::Json {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_struct(fields: &Fields) -> Result<TokenStream2> {
+    match fields {
+        Fields::Named(named) => {
+            let mut inserts = Vec::new();
+            for field in &named.named {
+                let options = FieldOptions::parse(&field.attrs)?;
+                if options.skip {
+                    continue;
+                }
+                let ident = field.ident.as_ref().unwrap();
+                let key = options.key(ident);
+                inserts.push(quote! {
+                    fields.insert(::std::string::String::from(#key), ::macros::ToJson::to_json(&self.#ident));
+                });
+            }
+            Ok(object(inserts))
+        }
+        Fields::Unnamed(unnamed) => {
+            let mut elements = Vec::new();
+            for (index, field) in unnamed.unnamed.iter().enumerate() {
+                reject_rename(&field.attrs)?;
+                if FieldOptions::parse(&field.attrs)?.skip {
+                    continue;
+                }
+                let index = syn::Index::from(index);
+                elements.push(quote! { ::macros::ToJson::to_json(&self.#index) });
+            }
+            Ok(sequence(unnamed.unnamed.len(), elements))
+        }
+        Fields::Unit => Ok(quote! { ::macros::Json::Null }),
+    }
+}
+
+fn expand_variant(
+    enum_name: &Ident,
+    variant: &Ident,
+    attrs: &[Attribute],
+    fields: &Fields,
+) -> Result<TokenStream2> {
+    let options = FieldOptions::parse(attrs)?;
+    if options.skip {
+        return Err(Error::new_spanned(
+            variant,
+            "enum variants cannot be skipped",
+        ));
+    }
+    let tag = options.key(variant);
+    let tagged = |value: TokenStream2| {
+        quote! {
+            {
+                let mut fields = ::std::boxed::Box::new(::std::collections::HashMap::new());
+                fields.insert(::std::string::String::from(#tag), #value);
+                ::macros::Json::Object(fields)
+            }
+        }
+    };
+
+    match fields {
+        Fields::Unit => Ok(quote! {
+            #enum_name::#variant => ::macros::Json::String(::std::string::String::from(#tag)),
+        }),
+        Fields::Unnamed(unnamed) => {
+            let bindings: Vec<_> = (0..unnamed.unnamed.len())
+                .map(|i| format_ident!("__field{}", i))
+                .collect();
+            let mut elements = Vec::new();
+            for (binding, field) in bindings.iter().zip(&unnamed.unnamed) {
+                reject_rename(&field.attrs)?;
+                if !FieldOptions::parse(&field.attrs)?.skip {
+                    elements.push(quote! { ::macros::ToJson::to_json(#binding) });
+                }
+            }
+            let value = tagged(sequence(bindings.len(), elements));
+            Ok(quote! {
+                #[allow(unused_variables)]
+                #enum_name::#variant( #( #bindings ),* ) => #value,
+            })
+        }
+        Fields::Named(named) => {
+            let mut bindings = Vec::new();
+            let mut inserts = Vec::new();
+            for field in &named.named {
+                let ident = field.ident.as_ref().unwrap();
+                bindings.push(ident);
+                let options = FieldOptions::parse(&field.attrs)?;
+                if options.skip {
+                    continue;
+                }
+                let key = options.key(ident);
+                inserts.push(quote! {
+                    fields.insert(::std::string::String::from(#key), ::macros::ToJson::to_json(#ident));
+                });
+            }
+            let value = tagged(object(inserts));
+            Ok(quote! {
+                #[allow(unused_variables)]
+                #enum_name::#variant { #( #bindings ),* } => #value,
+            })
+        }
+    }
+}
+
+fn object(inserts: Vec<TokenStream2>) -> TokenStream2 {
+    quote! {
+        {
+            #[allow(unused_mut)]
+            let mut fields = ::std::boxed::Box::new(::std::collections::HashMap::new());
+            #( #inserts )*
+            ::macros::Json::Object(fields)
+        }
+    }
+}
+
+// A newtype serializes as its only field; anything else becomes an array.
+fn sequence(field_count: usize, mut elements: Vec<TokenStream2>) -> TokenStream2 {
+    if field_count == 1 && elements.len() == 1 {
+        elements.pop().unwrap()
+    } else {
+        quote! { ::macros::Json::Array(::std::vec![ #( #elements ),* ]) }
+    }
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<LitStr>,
+    skip: bool,
+}
+
+impl FieldOptions {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = FieldOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown json attribute; expected `rename = \"...\"` or `skip`"))
+                }
+            })?;
+        }
+        if options.skip {
+            if let Some(rename) = &options.rename {
+                return Err(Error::new_spanned(
+                    rename,
+                    "`rename` has no effect on a skipped field",
+                ));
+            }
+        }
+        Ok(options)
+    }
+
+    fn key(&self, ident: &Ident) -> LitStr {
+        match &self.rename {
+            Some(rename) => rename.clone(),
+            None => {
+                let name = ident.to_string();
+                LitStr::new(name.strip_prefix("r#").unwrap_or(&name), ident.span())
+            }
+        }
+    }
+}
+
+fn reject_rename(attrs: &[Attribute]) -> Result<()> {
+    match FieldOptions::parse(attrs)?.rename {
+        Some(rename) => Err(Error::new_spanned(
+            rename,
+            "tuple fields have no names to rename",
+        )),
+        None => Ok(()),
+    }
+}
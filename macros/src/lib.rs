@@ -0,0 +1,325 @@
+// The `json!` macro from the book's Macros chapter, together with the `Json`
+// value type it builds and a small serializer for printing the result.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+
+/// `#[derive(ToJson)]`, implemented by the `macros-derive` procedural macro
+/// crate.
+pub use macros_derive::ToJson;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Json {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Box<HashMap<String, Json>>),
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Boolean(b)
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<'a> From<&'a str> for Json {
+    fn from(s: &'a str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(items: Vec<T>) -> Json {
+        Json::Array(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        match value {
+            Some(v) => v.into(),
+            None => Json::Null,
+        }
+    }
+}
+
+// Every numeric type converts the same way, so write the impls with a macro
+// rather than by hand.
+macro_rules! impl_from_num_for_json {
+    ( $( $t:ident )* ) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n: $t) -> Json {
+                    Json::Number(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_num_for_json!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize f32 f64);
+
+/// Conversion into a `Json` value, borrowing rather than consuming `self`.
+///
+/// Implemented here for the standard scalar and collection types; use
+/// `#[derive(ToJson)]` for your own structs and enums.
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl ToJson for Json {
+    fn to_json(&self) -> Json {
+        self.clone()
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Json {
+        Json::Boolean(*self)
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Json {
+        Json::String(self.to_string())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+macro_rules! impl_to_json_for_num {
+    ( $( $t:ident )* ) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Json {
+                    Json::Number(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_for_num!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize f32 f64);
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Json {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Json {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        match self {
+            Some(v) => v.to_json(),
+            None => Json::Null,
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for HashMap<String, T> {
+    fn to_json(&self) -> Json {
+        Json::Object(Box::new(
+            self.iter().map(|(k, v)| (k.clone(), v.to_json())).collect(),
+        ))
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> Json {
+        Json::Object(Box::new(
+            self.iter().map(|(k, v)| (k.clone(), v.to_json())).collect(),
+        ))
+    }
+}
+
+/// Build a `Json` value using JSON-like syntax.
+///
+/// Keys may be string literals or any parenthesized expression that implements
+/// `ToString`; values may be nested arrays and objects, `null`, or any single
+/// token tree convertible with `Json::from`. Wrap longer Rust expressions in
+/// parentheses to interpolate them.
+#[macro_export]
+macro_rules! json {
+    (null) => {
+        $crate::Json::Null
+    };
+    ([ $( $element:tt ),* $(,)? ]) => {
+        $crate::Json::Array(vec![ $( $crate::json!($element) ),* ])
+    };
+    ({ $( $key:tt : $value:tt ),* $(,)? }) => {
+        {
+            #[allow(unused_mut)]
+            let mut fields = ::std::boxed::Box::new(::std::collections::HashMap::new());
+            $(
+                fields.insert(::std::string::ToString::to_string(&$key), $crate::json!($value));
+            )*
+            $crate::Json::Object(fields)
+        }
+    };
+    (( $interpolated:expr )) => {
+        $crate::Json::from($interpolated)
+    };
+    ($other:tt) => {
+        $crate::Json::from($other)
+    };
+}
+
+impl Json {
+    /// Serialize on a single line with no insignificant whitespace.
+    pub fn to_compact_string(&self) -> String {
+        let mut out = String::new();
+        self.write_compact(&mut out).unwrap();
+        out
+    }
+
+    /// Serialize with one element per line, indenting nested values by
+    /// `indent` spaces per level.
+    pub fn to_pretty_string(&self, indent: usize) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, indent, 0).unwrap();
+        out
+    }
+
+    fn write_compact<W: Write>(&self, out: &mut W) -> fmt::Result {
+        match self {
+            Json::Array(elements) => {
+                out.write_char('[')?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        out.write_char(',')?;
+                    }
+                    element.write_compact(out)?;
+                }
+                out.write_char(']')
+            }
+            Json::Object(fields) => {
+                out.write_char('{')?;
+                for (i, (key, value)) in sorted_fields(fields).into_iter().enumerate() {
+                    if i > 0 {
+                        out.write_char(',')?;
+                    }
+                    write_string(out, key)?;
+                    out.write_char(':')?;
+                    value.write_compact(out)?;
+                }
+                out.write_char('}')
+            }
+            scalar => scalar.write_scalar(out),
+        }
+    }
+
+    fn write_pretty<W: Write>(&self, out: &mut W, indent: usize, depth: usize) -> fmt::Result {
+        let pad = |out: &mut W, depth: usize| write!(out, "{:width$}", "", width = indent * depth);
+        match self {
+            Json::Array(elements) if elements.is_empty() => out.write_str("[]"),
+            Json::Array(elements) => {
+                out.write_str("[\n")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        out.write_str(",\n")?;
+                    }
+                    pad(out, depth + 1)?;
+                    element.write_pretty(out, indent, depth + 1)?;
+                }
+                out.write_char('\n')?;
+                pad(out, depth)?;
+                out.write_char(']')
+            }
+            Json::Object(fields) if fields.is_empty() => out.write_str("{}"),
+            Json::Object(fields) => {
+                out.write_str("{\n")?;
+                for (i, (key, value)) in sorted_fields(fields).into_iter().enumerate() {
+                    if i > 0 {
+                        out.write_str(",\n")?;
+                    }
+                    pad(out, depth + 1)?;
+                    write_string(out, key)?;
+                    out.write_str(": ")?;
+                    value.write_pretty(out, indent, depth + 1)?;
+                }
+                out.write_char('\n')?;
+                pad(out, depth)?;
+                out.write_char('}')
+            }
+            scalar => scalar.write_scalar(out),
+        }
+    }
+
+    fn write_scalar<W: Write>(&self, out: &mut W) -> fmt::Result {
+        match self {
+            Json::Null => out.write_str("null"),
+            Json::Boolean(b) => write!(out, "{}", b),
+            // JSON has no representation for NaN or the infinities.
+            Json::Number(n) if !n.is_finite() => out.write_str("null"),
+            Json::Number(n) => write!(out, "{}", n),
+            Json::String(s) => write_string(out, s),
+            Json::Array(_) | Json::Object(_) => unreachable!("not a scalar"),
+        }
+    }
+}
+
+// `HashMap` iteration order is unspecified; sort so output is reproducible.
+fn sorted_fields(fields: &HashMap<String, Json>) -> Vec<(&String, &Json)> {
+    let mut sorted: Vec<_> = fields.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(b.0));
+    sorted
+}
+
+fn write_string<W: Write>(out: &mut W, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{08}' => out.write_str("\\b")?,
+            '\u{0c}' => out.write_str("\\f")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// `{}` prints compact JSON; `{:#}` pretty-prints with two-space indentation.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            self.write_pretty(f, 2, 0)
+        } else {
+            self.write_compact(f)
+        }
+    }
+}
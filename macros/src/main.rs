@@ -0,0 +1,45 @@
+use macros::{json, ToJson};
+
+#[derive(ToJson)]
+struct Student {
+    name: String,
+    #[json(rename = "classOf")]
+    class_of: u32,
+    #[json(skip)]
+    #[allow(dead_code)]
+    student_id: u64,
+}
+
+fn main() {
+    let width = 4.0;
+    let desc = json!({
+        "width": width,
+        "height": (width * 9.0 / 4.0),
+        "tags": ["rust", "macros", null],
+        "published": true
+    });
+
+    println!("Compact: {}", desc);
+    println!("Pretty:\n{:#}", desc);
+
+    let students = json!([
+        {
+            "name": "Jim Blandy",
+            "class_of": 1926,
+            "major": "Tibetan throat singing"
+        },
+        {
+            "name": "Jason Orendorff",
+            "class_of": 1702,
+            "major": "Knots"
+        }
+    ]);
+    println!("Students:\n{}", students.to_pretty_string(4));
+
+    let derived = Student {
+        name: "Ferris".to_string(),
+        class_of: 2015,
+        student_id: 42,
+    };
+    println!("Derived: {}", derived.to_json());
+}
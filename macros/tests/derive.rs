@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use macros::{json, Json, ToJson};
+
+#[derive(ToJson)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(ToJson)]
+struct User {
+    #[json(rename = "userName")]
+    name: String,
+    age: u32,
+    #[json(skip)]
+    #[allow(dead_code)]
+    password: String,
+    tags: Vec<&'static str>,
+    nickname: Option<String>,
+    home: Point,
+}
+
+#[derive(ToJson)]
+struct Meters(f64);
+
+#[derive(ToJson)]
+struct Pair(i32, &'static str);
+
+#[derive(ToJson)]
+struct Marker;
+
+#[derive(ToJson)]
+struct Wrapper<T> {
+    value: T,
+}
+
+#[derive(ToJson)]
+enum Shape {
+    Empty,
+    #[json(rename = "circle")]
+    Circle(f64),
+    Segment(Point, Point),
+    Rect {
+        width: u32,
+        #[json(rename = "h")]
+        height: u32,
+        #[json(skip)]
+        label: &'static str,
+    },
+}
+
+#[test]
+fn named_struct_becomes_object() {
+    assert_eq!(
+        Point { x: 1, y: -2 }.to_json(),
+        json!({ "x": 1, "y": (-2) })
+    );
+}
+
+#[test]
+fn rename_skip_and_nested_fields() {
+    let user = User {
+        name: "ferris".to_string(),
+        age: 7,
+        password: "hunter2".to_string(),
+        tags: vec!["crab", "mascot"],
+        nickname: None,
+        home: Point { x: 0, y: 0 },
+    };
+    assert_eq!(
+        user.to_json(),
+        json!({
+            "userName": "ferris",
+            "age": 7,
+            "tags": ["crab", "mascot"],
+            "nickname": null,
+            "home": { "x": 0, "y": 0 }
+        })
+    );
+}
+
+#[test]
+fn tuple_and_unit_structs() {
+    assert_eq!(Meters(2.5).to_json(), json!(2.5));
+    assert_eq!(Pair(1, "one").to_json(), json!([1, "one"]));
+    assert_eq!(Marker.to_json(), Json::Null);
+}
+
+#[test]
+fn generic_struct() {
+    assert_eq!(
+        Wrapper { value: vec![true] }.to_json(),
+        json!({ "value": [true] })
+    );
+}
+
+#[test]
+fn enum_variants_are_externally_tagged() {
+    assert_eq!(Shape::Empty.to_json(), json!("Empty"));
+    assert_eq!(Shape::Circle(1.5).to_json(), json!({ "circle": 1.5 }));
+    assert_eq!(
+        Shape::Segment(Point { x: 0, y: 0 }, Point { x: 3, y: 4 }).to_json(),
+        json!({ "Segment": [{ "x": 0, "y": 0 }, { "x": 3, "y": 4 }] })
+    );
+    assert_eq!(
+        Shape::Rect {
+            width: 2,
+            height: 3,
+            label: "ignored"
+        }
+        .to_json(),
+        json!({ "Rect": { "width": 2, "h": 3 } })
+    );
+}
+
+#[test]
+fn std_impls() {
+    let mut scores = HashMap::new();
+    scores.insert("a".to_string(), 1);
+    assert_eq!(scores.to_json(), json!({ "a": 1 }));
+    assert_eq!(Some(Box::new(3u8)).to_json(), json!(3));
+    assert_eq!("str".to_json(), json!("str"));
+}
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass-*.rs");
+    t.compile_fail("tests/ui/fail-*.rs");
+}
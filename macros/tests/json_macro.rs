@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use macros::{json, Json};
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(Box::new(
+        fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect::<HashMap<_, _>>(),
+    ))
+}
+
+#[test]
+fn json_null() {
+    assert_eq!(json!(null), Json::Null);
+}
+
+#[test]
+fn json_scalars() {
+    assert_eq!(json!(true), Json::Boolean(true));
+    assert_eq!(json!(false), Json::Boolean(false));
+    assert_eq!(json!(1), Json::Number(1.0));
+    // `-7` is two tokens, so negative literals need parentheses.
+    assert_eq!(json!((-7)), Json::Number(-7.0));
+    assert_eq!(json!(2.5), Json::Number(2.5));
+    assert_eq!(json!("hi"), Json::String("hi".to_string()));
+}
+
+#[test]
+fn json_array_with_json_element() {
+    let macro_generated_value = json!([
+        // valid JSON that doesn't match `$element:expr`
+        {
+            "pitch": 440.0
+        }
+    ]);
+    let hand_coded_value = Json::Array(vec![object(vec![("pitch", Json::Number(440.0))])]);
+    assert_eq!(macro_generated_value, hand_coded_value);
+}
+
+#[test]
+fn json_empty_containers() {
+    assert_eq!(json!([]), Json::Array(vec![]));
+    assert_eq!(json!({}), object(vec![]));
+}
+
+#[test]
+fn json_trailing_commas() {
+    assert_eq!(json!([1, 2,]), json!([1, 2]));
+    assert_eq!(json!({ "a": 1, }), json!({ "a": 1 }));
+}
+
+#[test]
+fn json_nested_structure() {
+    let value = json!({
+        "name": "Larry",
+        "tags": ["a", ["b", null]],
+        "meta": { "depth": { "level": 3 } }
+    });
+    let expected = object(vec![
+        ("name", Json::String("Larry".to_string())),
+        (
+            "tags",
+            Json::Array(vec![
+                Json::String("a".to_string()),
+                Json::Array(vec![Json::String("b".to_string()), Json::Null]),
+            ]),
+        ),
+        (
+            "meta",
+            object(vec![("depth", object(vec![("level", Json::Number(3.0))]))]),
+        ),
+    ]);
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn json_interpolates_variables_and_expressions() {
+    let width = 4.0;
+    let name = String::from("box");
+    let desc = json!({
+        "width": width,
+        "height": (width * 9.0 / 4.0),
+        "name": name
+    });
+    assert_eq!(
+        desc,
+        object(vec![
+            ("width", Json::Number(4.0)),
+            ("height", Json::Number(9.0)),
+            ("name", Json::String("box".to_string())),
+        ])
+    );
+}
+
+#[test]
+fn json_computed_keys() {
+    let key = "dynamic";
+    let value = json!({ key: 1, (format!("k{}", 2)): 2 });
+    assert_eq!(
+        value,
+        object(vec![
+            ("dynamic", Json::Number(1.0)),
+            ("k2", Json::Number(2.0))
+        ])
+    );
+}
+
+#[test]
+fn json_macro_is_hygienic() {
+    // A local named `fields` must not collide with the macro's temporary.
+    let fields = "OOPS";
+    let role = json!({
+        "name": "Jim Blandy",
+        "actor": fields
+    });
+    assert_eq!(
+        role,
+        object(vec![
+            ("name", Json::String("Jim Blandy".to_string())),
+            ("actor", Json::String("OOPS".to_string())),
+        ])
+    );
+}
+
+#[test]
+fn from_impls_cover_numeric_types() {
+    assert_eq!(Json::from(3u8), Json::Number(3.0));
+    assert_eq!(Json::from(-3i64), Json::Number(-3.0));
+    assert_eq!(Json::from(1.5f32), Json::Number(1.5));
+    assert_eq!(Json::from(10usize), Json::Number(10.0));
+    assert_eq!(
+        Json::from(vec![1, 2]),
+        Json::Array(vec![Json::Number(1.0), Json::Number(2.0)])
+    );
+    assert_eq!(Json::from(None::<i32>), Json::Null);
+    assert_eq!(Json::from(Some("x")), Json::String("x".to_string()));
+}
+
+#[test]
+fn compact_output() {
+    let value = json!({ "b": [1, true, null], "a": "x" });
+    assert_eq!(value.to_compact_string(), r#"{"a":"x","b":[1,true,null]}"#);
+    assert_eq!(value.to_string(), value.to_compact_string());
+}
+
+#[test]
+fn pretty_output() {
+    let value = json!({ "list": [1, 2], "empty": [], "obj": {} });
+    let expected = "\
+{
+  \"empty\": [],
+  \"list\": [
+    1,
+    2
+  ],
+  \"obj\": {}
+}";
+    assert_eq!(value.to_pretty_string(2), expected);
+    assert_eq!(format!("{:#}", value), expected);
+}
+
+#[test]
+fn string_escaping() {
+    let value = json!("quote \" backslash \\ newline \n tab \t bell \u{7}");
+    assert_eq!(
+        value.to_compact_string(),
+        r#""quote \" backslash \\ newline \n tab \t bell \u0007""#
+    );
+}
+
+#[test]
+fn non_finite_numbers_serialize_as_null() {
+    assert_eq!(json!((f64::NAN)).to_compact_string(), "null");
+    assert_eq!(json!((f64::INFINITY)).to_compact_string(), "null");
+    assert_eq!(json!(0.25).to_compact_string(), "0.25");
+}
@@ -0,0 +1,10 @@
+use macros::ToJson;
+
+struct Opaque;
+
+#[derive(ToJson)]
+struct Holder {
+    inner: Opaque,
+}
+
+fn main() {}
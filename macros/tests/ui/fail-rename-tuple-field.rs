@@ -0,0 +1,6 @@
+use macros::ToJson;
+
+#[derive(ToJson)]
+struct Celsius(#[json(rename = "degrees")] f64);
+
+fn main() {}
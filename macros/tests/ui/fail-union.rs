@@ -0,0 +1,9 @@
+use macros::ToJson;
+
+#[derive(ToJson)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use macros::ToJson;
+
+#[derive(ToJson)]
+struct Config {
+    #[json(flatten)]
+    inner: u32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use macros::{json, ToJson};
+
+/// No value of this type can exist, but it can still be serialized in the
+/// places it might appear.
+#[derive(ToJson)]
+enum Never {}
+
+fn main() {
+    let nothing: Option<Never> = None;
+    assert_eq!(nothing.to_json(), json!(null));
+}
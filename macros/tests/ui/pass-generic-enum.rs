@@ -0,0 +1,15 @@
+use macros::ToJson;
+
+#[derive(ToJson)]
+enum Tree<T> {
+    Leaf(T),
+    Node { left: Box<Tree<T>>, right: Box<Tree<T>> },
+}
+
+fn main() {
+    let tree = Tree::Node {
+        left: Box::new(Tree::Leaf(1)),
+        right: Box::new(Tree::Leaf(2)),
+    };
+    let _ = tree.to_json();
+}
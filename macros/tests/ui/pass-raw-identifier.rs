@@ -0,0 +1,10 @@
+use macros::{json, ToJson};
+
+#[derive(ToJson)]
+struct Token {
+    r#type: &'static str,
+}
+
+fn main() {
+    assert_eq!(Token { r#type: "ident" }.to_json(), json!({ "type": "ident" }));
+}
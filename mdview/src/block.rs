@@ -0,0 +1,413 @@
+// Block parsing.
+//
+// Each kind of block is recognized by the start of its first line: `#`
+// for a heading, a fence of backticks, a list marker, and so on. Lines
+// that don't start anything else make up paragraphs. Block quotes and
+// list items are containers: their lines, with the `>` or the item's
+// indentation stripped off, are parsed again as a document of their own.
+//
+// Not supported: reference-style links, tables, raw HTML and entities
+// (both are escaped, so they show up as typed), and the lazier corners of
+// CommonMark's continuation rules.
+
+use crate::inline::{parse_inlines, Inline};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    Paragraph(Vec<Inline>),
+    CodeBlock {
+        /// The first word after the opening fence, naming the language.
+        info: Option<String>,
+        code: String,
+    },
+    BlockQuote(Vec<Block>),
+    List {
+        /// The first item's number, for an ordered list.
+        start: Option<u64>,
+        /// Whether the items' paragraphs are rendered without `<p>` tags:
+        /// true unless blank lines separate the items or their contents.
+        tight: bool,
+        items: Vec<Vec<Block>>,
+    },
+    ThematicBreak,
+}
+
+/// Parse a Markdown document into blocks.
+pub fn parse(text: &str) -> Vec<Block> {
+    let lines = text.lines().map(expand_tabs).collect();
+    Parser { lines, pos: 0 }.blocks()
+}
+
+/// Replace tabs in a line's indentation with spaces, to the next multiple
+/// of four columns, so that indentation can be measured in bytes.
+fn expand_tabs(line: &str) -> String {
+    let body = line.trim_start_matches([' ', '\t']);
+    let mut expanded = String::new();
+    for c in line[..line.len() - body.len()].chars() {
+        if c == '\t' {
+            let width = 4 - expanded.len() % 4;
+            expanded.extend(std::iter::repeat_n(' ', width));
+        } else {
+            expanded.push(c);
+        }
+    }
+    expanded.push_str(body);
+    expanded
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// The line with up to three spaces of indentation removed, or `None` if
+/// it's indented four or more, which makes it code.
+fn unindent(line: &str) -> Option<&str> {
+    (indent(line) < 4).then(|| line.trim_start_matches(' '))
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let line = unindent(line)?;
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    // A closing run of `#`s is dropped, if there's a space before it.
+    let content = rest.trim();
+    let without_closing = content.trim_end_matches('#');
+    let content = if without_closing.is_empty() || without_closing.ends_with(' ') {
+        without_closing.trim_end()
+    } else {
+        content
+    };
+    Some((level as u8, content))
+}
+
+fn thematic_break(line: &str) -> bool {
+    let Some(line) = unindent(line) else {
+        return false;
+    };
+    let mut marks = line.chars().filter(|c| !c.is_whitespace());
+    match marks.next() {
+        Some(mark @ ('*' | '-' | '_')) => {
+            let rest: Vec<char> = marks.collect();
+            rest.len() >= 2 && rest.iter().all(|&c| c == mark)
+        }
+        _ => false,
+    }
+}
+
+fn block_quote(line: &str) -> Option<&str> {
+    let rest = unindent(line)?.strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+struct Fence {
+    ch: char,
+    len: usize,
+    indent: usize,
+    info: Option<String>,
+}
+
+fn fence_open(line: &str) -> Option<Fence> {
+    let body = unindent(line)?;
+    let ch = body.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = body.len() - body.trim_start_matches(ch).len();
+    let info = body[len..].trim();
+    if len < 3 || (ch == '`' && info.contains('`')) {
+        return None;
+    }
+    Some(Fence {
+        ch,
+        len,
+        indent: indent(line),
+        info: info.split_whitespace().next().map(str::to_string),
+    })
+}
+
+fn fence_close(line: &str, fence: &Fence) -> bool {
+    let Some(body) = unindent(line) else {
+        return false;
+    };
+    let rest = body.trim_start_matches(fence.ch);
+    body.len() - rest.len() >= fence.len && rest.trim().is_empty()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ListKind {
+    Bullet(char),
+    /// The character after the number: `.` or `)`.
+    Ordered(char),
+}
+
+struct Marker {
+    kind: ListKind,
+    number: Option<u64>,
+    /// The column where the item's content starts: continuation lines
+    /// must be indented at least this far.
+    content_indent: usize,
+    /// Whether anything follows the marker on its line.
+    has_content: bool,
+}
+
+fn list_marker(line: &str) -> Option<Marker> {
+    let body = unindent(line)?;
+    let (kind, number, marker_len) = match body.chars().next()? {
+        c @ ('-' | '*' | '+') => (ListKind::Bullet(c), None, 1),
+        _ => {
+            let digits = body.len() - body.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let delimiter = body[digits..].chars().next()?;
+            if !(1..=9).contains(&digits) || !matches!(delimiter, '.' | ')') {
+                return None;
+            }
+            let number = body[..digits].parse().ok()?;
+            (ListKind::Ordered(delimiter), Some(number), digits + 1)
+        }
+    };
+    let rest = &body[marker_len..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let spaces = indent(rest);
+    let has_content = !is_blank(rest);
+    // One to four spaces after the marker belong to it; with five or more,
+    // the content is indented code, and only the first space counts.
+    let spacing = if (1..=4).contains(&spaces) && has_content {
+        spaces
+    } else {
+        1
+    };
+    Some(Marker {
+        kind,
+        number,
+        content_indent: indent(line) + marker_len + spacing,
+        has_content,
+    })
+}
+
+/// Whether a line starts a new block, and so can interrupt a paragraph
+/// rather than continue it.
+fn interrupts_paragraph(line: &str) -> bool {
+    heading(line).is_some()
+        || fence_open(line).is_some()
+        || thematic_break(line)
+        || block_quote(line).is_some()
+        || list_marker(line).is_some_and(|m| m.has_content && m.number.unwrap_or(1) == 1)
+}
+
+/// An underline of `=` or `-` turns the paragraph above into a heading.
+fn setext_underline(line: &str) -> Option<u8> {
+    let body = unindent(line)?.trim_end();
+    let level = match body.chars().next()? {
+        '=' => 1,
+        '-' => 2,
+        _ => return None,
+    };
+    body.chars()
+        .all(|c| c == body.as_bytes()[0] as char)
+        .then_some(level)
+}
+
+struct Parser {
+    lines: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn blocks(&mut self) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        while self.pos < self.lines.len() {
+            let line = &self.lines[self.pos];
+            if is_blank(line) {
+                self.pos += 1;
+                continue;
+            }
+            let block = if indent(line) >= 4 {
+                self.indented_code()
+            } else if let Some(fence) = fence_open(line) {
+                self.fenced_code(fence)
+            } else if let Some((level, content)) = heading(line) {
+                let content = parse_inlines(content);
+                self.pos += 1;
+                Block::Heading { level, content }
+            } else if thematic_break(line) {
+                self.pos += 1;
+                Block::ThematicBreak
+            } else if block_quote(line).is_some() {
+                self.block_quote()
+            } else if let Some(marker) = list_marker(line) {
+                self.list(marker)
+            } else {
+                self.paragraph()
+            };
+            blocks.push(block);
+        }
+        blocks
+    }
+
+    fn indented_code(&mut self) -> Block {
+        let mut code_lines = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if indent(line) >= 4 {
+                code_lines.push(&line[4..]);
+            } else if is_blank(line) {
+                code_lines.push("");
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+        while code_lines.last() == Some(&"") {
+            code_lines.pop();
+        }
+        let code = code_lines
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect();
+        Block::CodeBlock { info: None, code }
+    }
+
+    /// A fenced code block runs to a closing fence at least as long as the
+    /// opening one, or to the end of the document. Lines lose as much
+    /// indentation as the opening fence had.
+    fn fenced_code(&mut self, fence: Fence) -> Block {
+        self.pos += 1;
+        let mut code = String::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            self.pos += 1;
+            if fence_close(line, &fence) {
+                break;
+            }
+            let strip = indent(line).min(fence.indent);
+            code.push_str(&line[strip..]);
+            code.push('\n');
+        }
+        Block::CodeBlock {
+            info: fence.info,
+            code,
+        }
+    }
+
+    /// Consecutive `>` lines, plus any lines that carry on a paragraph
+    /// without bothering to repeat the `>`.
+    fn block_quote(&mut self) -> Block {
+        let mut inner = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if let Some(rest) = block_quote(line) {
+                inner.push(rest.to_string());
+            } else if inner.last().is_some_and(|last: &String| !is_blank(last))
+                && !is_blank(line)
+                && !interrupts_paragraph(line)
+            {
+                inner.push(line.clone());
+            } else {
+                break;
+            }
+            self.pos += 1;
+        }
+        Block::BlockQuote(
+            Parser {
+                lines: inner,
+                pos: 0,
+            }
+            .blocks(),
+        )
+    }
+
+    fn list(&mut self, first: Marker) -> Block {
+        let kind = first.kind;
+        let start = first.number;
+        let mut tight = true;
+        let mut items = Vec::new();
+
+        while let Some(marker) = self
+            .lines
+            .get(self.pos)
+            .and_then(|line| list_marker(line))
+            .filter(|marker| marker.kind == kind)
+        {
+            let line = &self.lines[self.pos];
+            let first_line = line.get(marker.content_indent..).unwrap_or("").to_string();
+            let mut item = vec![first_line];
+            self.pos += 1;
+            while let Some(line) = self.lines.get(self.pos) {
+                if is_blank(line) {
+                    item.push(String::new());
+                } else if indent(line) >= marker.content_indent {
+                    item.push(line[marker.content_indent..].to_string());
+                } else if item.last().is_some_and(|last| !is_blank(last))
+                    && !interrupts_paragraph(line)
+                    && list_marker(line).is_none()
+                {
+                    // A lazy continuation of the item's last paragraph.
+                    item.push(line.trim_start().to_string());
+                } else {
+                    break;
+                }
+                self.pos += 1;
+            }
+
+            // Blank lines at the end of an item separate it from the next
+            // one, which makes the list loose.
+            let mut trailing_blank = false;
+            while item.last().is_some_and(|line| is_blank(line)) {
+                item.pop();
+                trailing_blank = true;
+            }
+            let more_items = self
+                .lines
+                .get(self.pos)
+                .and_then(|line| list_marker(line))
+                .is_some_and(|marker| marker.kind == kind);
+            if trailing_blank && more_items {
+                tight = false;
+            }
+
+            let has_blank = item.iter().any(|line| is_blank(line));
+            let blocks = Parser {
+                lines: item,
+                pos: 0,
+            }
+            .blocks();
+            if has_blank && blocks.len() > 1 {
+                tight = false;
+            }
+            items.push(blocks);
+        }
+
+        Block::List {
+            start,
+            tight,
+            items,
+        }
+    }
+
+    fn paragraph(&mut self) -> Block {
+        let mut text = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if !text.is_empty() {
+                if let Some(level) = setext_underline(line) {
+                    self.pos += 1;
+                    return Block::Heading {
+                        level,
+                        content: parse_inlines(text.join("\n").trim_end()),
+                    };
+                }
+                if is_blank(line) || interrupts_paragraph(line) {
+                    break;
+                }
+            }
+            text.push(line.trim_start().to_string());
+            self.pos += 1;
+        }
+        Block::Paragraph(parse_inlines(text.join("\n").trim_end()))
+    }
+}
@@ -0,0 +1,206 @@
+// Rendering blocks as HTML.
+//
+// The output follows the reference CommonMark renderer's layout: each
+// block starts on a new line, and a tight list item's paragraphs lose
+// their `<p>` tags.
+
+use crate::block::Block;
+use crate::inline::{plain_text, Inline};
+
+/// Render blocks as an HTML fragment.
+pub fn render(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        render_block(block, false, &mut out);
+    }
+    out
+}
+
+/// Wrap an HTML fragment in a complete page. The title is the text of the
+/// first heading in `blocks`, or `fallback` if there isn't one.
+pub fn standalone(blocks: &[Block], fallback: &str) -> String {
+    let title = blocks
+        .iter()
+        .find_map(|block| match block {
+            Block::Heading { content, .. } => Some(plain_text(content)),
+            _ => None,
+        })
+        .unwrap_or_else(|| fallback.to_string());
+    let mut page = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    page.push_str("<title>");
+    escape(&title, &mut page);
+    page.push_str("</title>\n</head>\n<body>\n");
+    page.push_str(&render(blocks));
+    page.push_str("</body>\n</html>\n");
+    page
+}
+
+/// Start a new line, unless we're at the start of one already.
+fn newline(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn render_block(block: &Block, tight: bool, out: &mut String) {
+    match block {
+        Block::Paragraph(content) if tight => render_inlines(content, out),
+        Block::Paragraph(content) => {
+            newline(out);
+            out.push_str("<p>");
+            render_inlines(content, out);
+            out.push_str("</p>\n");
+        }
+        Block::Heading { level, content } => {
+            newline(out);
+            out.push_str(&format!("<h{}>", level));
+            render_inlines(content, out);
+            out.push_str(&format!("</h{}>\n", level));
+        }
+        Block::CodeBlock { info, code } => {
+            newline(out);
+            out.push_str("<pre><code");
+            if let Some(language) = info {
+                out.push_str(" class=\"language-");
+                escape(language, out);
+                out.push('"');
+            }
+            out.push('>');
+            escape(code, out);
+            out.push_str("</code></pre>\n");
+        }
+        Block::BlockQuote(blocks) => {
+            newline(out);
+            out.push_str("<blockquote>\n");
+            for block in blocks {
+                render_block(block, false, out);
+            }
+            out.push_str("</blockquote>\n");
+        }
+        Block::List {
+            start,
+            tight,
+            items,
+        } => {
+            newline(out);
+            match start {
+                None => out.push_str("<ul>\n"),
+                Some(1) => out.push_str("<ol>\n"),
+                Some(n) => out.push_str(&format!("<ol start=\"{}\">\n", n)),
+            }
+            for item in items {
+                out.push_str("<li>");
+                for block in item {
+                    render_block(block, *tight, out);
+                }
+                out.push_str("</li>\n");
+            }
+            out.push_str(if start.is_some() {
+                "</ol>\n"
+            } else {
+                "</ul>\n"
+            });
+        }
+        Block::ThematicBreak => {
+            newline(out);
+            out.push_str("<hr />\n");
+        }
+    }
+}
+
+fn render_inlines(inlines: &[Inline], out: &mut String) {
+    for inline in inlines {
+        match inline {
+            Inline::Text(text) => escape(text, out),
+            Inline::Code(code) => {
+                out.push_str("<code>");
+                escape(code, out);
+                out.push_str("</code>");
+            }
+            Inline::Emphasis(children) => {
+                out.push_str("<em>");
+                render_inlines(children, out);
+                out.push_str("</em>");
+            }
+            Inline::Strong(children) => {
+                out.push_str("<strong>");
+                render_inlines(children, out);
+                out.push_str("</strong>");
+            }
+            Inline::Link {
+                url,
+                title,
+                content,
+            } => {
+                out.push_str("<a href=\"");
+                escape(&encode_url(url), out);
+                out.push('"');
+                render_title(title, out);
+                out.push('>');
+                render_inlines(content, out);
+                out.push_str("</a>");
+            }
+            Inline::Image { url, title, alt } => {
+                out.push_str("<img src=\"");
+                escape(&encode_url(url), out);
+                out.push_str("\" alt=\"");
+                escape(&plain_text(alt), out);
+                out.push('"');
+                render_title(title, out);
+                out.push_str(" />");
+            }
+            Inline::SoftBreak => out.push('\n'),
+            Inline::HardBreak => out.push_str("<br />\n"),
+        }
+    }
+}
+
+fn render_title(title: &Option<String>, out: &mut String) {
+    if let Some(title) = title {
+        out.push_str(" title=\"");
+        escape(title, out);
+        out.push('"');
+    }
+}
+
+/// Escape the characters that mean something in HTML text or a quoted
+/// attribute value.
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Schemes that run code when the link is followed, and so are never
+/// worth passing through from a document that might not be trusted.
+const UNSAFE_SCHEMES: &[&str] = &["javascript:", "vbscript:", "data:", "file:"];
+
+/// Percent-encode what can't appear in a URL as-is, leaving existing
+/// escapes alone, and defuse dangerous schemes.
+fn encode_url(url: &str) -> String {
+    let lower = url.trim_start().to_ascii_lowercase();
+    if UNSAFE_SCHEMES
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+    {
+        return "#".to_string();
+    }
+    let mut encoded = String::new();
+    for c in url.chars() {
+        if c.is_ascii_alphanumeric() || "-._~:/?#[]@!$&'()*+,;=%".contains(c) {
+            encoded.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    encoded
+}
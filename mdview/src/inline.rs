@@ -0,0 +1,529 @@
+// Inline parsing: code spans, emphasis, links, images, and line breaks.
+//
+// The text is scanned once, left to right, into a list of pieces: runs of
+// plain text, finished inlines like code spans, brackets that might open
+// a link, and runs of `*` or `_` that might open or close emphasis.
+// Links are resolved as soon as their `]` turns up. Emphasis is resolved
+// afterwards, by matching each closing run with the nearest compatible
+// opening run before it, which is CommonMark's delimiter algorithm.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inline {
+    Text(String),
+    Code(String),
+    Emphasis(Vec<Inline>),
+    Strong(Vec<Inline>),
+    Link {
+        url: String,
+        title: Option<String>,
+        content: Vec<Inline>,
+    },
+    Image {
+        url: String,
+        title: Option<String>,
+        alt: Vec<Inline>,
+    },
+    /// A line break in the source, rendered as a newline.
+    SoftBreak,
+    /// A line ending in two spaces or a backslash, rendered as `<br />`.
+    HardBreak,
+}
+
+/// A run of `*` or `_` characters.
+#[derive(Debug)]
+struct Delimiter {
+    ch: char,
+    count: usize,
+    /// The length of the run before any of it was used up; the "rule of
+    /// three" below depends on it.
+    original: usize,
+    can_open: bool,
+    can_close: bool,
+}
+
+#[derive(Debug)]
+enum Piece {
+    Inline(Inline),
+    Delimiter(Delimiter),
+    /// A `[`, or `![` for an image, waiting for its `]`. Brackets are
+    /// deactivated once a link closes after them, since links can't nest.
+    Bracket {
+        image: bool,
+        active: bool,
+    },
+}
+
+/// Parse the text of one block into inlines.
+pub fn parse_inlines(text: &str) -> Vec<Inline> {
+    let mut scanner = Scanner {
+        text,
+        pos: 0,
+        pieces: Vec::new(),
+        pending: String::new(),
+    };
+    scanner.scan();
+    process_emphasis(scanner.pieces)
+}
+
+/// The text of some inlines with all the markup stripped, as used for an
+/// image's `alt` attribute or a page title.
+pub fn plain_text(inlines: &[Inline]) -> String {
+    let mut text = String::new();
+    for inline in inlines {
+        match inline {
+            Inline::Text(s) | Inline::Code(s) => text.push_str(s),
+            Inline::Emphasis(children) | Inline::Strong(children) => {
+                text.push_str(&plain_text(children))
+            }
+            Inline::Link { content, .. } => text.push_str(&plain_text(content)),
+            Inline::Image { alt, .. } => text.push_str(&plain_text(alt)),
+            Inline::SoftBreak | Inline::HardBreak => text.push(' '),
+        }
+    }
+    text
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+    pieces: Vec<Piece>,
+    /// Plain text not yet pushed onto `pieces`.
+    pending: String,
+}
+
+impl Scanner<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            let text = std::mem::take(&mut self.pending);
+            self.pieces.push(Piece::Inline(Inline::Text(text)));
+        }
+    }
+
+    fn push(&mut self, piece: Piece) {
+        self.flush();
+        self.pieces.push(piece);
+    }
+
+    fn scan(&mut self) {
+        while let Some(c) = self.rest().chars().next() {
+            match c {
+                '\\' => self.backslash(),
+                '`' => self.code_span(),
+                '*' | '_' => self.delimiter_run(c),
+                '!' if self.rest().starts_with("![") => {
+                    self.push(Piece::Bracket {
+                        image: true,
+                        active: true,
+                    });
+                    self.pos += 2;
+                }
+                '[' => {
+                    self.push(Piece::Bracket {
+                        image: false,
+                        active: true,
+                    });
+                    self.pos += 1;
+                }
+                ']' => self.close_bracket(),
+                '<' => self.autolink(),
+                '\n' => self.line_break(),
+                _ => {
+                    self.pending.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+        self.flush();
+    }
+
+    fn backslash(&mut self) {
+        match self.rest()[1..].chars().next() {
+            Some(c) if c.is_ascii_punctuation() => {
+                self.pending.push(c);
+                self.pos += 2;
+            }
+            Some('\n') => {
+                self.pos += 1;
+                self.push(Piece::Inline(Inline::HardBreak));
+                self.skip_newline();
+            }
+            _ => {
+                self.pending.push('\\');
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn line_break(&mut self) {
+        let trimmed = self.pending.trim_end_matches(' ');
+        let hard = self.pending.len() - trimmed.len() >= 2;
+        self.pending.truncate(trimmed.len());
+        let inline = if hard {
+            Inline::HardBreak
+        } else {
+            Inline::SoftBreak
+        };
+        self.push(Piece::Inline(inline));
+        self.skip_newline();
+    }
+
+    /// Skip a newline and the indentation of the line after it.
+    fn skip_newline(&mut self) {
+        self.pos += 1;
+        self.pos += self.rest().len() - self.rest().trim_start_matches(' ').len();
+    }
+
+    /// A code span runs from a string of backticks to the next string of
+    /// exactly as many. Without one, the backticks are just text.
+    fn code_span(&mut self) {
+        let run = run_length(self.rest(), '`');
+        let body_start = self.pos + run;
+        let mut search = body_start;
+        while let Some(found) = self.text[search..].find('`') {
+            let start = search + found;
+            let len = run_length(&self.text[start..], '`');
+            if len == run {
+                let code = self.text[body_start..start].replace('\n', " ");
+                let code = match code.strip_prefix(' ').and_then(|c| c.strip_suffix(' ')) {
+                    Some(inner) if !code.trim().is_empty() => inner.to_string(),
+                    _ => code,
+                };
+                self.push(Piece::Inline(Inline::Code(code)));
+                self.pos = start + len;
+                return;
+            }
+            search = start + len;
+        }
+        let text = self.text;
+        self.pending.push_str(&text[self.pos..self.pos + run]);
+        self.pos += run;
+    }
+
+    /// Whether a run of `*` or `_` can open or close emphasis depends on
+    /// what's on either side of it: `*foo*` works, but `a * b * c` doesn't,
+    /// and `_` inside a word, as in `snake_case_name`, never counts.
+    fn delimiter_run(&mut self, ch: char) {
+        let count = run_length(self.rest(), ch);
+        let before = self.text[..self.pos].chars().next_back().unwrap_or(' ');
+        let after = self.rest()[count..].chars().next().unwrap_or(' ');
+        let left_flanking = !after.is_whitespace()
+            && (!is_punctuation(after) || before.is_whitespace() || is_punctuation(before));
+        let right_flanking = !before.is_whitespace()
+            && (!is_punctuation(before) || after.is_whitespace() || is_punctuation(after));
+        let (can_open, can_close) = if ch == '*' {
+            (left_flanking, right_flanking)
+        } else {
+            (
+                left_flanking && (!right_flanking || is_punctuation(before)),
+                right_flanking && (!left_flanking || is_punctuation(after)),
+            )
+        };
+        self.push(Piece::Delimiter(Delimiter {
+            ch,
+            count,
+            original: count,
+            can_open,
+            can_close,
+        }));
+        self.pos += count;
+    }
+
+    fn close_bracket(&mut self) {
+        self.flush();
+        self.pos += 1;
+        let Some(open) = self
+            .pieces
+            .iter()
+            .rposition(|piece| matches!(piece, Piece::Bracket { .. }))
+        else {
+            self.pending.push(']');
+            return;
+        };
+        let Piece::Bracket { image, active } = self.pieces[open] else {
+            unreachable!();
+        };
+        let target = if active {
+            link_target(self.rest())
+        } else {
+            None
+        };
+        let Some((url, title, len)) = target else {
+            let text = if image { "![" } else { "[" };
+            self.pieces[open] = Piece::Inline(Inline::Text(text.to_string()));
+            self.pending.push(']');
+            return;
+        };
+
+        self.pos += len;
+        let content = process_emphasis(self.pieces.split_off(open + 1));
+        self.pieces.pop();
+        if image {
+            self.pieces.push(Piece::Inline(Inline::Image {
+                url,
+                title,
+                alt: content,
+            }));
+        } else {
+            self.pieces.push(Piece::Inline(Inline::Link {
+                url,
+                title,
+                content,
+            }));
+            for piece in &mut self.pieces {
+                if let Piece::Bracket {
+                    image: false,
+                    active,
+                } = piece
+                {
+                    *active = false;
+                }
+            }
+        }
+    }
+
+    /// `<https://example.com>` or `<someone@example.com>`. Anything else in
+    /// angle brackets is text, which the renderer escapes.
+    fn autolink(&mut self) {
+        let rest = &self.rest()[1..];
+        let end = rest.find(|c: char| c == '>' || c == '<' || c.is_whitespace());
+        if let Some(end) = end.filter(|&end| rest[end..].starts_with('>')) {
+            let target = &rest[..end];
+            let url = if is_uri(target) {
+                Some(target.to_string())
+            } else if is_email(target) {
+                Some(format!("mailto:{}", target))
+            } else {
+                None
+            };
+            if let Some(url) = url {
+                self.push(Piece::Inline(Inline::Link {
+                    url,
+                    title: None,
+                    content: vec![Inline::Text(target.to_string())],
+                }));
+                self.pos += end + 2;
+                return;
+            }
+        }
+        self.pending.push('<');
+        self.pos += 1;
+    }
+}
+
+fn run_length(text: &str, ch: char) -> usize {
+    text.len() - text.trim_start_matches(ch).len()
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || (!c.is_ascii() && !c.is_alphanumeric() && !c.is_whitespace())
+}
+
+fn is_uri(text: &str) -> bool {
+    let Some((scheme, _)) = text.split_once(':') else {
+        return false;
+    };
+    (2..=32).contains(&scheme.len())
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+        && !text.chars().any(|c| c.is_control())
+}
+
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".!#$%&'*+/=?^_`{|}~-".contains(c))
+        && !domain.is_empty()
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// Parse the `(url "title")` after a link's `]`, returning the URL, the
+/// title, and how many bytes they took up.
+fn link_target(text: &str) -> Option<(String, Option<String>, usize)> {
+    let mut chars = text.char_indices().peekable();
+    if chars.next()?.1 != '(' {
+        return None;
+    }
+    let skip_space = |chars: &mut std::iter::Peekable<std::str::CharIndices>| {
+        let mut skipped = false;
+        while chars
+            .next_if(|&(_, c)| c == ' ' || c == '\t' || c == '\n')
+            .is_some()
+        {
+            skipped = true;
+        }
+        skipped
+    };
+    skip_space(&mut chars);
+
+    let mut url = String::new();
+    if chars.next_if(|&(_, c)| c == '<').is_some() {
+        loop {
+            match chars.next()? {
+                (_, '>') => break,
+                (_, '<' | '\n') => return None,
+                (_, '\\') if chars.peek().is_some_and(|&(_, c)| c.is_ascii_punctuation()) => {
+                    url.push(chars.next()?.1)
+                }
+                (_, c) => url.push(c),
+            }
+        }
+    } else {
+        // A bare URL can contain parentheses, as long as they balance.
+        let mut depth = 0;
+        while let Some(&(_, c)) = chars.peek() {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 0 => break,
+                ')' => depth -= 1,
+                _ if c.is_whitespace() || c.is_control() => break,
+                _ => {}
+            }
+            chars.next();
+            if c == '\\' && chars.peek().is_some_and(|&(_, c)| c.is_ascii_punctuation()) {
+                url.push(chars.next()?.1);
+            } else {
+                url.push(c);
+            }
+        }
+        if depth != 0 {
+            return None;
+        }
+    }
+
+    let spaced = skip_space(&mut chars);
+    let mut title = None;
+    if let Some(&(_, open @ ('"' | '\'' | '('))) = chars.peek() {
+        if !spaced {
+            return None;
+        }
+        chars.next();
+        let close = if open == '(' { ')' } else { open };
+        let mut text = String::new();
+        loop {
+            match chars.next()? {
+                (_, c) if c == close => break,
+                (_, '\\') if chars.peek().is_some_and(|&(_, c)| c.is_ascii_punctuation()) => {
+                    text.push(chars.next()?.1)
+                }
+                (_, c) => text.push(c),
+            }
+        }
+        title = Some(text);
+        skip_space(&mut chars);
+    }
+    match chars.next()? {
+        (end, ')') => Some((url, title, end + 1)),
+        _ => None,
+    }
+}
+
+/// Match up emphasis delimiters. Each closing run pairs with the nearest
+/// earlier opening run of the same character; what lies between them
+/// becomes the content of an `Emphasis` (one character from each run) or
+/// a `Strong` (two). Unmatched runs are left as text.
+fn process_emphasis(mut pieces: Vec<Piece>) -> Vec<Inline> {
+    let mut i = 0;
+    while i < pieces.len() {
+        let Piece::Delimiter(closer) = &pieces[i] else {
+            i += 1;
+            continue;
+        };
+        if !closer.can_close || closer.count == 0 {
+            i += 1;
+            continue;
+        }
+        let opener = pieces[..i].iter().rposition(|piece| match piece {
+            Piece::Delimiter(opener) => {
+                opener.ch == closer.ch
+                    && opener.can_open
+                    && opener.count > 0
+                    && !breaks_rule_of_three(opener, closer)
+            }
+            _ => false,
+        });
+        let Some(j) = opener else {
+            i += 1;
+            continue;
+        };
+
+        let (Piece::Delimiter(opener), Piece::Delimiter(closer)) = pick_two(&mut pieces, j, i)
+        else {
+            unreachable!();
+        };
+        let used = if opener.count >= 2 && closer.count >= 2 {
+            2
+        } else {
+            1
+        };
+        opener.count -= used;
+        closer.count -= used;
+        let (opener_done, closer_done) = (opener.count == 0, closer.count == 0);
+
+        // Any delimiters between the two can no longer match anything.
+        let content = into_inlines(pieces.drain(j + 1..i).collect());
+        let node = if used == 2 {
+            Inline::Strong(content)
+        } else {
+            Inline::Emphasis(content)
+        };
+        pieces.insert(j + 1, Piece::Inline(node));
+        i = j + 2;
+        if opener_done {
+            pieces.remove(j);
+            i -= 1;
+        }
+        if closer_done {
+            pieces.remove(i);
+        }
+    }
+    into_inlines(pieces)
+}
+
+/// When either run could both open and close, as with the middle `*` in
+/// `*foo**bar*`, they can't pair up if their lengths add up to a multiple
+/// of three, unless both are multiples of three.
+fn breaks_rule_of_three(opener: &Delimiter, closer: &Delimiter) -> bool {
+    (opener.can_close || closer.can_open)
+        && (opener.original + closer.original).is_multiple_of(3)
+        && !(opener.original.is_multiple_of(3) && closer.original.is_multiple_of(3))
+}
+
+fn pick_two(pieces: &mut [Piece], j: usize, i: usize) -> (&mut Piece, &mut Piece) {
+    let (left, right) = pieces.split_at_mut(i);
+    (&mut left[j], &mut right[0])
+}
+
+/// Turn leftover delimiters and brackets back into text, and merge
+/// neighbouring runs of text.
+fn into_inlines(pieces: Vec<Piece>) -> Vec<Inline> {
+    let mut inlines: Vec<Inline> = Vec::new();
+    for piece in pieces {
+        let inline = match piece {
+            Piece::Inline(inline) => inline,
+            Piece::Delimiter(d) if d.count == 0 => continue,
+            Piece::Delimiter(d) => Inline::Text(d.ch.to_string().repeat(d.count)),
+            Piece::Bracket { image: true, .. } => Inline::Text("![".to_string()),
+            Piece::Bracket { image: false, .. } => Inline::Text("[".to_string()),
+        };
+        match (inlines.last_mut(), inline) {
+            (Some(Inline::Text(last)), Inline::Text(text)) => last.push_str(&text),
+            (_, inline) => inlines.push(inline),
+        }
+    }
+    inlines
+}
@@ -0,0 +1,23 @@
+// A Markdown to HTML converter.
+//
+// Conversion happens in two passes, as in CommonMark: the block parser
+// splits the document into headings, paragraphs, lists, and so on, using
+// nothing but the start of each line; then the inline parser handles
+// emphasis, code spans, and links within each block's text. The HTML
+// renderer escapes everything it copies from the source, so raw HTML in
+// the Markdown comes out as text rather than markup.
+
+mod block;
+mod html;
+mod inline;
+mod watch;
+
+pub use block::{parse, Block};
+pub use html::{render, standalone};
+pub use inline::{parse_inlines, plain_text, Inline};
+pub use watch::Watcher;
+
+/// Convert a Markdown document to an HTML fragment.
+pub fn to_html(markdown: &str) -> String {
+    render(&parse(markdown))
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use std::{env, process};
+
+use mdview::Watcher;
+
+#[derive(Debug)]
+struct Arguments {
+    input: Option<String>,
+    output: Option<String>,
+    standalone: bool,
+    watch: bool,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            input: None,
+            output: None,
+            standalone: false,
+            watch: false,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-o" | "--output" => {
+                    let path = args
+                        .next()
+                        .unwrap_or_else(|| usage_error("--output needs a path"));
+                    arguments.output = Some(path);
+                }
+                "-s" | "--standalone" => arguments.standalone = true,
+                "-w" | "--watch" => arguments.watch = true,
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                "-" => {}
+                _ if arg.starts_with('-') => usage_error(&format!("unknown option {}", arg)),
+                _ if arguments.input.is_some() => usage_error("more than one input given"),
+                _ => arguments.input = Some(arg),
+            }
+        }
+        if arguments.watch && arguments.input.is_none() {
+            usage_error("--watch needs a directory");
+        }
+        arguments
+    }
+}
+
+fn print_usage() {
+    eprintln!("mdview - convert Markdown to HTML");
+    eprintln!("Usage: mdview [-s] [-o OUTPUT] [FILE]");
+    eprintln!("       mdview --watch DIR [-o OUTDIR]");
+    eprintln!("  -s, --standalone  write a complete HTML page, not a fragment");
+    eprintln!("  -o, --output      where to write; defaults to stdout, or DIR when watching");
+    eprintln!("  -w, --watch       convert every .md file under DIR, then again whenever");
+    eprintln!("                    one changes, writing complete pages");
+    eprintln!("FILE defaults to stdin.");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn fail(message: &str, e: io::Error) -> ! {
+    eprintln!("Error: {}: {}", message, e);
+    process::exit(1);
+}
+
+fn watch(dir: &str, output: &str) -> ! {
+    let mut watcher = Watcher::new(dir, output);
+    loop {
+        match watcher.scan() {
+            Ok(written) => {
+                for path in written {
+                    eprintln!("wrote {}", path.display());
+                }
+            }
+            Err(e) => eprintln!("Error: failed to scan '{}': {}", dir, e),
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn main() {
+    let args = Arguments::parse();
+    if args.watch {
+        let dir = args.input.as_deref().unwrap();
+        watch(dir, args.output.as_deref().unwrap_or(dir));
+    }
+
+    let markdown = match &args.input {
+        Some(path) => fs::read_to_string(path)
+            .unwrap_or_else(|e| fail(&format!("failed to read '{}'", path), e)),
+        None => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .unwrap_or_else(|e| fail("failed to read stdin", e));
+            text
+        }
+    };
+
+    let blocks = mdview::parse(&markdown);
+    let html = if args.standalone {
+        let title = args
+            .input
+            .as_deref()
+            .and_then(|path| Path::new(path).file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_string());
+        mdview::standalone(&blocks, &title)
+    } else {
+        mdview::render(&blocks)
+    };
+
+    match &args.output {
+        Some(path) => fs::write(path, html)
+            .unwrap_or_else(|e| fail(&format!("failed to write '{}'", path), e)),
+        None => print!("{}", html),
+    }
+}
@@ -0,0 +1,92 @@
+// Watching a directory for changes.
+//
+// There's no portable way to ask the operating system for change
+// notifications in the standard library, so the watcher polls: each scan
+// walks the directory and compares every Markdown file's modification
+// time with what it saw last time. That's cheap for a directory of
+// documents, and it works the same everywhere.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::block::parse;
+use crate::html::standalone;
+
+pub struct Watcher {
+    source: PathBuf,
+    output: PathBuf,
+    seen: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// Watch the Markdown files under `source`, writing each one's HTML to
+    /// the same relative path under `output`, with an `.html` extension.
+    /// The two directories may be the same.
+    pub fn new(source: impl Into<PathBuf>, output: impl Into<PathBuf>) -> Watcher {
+        Watcher {
+            source: source.into(),
+            output: output.into(),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Convert every Markdown file that's new or has been modified since
+    /// the last scan, and return the paths of the HTML files written. The
+    /// first scan converts everything.
+    pub fn scan(&mut self) -> io::Result<Vec<PathBuf>> {
+        let mut found = Vec::new();
+        find_markdown(&self.source, &self.output, &mut found)?;
+        found.sort();
+
+        let mut written = Vec::new();
+        let mut seen = HashMap::new();
+        for path in found {
+            let modified = fs::metadata(&path)?.modified()?;
+            if self.seen.get(&path) != Some(&modified) {
+                written.push(self.convert(&path)?);
+            }
+            seen.insert(path, modified);
+        }
+        // Forget files that have been deleted, so they're converted again
+        // if they come back.
+        self.seen = seen;
+        Ok(written)
+    }
+
+    fn convert(&self, path: &Path) -> io::Result<PathBuf> {
+        let relative = path.strip_prefix(&self.source).unwrap_or(path);
+        let target = self.output.join(relative).with_extension("html");
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let markdown = fs::read_to_string(path)?;
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        fs::write(&target, standalone(&parse(&markdown), &stem))?;
+        Ok(target)
+    }
+}
+
+/// Collect the `.md` files under `dir`, skipping the output directory if
+/// it's inside, and hidden directories like `.git`.
+fn find_markdown(dir: &Path, output: &Path, found: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !hidden && path != output {
+                find_markdown(&path, output, found)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
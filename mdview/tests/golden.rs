@@ -0,0 +1,55 @@
+// Each `tests/golden/NAME.md` is rendered and compared with the HTML in
+// `NAME.html`. After a deliberate change to the output, regenerate an
+// expected file with the command-line tool and review the diff:
+//
+//     cargo run -p mdview -- mdview/tests/golden/NAME.md > mdview/tests/golden/NAME.html
+
+use std::fs;
+use std::path::Path;
+
+fn golden(name: &str) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let markdown = fs::read_to_string(dir.join(name).with_extension("md")).unwrap();
+    let expected = fs::read_to_string(dir.join(name).with_extension("html")).unwrap();
+    assert_eq!(mdview::to_html(&markdown), expected, "{}", name);
+}
+
+#[test]
+fn blockquotes() {
+    golden("blockquotes");
+}
+
+#[test]
+fn code() {
+    golden("code");
+}
+
+#[test]
+fn document() {
+    golden("document");
+}
+
+#[test]
+fn emphasis() {
+    golden("emphasis");
+}
+
+#[test]
+fn escaping() {
+    golden("escaping");
+}
+
+#[test]
+fn headings() {
+    golden("headings");
+}
+
+#[test]
+fn links() {
+    golden("links");
+}
+
+#[test]
+fn lists() {
+    golden("lists");
+}
@@ -0,0 +1,105 @@
+use mdview::{parse, parse_inlines, plain_text, Block, Inline};
+
+fn text(s: &str) -> Inline {
+    Inline::Text(s.to_string())
+}
+
+#[test]
+fn emphasis_nests() {
+    assert_eq!(
+        parse_inlines("**a *b* c**"),
+        vec![Inline::Strong(vec![
+            text("a "),
+            Inline::Emphasis(vec![text("b")]),
+            text(" c"),
+        ])]
+    );
+    // Leftover delimiters stay outside the match.
+    assert_eq!(
+        parse_inlines("***a*"),
+        vec![text("**"), Inline::Emphasis(vec![text("a")])]
+    );
+    assert_eq!(parse_inlines("a_b_c"), vec![text("a_b_c")]);
+    assert_eq!(parse_inlines("* a *"), vec![text("* a *")]);
+}
+
+#[test]
+fn code_spans_hide_markup() {
+    assert_eq!(
+        parse_inlines("`*not* [a](link)`"),
+        vec![Inline::Code("*not* [a](link)".to_string())]
+    );
+    assert_eq!(parse_inlines("``unmatched`"), vec![text("``unmatched`")]);
+    assert_eq!(
+        parse_inlines("`a\nb`"),
+        vec![Inline::Code("a b".to_string())]
+    );
+}
+
+#[test]
+fn links_and_images() {
+    assert_eq!(
+        parse_inlines("[*a*](/u 'T')"),
+        vec![Inline::Link {
+            url: "/u".to_string(),
+            title: Some("T".to_string()),
+            content: vec![Inline::Emphasis(vec![text("a")])],
+        }]
+    );
+    // Emphasis can't straddle a link's brackets.
+    assert_eq!(
+        parse_inlines("*[a*](b)"),
+        vec![
+            text("*"),
+            Inline::Link {
+                url: "b".to_string(),
+                title: None,
+                content: vec![text("a*")],
+            }
+        ]
+    );
+    let image = parse_inlines("![**bold** alt](x.png)");
+    let Inline::Image { alt, .. } = &image[0] else {
+        panic!("expected an image, got {:?}", image);
+    };
+    assert_eq!(plain_text(alt), "bold alt");
+}
+
+#[test]
+fn line_breaks() {
+    assert_eq!(
+        parse_inlines("a\n   b  \nc\\\nd"),
+        vec![
+            text("a"),
+            Inline::SoftBreak,
+            text("b"),
+            Inline::HardBreak,
+            text("c"),
+            Inline::HardBreak,
+            text("d"),
+        ]
+    );
+}
+
+#[test]
+fn block_structure() {
+    let blocks = parse("# T\n\npara\n- a\n- b\n\n```\nx\n```\n");
+    assert_eq!(blocks.len(), 4);
+    assert!(matches!(blocks[0], Block::Heading { level: 1, .. }));
+    assert!(matches!(blocks[1], Block::Paragraph(_)));
+    let Block::List { tight, items, .. } = &blocks[2] else {
+        panic!("expected a list, got {:?}", blocks[2]);
+    };
+    assert!(tight);
+    assert_eq!(items.len(), 2);
+    assert_eq!(
+        blocks[3],
+        Block::CodeBlock {
+            info: None,
+            code: "x\n".to_string()
+        }
+    );
+    // An ordered list has to start at 1 to interrupt a paragraph, so this
+    // is just a sentence that happens to wrap before a number.
+    assert_eq!(parse("costs\n2. dollars").len(), 1);
+}
@@ -0,0 +1,67 @@
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+
+use mdview::Watcher;
+
+#[test]
+fn converts_new_and_changed_files() {
+    let source = tempfile::tempdir().unwrap();
+    let output = tempfile::tempdir().unwrap();
+    fs::create_dir(source.path().join("sub")).unwrap();
+    fs::create_dir(source.path().join(".hidden")).unwrap();
+    fs::write(source.path().join("index.md"), "# Home\n").unwrap();
+    fs::write(source.path().join("sub/page.md"), "text\n").unwrap();
+    fs::write(source.path().join(".hidden/skip.md"), "skip\n").unwrap();
+    fs::write(source.path().join("notes.txt"), "not markdown\n").unwrap();
+
+    let mut watcher = Watcher::new(source.path(), output.path());
+    let written = watcher.scan().unwrap();
+    assert_eq!(
+        written,
+        vec![
+            output.path().join("index.html"),
+            output.path().join("sub/page.html"),
+        ]
+    );
+    let page = fs::read_to_string(output.path().join("index.html")).unwrap();
+    assert!(page.contains("<title>Home</title>"), "{}", page);
+    assert!(page.contains("<h1>Home</h1>"), "{}", page);
+    let page = fs::read_to_string(output.path().join("sub/page.html")).unwrap();
+    assert!(page.contains("<title>page</title>"), "{}", page);
+
+    // Nothing has changed.
+    assert!(watcher.scan().unwrap().is_empty());
+
+    // Modification times can be coarse, so move this one on explicitly.
+    let path = source.path().join("sub/page.md");
+    fs::write(&path, "*changed*\n").unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    fs::write(source.path().join("new.md"), "new\n").unwrap();
+    assert_eq!(
+        watcher.scan().unwrap(),
+        vec![
+            output.path().join("new.html"),
+            output.path().join("sub/page.html"),
+        ]
+    );
+    let page = fs::read_to_string(output.path().join("sub/page.html")).unwrap();
+    assert!(page.contains("<em>changed</em>"), "{}", page);
+}
+
+#[test]
+fn output_inside_source() {
+    let source = tempfile::tempdir().unwrap();
+    let output = source.path().join("site");
+    fs::create_dir(&output).unwrap();
+    fs::write(source.path().join("a.md"), "a\n").unwrap();
+    // A Markdown file that's somehow in the output isn't converted again.
+    fs::write(output.join("stray.md"), "stray\n").unwrap();
+
+    let mut watcher = Watcher::new(source.path(), &output);
+    assert_eq!(watcher.scan().unwrap(), vec![output.join("a.html")]);
+}
@@ -0,0 +1,120 @@
+// Compare the channels with `std::sync::mpsc`.
+//
+// Each benchmark moves a batch of integers from producer threads to the
+// receiving thread, so it measures throughput, thread spawning included.
+// std's `channel` is a lock-free linked list of blocks, and its
+// `sync_channel` a bounded array; the homemade `mpsc` takes a lock on
+// every operation, and `spsc` takes none.
+
+use std::sync::mpsc as std_mpsc;
+use std::thread;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use mini_channel::{mpsc, spsc};
+
+const MESSAGES: u64 = 100_000;
+const CAPACITY: usize = 1024;
+
+/// One producer, one consumer: the case `spsc` is built for.
+fn one_to_one(c: &mut Criterion) {
+    let mut group = c.benchmark_group("one_to_one");
+    group.throughput(Throughput::Elements(MESSAGES));
+    group.bench_function("std channel", |b| {
+        b.iter(|| {
+            let (sender, receiver) = std_mpsc::channel();
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.bench_function("std sync_channel", |b| {
+        b.iter(|| {
+            let (sender, receiver) = std_mpsc::sync_channel(CAPACITY);
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.bench_function("mpsc", |b| {
+        b.iter(|| {
+            let (sender, receiver) = mpsc::channel();
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.bench_function("spsc", |b| {
+        b.iter(|| {
+            let (sender, receiver) = spsc::channel(CAPACITY);
+            let producer =
+                thread::spawn(move || (0..MESSAGES).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().for_each(|i| {
+                black_box(i);
+            });
+            producer.join().unwrap();
+        })
+    });
+    group.finish();
+}
+
+/// Several producers contending for one channel.
+fn many_to_one(c: &mut Criterion) {
+    let mut group = c.benchmark_group("many_to_one");
+    group.throughput(Throughput::Elements(MESSAGES));
+    for producers in [2, 4, 8] {
+        let each = MESSAGES / producers;
+        group.bench_with_input(
+            BenchmarkId::new("std channel", producers),
+            &producers,
+            |b, &producers| {
+                b.iter(|| {
+                    let (sender, receiver) = std_mpsc::channel();
+                    let threads: Vec<_> = (0..producers)
+                        .map(|_| {
+                            let sender = sender.clone();
+                            thread::spawn(move || (0..each).for_each(|i| sender.send(i).unwrap()))
+                        })
+                        .collect();
+                    drop(sender);
+                    receiver.iter().for_each(|i| {
+                        black_box(i);
+                    });
+                    threads.into_iter().for_each(|t| t.join().unwrap());
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("mpsc", producers),
+            &producers,
+            |b, &producers| {
+                b.iter(|| {
+                    let (sender, receiver) = mpsc::channel();
+                    let threads: Vec<_> = (0..producers)
+                        .map(|_| {
+                            let sender = sender.clone();
+                            thread::spawn(move || (0..each).for_each(|i| sender.send(i).unwrap()))
+                        })
+                        .collect();
+                    drop(sender);
+                    receiver.iter().for_each(|i| {
+                        black_box(i);
+                    });
+                    threads.into_iter().for_each(|t| t.join().unwrap());
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, one_to_one, many_to_one);
+criterion_main!(benches);
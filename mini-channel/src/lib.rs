@@ -0,0 +1,20 @@
+// Channels built from scratch, two ways.
+//
+// `mpsc` is the straightforward design: a `VecDeque` behind a `Mutex`, and
+// a `Condvar` for the receiver to sleep on. Any number of senders, one
+// receiver, no limit on the queue. `spsc` trades generality for speed: one
+// sender, one receiver, a fixed-size ring buffer, and no locks at all,
+// only two atomic counters. Both report disconnection the way
+// `std::sync::mpsc` does.
+//
+// Under `--cfg loom`, the `sync` module swaps std's threads, locks, and
+// atomics for loom's, which explore every interleaving of the tests in
+// `tests/loom.rs`.
+
+mod error;
+mod sync;
+
+pub mod mpsc;
+pub mod spsc;
+
+pub use error::{RecvError, SendError, TryRecvError, TrySendError};
@@ -0,0 +1,112 @@
+use std::sync::mpsc as std_mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use mini_channel::{mpsc, spsc};
+
+const CAPACITY: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Mpsc,
+    Spsc,
+    Std,
+}
+
+#[derive(Debug)]
+struct Arguments {
+    kinds: Vec<Kind>,
+    messages: u64,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            kinds: Vec::new(),
+            messages: 1_000_000,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "mpsc" => arguments.kinds.push(Kind::Mpsc),
+                "spsc" => arguments.kinds.push(Kind::Spsc),
+                "std" => arguments.kinds.push(Kind::Std),
+                "-n" => {
+                    let n = args
+                        .next()
+                        .unwrap_or_else(|| usage_error("-n needs a count"));
+                    arguments.messages = n
+                        .parse()
+                        .unwrap_or_else(|_| usage_error(&format!("bad message count {:?}", n)));
+                }
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        if arguments.kinds.is_empty() {
+            arguments.kinds = vec![Kind::Std, Kind::Mpsc, Kind::Spsc];
+        }
+        arguments
+    }
+}
+
+fn print_usage() {
+    eprintln!("mini-channel - time sending messages from one thread to another");
+    eprintln!("Usage: mini-channel [-n COUNT] [std|mpsc|spsc]...");
+    eprintln!("  -n COUNT   how many messages to send (default 1000000)");
+    eprintln!("With no channel named, times all three.");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+/// Send `0..messages` to the receiving thread, check they all arrive, and
+/// return how long that took.
+fn time(kind: Kind, messages: u64) -> Duration {
+    let start = Instant::now();
+    let sum: u64 = match kind {
+        Kind::Std => {
+            let (sender, receiver) = std_mpsc::channel();
+            thread::spawn(move || (0..messages).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().sum()
+        }
+        Kind::Mpsc => {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || (0..messages).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().sum()
+        }
+        Kind::Spsc => {
+            let (sender, receiver) = spsc::channel(CAPACITY);
+            thread::spawn(move || (0..messages).for_each(|i| sender.send(i).unwrap()));
+            receiver.iter().sum()
+        }
+    };
+    let elapsed = start.elapsed();
+    if sum != messages * messages.saturating_sub(1) / 2 {
+        eprintln!("Error: {:?} lost messages", kind);
+        process::exit(1);
+    }
+    elapsed
+}
+
+fn main() {
+    let args = Arguments::parse();
+    for kind in args.kinds {
+        let elapsed = time(kind, args.messages);
+        let rate = args.messages as f64 / elapsed.as_secs_f64();
+        println!(
+            "{:<5} {} messages in {:.1?} ({:.1} million/s)",
+            format!("{:?}", kind).to_lowercase(),
+            args.messages,
+            elapsed,
+            rate / 1e6
+        );
+    }
+}
@@ -0,0 +1,216 @@
+// A single-producer, single-consumer channel: a ring buffer with no locks.
+//
+// The sender owns `tail`, the number of values ever sent, and the
+// receiver owns `head`, the number ever received; slots `head..tail`,
+// taken modulo the capacity, hold values in flight. Each side writes only
+// its own counter and reads the other's. The sender fills a slot, then
+// publishes it with a `Release` store to `tail`; the receiver's `Acquire`
+// load of `tail` guarantees it sees the value written. In the other
+// direction, the receiver's store to `head` tells the sender a slot is
+// free to reuse.
+//
+// There's nobody to wake: a blocked `send` or `recv` just yields its
+// thread and tries again. That suits a consumer that's rarely idle, and
+// keeps every operation free of system calls.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+use crate::error::{RecvError, SendError, TryRecvError, TrySendError};
+use crate::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::sync::{thread, Arc, UnsafeCell};
+
+struct Ring<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    /// The capacity, a power of two, minus one.
+    mask: usize,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    sender: AtomicBool,
+    receiver: AtomicBool,
+}
+
+// SAFETY: Values move from the sender's thread to the receiver's, so `T`
+// must be `Send`. Each slot is accessed by one side at a time, handed over
+// by the counters, so sharing the ring is safe.
+unsafe impl<T: Send> Send for Ring<T> {}
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        let mut index = head;
+        while index != tail {
+            // SAFETY: `head..tail` are exactly the slots holding values
+            // sent but never received, and both sides are gone.
+            self.slots[index & self.mask].with_mut(|slot| unsafe { (*slot).assume_init_drop() });
+            index = index.wrapping_add(1);
+        }
+    }
+}
+
+/// The sending half. Not `Clone`, and not `Sync`: only one thread may send.
+pub struct Sender<T> {
+    ring: Arc<Ring<T>>,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+/// The receiving half. Not `Sync`: only one thread may receive.
+pub struct Receiver<T> {
+    ring: Arc<Ring<T>>,
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+/// Create a channel holding at most `capacity` values, rounded up to a
+/// power of two.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(
+        capacity > 0,
+        "an spsc channel needs room for at least one value"
+    );
+    let capacity = capacity.next_power_of_two();
+    let ring = Arc::new(Ring {
+        slots: (0..capacity)
+            .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+            .collect(),
+        mask: capacity - 1,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        sender: AtomicBool::new(true),
+        receiver: AtomicBool::new(true),
+    });
+    (
+        Sender {
+            ring: ring.clone(),
+            _not_sync: PhantomData,
+        },
+        Receiver {
+            ring,
+            _not_sync: PhantomData,
+        },
+    )
+}
+
+impl<T> Sender<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.slots.len()
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let ring = &*self.ring;
+        if !ring.receiver.load(Ordering::Acquire) {
+            return Err(TrySendError::Disconnected(value));
+        }
+        let tail = ring.tail.load(Ordering::Relaxed);
+        let head = ring.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == ring.slots.len() {
+            return Err(TrySendError::Full(value));
+        }
+        // SAFETY: The slot is outside `head..tail`, so the receiver won't
+        // touch it until the store below publishes it, and it's empty:
+        // either never used, or its value was moved out before `head`
+        // passed it.
+        ring.slots[tail & ring.mask]
+            .with_mut(|slot| unsafe { slot.write(MaybeUninit::new(value)) });
+        ring.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Send `value`, waiting for room if the channel is full.
+    pub fn send(&self, mut value: T) -> Result<(), SendError<T>> {
+        loop {
+            match self.try_send(value) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(v)) => return Err(SendError(v)),
+                Err(TrySendError::Full(v)) => {
+                    value = v;
+                    thread::yield_now();
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.ring.sender.store(false, Ordering::Release);
+    }
+}
+
+impl<T> Receiver<T> {
+    pub fn capacity(&self) -> usize {
+        self.ring.slots.len()
+    }
+
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let ring = &*self.ring;
+        let head = ring.head.load(Ordering::Relaxed);
+        if head == ring.tail.load(Ordering::Acquire) {
+            if ring.sender.load(Ordering::Acquire) {
+                return Err(TryRecvError::Empty);
+            }
+            // The sender is gone, but it may have sent a last value
+            // between our two loads. Anything it sent happened before it
+            // dropped, so one more look settles it.
+            if head == ring.tail.load(Ordering::Acquire) {
+                return Err(TryRecvError::Disconnected);
+            }
+        }
+        // SAFETY: The slot is in `head..tail`, so the sender has written it
+        // and won't touch it again until the store below frees it.
+        let value =
+            ring.slots[head & ring.mask].with_mut(|slot| unsafe { slot.read().assume_init() });
+        ring.head.store(head.wrapping_add(1), Ordering::Release);
+        Ok(value)
+    }
+
+    /// Wait for a value. Fails once the channel is empty and the sender has
+    /// been dropped.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+                Err(TryRecvError::Empty) => thread::yield_now(),
+            }
+        }
+    }
+
+    /// Iterate over received values until the sender is gone.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { receiver: self }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.ring.receiver.store(false, Ordering::Release);
+    }
+}
+
+pub struct Iter<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
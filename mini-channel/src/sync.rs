@@ -0,0 +1,31 @@
+// The concurrency primitives the channels use: std's normally, loom's
+// when model checking.
+
+#[cfg(loom)]
+pub(crate) use loom::{
+    cell::UnsafeCell,
+    sync::{atomic, Arc, Condvar, Mutex},
+    thread,
+};
+
+#[cfg(not(loom))]
+pub(crate) use std::{
+    sync::{atomic, Arc, Condvar, Mutex},
+    thread,
+};
+
+/// `std::cell::UnsafeCell` with loom's interface, which hands out raw
+/// pointers only inside a closure, so that loom can track each access.
+#[cfg(not(loom))]
+pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) fn new(value: T) -> UnsafeCell<T> {
+        UnsafeCell(std::cell::UnsafeCell::new(value))
+    }
+
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
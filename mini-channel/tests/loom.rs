@@ -0,0 +1,110 @@
+// Model-checked tests: loom runs each one under every interleaving of its
+// threads' atomic operations and lock acquisitions that could matter.
+//
+//     RUSTFLAGS="--cfg loom" cargo test -p mini-channel --test loom --release
+
+#![cfg(loom)]
+
+use loom::sync::atomic::{AtomicUsize, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+
+use mini_channel::{mpsc, spsc, RecvError, SendError, TryRecvError};
+
+#[test]
+fn mpsc_two_senders() {
+    loom::model(|| {
+        let (sender, receiver) = mpsc::channel();
+        let other = sender.clone();
+        let a = thread::spawn(move || sender.send(1).unwrap());
+        let b = thread::spawn(move || other.send(2).unwrap());
+        let mut received = [receiver.recv().unwrap(), receiver.recv().unwrap()];
+        received.sort();
+        assert_eq!(received, [1, 2]);
+        assert_eq!(receiver.recv(), Err(RecvError));
+        a.join().unwrap();
+        b.join().unwrap();
+    });
+}
+
+#[test]
+fn mpsc_receiver_leaves() {
+    loom::model(|| {
+        let (sender, receiver) = mpsc::channel();
+        let receiving = thread::spawn(move || receiver.try_recv());
+        let sent = sender.send(7);
+        match receiving.join().unwrap() {
+            // The receiver got there first, and left before the send.
+            Err(TryRecvError::Empty) => assert!(matches!(sent, Ok(()) | Err(SendError(7)))),
+            Ok(7) => assert_eq!(sent, Ok(())),
+            other => panic!("unexpected {:?}", other),
+        }
+    });
+}
+
+#[test]
+fn spsc_in_order_then_disconnected() {
+    loom::model(|| {
+        let (sender, receiver) = spsc::channel(2);
+        let producer = thread::spawn(move || {
+            for i in 0..3 {
+                sender.send(i).unwrap();
+            }
+        });
+        for i in 0..3 {
+            assert_eq!(receiver.recv(), Ok(i));
+        }
+        assert_eq!(receiver.recv(), Err(RecvError));
+        producer.join().unwrap();
+    });
+}
+
+#[test]
+fn spsc_last_value_before_disconnecting() {
+    loom::model(|| {
+        let (sender, receiver) = spsc::channel(1);
+        let producer = thread::spawn(move || sender.try_send(1).unwrap());
+        // Whatever the interleaving, the value is never lost: it's seen
+        // before `Disconnected` is.
+        loop {
+            match receiver.try_recv() {
+                Ok(value) => {
+                    assert_eq!(value, 1);
+                    break;
+                }
+                Err(TryRecvError::Empty) => thread::yield_now(),
+                Err(TryRecvError::Disconnected) => panic!("value lost"),
+            }
+        }
+        producer.join().unwrap();
+    });
+}
+
+#[derive(Debug)]
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn spsc_drops_each_value_once() {
+    loom::model(|| {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = spsc::channel(2);
+        let counted = drops.clone();
+        let producer = thread::spawn(move || {
+            for _ in 0..2 {
+                // Fails, and drops the value, if the receiver is gone.
+                let _ = sender.try_send(Counted(counted.clone()));
+            }
+        });
+        let received = receiver.try_recv();
+        drop(receiver);
+        drop(received);
+        producer.join().unwrap();
+        assert_eq!(drops.load(Ordering::SeqCst), 2);
+    });
+}
@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use mini_channel::mpsc::channel;
+use mini_channel::{RecvError, SendError, TryRecvError};
+
+#[test]
+fn values_arrive_in_order() {
+    let (sender, receiver) = channel();
+    for i in 0..100 {
+        sender.send(i).unwrap();
+    }
+    drop(sender);
+    assert_eq!(
+        receiver.iter().collect::<Vec<_>>(),
+        (0..100).collect::<Vec<_>>()
+    );
+    assert_eq!(receiver.recv(), Err(RecvError));
+}
+
+#[test]
+fn try_recv() {
+    let (sender, receiver) = channel();
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    sender.send("a").unwrap();
+    assert_eq!(receiver.try_recv(), Ok("a"));
+    let second = sender.clone();
+    drop(sender);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    second.send("b").unwrap();
+    drop(second);
+    assert_eq!(receiver.try_recv(), Ok("b"));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn many_producers() {
+    let (sender, receiver) = channel();
+    let producers: Vec<_> = (0..4)
+        .map(|p| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..10_000 {
+                    sender.send((p, i)).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+
+    // Each producer's values arrive in the order it sent them.
+    let mut next = [0; 4];
+    for (p, i) in receiver {
+        assert_eq!(i, next[p]);
+        next[p] += 1;
+    }
+    assert_eq!(next, [10_000; 4]);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+}
+
+#[test]
+fn a_chat_arrives_in_each_senders_order() {
+    // Each of four senders sends its part of a transcript on its own
+    // thread; the room hears each one's messages in the order they were
+    // said.
+    let chat = fixtures::transcript(4000, 4, 12);
+    let (sender, receiver) = channel();
+    let mut producers = Vec::new();
+    for name in ["ana", "bo", "chidi", "dana"] {
+        let sender = sender.clone();
+        let theirs: Vec<_> = chat.iter().filter(|m| m.from == name).cloned().collect();
+        producers.push(thread::spawn(move || {
+            for message in theirs {
+                sender.send(message).unwrap();
+            }
+        }));
+    }
+    drop(sender);
+
+    let heard: Vec<_> = receiver.into_iter().collect();
+    assert_eq!(heard.len(), chat.len());
+    for name in ["ana", "bo", "chidi", "dana"] {
+        let said = chat.iter().filter(|m| m.from == name);
+        assert!(said.eq(heard.iter().filter(|m| m.from == name)));
+    }
+    for producer in producers {
+        producer.join().unwrap();
+    }
+}
+
+#[test]
+fn recv_wakes_when_the_last_sender_leaves() {
+    let (sender, receiver) = channel::<()>();
+    let waiting = thread::spawn(move || receiver.recv());
+    thread::sleep(Duration::from_millis(20));
+    drop(sender);
+    assert_eq!(waiting.join().unwrap(), Err(RecvError));
+}
+
+#[test]
+fn send_after_the_receiver_leaves_returns_the_value() {
+    let (sender, receiver) = channel();
+    drop(receiver);
+    assert_eq!(
+        sender.send(String::from("lost")),
+        Err(SendError(String::from("lost")))
+    );
+}
+
+#[derive(Debug)]
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn unreceived_values_are_dropped_with_the_receiver() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = channel();
+    for _ in 0..3 {
+        sender.send(Counted(drops.clone())).unwrap();
+    }
+    drop(receiver.recv().unwrap());
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+    drop(receiver);
+    // The sender is still alive, but nobody can receive these.
+    assert_eq!(drops.load(Ordering::SeqCst), 3);
+    drop(sender);
+}
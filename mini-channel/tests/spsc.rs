@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use mini_channel::spsc::channel;
+use mini_channel::{RecvError, SendError, TryRecvError, TrySendError};
+
+#[test]
+fn capacity_rounds_up_to_a_power_of_two() {
+    assert_eq!(channel::<u8>(1).0.capacity(), 1);
+    assert_eq!(channel::<u8>(5).0.capacity(), 8);
+    assert_eq!(channel::<u8>(64).1.capacity(), 64);
+}
+
+#[test]
+#[should_panic(expected = "at least one value")]
+fn zero_capacity() {
+    channel::<u8>(0);
+}
+
+#[test]
+fn full_and_empty() {
+    let (sender, receiver) = channel(2);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    sender.try_send(1).unwrap();
+    sender.try_send(2).unwrap();
+    assert_eq!(sender.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(receiver.try_recv(), Ok(1));
+    sender.try_send(3).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(2));
+    assert_eq!(receiver.try_recv(), Ok(3));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn wraps_around_many_times() {
+    let (sender, receiver) = channel(4);
+    for i in 0..1000 {
+        sender.try_send(i).unwrap();
+        sender.try_send(-i).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(i));
+        assert_eq!(receiver.try_recv(), Ok(-i));
+    }
+}
+
+#[test]
+fn disconnection() {
+    let (sender, receiver) = channel(4);
+    sender.send("last").unwrap();
+    drop(sender);
+    assert_eq!(receiver.try_recv(), Ok("last"));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(receiver.recv(), Err(RecvError));
+
+    let (sender, receiver) = channel(1);
+    sender.send(1).unwrap();
+    drop(receiver);
+    assert_eq!(sender.try_send(2), Err(TrySendError::Disconnected(2)));
+    assert_eq!(sender.send(3), Err(SendError(3)));
+}
+
+#[test]
+fn blocked_sender_gives_up_when_the_receiver_leaves() {
+    let (sender, receiver) = channel(1);
+    sender.send(0).unwrap();
+    let blocked = thread::spawn(move || sender.send(1));
+    thread::sleep(Duration::from_millis(20));
+    drop(receiver);
+    assert_eq!(blocked.join().unwrap(), Err(SendError(1)));
+}
+
+#[test]
+fn across_threads() {
+    let (sender, receiver) = channel(16);
+    let producer = thread::spawn(move || {
+        for i in 0..100_000u64 {
+            sender.send(i).unwrap();
+        }
+    });
+    let mut expected = 0;
+    for i in &receiver {
+        assert_eq!(i, expected);
+        expected += 1;
+    }
+    assert_eq!(expected, 100_000);
+    producer.join().unwrap();
+}
+
+#[derive(Debug)]
+struct Counted(Arc<AtomicUsize>);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn every_value_is_dropped_exactly_once() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = channel(4);
+    // Wrap around once, so the values left behind straddle the end.
+    for _ in 0..3 {
+        sender.send(Counted(drops.clone())).unwrap();
+    }
+    for _ in 0..3 {
+        drop(receiver.recv().unwrap());
+    }
+    for _ in 0..3 {
+        sender.send(Counted(drops.clone())).unwrap();
+    }
+    drop(receiver.recv().unwrap());
+    assert_eq!(drops.load(Ordering::SeqCst), 4);
+    drop(sender);
+    drop(receiver);
+    assert_eq!(drops.load(Ordering::SeqCst), 6);
+}
@@ -0,0 +1,76 @@
+// How the maximum load factor affects `MiniMap`'s speed, with
+// `std::collections::HashMap` for reference.
+//
+// A fuller table means longer probe sequences, especially for keys that
+// aren't present, since those probes only stop at an empty slot.
+
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use mini_map::MiniMap;
+
+const ENTRIES: u64 = 50_000;
+const LOAD_FACTORS: [f64; 5] = [0.5, 0.7, 0.8, 0.875, 0.95];
+
+fn filled(load: f64) -> MiniMap<u64, u64> {
+    let mut map = MiniMap::new().with_max_load_factor(load);
+    for i in 0..ENTRIES {
+        map.insert(i, i);
+    }
+    map
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    for load in LOAD_FACTORS {
+        group.bench_with_input(BenchmarkId::new("MiniMap", load), &load, |b, &load| {
+            b.iter(|| filled(load))
+        });
+    }
+    group.bench_function("HashMap", |b| {
+        b.iter(|| (0..ENTRIES).map(|i| (i, i)).collect::<HashMap<_, _>>())
+    });
+    group.finish();
+}
+
+fn lookup(c: &mut Criterion) {
+    let std_map: HashMap<u64, u64> = (0..ENTRIES).map(|i| (i, i)).collect();
+    for (name, keys) in [
+        ("lookup_hit", 0..ENTRIES),
+        ("lookup_miss", ENTRIES..2 * ENTRIES),
+    ] {
+        let mut group = c.benchmark_group(name);
+        for load in LOAD_FACTORS {
+            let map = filled(load);
+            group.bench_with_input(BenchmarkId::new("MiniMap", load), &load, |b, _| {
+                b.iter(|| keys.clone().filter(|k| map.contains_key(k)).count())
+            });
+        }
+        group.bench_function("HashMap", |b| {
+            b.iter(|| keys.clone().filter(|k| std_map.contains_key(k)).count())
+        });
+        group.finish();
+    }
+}
+
+fn churn(c: &mut Criterion) {
+    // Removing and inserting at a steady size fills the table with
+    // tombstones, forcing periodic rebuilds.
+    let mut group = c.benchmark_group("churn");
+    for load in LOAD_FACTORS {
+        group.bench_with_input(BenchmarkId::new("MiniMap", load), &load, |b, &load| {
+            let mut map = filled(load);
+            let mut next = ENTRIES;
+            b.iter(|| {
+                map.remove(&(next - ENTRIES));
+                map.insert(next, next);
+                next += 1;
+                black_box(map.len())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, insert, lookup, churn);
+criterion_main!(benches);
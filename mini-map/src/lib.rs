@@ -0,0 +1,594 @@
+// A hash map from scratch, using open addressing: entries live directly in
+// one table of slots, and collisions are resolved by probing other slots in
+// a fixed sequence, rather than by chaining entries in per-bucket lists.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::mem;
+use std::ops::Index;
+
+/// The default maximum fraction of slots that may be full or tombstones
+/// before the table is resized.
+pub const DEFAULT_MAX_LOAD_FACTOR: f64 = 0.875;
+
+const MIN_CAPACITY: usize = 8;
+
+enum Slot<K, V> {
+    /// Never used since the table was (re)built. Probing stops here.
+    Empty,
+    /// Held an entry that was removed. Lookups must probe past it, since
+    /// the key they want may have been placed beyond it, but inserts may
+    /// reuse it.
+    Tombstone,
+    Full {
+        hash: u64,
+        key: K,
+        value: V,
+    },
+}
+
+/// A hash map using open addressing with quadratic probing.
+pub struct MiniMap<K, V, S = RandomState> {
+    /// The table. Its length is always zero or a power of two, so the probe
+    /// sequence visits every slot.
+    slots: Vec<Slot<K, V>>,
+    len: usize,
+    tombstones: usize,
+    max_load_factor: f64,
+    hash_builder: S,
+}
+
+/// Where a probe for a key ended up.
+enum Probe {
+    /// The key is in this slot.
+    Found(usize),
+    /// The key is absent; this is the slot to insert it into.
+    Vacant(usize),
+}
+
+impl<K, V> MiniMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        MiniMap::with_hasher(RandomState::new())
+    }
+
+    /// Create a map that can hold `capacity` entries without resizing.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut map = MiniMap::new();
+        map.slots = empty_slots(map.table_size_for(capacity));
+        map
+    }
+}
+
+impl<K, V, S> MiniMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        MiniMap {
+            slots: Vec::new(),
+            len: 0,
+            tombstones: 0,
+            max_load_factor: DEFAULT_MAX_LOAD_FACTOR,
+            hash_builder,
+        }
+    }
+
+    /// Set the fraction of slots that may be in use (by entries or
+    /// tombstones) before the table grows. Lower values trade memory for
+    /// shorter probe sequences.
+    ///
+    /// Panics unless `0.0 < max_load_factor < 1.0`: the table must always
+    /// have an empty slot, or a probe for a missing key would never end.
+    pub fn with_max_load_factor(mut self, max_load_factor: f64) -> Self {
+        assert!(
+            max_load_factor > 0.0 && max_load_factor < 1.0,
+            "max load factor must be strictly between 0 and 1"
+        );
+        self.max_load_factor = max_load_factor;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of entries the map can hold without resizing, assuming no
+    /// tombstones.
+    pub fn capacity(&self) -> usize {
+        self.max_used(self.slots.len())
+    }
+
+    /// The number of slots in the table, whether empty, tombstones, or full.
+    pub fn table_size(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn max_load_factor(&self) -> f64 {
+        self.max_load_factor
+    }
+
+    /// Remove every entry, keeping the table's memory.
+    pub fn clear(&mut self) {
+        for slot in &mut self.slots {
+            *slot = Slot::Empty;
+        }
+        self.len = 0;
+        self.tombstones = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            slots: self.slots.iter(),
+            remaining: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            slots: self.slots.iter_mut(),
+            remaining: self.len,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// The most slots a table of `table_size` may have in use.
+    fn max_used(&self, table_size: usize) -> usize {
+        // Always leave at least one slot empty.
+        ((table_size as f64 * self.max_load_factor) as usize).min(table_size.saturating_sub(1))
+    }
+
+    /// The smallest table that can hold `entries` entries.
+    fn table_size_for(&self, entries: usize) -> usize {
+        let mut size = MIN_CAPACITY;
+        while self.max_used(size) < entries {
+            size *= 2;
+        }
+        size
+    }
+}
+
+impl<K, V, S> MiniMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Insert a key-value pair. If the map already had an entry for `key`,
+    /// replace its value and return the old one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self.hash_builder.hash_one(&key);
+        if let Some(Probe::Found(index)) = self.probe(hash, &key) {
+            match &mut self.slots[index] {
+                Slot::Full { value: old, .. } => return Some(mem::replace(old, value)),
+                _ => unreachable!("probe found a non-full slot"),
+            }
+        }
+
+        self.reserve(1);
+        let index = match self.probe(hash, &key) {
+            Some(Probe::Vacant(index)) => index,
+            _ => unreachable!("key appeared during resize"),
+        };
+        if let Slot::Tombstone = self.slots[index] {
+            self.tombstones -= 1;
+        }
+        self.slots[index] = Slot::Full { hash, key, value };
+        self.len += 1;
+        None
+    }
+
+    /// Look up `key`. As with `std::collections::HashMap`, the key may be
+    /// any borrowed form of the map's key type: a `MiniMap<String, V>` can
+    /// be searched with a `&str`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.find(key)? {
+            Slot::Full { key, value, .. } => Some((key, value)),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find_index(key)?;
+        match &mut self.slots[index] {
+            Slot::Full { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Remove `key`'s entry, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Remove `key`'s entry, returning the stored key and value. The slot
+    /// becomes a tombstone.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find_index(key)?;
+        match mem::replace(&mut self.slots[index], Slot::Tombstone) {
+            Slot::Full { key, value, .. } => {
+                self.len -= 1;
+                self.tombstones += 1;
+                Some((key, value))
+            }
+            _ => unreachable!("find_index returned a non-full slot"),
+        }
+    }
+
+    /// Make sure `additional` more entries can be inserted without
+    /// resizing.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len + additional;
+        let table_size = self.slots.len();
+        if self.len + self.tombstones + additional <= self.max_used(table_size) {
+            return;
+        }
+        // If the entries alone would fill no more than half the usable
+        // slots, it's tombstones that have filled the table, and rebuilding
+        // it at the same size clears them out. Otherwise, at least double,
+        // so that the cost of rehashing is amortized over many inserts.
+        let new_size = if needed <= self.max_used(table_size) / 2 {
+            table_size
+        } else {
+            self.table_size_for(needed).max(table_size * 2)
+        };
+        self.rebuild(new_size);
+    }
+
+    /// Shrink the table as much as possible, clearing out tombstones.
+    pub fn shrink_to_fit(&mut self) {
+        let size = if self.len == 0 {
+            0
+        } else {
+            self.table_size_for(self.len)
+        };
+        self.rebuild(size);
+    }
+
+    /// Rehash every entry into a fresh table of `table_size` slots. The
+    /// entries' hashes are cached, so keys aren't rehashed, and since the new
+    /// table has no tombstones, neither is equality ever checked.
+    fn rebuild(&mut self, table_size: usize) {
+        let old = mem::replace(&mut self.slots, empty_slots(table_size));
+        self.tombstones = 0;
+        for slot in old {
+            if let Slot::Full { hash, key, value } = slot {
+                let index = self
+                    .probe_sequence(hash)
+                    .find(|&index| matches!(self.slots[index], Slot::Empty))
+                    .expect("table has an empty slot");
+                self.slots[index] = Slot::Full { hash, key, value };
+            }
+        }
+    }
+
+    /// The slot holding `key`, if any.
+    fn find<Q>(&self, key: &Q) -> Option<&Slot<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_index(key).map(|index| &self.slots[index])
+    }
+
+    fn find_index<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.probe(self.hash_builder.hash_one(key), key)? {
+            Probe::Found(index) => Some(index),
+            Probe::Vacant(_) => None,
+        }
+    }
+
+    /// Follow `hash`'s probe sequence until we find `key`, or an empty slot
+    /// proving it's absent. Returns `None` only if the table has no slots.
+    fn probe<Q>(&self, hash: u64, key: &Q) -> Option<Probe>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let mut first_tombstone = None;
+        for index in self.probe_sequence(hash) {
+            match &self.slots[index] {
+                Slot::Empty => return Some(Probe::Vacant(first_tombstone.unwrap_or(index))),
+                Slot::Tombstone => {
+                    first_tombstone.get_or_insert(index);
+                }
+                Slot::Full {
+                    hash: slot_hash,
+                    key: slot_key,
+                    ..
+                } => {
+                    // Comparing the cached hashes first skips most calls
+                    // to `eq`, which may be expensive.
+                    if *slot_hash == hash && slot_key.borrow() == key {
+                        return Some(Probe::Found(index));
+                    }
+                }
+            }
+        }
+        // Every slot in use: only possible for an empty table, since we
+        // always keep a slot empty.
+        first_tombstone.map(Probe::Vacant)
+    }
+
+    /// The order in which to try slots for `hash`: the home slot, then
+    /// offsets of 1, 3, 6, 10, … (the triangular numbers). For a power-of-two
+    /// table size, this visits every slot exactly once.
+    fn probe_sequence(&self, hash: u64) -> impl Iterator<Item = usize> {
+        let mask = self.slots.len().wrapping_sub(1);
+        let mut index = hash as usize;
+        (0..self.slots.len()).map(move |step| {
+            index = index.wrapping_add(step) & mask;
+            index
+        })
+    }
+}
+
+fn empty_slots<K, V>(table_size: usize) -> Vec<Slot<K, V>> {
+    let mut slots = Vec::with_capacity(table_size);
+    slots.resize_with(table_size, || Slot::Empty);
+    slots
+}
+
+impl<K, V> Default for MiniMap<K, V, RandomState> {
+    fn default() -> Self {
+        MiniMap::new()
+    }
+}
+
+impl<K, V, S> Clone for MiniMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        MiniMap {
+            slots: self
+                .slots
+                .iter()
+                .map(|slot| match slot {
+                    Slot::Empty => Slot::Empty,
+                    Slot::Tombstone => Slot::Tombstone,
+                    Slot::Full { hash, key, value } => Slot::Full {
+                        hash: *hash,
+                        key: key.clone(),
+                        value: value.clone(),
+                    },
+                })
+                .collect(),
+            len: self.len,
+            tombstones: self.tombstones,
+            max_load_factor: self.max_load_factor,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<K, V, S> PartialEq for MiniMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V, S> Eq for MiniMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for MiniMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for MiniMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for MiniMap<K, V, RandomState> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = MiniMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, Q, S> Index<&Q> for MiniMap<K, V, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Panics if `key` is not present.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    slots: std::slice::Iter<'a, Slot<K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for slot in self.slots.by_ref() {
+            if let Slot::Full { key, value, .. } = slot {
+                self.remaining -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            slots: self.slots.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+
+pub struct IterMut<'a, K, V> {
+    slots: std::slice::IterMut<'a, Slot<K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for slot in self.slots.by_ref() {
+            if let Slot::Full { key, value, .. } = slot {
+                self.remaining -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+pub struct IntoIter<K, V> {
+    slots: std::vec::IntoIter<Slot<K, V>>,
+    remaining: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        for slot in self.slots.by_ref() {
+            if let Slot::Full { key, value, .. } = slot {
+                self.remaining -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+impl<K, V, S> IntoIterator for MiniMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            slots: self.slots.into_iter(),
+            remaining: self.len,
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a MiniMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut MiniMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
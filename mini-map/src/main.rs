@@ -0,0 +1,23 @@
+use mini_map::MiniMap;
+
+fn main() {
+    let mut ballots: MiniMap<String, u32> = MiniMap::new();
+    for vote in "fern moss fern lichen fern moss".split_whitespace() {
+        let count = ballots.get(vote).copied().unwrap_or(0);
+        ballots.insert(vote.to_string(), count + 1);
+    }
+
+    // Lookups take any borrowed form of the key: here, `&str` for `String`.
+    println!("fern: {}", ballots["fern"]);
+    println!("moss: {:?}", ballots.get("moss"));
+    println!("cactus: {:?}", ballots.get("cactus"));
+
+    ballots.remove("lichen");
+    println!("after removing lichen: {:?}", ballots);
+    println!(
+        "{} entries in a table of {} slots (capacity {})",
+        ballots.len(),
+        ballots.table_size(),
+        ballots.capacity()
+    );
+}
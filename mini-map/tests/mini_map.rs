@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::rc::Rc;
+
+use mini_map::MiniMap;
+use proptest::prelude::*;
+
+/// A hasher that sends every key to the same slot, so that every operation
+/// has to probe past every other entry.
+#[derive(Clone, Default)]
+struct Collide;
+
+impl Hasher for Collide {
+    fn finish(&self) -> u64 {
+        0
+    }
+    fn write(&mut self, _bytes: &[u8]) {}
+}
+
+impl BuildHasher for Collide {
+    type Hasher = Collide;
+    fn build_hasher(&self) -> Collide {
+        Collide
+    }
+}
+
+#[test]
+fn insert_get_remove() {
+    let mut map = MiniMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.get(&1), None);
+
+    assert_eq!(map.insert(1, "one"), None);
+    assert_eq!(map.insert(2, "two"), None);
+    assert_eq!(map.insert(1, "uno"), Some("one"));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&1), Some(&"uno"));
+    assert_eq!(map[&2], "two");
+
+    assert_eq!(map.remove(&1), Some("uno"));
+    assert_eq!(map.remove(&1), None);
+    assert!(!map.contains_key(&1));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn borrowed_lookups() {
+    let mut map: MiniMap<String, Vec<u8>> = MiniMap::new();
+    map.insert("bytes".to_string(), vec![1, 2, 3]);
+
+    // `String: Borrow<str>`, so a `&str` will do.
+    assert_eq!(map.get("bytes").map(Vec::len), Some(3));
+    map.get_mut("bytes").unwrap().push(4);
+    assert_eq!(
+        map.get_key_value("bytes"),
+        Some((&"bytes".to_string(), &vec![1, 2, 3, 4]))
+    );
+    assert_eq!(
+        map.remove_entry("bytes"),
+        Some(("bytes".to_string(), vec![1, 2, 3, 4]))
+    );
+    assert!(map.get("bytes").is_none());
+}
+
+#[test]
+#[should_panic(expected = "no entry found for key")]
+fn indexing_a_missing_key_panics() {
+    let map: MiniMap<&str, i32> = MiniMap::new();
+    let _ = map["missing"];
+}
+
+#[test]
+fn tombstones_keep_later_entries_reachable() {
+    // With every key in one probe chain, removing an entry in the middle
+    // must not cut off the entries beyond it.
+    let mut map = MiniMap::with_hasher(Collide);
+    for i in 0..6 {
+        map.insert(i, i * 10);
+    }
+    map.remove(&2);
+    map.remove(&0);
+    for i in [1, 3, 4, 5] {
+        assert_eq!(map.get(&i), Some(&(i * 10)));
+    }
+    assert_eq!(map.get(&2), None);
+
+    // Reinserting reuses a tombstone, and doesn't duplicate keys that sit
+    // past one.
+    map.insert(5, 55);
+    map.insert(2, 22);
+    assert_eq!(map.len(), 5);
+    assert_eq!(map.get(&5), Some(&55));
+    assert_eq!(map.iter().count(), 5);
+}
+
+#[test]
+fn churn_does_not_grow_the_table_forever() {
+    let mut map = MiniMap::new();
+    for i in 0..50 {
+        map.insert(i, ());
+    }
+    let initial = map.table_size();
+    // The first tombstone-triggered rebuild may grow the table once, if the
+    // entries fill more than half of it; after that, rebuilds happen in
+    // place.
+    let mut sizes = Vec::new();
+    for i in 50..100_000 {
+        map.remove(&(i - 50));
+        map.insert(i, ());
+        if sizes.last() != Some(&map.table_size()) {
+            sizes.push(map.table_size());
+        }
+    }
+    assert_eq!(map.len(), 50);
+    assert!(sizes.len() <= 2, "table sizes: {:?}", sizes);
+    assert!(map.table_size() <= initial * 2);
+    assert!((99_950..100_000).all(|i| map.contains_key(&i)));
+}
+
+#[test]
+fn resizing_keeps_every_entry() {
+    let mut map = MiniMap::new();
+    let mut sizes = vec![map.table_size()];
+    for i in 0..10_000u32 {
+        map.insert(i, i.to_string());
+        if *sizes.last().unwrap() != map.table_size() {
+            sizes.push(map.table_size());
+        }
+    }
+    assert!(sizes[1..].iter().all(|size| size.is_power_of_two()));
+    assert!(sizes.windows(2).all(|w| w[1] >= w[0] * 2));
+    assert!(map.len() as f64 <= map.table_size() as f64 * map.max_load_factor());
+    for i in 0..10_000u32 {
+        assert_eq!(map[&i], i.to_string());
+    }
+
+    for i in 100..10_000 {
+        map.remove(&i);
+    }
+    map.shrink_to_fit();
+    assert!(map.table_size() < 256);
+    assert_eq!(map.len(), 100);
+    assert!((0..100).all(|i| map.contains_key(&i)));
+}
+
+#[test]
+fn with_capacity_avoids_resizing() {
+    let mut map = MiniMap::with_capacity(1000);
+    assert!(map.capacity() >= 1000);
+    let size = map.table_size();
+    map.extend((0..1000).map(|i| (i, i)));
+    assert_eq!(map.table_size(), size);
+}
+
+#[test]
+#[should_panic(expected = "strictly between 0 and 1")]
+fn load_factor_one_is_rejected() {
+    let _ = MiniMap::<u8, u8>::new().with_max_load_factor(1.0);
+}
+
+#[test]
+fn high_load_factor_still_terminates() {
+    let mut map = MiniMap::new().with_max_load_factor(0.99);
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+    assert!(map.len() < map.table_size());
+    for i in 1000..2000 {
+        assert_eq!(map.get(&i), None);
+    }
+}
+
+#[test]
+fn values_are_dropped_exactly_once() {
+    let token = Rc::new(());
+    {
+        let mut map = MiniMap::new();
+        for i in 0..100 {
+            map.insert(i, token.clone());
+        }
+        for i in 0..50 {
+            map.insert(i, token.clone());
+        }
+        for i in 25..75 {
+            map.remove(&i);
+        }
+        map.clear();
+        for i in 0..100 {
+            map.insert(i, token.clone());
+        }
+        let mut rest = map.clone().into_iter();
+        rest.next();
+        assert_eq!(Rc::strong_count(&token), 1 + 100 + 99);
+    }
+    assert_eq!(Rc::strong_count(&token), 1);
+}
+
+#[test]
+fn iteration_and_equality() {
+    let map: MiniMap<char, usize> = "hello".chars().zip(0..).collect();
+    let mut entries: Vec<(char, usize)> = map.iter().map(|(&k, &v)| (k, v)).collect();
+    entries.sort();
+    assert_eq!(entries, [('e', 1), ('h', 0), ('l', 3), ('o', 4)]);
+    assert_eq!(map.iter().len(), 4);
+
+    let mut doubled = map.clone();
+    for (_, value) in &mut doubled {
+        *value *= 2;
+    }
+    assert_ne!(doubled, map);
+    assert_eq!(doubled[&'l'], 6);
+    let mut keys: Vec<char> = doubled.keys().copied().collect();
+    keys.sort();
+    assert_eq!(keys, ['e', 'h', 'l', 'o']);
+    assert_eq!(doubled.values().sum::<usize>(), 16);
+
+    let same: MiniMap<char, usize> = [('o', 4), ('l', 3), ('h', 0), ('e', 1)]
+        .into_iter()
+        .collect();
+    assert_eq!(same, map);
+    assert_eq!(format!("{:?}", MiniMap::<u8, u8>::new()), "{}");
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u8, u16),
+    Remove(u8),
+    Get(u8),
+    Clear,
+    Shrink,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    // Small keys, so that inserts, removes, and lookups often collide.
+    prop_oneof![
+        10 => (any::<u8>().prop_map(|k| k % 64), any::<u16>()).prop_map(|(k, v)| Op::Insert(k, v)),
+        6 => any::<u8>().prop_map(|k| Op::Remove(k % 64)),
+        6 => any::<u8>().prop_map(|k| Op::Get(k % 64)),
+        1 => Just(Op::Clear),
+        1 => Just(Op::Shrink),
+    ]
+}
+
+fn run_against_std<S: BuildHasher>(
+    mut map: MiniMap<u8, u16, S>,
+    ops: Vec<Op>,
+) -> Result<(), TestCaseError> {
+    let mut model = HashMap::new();
+    for op in ops {
+        match op {
+            Op::Insert(k, v) => prop_assert_eq!(map.insert(k, v), model.insert(k, v)),
+            Op::Remove(k) => prop_assert_eq!(map.remove(&k), model.remove(&k)),
+            Op::Get(k) => prop_assert_eq!(map.get(&k), model.get(&k)),
+            Op::Clear => {
+                map.clear();
+                model.clear();
+            }
+            Op::Shrink => map.shrink_to_fit(),
+        }
+        prop_assert_eq!(map.len(), model.len());
+        prop_assert!(map.len() < map.table_size() || map.table_size() == 0);
+    }
+    let mut entries: Vec<(u8, u16)> = map.into_iter().collect();
+    let mut expected: Vec<(u8, u16)> = model.into_iter().collect();
+    entries.sort();
+    expected.sort();
+    prop_assert_eq!(entries, expected);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn matches_std_hashmap(ops in prop::collection::vec(op(), 0..300)) {
+        run_against_std(MiniMap::new(), ops)?;
+    }
+
+    #[test]
+    fn matches_std_hashmap_at_any_load_factor(ops in prop::collection::vec(op(), 0..300), load in 0.1f64..0.99) {
+        run_against_std(MiniMap::new().with_max_load_factor(load), ops)?;
+    }
+
+    #[test]
+    fn matches_std_hashmap_with_total_collisions(ops in prop::collection::vec(op(), 0..150)) {
+        run_against_std(MiniMap::with_hasher(Collide), ops)?;
+    }
+}
@@ -0,0 +1,265 @@
+// The executor: a queue of ready tasks, and a loop that polls them.
+//
+// Every task's `Waker` knows only the task's number and the queue. Waking
+// a task pushes its number onto the queue, at most once until the task is
+// next polled, and wakes the executor's thread if it's parked. The tasks
+// themselves stay in a map owned by the executor.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Instant;
+
+use crate::task::{self, JoinHandle};
+use crate::timer::Wheel;
+
+type TaskId = u64;
+
+/// The future passed to `block_on` isn't stored with the spawned tasks,
+/// since it needn't be `'static`, but it's woken the same way.
+const MAIN: TaskId = 0;
+
+struct Queue {
+    ready: Mutex<VecDeque<TaskId>>,
+    available: Condvar,
+}
+
+impl Queue {
+    fn push(&self, id: TaskId) {
+        self.ready.lock().unwrap().push_back(id);
+        self.available.notify_one();
+    }
+
+    fn pop(&self) -> Option<TaskId> {
+        self.ready.lock().unwrap().pop_front()
+    }
+
+    /// Block until a task is ready, or until `deadline` if there is one.
+    fn park(&self, deadline: Option<Instant>) {
+        let mut ready = self.ready.lock().unwrap();
+        while ready.is_empty() {
+            match deadline {
+                None => ready = self.available.wait(ready).unwrap(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+                    ready = self
+                        .available
+                        .wait_timeout(ready, deadline - now)
+                        .unwrap()
+                        .0;
+                }
+            }
+        }
+    }
+}
+
+struct TaskWaker {
+    id: TaskId,
+    /// Set while the task's number is in the queue, so that waking it
+    /// again before it runs doesn't queue it twice.
+    scheduled: AtomicBool,
+    queue: Arc<Queue>,
+}
+
+impl TaskWaker {
+    fn schedule(&self) {
+        if !self.scheduled.swap(true, Ordering::AcqRel) {
+            self.queue.push(self.id);
+        }
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.schedule();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.schedule();
+    }
+}
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    header: Arc<TaskWaker>,
+    waker: Waker,
+}
+
+pub(crate) struct Shared {
+    queue: Arc<Queue>,
+    tasks: RefCell<HashMap<TaskId, Task>>,
+    next_id: Cell<TaskId>,
+    pub(crate) timers: RefCell<Wheel>,
+}
+
+impl Shared {
+    fn waker(&self, id: TaskId) -> Arc<TaskWaker> {
+        Arc::new(TaskWaker {
+            id,
+            scheduled: AtomicBool::new(false),
+            queue: self.queue.clone(),
+        })
+    }
+
+    fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        let (handle, future) = task::joinable(future);
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let header = self.waker(id);
+        let task = Task {
+            future: Box::pin(future),
+            header: header.clone(),
+            waker: Waker::from(header.clone()),
+        };
+        self.tasks.borrow_mut().insert(id, task);
+        header.schedule();
+        handle
+    }
+
+    fn run(&self, id: TaskId) {
+        // A finished task can still be woken by a stray clone of its waker.
+        let Some(mut task) = self.tasks.borrow_mut().remove(&id) else {
+            return;
+        };
+        // Clear the flag first: if the task wakes itself while it's being
+        // polled, it has to go back in the queue.
+        task.header.scheduled.store(false, Ordering::Release);
+        let mut cx = Context::from_waker(&task.waker);
+        // The task is out of the map while it runs, so it can spawn more.
+        if task.future.as_mut().poll(&mut cx).is_pending() {
+            self.tasks.borrow_mut().insert(id, task);
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Shared>>> = const { RefCell::new(None) };
+}
+
+/// Marks the thread as running a runtime, until dropped.
+struct Enter;
+
+fn enter(shared: Rc<Shared>) -> Enter {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        assert!(
+            current.is_none(),
+            "block_on called from inside a runtime: this would block every other task"
+        );
+        *current = Some(shared);
+    });
+    Enter
+}
+
+impl Drop for Enter {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+pub(crate) fn with_current<R>(f: impl FnOnce(&Shared) -> R) -> R {
+    let shared = CURRENT
+        .with(|current| current.borrow().clone())
+        .expect("must be called from a task running on a mini-runtime");
+    f(&shared)
+}
+
+/// A single-threaded executor with a timer wheel.
+///
+/// Tasks spawned onto a runtime run only while one of its `block_on`
+/// calls is in progress. Those still unfinished when `block_on` returns
+/// carry on in the next one, or are dropped with the runtime.
+pub struct Runtime {
+    shared: Rc<Shared>,
+}
+
+impl Runtime {
+    pub fn new() -> Runtime {
+        Runtime {
+            shared: Rc::new(Shared {
+                queue: Arc::new(Queue {
+                    ready: Mutex::new(VecDeque::new()),
+                    available: Condvar::new(),
+                }),
+                tasks: RefCell::new(HashMap::new()),
+                next_id: Cell::new(MAIN + 1),
+                timers: RefCell::new(Wheel::new(Instant::now())),
+            }),
+        }
+    }
+
+    /// Run `future` to completion, along with any spawned tasks, and
+    /// return its output. A panic in any task propagates out of here.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let shared = &self.shared;
+        let _enter = enter(shared.clone());
+        let mut future = pin!(future);
+        let main = shared.waker(MAIN);
+        let waker = Waker::from(main.clone());
+        main.schedule();
+        loop {
+            shared.timers.borrow_mut().advance(Instant::now());
+            match shared.queue.pop() {
+                Some(MAIN) => {
+                    main.scheduled.store(false, Ordering::Release);
+                    let mut cx = Context::from_waker(&waker);
+                    if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                        return output;
+                    }
+                }
+                Some(id) => shared.run(id),
+                None => {
+                    let deadline = shared.timers.borrow().next_deadline();
+                    shared.queue.park(deadline);
+                }
+            }
+        }
+    }
+
+    /// Add a task to the runtime. It starts running at the next
+    /// `block_on`.
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'static,
+        F::Output: 'static,
+    {
+        self.shared.spawn(future)
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Runtime {
+        Runtime::new()
+    }
+}
+
+/// Run `future` to completion on a new runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    Runtime::new().block_on(future)
+}
+
+/// Spawn a task onto the runtime that's running the current task. The
+/// task runs until it finishes, even if the `JoinHandle` is dropped.
+///
+/// # Panics
+///
+/// Panics if called outside `block_on`.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    with_current(|shared| shared.spawn(future))
+}
@@ -0,0 +1,89 @@
+// `join!`: wait for several futures at once.
+//
+// The macro puts each future in a `MaybeDone` inside one tuple, and polls
+// every unfinished one whenever the joined future is woken. To reach the
+// nth element of the tuple without naming a field, it pairs each future
+// with a pattern: `(future, ..)`, then `(_, future, ..)`, and so on.
+
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub enum MaybeDone<F: Future> {
+    /// Boxed so that the tuple of `MaybeDone`s can move freely, and the
+    /// macro needs no `unsafe` pinning.
+    Running(Pin<Box<F>>),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    pub fn new(future: F) -> MaybeDone<F> {
+        MaybeDone::Running(Box::pin(future))
+    }
+
+    /// Poll the future if it's still running. Returns true once it has
+    /// finished.
+    pub fn poll_done(&mut self, cx: &mut Context<'_>) -> bool {
+        if let MaybeDone::Running(future) = self {
+            match future.as_mut().poll(cx) {
+                Poll::Ready(output) => *self = MaybeDone::Done(output),
+                Poll::Pending => return false,
+            }
+        }
+        true
+    }
+
+    pub fn take(&mut self) -> F::Output {
+        match mem::replace(self, MaybeDone::Taken) {
+            MaybeDone::Done(output) => output,
+            _ => panic!("join! output taken before it was ready"),
+        }
+    }
+}
+
+/// Wait for every future, running them concurrently, and evaluate to a
+/// tuple of their outputs. Can only be used inside `async` code.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let (a, b) = mini_runtime::block_on(async {
+///     mini_runtime::join!(
+///         async {
+///             mini_runtime::sleep(Duration::from_millis(20)).await;
+///             1
+///         },
+///         async { "two" },
+///     )
+/// });
+/// assert_eq!((a, b), (1, "two"));
+/// ```
+#[macro_export]
+macro_rules! join {
+    (@pair [$($paired:tt)*] [$($skip:tt)*] $future:expr, $($rest:tt)*) => {
+        $crate::join!(@pair [$($paired)* [($($skip)*) $future]] [$($skip)* _] $($rest)*)
+    };
+    (@pair [$([($($skip:tt)*) $future:expr])*] [$($unused:tt)*]) => {{
+        let mut futures = ($($crate::join::MaybeDone::new($future),)*);
+        ::std::future::poll_fn(|cx| {
+            let mut done = true;
+            $(
+                let ($($skip,)* future, ..) = &mut futures;
+                done &= future.poll_done(cx);
+            )*
+            if !done {
+                return ::std::task::Poll::Pending;
+            }
+            ::std::task::Poll::Ready(($({
+                let ($($skip,)* future, ..) = &mut futures;
+                future.take()
+            },)*))
+        })
+        .await
+    }};
+    ($($future:expr),+ $(,)?) => {
+        $crate::join!(@pair [] [] $($future,)+)
+    };
+}
@@ -0,0 +1,19 @@
+// A minimal async runtime, small enough to read in one sitting.
+//
+// `async fn`s compile to state machines that do nothing until polled. A
+// runtime supplies the polling: `block_on` polls a future until it
+// finishes, `spawn` hands the executor more futures to poll alongside it,
+// and when nothing can make progress, the executor parks its thread until
+// a `Waker` says otherwise, or until the timer wheel's next deadline.
+// Everything runs on the thread that called `block_on`, so tasks needn't
+// be `Send`; only their wakers cross threads.
+
+mod executor;
+#[doc(hidden)]
+pub mod join;
+mod task;
+mod timer;
+
+pub use executor::{block_on, spawn, Runtime};
+pub use task::{yield_now, JoinHandle, YieldNow};
+pub use timer::{sleep, sleep_until, Sleep};
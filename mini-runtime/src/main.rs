@@ -0,0 +1,152 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+/// What the demo program needs from a runtime.
+trait Platform: 'static {
+    const NAME: &'static str;
+
+    fn block_on(program: impl Future<Output = ()> + 'static);
+    fn sleep(millis: u64) -> impl Future<Output = ()>;
+    fn spawn<T: 'static>(task: impl Future<Output = T> + 'static) -> impl Future<Output = T>;
+}
+
+struct Mini;
+
+impl Platform for Mini {
+    const NAME: &'static str = "mini-runtime";
+
+    fn block_on(program: impl Future<Output = ()> + 'static) {
+        mini_runtime::block_on(program)
+    }
+
+    fn sleep(millis: u64) -> impl Future<Output = ()> {
+        mini_runtime::sleep(Duration::from_millis(millis))
+    }
+
+    fn spawn<T: 'static>(task: impl Future<Output = T> + 'static) -> impl Future<Output = T> {
+        mini_runtime::spawn(task)
+    }
+}
+
+/// Tokio's single-threaded runtime, with a `LocalSet` so that tasks
+/// needn't be `Send`: the same deal mini-runtime offers.
+struct Tokio;
+
+impl Platform for Tokio {
+    const NAME: &'static str = "tokio";
+
+    fn block_on(program: impl Future<Output = ()> + 'static) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap_or_else(|e| {
+                eprintln!("Error: failed to start tokio: {}", e);
+                process::exit(1);
+            });
+        tokio::task::LocalSet::new().block_on(&runtime, program)
+    }
+
+    fn sleep(millis: u64) -> impl Future<Output = ()> {
+        tokio::time::sleep(Duration::from_millis(millis))
+    }
+
+    fn spawn<T: 'static>(task: impl Future<Output = T> + 'static) -> impl Future<Output = T> {
+        let handle = tokio::task::spawn_local(task);
+        async { handle.await.expect("task panicked") }
+    }
+}
+
+struct Log {
+    start: Instant,
+    events: RefCell<Vec<String>>,
+}
+
+impl Log {
+    fn event(&self, event: String) {
+        println!("{:>4} ms  {}", self.start.elapsed().as_millis(), event);
+        self.events.borrow_mut().push(event);
+    }
+}
+
+/// Three downloads of different lengths, started in order, and a ticker
+/// running alongside them. Then two waits joined, to show they overlap.
+async fn program<P: Platform>(log: Rc<Log>) {
+    let downloads: Vec<_> = [("large", 120), ("small", 40), ("medium", 80)]
+        .into_iter()
+        .map(|(name, millis)| {
+            let log = log.clone();
+            P::spawn(async move {
+                log.event(format!("{} download started", name));
+                P::sleep(millis).await;
+                log.event(format!("{} download finished", name));
+                millis
+            })
+        })
+        .collect();
+
+    for tick in 1..=3 {
+        P::sleep(30).await;
+        log.event(format!("tick {}", tick));
+    }
+
+    let mut total = 0;
+    for download in downloads {
+        total += download.await;
+    }
+    log.event(format!("downloads took {} ms between them", total));
+
+    let before = Instant::now();
+    mini_runtime::join!(P::sleep(50), P::sleep(50));
+    let overlap = if before.elapsed() < Duration::from_millis(100) {
+        "concurrently"
+    } else {
+        "one after the other"
+    };
+    log.event(format!("two 50 ms sleeps ran {}", overlap));
+}
+
+fn run<P: Platform>() {
+    println!("running on {}", P::NAME);
+    let log = Rc::new(Log {
+        start: Instant::now(),
+        events: RefCell::new(Vec::new()),
+    });
+    P::block_on(program::<P>(log.clone()));
+    println!(
+        "{} events in {:.1?}",
+        log.events.borrow().len(),
+        log.start.elapsed()
+    );
+}
+
+fn print_usage() {
+    eprintln!("mini-runtime - run a small async program on a homemade runtime");
+    eprintln!("Usage: mini-runtime [--tokio]");
+    eprintln!("      --tokio   run the same program on tokio, for comparison");
+}
+
+fn main() {
+    let mut tokio = false;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--tokio" => tokio = true,
+            "-h" | "--help" => {
+                print_usage();
+                process::exit(0);
+            }
+            _ => {
+                print_usage();
+                eprintln!("Error: unknown argument {}", arg);
+                process::exit(2);
+            }
+        }
+    }
+    if tokio {
+        run::<Tokio>();
+    } else {
+        run::<Mini>();
+    }
+}
@@ -0,0 +1,84 @@
+// Spawned tasks' results, and yielding to other tasks.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+enum State<T> {
+    Running(Option<Waker>),
+    Finished(T),
+    Taken,
+}
+
+/// Resolves to a spawned task's output.
+pub struct JoinHandle<T> {
+    state: Rc<RefCell<State<T>>>,
+}
+
+/// Wrap `future` so that its output goes to the returned handle, waking
+/// whoever is waiting on it.
+pub(crate) fn joinable<F: Future>(future: F) -> (JoinHandle<F::Output>, impl Future<Output = ()>) {
+    let state = Rc::new(RefCell::new(State::Running(None)));
+    let handle = JoinHandle {
+        state: state.clone(),
+    };
+    let task = async move {
+        let output = future.await;
+        let waiting = mem::replace(&mut *state.borrow_mut(), State::Finished(output));
+        if let State::Running(Some(waker)) = waiting {
+            waker.wake();
+        }
+    };
+    (handle, task)
+}
+
+impl<T> JoinHandle<T> {
+    pub fn is_finished(&self) -> bool {
+        !matches!(*self.state.borrow(), State::Running(_))
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.borrow_mut();
+        match mem::replace(&mut *state, State::Taken) {
+            State::Running(_) => {
+                *state = State::Running(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+            State::Finished(output) => Poll::Ready(output),
+            State::Taken => panic!("JoinHandle polled after completion"),
+        }
+    }
+}
+
+/// Returned by `yield_now`.
+pub struct YieldNow {
+    yielded: bool,
+}
+
+/// Let every other ready task run before this one continues.
+///
+/// The task wakes itself and returns `Pending` once. Its number goes to
+/// the back of the queue, behind whatever is already there.
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
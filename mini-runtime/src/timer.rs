@@ -0,0 +1,184 @@
+// Timers: `sleep`, and the wheel that wakes sleepers on time.
+//
+// The wheel is a ring of slots, one per millisecond tick. A timer due at
+// tick t goes in slot t % SLOTS. One due more than a full turn ahead
+// shares its slot with nearer timers and stays put when the wheel passes,
+// its tick number saying it belongs to a later turn. Inserting a timer is
+// O(1), and advancing the wheel visits only the slots for ticks that have
+// gone by. Finding the next deadline, so the executor knows how long it
+// may park, scans every timer; production wheels stack several coarser
+// wheels to avoid that, but this one is meant to be read.
+//
+// Deadlines round up to the next tick, so a timer can fire up to a tick
+// late, plus however long the executor takes to notice, but never early.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::executor::with_current;
+
+const TICK: Duration = Duration::from_millis(1);
+const SLOTS: u64 = 256;
+
+/// Shared by a timer in the wheel and the `Sleep` that registered it. When
+/// the `Sleep` is dropped, the wheel holds the only reference, and
+/// discards the timer the next time it passes.
+type Entry = Rc<RefCell<Waker>>;
+
+struct Timer {
+    tick: u64,
+    entry: Entry,
+}
+
+impl Timer {
+    fn cancelled(&self) -> bool {
+        Rc::strong_count(&self.entry) == 1
+    }
+}
+
+pub(crate) struct Wheel {
+    start: Instant,
+    /// Every tick up to and including this one has fired.
+    elapsed: u64,
+    slots: Vec<Vec<Timer>>,
+}
+
+impl Wheel {
+    pub(crate) fn new(start: Instant) -> Wheel {
+        Wheel {
+            start,
+            elapsed: 0,
+            slots: (0..SLOTS).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    fn slot(&mut self, tick: u64) -> &mut Vec<Timer> {
+        &mut self.slots[(tick % SLOTS) as usize]
+    }
+
+    fn instant(&self, tick: u64) -> Instant {
+        // `Duration * u32` would wrap after 2³² ticks, about 49 days.
+        let nanos = TICK.as_nanos() * u128::from(tick);
+        let since = Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        );
+        self.start + since
+    }
+
+    fn insert(&mut self, deadline: Instant, waker: Waker) -> Entry {
+        let since = deadline.saturating_duration_since(self.start);
+        let tick = (since.as_nanos().div_ceil(TICK.as_nanos()) as u64).max(self.elapsed + 1);
+        let entry = Rc::new(RefCell::new(waker));
+        self.slot(tick).push(Timer {
+            tick,
+            entry: entry.clone(),
+        });
+        entry
+    }
+
+    /// Fire every timer due by `now`, earliest first.
+    pub(crate) fn advance(&mut self, now: Instant) {
+        let now_tick =
+            (now.saturating_duration_since(self.start).as_nanos() / TICK.as_nanos()) as u64;
+        if now_tick <= self.elapsed {
+            return;
+        }
+        // After a long gap, one full turn covers every slot.
+        let passed = (now_tick - self.elapsed).min(SLOTS);
+        let mut due = Vec::new();
+        for tick in self.elapsed + 1..=self.elapsed + passed {
+            self.slot(tick).retain(|timer| {
+                if timer.cancelled() {
+                    false
+                } else if timer.tick <= now_tick {
+                    due.push((timer.tick, timer.entry.clone()));
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+        self.elapsed = now_tick;
+        due.sort_by_key(|&(tick, _)| tick);
+        for (_, entry) in due {
+            entry.borrow().wake_by_ref();
+        }
+    }
+
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let tick = self
+            .slots
+            .iter()
+            .flatten()
+            .filter(|timer| !timer.cancelled())
+            .map(|timer| timer.tick)
+            .min()?;
+        Some(self.instant(tick))
+    }
+}
+
+/// A future that finishes at a given instant. Returned by `sleep` and
+/// `sleep_until`.
+pub struct Sleep {
+    deadline: Instant,
+    entry: Option<Entry>,
+}
+
+/// Wait until `duration` has passed.
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(Instant::now() + duration)
+}
+
+/// Wait until `deadline`.
+///
+/// The returned future registers with the timer wheel of the runtime that
+/// first polls it, so it can only be polled inside `block_on`.
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep {
+        deadline,
+        entry: None,
+    }
+}
+
+impl Sleep {
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            self.entry = None;
+            return Poll::Ready(());
+        }
+        match &self.entry {
+            // The task may have moved to a different waker since the last
+            // poll, as happens when a `Sleep` is passed to another task.
+            Some(entry) => {
+                let mut waker = entry.borrow_mut();
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+            }
+            None => {
+                let deadline = self.deadline;
+                let entry = with_current(|shared| {
+                    shared
+                        .timers
+                        .borrow_mut()
+                        .insert(deadline, cx.waker().clone())
+                });
+                self.entry = Some(entry);
+            }
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,38 @@
+use std::process::Command;
+
+/// Run the demo and return its events, without their timestamps.
+fn events(args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_mini-runtime"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split_once(" ms  "))
+        .map(|(_, event)| event.to_string())
+        .collect()
+}
+
+#[test]
+fn same_program_same_events_as_tokio() {
+    let mini = events(&[]);
+    assert_eq!(
+        mini,
+        [
+            "large download started",
+            "small download started",
+            "medium download started",
+            "tick 1",
+            "small download finished",
+            "tick 2",
+            "medium download finished",
+            "tick 3",
+            "large download finished",
+            "downloads took 240 ms between them",
+            "two 50 ms sleeps ran concurrently",
+        ]
+    );
+    assert_eq!(mini, events(&["--tokio"]));
+}
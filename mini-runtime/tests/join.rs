@@ -0,0 +1,62 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use mini_runtime::{block_on, join, sleep, yield_now};
+
+#[test]
+fn outputs_in_argument_order() {
+    let outputs = block_on(async {
+        join!(
+            async {
+                sleep(Duration::from_millis(20)).await;
+                1
+            },
+            async { "two" },
+            async {
+                yield_now().await;
+                3.0
+            },
+        )
+    });
+    assert_eq!(outputs, (1, "two", 3.0));
+}
+
+#[test]
+fn single_future() {
+    assert_eq!(block_on(async { join!(async { 7 }) }), (7,));
+}
+
+#[test]
+fn futures_make_progress_together() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let step = |name: &'static str| {
+        let log = log.clone();
+        async move {
+            for i in 0..3 {
+                log.borrow_mut().push(format!("{}{}", name, i));
+                yield_now().await;
+            }
+        }
+    };
+    block_on(async { join!(step("a"), step("b")) });
+    assert_eq!(*log.borrow(), ["a0", "b0", "a1", "b1", "a2", "b2"]);
+}
+
+#[test]
+fn borrows_across_awaits() {
+    let mut words = vec!["x"];
+    let start = Instant::now();
+    let (length, ()) = block_on(async {
+        join!(
+            async {
+                sleep(Duration::from_millis(30)).await;
+                words.len()
+            },
+            sleep(Duration::from_millis(30)),
+        )
+    });
+    assert!(start.elapsed() < Duration::from_millis(55));
+    words.push("y");
+    assert_eq!(length, 1);
+}
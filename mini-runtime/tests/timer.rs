@@ -0,0 +1,137 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use mini_runtime::{block_on, join, sleep, sleep_until, spawn};
+
+/// How late a timer may fire. The wheel rounds up to a millisecond; the
+/// rest allows for a busy test machine.
+const SLACK: Duration = Duration::from_millis(25);
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn sleeps_are_never_early_and_rarely_late() {
+    block_on(async {
+        for millis in [1, 5, 20, 50] {
+            let start = Instant::now();
+            sleep(ms(millis)).await;
+            let elapsed = start.elapsed();
+            assert!(
+                elapsed >= ms(millis),
+                "{:?} sleep took {:?}",
+                ms(millis),
+                elapsed
+            );
+            assert!(
+                elapsed < ms(millis) + SLACK,
+                "{:?} sleep took {:?}",
+                ms(millis),
+                elapsed
+            );
+        }
+    });
+}
+
+#[test]
+fn elapsed_deadlines_are_ready_at_once() {
+    block_on(async {
+        let start = Instant::now();
+        sleep(Duration::ZERO).await;
+        sleep_until(start - ms(10)).await;
+        assert!(start.elapsed() < ms(5));
+    });
+}
+
+#[test]
+fn timers_fire_in_deadline_order() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    block_on({
+        let log = log.clone();
+        async move {
+            let tasks: Vec<_> = [30, 10, 50, 20, 40]
+                .into_iter()
+                .map(|millis| {
+                    let log = log.clone();
+                    spawn(async move {
+                        sleep(ms(millis)).await;
+                        log.borrow_mut().push(millis);
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await;
+            }
+        }
+    });
+    assert_eq!(*log.borrow(), [10, 20, 30, 40, 50]);
+}
+
+#[test]
+fn sleeps_overlap() {
+    block_on(async {
+        let start = Instant::now();
+        join!(sleep(ms(60)), sleep(ms(60)), sleep(ms(60)));
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= ms(60) && elapsed < ms(60) + SLACK,
+            "{:?}",
+            elapsed
+        );
+    });
+}
+
+/// The wheel has 256 one-millisecond slots. A timer more than a turn away
+/// shares a slot with nearer ones, and must wait for its own turn.
+#[test]
+fn timers_beyond_one_turn_of_the_wheel() {
+    block_on(async {
+        let start = Instant::now();
+        let far = spawn(async move {
+            sleep(ms(300)).await;
+            start.elapsed()
+        });
+        // 300 - 256 = 44: probably the far timer's slot.
+        let near = spawn(async move {
+            sleep(ms(44)).await;
+            start.elapsed()
+        });
+        let near = near.await;
+        assert!(near >= ms(44) && near < ms(44) + SLACK, "{:?}", near);
+        let far = far.await;
+        assert!(far >= ms(300) && far < ms(300) + SLACK, "{:?}", far);
+    });
+}
+
+#[test]
+fn many_timers() {
+    let lateness = Rc::new(RefCell::new(Vec::new()));
+    block_on({
+        let lateness = lateness.clone();
+        async move {
+            let start = Instant::now();
+            let tasks: Vec<_> = (0..500u64)
+                .map(|i| {
+                    let lateness = lateness.clone();
+                    // Spread deadlines over 0..100 ms, out of order.
+                    let deadline = start + ms(i * 37 % 100);
+                    spawn(async move {
+                        sleep_until(deadline).await;
+                        let now = Instant::now();
+                        assert!(now >= deadline, "fired early");
+                        lateness.borrow_mut().push(now - deadline);
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await;
+            }
+        }
+    });
+    let lateness = lateness.borrow();
+    assert_eq!(lateness.len(), 500);
+    let worst = lateness.iter().max().unwrap();
+    assert!(*worst < SLACK, "worst lateness {:?}", worst);
+}
@@ -0,0 +1,226 @@
+use std::cell::{Cell, RefCell};
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+use mini_runtime::{block_on, spawn, yield_now, Runtime};
+
+/// A future that finishes once `fire` is called, remembering the waker it
+/// was last polled with.
+#[derive(Clone, Default)]
+struct Signal {
+    state: Rc<RefCell<(bool, Option<Waker>)>>,
+}
+
+impl Signal {
+    fn fire(&self) {
+        let mut state = self.state.borrow_mut();
+        state.0 = true;
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Signal {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.borrow_mut();
+        if state.0 {
+            return Poll::Ready(());
+        }
+        state.1 = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[test]
+fn spawned_tasks_run_in_spawn_order() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    block_on({
+        let log = log.clone();
+        async move {
+            for i in 0..5 {
+                let log = log.clone();
+                spawn(async move { log.borrow_mut().push(i) });
+            }
+            assert!(log.borrow().is_empty(), "spawning doesn't run the task");
+            yield_now().await;
+        }
+    });
+    assert_eq!(*log.borrow(), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn tasks_run_in_the_order_they_were_woken() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    block_on({
+        let log = log.clone();
+        async move {
+            let signals: Vec<Signal> = (0..3).map(|_| Signal::default()).collect();
+            for (i, signal) in signals.iter().enumerate() {
+                let (log, signal) = (log.clone(), signal.clone());
+                spawn(async move {
+                    signal.await;
+                    log.borrow_mut().push(i);
+                });
+            }
+            // Let every task reach its signal.
+            yield_now().await;
+            for i in [2, 0, 1] {
+                signals[i].fire();
+            }
+            yield_now().await;
+        }
+    });
+    assert_eq!(*log.borrow(), [2, 0, 1]);
+}
+
+#[test]
+fn waking_twice_polls_once() {
+    let polls = Rc::new(Cell::new(0));
+    let waker = Rc::new(RefCell::new(None::<Waker>));
+    block_on({
+        let (polls, waker) = (polls.clone(), waker.clone());
+        async move {
+            let (task_polls, task_waker) = (polls.clone(), waker.clone());
+            spawn(poll_fn(move |cx| {
+                task_polls.set(task_polls.get() + 1);
+                *task_waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::<()>::Pending
+            }));
+            yield_now().await;
+            assert_eq!(polls.get(), 1);
+
+            let task = waker.borrow().clone().unwrap();
+            task.wake_by_ref();
+            task.wake_by_ref();
+            task.wake();
+            yield_now().await;
+            yield_now().await;
+            assert_eq!(polls.get(), 2);
+        }
+    });
+}
+
+#[test]
+fn yielding_tasks_take_turns() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    block_on({
+        let log = log.clone();
+        async move {
+            let tasks: Vec<_> = ["a", "b"]
+                .into_iter()
+                .map(|name| {
+                    let log = log.clone();
+                    spawn(async move {
+                        for _ in 0..3 {
+                            log.borrow_mut().push(name);
+                            yield_now().await;
+                        }
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await;
+            }
+        }
+    });
+    assert_eq!(*log.borrow(), ["a", "b", "a", "b", "a", "b"]);
+}
+
+#[test]
+fn woken_from_another_thread() {
+    let done = block_on(async {
+        let slot = Arc::new(Mutex::new((false, None::<Waker>)));
+        let wakes = slot.clone();
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            let mut slot = wakes.lock().unwrap();
+            slot.0 = true;
+            if let Some(waker) = slot.1.take() {
+                waker.wake();
+            }
+        });
+        poll_fn(|cx| {
+            let mut slot = slot.lock().unwrap();
+            if slot.0 {
+                return Poll::Ready(());
+            }
+            slot.1 = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await;
+        thread.join().is_ok()
+    });
+    assert!(done);
+}
+
+#[test]
+fn join_handles() {
+    block_on(async {
+        let handle = spawn(async {
+            yield_now().await;
+            6 * 7
+        });
+        assert!(!handle.is_finished());
+        yield_now().await;
+        yield_now().await;
+        assert!(handle.is_finished());
+        assert_eq!(handle.await, 42);
+
+        // A task whose handle is dropped still runs.
+        let ran = Rc::new(Cell::new(false));
+        drop(spawn({
+            let ran = ran.clone();
+            async move { ran.set(true) }
+        }));
+        yield_now().await;
+        assert!(ran.get());
+
+        // Tasks can spawn tasks.
+        let nested = spawn(async { spawn(async { "inner" }).await });
+        assert_eq!(nested.await, "inner");
+    });
+}
+
+#[test]
+fn tasks_outlive_block_on() {
+    let runtime = Runtime::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let signal = Signal::default();
+    let handle = runtime.spawn({
+        let (log, signal) = (log.clone(), signal.clone());
+        async move {
+            log.borrow_mut().push("started");
+            signal.await;
+            log.borrow_mut().push("finished");
+            "result"
+        }
+    });
+    assert!(log.borrow().is_empty(), "tasks only run inside block_on");
+
+    runtime.block_on(yield_now());
+    assert_eq!(*log.borrow(), ["started"]);
+
+    signal.fire();
+    assert_eq!(runtime.block_on(handle), "result");
+    assert_eq!(*log.borrow(), ["started", "finished"]);
+}
+
+#[test]
+#[should_panic(expected = "must be called from a task running on a mini-runtime")]
+fn spawn_outside_a_runtime() {
+    spawn(async {});
+}
+
+#[test]
+#[should_panic(expected = "block_on called from inside a runtime")]
+fn nested_block_on() {
+    block_on(async { block_on(async {}) });
+}
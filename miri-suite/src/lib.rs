@@ -0,0 +1,95 @@
+// Tests for the workspace's `unsafe` code, written to run under Miri as
+// well as natively: one suite in `tests/` for each member with `unsafe`
+// blocks, and what they share, here.
+//
+// Miri interprets the program and checks each pointer use, so it finds
+// undefined behavior that a native run gets away with. But it's about a
+// thousand times slower, and it can't open files or call C. So the suites
+// do no I/O, and every loop count goes through `scale`, which picks a
+// smaller one under Miri. `cfg(miri)` is set by Miri itself; nothing has
+// to be enabled.
+//
+// Natively, a use-after-free or a double drop usually passes unnoticed.
+// `Tracked` values count their drops, so a suite can check that every
+// value was dropped exactly once either way.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// `native`, or `miri` when running under Miri.
+pub const fn scale(native: usize, miri: usize) -> usize {
+    if cfg!(miri) {
+        miri
+    } else {
+        native
+    }
+}
+
+/// Define a test that runs natively and under Miri, with a loop count
+/// chosen by `scale`:
+///
+/// ```
+/// miri_suite::both! {
+///     /// Pushing and popping.
+///     fn push_pop(n = 10_000, miri = 100) {
+///         let mut v = Vec::new();
+///         v.extend(0..n);
+///         assert_eq!(v.len(), n);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! both {
+    ($($(#[$meta:meta])* fn $name:ident($n:ident = $native:expr, miri = $miri:expr) $body:block)*) => {
+        $(
+            $(#[$meta])*
+            #[test]
+            fn $name() {
+                let $n: usize = $crate::scale($native, $miri);
+                $body
+            }
+        )*
+    };
+}
+
+/// Counts drops of the values made from it.
+#[derive(Clone, Default)]
+pub struct DropCounter(Arc<AtomicUsize>);
+
+impl DropCounter {
+    pub fn new() -> DropCounter {
+        DropCounter::default()
+    }
+
+    /// A value that counts itself when it's dropped.
+    pub fn track(&self, value: usize) -> Tracked {
+        Tracked {
+            value,
+            drops: self.0.clone(),
+        }
+    }
+
+    pub fn drops(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// A value whose drop is counted. It owns a heap allocation, so Miri
+/// notices if one is read after it's freed, or freed twice.
+#[derive(Debug)]
+pub struct Tracked {
+    pub value: usize,
+    drops: Arc<AtomicUsize>,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl PartialEq for Tracked {
+    fn eq(&self, other: &Tracked) -> bool {
+        self.value == other.value
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use alloc_lab::{arena, measure, Lab};
+use miri_suite::both;
+
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+both! {
+    /// The counting allocator under ordinary use: allocations, growth by
+    /// `realloc`, and frees all pass through its `unsafe` methods.
+    fn counts_allocations(n = 10_000, miri = 100) {
+        let (sum, stats) = measure(|| {
+            let mut v = Vec::new();
+            for i in 0..n {
+                v.push(i);
+            }
+            let map: BTreeMap<usize, String> = v.iter().map(|&i| (i, i.to_string())).collect();
+            map.values().map(String::len).sum::<usize>()
+        });
+        assert!(sum >= n);
+        assert!(stats.allocations >= n);
+        assert!(stats.reallocations > 0);
+        assert_eq!(stats.live_bytes, 0);
+    }
+
+    /// Inside a scope, allocations come from the bump arena, and can
+    /// outlive it; the chunk is freed once everything in it is.
+    fn arena_allocations(n = 10_000, miri = 100) {
+        let (kept, stats) = measure(|| {
+            arena::scope(|| {
+                let strings: Vec<String> = (0..n).map(|i| format!("item {}", i)).collect();
+                let mut grown = String::new();
+                for s in &strings {
+                    grown.push_str(s);
+                }
+                assert!(arena::active());
+                (strings, grown)
+            })
+        });
+        assert!(!arena::active());
+        assert!(stats.arena_allocations >= n);
+        assert_eq!(kept.0.len(), n);
+        assert!(kept.1.starts_with("item 0item 1"));
+        drop(kept);
+    }
+
+    /// Zeroed allocations really are zero, from the arena or not.
+    fn zeroed(n = 1_000, miri = 20) {
+        for _ in 0..n / 10 {
+            let outside = vec![0u64; n];
+            let inside = arena::scope(|| vec![0u64; n]);
+            assert!(outside.iter().chain(&inside).all(|&x| x == 0));
+        }
+    }
+}
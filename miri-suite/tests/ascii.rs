@@ -0,0 +1,28 @@
+use ascii::Ascii;
+use miri_suite::both;
+
+both! {
+    /// `as_str` and the conversion to `String` skip UTF-8 validation,
+    /// trusting the invariant; Miri checks the bytes really are valid.
+    fn unchecked_conversions(n = 10_000, miri = 200) {
+        let bytes: Vec<u8> = (0..n).map(|i| (i % 128) as u8).collect();
+        let ascii = Ascii::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(ascii.as_str().as_bytes(), &bytes[..]);
+        let string = String::from(ascii);
+        assert_eq!(string.len(), n);
+    }
+
+    /// The unsafe constructor, used correctly, and the safe mutations
+    /// that keep the invariant.
+    fn unchecked_constructor(n = 10_000, miri = 200) {
+        let bytes: Vec<u8> = (0..n).map(|i| b'a' + (i % 26) as u8).collect();
+        // SAFETY: every byte is a lowercase letter.
+        let mut ascii = unsafe { Ascii::from_bytes_unchecked(bytes) };
+        ascii.make_ascii_uppercase();
+        assert!(ascii.push(b'!'));
+        assert!(!ascii.push(0xff));
+        assert!(ascii.as_str().starts_with("ABC"));
+        assert!(ascii.as_str().ends_with('!'));
+        assert_eq!(ascii.into_bytes().len(), n + 1);
+    }
+}
@@ -0,0 +1,67 @@
+use gap_buffer::GapBuffer;
+use miri_suite::{both, DropCounter};
+
+both! {
+    /// Moving the gap back and forth copies elements across it; a wrong
+    /// count or offset reads uninitialized memory.
+    fn moving_the_gap(n = 2_000, miri = 50) {
+        let mut buffer = GapBuffer::new();
+        buffer.extend(0..n);
+        let mut model: Vec<usize> = (0..n).collect();
+        for i in 0..n {
+            let pos = (i * 7919) % (buffer.len() + 1);
+            buffer.set_position(pos);
+            buffer.insert(i);
+            model.insert(pos, i);
+        }
+        assert!(buffer.iter().eq(model.iter()));
+        let (before, after) = buffer.as_slices();
+        assert_eq!(before.len() + after.len(), model.len());
+    }
+
+    /// Every value is dropped once: those removed, and those still in the
+    /// buffer when it goes.
+    fn drops_each_value_once(n = 1_000, miri = 40) {
+        let counter = DropCounter::new();
+        let mut buffer = GapBuffer::new();
+        for i in 0..n {
+            buffer.insert(counter.track(i));
+        }
+        buffer.set_position(n / 2);
+        for _ in 0..n / 4 {
+            drop(buffer.remove());
+            drop(buffer.remove_before());
+        }
+        assert_eq!(counter.drops(), 2 * (n / 4));
+        drop(buffer);
+        assert_eq!(counter.drops(), n);
+    }
+
+    /// A clone owns copies of its own, gap and all.
+    fn clone_and_compare(n = 1_000, miri = 40) {
+        let mut buffer: GapBuffer<String> = (0..n).map(|i| i.to_string()).collect();
+        buffer.set_position(n / 2);
+        let clone = buffer.clone();
+        buffer.insert("extra".to_string());
+        assert_ne!(buffer, clone);
+        drop(buffer.remove_before());
+        assert_eq!(buffer, clone);
+        drop(buffer);
+        assert_eq!(clone.len(), n);
+        assert_eq!(clone.get(n - 1).map(String::as_str), Some((n - 1).to_string().as_str()));
+    }
+
+    /// An iterator dropped partway drops what it didn't yield.
+    fn into_iter_partly_consumed(n = 1_000, miri = 40) {
+        let counter = DropCounter::new();
+        let mut buffer: GapBuffer<_> = (0..n).map(|i| counter.track(i)).collect();
+        buffer.set_position(n / 3);
+        let mut iter = buffer.into_iter();
+        let first = iter.next().unwrap();
+        assert_eq!(first.value, 0);
+        drop(iter);
+        assert_eq!(counter.drops(), n - 1);
+        drop(first);
+        assert_eq!(counter.drops(), n);
+    }
+}
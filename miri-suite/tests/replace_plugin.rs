@@ -0,0 +1,62 @@
+use miri_suite::both;
+use replace_plugin::{Buffer, ABI_VERSION};
+
+// Miri can't load a shared library, but the table a plugin exports can be
+// called from the binary that exports it, which is everything but the
+// loading: the `Buffer` handed out by one side and freed by the other.
+fn shout(text: &str) -> String {
+    if text == "panic" {
+        panic!("asked to");
+    }
+    text.to_uppercase()
+}
+
+replace_plugin::export_plugin!("shout", shout);
+
+/// Call the exported transform as `Plugin::transform` does, copying the
+/// result out and handing the buffer back to be freed.
+fn transform(text: &str) -> Option<String> {
+    let table = quickreplace_plugin();
+    // SAFETY: `text` is `text.len()` bytes of UTF-8, and the buffer goes
+    // back to the `free` of the table it came from, once.
+    unsafe {
+        let buffer = (table.transform)(text.as_ptr(), text.len());
+        if buffer.ptr.is_null() {
+            return None;
+        }
+        let bytes = std::slice::from_raw_parts(buffer.ptr, buffer.len).to_vec();
+        (table.free)(buffer);
+        Some(String::from_utf8(bytes).unwrap())
+    }
+}
+
+both! {
+    /// Each result is built in the plugin, read by the host, and freed by
+    /// the plugin, whatever its length, empty ones included.
+    fn buffers_cross_and_come_back(n = 1_000, miri = 20) {
+        assert_eq!(quickreplace_plugin_abi(), ABI_VERSION);
+        for i in 0..n {
+            let text = "ab".repeat(i);
+            assert_eq!(transform(&text).unwrap(), "AB".repeat(i));
+        }
+    }
+
+    /// A panic is caught on the plugin's side of the boundary, and nothing
+    /// is left allocated.
+    fn panics_are_failures(n = 100, miri = 3) {
+        for _ in 0..n {
+            assert_eq!(transform("panic"), None);
+        }
+    }
+
+    /// `from_vec` and `into_vec` give back the same allocation.
+    fn buffers_round_trip(n = 1_000, miri = 20) {
+        for i in 0..n {
+            let mut bytes = Vec::with_capacity(i * 2);
+            bytes.extend((0..i).map(|b| b as u8));
+            // SAFETY: straight back from `from_vec`, once.
+            let back = unsafe { Buffer::from_vec(bytes.clone()).into_vec() };
+            assert_eq!(back, bytes);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::thread;
+
+use mini_channel::spsc;
+use mini_channel::{TryRecvError, TrySendError};
+use miri_suite::{both, DropCounter};
+
+both! {
+    /// Values cross between threads through the ring, wrapping around it
+    /// many times; Miri's data race detector checks the orderings.
+    fn across_threads(n = 100_000, miri = 200) {
+        let counter = DropCounter::new();
+        let (sender, receiver) = spsc::channel(8);
+        let producer = {
+            let counter = counter.clone();
+            thread::spawn(move || {
+                for i in 0..n {
+                    sender.send(counter.track(i)).unwrap();
+                }
+            })
+        };
+        for i in 0..n {
+            assert_eq!(receiver.recv().unwrap().value, i);
+        }
+        producer.join().unwrap();
+        assert!(receiver.recv().is_err());
+        assert_eq!(counter.drops(), n);
+    }
+
+    /// Values still in the ring when both ends are gone are dropped with
+    /// it, once each.
+    fn drops_values_in_flight(n = 1_000, miri = 20) {
+        let counter = DropCounter::new();
+        for round in 0..n {
+            let (sender, receiver) = spsc::channel(4);
+            for i in 0..4 {
+                sender.try_send(counter.track(i)).unwrap();
+            }
+            match sender.try_send(counter.track(4)) {
+                Err(TrySendError::Full(value)) => drop(value),
+                other => panic!("expected Full, got {:?}", other),
+            }
+            // Take some out, so the ring's live slots wrap.
+            for _ in 0..round % 4 {
+                drop(receiver.try_recv().unwrap());
+            }
+            drop(sender);
+            drop(receiver);
+            assert_eq!(counter.drops(), 5 * (round + 1));
+        }
+    }
+
+    /// A receiver sees `Empty`, then what was sent, then `Disconnected`.
+    fn try_recv_states(n = 1_000, miri = 50) {
+        let (sender, receiver) = spsc::channel(2);
+        for i in 0..n {
+            assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+            sender.try_send(i).unwrap();
+            assert_eq!(receiver.try_recv(), Ok(i));
+        }
+        drop(sender);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    }
+}
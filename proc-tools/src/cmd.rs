@@ -0,0 +1,142 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::output::Output;
+use crate::pipeline::Pipeline;
+use crate::running::Running;
+
+/// A command to run, built up like `std::process::Command` but by value,
+/// so that it can be chained into a pipeline.
+#[derive(Clone, Debug)]
+pub struct Cmd {
+    program: OsString,
+    args: Vec<OsString>,
+    envs: Vec<(OsString, Option<OsString>)>,
+    dir: Option<PathBuf>,
+}
+
+/// Start describing a command that runs `program`, found on `PATH` the
+/// same way the shell would find it.
+pub fn cmd(program: impl AsRef<OsStr>) -> Cmd {
+    Cmd {
+        program: program.as_ref().to_owned(),
+        args: Vec::new(),
+        envs: Vec::new(),
+        dir: None,
+    }
+}
+
+impl Cmd {
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Cmd {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    pub fn args<I>(mut self, args: I) -> Cmd
+    where
+        I: IntoIterator,
+        I::Item: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_owned()));
+        self
+    }
+
+    /// Set an environment variable for this command only.
+    pub fn env(mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> Cmd {
+        let value = Some(value.as_ref().to_owned());
+        self.envs.push((key.as_ref().to_owned(), value));
+        self
+    }
+
+    /// Run this command without an inherited environment variable.
+    pub fn env_remove(mut self, key: impl AsRef<OsStr>) -> Cmd {
+        self.envs.push((key.as_ref().to_owned(), None));
+        self
+    }
+
+    /// Run this command in `dir` rather than the current directory.
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Cmd {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// Send this command's standard output to `next`'s standard input.
+    pub fn pipe(self, next: Cmd) -> Pipeline {
+        Pipeline::from(self).pipe(next)
+    }
+
+    /// The program's name, for error messages.
+    pub(crate) fn program(&self) -> String {
+        self.program.to_string_lossy().into_owned()
+    }
+
+    pub(crate) fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        for (key, value) in &self.envs {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+        command
+    }
+
+    // A lone command is a pipeline of one; these are for convenience.
+
+    /// See [`Pipeline::input`].
+    pub fn input(self, input: impl Into<Vec<u8>>) -> Pipeline {
+        Pipeline::from(self).input(input)
+    }
+
+    /// See [`Pipeline::timeout`].
+    pub fn timeout(self, timeout: Duration) -> Pipeline {
+        Pipeline::from(self).timeout(timeout)
+    }
+
+    /// See [`Pipeline::unchecked`].
+    pub fn unchecked(self) -> Pipeline {
+        Pipeline::from(self).unchecked()
+    }
+
+    /// See [`Pipeline::capture`].
+    pub fn capture(self) -> Result<Output> {
+        Pipeline::from(self).capture()
+    }
+
+    /// See [`Pipeline::stream`].
+    pub fn stream(self, on_line: impl FnMut(&str)) -> Result<Output> {
+        Pipeline::from(self).stream(on_line)
+    }
+
+    /// See [`Pipeline::spawn`].
+    pub fn spawn(self) -> Result<Running> {
+        Pipeline::from(self).spawn()
+    }
+}
+
+/// Shows the command roughly as you'd type it; arguments with spaces or
+/// quotes in them are quoted Rust-style, which is close enough for logs.
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.program.to_string_lossy())?;
+        for arg in &self.args {
+            let arg = arg.to_string_lossy();
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            {
+                write!(f, " {:?}", arg)?;
+            } else {
+                write!(f, " {}", arg)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::fmt;
+use std::io;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use crate::output::Output;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The program couldn't be started: usually, it isn't installed.
+    Spawn {
+        program: String,
+        source: io::Error,
+    },
+    Io(io::Error),
+    /// The pipeline was still running when its timeout expired, and was
+    /// killed. `output` holds whatever it wrote before then.
+    TimedOut {
+        timeout: Duration,
+        output: Output,
+    },
+    /// A command exited unsuccessfully. In a pipeline, this is the last
+    /// command to fail, as with the shell's `pipefail` option.
+    Failed {
+        program: String,
+        status: ExitStatus,
+        output: Output,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Spawn { program, source } => write!(f, "can't run {}: {}", program, source),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::TimedOut { timeout, .. } => write!(f, "timed out after {:?}", timeout),
+            Error::Failed {
+                program,
+                status,
+                output,
+            } => {
+                write!(f, "{} failed ({})", program, status)?;
+                // The first line of stderr usually says why.
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                match stderr.lines().find(|line| !line.trim().is_empty()) {
+                    Some(line) => write!(f, ": {}", line.trim()),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Spawn { source, .. } => Some(source),
+            Error::Io(e) => Some(e),
+            Error::TimedOut { .. } | Error::Failed { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,18 @@
+// Running other programs: single commands and shell-style pipelines, with
+// their output captured or streamed line by line, and timeouts that kill
+// whatever is still running.
+//
+//     let output = cmd("sort").pipe(cmd("uniq").arg("-c")).input("b\na\nb\n").capture()?;
+//     assert_eq!(output.text(), "      1 a\n      2 b");
+
+mod cmd;
+mod error;
+mod output;
+mod pipeline;
+mod running;
+
+pub use cmd::{cmd, Cmd};
+pub use error::{Error, Result};
+pub use output::Output;
+pub use pipeline::Pipeline;
+pub use running::Running;
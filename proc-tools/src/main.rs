@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+use std::{env, process};
+
+use proc_tools::{cmd, Error, Pipeline};
+
+#[derive(Debug)]
+struct Arguments {
+    timeout: Option<Duration>,
+    pipeline: Pipeline,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut timeout = None;
+        let mut args = env::args().skip(1).peekable();
+        while let Some(arg) = args.next_if(|arg| arg.starts_with('-')) {
+            match arg.as_str() {
+                "--timeout" => {
+                    let ms = args
+                        .next()
+                        .unwrap_or_else(|| usage_error("--timeout needs a duration"));
+                    let ms = ms.parse().unwrap_or_else(|_| {
+                        usage_error(&format!("bad duration {:?}: expected milliseconds", ms))
+                    });
+                    timeout = Some(Duration::from_millis(ms));
+                }
+                "--" => break,
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown option {}", arg)),
+            }
+        }
+
+        let words: Vec<String> = args.collect();
+        let mut stages = words.split(|word| word == "|").map(|stage| match stage {
+            [program, args @ ..] => cmd(program).args(args),
+            [] => usage_error("empty command in pipeline"),
+        });
+        let first = stages
+            .next()
+            .unwrap_or_else(|| usage_error("no command given"));
+        let mut pipeline = stages.fold(Pipeline::from(first), Pipeline::pipe);
+        if let Some(timeout) = timeout {
+            pipeline = pipeline.timeout(timeout);
+        }
+        Arguments { timeout, pipeline }
+    }
+}
+
+fn print_usage() {
+    eprintln!("proc-tools - run a pipeline, numbering its output lines as they arrive");
+    eprintln!("Usage: proc-tools [--timeout MS] COMMAND [ARGS...] ['|' COMMAND [ARGS...]]...");
+    eprintln!("Quote the '|' so that your shell passes it along rather than acting on it.");
+    eprintln!("Example: proc-tools --timeout 2000 cat Cargo.toml '|' sort '|' uniq -c");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn main() {
+    let args = Arguments::parse();
+    eprintln!("running: {}", args.pipeline);
+    let start = Instant::now();
+    let mut lines = 0;
+    let result = args.pipeline.stream(|line| {
+        lines += 1;
+        println!(
+            "{:>5.1}s {:>4}  {}",
+            start.elapsed().as_secs_f64(),
+            lines,
+            line
+        );
+    });
+    match result {
+        Ok(output) => {
+            let statuses: Vec<String> = output.statuses.iter().map(|s| s.to_string()).collect();
+            eprintln!("done in {:.2?}: {}", start.elapsed(), statuses.join(", "));
+        }
+        Err(e) => {
+            if let Error::TimedOut { .. } = e {
+                eprintln!("(the limit was {:?})", args.timeout.unwrap());
+            }
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::process::ExitStatus;
+
+/// What a finished pipeline produced.
+#[derive(Clone, Debug)]
+pub struct Output {
+    /// Each command's exit status, in pipeline order.
+    pub statuses: Vec<ExitStatus>,
+    /// The last command's standard output. Empty if it was streamed.
+    pub stdout: Vec<u8>,
+    /// Every command's standard error, one after another.
+    pub stderr: Vec<u8>,
+}
+
+impl Output {
+    /// The last command's exit status, which is the pipeline's status as
+    /// far as the shell is concerned.
+    pub fn status(&self) -> ExitStatus {
+        *self
+            .statuses
+            .last()
+            .expect("a pipeline has at least one command")
+    }
+
+    pub fn success(&self) -> bool {
+        self.statuses.iter().all(ExitStatus::success)
+    }
+
+    /// Standard output as text, without trailing newlines, like the
+    /// shell's `$(...)`. Invalid UTF-8 is replaced rather than rejected.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.stdout)
+            .trim_end_matches(['\n', '\r'])
+            .to_string()
+    }
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::cmd::Cmd;
+use crate::error::Result;
+use crate::output::Output;
+use crate::running::{self, Running};
+
+/// One or more commands, each one's standard output connected to the
+/// next one's standard input, as with `|` in the shell.
+#[derive(Clone, Debug)]
+pub struct Pipeline {
+    pub(crate) stages: Vec<Cmd>,
+    pub(crate) input: Option<Vec<u8>>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) checked: bool,
+}
+
+impl From<Cmd> for Pipeline {
+    fn from(cmd: Cmd) -> Pipeline {
+        Pipeline {
+            stages: vec![cmd],
+            input: None,
+            timeout: None,
+            checked: true,
+        }
+    }
+}
+
+impl Pipeline {
+    pub fn pipe(mut self, next: Cmd) -> Pipeline {
+        self.stages.push(next);
+        self
+    }
+
+    /// Feed `input` to the first command's standard input. Without this,
+    /// the first command reads from an empty stdin, never the terminal.
+    pub fn input(mut self, input: impl Into<Vec<u8>>) -> Pipeline {
+        self.input = Some(input.into());
+        self
+    }
+
+    /// Kill every command still running once `timeout` has passed since
+    /// the pipeline started, and fail with `Error::TimedOut`.
+    pub fn timeout(mut self, timeout: Duration) -> Pipeline {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Don't treat an unsuccessful exit status as an error; check
+    /// `Output::statuses` instead.
+    pub fn unchecked(mut self) -> Pipeline {
+        self.checked = false;
+        self
+    }
+
+    /// Run the pipeline to completion and collect its output.
+    pub fn capture(self) -> Result<Output> {
+        self.spawn()?.wait()
+    }
+
+    /// Run the pipeline to completion, passing each line of the last
+    /// command's output to `on_line` as soon as it's written. Lines don't
+    /// include their newline.
+    pub fn stream(self, on_line: impl FnMut(&str)) -> Result<Output> {
+        self.spawn()?.stream(on_line)
+    }
+
+    /// Start the pipeline, and return without waiting for it to finish.
+    pub fn spawn(self) -> Result<Running> {
+        running::spawn(self)
+    }
+}
+
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, stage) in self.stages.iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(f, "{}", stage)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+// A pipeline in flight.
+//
+// The commands' output is read on helper threads: one for the last
+// command's stdout, and one for each command's stderr, since a command
+// whose stderr pipe fills up stops until someone empties it. The stdout
+// thread forwards what it reads over a channel, so the caller can wait for
+// it with a timeout. The stderr threads hold clones of the same channel's
+// sender without sending anything, so the channel disconnects only once
+// every output pipe is closed: that is, once the commands, and anything
+// they started that inherited their pipes, have finished writing.
+
+use std::io::{self, Read, Write};
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::output::Output;
+use crate::pipeline::Pipeline;
+
+type Chunk = io::Result<Vec<u8>>;
+
+/// A running pipeline, from `Pipeline::spawn`. Dropping it kills any
+/// commands that are still running.
+pub struct Running {
+    stages: Vec<Stage>,
+    stdout: Receiver<Chunk>,
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    checked: bool,
+}
+
+struct Stage {
+    program: String,
+    child: Child,
+    status: Option<ExitStatus>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+}
+
+pub(crate) fn spawn(pipeline: Pipeline) -> Result<Running> {
+    let (sender, receiver) = mpsc::channel();
+    let mut running = Running {
+        stages: Vec::new(),
+        stdout: receiver,
+        timeout: pipeline.timeout,
+        deadline: pipeline.timeout.map(|timeout| Instant::now() + timeout),
+        checked: pipeline.checked,
+    };
+
+    let mut previous = None;
+    for cmd in &pipeline.stages {
+        let mut command = cmd.to_command();
+        command.stdin(match previous.take() {
+            Some(stdout) => Stdio::from(stdout),
+            None if pipeline.input.is_some() => Stdio::piped(),
+            None => Stdio::null(),
+        });
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        // If this fails, dropping `running` kills the commands already
+        // started.
+        let mut child = command.spawn().map_err(|source| Error::Spawn {
+            program: cmd.program(),
+            source,
+        })?;
+        previous = child.stdout.take();
+
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+        let pipe = child.stderr.take().unwrap();
+        let buffer = stderr.clone();
+        let sender = sender.clone();
+        thread::spawn(move || {
+            drain_stderr(pipe, &buffer);
+            drop(sender);
+        });
+
+        running.stages.push(Stage {
+            program: cmd.program(),
+            child,
+            status: None,
+            stderr,
+        });
+    }
+
+    if let Some(input) = pipeline.input {
+        let mut stdin = running.stages[0].child.stdin.take().unwrap();
+        // A command may exit without reading all its input, as `head`
+        // does, so errors writing it aren't worth reporting.
+        thread::spawn(move || stdin.write_all(&input));
+    }
+    let stdout = previous.unwrap();
+    thread::spawn(move || read_stdout(stdout, sender));
+    Ok(running)
+}
+
+fn drain_stderr(mut pipe: impl Read, buffer: &Mutex<Vec<u8>>) {
+    let mut chunk = [0; 4096];
+    while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+        buffer.lock().unwrap().extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn read_stdout(mut pipe: impl Read, sender: Sender<Chunk>) {
+    let mut chunk = [0; 8192];
+    loop {
+        let message = match pipe.read(&mut chunk) {
+            Ok(0) => return,
+            Ok(n) => Ok(chunk[..n].to_vec()),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => Err(e),
+        };
+        let failed = message.is_err();
+        if sender.send(message).is_err() || failed {
+            return;
+        }
+    }
+}
+
+impl Running {
+    /// The process IDs of the pipeline's commands, in order.
+    pub fn pids(&self) -> Vec<u32> {
+        self.stages.iter().map(|stage| stage.child.id()).collect()
+    }
+
+    /// Kill every command that's still running. Waiting afterwards
+    /// reports them as failed, killed by a signal.
+    pub fn kill(&mut self) -> io::Result<()> {
+        for stage in &mut self.stages {
+            if stage.status.is_none() {
+                stage.child.kill()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait for the pipeline to finish, collecting its output.
+    pub fn wait(mut self) -> Result<Output> {
+        self.finish(true, &mut |_| {})
+    }
+
+    /// Wait for the pipeline to finish, passing each line of its output
+    /// to `on_line` as it arrives. See `Pipeline::stream`.
+    pub fn stream(mut self, mut on_line: impl FnMut(&str)) -> Result<Output> {
+        let mut pending = Vec::new();
+        let output = self.finish(false, &mut |chunk| {
+            pending.extend_from_slice(chunk);
+            while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                on_line(&text(&line[..end]));
+            }
+        })?;
+        // A last line with no newline after it.
+        if !pending.is_empty() {
+            on_line(&text(&pending));
+        }
+        Ok(output)
+    }
+
+    fn finish(&mut self, collect: bool, on_chunk: &mut dyn FnMut(&[u8])) -> Result<Output> {
+        let mut stdout = Vec::new();
+        loop {
+            let next = match self.deadline {
+                Some(deadline) => self
+                    .stdout
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self
+                    .stdout
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match next {
+                Ok(chunk) => {
+                    let chunk = chunk?;
+                    on_chunk(&chunk);
+                    if collect {
+                        stdout.extend_from_slice(&chunk);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => return Err(self.time_out(stdout)),
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        // The output pipes are closed, but a command can close its output
+        // and carry on running.
+        for i in 0..self.stages.len() {
+            while self.stages[i].status.is_none() {
+                let stage = &mut self.stages[i];
+                match self.deadline {
+                    None => stage.status = Some(stage.child.wait()?),
+                    Some(deadline) => {
+                        stage.status = stage.child.try_wait()?;
+                        let left = deadline.saturating_duration_since(Instant::now());
+                        if stage.status.is_some() {
+                            break;
+                        } else if left.is_zero() {
+                            return Err(self.time_out(stdout));
+                        }
+                        thread::sleep(left.min(Duration::from_millis(5)));
+                    }
+                }
+            }
+        }
+
+        let output = self.output(stdout);
+        let last = self.stages.len() - 1;
+        let failed = self.stages.iter().enumerate().rev().find(|(i, stage)| {
+            let status = stage.status.unwrap();
+            // An earlier command killed for writing to a pipe that the
+            // next command stopped reading, like `yes` in `yes | head`,
+            // hasn't failed: that's how it's told to stop.
+            let stopped = *i < last && broken_pipe(status);
+            !(status.success() || stopped)
+        });
+        match failed {
+            Some((_, stage)) if self.checked => Err(Error::Failed {
+                program: stage.program.clone(),
+                status: stage.status.unwrap(),
+                output,
+            }),
+            _ => Ok(output),
+        }
+    }
+
+    fn time_out(&mut self, stdout: Vec<u8>) -> Error {
+        self.reap();
+        Error::TimedOut {
+            timeout: self.timeout.unwrap(),
+            output: self.output(stdout),
+        }
+    }
+
+    /// Kill whatever's still running and wait for it to exit.
+    fn reap(&mut self) {
+        for stage in &mut self.stages {
+            if stage.status.is_none() {
+                let _ = stage.child.kill();
+                stage.status = stage.child.wait().ok();
+            }
+        }
+    }
+
+    fn output(&self, stdout: Vec<u8>) -> Output {
+        Output {
+            statuses: self
+                .stages
+                .iter()
+                .filter_map(|stage| stage.status)
+                .collect(),
+            stdout,
+            stderr: self
+                .stages
+                .iter()
+                .flat_map(|stage| stage.stderr.lock().unwrap().clone())
+                .collect(),
+        }
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        self.reap();
+    }
+}
+
+fn text(line: &[u8]) -> std::borrow::Cow<'_, str> {
+    String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+#[cfg(unix)]
+fn broken_pipe(status: ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal() == Some(libc::SIGPIPE)
+}
+
+#[cfg(not(unix))]
+fn broken_pipe(_: ExitStatus) -> bool {
+    false
+}
@@ -0,0 +1,159 @@
+// Timeouts, streaming, and killing: the parts that are about time.
+#![cfg(unix)]
+
+use std::time::{Duration, Instant};
+
+use proc_tools::{cmd, Error};
+
+fn is_running(pid: u32) -> bool {
+    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+}
+
+#[test]
+fn timeout_kills() {
+    let start = Instant::now();
+    let e = cmd("sleep")
+        .arg("30")
+        .timeout(Duration::from_millis(200))
+        .capture()
+        .unwrap_err();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(10), "{:?}", elapsed);
+    assert_eq!(e.to_string(), "timed out after 200ms");
+    match e {
+        Error::TimedOut { timeout, output } => {
+            assert_eq!(timeout, Duration::from_millis(200));
+            assert!(!output.success());
+        }
+        e => panic!("{:?}", e),
+    }
+}
+
+#[test]
+fn timeout_keeps_partial_output() {
+    // `sh` runs `sleep` as a child of its own, which inherits the pipes and
+    // outlives `sh` being killed: the deadline has to hold regardless.
+    let start = Instant::now();
+    let script = "echo partial; echo complaint >&2; sleep 30";
+    let e = cmd("sh")
+        .args(["-c", script])
+        .pipe(cmd("cat"))
+        .timeout(Duration::from_millis(300))
+        .capture()
+        .unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(10));
+    match e {
+        Error::TimedOut { output, .. } => {
+            assert_eq!(output.stdout, b"partial\n");
+            assert_eq!(output.stderr, b"complaint\n");
+            assert_eq!(output.statuses.len(), 2);
+        }
+        e => panic!("{:?}", e),
+    }
+}
+
+#[test]
+fn finishing_in_time() {
+    let output = cmd("sh")
+        .args(["-c", "sleep 0.1; echo done"])
+        .timeout(Duration::from_secs(10))
+        .capture()
+        .unwrap();
+    assert_eq!(output.text(), "done");
+}
+
+#[test]
+fn timeout_after_closing_output() {
+    // The output is finished, but the command isn't.
+    let e = cmd("sh")
+        .args(["-c", "exec >&- 2>&-; sleep 30"])
+        .timeout(Duration::from_millis(200))
+        .capture()
+        .unwrap_err();
+    assert!(matches!(e, Error::TimedOut { .. }), "{:?}", e);
+}
+
+#[test]
+fn streaming_is_incremental() {
+    let start = Instant::now();
+    let mut lines = Vec::new();
+    let output = cmd("sh")
+        .args(["-c", "echo one; sleep 0.5; echo two"])
+        .stream(|line| lines.push((line.to_string(), start.elapsed())))
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!((lines[0].0.as_str(), lines[1].0.as_str()), ("one", "two"));
+    // The first line arrived before the command got around to the second.
+    assert!(
+        lines[1].1 - lines[0].1 >= Duration::from_millis(300),
+        "{:?}",
+        lines
+    );
+    // Streamed output isn't kept as well.
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn streaming_line_endings() {
+    let mut lines = Vec::new();
+    cmd("printf")
+        .arg("dos\\r\\nunix\\n\\nlast")
+        .stream(|line| lines.push(line.to_string()))
+        .unwrap();
+    assert_eq!(lines, ["dos", "unix", "", "last"]);
+}
+
+#[test]
+fn streaming_a_pipeline() {
+    let mut lines = Vec::new();
+    let output = cmd("seq")
+        .arg("100000")
+        .pipe(cmd("grep").arg("7$"))
+        .pipe(cmd("tail").args(["-n", "3"]))
+        .stream(|line| lines.push(line.parse::<u32>().unwrap()))
+        .unwrap();
+    assert_eq!(lines, [99977, 99987, 99997]);
+    assert_eq!(output.statuses.len(), 3);
+}
+
+#[test]
+fn streaming_timeout() {
+    let mut lines = Vec::new();
+    let e = cmd("sh")
+        .args(["-c", "echo before; sleep 30; echo after"])
+        .timeout(Duration::from_millis(300))
+        .stream(|line| lines.push(line.to_string()))
+        .unwrap_err();
+    assert!(matches!(e, Error::TimedOut { .. }), "{:?}", e);
+    assert_eq!(lines, ["before"]);
+}
+
+#[test]
+fn dropping_kills() {
+    let running = cmd("sleep")
+        .arg("30")
+        .pipe(cmd("sleep").arg("30"))
+        .spawn()
+        .unwrap();
+    let pids = running.pids();
+    assert_eq!(pids.len(), 2);
+    assert!(pids.iter().all(|&pid| is_running(pid)));
+    drop(running);
+    // Killed, and waited for, so not even a zombie remains.
+    assert!(pids.iter().all(|&pid| !is_running(pid)));
+}
+
+#[test]
+fn killing() {
+    let mut running = cmd("sleep").arg("30").spawn().unwrap();
+    running.kill().unwrap();
+    match running.wait() {
+        Err(Error::Failed { status, .. }) => assert_eq!(status.code(), None),
+        other => panic!("{:?}", other),
+    }
+
+    let mut running = cmd("sleep").arg("30").unchecked().spawn().unwrap();
+    running.kill().unwrap();
+    assert!(!running.wait().unwrap().success());
+}
@@ -0,0 +1,184 @@
+// These tests run commands that any Unix has: sh, echo, cat, sort, and
+// so on.
+#![cfg(unix)]
+
+use std::time::{Duration, Instant};
+
+use proc_tools::{cmd, Error};
+
+#[test]
+fn single_command() {
+    let output = cmd("echo").args(["hello", "world"]).capture().unwrap();
+    assert_eq!(output.stdout, b"hello world\n");
+    assert_eq!(output.text(), "hello world");
+    assert!(output.stderr.is_empty());
+    assert_eq!(output.statuses.len(), 1);
+    assert!(output.success());
+}
+
+#[test]
+fn pipes() {
+    let output = cmd("sort")
+        .pipe(cmd("uniq").arg("-c"))
+        .pipe(cmd("sort").arg("-rn"))
+        .input("b\na\nc\nb\na\nb\n")
+        .capture()
+        .unwrap();
+    let text = output.text();
+    let counts: Vec<Vec<&str>> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(counts, [["3", "b"], ["2", "a"], ["1", "c"]]);
+    assert_eq!(output.statuses.len(), 3);
+}
+
+#[test]
+fn stdin_is_empty_by_default() {
+    // If it inherited the terminal, this would wait for typing.
+    assert_eq!(cmd("cat").capture().unwrap().stdout, b"");
+}
+
+#[test]
+fn large_input_and_output() {
+    // Far more than a pipe holds, through three commands: a deadlock in
+    // the plumbing would hang here.
+    let input: Vec<u8> = (0..2_000_000).map(|i| b'a' + (i % 26) as u8).collect();
+    let output = cmd("cat")
+        .pipe(cmd("cat"))
+        .pipe(cmd("cat"))
+        .input(input.clone())
+        .timeout(Duration::from_secs(20))
+        .capture()
+        .unwrap();
+    assert_eq!(output.stdout, input);
+}
+
+#[test]
+fn stderr_is_captured_without_blocking() {
+    let script = "head -c 300000 /dev/zero | tr '\\0' x >&2; echo done";
+    let output = cmd("sh")
+        .args(["-c", script])
+        .pipe(cmd("sh").args(["-c", "cat; echo second >&2"]))
+        .timeout(Duration::from_secs(20))
+        .capture()
+        .unwrap();
+    assert_eq!(output.text(), "done");
+    assert_eq!(output.stderr.len(), 300000 + "second\n".len());
+    // Each command's stderr is kept whole, in pipeline order.
+    assert!(output.stderr.ends_with(b"xxxsecond\n"));
+}
+
+#[test]
+fn environment_and_directory() {
+    let output = cmd("sh")
+        .args(["-c", "echo \"$GREETING ${HOME:-unset}\"; pwd"])
+        .env("GREETING", "hi")
+        .env_remove("HOME")
+        .dir("/")
+        .capture()
+        .unwrap();
+    assert_eq!(output.text(), "hi unset\n/");
+}
+
+#[test]
+fn failure() {
+    let e = cmd("sh")
+        .args(["-c", "echo some output; echo it broke >&2; exit 3"])
+        .capture()
+        .unwrap_err();
+    assert_eq!(e.to_string(), "sh failed (exit status: 3): it broke");
+    match e {
+        Error::Failed {
+            program,
+            status,
+            output,
+        } => {
+            assert_eq!(program, "sh");
+            assert_eq!(status.code(), Some(3));
+            assert_eq!(output.text(), "some output");
+        }
+        e => panic!("{:?}", e),
+    }
+}
+
+#[test]
+fn unchecked() {
+    let output = cmd("false")
+        .pipe(cmd("echo").arg("ok"))
+        .unchecked()
+        .capture()
+        .unwrap();
+    assert_eq!(output.text(), "ok");
+    assert!(!output.success());
+    assert!(output.status().success());
+    assert_eq!(output.statuses[0].code(), Some(1));
+}
+
+#[test]
+fn any_failure_fails_the_pipeline() {
+    match cmd("false").pipe(cmd("cat")).capture() {
+        Err(Error::Failed { program, .. }) => assert_eq!(program, "false"),
+        other => panic!("{:?}", other),
+    }
+    // When several fail, the last one is reported, as with `pipefail`.
+    match cmd("false")
+        .pipe(cmd("sh").args(["-c", "exit 4"]))
+        .capture()
+    {
+        Err(Error::Failed {
+            program, status, ..
+        }) => {
+            assert_eq!((program.as_str(), status.code()), ("sh", Some(4)))
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn broken_pipes_are_not_failures() {
+    // `head` exits after two lines, and `yes` dies of SIGPIPE.
+    let output = cmd("yes")
+        .pipe(cmd("head").args(["-n", "2"]))
+        .capture()
+        .unwrap();
+    assert_eq!(output.text(), "y\ny");
+    assert!(!output.statuses[0].success());
+    // But the last command has nowhere to have been writing.
+    let e = cmd("sh")
+        .args(["-c", "kill -PIPE $$"])
+        .capture()
+        .unwrap_err();
+    assert!(matches!(e, Error::Failed { .. }), "{:?}", e);
+}
+
+#[test]
+fn missing_programs() {
+    match cmd("no-such-program-here").capture() {
+        Err(Error::Spawn { program, source }) => {
+            assert_eq!(program, "no-such-program-here");
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("{:?}", other),
+    }
+    // The commands already started are killed, not waited for.
+    let start = Instant::now();
+    let e = cmd("sleep")
+        .arg("30")
+        .pipe(cmd("no-such-program-here"))
+        .capture()
+        .unwrap_err();
+    assert_eq!(
+        e.to_string().split(':').next(),
+        Some("can't run no-such-program-here")
+    );
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn display() {
+    let pipeline = cmd("grep")
+        .args(["-v", "two words", ""])
+        .pipe(cmd("wc").arg("-l"));
+    assert_eq!(pipeline.to_string(), r#"grep -v "two words" "" | wc -l"#);
+}
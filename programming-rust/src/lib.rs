@@ -0,0 +1,90 @@
+//! The workspace's chapter libraries, under one name.
+//!
+//! Each chapter's library is a module here, behind a feature of the same
+//! name, and `prelude` has the types and functions most programs start
+//! from. The pieces are meant to be put together:
+//!
+//! ```
+//! # #[cfg(all(feature = "calc", feature = "sum"))] {
+//! use programming_rust::prelude::*;
+//!
+//! // Sum the numbers that the calculator says are multiples of 3.
+//! let numbers = "3\n4\n9\n10\n12\n";
+//! let kept: String = numbers
+//!     .lines()
+//!     .filter(|n| eval(&format!("{} % 3", n)) == Ok(0.0))
+//!     .map(|n| format!("{}\n", n))
+//!     .collect();
+//! assert_eq!(sum_lines(kept.as_bytes()).unwrap(), 24);
+//! # }
+//! ```
+//!
+//! ```
+//! # #[cfg(all(feature = "replace", feature = "calc"))] {
+//! use programming_rust::prelude::*;
+//!
+//! // Put a value in for a variable, then evaluate.
+//! let replacer = Replacer::new(r"\bx\b", "4").unwrap();
+//! let (expression, count) = replacer.replace("x ^ 2 + sqrt(x)");
+//! assert_eq!(count, 2);
+//! assert_eq!(eval(&expression), Ok(18.0));
+//! # }
+//! ```
+//!
+//! ```
+//! # #[cfg(all(feature = "json", feature = "complete", feature = "calc"))] {
+//! use programming_rust::prelude::*;
+//!
+//! // Formulas by name, in JSON; the ones starting with "a", evaluated.
+//! let doc = programming_rust::json::parse(
+//!     r#"{"area": "pi * 2 ^ 2", "average": "(3 + 4 + 8) / 3", "total": "6 * 7"}"#,
+//! )
+//! .unwrap();
+//! let names: StringTable = doc.as_object().unwrap().keys().collect();
+//! let results: Json = names
+//!     .completions("a")
+//!     .map(|name| (name, eval(doc[name].as_str().unwrap()).unwrap()))
+//!     .collect();
+//! assert_eq!(results["average"].as_f64(), Some(5.0));
+//! assert!(results["total"].is_null());
+//! # }
+//! ```
+
+/// quickreplace's engine: `Replacer`, and walking a directory tree.
+#[cfg(feature = "replace")]
+pub use ch_02 as replace;
+
+/// Chapter 3's arithmetic evaluator.
+#[cfg(feature = "calc")]
+pub use ch_03 as calc;
+
+/// Chapter 5's `StringTable`, searched by prefix.
+#[cfg(feature = "complete")]
+pub use ch_05 as complete;
+
+/// Chapter 7's sums of files of numbers, and following one as it grows.
+#[cfg(feature = "sum")]
+pub use ch_07 as sum;
+
+/// A JSON value, with a parser and a printer.
+#[cfg(feature = "json")]
+pub use tiny_json as json;
+
+/// `use programming_rust::prelude::*;` for the names above that most
+/// programs need.
+pub mod prelude {
+    #[cfg(feature = "replace")]
+    pub use ch_02::{Change, Replacer};
+
+    #[cfg(feature = "calc")]
+    pub use ch_03::{eval, EvalError};
+
+    #[cfg(feature = "complete")]
+    pub use ch_05::StringTable;
+
+    #[cfg(feature = "sum")]
+    pub use ch_07::{read_and_sum, sum_lines, SumFollower, Update};
+
+    #[cfg(feature = "json")]
+    pub use tiny_json::Json;
+}
@@ -0,0 +1,73 @@
+// Compare `Queue<T>` with `std::collections::VecDeque<T>`.
+//
+// `Queue::pop` occasionally reverses a whole stack, so an individual pop can
+// be O(n); but each element is moved at most once, so the cost per operation
+// is O(1) amortized. These benchmarks measure whole workloads, which is where
+// amortized bounds show up.
+
+use std::collections::VecDeque;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use queue::Queue;
+
+const SIZES: [u64; 3] = [100, 10_000, 1_000_000];
+
+/// Push `n` elements, then pop them all.
+fn fill_then_drain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill_then_drain");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Queue", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut q = Queue::new();
+                for i in 0..n {
+                    q.push(i);
+                }
+                while let Some(x) = q.pop() {
+                    black_box(x);
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("VecDeque", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut q = VecDeque::new();
+                for i in 0..n {
+                    q.push_back(i);
+                }
+                while let Some(x) = q.pop_front() {
+                    black_box(x);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Keep the queue at a steady size, alternating one push with one pop. For
+/// `Queue`, this triggers a reversal every `n` pops.
+fn steady_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("steady_state");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("Queue", n), &n, |b, &n| {
+            let mut q: Queue<u64> = (0..n).collect();
+            let mut i = n;
+            b.iter(|| {
+                q.push(i);
+                i += 1;
+                black_box(q.pop())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("VecDeque", n), &n, |b, &n| {
+            let mut q: VecDeque<u64> = (0..n).collect();
+            let mut i = n;
+            b.iter(|| {
+                q.push_back(i);
+                i += 1;
+                black_box(q.pop_front())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fill_then_drain, steady_state);
+criterion_main!(benches);
@@ -0,0 +1,162 @@
+// The book's generic `Queue<T>`: a first-in, first-out queue built from two
+// stacks, giving amortized constant-time `push` and `pop`.
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::mem::swap;
+
+/// A first-in, first-out queue of `T`s.
+pub struct Queue<T> {
+    older: Vec<T>,   // older elements, eldest last.
+    younger: Vec<T>, // younger elements, youngest last.
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Queue<T> {
+        Queue {
+            older: Vec::new(),
+            younger: Vec::new(),
+        }
+    }
+
+    /// Push `t` onto the back of the queue.
+    pub fn push(&mut self, t: T) {
+        self.younger.push(t);
+    }
+
+    /// Pop a value from the front of the queue. Return `Some(v)` if there
+    /// was such a value, or `None` if the queue was empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.refill();
+        self.older.pop()
+    }
+
+    /// Return a reference to the value at the front of the queue without
+    /// removing it.
+    pub fn peek(&mut self) -> Option<&T> {
+        self.refill();
+        self.older.last()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.older.is_empty() && self.younger.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.older.len() + self.younger.len()
+    }
+
+    /// Consume the queue, returning its two stacks: the older elements
+    /// (eldest last) and the younger ones (youngest last).
+    pub fn split(self) -> (Vec<T>, Vec<T>) {
+        (self.older, self.younger)
+    }
+
+    /// Remove every element, yielding them front to back. Elements the
+    /// iterator doesn't get to are still removed when it's dropped.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { queue: self }
+    }
+
+    /// If `older` is empty, move the elements of `younger` over to it, in
+    /// reverse order, so the eldest is on top. Each element makes this trip
+    /// at most once, which is what makes `pop` amortized O(1).
+    fn refill(&mut self) {
+        if self.older.is_empty() {
+            // Bring the elements in younger over to older, and put them in
+            // the promised order.
+            swap(&mut self.older, &mut self.younger);
+            self.older.reverse();
+        }
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Queue::new()
+    }
+}
+
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.younger.extend(iter);
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Queue {
+            older: Vec::new(),
+            younger: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Queue<T> {
+    /// Shows the elements front to back.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.older.iter().rev().chain(self.younger.iter()))
+            .finish()
+    }
+}
+
+/// An iterator that pops every element of a queue. See `Queue::drain`.
+pub struct Drain<'a, T> {
+    queue: &'a mut Queue<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.queue.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queue.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        self.queue.older.clear();
+        self.queue.younger.clear();
+    }
+}
+
+/// An owning iterator over a queue's elements, front to back.
+pub struct IntoIter<T> {
+    queue: Queue<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.queue.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queue.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for Queue<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { queue: self }
+    }
+}
@@ -0,0 +1,20 @@
+use queue::Queue;
+
+fn main() {
+    let mut q = Queue::new();
+
+    q.push('*');
+    q.push('1');
+    assert_eq!(q.pop(), Some('*'));
+    q.push('∞');
+    println!("queue: {:?}", q);
+
+    let (older, younger) = q.split();
+    // q is now uninitialized.
+    println!("older stack: {:?}, younger stack: {:?}", older, younger);
+
+    let mut numbers: Queue<u32> = (1..=5).collect();
+    numbers.extend(6..=8);
+    let evens: Vec<u32> = numbers.drain().filter(|n| n % 2 == 0).collect();
+    println!("evens: {:?}, left in queue: {}", evens, numbers.len());
+}
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use queue::Queue;
+
+#[test]
+fn push_and_pop_in_fifo_order() {
+    let mut q = Queue::new();
+
+    q.push('*');
+    q.push('1');
+    assert_eq!(q.pop(), Some('*'));
+    assert_eq!(q.pop(), Some('1'));
+    assert_eq!(q.pop(), None);
+
+    q.push('0');
+    q.push('1');
+    assert_eq!(q.pop(), Some('0'));
+
+    q.push('∞');
+    assert_eq!(q.pop(), Some('1'));
+    assert_eq!(q.pop(), Some('∞'));
+    assert_eq!(q.pop(), None);
+}
+
+#[test]
+fn is_empty_len_and_peek() {
+    let mut q = Queue::new();
+    assert!(q.is_empty());
+    assert_eq!(q.peek(), None);
+
+    q.push("☉");
+    q.push("☽");
+    assert!(!q.is_empty());
+    assert_eq!(q.len(), 2);
+    assert_eq!(q.peek(), Some(&"☉"));
+    q.pop();
+    assert_eq!(q.peek(), Some(&"☽"));
+    q.pop();
+    assert!(q.is_empty());
+}
+
+#[test]
+fn split_returns_both_stacks() {
+    let mut q = Queue::new();
+
+    q.push('P');
+    q.push('D');
+    assert_eq!(q.pop(), Some('P'));
+    q.push('X');
+
+    assert_eq!(q.split(), (vec!['D'], vec!['X']));
+}
+
+#[test]
+fn generic_over_element_types() {
+    let mut q = Queue::<String>::new();
+    q.push("hello".to_string());
+    let mut r: Queue<f64> = Queue::default();
+    r.push(0.74);
+    r.push(2.7);
+    assert_eq!(q.pop().as_deref(), Some("hello"));
+    assert_eq!(r.pop(), Some(0.74));
+}
+
+#[test]
+fn drain_yields_front_to_back_and_empties() {
+    let mut q: Queue<i32> = (1..=3).collect();
+    q.pop();
+    q.extend([4, 5]);
+    let drain = q.drain();
+    assert_eq!(drain.len(), 4);
+    assert_eq!(drain.collect::<Vec<_>>(), [2, 3, 4, 5]);
+    assert!(q.is_empty());
+
+    // Dropping a drain early still removes everything.
+    q.extend([6, 7, 8]);
+    assert_eq!(q.drain().next(), Some(6));
+    assert!(q.is_empty());
+}
+
+#[test]
+fn into_iter_and_debug() {
+    let mut q: Queue<u8> = vec![1, 2].into_iter().collect();
+    assert_eq!(q.pop(), Some(1));
+    q.push(3);
+    assert_eq!(format!("{:?}", q), "[2, 3]");
+    assert_eq!(q.into_iter().collect::<Vec<_>>(), [2, 3]);
+}
+
+#[test]
+fn matches_vecdeque_under_mixed_operations() {
+    let mut q = Queue::new();
+    let mut model = VecDeque::new();
+    let mut seed: u32 = 12345;
+    for i in 0..10_000 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        if seed.is_multiple_of(3) {
+            assert_eq!(q.pop(), model.pop_front());
+        } else {
+            q.push(i);
+            model.push_back(i);
+        }
+        assert_eq!(q.len(), model.len());
+    }
+    assert!(q.into_iter().eq(model));
+}
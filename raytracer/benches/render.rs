@@ -0,0 +1,29 @@
+// Rendering the same picture on one thread and on rayon's pool, for the
+// speedup parallelism buys on this machine.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use raytracer::{render, render_serial, Scene, Settings};
+
+fn rendering(c: &mut Criterion) {
+    let settings = Settings {
+        width: 160,
+        height: 90,
+        samples: 8,
+        ..Settings::default()
+    };
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    for name in Scene::NAMES {
+        let scene = Scene::named(name, settings.aspect(), settings.seed).unwrap();
+        group.bench_function(format!("{}/serial", name), |b| {
+            b.iter(|| render_serial(&scene, &settings))
+        });
+        group.bench_function(format!("{}/rayon", name), |b| {
+            b.iter(|| render(&scene, &settings))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, rendering);
+criterion_main!(benches);
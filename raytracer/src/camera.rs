@@ -0,0 +1,44 @@
+use vecmath::Vec3;
+
+use crate::ray::Ray;
+
+/// A pinhole camera: every ray starts at the same point and passes through
+/// a rectangle, the viewport, in front of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    origin: Vec3,
+    lower_left: Vec3,
+    horizontal: Vec3,
+    vertical: Vec3,
+}
+
+impl Camera {
+    /// A camera at `look_from`, pointed at `look_at`, with `up` toward the
+    /// top of the picture. `vfov` is the vertical field of view in degrees,
+    /// and `aspect` the picture's width divided by its height.
+    pub fn new(look_from: Vec3, look_at: Vec3, up: Vec3, vfov: f64, aspect: f64) -> Camera {
+        let height = 2.0 * (vfov.to_radians() / 2.0).tan();
+        let width = aspect * height;
+
+        // An orthonormal basis: `w` points backward, out of the picture.
+        let w = (look_from - look_at).normalize();
+        let u = up.cross(w).normalize();
+        let v = w.cross(u);
+
+        let horizontal = width * u;
+        let vertical = height * v;
+        Camera {
+            origin: look_from,
+            lower_left: look_from - horizontal / 2.0 - vertical / 2.0 - w,
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// The ray through the viewport at `(s, t)`: `(0, 0)` is the bottom
+    /// left corner, and `(1, 1)` the top right.
+    pub fn ray(&self, s: f64, t: f64) -> Ray {
+        let target = self.lower_left + s * self.horizontal + t * self.vertical;
+        Ray::new(self.origin, target - self.origin)
+    }
+}
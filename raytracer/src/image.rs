@@ -0,0 +1,82 @@
+// A rendered picture: 8-bit RGB pixels, top row first.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// An image of the given size from `pixels`, three bytes per pixel.
+    ///
+    /// # Panics
+    ///
+    /// If `pixels` is the wrong length for the size.
+    pub fn from_rgb(width: usize, height: usize, pixels: Vec<u8>) -> Image {
+        assert_eq!(pixels.len(), width * height * 3, "wrong number of bytes");
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// All the pixels, three bytes each, row by row from the top.
+    pub fn as_rgb(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The pixel in column `x` of row `y`, counting rows from the top.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        assert!(x < self.width && y < self.height, "pixel out of range");
+        let i = (y * self.width + x) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    /// A 64-bit FNV-1a hash of the size and pixels. Equal images have equal
+    /// checksums, and a change to any pixel almost certainly changes it,
+    /// which is all a regression test needs to know.
+    pub fn checksum(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        };
+        feed(&(self.width as u64).to_le_bytes());
+        feed(&(self.height as u64).to_le_bytes());
+        feed(&self.pixels);
+        hash
+    }
+
+    /// Encode the image as a PNG.
+    pub fn write_png<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_png(&mut out)?;
+        out.flush()
+    }
+}
@@ -0,0 +1,25 @@
+// A small path tracer: spheres and planes, matte and metal surfaces, a
+// positionable camera, and PNG output, rendered on every core with rayon.
+//
+// Rendering is deterministic. Each pixel draws its random numbers from a
+// generator seeded by the image's seed and the pixel's position, so an
+// image comes out the same whichever thread renders which row, and the
+// tests can compare whole images by checksum.
+
+mod camera;
+mod image;
+mod material;
+mod random;
+mod ray;
+mod render;
+mod scene;
+mod shape;
+
+pub use camera::Camera;
+pub use image::Image;
+pub use material::Material;
+pub use random::Rng;
+pub use ray::Ray;
+pub use render::{render, render_serial, Settings};
+pub use scene::{Object, Scene};
+pub use shape::{Hit, Shape};
@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Instant;
+use std::{env, process};
+
+use raytracer::{render, render_serial, Scene, Settings};
+
+#[derive(Debug)]
+struct Arguments {
+    settings: Settings,
+    scene: String,
+    threads: Option<usize>,
+    serial: bool,
+    output: PathBuf,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            settings: Settings::default(),
+            scene: "demo".to_string(),
+            threads: None,
+            serial: false,
+            output: PathBuf::from("render.png"),
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = |what: &str| {
+                args.next()
+                    .unwrap_or_else(|| usage_error(&format!("{} needs {}", arg, what)))
+            };
+            match arg.as_str() {
+                "--size" => {
+                    let size = value("a size");
+                    let (width, height) = size
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((number(w)?, number(h)?)))
+                        .filter(|&(w, h)| w > 0 && h > 0)
+                        .unwrap_or_else(|| {
+                            usage_error(&format!("bad size {:?}: expected WIDTHxHEIGHT", size))
+                        });
+                    arguments.settings.width = width;
+                    arguments.settings.height = height;
+                }
+                "--samples" => arguments.settings.samples = count(&value("a count")),
+                "--depth" => arguments.settings.max_depth = count(&value("a count")),
+                "--seed" => {
+                    let seed = value("a number");
+                    arguments.settings.seed = number(&seed)
+                        .unwrap_or_else(|| usage_error(&format!("bad seed {:?}", seed)));
+                }
+                "--scene" => arguments.scene = value("a name"),
+                "--threads" => arguments.threads = Some(count(&value("a count"))),
+                "--serial" => arguments.serial = true,
+                "-o" | "--output" => arguments.output = PathBuf::from(value("a path")),
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        arguments
+    }
+}
+
+fn number<T: FromStr>(s: &str) -> Option<T> {
+    s.parse().ok()
+}
+
+fn count<T: FromStr + Default + PartialOrd>(s: &str) -> T {
+    number(s)
+        .filter(|n| *n > T::default())
+        .unwrap_or_else(|| usage_error(&format!("bad count {:?}", s)))
+}
+
+fn print_usage() {
+    eprintln!("raytracer - render a scene of spheres to a PNG file");
+    eprintln!("Usage: raytracer [options]");
+    eprintln!("  --size WxH      image size in pixels (default 400x225)");
+    eprintln!("  --samples N     rays per pixel (default 32)");
+    eprintln!("  --depth N       most bounces per ray (default 16)");
+    eprintln!("  --seed N        seed for sampling and generated scenes (default 1)");
+    eprintln!(
+        "  --scene NAME    {} (default demo)",
+        Scene::NAMES.join(" or ")
+    );
+    eprintln!("  --threads N     render on N threads (default: one per core)");
+    eprintln!("  --serial        render on this thread alone, without rayon");
+    eprintln!("  -o PATH         where to write the image (default render.png)");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn main() {
+    let args = Arguments::parse();
+    let settings = args.settings;
+
+    let scene = Scene::named(&args.scene, settings.aspect(), settings.seed)
+        .unwrap_or_else(|| usage_error(&format!("unknown scene {:?}", args.scene)));
+    if let Some(threads) = args.threads {
+        if let Err(e) = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+        {
+            eprintln!("Error: failed to start threads: {}", e);
+            process::exit(1);
+        }
+    }
+
+    let start = Instant::now();
+    let image = if args.serial {
+        render_serial(&scene, &settings)
+    } else {
+        render(&scene, &settings)
+    };
+    let elapsed = start.elapsed();
+
+    if let Err(e) = image.save_png(&args.output) {
+        eprintln!("Error: failed to write {}: {}", args.output.display(), e);
+        process::exit(1);
+    }
+    let threads = if args.serial {
+        1
+    } else {
+        rayon::current_num_threads()
+    };
+    println!(
+        "{}: {}x{}, {} samples, {:.2?} on {} thread{}, checksum {:016x}",
+        args.output.display(),
+        settings.width,
+        settings.height,
+        settings.samples,
+        elapsed,
+        threads,
+        if threads == 1 { "" } else { "s" },
+        image.checksum()
+    );
+}
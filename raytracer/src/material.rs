@@ -0,0 +1,42 @@
+// How surfaces scatter the light that reaches them.
+
+use vecmath::Vec3;
+
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::shape::Hit;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Material {
+    /// A matte surface, scattering light in all directions, more of it
+    /// near the normal. `albedo` is the fraction of each of red, green,
+    /// and blue it reflects.
+    Lambertian { albedo: Vec3 },
+    /// A mirror, blurred by `fuzz`: 0 is a perfect reflection, 1 about as
+    /// rough as metal gets.
+    Metal { albedo: Vec3, fuzz: f64 },
+}
+
+impl Material {
+    /// The ray that carries light away from `hit`, and how much of each
+    /// color survives the bounce. `None` means the light was absorbed.
+    pub fn scatter(&self, ray: &Ray, hit: &Hit, rng: &mut Rng) -> Option<(Ray, Vec3)> {
+        match *self {
+            Material::Lambertian { albedo } => {
+                // A random point on the unit sphere touching the surface at
+                // `hit` gives the cosine-weighted distribution matte
+                // surfaces have.
+                let direction = (hit.normal + rng.unit_vector())
+                    .try_normalize()
+                    .unwrap_or(hit.normal);
+                Some((Ray::new(hit.point, direction), albedo))
+            }
+            Material::Metal { albedo, fuzz } => {
+                let reflected = ray.direction.normalize().reflect(hit.normal);
+                let direction = reflected + fuzz * rng.in_unit_sphere();
+                // Fuzz can push the ray below the surface; then it's lost.
+                (direction.dot(hit.normal) > 0.0).then(|| (Ray::new(hit.point, direction), albedo))
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// A small seeded pseudo-random number generator, and the random directions
+// that scattering light needs.
+
+use vecmath::Vec3;
+
+/// SplitMix64: tiny state, so it's cheap to make a fresh one per pixel.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// The generator for pixel `index` of an image rendered with `seed`.
+    /// Neighbouring pixels get unrelated streams, because SplitMix64
+    /// scrambles its state thoroughly on the way out.
+    pub fn for_pixel(seed: u64, index: u64) -> Rng {
+        Rng::seeded(seed ^ index.wrapping_mul(0xd1b5_4a32_d192_ed03))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A float uniformly distributed in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill an f64's mantissa exactly.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A float uniformly distributed in `low..high`.
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// A point uniformly distributed inside the unit sphere, found by
+    /// picking points in the enclosing cube until one lands inside.
+    pub fn in_unit_sphere(&mut self) -> Vec3 {
+        loop {
+            let p = Vec3::new(
+                self.range(-1.0, 1.0),
+                self.range(-1.0, 1.0),
+                self.range(-1.0, 1.0),
+            );
+            if p.length_squared() < 1.0 {
+                return p;
+            }
+        }
+    }
+
+    /// A direction uniformly distributed over the unit sphere.
+    pub fn unit_vector(&mut self) -> Vec3 {
+        loop {
+            if let Some(v) = self.in_unit_sphere().try_normalize() {
+                return v;
+            }
+        }
+    }
+}
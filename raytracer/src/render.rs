@@ -0,0 +1,94 @@
+// Turning a scene into pixels, on one thread or all of them.
+//
+// Every pixel is independent of every other, which makes rendering the
+// easiest kind of parallelism there is: split the image into rows and hand
+// them out. Rayon's `par_chunks_mut` does exactly that, giving each task
+// exclusive access to its own rows of the output buffer, so there's nothing
+// to lock.
+
+use rayon::prelude::*;
+use vecmath::Vec3;
+
+use crate::image::Image;
+use crate::random::Rng;
+use crate::scene::Scene;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub width: usize,
+    pub height: usize,
+    /// Rays per pixel, averaged to smooth edges and soften noise.
+    pub samples: u32,
+    /// The most times a ray may bounce.
+    pub max_depth: u32,
+    pub seed: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            width: 400,
+            height: 225,
+            samples: 32,
+            max_depth: 16,
+            seed: 1,
+        }
+    }
+}
+
+impl Settings {
+    pub fn aspect(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+}
+
+/// Render `scene` on rayon's thread pool.
+pub fn render(scene: &Scene, settings: &Settings) -> Image {
+    let mut pixels = vec![0; settings.width * settings.height * 3];
+    if settings.width > 0 {
+        pixels
+            .par_chunks_mut(settings.width * 3)
+            .enumerate()
+            .for_each(|(y, row)| render_row(scene, settings, y, row));
+    }
+    Image::from_rgb(settings.width, settings.height, pixels)
+}
+
+/// Render `scene` on the calling thread. The result is identical to
+/// `render`'s, only slower.
+pub fn render_serial(scene: &Scene, settings: &Settings) -> Image {
+    let mut pixels = vec![0; settings.width * settings.height * 3];
+    if settings.width > 0 {
+        pixels
+            .chunks_mut(settings.width * 3)
+            .enumerate()
+            .for_each(|(y, row)| render_row(scene, settings, y, row));
+    }
+    Image::from_rgb(settings.width, settings.height, pixels)
+}
+
+/// Fill in row `y`, counting from the top.
+fn render_row(scene: &Scene, settings: &Settings, y: usize, row: &mut [u8]) {
+    for (x, pixel) in row.chunks_exact_mut(3).enumerate() {
+        let index = (y * settings.width + x) as u64;
+        let mut rng = Rng::for_pixel(settings.seed, index);
+        let mut sum = Vec3::ZERO;
+        for _ in 0..settings.samples {
+            // A random point within the pixel, with t running bottom to top.
+            let s = (x as f64 + rng.next_f64()) / settings.width as f64;
+            let t = ((settings.height - 1 - y) as f64 + rng.next_f64()) / settings.height as f64;
+            let ray = scene.camera.ray(s, t);
+            sum += scene.color(&ray, settings.max_depth, &mut rng);
+        }
+        let color = sum / settings.samples.max(1) as f64;
+        pixel.copy_from_slice(&to_rgb(color));
+    }
+}
+
+/// Light intensities to bytes. Displays expect gamma-encoded values, and a
+/// square root is close enough to the real curve.
+fn to_rgb(color: Vec3) -> [u8; 3] {
+    color
+        .to_array()
+        .map(|c| (256.0 * c.sqrt().clamp(0.0, 0.999)) as u8)
+}
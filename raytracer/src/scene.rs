@@ -0,0 +1,196 @@
+// What's in the picture, and how much light reaches the camera along a ray.
+
+use vecmath::Vec3;
+
+use crate::camera::Camera;
+use crate::material::Material;
+use crate::random::Rng;
+use crate::ray::Ray;
+use crate::shape::{Hit, Shape};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Object {
+    pub shape: Shape,
+    pub material: Material,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scene {
+    pub objects: Vec<Object>,
+    pub camera: Camera,
+}
+
+/// Hits closer than this are the surface a ray just left, found again
+/// because of rounding error. Ignoring them avoids speckled "shadow acne".
+const T_MIN: f64 = 1e-3;
+
+impl Scene {
+    /// The names `Scene::named` knows.
+    pub const NAMES: [&'static str; 2] = ["demo", "spheres"];
+
+    /// A built-in scene, for a picture with the given aspect ratio.
+    /// `seed` only matters to scenes that are generated.
+    pub fn named(name: &str, aspect: f64, seed: u64) -> Option<Scene> {
+        match name {
+            "demo" => Some(Scene::demo(aspect)),
+            "spheres" => Some(Scene::spheres(aspect, seed)),
+            _ => None,
+        }
+    }
+
+    /// Three spheres on a floor: matte, polished metal, and brushed metal.
+    pub fn demo(aspect: f64) -> Scene {
+        let objects = vec![
+            Object {
+                shape: Shape::Plane {
+                    point: Vec3::new(0.0, -0.5, 0.0),
+                    normal: Vec3::Y,
+                },
+                material: Material::Lambertian {
+                    albedo: Vec3::new(0.8, 0.8, 0.0),
+                },
+            },
+            Object {
+                shape: Shape::Sphere {
+                    center: Vec3::new(0.0, 0.0, -1.0),
+                    radius: 0.5,
+                },
+                material: Material::Lambertian {
+                    albedo: Vec3::new(0.1, 0.2, 0.5),
+                },
+            },
+            Object {
+                shape: Shape::Sphere {
+                    center: Vec3::new(-1.0, 0.0, -1.0),
+                    radius: 0.5,
+                },
+                material: Material::Metal {
+                    albedo: Vec3::new(0.8, 0.8, 0.8),
+                    fuzz: 0.0,
+                },
+            },
+            Object {
+                shape: Shape::Sphere {
+                    center: Vec3::new(1.0, 0.0, -1.0),
+                    radius: 0.5,
+                },
+                material: Material::Metal {
+                    albedo: Vec3::new(0.8, 0.6, 0.2),
+                    fuzz: 0.3,
+                },
+            },
+        ];
+        let camera = Camera::new(
+            Vec3::new(0.0, 0.5, 1.5),
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::Y,
+            50.0,
+            aspect,
+        );
+        Scene { objects, camera }
+    }
+
+    /// A field of small spheres of random colors and materials around a
+    /// large mirror, generated from `seed`.
+    pub fn spheres(aspect: f64, seed: u64) -> Scene {
+        let mut rng = Rng::seeded(seed);
+        let mut objects = vec![
+            Object {
+                shape: Shape::Plane {
+                    point: Vec3::ZERO,
+                    normal: Vec3::Y,
+                },
+                material: Material::Lambertian {
+                    albedo: Vec3::splat(0.5),
+                },
+            },
+            Object {
+                shape: Shape::Sphere {
+                    center: Vec3::new(0.0, 1.0, 0.0),
+                    radius: 1.0,
+                },
+                material: Material::Metal {
+                    albedo: Vec3::new(0.7, 0.6, 0.5),
+                    fuzz: 0.0,
+                },
+            },
+        ];
+        for a in -5..5 {
+            for b in -5..5 {
+                let center = Vec3::new(
+                    a as f64 + 0.8 * rng.next_f64(),
+                    0.2,
+                    b as f64 + 0.8 * rng.next_f64(),
+                );
+                // Leave room for the mirror.
+                if (center - Vec3::new(0.0, 0.2, 0.0)).length() < 1.3 {
+                    continue;
+                }
+                let color = Vec3::new(rng.next_f64(), rng.next_f64(), rng.next_f64());
+                let material = if rng.next_f64() < 0.75 {
+                    Material::Lambertian {
+                        albedo: color.mul_elements(color),
+                    }
+                } else {
+                    Material::Metal {
+                        albedo: 0.5 * (Vec3::ONE + color),
+                        fuzz: rng.range(0.0, 0.5),
+                    }
+                };
+                objects.push(Object {
+                    shape: Shape::Sphere {
+                        center,
+                        radius: 0.2,
+                    },
+                    material,
+                });
+            }
+        }
+        let camera = Camera::new(
+            Vec3::new(7.0, 2.5, 4.0),
+            Vec3::new(0.0, 0.5, 0.0),
+            Vec3::Y,
+            35.0,
+            aspect,
+        );
+        Scene { objects, camera }
+    }
+
+    /// The nearest object `ray` hits, and where.
+    pub fn hit(&self, ray: &Ray) -> Option<(Hit, &Object)> {
+        let mut nearest = None;
+        let mut t_max = f64::INFINITY;
+        for object in &self.objects {
+            if let Some(hit) = object.shape.hit(ray, T_MIN, t_max) {
+                t_max = hit.t;
+                nearest = Some((hit, object));
+            }
+        }
+        nearest
+    }
+
+    /// The light arriving along `ray`, following at most `depth` bounces.
+    /// Light that's still bouncing after that is counted as none at all.
+    pub fn color(&self, ray: &Ray, depth: u32, rng: &mut Rng) -> Vec3 {
+        let mut ray = *ray;
+        let mut throughput = Vec3::ONE;
+        for _ in 0..depth {
+            let Some((hit, object)) = self.hit(&ray) else {
+                return throughput.mul_elements(sky(&ray));
+            };
+            let Some((scattered, attenuation)) = object.material.scatter(&ray, &hit, rng) else {
+                return Vec3::ZERO;
+            };
+            throughput = throughput.mul_elements(attenuation);
+            ray = scattered;
+        }
+        Vec3::ZERO
+    }
+}
+
+/// The only light source: a sky fading from white at the horizon to blue
+/// overhead.
+fn sky(ray: &Ray) -> Vec3 {
+    let t = 0.5 * (ray.direction.normalize().y + 1.0);
+    Vec3::ONE.lerp(Vec3::new(0.5, 0.7, 1.0), t)
+}
@@ -0,0 +1,78 @@
+// The surfaces a ray can hit.
+
+use vecmath::Vec3;
+
+use crate::ray::Ray;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Sphere {
+        center: Vec3,
+        radius: f64,
+    },
+    /// An infinite plane through `point`, perpendicular to the unit vector
+    /// `normal`.
+    Plane {
+        point: Vec3,
+        normal: Vec3,
+    },
+}
+
+/// Where a ray meets a surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    /// The distance along the ray, in multiples of its direction.
+    pub t: f64,
+    pub point: Vec3,
+    /// The unit normal on the side the ray came from, so scattered light
+    /// always leaves along the side it arrived on.
+    pub normal: Vec3,
+}
+
+impl Shape {
+    /// The nearest point where `ray` meets the surface with `t` strictly
+    /// between `t_min` and `t_max`, if there is one.
+    pub fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<Hit> {
+        let within = |t: f64| t > t_min && t < t_max;
+        let (t, outward) = match *self {
+            Shape::Sphere { center, radius } => {
+                // Solve |origin + t direction - center|² = radius², a
+                // quadratic in t, with b halved to save some arithmetic.
+                let oc = ray.origin - center;
+                let a = ray.direction.length_squared();
+                let half_b = oc.dot(ray.direction);
+                let c = oc.length_squared() - radius * radius;
+                let discriminant = half_b * half_b - a * c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                let t = [(-half_b - root) / a, (-half_b + root) / a]
+                    .into_iter()
+                    .find(|&t| within(t))?;
+                (t, (ray.at(t) - center) / radius)
+            }
+            Shape::Plane { point, normal } => {
+                let denominator = ray.direction.dot(normal);
+                if denominator.abs() < 1e-12 {
+                    return None;
+                }
+                let t = (point - ray.origin).dot(normal) / denominator;
+                if !within(t) {
+                    return None;
+                }
+                (t, normal)
+            }
+        };
+        let normal = if ray.direction.dot(outward) > 0.0 {
+            -outward
+        } else {
+            outward
+        };
+        Some(Hit {
+            t,
+            point: ray.at(t),
+            normal,
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use raytracer::{Camera, Hit, Material, Ray, Rng, Shape};
+use vecmath::{ApproxEq, Vec3};
+
+fn unit_sphere() -> Shape {
+    Shape::Sphere {
+        center: Vec3::new(0.0, 0.0, -3.0),
+        radius: 1.0,
+    }
+}
+
+#[test]
+fn sphere_hit_from_outside() {
+    let ray = Ray::new(Vec3::ZERO, -Vec3::Z);
+    let hit = unit_sphere().hit(&ray, 0.0, f64::INFINITY).unwrap();
+    assert!(hit.t.approx_eq(&2.0));
+    assert!(hit.point.approx_eq(&Vec3::new(0.0, 0.0, -2.0)));
+    assert!(hit.normal.approx_eq(&Vec3::Z));
+}
+
+#[test]
+fn sphere_missed() {
+    let ray = Ray::new(Vec3::ZERO, Vec3::new(0.0, 1.0, -1.0));
+    assert_eq!(unit_sphere().hit(&ray, 0.0, f64::INFINITY), None);
+    // Pointing away from it.
+    let ray = Ray::new(Vec3::ZERO, Vec3::Z);
+    assert_eq!(unit_sphere().hit(&ray, 0.0, f64::INFINITY), None);
+}
+
+#[test]
+fn sphere_hit_from_inside_faces_the_ray() {
+    let ray = Ray::new(Vec3::new(0.0, 0.0, -3.0), -Vec3::Z);
+    let hit = unit_sphere().hit(&ray, 0.0, f64::INFINITY).unwrap();
+    assert!(hit.t.approx_eq(&1.0));
+    assert!(hit.normal.approx_eq(&Vec3::Z));
+}
+
+#[test]
+fn hits_outside_the_range_are_ignored() {
+    let ray = Ray::new(Vec3::ZERO, -Vec3::Z);
+    // The near side is at 2 and the far side at 4.
+    let hit = unit_sphere().hit(&ray, 2.5, f64::INFINITY).unwrap();
+    assert!(hit.t.approx_eq(&4.0));
+    assert_eq!(unit_sphere().hit(&ray, 0.0, 1.5), None);
+}
+
+#[test]
+fn planes() {
+    let floor = Shape::Plane {
+        point: Vec3::new(0.0, -1.0, 0.0),
+        normal: Vec3::Y,
+    };
+    let down = Ray::new(Vec3::ZERO, Vec3::new(0.0, -2.0, -2.0));
+    let hit = floor.hit(&down, 0.0, f64::INFINITY).unwrap();
+    assert!(hit.t.approx_eq(&0.5));
+    assert!(hit.point.approx_eq(&Vec3::new(0.0, -1.0, -1.0)));
+    assert!(hit.normal.approx_eq(&Vec3::Y));
+
+    let level = Ray::new(Vec3::ZERO, -Vec3::Z);
+    assert_eq!(floor.hit(&level, 0.0, f64::INFINITY), None);
+
+    // From below, the normal flips to face the ray.
+    let up = Ray::new(Vec3::new(0.0, -2.0, 0.0), Vec3::Y);
+    let hit = floor.hit(&up, 0.0, f64::INFINITY).unwrap();
+    assert!(hit.normal.approx_eq(&-Vec3::Y));
+}
+
+#[test]
+fn camera_aims_at_its_target() {
+    let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y, 90.0, 2.0);
+    let center = camera.ray(0.5, 0.5);
+    assert!(center.origin.approx_eq(&Vec3::new(0.0, 0.0, 5.0)));
+    assert!(center.direction.normalize().approx_eq(&-Vec3::Z));
+
+    // A 90° field of view spans 45° either side of center, vertically.
+    let top = camera.ray(0.5, 1.0).direction.normalize();
+    assert!(top.approx_eq(&Vec3::new(0.0, 1.0, -1.0).normalize()));
+    // Twice as wide as it is tall.
+    let right = camera.ray(1.0, 0.5).direction;
+    assert!(right.approx_eq(&Vec3::new(2.0, 0.0, -1.0)));
+}
+
+#[test]
+fn polished_metal_reflects() {
+    let mirror = Material::Metal {
+        albedo: Vec3::splat(0.9),
+        fuzz: 0.0,
+    };
+    let ray = Ray::new(Vec3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
+    let hit = Hit {
+        t: 1.0,
+        point: Vec3::ZERO,
+        normal: Vec3::Y,
+    };
+    let (scattered, attenuation) = mirror.scatter(&ray, &hit, &mut Rng::seeded(1)).unwrap();
+    assert!(scattered.origin.approx_eq(&Vec3::ZERO));
+    assert!(scattered
+        .direction
+        .approx_eq(&Vec3::new(1.0, 1.0, 0.0).normalize()));
+    assert_eq!(attenuation, Vec3::splat(0.9));
+}
+
+#[test]
+fn matte_surfaces_scatter_away_from_the_surface() {
+    let matte = Material::Lambertian {
+        albedo: Vec3::new(0.5, 0.25, 0.125),
+    };
+    let ray = Ray::new(Vec3::Y, -Vec3::Y);
+    let hit = Hit {
+        t: 1.0,
+        point: Vec3::ZERO,
+        normal: Vec3::Y,
+    };
+    let mut rng = Rng::seeded(7);
+    for _ in 0..1000 {
+        let (scattered, attenuation) = matte.scatter(&ray, &hit, &mut rng).unwrap();
+        assert!(scattered.direction.dot(hit.normal) >= 0.0);
+        assert!(scattered.direction.length().approx_eq(&1.0));
+        assert_eq!(attenuation, Vec3::new(0.5, 0.25, 0.125));
+    }
+}
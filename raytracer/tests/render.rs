@@ -0,0 +1,115 @@
+use raytracer::{render, render_serial, Image, Scene, Settings};
+
+/// Small enough to render quickly in a debug build, big enough that every
+/// object and material in the scenes shows up.
+fn small(seed: u64) -> Settings {
+    Settings {
+        width: 64,
+        height: 36,
+        samples: 8,
+        max_depth: 8,
+        seed,
+    }
+}
+
+fn scene(name: &str, settings: &Settings) -> Scene {
+    Scene::named(name, settings.aspect(), settings.seed).unwrap()
+}
+
+#[test]
+fn parallel_matches_serial() {
+    for name in Scene::NAMES {
+        let settings = small(3);
+        let scene = scene(name, &settings);
+        let serial = render_serial(&scene, &settings);
+        // However many threads share the rows, and in whatever order.
+        for threads in [1, 3, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            let parallel = pool.install(|| render(&scene, &settings));
+            assert!(parallel == serial, "{} on {} threads", name, threads);
+        }
+    }
+}
+
+#[test]
+fn seeds() {
+    let settings = small(1);
+    let scene = scene("demo", &settings);
+    assert_eq!(render(&scene, &settings), render(&scene, &settings));
+    assert_ne!(
+        render(&scene, &settings).checksum(),
+        render(&scene, &small(2)).checksum()
+    );
+}
+
+/// Checksums of images rendered before, to catch any change to what the
+/// renderer draws. A deliberate change means updating them: view the new
+/// image, then copy the checksum `raytracer` prints for the same settings.
+///
+/// They rely on the same floating-point results on every platform, which
+/// `+`, `*`, `/`, and `sqrt` guarantee. The camera's one `tan` comes from
+/// the platform's math library, which could conceivably round differently.
+#[test]
+fn regression() {
+    for (name, seed, expected) in [
+        ("demo", 1, 0xa079_003d_4a5f_d9d3),
+        ("demo", 42, 0xf501_2204_82c2_c05e),
+        ("spheres", 1, 0x36e9_5719_5ab7_22d2),
+        ("spheres", 42, 0x200a_7afc_ed40_6680),
+    ] {
+        let settings = small(seed);
+        let image = render(&scene(name, &settings), &settings);
+        assert_eq!(
+            image.checksum(),
+            expected,
+            "{} with seed {}: got {:#018x}",
+            name,
+            seed,
+            image.checksum()
+        );
+    }
+}
+
+#[test]
+fn sky_and_floor() {
+    let settings = small(1);
+    let image = render(&scene("demo", &settings), &settings);
+    // The top row is all sky, bluer than it is red.
+    let [r, _, b] = image.pixel(32, 0);
+    assert!(b > r, "{:?}", image.pixel(32, 0));
+    // The bottom corners are the yellow floor: red and green, little blue.
+    let [r, g, b] = image.pixel(0, 35);
+    assert!(r > 2 * b && g > 2 * b, "{:?}", image.pixel(0, 35));
+}
+
+#[test]
+fn png_round_trip() {
+    let settings = small(1);
+    let image = render(&scene("spheres", &settings), &settings);
+    let mut bytes = Vec::new();
+    image.write_png(&mut bytes).unwrap();
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+    let decoder = png::Decoder::new(bytes.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height), (64, 36));
+    assert_eq!(info.color_type, png::ColorType::Rgb);
+    let decoded = Image::from_rgb(64, 36, pixels[..info.buffer_size()].to_vec());
+    assert_eq!(decoded, image);
+}
+
+#[test]
+fn empty_images() {
+    let settings = Settings {
+        width: 0,
+        height: 0,
+        ..small(1)
+    };
+    let image = render(&scene("demo", &small(1)), &settings);
+    assert_eq!((image.width(), image.height()), (0, 0));
+}
@@ -0,0 +1,118 @@
+// The loading side: open a plugin's library, check its ABI version, and
+// call its transform through safe methods.
+
+use std::ffi::CStr;
+use std::fmt;
+use std::path::Path;
+use std::slice;
+
+use libloading::{Library, Symbol};
+
+use crate::{Buffer, PluginV1, ABI_VERSION};
+
+/// A loaded plugin. The library stays loaded for as long as this does.
+pub struct Plugin {
+    name: String,
+    transform: unsafe extern "C" fn(text: *const u8, len: usize) -> Buffer,
+    free: unsafe extern "C" fn(buffer: Buffer),
+    // Dropped last, after nothing can call into it.
+    _library: Library,
+}
+
+impl Plugin {
+    /// Load the plugin at `path`.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization code, and nothing can
+    /// check that its exports are what their names promise. `path` must
+    /// be a plugin built with `export_plugin!`, or one that keeps the same
+    /// contract.
+    pub unsafe fn load(path: impl AsRef<Path>) -> Result<Plugin, Error> {
+        let library = Library::new(path.as_ref()).map_err(Error::Load)?;
+        let abi: Symbol<extern "C" fn() -> u32> = library
+            .get(b"quickreplace_plugin_abi\0")
+            .map_err(Error::Load)?;
+        let version = abi();
+        if version != ABI_VERSION {
+            return Err(Error::Version { found: version });
+        }
+        let entry: Symbol<extern "C" fn() -> PluginV1> =
+            library.get(b"quickreplace_plugin\0").map_err(Error::Load)?;
+        let table = entry();
+        let name = CStr::from_ptr(table.name).to_string_lossy().into_owned();
+        Ok(Plugin {
+            name,
+            transform: table.transform,
+            free: table.free,
+            _library: library,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Pass `text` through the plugin.
+    pub fn transform(&self, text: &str) -> Result<String, Error> {
+        // Safety: `load`'s caller promised these functions keep the
+        // contract on `PluginV1`, and the library is still loaded.
+        let bytes = unsafe {
+            let buffer = (self.transform)(text.as_ptr(), text.len());
+            if buffer.ptr.is_null() {
+                return Err(Error::Failed {
+                    plugin: self.name.clone(),
+                });
+            }
+            let bytes = slice::from_raw_parts(buffer.ptr, buffer.len).to_vec();
+            (self.free)(buffer);
+            bytes
+        };
+        String::from_utf8(bytes).map_err(|_| Error::NotUtf8 {
+            plugin: self.name.clone(),
+        })
+    }
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Plugin").field("name", &self.name).finish()
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The library couldn't be opened, or doesn't export a plugin's
+    /// functions.
+    Load(libloading::Error),
+    /// The plugin was built for another version of the interface.
+    Version { found: u32 },
+    /// The plugin's transform panicked, or was given text it refused.
+    Failed { plugin: String },
+    /// The plugin's transform returned something that isn't UTF-8.
+    NotUtf8 { plugin: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Load(_) => write!(f, "couldn't load the library"),
+            Error::Version { found } => write!(
+                f,
+                "plugin interface version {} isn't supported; expected {}",
+                found, ABI_VERSION
+            ),
+            Error::Failed { plugin } => write!(f, "plugin '{}' failed", plugin),
+            Error::NotUtf8 { plugin } => write!(f, "plugin '{}' returned invalid UTF-8", plugin),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Load(e) => Some(e),
+            _ => None,
+        }
+    }
+}
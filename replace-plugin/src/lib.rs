@@ -0,0 +1,134 @@
+// quickreplace's plugins: shared libraries, loaded while it runs, that
+// rework each replacement before it goes into the text.
+//
+// Rust has no stable ABI, so a plugin and the program loading it, perhaps
+// built by different compilers, can only agree on C's. A plugin exports
+// two functions: `quickreplace_plugin_abi`, returning the `ABI_VERSION`
+// it was built for, and `quickreplace_plugin`, returning a `PluginV1` of
+// function pointers. The version is checked first, so a plugin expecting
+// some other layout is refused before its table can be misread.
+//
+// A plugin's author writes an ordinary `fn(&str) -> String` and lets
+// `export_plugin!` write the C side. The program loading it uses
+// `Plugin`, in `host`.
+
+pub mod host;
+
+pub use host::{Error, Plugin};
+
+use std::mem::ManuallyDrop;
+use std::os::raw::c_char;
+use std::ptr;
+
+/// The version of `PluginV1` and `Buffer` this crate defines. Any change
+/// to either one's layout or meaning must change it.
+pub const ABI_VERSION: u32 = 1;
+
+/// What a plugin's `quickreplace_plugin` returns.
+#[repr(C)]
+pub struct PluginV1 {
+    /// The plugin's name, NUL-terminated, valid for as long as the library
+    /// is loaded.
+    pub name: *const c_char,
+    /// Rework the `len` bytes of UTF-8 at `text`. The result belongs to
+    /// the plugin, and must be handed back to `free`. It may be called
+    /// from any thread.
+    pub transform: unsafe extern "C" fn(text: *const u8, len: usize) -> Buffer,
+    pub free: unsafe extern "C" fn(buffer: Buffer),
+}
+
+/// A `Vec<u8>` taken apart to cross the boundary, so that the plugin that
+/// allocated it can put it back together to free it. A null `ptr` means
+/// the transform failed.
+#[repr(C)]
+pub struct Buffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl Buffer {
+    pub const FAILED: Buffer = Buffer {
+        ptr: ptr::null_mut(),
+        len: 0,
+        capacity: 0,
+    };
+
+    pub fn from_vec(bytes: Vec<u8>) -> Buffer {
+        let mut bytes = ManuallyDrop::new(bytes);
+        Buffer {
+            ptr: bytes.as_mut_ptr(),
+            len: bytes.len(),
+            capacity: bytes.capacity(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `self` must have come from `from_vec`, in the same library, and not
+    /// been turned back already.
+    pub unsafe fn into_vec(self) -> Vec<u8> {
+        Vec::from_raw_parts(self.ptr, self.len, self.capacity)
+    }
+}
+
+/// Export `transform`, a `fn(&str) -> String`, as a plugin named `name`:
+///
+/// ```ignore
+/// fn shout(text: &str) -> String {
+///     text.to_uppercase()
+/// }
+///
+/// replace_plugin::export_plugin!("shout", shout);
+/// ```
+///
+/// The crate must be built as a `cdylib`. A panic in `transform` is caught
+/// and reported to the program as a failure, rather than unwinding into
+/// code that can't handle it.
+#[macro_export]
+macro_rules! export_plugin {
+    ($name:literal, $transform:path) => {
+        #[no_mangle]
+        pub extern "C" fn quickreplace_plugin_abi() -> u32 {
+            $crate::ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn quickreplace_plugin() -> $crate::PluginV1 {
+            unsafe extern "C" fn transform(text: *const u8, len: usize) -> $crate::Buffer {
+                $crate::__private::transform($transform, text, len)
+            }
+            unsafe extern "C" fn free(buffer: $crate::Buffer) {
+                drop($crate::Buffer::into_vec(buffer));
+            }
+            $crate::PluginV1 {
+                name: concat!($name, "\0").as_ptr().cast(),
+                transform,
+                free,
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use std::panic::{self, AssertUnwindSafe};
+    use std::{slice, str};
+
+    use crate::Buffer;
+
+    /// The body of an exported `transform`.
+    ///
+    /// # Safety
+    ///
+    /// `text` must point to `len` readable bytes.
+    pub unsafe fn transform(f: fn(&str) -> String, text: *const u8, len: usize) -> Buffer {
+        let Ok(text) = str::from_utf8(slice::from_raw_parts(text, len)) else {
+            return Buffer::FAILED;
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| f(text))) {
+            Ok(result) => Buffer::from_vec(result.into_bytes()),
+            Err(_) => Buffer::FAILED,
+        }
+    }
+}
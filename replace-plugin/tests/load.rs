@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::{env, fs, thread};
+
+use replace_plugin::{Error, Plugin, ABI_VERSION};
+
+// This test binary exports a plugin of its own, which panics, to call
+// straight through its table.
+fn explode(text: &str) -> String {
+    panic!("can't transform {:?}", text);
+}
+
+replace_plugin::export_plugin!("explode", explode);
+
+/// Build the example plugin and return its library's path. A test can only
+/// ask Cargo about its own package's targets, so this asks it to build
+/// another, as cli-tests does.
+fn upper_plugin() -> PathBuf {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .args([
+            "build",
+            "--quiet",
+            "--package",
+            "upper-plugin",
+            "--manifest-path",
+        ])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/../Cargo.toml"))
+        .status()
+        .expect("can't run cargo");
+    assert!(status.success(), "building upper-plugin failed");
+
+    // Tests run from `target/debug/deps`; libraries are built one level up.
+    let exe = env::current_exe().unwrap();
+    let mut dir = exe.parent().unwrap();
+    if dir.ends_with("deps") {
+        dir = dir.parent().unwrap();
+    }
+    dir.join(format!(
+        "{}upper_plugin{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    ))
+}
+
+#[test]
+fn loads_and_transforms() {
+    let plugin = unsafe { Plugin::load(upper_plugin()) }.unwrap();
+    assert_eq!(plugin.name(), "upper");
+    assert_eq!(plugin.transform("hello, world").unwrap(), "HELLO, WORLD");
+    assert_eq!(plugin.transform("straße").unwrap(), "STRASSE");
+    assert_eq!(plugin.transform("").unwrap(), "");
+}
+
+#[test]
+fn plugins_can_be_shared_between_threads() {
+    let plugin = unsafe { Plugin::load(upper_plugin()) }.unwrap();
+    thread::scope(|scope| {
+        for word in ["one", "two", "three"] {
+            let plugin = &plugin;
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    assert_eq!(plugin.transform(word).unwrap(), word.to_uppercase());
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn loading_something_else_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing.so");
+    assert!(matches!(
+        unsafe { Plugin::load(&missing) },
+        Err(Error::Load(_))
+    ));
+    let text = dir.path().join("text.so");
+    fs::write(&text, "not a library").unwrap();
+    let error = unsafe { Plugin::load(&text) }.unwrap_err();
+    assert!(matches!(error, Error::Load(_)));
+    assert_eq!(error.to_string(), "couldn't load the library");
+}
+
+#[test]
+fn errors_say_what_went_wrong() {
+    let version = Error::Version {
+        found: ABI_VERSION + 1,
+    };
+    assert_eq!(
+        version.to_string(),
+        format!(
+            "plugin interface version {} isn't supported; expected {}",
+            ABI_VERSION + 1,
+            ABI_VERSION
+        )
+    );
+    let failed = Error::Failed {
+        plugin: "upper".to_string(),
+    };
+    assert_eq!(failed.to_string(), "plugin 'upper' failed");
+}
+
+#[test]
+fn a_panic_is_a_failure_not_an_abort() {
+    assert_eq!(quickreplace_plugin_abi(), ABI_VERSION);
+    let table = quickreplace_plugin();
+    let text = "boom";
+    let buffer = unsafe { (table.transform)(text.as_ptr(), text.len()) };
+    assert!(buffer.ptr.is_null());
+}
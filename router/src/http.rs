@@ -0,0 +1,155 @@
+// Just enough of HTTP to route: requests, responses, and methods.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Options,
+}
+
+impl Method {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+            Method::Options => "OPTIONS",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownMethod(pub String);
+
+impl fmt::Display for UnknownMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown HTTP method: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMethod {}
+
+impl FromStr for Method {
+    type Err = UnknownMethod;
+
+    fn from_str(s: &str) -> Result<Method, UnknownMethod> {
+        Ok(match s {
+            "GET" => Method::Get,
+            "HEAD" => Method::Head,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "PATCH" => Method::Patch,
+            "DELETE" => Method::Delete,
+            "OPTIONS" => Method::Options,
+            _ => return Err(UnknownMethod(s.to_string())),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+    /// Values of the route's `:name` path parameters, filled in by the
+    /// router before the handler is called.
+    pub params: HashMap<String, String>,
+}
+
+impl Request {
+    pub fn new(method: Method, url: &str) -> Request {
+        Request {
+            method,
+            url: url.to_string(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+            params: HashMap::new(),
+        }
+    }
+
+    pub fn get(url: &str) -> Request {
+        Request::new(Method::Get, url)
+    }
+
+    pub fn post(url: &str, body: &[u8]) -> Request {
+        Request {
+            body: body.to_vec(),
+            ..Request::new(Method::Post, url)
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Request {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// The path part of the URL, without any query string.
+    pub fn path(&self) -> &str {
+        match self.url.split_once('?') {
+            Some((path, _query)) => path,
+            None => &self.url,
+        }
+    }
+
+    /// The value of the path parameter `name`.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    pub code: u32,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(code: u32, body: impl Into<Vec<u8>>) -> Response {
+        Response {
+            code,
+            headers: HashMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn ok(body: impl Into<Vec<u8>>) -> Response {
+        Response::new(200, body)
+    }
+
+    pub fn not_found() -> Response {
+        Response::new(404, "Not Found")
+    }
+
+    pub fn method_not_allowed() -> Response {
+        Response::new(405, "Method Not Allowed")
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Response {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// The body as text, if it's valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.body).ok()
+    }
+}
@@ -0,0 +1,9 @@
+// The book's closure-based router: each route's handler is a boxed closure,
+// so handlers can capture whatever state they need, and every route can
+// have a different closure type yet live in the same table.
+
+pub mod http;
+pub mod router;
+
+pub use http::{Method, Request, Response};
+pub use router::{BasicRouter, BoxedCallback};
@@ -0,0 +1,45 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use router::{BasicRouter, Request, Response};
+
+fn main() {
+    let mut router = BasicRouter::new();
+
+    router.get("/", |_| Response::ok("Welcome!"));
+
+    // Handlers are `Fn`, but can still keep counts using interior
+    // mutability.
+    let hits = Rc::new(Cell::new(0));
+    let counter = hits.clone();
+    router.get("/gcd", move |_| {
+        counter.set(counter.get() + 1);
+        Response::ok("Compute the GCD of two numbers")
+    });
+
+    router.get("/users/:name", |req| {
+        Response::ok(format!("Hello, {}!", req.param("name").unwrap()))
+    });
+
+    let server_name = String::from("router-demo");
+    router.wrap(move |req, next| next(req).with_header("Server", &server_name));
+
+    let requests = [
+        Request::get("/"),
+        Request::get("/gcd"),
+        Request::get("/users/ferris"),
+        Request::get("/gcd?n=12&m=18"),
+        Request::post("/gcd", b"n=12&m=18"),
+        Request::get("/nowhere"),
+    ];
+    router.handle_all(&requests, |req, resp| {
+        println!(
+            "{} {} -> {} {:?}",
+            req.method,
+            req.url,
+            resp.code,
+            resp.text().unwrap_or("<binary>")
+        );
+    });
+    println!("/gcd was hit {} times", hits.get());
+}
@@ -0,0 +1,186 @@
+// `BasicRouter`: a table of routes, each with a boxed callback.
+
+use std::collections::HashMap;
+
+use crate::http::{Method, Request, Response};
+
+/// A route handler. Different closures have different types, so to store
+/// many in one table, the router boxes them as trait objects.
+pub type BoxedCallback = Box<dyn Fn(&Request) -> Response>;
+
+/// Middleware gets the request and the rest of the chain, which it may call
+/// (or not) and whose response it may alter.
+type BoxedMiddleware = Box<dyn Fn(&Request, &dyn Fn(&Request) -> Response) -> Response>;
+
+enum Segment {
+    Literal(String),
+    Param(String),
+}
+
+struct Route {
+    method: Method,
+    pattern: Vec<Segment>,
+    callback: BoxedCallback,
+}
+
+impl Route {
+    /// If `path` matches this route's pattern, return its parameters.
+    fn match_path(&self, path: &str) -> Option<HashMap<String, String>> {
+        let mut params = HashMap::new();
+        let mut parts = split_path(path);
+        for segment in &self.pattern {
+            let part = parts.next()?;
+            match segment {
+                Segment::Literal(literal) if literal == part => {}
+                Segment::Literal(_) => return None,
+                Segment::Param(name) => {
+                    params.insert(name.clone(), part.to_string());
+                }
+            }
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(params)
+    }
+}
+
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|part| !part.is_empty())
+}
+
+pub struct BasicRouter {
+    routes: Vec<Route>,
+    fallback: BoxedCallback,
+    middleware: Vec<BoxedMiddleware>,
+}
+
+impl BasicRouter {
+    /// Create an empty router, which answers every request with 404.
+    pub fn new() -> BasicRouter {
+        BasicRouter {
+            routes: Vec::new(),
+            fallback: Box::new(|_| Response::not_found()),
+            middleware: Vec::new(),
+        }
+    }
+
+    /// Add a route to the router. Segments of `pattern` that start with `:`
+    /// match any single path segment, and are passed to the callback as
+    /// `request.params`. Routes are tried in the order they were added.
+    ///
+    /// The callback must be `Fn`, since the router may call it any number
+    /// of times, and `'static`, since the router keeps it indefinitely.
+    pub fn add_route<C>(&mut self, method: Method, pattern: &str, callback: C)
+    where
+        C: Fn(&Request) -> Response + 'static,
+    {
+        let pattern = split_path(pattern)
+            .map(|part| match part.strip_prefix(':') {
+                Some(name) => Segment::Param(name.to_string()),
+                None => Segment::Literal(part.to_string()),
+            })
+            .collect();
+        self.routes.push(Route {
+            method,
+            pattern,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Shorthand for `add_route(Method::Get, ...)`.
+    pub fn get<C>(&mut self, pattern: &str, callback: C)
+    where
+        C: Fn(&Request) -> Response + 'static,
+    {
+        self.add_route(Method::Get, pattern, callback);
+    }
+
+    /// Shorthand for `add_route(Method::Post, ...)`.
+    pub fn post<C>(&mut self, pattern: &str, callback: C)
+    where
+        C: Fn(&Request) -> Response + 'static,
+    {
+        self.add_route(Method::Post, pattern, callback);
+    }
+
+    /// Replace the handler for requests that match no route.
+    pub fn set_fallback<C>(&mut self, callback: C)
+    where
+        C: Fn(&Request) -> Response + 'static,
+    {
+        self.fallback = Box::new(callback);
+    }
+
+    /// Wrap every request in `middleware`. The most recently added
+    /// middleware runs first.
+    pub fn wrap<M>(&mut self, middleware: M)
+    where
+        M: Fn(&Request, &dyn Fn(&Request) -> Response) -> Response + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+    }
+
+    /// Route `request` and return the response.
+    ///
+    /// A request whose path matches some route, but not with the request's
+    /// method, gets a 405 response rather than going to the fallback.
+    pub fn handle_request(&self, request: &Request) -> Response {
+        self.run_middleware(self.middleware.len(), request)
+    }
+
+    /// Handle each request in turn, passing each response to
+    /// `on_response`. Unlike route handlers, which may be called any number
+    /// of times from anywhere, `on_response` is called only from here, one
+    /// response at a time, so it may be `FnMut`.
+    pub fn handle_all<'r, I, F>(&self, requests: I, mut on_response: F)
+    where
+        I: IntoIterator<Item = &'r Request>,
+        F: FnMut(&Request, Response),
+    {
+        for request in requests {
+            on_response(request, self.handle_request(request));
+        }
+    }
+
+    fn run_middleware(&self, depth: usize, request: &Request) -> Response {
+        match depth.checked_sub(1) {
+            None => self.dispatch(request),
+            Some(inner) => {
+                let next = |request: &Request| self.run_middleware(inner, request);
+                (self.middleware[inner])(request, &next)
+            }
+        }
+    }
+
+    fn dispatch(&self, request: &Request) -> Response {
+        let mut path_matched = false;
+        for route in &self.routes {
+            if let Some(params) = route.match_path(request.path()) {
+                if route.method != request.method {
+                    path_matched = true;
+                    continue;
+                }
+                if params.is_empty() {
+                    return (route.callback)(request);
+                }
+                let request = Request {
+                    params,
+                    ..request.clone()
+                };
+                return (route.callback)(&request);
+            }
+        }
+        if path_matched {
+            Response::method_not_allowed()
+        } else {
+            (self.fallback)(request)
+        }
+    }
+}
+
+impl Default for BasicRouter {
+    fn default() -> Self {
+        BasicRouter::new()
+    }
+}
@@ -0,0 +1,252 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use router::{BasicRouter, Method, Request, Response};
+
+fn body(response: &Response) -> &str {
+    response.text().unwrap()
+}
+
+#[test]
+fn routes_by_exact_path() {
+    let mut router = BasicRouter::new();
+    router.get("/", |_| Response::ok("home"));
+    router.get("/gcd", |_| Response::ok("gcd form"));
+
+    assert_eq!(body(&router.handle_request(&Request::get("/"))), "home");
+    assert_eq!(
+        body(&router.handle_request(&Request::get("/gcd"))),
+        "gcd form"
+    );
+    assert_eq!(
+        body(&router.handle_request(&Request::get("/gcd?x=1"))),
+        "gcd form"
+    );
+    assert_eq!(router.handle_request(&Request::get("/gcd/extra")).code, 404);
+}
+
+#[test]
+fn unmatched_requests_go_to_the_fallback() {
+    let mut router = BasicRouter::new();
+    assert_eq!(
+        router.handle_request(&Request::get("/")),
+        Response::not_found()
+    );
+
+    router.set_fallback(|req| Response::new(404, format!("no page at {}", req.path())));
+    let response = router.handle_request(&Request::get("/missing?q=1"));
+    assert_eq!(response.code, 404);
+    assert_eq!(body(&response), "no page at /missing");
+}
+
+#[test]
+fn path_parameters() {
+    let mut router = BasicRouter::new();
+    router.get("/users/:user/posts/:post", |req| {
+        Response::ok(format!(
+            "{} #{}",
+            req.param("user").unwrap(),
+            req.param("post").unwrap()
+        ))
+    });
+    router.get("/users/me", |_| Response::ok("unreachable"));
+
+    let response = router.handle_request(&Request::get("/users/ferris/posts/42"));
+    assert_eq!(body(&response), "ferris #42");
+    // Routes are tried in order, so the parameter route wins.
+    assert_eq!(
+        router
+            .handle_request(&Request::get("/users/me/posts/1"))
+            .code,
+        200
+    );
+    assert_eq!(
+        router
+            .handle_request(&Request::get("/users/ferris/posts"))
+            .code,
+        404
+    );
+}
+
+#[test]
+fn parameters_are_not_visible_outside_the_handler() {
+    let mut router = BasicRouter::new();
+    router.get("/item/:id", |req| {
+        assert_eq!(
+            req.params,
+            HashMap::from([("id".to_string(), "7".to_string())])
+        );
+        Response::ok("")
+    });
+    let request = Request::get("/item/7");
+    router.handle_request(&request);
+    assert!(request.params.is_empty());
+}
+
+#[test]
+fn methods_must_match() {
+    let mut router = BasicRouter::new();
+    router.get("/gcd", |_| Response::ok("form"));
+    router.post("/gcd", |req| {
+        Response::ok(format!("got {} bytes", req.body.len()))
+    });
+    router.add_route(Method::Delete, "/things/:id", |_| Response::new(204, ""));
+
+    assert_eq!(body(&router.handle_request(&Request::get("/gcd"))), "form");
+    assert_eq!(
+        body(&router.handle_request(&Request::post("/gcd", b"n=1"))),
+        "got 3 bytes"
+    );
+    assert_eq!(
+        router
+            .handle_request(&Request::new(Method::Delete, "/things/3"))
+            .code,
+        204
+    );
+    assert_eq!(
+        router.handle_request(&Request::new(Method::Put, "/gcd")),
+        Response::method_not_allowed()
+    );
+    assert_eq!(router.handle_request(&Request::get("/things/3")).code, 405);
+}
+
+#[test]
+fn handlers_capture_their_environment() {
+    let greeting = String::from("Bonjour");
+    let pages: HashMap<&str, &str> = HashMap::from([("a", "page A"), ("b", "page B")]);
+
+    let mut router = BasicRouter::new();
+    // `move` closures take ownership of what they capture, which the
+    // router's `'static` bound requires.
+    router.get("/hello/:name", move |req| {
+        Response::ok(format!("{}, {}!", greeting, req.param("name").unwrap()))
+    });
+    router.get("/pages/:page", move |req| {
+        match pages.get(req.param("page").unwrap()) {
+            Some(page) => Response::ok(*page),
+            None => Response::not_found(),
+        }
+    });
+
+    assert_eq!(
+        body(&router.handle_request(&Request::get("/hello/Ferris"))),
+        "Bonjour, Ferris!"
+    );
+    assert_eq!(
+        body(&router.handle_request(&Request::get("/pages/b"))),
+        "page B"
+    );
+    assert_eq!(router.handle_request(&Request::get("/pages/c")).code, 404);
+}
+
+#[test]
+fn fn_handlers_count_with_interior_mutability() {
+    let hits = Rc::new(Cell::new(0));
+    let mut router = BasicRouter::new();
+    let counter = hits.clone();
+    router.get("/", move |_| {
+        counter.set(counter.get() + 1);
+        Response::ok(counter.get().to_string())
+    });
+
+    for expected in 1..=3 {
+        assert_eq!(
+            body(&router.handle_request(&Request::get("/"))),
+            expected.to_string()
+        );
+    }
+    assert_eq!(hits.get(), 3);
+}
+
+#[test]
+fn fnmut_callbacks_can_count_directly() {
+    let mut router = BasicRouter::new();
+    router.get("/ok", |_| Response::ok("ok"));
+
+    let requests = [
+        Request::get("/ok"),
+        Request::get("/nope"),
+        Request::get("/ok"),
+    ];
+    let mut by_code: HashMap<u32, usize> = HashMap::new();
+    let mut total_bytes = 0;
+    router.handle_all(&requests, |_, response| {
+        *by_code.entry(response.code).or_insert(0) += 1;
+        total_bytes += response.body.len();
+    });
+
+    assert_eq!(by_code, HashMap::from([(200, 2), (404, 1)]));
+    assert_eq!(total_bytes, "ok".len() * 2 + "Not Found".len());
+}
+
+#[test]
+fn middleware_wraps_every_request() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let mut router = BasicRouter::new();
+    router.get("/", |_| Response::ok("home"));
+
+    let inner_log = log.clone();
+    router.wrap(move |req, next| {
+        inner_log.borrow_mut().push(format!("inner {}", req.url));
+        next(req).with_header("X-Inner", "yes")
+    });
+    let outer_log = log.clone();
+    router.wrap(move |req, next| {
+        outer_log.borrow_mut().push(format!("outer {}", req.url));
+        let response = next(req);
+        outer_log
+            .borrow_mut()
+            .push(format!("outer got {}", response.code));
+        response
+    });
+
+    let response = router.handle_request(&Request::get("/"));
+    assert_eq!(
+        response.headers.get("X-Inner").map(String::as_str),
+        Some("yes")
+    );
+    router.handle_request(&Request::get("/missing"));
+
+    assert_eq!(
+        *log.borrow(),
+        [
+            "outer /",
+            "inner /",
+            "outer got 200",
+            "outer /missing",
+            "inner /missing",
+            "outer got 404"
+        ]
+    );
+}
+
+#[test]
+fn middleware_can_short_circuit() {
+    let mut router = BasicRouter::new();
+    router.get("/admin", |_| Response::ok("secrets"));
+    router.wrap(|req, next| {
+        if req.headers.get("Authorization").map(String::as_str) == Some("let me in") {
+            next(req)
+        } else {
+            Response::new(401, "Unauthorized")
+        }
+    });
+
+    assert_eq!(router.handle_request(&Request::get("/admin")).code, 401);
+    let authorized = Request::get("/admin").with_header("Authorization", "let me in");
+    assert_eq!(body(&router.handle_request(&authorized)), "secrets");
+}
+
+#[test]
+fn methods_parse_and_display() {
+    assert_eq!("PATCH".parse(), Ok(Method::Patch));
+    assert!("FETCH".parse::<Method>().is_err());
+    assert_eq!(Method::Options.to_string(), "OPTIONS");
+}
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/fail-*.rs");
+}
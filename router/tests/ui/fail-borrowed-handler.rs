@@ -0,0 +1,9 @@
+use router::{BasicRouter, Response};
+
+fn main() {
+    let mut router = BasicRouter::new();
+    let greeting = String::from("hello");
+    // Without `move`, the closure borrows `greeting`, which won't live as
+    // long as the router requires.
+    router.get("/", |_| Response::ok(greeting.clone()));
+}
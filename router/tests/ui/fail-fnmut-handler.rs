@@ -0,0 +1,12 @@
+use router::{BasicRouter, Response};
+
+fn main() {
+    let mut router = BasicRouter::new();
+    let mut hits = 0;
+    // Mutating captured state makes this closure `FnMut`, but handlers must
+    // be `Fn`.
+    router.get("/", move |_| {
+        hits += 1;
+        Response::ok(hits.to_string())
+    });
+}
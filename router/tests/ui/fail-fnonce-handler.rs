@@ -0,0 +1,9 @@
+use router::{BasicRouter, Response};
+
+fn main() {
+    let mut router = BasicRouter::new();
+    let page = String::from("<h1>Welcome</h1>");
+    // Returning `page` moves it out of the closure, so the closure can only
+    // be called once. A router needs to call its handlers again and again.
+    router.get("/", move |_| Response::ok(page));
+}
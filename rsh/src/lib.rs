@@ -0,0 +1,9 @@
+// A minimal shell: a parser for command lines with quoting, variables,
+// pipes, and redirections, and an executor that runs them with
+// `std::process::Command`.
+
+mod parse;
+mod shell;
+
+pub use parse::{parse, ParseError, Pipeline, Redirect, SimpleCommand};
+pub use shell::{Outcome, Shell};
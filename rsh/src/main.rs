@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::{env, process};
+
+use rsh::{Outcome, Shell};
+
+fn print_usage() {
+    eprintln!("rsh - a minimal shell");
+    eprintln!("Usage: rsh                run commands from stdin");
+    eprintln!("       rsh SCRIPT         run commands from a file");
+    eprintln!("       rsh -c COMMAND     run one command line");
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut shell = Shell::new().unwrap_or_else(|e| {
+        eprintln!("rsh: {}", e);
+        process::exit(1);
+    });
+
+    let status = match args.as_slice() {
+        [] => {
+            let stdin = io::stdin();
+            let interactive = stdin.is_terminal();
+            run(&mut shell, stdin.lock(), interactive)
+        }
+        [flag, line] if flag == "-c" => match shell.run_line(line) {
+            Outcome::Exit(code) => code,
+            Outcome::Continue => shell.last_status(),
+        },
+        [flag] if flag == "-h" || flag == "--help" => {
+            print_usage();
+            0
+        }
+        [path] => match File::open(path) {
+            Ok(file) => run(&mut shell, BufReader::new(file), false),
+            Err(e) => {
+                eprintln!("rsh: {}: {}", path, e);
+                127
+            }
+        },
+        _ => {
+            print_usage();
+            2
+        }
+    };
+    process::exit(status);
+}
+
+/// Run every line of `input`, returning the status to exit with.
+fn run<R: BufRead>(shell: &mut Shell, input: R, interactive: bool) -> i32 {
+    let mut lines = input.lines();
+    loop {
+        if interactive {
+            prompt(shell);
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => {
+                eprintln!("rsh: {}", e);
+                return 1;
+            }
+            None => break,
+        };
+        if let Outcome::Exit(code) = shell.run_line(&line) {
+            return code;
+        }
+    }
+    if interactive {
+        eprintln!();
+    }
+    shell.last_status()
+}
+
+/// Show the directory, and the last status if it was a failure.
+fn prompt(shell: &Shell) {
+    let dir = shell.cwd().file_name().map_or_else(
+        || shell.cwd().display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    let status = match shell.last_status() {
+        0 => String::new(),
+        code => format!("[{}] ", code),
+    };
+    eprint!("{}{} $ ", status, dir);
+    let _ = io::stderr().flush();
+}
@@ -0,0 +1,256 @@
+// Parsing a command line into a pipeline.
+//
+// The grammar is a small subset of the POSIX shell's:
+//
+//     pipeline  = command { "|" command }
+//     command   = { word | redirect }      (at least one word)
+//     redirect  = ("<" | ">" | ">>") word
+//
+// Words can be quoted: inside '...' every character is literal; inside
+// "..." a backslash escapes `"`, `\`, and `$`, and variables are expanded;
+// outside quotes a backslash escapes any character. `$NAME`, `${NAME}`,
+// and `$?` expand to a variable's value, and `#` at the start of a word
+// begins a comment. Unlike a real shell, expansions are never split into
+// several words.
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pipeline {
+    pub commands: Vec<SimpleCommand>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimpleCommand {
+    /// The program name followed by its arguments. Never empty.
+    pub argv: Vec<String>,
+    pub stdin: Option<String>,
+    pub stdout: Option<Redirect>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Redirect {
+    pub path: String,
+    /// `>>` rather than `>`.
+    pub append: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A quote with no matching close quote.
+    UnterminatedQuote(char),
+    /// A backslash at the very end of the line.
+    TrailingBackslash,
+    /// `${` without a closing brace, or with an invalid name inside.
+    BadSubstitution,
+    /// A `|` with no command on one side of it.
+    EmptyCommand,
+    /// A redirection operator not followed by a file name.
+    MissingRedirectTarget(&'static str),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnterminatedQuote(q) => write!(f, "unterminated {} quote", q),
+            ParseError::TrailingBackslash => write!(f, "backslash at end of line"),
+            ParseError::BadSubstitution => write!(f, "bad substitution"),
+            ParseError::EmptyCommand => write!(f, "syntax error: empty command in pipeline"),
+            ParseError::MissingRedirectTarget(op) => {
+                write!(f, "syntax error: expected a file name after '{}'", op)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Pipe,
+    Less,
+    Greater,
+    DoubleGreater,
+}
+
+/// Parse `line`, expanding variables with `lookup`. Returns `Ok(None)` for
+/// a line with nothing to run: blank, or only a comment.
+pub fn parse<F>(line: &str, lookup: F) -> Result<Option<Pipeline>, ParseError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let tokens = tokenize(line, &lookup)?;
+    if tokens.is_empty() {
+        return Ok(None);
+    }
+
+    let mut commands = Vec::new();
+    let mut current = SimpleCommand::default();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word(word) => current.argv.push(word),
+            Token::Pipe => {
+                if current.argv.is_empty() {
+                    return Err(ParseError::EmptyCommand);
+                }
+                commands.push(std::mem::take(&mut current));
+            }
+            Token::Less => current.stdin = Some(redirect_target(tokens.next(), "<")?),
+            Token::Greater | Token::DoubleGreater => {
+                let append = token == Token::DoubleGreater;
+                let op = if append { ">>" } else { ">" };
+                current.stdout = Some(Redirect {
+                    path: redirect_target(tokens.next(), op)?,
+                    append,
+                });
+            }
+        }
+    }
+    if current.argv.is_empty() {
+        return Err(ParseError::EmptyCommand);
+    }
+    commands.push(current);
+    Ok(Some(Pipeline { commands }))
+}
+
+fn redirect_target(token: Option<Token>, op: &'static str) -> Result<String, ParseError> {
+    match token {
+        Some(Token::Word(path)) => Ok(path),
+        _ => Err(ParseError::MissingRedirectTarget(op)),
+    }
+}
+
+fn tokenize<F>(line: &str, lookup: &F) -> Result<Vec<Token>, ParseError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    // The word being built, and whether anything has started it. A word
+    // can be empty but still present, as with `''`.
+    let mut word = String::new();
+    let mut in_word = false;
+
+    fn finish(tokens: &mut Vec<Token>, word: &mut String, in_word: &mut bool) {
+        if *in_word {
+            tokens.push(Token::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => finish(&mut tokens, &mut word, &mut in_word),
+            '#' if !in_word => break,
+            '|' | '<' | '>' => {
+                finish(&mut tokens, &mut word, &mut in_word);
+                tokens.push(match c {
+                    '|' => Token::Pipe,
+                    '<' => Token::Less,
+                    _ if chars.next_if_eq(&'>').is_some() => Token::DoubleGreater,
+                    _ => Token::Greater,
+                });
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(ParseError::UnterminatedQuote('\'')),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(ParseError::UnterminatedQuote('"')),
+                        },
+                        Some('$') => expand(&mut chars, lookup, &mut word)?,
+                        Some(c) => word.push(c),
+                        None => return Err(ParseError::UnterminatedQuote('"')),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.push(chars.next().ok_or(ParseError::TrailingBackslash)?);
+            }
+            '$' => {
+                // An unquoted expansion that comes out empty doesn't make
+                // a word on its own: `echo $UNSET x` has one argument.
+                let before = word.len();
+                expand(&mut chars, lookup, &mut word)?;
+                in_word |= word.len() > before;
+            }
+            '~' if !in_word && matches!(chars.peek(), None | Some('/' | ' ' | '\t')) => {
+                in_word = true;
+                word.push_str(&lookup("HOME").unwrap_or_else(|| "~".to_string()));
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    finish(&mut tokens, &mut word, &mut in_word);
+    Ok(tokens)
+}
+
+/// Expand the variable reference following a `$`, appending its value to
+/// `word`. A `$` not followed by a name is just a dollar sign.
+fn expand<I, F>(
+    chars: &mut std::iter::Peekable<I>,
+    lookup: &F,
+    word: &mut String,
+) -> Result<(), ParseError>
+where
+    I: Iterator<Item = char>,
+    F: Fn(&str) -> Option<String>,
+{
+    let is_name_char = |c: &char| c.is_ascii_alphanumeric() || *c == '_';
+    let name = match chars.peek() {
+        Some('?') => {
+            chars.next();
+            "?".to_string()
+        }
+        Some('{') => {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) if is_name_char(&c) => name.push(c),
+                    _ => return Err(ParseError::BadSubstitution),
+                }
+            }
+            if name.is_empty() {
+                return Err(ParseError::BadSubstitution);
+            }
+            name
+        }
+        Some(c) if is_name_char(c) => {
+            let mut name = String::new();
+            while let Some(c) = chars.next_if(is_name_char) {
+                name.push(c);
+            }
+            name
+        }
+        _ => {
+            word.push('$');
+            return Ok(());
+        }
+    };
+    word.push_str(&lookup(&name).unwrap_or_default());
+    Ok(())
+}
@@ -0,0 +1,321 @@
+// Running parsed pipelines.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, ExitStatus, Stdio};
+
+use crate::parse::{parse, Pipeline, SimpleCommand};
+
+/// What the caller should do after a line has run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    /// The `exit` builtin ran: stop, and exit with this status.
+    Exit(i32),
+}
+
+/// The shell's state: everything a command line can change that outlives
+/// it.
+pub struct Shell {
+    cwd: PathBuf,
+    /// The environment passed to every command. Starts as a copy of the
+    /// shell's own; `export` adds to it.
+    env: BTreeMap<String, String>,
+    last_status: i32,
+}
+
+/// The status of a command that couldn't be found, as in POSIX shells.
+const NOT_FOUND: i32 = 127;
+/// The status of a command that was found but couldn't be run.
+const NOT_EXECUTABLE: i32 = 126;
+
+impl Shell {
+    /// A shell starting in the current directory, with the current
+    /// environment.
+    pub fn new() -> io::Result<Shell> {
+        Ok(Shell {
+            cwd: std::env::current_dir()?,
+            env: std::env::vars().collect(),
+            last_status: 0,
+        })
+    }
+
+    pub fn cwd(&self) -> &Path {
+        &self.cwd
+    }
+
+    /// The exit status of the last command line run.
+    pub fn last_status(&self) -> i32 {
+        self.last_status
+    }
+
+    /// The value of a variable, or of `$?`.
+    pub fn var(&self, name: &str) -> Option<String> {
+        match name {
+            "?" => Some(self.last_status.to_string()),
+            _ => self.env.get(name).cloned(),
+        }
+    }
+
+    /// Parse and run one command line. Errors are reported on stderr, and
+    /// set the exit status, as in any shell.
+    pub fn run_line(&mut self, line: &str) -> Outcome {
+        match parse(line, |name| self.var(name)) {
+            Ok(None) => Outcome::Continue,
+            Ok(Some(pipeline)) => self.run(&pipeline),
+            Err(e) => {
+                eprintln!("rsh: {}", e);
+                self.last_status = 2;
+                Outcome::Continue
+            }
+        }
+    }
+
+    /// Run a pipeline, setting `last_status`.
+    pub fn run(&mut self, pipeline: &Pipeline) -> Outcome {
+        if let [command] = &pipeline.commands[..] {
+            if let Some(outcome) = self.builtin(command) {
+                return outcome;
+            }
+        }
+        if let Some(command) = pipeline.commands.iter().find(|c| is_builtin(&c.argv[0])) {
+            eprintln!(
+                "rsh: {}: builtins can't be part of a pipeline",
+                command.argv[0]
+            );
+            self.last_status = 1;
+            return Outcome::Continue;
+        }
+
+        // Anything the shell itself printed should come out before the
+        // commands' output.
+        let _ = io::stdout().flush();
+        self.last_status = self.spawn_pipeline(pipeline);
+        Outcome::Continue
+    }
+
+    /// Start every command in the pipeline, each reading the previous one's
+    /// output, then wait for them all. The pipeline's status is the last
+    /// command's.
+    fn spawn_pipeline(&self, pipeline: &Pipeline) -> i32 {
+        let mut children: Vec<Option<Child>> = Vec::new();
+        let mut statuses = Vec::new();
+        let mut previous: Option<ChildStdout> = None;
+        let last = pipeline.commands.len() - 1;
+
+        for (i, command) in pipeline.commands.iter().enumerate() {
+            let stdin = match &command.stdin {
+                Some(path) => match File::open(self.cwd.join(path)) {
+                    Ok(file) => Stdio::from(file),
+                    Err(e) => {
+                        eprintln!("rsh: {}: {}", path, e);
+                        children.push(None);
+                        statuses.push(Some(1));
+                        previous = None;
+                        continue;
+                    }
+                },
+                None => match previous.take() {
+                    Some(pipe) => Stdio::from(pipe),
+                    // The first command reads the shell's input. Later
+                    // ones whose predecessor failed to start, or sent its
+                    // output to a file, read nothing.
+                    None if i == 0 => Stdio::inherit(),
+                    None => Stdio::null(),
+                },
+            };
+            let stdout = match &command.stdout {
+                Some(redirect) => {
+                    let file = OpenOptions::new()
+                        .write(true)
+                        .create(true)
+                        .append(redirect.append)
+                        .truncate(!redirect.append)
+                        .open(self.cwd.join(&redirect.path));
+                    match file {
+                        Ok(file) => Stdio::from(file),
+                        Err(e) => {
+                            eprintln!("rsh: {}: {}", redirect.path, e);
+                            children.push(None);
+                            statuses.push(Some(1));
+                            continue;
+                        }
+                    }
+                }
+                None if i == last => Stdio::inherit(),
+                None => Stdio::piped(),
+            };
+
+            match self.command(command).stdin(stdin).stdout(stdout).spawn() {
+                Ok(mut child) => {
+                    previous = child.stdout.take();
+                    children.push(Some(child));
+                    statuses.push(None);
+                }
+                Err(e) => {
+                    let program = &command.argv[0];
+                    let status = if e.kind() == io::ErrorKind::NotFound {
+                        eprintln!("rsh: command not found: {}", program);
+                        NOT_FOUND
+                    } else {
+                        eprintln!("rsh: {}: {}", program, e);
+                        NOT_EXECUTABLE
+                    };
+                    children.push(None);
+                    statuses.push(Some(status));
+                }
+            }
+        }
+        // Don't hold the last pipe open: the command reading it would
+        // never see end of file.
+        drop(previous);
+
+        let mut status = 0;
+        for (child, early) in children.into_iter().zip(statuses) {
+            status = match (child, early) {
+                (Some(mut child), _) => match child.wait() {
+                    Ok(exit) => self.status_code(exit),
+                    Err(e) => {
+                        eprintln!("rsh: wait failed: {}", e);
+                        1
+                    }
+                },
+                (None, Some(early)) => early,
+                (None, None) => unreachable!("every command has a child or a status"),
+            };
+        }
+        status
+    }
+
+    fn command(&self, command: &SimpleCommand) -> Command {
+        let mut cmd = Command::new(&command.argv[0]);
+        cmd.args(&command.argv[1..])
+            .current_dir(&self.cwd)
+            .env_clear()
+            .envs(&self.env);
+        cmd
+    }
+
+    /// Convert an exit status to a number, reporting death by signal.
+    fn status_code(&self, status: ExitStatus) -> i32 {
+        if let Some(code) = status.code() {
+            return code;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = status.signal() {
+                eprintln!("rsh: terminated by signal {}", signal);
+                return 128 + signal;
+            }
+        }
+        1
+    }
+
+    /// Run `command` if it's a builtin. Builtins change the shell's own
+    /// state, so they can't be separate processes.
+    fn builtin(&mut self, command: &SimpleCommand) -> Option<Outcome> {
+        let args = &command.argv[1..];
+        let status = match command.argv[0].as_str() {
+            "cd" => self.cd(args),
+            "exit" => return Some(self.exit(args)),
+            "export" => self.export(args),
+            _ => return None,
+        };
+        self.last_status = status;
+        Some(Outcome::Continue)
+    }
+
+    fn cd(&mut self, args: &[String]) -> i32 {
+        let target = match args {
+            [] => match self.env.get("HOME") {
+                Some(home) => PathBuf::from(home),
+                None => {
+                    eprintln!("rsh: cd: HOME not set");
+                    return 1;
+                }
+            },
+            [dir] => self.cwd.join(dir),
+            _ => {
+                eprintln!("rsh: cd: too many arguments");
+                return 1;
+            }
+        };
+        match target.canonicalize() {
+            Ok(dir) if dir.is_dir() => {
+                self.env
+                    .insert("PWD".to_string(), dir.display().to_string());
+                self.cwd = dir;
+                0
+            }
+            Ok(_) => {
+                eprintln!("rsh: cd: {}: Not a directory", target.display());
+                1
+            }
+            Err(e) => {
+                eprintln!("rsh: cd: {}: {}", target.display(), e);
+                1
+            }
+        }
+    }
+
+    fn exit(&mut self, args: &[String]) -> Outcome {
+        match args {
+            [] => Outcome::Exit(self.last_status),
+            [code] => match code.parse() {
+                Ok(code) => Outcome::Exit(code),
+                Err(_) => {
+                    eprintln!("rsh: exit: {}: numeric argument required", code);
+                    Outcome::Exit(2)
+                }
+            },
+            _ => {
+                eprintln!("rsh: exit: too many arguments");
+                self.last_status = 1;
+                Outcome::Continue
+            }
+        }
+    }
+
+    fn export(&mut self, args: &[String]) -> i32 {
+        if args.is_empty() {
+            for (name, value) in &self.env {
+                println!("export {}={:?}", name, value);
+            }
+            return 0;
+        }
+        let mut status = 0;
+        for arg in args {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (arg.as_str(), None),
+            };
+            if !is_valid_name(name) {
+                eprintln!("rsh: export: {:?}: not a valid identifier", name);
+                status = 1;
+                continue;
+            }
+            // `export NAME` with no value exports an existing variable.
+            // Every variable here is already exported, so it only creates
+            // the variable, empty, if it's missing.
+            let value = value.map(str::to_string);
+            let entry = self.env.entry(name.to_string()).or_default();
+            if let Some(value) = value {
+                *entry = value;
+            }
+        }
+        status
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    matches!(name, "cd" | "exit" | "export")
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,139 @@
+use rsh::{parse, ParseError, Pipeline, Redirect, SimpleCommand};
+
+fn vars(name: &str) -> Option<String> {
+    match name {
+        "HOME" => Some("/home/ferris".to_string()),
+        "GREETING" => Some("hello world".to_string()),
+        "?" => Some("3".to_string()),
+        _ => None,
+    }
+}
+
+fn words(line: &str) -> Vec<String> {
+    let pipeline = parse(line, vars).unwrap().unwrap();
+    assert_eq!(pipeline.commands.len(), 1);
+    pipeline.commands.into_iter().next().unwrap().argv
+}
+
+fn command(argv: &[&str]) -> SimpleCommand {
+    SimpleCommand {
+        argv: argv.iter().map(|s| s.to_string()).collect(),
+        ..SimpleCommand::default()
+    }
+}
+
+#[test]
+fn blank_and_comments() {
+    assert_eq!(parse("", vars), Ok(None));
+    assert_eq!(parse("   \t ", vars), Ok(None));
+    assert_eq!(parse("# nothing to see", vars), Ok(None));
+    assert_eq!(words("echo a#b # comment"), ["echo", "a#b"]);
+}
+
+#[test]
+fn quoting() {
+    assert_eq!(words("echo  a   b"), ["echo", "a", "b"]);
+    assert_eq!(words("echo 'a   b' \"c  d\""), ["echo", "a   b", "c  d"]);
+    assert_eq!(words("echo 'it''s' x\"y\"z"), ["echo", "its", "xyz"]);
+    assert_eq!(
+        words(r#"echo "say \"hi\" \\ \n""#),
+        ["echo", r#"say "hi" \ \n"#]
+    );
+    assert_eq!(words(r"echo a\ b \| \$HOME"), ["echo", "a b", "|", "$HOME"]);
+    assert_eq!(words("echo '' \"\""), ["echo", "", ""]);
+    assert_eq!(words("echo '|' '>' \"<\""), ["echo", "|", ">", "<"]);
+}
+
+#[test]
+fn variables() {
+    assert_eq!(words("echo $GREETING"), ["echo", "hello world"]);
+    assert_eq!(
+        words("echo \"$GREETING, ${HOME}!\""),
+        ["echo", "hello world, /home/ferris!"]
+    );
+    assert_eq!(words("echo '$HOME'"), ["echo", "$HOME"]);
+    assert_eq!(words("echo $?"), ["echo", "3"]);
+    assert_eq!(words("echo ${HOME}x $HOMEx"), ["echo", "/home/ferrisx"]);
+    assert_eq!(words("echo $UNSET end"), ["echo", "end"]);
+    assert_eq!(words("echo \"$UNSET\" end"), ["echo", "", "end"]);
+    assert_eq!(words("echo $ 5$ a$-b"), ["echo", "$", "5$", "a$-b"]);
+    assert_eq!(
+        words("ls ~ ~/src a~"),
+        ["ls", "/home/ferris", "/home/ferris/src", "a~"]
+    );
+}
+
+#[test]
+fn pipes_and_redirections() {
+    let pipeline = parse("sort < in.txt | uniq -c >> out.txt", vars)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        pipeline,
+        Pipeline {
+            commands: vec![
+                SimpleCommand {
+                    stdin: Some("in.txt".to_string()),
+                    ..command(&["sort"])
+                },
+                SimpleCommand {
+                    stdout: Some(Redirect {
+                        path: "out.txt".to_string(),
+                        append: true,
+                    }),
+                    ..command(&["uniq", "-c"])
+                },
+            ]
+        }
+    );
+
+    // Operators don't need spaces around them.
+    let pipeline = parse("echo hi>out|cat", vars).unwrap().unwrap();
+    assert_eq!(pipeline.commands[0].argv, ["echo", "hi"]);
+    assert_eq!(
+        pipeline.commands[0].stdout,
+        Some(Redirect {
+            path: "out".to_string(),
+            append: false
+        })
+    );
+    assert_eq!(pipeline.commands[1], command(&["cat"]));
+
+    // Redirections can come anywhere in a command.
+    let pipeline = parse("> out echo hi", vars).unwrap().unwrap();
+    assert_eq!(pipeline.commands[0].argv, ["echo", "hi"]);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        parse("echo 'oops", vars),
+        Err(ParseError::UnterminatedQuote('\''))
+    );
+    assert_eq!(
+        parse("echo \"oops", vars),
+        Err(ParseError::UnterminatedQuote('"'))
+    );
+    assert_eq!(
+        parse("echo oops\\", vars),
+        Err(ParseError::TrailingBackslash)
+    );
+    assert_eq!(parse("echo ${HOME", vars), Err(ParseError::BadSubstitution));
+    assert_eq!(parse("echo ${}", vars), Err(ParseError::BadSubstitution));
+    assert_eq!(parse("| wc", vars), Err(ParseError::EmptyCommand));
+    assert_eq!(parse("ls |", vars), Err(ParseError::EmptyCommand));
+    assert_eq!(parse("ls | | wc", vars), Err(ParseError::EmptyCommand));
+    assert_eq!(parse("> out", vars), Err(ParseError::EmptyCommand));
+    assert_eq!(
+        parse("cat <", vars),
+        Err(ParseError::MissingRedirectTarget("<"))
+    );
+    assert_eq!(
+        parse("ls > | wc", vars),
+        Err(ParseError::MissingRedirectTarget(">"))
+    );
+    assert_eq!(
+        ParseError::MissingRedirectTarget(">>").to_string(),
+        "syntax error: expected a file name after '>>'"
+    );
+}
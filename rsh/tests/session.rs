@@ -0,0 +1,222 @@
+// Scripted sessions: feed the shell a script on stdin and check what it
+// prints and how it exits.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn session(dir: &Path, script: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rsh"))
+        .current_dir(dir)
+        .env("HOME", dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn runs_commands() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = session(
+        dir.path(),
+        "echo hello   world\necho 'a  b' \"$HOME\" | tr a-z A-Z\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "hello world\nA  B {}\n",
+            dir.path().display().to_string().to_uppercase()
+        )
+    );
+}
+
+#[test]
+fn pipelines() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = "printf 'b\\na\\nc\\na\\n' | sort | uniq -c | sort -rn | head -n 1\n";
+    let output = session(dir.path(), script);
+    assert_eq!(
+        stdout(&output).split_whitespace().collect::<Vec<_>>(),
+        ["2", "a"]
+    );
+
+    // A long pipeline, to make sure no pipe is left open by the shell.
+    let output = session(dir.path(), "seq 1 1000 | cat | cat | cat | cat | wc -l\n");
+    assert_eq!(stdout(&output).trim(), "1000");
+}
+
+#[test]
+fn redirections() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = "\
+echo first > out.txt
+echo second >> out.txt
+sort -r < out.txt > sorted.txt
+cat < sorted.txt | wc -l > count.txt
+echo overwritten > out.txt
+";
+    let output = session(dir.path(), script);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "overwritten\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("sorted.txt")).unwrap(),
+        "second\nfirst\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("count.txt"))
+            .unwrap()
+            .trim(),
+        "2"
+    );
+}
+
+#[test]
+fn cd_and_export() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub/file.txt"), "in sub\n").unwrap();
+    let script = "\
+cd sub
+cat file.txt
+pwd
+export GREETING='hi there' EMPTY
+sh -c 'echo \"$GREETING|$EMPTY|\"'
+cd
+pwd
+cd no-such-dir
+echo status $?
+";
+    let output = session(dir.path(), script);
+    let home = dir.path().canonicalize().unwrap();
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "in sub\n{}/sub\nhi there||\n{}\nstatus 1\n",
+            home.display(),
+            home.display()
+        )
+    );
+    assert!(stderr(&output).contains("rsh: cd: "), "{}", stderr(&output));
+}
+
+#[test]
+fn exit_statuses() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = "\
+false
+echo $?
+sh -c 'exit 7'
+echo $?
+true | false
+echo $?
+false | true
+echo $?
+no-such-command --flag
+echo $?
+echo 'unterminated
+echo $?
+sh -c 'kill -9 $$'
+echo $?
+";
+    let output = session(dir.path(), script);
+    assert_eq!(stdout(&output), "1\n7\n1\n0\n127\n2\n137\n");
+    let stderr = stderr(&output);
+    assert!(
+        stderr.contains("rsh: command not found: no-such-command"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("rsh: unterminated ' quote"), "{}", stderr);
+    assert!(stderr.contains("rsh: terminated by signal 9"), "{}", stderr);
+}
+
+#[test]
+fn exit_builtin() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = session(dir.path(), "echo before\nexit 3\necho after\n");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout(&output), "before\n");
+
+    // At end of input, or on a bare `exit`, the shell exits with the last
+    // command's status.
+    assert_eq!(
+        session(dir.path(), "sh -c 'exit 4'\n").status.code(),
+        Some(4)
+    );
+    assert_eq!(
+        session(dir.path(), "sh -c 'exit 5'\nexit\n").status.code(),
+        Some(5)
+    );
+    assert_eq!(session(dir.path(), "exit nope\n").status.code(), Some(2));
+}
+
+#[test]
+fn builtins_in_pipelines_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = session(dir.path(), "cd / | cat\necho $?\npwd\n");
+    assert_eq!(
+        stdout(&output),
+        format!("1\n{}\n", dir.path().canonicalize().unwrap().display())
+    );
+    assert!(stderr(&output).contains("builtins can't be part of a pipeline"));
+}
+
+#[test]
+fn missing_redirect_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = session(dir.path(), "cat < missing.txt | wc -l\necho $?\n");
+    assert_eq!(
+        stdout(&output).split_whitespace().collect::<Vec<_>>(),
+        ["0", "0"]
+    );
+    assert!(stderr(&output).contains("rsh: missing.txt: "));
+}
+
+#[test]
+fn command_line_modes() {
+    let dir = tempfile::tempdir().unwrap();
+    let rsh = || {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_rsh"));
+        command.current_dir(dir.path());
+        command
+    };
+
+    let output = rsh().args(["-c", "echo one | tr o 0"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "0ne\n");
+    let output = rsh().args(["-c", "exit 9"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(9));
+
+    fs::write(
+        dir.path().join("script.rsh"),
+        "# a script\necho from script\nexit 6\n",
+    )
+    .unwrap();
+    let output = rsh().arg("script.rsh").output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "from script\n");
+    assert_eq!(output.status.code(), Some(6));
+}
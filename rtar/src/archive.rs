@@ -0,0 +1,123 @@
+use std::io::{self, Read};
+
+use crate::error::Result;
+use crate::header::{Header, BLOCK_SIZE};
+
+/// Reads an archive from `R`, one entry at a time, without seeking: it
+/// works as well on a pipe as on a file.
+pub struct Archive<R: Read> {
+    inner: R,
+    /// How far into the archive we've read.
+    offset: u64,
+    /// Bytes of the current entry's data not yet read.
+    remaining: u64,
+    /// Padding after the current entry's data.
+    padding: u64,
+    done: bool,
+}
+
+/// One entry in an archive: its header, and a reader for its data.
+///
+/// An `Entry` borrows its `Archive`, so only one can exist at a time.
+/// Whatever data isn't read is skipped when the next entry is requested.
+pub struct Entry<'a, R: Read> {
+    header: Header,
+    archive: &'a mut Archive<R>,
+}
+
+impl<R: Read> Archive<R> {
+    pub fn new(inner: R) -> Archive<R> {
+        Archive {
+            inner,
+            offset: 0,
+            remaining: 0,
+            padding: 0,
+            done: false,
+        }
+    }
+
+    /// The next entry, or `None` at the end of the archive.
+    pub fn next_entry(&mut self) -> Result<Option<Entry<'_, R>>> {
+        if self.done {
+            return Ok(None);
+        }
+        self.skip(self.remaining + self.padding)?;
+        self.remaining = 0;
+        self.padding = 0;
+
+        let mut block = [0; BLOCK_SIZE];
+        let header_offset = self.offset;
+        if !self.read_block(&mut block)? {
+            // Ending without the two zero blocks is common enough to
+            // accept.
+            self.done = true;
+            return Ok(None);
+        }
+        let Some(header) = Header::decode(&block, header_offset)? else {
+            // The end marker is two zero blocks, but many readers stop at
+            // one; so do we, without requiring the second.
+            self.done = true;
+            return Ok(None);
+        };
+        self.remaining = header.size;
+        self.padding = (BLOCK_SIZE as u64 - header.size % BLOCK_SIZE as u64) % BLOCK_SIZE as u64;
+        Ok(Some(Entry {
+            header,
+            archive: self,
+        }))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read a whole block. Returns false at a clean end of input.
+    fn read_block(&mut self, block: &mut [u8; BLOCK_SIZE]) -> io::Result<bool> {
+        let mut filled = 0;
+        while filled < BLOCK_SIZE {
+            match self.inner.read(&mut block[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.offset += BLOCK_SIZE as u64;
+        Ok(true)
+    }
+
+    fn skip(&mut self, n: u64) -> io::Result<()> {
+        let skipped = io::copy(&mut (&mut self.inner).take(n), &mut io::sink())?;
+        self.offset += skipped;
+        if skipped < n {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Entry<'_, R> {
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+}
+
+impl<R: Read> Read for Entry<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let archive = &mut *self.archive;
+        let max = buf
+            .len()
+            .min(archive.remaining.try_into().unwrap_or(usize::MAX));
+        if max == 0 {
+            return Ok(0);
+        }
+        let n = archive.inner.read(&mut buf[..max])?;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        archive.remaining -= n as u64;
+        archive.offset += n as u64;
+        Ok(n)
+    }
+}
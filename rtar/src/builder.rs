@@ -0,0 +1,142 @@
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::error::{Error, Result};
+use crate::header::{EntryKind, Header, BLOCK_SIZE};
+
+/// Writes an archive to `W`, one entry at a time.
+pub struct Builder<W: Write> {
+    out: W,
+}
+
+impl<W: Write> Builder<W> {
+    pub fn new(out: W) -> Builder<W> {
+        Builder { out }
+    }
+
+    /// Append an entry whose data comes from `data`, which must supply
+    /// exactly `header.size` bytes. No checks are made on the header's
+    /// path: it's written exactly as given.
+    pub fn append<R: Read>(&mut self, header: &Header, mut data: R) -> Result<()> {
+        self.out.write_all(&header.encode()?)?;
+        let copied = io::copy(&mut (&mut data).take(header.size), &mut self.out)?;
+        if copied != header.size {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "{}: expected {} bytes, got {}",
+                    header.path, header.size, copied
+                ),
+            )
+            .into());
+        }
+        self.pad(header.size)
+    }
+
+    /// Append the file, directory, or symbolic link at `path`, under the
+    /// name `name`. Directories are added without their contents; see
+    /// `append_dir_all`.
+    pub fn append_path<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let metadata = fs::symlink_metadata(path)?;
+        let mut header = header_from_metadata(name, &metadata);
+        if metadata.is_file() {
+            self.append(&header, File::open(path)?)
+        } else {
+            if metadata.file_type().is_symlink() {
+                let target = fs::read_link(path)?;
+                header.link_name = target
+                    .to_str()
+                    .ok_or(Error::NonUtf8Path(target.clone()))?
+                    .to_string();
+            }
+            self.append(&header, io::empty())
+        }
+    }
+
+    /// Append the directory `dir` and everything under it, with `dir`
+    /// itself named `name`. Entries are added in sorted order, parents
+    /// before children, so the same tree always makes the same archive.
+    pub fn append_dir_all<P: AsRef<Path>>(&mut self, name: &str, dir: P) -> Result<()> {
+        let dir = dir.as_ref();
+        self.append_path(name, dir)?;
+        if !fs::symlink_metadata(dir)?.is_dir() {
+            return Ok(());
+        }
+        let mut children = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+        children.sort_by_key(|entry| entry.file_name());
+        for child in children {
+            let file_name = child.file_name();
+            let file_name = file_name
+                .to_str()
+                .ok_or_else(|| Error::NonUtf8Path(child.path()))?;
+            let child_name = if name.is_empty() || name == "." {
+                file_name.to_string()
+            } else {
+                format!("{}/{}", name.trim_end_matches('/'), file_name)
+            };
+            self.append_dir_all(&child_name, child.path())?;
+        }
+        Ok(())
+    }
+
+    /// Write the end-of-archive marker and return the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.out.write_all(&[0; 2 * BLOCK_SIZE])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    /// Pad data of length `size` out to a whole number of blocks.
+    fn pad(&mut self, size: u64) -> Result<()> {
+        let partial = (size % BLOCK_SIZE as u64) as usize;
+        if partial != 0 {
+            self.out.write_all(&[0; BLOCK_SIZE][partial..])?;
+        }
+        Ok(())
+    }
+}
+
+fn header_from_metadata(name: &str, metadata: &Metadata) -> Header {
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_symlink() {
+        EntryKind::Symlink
+    } else if file_type.is_dir() {
+        EntryKind::Directory
+    } else {
+        EntryKind::File
+    };
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let mut header = Header {
+        path: name.to_string(),
+        kind,
+        mode: if kind == EntryKind::Directory {
+            0o755
+        } else {
+            0o644
+        },
+        uid: 0,
+        gid: 0,
+        size: if kind == EntryKind::File {
+            metadata.len()
+        } else {
+            0
+        },
+        mtime,
+        link_name: String::new(),
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        header.mode = metadata.mode() & 0o7777;
+        header.uid = u64::from(metadata.uid());
+        header.gid = u64::from(metadata.gid());
+    }
+    header
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A header block that doesn't parse, at this offset in the archive.
+    InvalidHeader {
+        offset: u64,
+        reason: &'static str,
+    },
+    /// A path too long to fit in a ustar header.
+    NameTooLong(String),
+    /// A path that isn't valid UTF-8. Archive paths are stored as strings.
+    NonUtf8Path(PathBuf),
+    /// An entry that would be extracted outside the destination directory:
+    /// an absolute path, a path through `..`, a path through a symbolic
+    /// link, or a link pointing outside.
+    UnsafePath(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::InvalidHeader { offset, reason } => {
+                write!(f, "invalid header at offset {}: {}", offset, reason)
+            }
+            Error::NameTooLong(name) => write!(f, "name too long for ustar: {}", name),
+            Error::NonUtf8Path(path) => write!(f, "path is not valid UTF-8: {}", path.display()),
+            Error::UnsafePath(path) => write!(f, "refusing to extract unsafe path: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
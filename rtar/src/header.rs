@@ -0,0 +1,281 @@
+// The ustar header block.
+//
+//     offset  size  field
+//          0   100  name
+//        100     8  mode      (octal)
+//        108     8  uid       (octal)
+//        116     8  gid       (octal)
+//        124    12  size      (octal, or GNU base-256 for huge files)
+//        136    12  mtime     (octal)
+//        148     8  checksum  (octal)
+//        156     1  typeflag
+//        157   100  linkname
+//        257     6  magic     "ustar\0"
+//        263     2  version   "00"
+//        265    32  uname
+//        297    32  gname
+//        329     8  devmajor
+//        337     8  devminor
+//        345   155  prefix
+//
+// Numeric fields are ASCII octal, NUL-terminated. Paths longer than 100
+// bytes are split at a `/` between `prefix` and `name`.
+
+use crate::error::{Error, Result};
+
+pub const BLOCK_SIZE: usize = 512;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+    HardLink,
+    /// Device files, FIFOs, and vendor extensions, which this crate lists
+    /// but doesn't extract.
+    Other(u8),
+}
+
+impl EntryKind {
+    fn typeflag(self) -> u8 {
+        match self {
+            EntryKind::File => b'0',
+            EntryKind::HardLink => b'1',
+            EntryKind::Symlink => b'2',
+            EntryKind::Directory => b'5',
+            EntryKind::Other(flag) => flag,
+        }
+    }
+
+    fn from_typeflag(flag: u8) -> EntryKind {
+        match flag {
+            // NUL is how pre-POSIX tars marked regular files.
+            b'0' | b'\0' | b'7' => EntryKind::File,
+            b'1' => EntryKind::HardLink,
+            b'2' => EntryKind::Symlink,
+            b'5' => EntryKind::Directory,
+            other => EntryKind::Other(other),
+        }
+    }
+}
+
+/// The metadata describing one archive entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// The entry's path within the archive, `/`-separated. Directories are
+    /// stored with a trailing slash, which isn't included here.
+    pub path: String,
+    pub kind: EntryKind,
+    /// Permission bits, like `0o644`.
+    pub mode: u32,
+    pub uid: u64,
+    pub gid: u64,
+    /// The length of the entry's data. Zero for everything but files.
+    pub size: u64,
+    /// Modification time, in seconds since the Unix epoch.
+    pub mtime: u64,
+    /// The target of a symbolic or hard link.
+    pub link_name: String,
+}
+
+impl Header {
+    /// A header for a regular file with typical defaults.
+    pub fn file(path: &str, size: u64) -> Header {
+        Header {
+            path: path.to_string(),
+            kind: EntryKind::File,
+            mode: 0o644,
+            uid: 0,
+            gid: 0,
+            size,
+            mtime: 0,
+            link_name: String::new(),
+        }
+    }
+
+    /// A header for a directory with typical defaults.
+    pub fn directory(path: &str) -> Header {
+        Header {
+            kind: EntryKind::Directory,
+            mode: 0o755,
+            ..Header::file(path, 0)
+        }
+    }
+
+    /// A header for a symbolic link to `target`.
+    pub fn symlink(path: &str, target: &str) -> Header {
+        Header {
+            kind: EntryKind::Symlink,
+            mode: 0o777,
+            link_name: target.to_string(),
+            ..Header::file(path, 0)
+        }
+    }
+
+    pub fn encode(&self) -> Result<[u8; BLOCK_SIZE]> {
+        let mut block = [0; BLOCK_SIZE];
+        let mut path = self.path.trim_end_matches('/').to_string();
+        if self.kind == EntryKind::Directory {
+            path.push('/');
+        }
+        let (prefix, name) = split_path(&path).ok_or_else(|| Error::NameTooLong(path.clone()))?;
+        if self.link_name.len() > 100 {
+            return Err(Error::NameTooLong(self.link_name.clone()));
+        }
+
+        block[..name.len()].copy_from_slice(name.as_bytes());
+        write_octal(&mut block[100..108], u64::from(self.mode & 0o7777));
+        write_number(&mut block[108..116], self.uid);
+        write_number(&mut block[116..124], self.gid);
+        write_number(&mut block[124..136], self.size);
+        write_number(&mut block[136..148], self.mtime);
+        block[156] = self.kind.typeflag();
+        block[157..157 + self.link_name.len()].copy_from_slice(self.link_name.as_bytes());
+        block[257..263].copy_from_slice(b"ustar\0");
+        block[263..265].copy_from_slice(b"00");
+        block[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+        // The checksum is computed with its own field filled with spaces,
+        // and stored as six octal digits, a NUL, and a space.
+        block[148..156].fill(b' ');
+        let sum = checksum(&block);
+        block[148..154].copy_from_slice(format!("{:06o}", sum).as_bytes());
+        block[154] = 0;
+        Ok(block)
+    }
+
+    /// Parse a header block found at `offset`. Returns `None` for a block
+    /// of zeros, which marks the end of the archive.
+    pub fn decode(block: &[u8; BLOCK_SIZE], offset: u64) -> Result<Option<Header>> {
+        if block.iter().all(|&b| b == 0) {
+            return Ok(None);
+        }
+        let invalid = |reason| Error::InvalidHeader { offset, reason };
+
+        let stored = parse_octal(&block[148..156]).ok_or_else(|| invalid("bad checksum field"))?;
+        let mut copy = *block;
+        copy[148..156].fill(b' ');
+        if checksum(&copy) != stored {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        let field = |range: std::ops::Range<usize>| parse_number(&block[range]);
+        let name = string_field(&block[..100]).ok_or_else(|| invalid("name is not UTF-8"))?;
+        // Only ustar headers have a prefix field; older formats keep other
+        // data there.
+        let prefix = if &block[257..262] == b"ustar" {
+            string_field(&block[345..500]).ok_or_else(|| invalid("prefix is not UTF-8"))?
+        } else {
+            ""
+        };
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let kind = match EntryKind::from_typeflag(block[156]) {
+            // Old tars mark directories with a trailing slash alone.
+            EntryKind::File if path.ends_with('/') => EntryKind::Directory,
+            kind => kind,
+        };
+        let size = field(124..136).ok_or_else(|| invalid("bad size"))?;
+
+        Ok(Some(Header {
+            path: path.trim_end_matches('/').to_string(),
+            kind,
+            mode: field(100..108).ok_or_else(|| invalid("bad mode"))? as u32 & 0o7777,
+            uid: field(108..116).ok_or_else(|| invalid("bad uid"))?,
+            gid: field(116..124).ok_or_else(|| invalid("bad gid"))?,
+            // Only files have data, whatever the header claims; that's
+            // what keeps a reader in step with the archive's blocks.
+            size: if matches!(kind, EntryKind::File | EntryKind::Other(_)) {
+                size
+            } else {
+                0
+            },
+            mtime: field(136..148).ok_or_else(|| invalid("bad mtime"))?,
+            link_name: string_field(&block[157..257])
+                .ok_or_else(|| invalid("link name is not UTF-8"))?
+                .to_string(),
+        }))
+    }
+}
+
+/// Split `path` into ustar's prefix and name fields: the name at most 100
+/// bytes, the prefix at most 155, split at a `/` that's then dropped.
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    if path.len() <= 100 {
+        return Some(("", path));
+    }
+    // Try each slash, leftmost first, so the prefix is as short as it can
+    // be; a trailing slash (on a directory) is part of the name.
+    let searchable = path.strip_suffix('/').unwrap_or(path);
+    searchable
+        .match_indices('/')
+        .map(|(i, _)| (&path[..i], &path[i + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && name.len() <= 100 && !prefix.is_empty())
+}
+
+/// The ustar checksum: the sum of the block's bytes, unsigned.
+fn checksum(block: &[u8; BLOCK_SIZE]) -> u64 {
+    block.iter().map(|&b| u64::from(b)).sum()
+}
+
+/// The text of a NUL-padded field.
+fn string_field(field: &[u8]) -> Option<&str> {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    std::str::from_utf8(&field[..end]).ok()
+}
+
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let text = format!("{:0width$o}", value, width = digits);
+    assert!(
+        text.len() == digits,
+        "{} doesn't fit in {} octal digits",
+        value,
+        digits
+    );
+    field[..digits].copy_from_slice(text.as_bytes());
+    field[digits] = 0;
+}
+
+/// Write an octal number, or for values too large for the field, GNU
+/// tar's base-256 encoding: a set high bit, then the value big-endian.
+fn write_number(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    if value < 1 << (3 * digits) {
+        write_octal(field, value);
+    } else {
+        field.fill(0);
+        let len = field.len();
+        field[len - 8..].copy_from_slice(&value.to_be_bytes());
+        field[0] |= 0x80;
+    }
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    // Tolerate the leading spaces and trailing spaces or NULs that various
+    // tars have used as padding.
+    let text = std::str::from_utf8(field).ok()?;
+    let digits = text.trim_matches(|c| c == ' ' || c == '\0');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(digits, 8).ok()
+}
+
+fn parse_number(field: &[u8]) -> Option<u64> {
+    if field[0] & 0x80 == 0 {
+        return parse_octal(field);
+    }
+    // Base-256, big-endian after the marker bit. A first byte of 0xff
+    // marks a negative number, which no field here can hold.
+    if field[0] == 0xff {
+        return None;
+    }
+    field.iter().enumerate().try_fold(0u64, |value, (i, &b)| {
+        let b = if i == 0 { b & 0x7f } else { b };
+        value.checked_mul(256)?.checked_add(u64::from(b))
+    })
+}
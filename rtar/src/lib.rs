@@ -0,0 +1,17 @@
+// Reading and writing tar archives in the POSIX ustar format, which every
+// `tar` implementation understands.
+//
+// An archive is a sequence of 512-byte blocks: each entry is a header
+// block describing a file, followed by the file's contents padded to a
+// whole number of blocks. Two blocks of zeros mark the end.
+
+mod archive;
+mod builder;
+mod error;
+mod header;
+mod unpack;
+
+pub use archive::{Archive, Entry};
+pub use builder::Builder;
+pub use error::{Error, Result};
+pub use header::{EntryKind, Header, BLOCK_SIZE};
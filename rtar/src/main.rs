@@ -0,0 +1,138 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::{env, process};
+
+use rtar::{Archive, Builder, EntryKind};
+
+fn print_usage() {
+    eprintln!("rtar - create, list, and extract ustar archives");
+    eprintln!("Usage: rtar create ARCHIVE PATH...    pack PATHs (recursively) into ARCHIVE");
+    eprintln!("       rtar list [-v] ARCHIVE         list the entries in ARCHIVE");
+    eprintln!("       rtar extract ARCHIVE [DIR]     unpack ARCHIVE into DIR (default .)");
+    eprintln!("ARCHIVE can be '-' for stdin or stdout.");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", e);
+    process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["create", archive, paths @ ..] if !paths.is_empty() => create(archive, paths),
+        ["list", archive] => list(archive, false),
+        ["list", "-v", archive] => list(archive, true),
+        ["extract", archive] => extract(archive, "."),
+        ["extract", archive, dir] => extract(archive, dir),
+        ["-h" | "--help"] => {
+            print_usage();
+            return;
+        }
+        [] => usage_error("no command given"),
+        [command, ..] if ["create", "list", "extract"].contains(command) => {
+            usage_error(&format!("wrong arguments for {}", command))
+        }
+        [command, ..] => usage_error(&format!("unknown command {}", command)),
+    };
+    if let Err(e) = result {
+        fail(e);
+    }
+}
+
+fn open_input(archive: &str) -> rtar::Result<Box<dyn Read>> {
+    Ok(if archive == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(archive)?))
+    })
+}
+
+fn create(archive: &str, paths: &[&str]) -> rtar::Result<()> {
+    let out: Box<dyn Write> = if archive == "-" {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(BufWriter::new(File::create(archive)?))
+    };
+    let mut builder = Builder::new(out);
+    for path in paths {
+        // Store paths as given, minus anything that would make them unsafe
+        // to extract, as tar does.
+        let name = path.trim_start_matches('/');
+        let name = if name.is_empty() { "." } else { name };
+        if Path::new(name)
+            .components()
+            .any(|c| c == std::path::Component::ParentDir)
+        {
+            eprintln!("rtar: skipping {}: contains '..'", path);
+            continue;
+        }
+        builder.append_dir_all(name, path)?;
+    }
+    builder.finish()?;
+    Ok(())
+}
+
+fn list(archive: &str, verbose: bool) -> rtar::Result<()> {
+    let mut archive = Archive::new(open_input(archive)?);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    while let Some(entry) = archive.next_entry()? {
+        let header = entry.header();
+        if !verbose {
+            writeln!(out, "{}", header.path)?;
+            continue;
+        }
+        let kind = match header.kind {
+            EntryKind::File => '-',
+            EntryKind::Directory => 'd',
+            EntryKind::Symlink => 'l',
+            EntryKind::HardLink => 'h',
+            EntryKind::Other(_) => '?',
+        };
+        write!(
+            out,
+            "{}{} {:>10} {}",
+            kind,
+            permissions(header.mode),
+            header.size,
+            header.path
+        )?;
+        match header.kind {
+            EntryKind::Symlink => writeln!(out, " -> {}", header.link_name)?,
+            EntryKind::HardLink => writeln!(out, " link to {}", header.link_name)?,
+            _ => writeln!(out)?,
+        }
+    }
+    Ok(())
+}
+
+/// `0o754` as `rwxr-xr--`.
+fn permissions(mode: u32) -> String {
+    (0..9)
+        .map(|i| {
+            let bit = 1 << (8 - i);
+            if mode & bit == 0 {
+                '-'
+            } else {
+                ['r', 'w', 'x'][i % 3]
+            }
+        })
+        .collect()
+}
+
+fn extract(archive: &str, dir: &str) -> rtar::Result<()> {
+    Archive::new(open_input(archive)?).unpack(dir)
+}
@@ -0,0 +1,202 @@
+// Extracting an archive safely.
+//
+// An archive is untrusted input. Its entry names could be absolute
+// (`/etc/passwd`) or climb out of the destination (`../../.bashrc`), or it
+// could contain a symbolic link to somewhere outside followed by a file
+// "inside" the link. Each of these would let an archive write anywhere the
+// user can. Extraction refuses all of them.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::archive::{Archive, Entry};
+use crate::error::{Error, Result};
+use crate::header::{EntryKind, Header};
+
+impl<R: Read> Archive<R> {
+    /// Extract every entry into `dest`, creating it if necessary. Entries
+    /// of kinds that can't be extracted, like device files, are skipped.
+    pub fn unpack<P: AsRef<Path>>(&mut self, dest: P) -> Result<()> {
+        let dest = dest.as_ref();
+        fs::create_dir_all(dest)?;
+        // Directory permissions and times are set last: creating files in
+        // a directory changes its modification time, and a read-only
+        // directory couldn't be filled.
+        let mut directories = Vec::new();
+        while let Some(mut entry) = self.next_entry()? {
+            if let Some(dir) = entry.unpack_in(dest)? {
+                directories.push(dir);
+            }
+        }
+        for (path, header) in directories.iter().rev() {
+            set_metadata(path, header)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Entry<'_, R> {
+    /// Extract this entry under `dest`. Returns the path and header of a
+    /// directory, whose metadata the caller should set once its contents
+    /// are extracted.
+    fn unpack_in(&mut self, dest: &Path) -> Result<Option<(PathBuf, Header)>> {
+        let header = self.header().clone();
+        let relative = safe_relative_path(&header.path)?;
+        let Some(name) = relative.file_name() else {
+            // The archive's root, `.` or `./`: already exists.
+            return Ok(None);
+        };
+        let parent = relative.parent().unwrap_or(Path::new(""));
+        create_dirs_within(dest, parent)?;
+        let target = dest.join(parent).join(name);
+
+        match header.kind {
+            EntryKind::Directory => {
+                match fs::symlink_metadata(&target) {
+                    Ok(m) if m.is_dir() => {}
+                    Ok(_) => return Err(Error::UnsafePath(header.path)),
+                    Err(_) => fs::create_dir(&target)?,
+                }
+                return Ok(Some((target, header)));
+            }
+            EntryKind::File => {
+                remove_existing(&target)?;
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&target)?;
+                io::copy(self, &mut file)?;
+                drop(file);
+                set_metadata(&target, &header)?;
+            }
+            EntryKind::Symlink => {
+                if !link_stays_within(dest, parent, &header.link_name)? {
+                    return Err(Error::UnsafePath(format!(
+                        "{} -> {}",
+                        header.path, header.link_name
+                    )));
+                }
+                remove_existing(&target)?;
+                symlink(&header.link_name, &target)?;
+            }
+            EntryKind::HardLink => {
+                let source = safe_relative_path(&header.link_name)?;
+                create_dirs_within(dest, source.parent().unwrap_or(Path::new("")))?;
+                remove_existing(&target)?;
+                fs::hard_link(dest.join(source), &target)?;
+            }
+            EntryKind::Other(_) => {}
+        }
+        Ok(None)
+    }
+}
+
+/// Check that an entry name stays inside the destination, returning it as
+/// a relative path.
+fn safe_relative_path(name: &str) -> Result<PathBuf> {
+    let mut path = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(Error::UnsafePath(name.to_string()));
+            }
+        }
+    }
+    Ok(path)
+}
+
+/// Create `relative`'s directories under `dest`, refusing to go through
+/// a symbolic link: an earlier entry could have planted one pointing
+/// anywhere.
+fn create_dirs_within(dest: &Path, relative: &Path) -> Result<()> {
+    let mut current = dest.to_path_buf();
+    for part in relative.components() {
+        current.push(part);
+        match fs::symlink_metadata(&current) {
+            Ok(m) if m.is_dir() => {}
+            Ok(_) => return Err(Error::UnsafePath(relative.display().to_string())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => fs::create_dir(&current)?,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+/// True if a link in directory `parent` (relative to the destination)
+/// pointing at `target` resolves to somewhere inside the destination.
+///
+/// Counting `..` components isn't enough: `..` after a symbolic link steps
+/// out of wherever the link points, not out of the link's directory. So a
+/// `..` is only allowed while every component before it is a directory
+/// that really exists. Directories stay put for the rest of the
+/// extraction, but a later entry could replace a link, or create one where
+/// nothing is yet, so once the path passes through either, it may only go
+/// deeper. That's safe because every link was checked the same way when it
+/// was made.
+fn link_stays_within(dest: &Path, parent: &Path, target: &str) -> Result<bool> {
+    let mut current = dest.to_path_buf();
+    let mut depth = 0;
+    let mut settled = true;
+    for component in parent.components().chain(Path::new(target).components()) {
+        match component {
+            Component::Normal(part) => {
+                current.push(part);
+                depth += 1;
+                if settled {
+                    settled = match fs::symlink_metadata(&current) {
+                        Ok(m) => m.is_dir(),
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => false,
+                        Err(e) => return Err(e.into()),
+                    };
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir if settled && depth > 0 => {
+                current.pop();
+                depth -= 1;
+            }
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+/// Remove whatever's at `path`, unless it's a directory, so that a new
+/// file can be created there without following an old symbolic link.
+fn remove_existing(path: &Path) -> Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => Err(Error::UnsafePath(path.display().to_string())),
+        Ok(_) => Ok(fs::remove_file(path)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn set_metadata(path: &Path, header: &Header) -> Result<()> {
+    let mtime = UNIX_EPOCH + Duration::from_secs(header.mtime);
+    File::options()
+        .write(header.kind == EntryKind::File)
+        .read(true)
+        .open(path)?
+        .set_modified(mtime)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(header.mode))?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(target: &str, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &str, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
@@ -0,0 +1,111 @@
+use std::fs;
+use std::process::Command;
+
+fn rtar() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_rtar"))
+}
+
+#[test]
+fn create_list_extract() {
+    let work = tempfile::tempdir().unwrap();
+    let src = work.path().join("project");
+    fs::create_dir_all(src.join("src")).unwrap();
+    fs::write(src.join("Cargo.toml"), "[package]\n").unwrap();
+    fs::write(src.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+    let status = rtar()
+        .current_dir(work.path())
+        .args(["create", "project.tar", "project"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = rtar()
+        .current_dir(work.path())
+        .args(["list", "project.tar"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "project\nproject/Cargo.toml\nproject/src\nproject/src/main.rs\n"
+    );
+
+    let output = rtar()
+        .current_dir(work.path())
+        .args(["list", "-v", "project.tar"])
+        .output()
+        .unwrap();
+    let listing = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        listing
+            .lines()
+            .any(|l| l.starts_with('d') && l.ends_with(" project/src")),
+        "{}",
+        listing
+    );
+    assert!(
+        listing
+            .lines()
+            .any(|l| l.contains(" 13 project/src/main.rs")),
+        "{}",
+        listing
+    );
+
+    let status = rtar()
+        .current_dir(work.path())
+        .args(["extract", "project.tar", "copy"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(work.path().join("copy/project/src/main.rs")).unwrap(),
+        "fn main() {}\n"
+    );
+}
+
+#[test]
+fn streams_through_pipes() {
+    let work = tempfile::tempdir().unwrap();
+    fs::write(work.path().join("note.txt"), "streamed\n").unwrap();
+    let archive = rtar()
+        .current_dir(work.path())
+        .args(["create", "-", "note.txt"])
+        .output()
+        .unwrap();
+    assert!(archive.status.success());
+    assert_eq!(archive.stdout.len() % 512, 0);
+
+    let mut child = rtar()
+        .current_dir(work.path())
+        .args(["extract", "-", "out"])
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::io::Write::write_all(&mut child.stdin.take().unwrap(), &archive.stdout).unwrap();
+    assert!(child.wait().unwrap().success());
+    assert_eq!(
+        fs::read_to_string(work.path().join("out/note.txt")).unwrap(),
+        "streamed\n"
+    );
+}
+
+#[test]
+fn errors() {
+    let work = tempfile::tempdir().unwrap();
+    let output = rtar()
+        .current_dir(work.path())
+        .args(["list", "missing.tar"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    fs::write(work.path().join("junk.tar"), vec![b'j'; 1024]).unwrap();
+    let output = rtar()
+        .current_dir(work.path())
+        .args(["list", "junk.tar"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid header at offset 0"));
+    assert_eq!(rtar().arg("frobnicate").status().unwrap().code(), Some(2));
+    assert_eq!(rtar().arg("create").status().unwrap().code(), Some(2));
+}
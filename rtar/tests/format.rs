@@ -0,0 +1,142 @@
+use std::io::{self, Read};
+
+use rtar::{Archive, Builder, EntryKind, Error, Header, BLOCK_SIZE};
+
+/// Build an archive in memory from (header, data) pairs.
+fn archive(entries: &[(Header, &[u8])]) -> Vec<u8> {
+    let mut builder = Builder::new(Vec::new());
+    for (header, data) in entries {
+        builder.append(header, *data).unwrap();
+    }
+    builder.finish().unwrap()
+}
+
+#[test]
+fn header_round_trip() {
+    let header = Header {
+        mode: 0o4755,
+        uid: 1000,
+        gid: 100,
+        mtime: 1_700_000_000,
+        ..Header::file("dir/file.txt", 12345)
+    };
+    let block = header.encode().unwrap();
+    assert_eq!(&block[257..265], b"ustar\x0000");
+    assert_eq!(Header::decode(&block, 0).unwrap(), Some(header));
+
+    let dir = Header::directory("some/dir");
+    let block = dir.encode().unwrap();
+    assert_eq!(&block[..9], b"some/dir/");
+    assert_eq!(Header::decode(&block, 0).unwrap(), Some(dir));
+
+    let link = Header::symlink("latest", "v1.2.3");
+    assert_eq!(
+        Header::decode(&link.encode().unwrap(), 0).unwrap(),
+        Some(link)
+    );
+}
+
+#[test]
+fn long_paths_use_the_prefix() {
+    let long = format!("{}/{}/{}", "a".repeat(80), "b".repeat(70), "c".repeat(90));
+    let header = Header::file(&long, 0);
+    let block = header.encode().unwrap();
+    assert_eq!(&block[..90], "c".repeat(90).as_bytes());
+    assert_eq!(Header::decode(&block, 0).unwrap().unwrap().path, long);
+
+    // A single component over 100 bytes can't be split.
+    let unsplittable = format!("dir/{}", "x".repeat(101));
+    assert!(matches!(
+        Header::file(&unsplittable, 0).encode(),
+        Err(Error::NameTooLong(_))
+    ));
+    // Nor can anything over 256 bytes.
+    let huge = vec!["abcdefgh"; 30].join("/");
+    assert!(matches!(
+        Header::file(&huge, 0).encode(),
+        Err(Error::NameTooLong(_))
+    ));
+}
+
+#[test]
+fn corrupt_headers_are_detected() {
+    let mut block = Header::file("f", 3).encode().unwrap();
+    block[0] = b'g';
+    match Header::decode(&block, 1024) {
+        Err(Error::InvalidHeader { offset, reason }) => {
+            assert_eq!(offset, 1024);
+            assert_eq!(reason, "checksum mismatch");
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(Header::decode(&[0; BLOCK_SIZE], 0).unwrap().is_none());
+}
+
+#[test]
+fn huge_sizes_use_base_256() {
+    let size = 20 * 1024 * 1024 * 1024; // over the 8 GiB octal limit
+    let block = Header::file("big", size).encode().unwrap();
+    assert_eq!(block[124], 0x80);
+    assert_eq!(Header::decode(&block, 0).unwrap().unwrap().size, size);
+}
+
+#[test]
+fn streaming_reads_and_skips() {
+    let data = archive(&[
+        (Header::file("a", 5), b"hello"),
+        (Header::directory("d"), b""),
+        (Header::file("d/b", 600), &[7; 600]),
+        (Header::file("empty", 0), b""),
+        (Header::file("c", 3), b"end"),
+    ]);
+    // Every entry is a header block plus its data padded to whole blocks,
+    // then two blocks of zeros.
+    assert_eq!(data.len(), BLOCK_SIZE * (2 + 1 + 3 + 1 + 2 + 2));
+
+    let mut archive = Archive::new(io::Cursor::new(data));
+    let mut seen = Vec::new();
+    while let Some(mut entry) = archive.next_entry().unwrap() {
+        let header = entry.header().clone();
+        // Read only the first two bytes: the rest must be skipped.
+        let mut start = Vec::new();
+        (&mut entry).take(2).read_to_end(&mut start).unwrap();
+        seen.push((header.path, header.kind, header.size, start));
+    }
+    assert_eq!(
+        seen,
+        vec![
+            ("a".to_string(), EntryKind::File, 5, b"he".to_vec()),
+            ("d".to_string(), EntryKind::Directory, 0, vec![]),
+            ("d/b".to_string(), EntryKind::File, 600, vec![7, 7]),
+            ("empty".to_string(), EntryKind::File, 0, vec![]),
+            ("c".to_string(), EntryKind::File, 3, b"en".to_vec()),
+        ]
+    );
+    assert!(archive.next_entry().unwrap().is_none());
+}
+
+#[test]
+fn missing_end_marker_is_tolerated() {
+    let mut data = archive(&[(Header::file("a", 1), b"x")]);
+    data.truncate(data.len() - 2 * BLOCK_SIZE);
+    let mut archive = Archive::new(&data[..]);
+    assert_eq!(archive.next_entry().unwrap().unwrap().header().path, "a");
+    assert!(archive.next_entry().unwrap().is_none());
+}
+
+#[test]
+fn truncated_data_is_an_error() {
+    let data = archive(&[(Header::file("a", 1000), &[1; 1000])]);
+    let mut archive = Archive::new(&data[..700]);
+    let mut entry = archive.next_entry().unwrap().unwrap();
+    let err = io::copy(&mut entry, &mut io::sink()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn short_data_is_refused_when_building() {
+    let mut builder = Builder::new(Vec::new());
+    assert!(builder
+        .append(&Header::file("a", 10), &b"short"[..])
+        .is_err());
+}
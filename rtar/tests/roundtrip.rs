@@ -0,0 +1,205 @@
+// Pack generated directory trees, unpack them, and compare.
+
+#![cfg(unix)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, UNIX_EPOCH};
+
+use rtar::{Archive, Builder};
+
+/// A small deterministic random number generator, so each seed always
+/// generates the same tree.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// Fill `dir` with a random tree: nested directories, files of awkward
+/// sizes (empty, exact multiples of the block size, one byte either side),
+/// names long enough to need ustar's prefix field, symbolic links, and
+/// varied permissions and times.
+fn generate(dir: &Path, rng: &mut Lcg, depth: u32) {
+    let sizes = [0, 1, 511, 512, 513, 1024, 5000, 70_000];
+    for i in 0..3 + rng.next(4) {
+        let long = "n".repeat(rng.next(3) as usize * 25);
+        let name = format!("{}{}-{}", long, depth, i);
+        let path = dir.join(&name);
+        match rng.next(6) {
+            0 if depth < 3 => {
+                fs::create_dir(&path).unwrap();
+                generate(&path, rng, depth + 1);
+                let mode = [0o755, 0o700, 0o750][rng.next(3) as usize];
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+            }
+            1 => {
+                let target = if rng.next(2) == 0 {
+                    "missing-target"
+                } else {
+                    "."
+                };
+                std::os::unix::fs::symlink(target, &path).unwrap();
+            }
+            _ => {
+                let size = sizes[rng.next(sizes.len() as u64) as usize];
+                let data: Vec<u8> = (0..size).map(|_| rng.next(256) as u8).collect();
+                fs::write(&path, data).unwrap();
+                let mode = [0o644, 0o600, 0o755, 0o444][rng.next(4) as usize];
+                fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+                let mtime = UNIX_EPOCH + Duration::from_secs(1_000_000_000 + rng.next(1 << 30));
+                fs::File::options()
+                    .write(true)
+                    .open(&path)
+                    .ok()
+                    .map(|f| f.set_modified(mtime));
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    File {
+        data: Vec<u8>,
+        mode: u32,
+        mtime: u64,
+    },
+    Dir {
+        mode: u32,
+    },
+    Link(String),
+}
+
+/// Everything under `root` that an archive should preserve, by relative
+/// path.
+fn snapshot(root: &Path) -> BTreeMap<String, Node> {
+    let mut nodes = BTreeMap::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let relative = path
+                .strip_prefix(root)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+            let metadata = fs::symlink_metadata(&path).unwrap();
+            let mode = metadata.permissions().mode() & 0o7777;
+            let node = if metadata.file_type().is_symlink() {
+                Node::Link(fs::read_link(&path).unwrap().to_str().unwrap().to_string())
+            } else if metadata.is_dir() {
+                stack.push(path.clone());
+                Node::Dir { mode }
+            } else {
+                let mtime = metadata
+                    .modified()
+                    .unwrap()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                Node::File {
+                    data: fs::read(&path).unwrap(),
+                    mode,
+                    mtime,
+                }
+            };
+            nodes.insert(relative, node);
+        }
+    }
+    nodes
+}
+
+fn pack(dir: &Path) -> Vec<u8> {
+    let mut builder = Builder::new(Vec::new());
+    builder.append_dir_all(".", dir).unwrap();
+    builder.finish().unwrap()
+}
+
+#[test]
+fn generated_trees_round_trip() {
+    for seed in 0..8 {
+        let source = tempfile::tempdir().unwrap();
+        generate(source.path(), &mut Lcg(seed), 0);
+        let expected = snapshot(source.path());
+
+        let archive = pack(source.path());
+        // Packing is deterministic.
+        assert_eq!(archive, pack(source.path()), "seed {}", seed);
+
+        let dest = tempfile::tempdir().unwrap();
+        Archive::new(&archive[..]).unpack(dest.path()).unwrap();
+        assert_eq!(snapshot(dest.path()), expected, "seed {}", seed);
+    }
+}
+
+/// Is a `tar` command available to check against?
+fn system_tar() -> bool {
+    Command::new("tar")
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+#[test]
+fn system_tar_reads_our_archives() {
+    if !system_tar() {
+        eprintln!("skipping: no tar command");
+        return;
+    }
+    let source = tempfile::tempdir().unwrap();
+    generate(source.path(), &mut Lcg(42), 0);
+    let work = tempfile::tempdir().unwrap();
+    let archive = work.path().join("ours.tar");
+    fs::write(&archive, pack(source.path())).unwrap();
+
+    let dest = work.path().join("out");
+    fs::create_dir(&dest).unwrap();
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&dest)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(snapshot(&dest), snapshot(source.path()));
+}
+
+#[test]
+fn we_read_system_tar_archives() {
+    if !system_tar() {
+        eprintln!("skipping: no tar command");
+        return;
+    }
+    let source = tempfile::tempdir().unwrap();
+    generate(source.path(), &mut Lcg(7), 0);
+    let work = tempfile::tempdir().unwrap();
+    let archive = work.path().join("theirs.tar");
+    let status = Command::new("tar")
+        .arg("--format=ustar")
+        .arg("-cf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(source.path())
+        .arg(".")
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let dest = work.path().join("out");
+    Archive::new(fs::File::open(&archive).unwrap())
+        .unpack(&dest)
+        .unwrap();
+    assert_eq!(snapshot(&dest), snapshot(source.path()));
+}
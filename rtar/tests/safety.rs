@@ -0,0 +1,169 @@
+// Extraction must never write outside the destination directory.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::path::Path;
+
+use rtar::{Archive, Builder, Error, Header};
+
+fn archive(entries: &[(Header, &[u8])]) -> Vec<u8> {
+    let mut builder = Builder::new(Vec::new());
+    for (header, data) in entries {
+        builder.append(header, *data).unwrap();
+    }
+    builder.finish().unwrap()
+}
+
+/// Unpack into `root/dest`, so escapes would land in `root`, where we
+/// can look for them.
+fn unpack(root: &Path, data: &[u8]) -> rtar::Result<()> {
+    Archive::new(data).unpack(root.join("dest"))
+}
+
+fn assert_unsafe(result: rtar::Result<()>) {
+    match result {
+        Err(Error::UnsafePath(_)) => {}
+        other => panic!("expected UnsafePath, got {:?}", other),
+    }
+}
+
+#[test]
+fn parent_directory_components() {
+    let root = tempfile::tempdir().unwrap();
+    for path in ["../evil", "a/../../evil", "./../evil"] {
+        assert_unsafe(unpack(
+            root.path(),
+            &archive(&[(Header::file(path, 4), b"evil")]),
+        ));
+    }
+    assert!(!root.path().join("evil").exists());
+}
+
+#[test]
+fn absolute_paths() {
+    let root = tempfile::tempdir().unwrap();
+    let target = root.path().join("absolute");
+    let data = archive(&[(Header::file(target.to_str().unwrap(), 4), b"evil")]);
+    assert_unsafe(unpack(root.path(), &data));
+    assert!(!target.exists());
+}
+
+#[test]
+fn writing_through_a_symlink() {
+    // The classic trick: a link to somewhere outside, then a file "in" it.
+    let root = tempfile::tempdir().unwrap();
+    let outside = root.path().join("outside");
+    fs::create_dir(&outside).unwrap();
+    let data = archive(&[
+        (Header::symlink("link", outside.to_str().unwrap()), b""),
+        (Header::file("link/file", 4), b"evil"),
+    ]);
+    assert_unsafe(unpack(root.path(), &data));
+    assert!(!outside.join("file").exists());
+
+    // The same with a relative link, which is safe itself, but is then
+    // used as a directory.
+    let data = archive(&[
+        (Header::directory("a"), b""),
+        (Header::symlink("a/up", ".."), b""),
+        (Header::file("a/up/file", 4), b"evil"),
+    ]);
+    let root = tempfile::tempdir().unwrap();
+    assert_unsafe(unpack(root.path(), &data));
+    assert!(!root.path().join("dest/file").exists());
+}
+
+#[test]
+fn symlinks_pointing_outside() {
+    let root = tempfile::tempdir().unwrap();
+    for target in ["/etc/passwd", "../secret", "a/../../secret"] {
+        let data = archive(&[(Header::symlink("link", target), b"")]);
+        assert_unsafe(unpack(root.path(), &data));
+    }
+    // Links that stay inside are fine.
+    let data = archive(&[
+        (Header::directory("a"), b""),
+        (Header::symlink("a/up", ".."), b""),
+        (Header::symlink("a/sibling", "../b/c"), b""),
+    ]);
+    unpack(root.path(), &data).unwrap();
+    assert_eq!(
+        fs::read_link(root.path().join("dest/a/up")).unwrap(),
+        Path::new("..")
+    );
+}
+
+#[test]
+fn chained_symlinks_pointing_outside() {
+    // Each link looks harmless on its own, but `e` goes up from `d/up`,
+    // which is already the destination.
+    let root = tempfile::tempdir().unwrap();
+    let data = archive(&[
+        (Header::directory("d"), b""),
+        (Header::symlink("d/up", ".."), b""),
+        (Header::symlink("e", "d/up/.."), b""),
+    ]);
+    assert_unsafe(unpack(root.path(), &data));
+    assert!(fs::symlink_metadata(root.path().join("dest/e")).is_err());
+
+    // Going up past a link that a later entry could replace, or a name
+    // that a later entry could make a link, is refused too.
+    for target in ["d/up/x/..", "missing/.."] {
+        let root = tempfile::tempdir().unwrap();
+        let data = archive(&[
+            (Header::directory("d"), b""),
+            (Header::symlink("d/up", ".."), b""),
+            (Header::symlink("e", target), b""),
+        ]);
+        assert_unsafe(unpack(root.path(), &data));
+    }
+
+    // Going up out of real directories, then through a link, is fine.
+    let root = tempfile::tempdir().unwrap();
+    let data = archive(&[
+        (Header::directory("d"), b""),
+        (Header::directory("d/sub"), b""),
+        (Header::symlink("d/up", ".."), b""),
+        (Header::symlink("d/sub/e", "../up/d"), b""),
+    ]);
+    unpack(root.path(), &data).unwrap();
+    assert_eq!(
+        root.path().join("dest/d/sub/e").canonicalize().unwrap(),
+        root.path().join("dest/d").canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn overwriting_a_symlink_replaces_it() {
+    // A file entry whose path already holds a link must not write through
+    // the link.
+    let root = tempfile::tempdir().unwrap();
+    let victim = root.path().join("victim");
+    fs::write(&victim, "original").unwrap();
+    let dest = root.path().join("dest");
+    fs::create_dir(&dest).unwrap();
+    std::os::unix::fs::symlink(&victim, dest.join("name")).unwrap();
+
+    unpack(root.path(), &archive(&[(Header::file("name", 3), b"new")])).unwrap();
+    assert_eq!(fs::read_to_string(&victim).unwrap(), "original");
+    assert_eq!(fs::read_to_string(dest.join("name")).unwrap(), "new");
+    assert!(!fs::symlink_metadata(dest.join("name"))
+        .unwrap()
+        .file_type()
+        .is_symlink());
+}
+
+#[test]
+fn hard_links_outside() {
+    let root = tempfile::tempdir().unwrap();
+    let data = archive(&[(
+        Header {
+            kind: rtar::EntryKind::HardLink,
+            link_name: "../victim".to_string(),
+            ..Header::file("link", 0)
+        },
+        b"",
+    )]);
+    assert_unsafe(unpack(root.path(), &data));
+}
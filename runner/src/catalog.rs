@@ -0,0 +1,218 @@
+// Every program in the workspace, with a line about what it shows and a
+// run that should succeed quickly.
+
+/// A program in the workspace.
+#[derive(Debug)]
+pub struct Demo {
+    /// The binary's name, which is how `demos run` refers to it.
+    pub bin: &'static str,
+    pub package: &'static str,
+    pub description: &'static str,
+    pub smoke: Smoke,
+}
+
+/// A canned run of a demo, for `demos run --all`.
+///
+/// It runs in a fresh temporary directory, holding `files`. In `args`,
+/// `{workspace}` stands for the workspace's top directory.
+#[derive(Debug)]
+pub struct Smoke {
+    pub args: &'static [&'static str],
+    pub stdin: &'static str,
+    pub files: &'static [(&'static str, &'static str)],
+    /// The exit code expected.
+    pub code: i32,
+}
+
+const NONE: Smoke = Smoke {
+    args: &[],
+    stdin: "",
+    files: &[],
+    code: 0,
+};
+
+const fn args(args: &'static [&'static str]) -> Smoke {
+    Smoke { args, ..NONE }
+}
+
+/// `--help`, for programs that otherwise run until they're stopped.
+const HELP: Smoke = args(&["--help"]);
+
+const HELLO: &[(&str, &str)] = &[("hello.txt", "hello world\n")];
+
+const fn demo(bin: &'static str, description: &'static str, smoke: Smoke) -> Demo {
+    Demo {
+        bin,
+        package: bin,
+        description,
+        smoke,
+    }
+}
+
+pub static DEMOS: &[Demo] = &[
+    Demo {
+        package: "ch_1",
+        ..demo("ch_1", "chapter 1: a tour of what Rust promises", NONE)
+    },
+    demo(
+        "ch_02",
+        "chapter 2: quickreplace, a regex find-and-replace",
+        Smoke {
+            args: &["world", "Rust", "hello.txt", "out.txt"],
+            files: HELLO,
+            ..NONE
+        },
+    ),
+    demo(
+        "ch_03",
+        "chapter 3: fundamental types, reading a file",
+        Smoke {
+            files: &[("file.txt", "some contents\n")],
+            ..NONE
+        },
+    ),
+    demo("ch_04", "chapter 4: ownership, moves, and Rc", NONE),
+    demo("ch_05", "chapter 5: references, and a StringTable", NONE),
+    demo("ch_06", "chapter 6: expressions", NONE),
+    demo("ch_07", "chapter 7: error handling", NONE),
+    demo("ch_08", "chapter 8: crates and modules", NONE),
+    demo("ch_09", "chapter 9: structs", NONE),
+    demo("macros", "a json! macro and derive macros", NONE),
+    demo(
+        "git-toy",
+        "foreign functions: libgit2's latest commit",
+        args(&["{workspace}"]),
+    ),
+    demo("gap-buffer", "unsafe code: a gap buffer", NONE),
+    demo(
+        "ascii",
+        "unsafe functions and the invariants they rely on",
+        NONE,
+    ),
+    demo("binary-tree", "iterators over a binary tree", NONE),
+    demo("interval", "partial ordering of intervals", NONE),
+    demo("complex", "operator overloading on complex numbers", NONE),
+    demo("queue", "a generic queue made of two stacks", NONE),
+    demo("fern-sim", "modules: a fern growth simulator", NONE),
+    demo("iter-tools", "writing iterator adapters", NONE),
+    demo("router", "closures: a callback-based router", NONE),
+    demo("mini-map", "a hash map from scratch", NONE),
+    demo(
+        "textwork",
+        "strings and Unicode: widths, normalization, graphemes",
+        args(&["graphemes", "e\u{301}te\u{301}"]),
+    ),
+    demo(
+        "iocopy",
+        "composable I/O adapters: gzip, hashing, rate limits",
+        Smoke {
+            args: &["--sha256", "hello.txt", "out.txt"],
+            files: HELLO,
+            ..NONE
+        },
+    ),
+    demo("vecmath", "3D vectors with operator overloading", NONE),
+    demo("conversions", "From, TryFrom, and AsRef", NONE),
+    demo(
+        "tiny-json",
+        "enums and patterns: a JSON parser and printer",
+        Smoke {
+            stdin: r#"{"a": [1, 2, {"b": null}]}"#,
+            ..NONE
+        },
+    ),
+    demo(
+        "generic-io",
+        "traits and generics: writing to any sink",
+        NONE,
+    ),
+    demo(
+        "kvstore",
+        "a Bitcask-style key-value store",
+        args(&["set", "k", "v"]),
+    ),
+    demo(
+        "argparse-lite",
+        "an argument parser from scratch",
+        args(&["-vv", "add", "-t", "home", "-p1", "buy", "milk"]),
+    ),
+    demo(
+        "rsh",
+        "processes: a minimal shell",
+        args(&["-c", "echo hi"]),
+    ),
+    demo(
+        "rtar",
+        "a tar archiver",
+        Smoke {
+            args: &["create", "hello.tar", "hello.txt"],
+            files: HELLO,
+            ..NONE
+        },
+    ),
+    demo("btree-disk", "an on-disk B-tree", args(&["put", "k", "v"])),
+    demo(
+        "mdview",
+        "Markdown to HTML",
+        Smoke {
+            stdin: "# Hello\n\nSome *emphasis*.\n",
+            ..NONE
+        },
+    ),
+    demo("shorty", "a URL shortening web service", HELP),
+    demo(
+        "tools-api",
+        "quickreplace, the calculator, and sum as a JSON web service",
+        HELP,
+    ),
+    demo(
+        "lexer",
+        "a lexer toolkit",
+        Smoke {
+            args: &["calc"],
+            stdin: "1 + 2 * x\n",
+            ..NONE
+        },
+    ),
+    demo("mini-runtime", "a minimal async runtime", NONE),
+    demo(
+        "mini-channel",
+        "channels from scratch, timed",
+        args(&["-n", "1000"]),
+    ),
+    demo(
+        "daemon",
+        "a daemon that shuts down cleanly on signals",
+        HELP,
+    ),
+    demo(
+        "serde-bench",
+        "comparing serialization formats",
+        args(&["-n", "10", "--rounds", "1"]),
+    ),
+    demo(
+        "proc-tools",
+        "processes and pipelines",
+        args(&["echo", "hi", "|", "tr", "a-z", "A-Z"]),
+    ),
+    demo(
+        "alloc-lab",
+        "a counting global allocator and an arena",
+        args(&["-n", "50"]),
+    ),
+    demo(
+        "stress",
+        "kvstore, mini-channel, and mini-runtime soaked under load",
+        args(&["--duration", "0", "--rounds", "1", "--threads", "2"]),
+    ),
+    demo(
+        "raytracer",
+        "a parallel raytracer",
+        args(&["--size", "16x9", "--samples", "1"]),
+    ),
+    demo(
+        "dashboard",
+        "a terminal dashboard of earlier chapters",
+        HELP,
+    ),
+];
@@ -0,0 +1,21 @@
+// One front door for the workspace's programs: list them, run one, or run
+// them all with canned inputs as a smoke test.
+//
+// The catalog is written out by hand, since a description and a sensible
+// set of arguments can't be inferred. A test checks it against the
+// workspace's members, so a new program can't be left out.
+
+mod catalog;
+mod locate;
+mod smoke;
+
+pub use catalog::{Demo, Smoke, DEMOS};
+pub use locate::{command, workspace_root};
+pub use smoke::{smoke, transcript, Outcome, Transcript};
+
+/// The demo with the given name or package name.
+pub fn find(name: &str) -> Option<&'static Demo> {
+    DEMOS
+        .iter()
+        .find(|demo| demo.bin == name || demo.package == name)
+}
@@ -0,0 +1,46 @@
+// Finding a demo's program.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::Demo;
+
+/// The workspace's top directory.
+pub fn workspace_root() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+}
+
+/// A `Command` that runs `demo`'s program, with no arguments yet.
+///
+/// After `cargo build --workspace`, every program sits in the same
+/// directory as `demos` itself, and is run from there. Failing that, the
+/// command is `cargo run` on the demo's package, which builds it first.
+pub fn command(demo: &Demo) -> Command {
+    match built(demo.bin) {
+        Some(path) => Command::new(path),
+        None => {
+            let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+            let mut command = Command::new(cargo);
+            command
+                .arg("run")
+                .arg("--quiet")
+                .arg("--manifest-path")
+                .arg(workspace_root().join("Cargo.toml"))
+                .args(["--package", demo.package, "--bin", demo.bin, "--"]);
+            command
+        }
+    }
+}
+
+/// `bin`'s path in the directory this program was run from, if it's there.
+/// Tests run from `target/debug/deps`, so look one level up from there too.
+fn built(bin: &str) -> Option<PathBuf> {
+    let exe = env::current_exe().ok()?;
+    let mut dir = exe.parent()?;
+    if dir.ends_with("deps") {
+        dir = dir.parent()?;
+    }
+    let path = dir.join(format!("{}{}", bin, env::consts::EXE_SUFFIX));
+    path.is_file().then_some(path)
+}
@@ -0,0 +1,135 @@
+use std::env;
+use std::process::ExitCode;
+
+use common::{exit, CliError, Error};
+use runner::{command, find, smoke, DEMOS};
+
+#[derive(Debug)]
+enum Arguments {
+    List,
+    Run { name: String, args: Vec<String> },
+    All { only: Vec<String> },
+}
+
+impl Arguments {
+    fn parse() -> Result<Self, Error> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        match args.first().map(String::as_str) {
+            None => Ok(Arguments::List),
+            Some("list") if args.len() == 1 => Ok(Arguments::List),
+            Some("run") => match args.get(1).map(String::as_str) {
+                Some("--all") => Ok(Arguments::All {
+                    only: args[2..].to_vec(),
+                }),
+                Some(name) => {
+                    let rest = &args[2..];
+                    // Anything after the name goes to the demo; a `--`
+                    // first is allowed, and dropped.
+                    let rest = match rest.first().map(String::as_str) {
+                        Some("--") => &rest[1..],
+                        _ => rest,
+                    };
+                    Ok(Arguments::Run {
+                        name: name.to_string(),
+                        args: rest.to_vec(),
+                    })
+                }
+                None => Err(usage_error("run needs a demo's name, or --all")),
+            },
+            Some("-h") | Some("--help") => {
+                print_usage();
+                std::process::exit(0);
+            }
+            Some(other) => Err(usage_error(&format!("unexpected argument {}", other))),
+        }
+    }
+}
+
+fn print_usage() {
+    eprintln!("demos - list and run the workspace's programs");
+    eprintln!("Usage: demos list                   what there is to run");
+    eprintln!("       demos run NAME [--] [ARGS]   run one, passing it ARGS");
+    eprintln!("       demos run --all [NAME...]    run each with canned inputs, checking");
+    eprintln!("                                    that it succeeds");
+}
+
+fn usage_error(message: &str) -> Error {
+    print_usage();
+    Error::usage(message)
+}
+
+fn list() {
+    let width = DEMOS.iter().map(|demo| demo.bin.len()).max().unwrap_or(0);
+    for demo in DEMOS {
+        println!("{:width$}  {}", demo.bin, demo.description, width = width);
+    }
+}
+
+fn run(name: &str, args: &[String]) -> Result<ExitCode, Error> {
+    let demo = find(name).ok_or_else(|| {
+        Error::new(format!("no demo called {:?}; try `demos list`", name)).with_code(exit::USAGE)
+    })?;
+    let status = command(demo)
+        .args(args)
+        .status()
+        .map_err(|e| Error::new(format!("can't run {}: {}", demo.bin, e)))?;
+    // Pass the demo's own exit code along.
+    Ok(match status.code() {
+        Some(code) => ExitCode::from(code as u8),
+        None => ExitCode::FAILURE,
+    })
+}
+
+fn all(only: &[String]) -> Result<ExitCode, Error> {
+    for name in only {
+        if find(name).is_none() {
+            return Err(Error::usage(format!("no demo called {:?}", name)));
+        }
+    }
+    let chosen = DEMOS.iter().filter(|demo| {
+        only.is_empty()
+            || only
+                .iter()
+                .any(|name| name == demo.bin || name == demo.package)
+    });
+
+    let width = DEMOS.iter().map(|demo| demo.bin.len()).max().unwrap_or(0);
+    let (mut passed, mut failed) = (0, Vec::new());
+    for demo in chosen {
+        let outcome = smoke(demo);
+        println!("{:width$}  {}", demo.bin, outcome, width = width);
+        if outcome.passed() {
+            passed += 1;
+        } else {
+            failed.push(demo.bin);
+        }
+    }
+
+    println!("{} passed, {} failed", passed, failed.len());
+    if failed.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Err(Error::new(format!("failed: {}", failed.join(", "))))
+    }
+}
+
+fn demos() -> Result<ExitCode, Error> {
+    match Arguments::parse()? {
+        Arguments::List => {
+            list();
+            Ok(ExitCode::SUCCESS)
+        }
+        Arguments::Run { name, args } => run(&name, &args),
+        Arguments::All { only } => all(&only),
+    }
+}
+
+fn main() -> ExitCode {
+    match demos() {
+        Ok(code) => code,
+        Err(e) => {
+            common::report(&e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Running a demo's canned smoke test.
+
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use proc_tools::{cmd, Error, Output};
+
+use crate::{command, workspace_root, Demo};
+
+/// How long a smoke run may take.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How a smoke run went.
+#[derive(Debug)]
+pub enum Outcome {
+    Passed {
+        time: Duration,
+    },
+    /// It exited with the wrong code; `stderr` is what it said about it.
+    WrongCode {
+        code: Option<i32>,
+        stderr: String,
+    },
+    TimedOut,
+    /// It couldn't be run at all.
+    Error(String),
+}
+
+impl Outcome {
+    pub fn passed(&self) -> bool {
+        matches!(self, Outcome::Passed { .. })
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Passed { time } => write!(f, "ok in {:.2?}", time),
+            Outcome::WrongCode { code, stderr } => {
+                match code {
+                    Some(code) => write!(f, "exited with {}", code)?,
+                    None => write!(f, "killed by a signal")?,
+                }
+                // The last few lines say most about what went wrong.
+                let lines: Vec<&str> = stderr.lines().collect();
+                for line in &lines[lines.len().saturating_sub(5)..] {
+                    write!(f, "\n    {}", line)?;
+                }
+                Ok(())
+            }
+            Outcome::TimedOut => write!(f, "still running after {:?}", TIMEOUT),
+            Outcome::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// What a demo printed on its smoke run.
+#[derive(Debug)]
+pub struct Transcript {
+    /// `None` if a signal killed it.
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub time: Duration,
+}
+
+/// Run `demo` with its canned arguments, input, and files, in a fresh
+/// temporary directory, and check its exit code.
+pub fn smoke(demo: &Demo) -> Outcome {
+    match transcript(demo) {
+        Ok(run) if run.code == Some(demo.smoke.code) => Outcome::Passed { time: run.time },
+        Ok(run) => Outcome::WrongCode {
+            code: run.code,
+            stderr: run.stderr,
+        },
+        Err(outcome) => outcome,
+    }
+}
+
+/// Run `demo` as `smoke` does, and return what it printed, whatever its
+/// exit code. The error is the outcome if it couldn't be run to the end.
+pub fn transcript(demo: &Demo) -> Result<Transcript, Outcome> {
+    let dir = tempfile::tempdir()
+        .map_err(|e| Outcome::Error(format!("can't make a directory: {}", e)))?;
+    for (name, contents) in demo.smoke.files {
+        fs::write(dir.path().join(name), contents)
+            .map_err(|e| Outcome::Error(format!("can't write {}: {}", name, e)))?;
+    }
+
+    let workspace = workspace_root().to_string_lossy();
+    let command = command(demo);
+    let run = cmd(command.get_program())
+        .args(command.get_args())
+        .args(
+            demo.smoke
+                .args
+                .iter()
+                .map(|arg| arg.replace("{workspace}", &workspace)),
+        )
+        .dir(dir.path())
+        .input(demo.smoke.stdin)
+        .timeout(TIMEOUT)
+        .unchecked();
+
+    let start = Instant::now();
+    let output: Output = match run.capture() {
+        Ok(output) => output,
+        Err(Error::TimedOut { .. }) => return Err(Outcome::TimedOut),
+        Err(e) => return Err(Outcome::Error(e.to_string())),
+    };
+    Ok(Transcript {
+        code: output.status().code(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        time: start.elapsed(),
+    })
+}
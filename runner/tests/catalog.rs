@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+use std::fs;
+
+use runner::{find, smoke, workspace_root, Demo, Outcome, Smoke, DEMOS};
+
+/// The workspace's members, from its `Cargo.toml`.
+fn members() -> Vec<String> {
+    let manifest = fs::read_to_string(workspace_root().join("Cargo.toml")).unwrap();
+    let start = manifest.find("members = [").unwrap();
+    let end = start + manifest[start..].find(']').unwrap();
+    manifest[start..end]
+        .lines()
+        .skip(1)
+        .map(|line| {
+            line.trim()
+                .trim_end_matches(',')
+                .trim_matches('"')
+                .to_string()
+        })
+        .filter(|member| !member.is_empty())
+        .collect()
+}
+
+#[test]
+fn every_program_is_listed() {
+    let listed: HashSet<&str> = DEMOS.iter().map(|demo| demo.package).collect();
+    for member in members() {
+        let dir = workspace_root().join(&member);
+        // The runner and xtask are tools for the workspace, not demos.
+        if !dir.join("src/main.rs").exists() || member == "runner" || member == "xtask" {
+            continue;
+        }
+        // Package names can differ from directory names: ch_01 is `ch_1`.
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        let package = manifest
+            .lines()
+            .find_map(|line| line.strip_prefix("name = "))
+            .unwrap()
+            .trim_matches('"');
+        assert!(listed.contains(package), "{} isn't in the catalog", package);
+    }
+}
+
+#[test]
+fn names_are_unique() {
+    let mut seen = HashSet::new();
+    for demo in DEMOS {
+        assert!(seen.insert(demo.bin), "{} is listed twice", demo.bin);
+    }
+}
+
+#[test]
+fn find_by_either_name() {
+    assert_eq!(find("ch_1").unwrap().bin, "ch_1");
+    assert_eq!(find("raytracer").unwrap().package, "raytracer");
+    assert!(find("no-such-demo").is_none());
+}
+
+#[test]
+fn smoke_runs_in_a_scratch_directory() {
+    // quickreplace reads and writes files, which must be in the temporary
+    // directory, not here.
+    let outcome = smoke(find("ch_02").unwrap());
+    assert!(outcome.passed(), "{}", outcome);
+    assert!(!std::path::Path::new("out.txt").exists());
+}
+
+#[test]
+fn smoke_reports_the_wrong_code() {
+    let demo = Demo {
+        bin: "ch_02",
+        package: "ch_02",
+        description: "quickreplace with no arguments",
+        smoke: Smoke {
+            args: &[],
+            stdin: "",
+            files: &[],
+            code: 0,
+        },
+    };
+    match smoke(&demo) {
+        Outcome::WrongCode { code, stderr } => {
+            assert_eq!(code, Some(2));
+            assert!(stderr.contains("Usage: quickreplace"), "{}", stderr);
+        }
+        other => panic!("expected the wrong code, got {}", other),
+    }
+}
+
+/// The workspace's `default-members`, from its `Cargo.toml`.
+fn default_members() -> Vec<String> {
+    let manifest = fs::read_to_string(workspace_root().join("Cargo.toml")).unwrap();
+    let start = manifest.find("default-members = [").unwrap();
+    let end = start + manifest[start..].find(']').unwrap();
+    manifest[start..end]
+        .lines()
+        .skip(1)
+        .map(|line| {
+            line.trim()
+                .trim_end_matches(',')
+                .trim_matches('"')
+                .to_string()
+        })
+        .filter(|member| !member.is_empty())
+        .collect()
+}
+
+#[test]
+fn default_members_build_alone() {
+    // `cargo build` without `--workspace` builds only the default members,
+    // so none of them may need a member outside the set, even for tests.
+    let members = members();
+    let defaults = default_members();
+    for member in &defaults {
+        assert!(members.contains(member), "{} isn't a member", member);
+        let manifest =
+            fs::read_to_string(workspace_root().join(member).join("Cargo.toml")).unwrap();
+        for line in manifest.lines() {
+            let Some(start) = line.find("path = \"../") else {
+                continue;
+            };
+            let rest = &line[start + "path = \"../".len()..];
+            let dependency = &rest[..rest.find('"').unwrap()];
+            assert!(
+                defaults.iter().any(|d| d == dependency),
+                "{} depends on {}, which isn't a default member",
+                member,
+                dependency
+            );
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// The output of every demo's smoke run, as insta snapshots, so that a
+// change to what a program prints shows up in review. Run
+// `cargo insta review` to accept one that was meant.
+//
+// Colors are stripped, and what changes from run to run or machine to
+// machine is replaced by a placeholder: timings and rates, thread counts,
+// heap sizes, hash map order, the scratch directory, the workspace's
+// path.
+
+use runner::{transcript, workspace_root, Demo, DEMOS};
+
+/// Demos whose whole output changes from run to run, with why. They're
+/// still smoke-tested; there's just nothing stable to snapshot.
+const UNSTABLE: &[(&str, &str)] = &[("git-toy", "it prints the latest commit")];
+
+fn render(demo: &Demo) -> String {
+    let run = transcript(demo).unwrap_or_else(|outcome| panic!("{}: {}", demo.bin, outcome));
+    let code = match run.code {
+        Some(code) => code.to_string(),
+        None => "killed by a signal".to_string(),
+    };
+    format!(
+        "exit: {}\n--- stdout ---\n{}--- stderr ---\n{}",
+        code, run.stdout, run.stderr
+    )
+}
+
+#[test]
+fn demo_output() {
+    let workspace = workspace_root().to_string_lossy().into_owned();
+    let mut settings = insta::Settings::clone_current();
+    settings.set_prepend_module_to_snapshot(false);
+    settings.add_filter(r"\x1b\[[0-9;]*[A-Za-z]", "");
+    settings.add_filter(&regex::escape(&workspace), "[workspace]");
+    settings.add_filter(r"/[^\s'\x22]*/\.tmp\w+", "[scratch]");
+    // Padding to line up a column of times varies with their widths, so
+    // it goes too.
+    settings.add_filter(r" *\b\d+(\.\d+)?\s?(ns|µs|us|ms|s)\b", " [time]");
+    settings.add_filter(r"\d+(\.\d+)? million/s", "[rate]");
+    settings.add_filter(r"on \d+ threads?\b", "on [n] threads");
+    // What's left on the heap depends on which thread got there first.
+    settings.add_filter(r" *\b\d+ bytes live", " [n] bytes live");
+    // A hash map's `Debug` output is in hash order, which is seeded afresh
+    // each run.
+    settings.add_filter(r#"\{"\w+": \d+(, "\w+": \d+)*\}"#, "{[hash order]}");
+    settings.bind(|| {
+        for demo in DEMOS {
+            if UNSTABLE.iter().any(|&(bin, _)| bin == demo.bin) {
+                continue;
+            }
+            insta::assert_snapshot!(demo.bin, render(demo));
+        }
+    });
+}
@@ -0,0 +1,38 @@
+// Encode and decode the same orders in each format.
+//
+// `serde-bench` itself prints a quick table; these benchmarks take the
+// time to be statistically careful about it.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_bench::model::Order;
+use serde_bench::{orders, Format};
+
+const ORDERS: usize = 1000;
+
+fn encode(c: &mut Criterion) {
+    let data = orders(ORDERS, 42);
+    let mut group = c.benchmark_group("encode");
+    group.throughput(Throughput::Elements(ORDERS as u64));
+    for format in Format::ALL {
+        group.bench_with_input(BenchmarkId::from_parameter(format), &data, |b, data| {
+            b.iter(|| format.encode(black_box(data)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let data = orders(ORDERS, 42);
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(ORDERS as u64));
+    for format in Format::ALL {
+        let bytes = format.encode(&data).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(format), &bytes, |b, bytes| {
+            b.iter(|| format.decode::<Vec<Order>>(black_box(bytes)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
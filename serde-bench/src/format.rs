@@ -0,0 +1,113 @@
+// The four formats, behind one interface.
+//
+// JSON is text, and self-describing: field names travel with the data.
+// Bincode is the opposite: values laid end to end in declaration order,
+// with nothing to say what they are, so the reader must have the writer's
+// exact type. MessagePack and CBOR are binary but self-describing, like a
+// compact JSON. MessagePack can also be written without field names,
+// bincode-style; here it's written with them, which is what makes it
+// tolerant of schema changes.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Bincode,
+    MessagePack,
+    Cbor,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    Bincode(bincode::Error),
+    MessagePackEncode(rmp_serde::encode::Error),
+    MessagePackDecode(rmp_serde::decode::Error),
+    CborEncode(ciborium::ser::Error<std::io::Error>),
+    CborDecode(ciborium::de::Error<std::io::Error>),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Format {
+    pub const ALL: [Format; 4] = [
+        Format::Json,
+        Format::Bincode,
+        Format::MessagePack,
+        Format::Cbor,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Bincode => "bincode",
+            Format::MessagePack => "MessagePack",
+            Format::Cbor => "CBOR",
+        }
+    }
+
+    /// Whether the encoding carries field names and types, so that a
+    /// reader with a different version of the data model can still make
+    /// sense of it.
+    pub fn is_self_describing(self) -> bool {
+        self != Format::Bincode
+    }
+
+    pub fn encode<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            Format::Json => serde_json::to_vec(value).map_err(Error::Json),
+            Format::Bincode => bincode::serialize(value).map_err(Error::Bincode),
+            Format::MessagePack => rmp_serde::to_vec_named(value).map_err(Error::MessagePackEncode),
+            Format::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes).map_err(Error::CborEncode)?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        match self {
+            Format::Json => serde_json::from_slice(bytes).map_err(Error::Json),
+            Format::Bincode => bincode::deserialize(bytes).map_err(Error::Bincode),
+            Format::MessagePack => rmp_serde::from_slice(bytes).map_err(Error::MessagePackDecode),
+            Format::Cbor => ciborium::from_reader(bytes).map_err(Error::CborDecode),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Json(e) => write!(f, "JSON: {}", e),
+            Error::Bincode(e) => write!(f, "bincode: {}", e),
+            Error::MessagePackEncode(e) => write!(f, "MessagePack: {}", e),
+            Error::MessagePackDecode(e) => write!(f, "MessagePack: {}", e),
+            Error::CborEncode(e) => write!(f, "CBOR: {}", e),
+            Error::CborDecode(e) => write!(f, "CBOR: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Json(e) => Some(e),
+            Error::Bincode(e) => Some(e),
+            Error::MessagePackEncode(e) => Some(e),
+            Error::MessagePackDecode(e) => Some(e),
+            Error::CborEncode(e) => Some(e),
+            Error::CborDecode(e) => Some(e),
+        }
+    }
+}
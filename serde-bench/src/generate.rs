@@ -0,0 +1,143 @@
+// Deterministic sample data.
+//
+// A small xorshift generator is plenty for making varied but repeatable
+// orders, and saves a dependency on `rand`.
+
+use std::collections::BTreeMap;
+
+use crate::model::{Address, Customer, LineItem, Order, Status};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn pick<'a>(&mut self, choices: &[&'a str]) -> &'a str {
+        choices[self.below(choices.len() as u64) as usize]
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+}
+
+const FIRST: &[&str] = &[
+    "Ada", "Grace", "Alan", "Barbara", "Ken", "Margaret", "Dennis", "Frances",
+];
+const LAST: &[&str] = &[
+    "Lovelace", "Hopper", "Turing", "Liskov", "Thompson", "Hamilton", "Ritchie", "Allen",
+];
+const STREETS: &[&str] = &[
+    "High Street",
+    "Station Road",
+    "Church Lane",
+    "Mill Way",
+    "Park Avenue",
+];
+const CITIES: &[&str] = &["Leeds", "Lyon", "Porto", "Kraków", "Malmö", "Cork", "Ghent"];
+const COUNTRIES: &[&str] = &["GB", "FR", "PT", "PL", "SE", "IE", "BE"];
+const TAGS: &[&str] = &[
+    "gift",
+    "fragile",
+    "sale",
+    "new",
+    "bulk",
+    "preorder",
+    "clearance",
+];
+const CARRIERS: &[&str] = &["Royal Mail", "DHL", "UPS", "La Poste"];
+const REASONS: &[&str] = &["customer request", "out of stock", "payment failed"];
+
+/// `count` orders, the same ones for the same `seed`.
+pub fn orders(count: usize, seed: u64) -> Vec<Order> {
+    // Xorshift gets stuck at zero.
+    let mut rng = Rng(seed.max(1));
+    (0..count as u64)
+        .map(|id| order(&mut rng, id + 1))
+        .collect()
+}
+
+fn order(rng: &mut Rng, id: u64) -> Order {
+    let placed_at = 1_700_000_000_000 + rng.below(30_000_000_000);
+    let items: Vec<LineItem> = (0..1 + rng.below(6))
+        .map(|_| LineItem {
+            sku: format!("SKU-{:06}", rng.below(1_000_000)),
+            // Mostly small quantities, with the odd bulk order.
+            quantity: 1 + if rng.chance(10) {
+                rng.below(500)
+            } else {
+                rng.below(4)
+            } as u32,
+            unit_price_cents: 99 + rng.below(50_000),
+            tags: (0..rng.below(3))
+                .map(|_| rng.pick(TAGS).to_string())
+                .collect(),
+        })
+        .collect();
+    let total: u64 = items
+        .iter()
+        .map(|item| item.unit_price_cents * u64::from(item.quantity))
+        .sum();
+    let status = match rng.below(5) {
+        0 => Status::Pending,
+        1 => Status::Paid {
+            amount_cents: total,
+        },
+        2 => Status::Shipped {
+            carrier: rng.pick(CARRIERS).to_string(),
+            tracking: format!("{:016X}", rng.next()),
+        },
+        3 => Status::Delivered {
+            at: placed_at + rng.below(1_000_000_000),
+        },
+        _ => Status::Cancelled {
+            reason: rng.pick(REASONS).to_string(),
+        },
+    };
+    let (first, last) = (rng.pick(FIRST), rng.pick(LAST));
+    let customer = Customer {
+        id: rng.below(100_000) as u32,
+        name: format!("{} {}", first, last),
+        email: format!("{}.{}@example.com", first, last).to_lowercase(),
+        address: rng.chance(80).then(|| Address {
+            street: format!("{} {}", 1 + rng.below(200), rng.pick(STREETS)),
+            city: rng.pick(CITIES).to_string(),
+            postcode: format!("{:05}", rng.below(100_000)),
+            country: rng.pick(COUNTRIES).to_string(),
+        }),
+    };
+    let notes = rng
+        .chance(25)
+        .then(|| "Please leave the parcel with a neighbour if nobody is home.".to_string());
+    let mut metadata = BTreeMap::new();
+    if rng.chance(50) {
+        metadata.insert(
+            "channel".to_string(),
+            rng.pick(&["web", "app", "phone"]).to_string(),
+        );
+    }
+    if rng.chance(20) {
+        metadata.insert(
+            "coupon".to_string(),
+            format!("SAVE{}", 5 * (1 + rng.below(6))),
+        );
+    }
+    Order {
+        id,
+        customer,
+        items,
+        status,
+        placed_at,
+        notes,
+        metadata,
+    }
+}
@@ -0,0 +1,17 @@
+// Comparing serialization formats on realistic data.
+//
+// Serde separates what gets serialized, a type's `Serialize` and
+// `Deserialize` impls, from how: the format crate's `Serializer` and
+// `Deserializer`. So one data model can be written as JSON, bincode,
+// MessagePack, and CBOR without changing a line of it, and the formats
+// can be compared fairly: bytes on the wire, time to encode, time to
+// decode, and how each copes when the data model changes.
+
+mod format;
+mod generate;
+mod measure;
+pub mod model;
+
+pub use format::{Error, Format};
+pub use generate::orders;
+pub use measure::{measure, Measurement};
@@ -0,0 +1,85 @@
+use std::{env, process};
+
+use serde_bench::{measure, orders, Format};
+
+#[derive(Debug)]
+struct Arguments {
+    orders: usize,
+    rounds: usize,
+    seed: u64,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            orders: 1000,
+            rounds: 10,
+            seed: 42,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut number = || -> u64 {
+                let value = args
+                    .next()
+                    .unwrap_or_else(|| usage_error(&format!("{} needs a number", arg)));
+                value
+                    .parse()
+                    .unwrap_or_else(|_| usage_error(&format!("bad number {:?}", value)))
+            };
+            match arg.as_str() {
+                "-n" | "--orders" => arguments.orders = number() as usize,
+                "--rounds" => arguments.rounds = number().max(1) as usize,
+                "--seed" => arguments.seed = number(),
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        arguments
+    }
+}
+
+fn print_usage() {
+    eprintln!("serde-bench - compare serialization formats on sample orders");
+    eprintln!("Usage: serde-bench [options]");
+    eprintln!("  -n, --orders N   how many orders to serialize (default 1000)");
+    eprintln!("      --rounds N   time the best of N runs (default 10)");
+    eprintln!("      --seed N     seed for the sample data (default 42)");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn main() {
+    let args = Arguments::parse();
+    let data = orders(args.orders, args.seed);
+    println!("{} orders, best of {} rounds", data.len(), args.rounds);
+    println!(
+        "{:<12} {:>10} {:>8} {:>11} {:>11}",
+        "format", "bytes", "vs JSON", "encode", "decode"
+    );
+    let mut json_size = None;
+    for format in Format::ALL {
+        let m = match measure(format, &data, args.rounds) {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(1);
+            }
+        };
+        let json_size = *json_size.get_or_insert(m.size);
+        println!(
+            "{:<12} {:>10} {:>7.0}% {:>11.2?} {:>11.2?}",
+            format,
+            m.size,
+            100.0 * m.size as f64 / json_size as f64,
+            m.encode,
+            m.decode
+        );
+    }
+}
@@ -0,0 +1,53 @@
+// Timing encodes and decodes.
+//
+// Each is run several times and the fastest run kept: the minimum is the
+// measurement least disturbed by whatever else the machine was doing.
+
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::format::{Format, Result};
+
+#[derive(Clone, Debug)]
+pub struct Measurement {
+    pub format: Format,
+    /// Encoded size in bytes.
+    pub size: usize,
+    pub encode: Duration,
+    pub decode: Duration,
+}
+
+/// Encode and decode `value` in `format`, `rounds` times each, checking
+/// that it comes back unchanged.
+///
+/// # Panics
+///
+/// Panics if the value doesn't round-trip, or if `rounds` is zero.
+pub fn measure<T>(format: Format, value: &T, rounds: usize) -> Result<Measurement>
+where
+    T: Serialize + DeserializeOwned + PartialEq,
+{
+    assert!(rounds > 0, "measuring needs at least one round");
+    let mut bytes = Vec::new();
+    let mut encode = Duration::MAX;
+    for _ in 0..rounds {
+        let start = Instant::now();
+        bytes = format.encode(value)?;
+        encode = encode.min(start.elapsed());
+    }
+    let mut decode = Duration::MAX;
+    for _ in 0..rounds {
+        let start = Instant::now();
+        let decoded: T = format.decode(&bytes)?;
+        decode = decode.min(start.elapsed());
+        assert!(decoded == *value, "{} didn't round-trip", format);
+    }
+    Ok(Measurement {
+        format,
+        size: bytes.len(),
+        encode,
+        decode,
+    })
+}
@@ -0,0 +1,68 @@
+// The data model: an online shop's orders.
+//
+// It has what real data has: nested structs, vectors of them, optional
+// fields, an enum whose variants carry different data, a map, strings of
+// varied length, and integers both small and large.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: u64,
+    pub customer: Customer,
+    pub items: Vec<LineItem>,
+    pub status: Status,
+    /// Milliseconds since the Unix epoch.
+    pub placed_at: u64,
+    pub notes: Option<String>,
+    /// Added after the first version of the model. Older data has no
+    /// metadata, so it reads as an empty map; see `tests/evolution.rs`.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Customer {
+    pub id: u32,
+    pub name: String,
+    pub email: String,
+    pub address: Option<Address>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub postcode: String,
+    pub country: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineItem {
+    pub sku: String,
+    pub quantity: u32,
+    /// Prices are integers to keep floating point out of the
+    /// comparison: every format round-trips integers exactly.
+    pub unit_price_cents: u64,
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Status {
+    Pending,
+    Paid { amount_cents: u64 },
+    Shipped { carrier: String, tracking: String },
+    Delivered { at: u64 },
+    Cancelled { reason: String },
+}
+
+impl Order {
+    pub fn total_cents(&self) -> u64 {
+        self.items
+            .iter()
+            .map(|item| item.unit_price_cents * u64::from(item.quantity))
+            .sum()
+    }
+}
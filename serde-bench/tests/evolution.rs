@@ -0,0 +1,246 @@
+// What happens to stored data when the data model changes.
+//
+// Each test writes values of one version of a type and reads them back as
+// another. The self-describing formats match fields by name, so they
+// cope with added, removed, and reordered fields, given the right
+// attributes. Bincode matches fields by position and trusts the reader to
+// know the layout, so almost any change breaks it, sometimes silently.
+
+use serde::{Deserialize, Serialize};
+use serde_bench::model::{Customer, LineItem, Order, Status};
+use serde_bench::{orders, Format};
+
+fn self_describing() -> impl Iterator<Item = Format> {
+    Format::ALL.into_iter().filter(|f| f.is_self_describing())
+}
+
+/// `Order` as it was before `metadata` was added.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct OrderV1 {
+    id: u64,
+    customer: Customer,
+    items: Vec<LineItem>,
+    status: Status,
+    placed_at: u64,
+    notes: Option<String>,
+}
+
+impl From<&Order> for OrderV1 {
+    fn from(order: &Order) -> OrderV1 {
+        OrderV1 {
+            id: order.id,
+            customer: order.customer.clone(),
+            items: order.items.clone(),
+            status: order.status.clone(),
+            placed_at: order.placed_at,
+            notes: order.notes.clone(),
+        }
+    }
+}
+
+#[test]
+fn new_reader_old_data() {
+    let current = orders(50, 21);
+    let old: Vec<OrderV1> = current.iter().map(OrderV1::from).collect();
+    for format in self_describing() {
+        let read: Vec<Order> = format.decode(&format.encode(&old).unwrap()).unwrap();
+        // Everything matches, except that the metadata is empty: the
+        // field's `#[serde(default)]` filled it in.
+        for (read, current) in read.iter().zip(&current) {
+            assert!(read.metadata.is_empty());
+            assert_eq!(OrderV1::from(read), OrderV1::from(current));
+        }
+    }
+    let bytes = Format::Bincode.encode(&old).unwrap();
+    assert!(Format::Bincode.decode::<Vec<Order>>(&bytes).is_err());
+}
+
+#[test]
+fn old_reader_new_data() {
+    let current = orders(50, 22);
+    for format in self_describing() {
+        // Fields the reader doesn't know are skipped.
+        let read: Vec<OrderV1> = format.decode(&format.encode(&current).unwrap()).unwrap();
+        assert_eq!(read, current.iter().map(OrderV1::from).collect::<Vec<_>>());
+    }
+    // Bincode reads the first order's metadata as the start of the second
+    // order, and derails.
+    let bytes = Format::Bincode.encode(&current).unwrap();
+    assert!(Format::Bincode.decode::<Vec<OrderV1>>(&bytes).is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Contact {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ContactWithPhone {
+    id: u32,
+    name: String,
+    phone: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ContactWithRequiredFlag {
+    id: u32,
+    name: String,
+    vip: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictContact {
+    id: u32,
+    name: String,
+}
+
+fn contacts() -> Vec<Contact> {
+    vec![
+        Contact {
+            id: 1,
+            name: "Ada".to_string(),
+        },
+        Contact {
+            id: 2,
+            name: "Grace".to_string(),
+        },
+    ]
+}
+
+#[test]
+fn missing_options_are_none_but_missing_values_are_errors() {
+    for format in self_describing() {
+        let bytes = format.encode(&contacts()).unwrap();
+        let read: Vec<ContactWithPhone> = format.decode(&bytes).unwrap();
+        assert!(read.iter().all(|c| c.phone.is_none()));
+
+        let error = format
+            .decode::<Vec<ContactWithRequiredFlag>>(&bytes)
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("missing field `vip`"),
+            "{}",
+            error
+        );
+    }
+}
+
+#[test]
+fn deny_unknown_fields() {
+    let with_phone = vec![ContactWithPhone {
+        id: 1,
+        name: "Ada".to_string(),
+        phone: None,
+    }];
+    for format in self_describing() {
+        let bytes = format.encode(&with_phone).unwrap();
+        let error = format.decode::<Vec<StrictContact>>(&bytes).unwrap_err();
+        assert!(
+            error.to_string().contains("unknown field `phone`"),
+            "{}",
+            error
+        );
+    }
+}
+
+#[test]
+fn bincode_ignores_trailing_bytes() {
+    // One value on its own, read with a type that has fewer fields,
+    // decodes without complaint: the extra bytes are simply never read.
+    let bytes = Format::Bincode
+        .encode(&ContactWithPhone {
+            id: 1,
+            name: "Ada".to_string(),
+            phone: Some("555-0100".to_string()),
+        })
+        .unwrap();
+    let read: Contact = Format::Bincode.decode(&bytes).unwrap();
+    assert_eq!(read, contacts().remove(0));
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Name {
+    first: String,
+    last: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct NameReordered {
+    last: String,
+    first: String,
+}
+
+#[test]
+fn reordered_fields() {
+    let name = Name {
+        first: "Grace".to_string(),
+        last: "Hopper".to_string(),
+    };
+    for format in self_describing() {
+        let read: NameReordered = format.decode(&format.encode(&name).unwrap()).unwrap();
+        assert_eq!(
+            (read.first.as_str(), read.last.as_str()),
+            ("Grace", "Hopper")
+        );
+    }
+    // Same types in a different order: bincode can't tell, and swaps them.
+    let read: NameReordered = Format::Bincode
+        .decode(&Format::Bincode.encode(&name).unwrap())
+        .unwrap();
+    assert_eq!(
+        (read.first.as_str(), read.last.as_str()),
+        ("Hopper", "Grace")
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Renamed {
+    id: u32,
+    #[serde(alias = "name")]
+    display_name: String,
+}
+
+#[test]
+fn renamed_fields_with_an_alias() {
+    for format in self_describing() {
+        let read: Vec<Renamed> = format.decode(&format.encode(&contacts()).unwrap()).unwrap();
+        assert_eq!(read[1].display_name, "Grace");
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Event {
+    Created,
+    Renamed(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum EventV2 {
+    Created,
+    Renamed(String),
+    Archived,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum TolerantEvent {
+    Created,
+    Renamed(String),
+    #[serde(other)]
+    Unknown,
+}
+
+#[test]
+fn new_enum_variants() {
+    let events = vec![EventV2::Created, EventV2::Archived];
+    for format in Format::ALL {
+        let bytes = format.encode(&events).unwrap();
+        assert!(format.decode::<Vec<Event>>(&bytes).is_err(), "{}", format);
+        // A catch-all unit variant absorbs variants the reader doesn't
+        // know. Bincode identifies variants by index, so for it, this
+        // works only while new variants are added at the end.
+        let read: Vec<TolerantEvent> = format.decode(&bytes).unwrap();
+        assert_eq!(read, [TolerantEvent::Created, TolerantEvent::Unknown]);
+    }
+}
@@ -0,0 +1,81 @@
+use serde_bench::model::{Order, Status};
+use serde_bench::{measure, orders, Format};
+
+#[test]
+fn every_format_round_trips() {
+    let data = orders(200, 7);
+    for format in Format::ALL {
+        let bytes = format.encode(&data).unwrap();
+        let decoded: Vec<Order> = format.decode(&bytes).unwrap();
+        assert_eq!(decoded, data, "{}", format);
+    }
+}
+
+#[test]
+fn sample_data_is_deterministic_and_varied() {
+    assert_eq!(orders(50, 1), orders(50, 1));
+    assert_ne!(orders(50, 1), orders(50, 2));
+    // A zero seed would stall the generator.
+    assert_ne!(orders(2, 0)[0], orders(2, 0)[1]);
+
+    let data = orders(500, 3);
+    assert_eq!(data.len(), 500);
+    assert!(data
+        .iter()
+        .enumerate()
+        .all(|(i, order)| order.id == i as u64 + 1));
+    let count = |f: fn(&Order) -> bool| data.iter().filter(|o| f(o)).count();
+    assert!(count(|o| o.status == Status::Pending) > 50);
+    assert!(count(|o| matches!(o.status, Status::Shipped { .. })) > 50);
+    assert!(count(|o| o.customer.address.is_none()) > 25);
+    assert!(count(|o| o.notes.is_some()) > 50);
+    assert!(count(|o| o.metadata.is_empty()) > 50);
+    assert!(count(|o| o.items.len() > 3) > 50);
+    for order in &data {
+        if let Status::Paid { amount_cents } = order.status {
+            assert_eq!(amount_cents, order.total_cents());
+        }
+    }
+}
+
+#[test]
+fn binary_formats_are_smaller() {
+    let data = orders(300, 11);
+    let size = |format: Format| format.encode(&data).unwrap().len();
+    let json = size(Format::Json);
+    for format in [Format::Bincode, Format::MessagePack, Format::Cbor] {
+        assert!(size(format) < json, "{} isn't smaller than JSON", format);
+    }
+    // With no field names or type tags, bincode is smallest of all.
+    for format in [Format::MessagePack, Format::Cbor] {
+        assert!(size(Format::Bincode) < size(format));
+    }
+}
+
+#[test]
+fn json_is_readable() {
+    let order = &orders(1, 5)[0];
+    let json = String::from_utf8(Format::Json.encode(order).unwrap()).unwrap();
+    assert!(json.starts_with(r#"{"id":1,"customer":{"#), "{}", json);
+    assert!(json.contains(&format!(r#""email":"{}""#, order.customer.email)));
+}
+
+#[test]
+fn measurements() {
+    let data = orders(20, 9);
+    for format in Format::ALL {
+        let m = measure(format, &data, 3).unwrap();
+        assert_eq!(m.format, format);
+        assert_eq!(m.size, format.encode(&data).unwrap().len());
+    }
+}
+
+#[test]
+fn decode_errors_name_the_format() {
+    for format in Format::ALL {
+        let error = format
+            .decode::<Vec<Order>>(b"\xff\xff\xff\xff\xff")
+            .unwrap_err();
+        assert!(error.to_string().starts_with(format.name()), "{}", error);
+    }
+}
@@ -0,0 +1,31 @@
+// Short codes: generating them, and checking the ones clients choose.
+
+use std::hash::{BuildHasher, RandomState};
+
+const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+pub(crate) const GENERATED_LEN: usize = 7;
+
+/// Paths the service uses itself, which a link can't be given.
+const RESERVED: &[&str] = &["shorten"];
+
+/// A code for `url`. The hasher is seeded randomly for each process, so
+/// codes aren't predictable, and `attempt` gives a different code each
+/// time around when one turns out to be taken. Seven base-62 digits make
+/// 3.5 trillion codes, so retries are rare.
+pub(crate) fn generate(hasher: &RandomState, url: &str, attempt: u32) -> String {
+    let mut n = hasher.hash_one((url, attempt));
+    let mut code = String::with_capacity(GENERATED_LEN);
+    for _ in 0..GENERATED_LEN {
+        code.push(ALPHABET[(n % 62) as usize] as char);
+        n /= 62;
+    }
+    code
+}
+
+pub(crate) fn valid_custom(code: &str) -> bool {
+    (3..=32).contains(&code.len())
+        && code
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        && !RESERVED.contains(&code)
+}
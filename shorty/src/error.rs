@@ -0,0 +1,93 @@
+use std::fmt;
+use std::time::Duration;
+
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The URL to shorten isn't an absolute `http` or `https` URL.
+    InvalidUrl(&'static str),
+    /// A custom code with characters other than letters, digits, `-` and
+    /// `_`, or of the wrong length, or one reserved for the service.
+    InvalidCode,
+    /// The custom code is already taken by a different URL.
+    CodeTaken(String),
+    NotFound,
+    /// The client has made too many requests, and may try again after
+    /// the given time.
+    RateLimited(Duration),
+    Storage(kvstore::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidUrl(reason) => write!(f, "invalid URL: {}", reason),
+            Error::InvalidCode => write!(
+                f,
+                "codes must be 3 to 32 letters, digits, '-' or '_', and not a reserved word"
+            ),
+            Error::CodeTaken(code) => write!(f, "the code '{}' is already in use", code),
+            Error::NotFound => write!(f, "no such link"),
+            Error::RateLimited(wait) => {
+                write!(f, "too many requests; try again in {:.1?}", wait)
+            }
+            Error::Storage(e) => write!(f, "storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Storage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<kvstore::Error> for Error {
+    fn from(e: kvstore::Error) -> Error {
+        Error::Storage(e)
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Errors go back to the client as JSON, with a status code to match.
+/// Storage errors are the server's problem, so their details are logged
+/// rather than sent.
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Error::InvalidUrl(_) | Error::InvalidCode => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::CodeTaken(_) => StatusCode::CONFLICT,
+            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let message = match &self {
+            Error::Storage(e) => {
+                eprintln!("Error: {}", e);
+                "internal error".to_string()
+            }
+            _ => self.to_string(),
+        };
+        let mut response = (status, Json(ErrorBody { error: message })).into_response();
+        if let Error::RateLimited(wait) = self {
+            // Retry-After is in whole seconds; round up, so a client that
+            // waits exactly that long isn't turned away again.
+            let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, seconds.max(1).into());
+        }
+        response
+    }
+}
@@ -0,0 +1,34 @@
+// A URL shortener: an axum web service that keeps its links in the
+// kvstore chapter's log-structured store.
+//
+//     POST /shorten   {"url": "https://...", "code": "optional-custom-code"}
+//     GET  /:code     redirects to the URL
+//
+// The storage code is ordinary blocking Rust, kept apart from the async
+// handlers, which hand it to tokio's blocking thread pool.
+
+mod codes;
+mod error;
+mod limit;
+mod links;
+mod routes;
+
+pub use error::Error;
+pub use limit::{RateLimit, RateLimiter};
+pub use links::{Links, Shortened};
+pub use routes::{app, AppState};
+
+use std::net::SocketAddr;
+
+use tokio::net::TcpListener;
+
+/// Serve `app` on `listener` until the process exits. The rate limiter
+/// needs to know each client's address, so this is the way to run the
+/// router, rather than `axum::serve` on its own.
+pub async fn serve(listener: TcpListener, app: axum::Router) -> std::io::Result<()> {
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+}
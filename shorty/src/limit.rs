@@ -0,0 +1,114 @@
+// Per-client rate limiting, with a token bucket for each IP address.
+//
+// Each client's bucket holds up to `burst` tokens and refills at
+// `per_second`; every request takes a token, and a client with an empty
+// bucket is told how long to wait. A full bucket is the same as no bucket,
+// so idle clients' buckets can be thrown away to keep the map small.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::error::Error;
+use crate::routes::AppState;
+
+/// How many buckets to keep before sweeping out the full ones.
+const SWEEP_AT: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub per_second: f64,
+}
+
+impl RateLimit {
+    /// `n` requests a minute, all of which can come at once.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is zero: a bucket that never refills would tell its client
+    /// to wait forever.
+    pub fn per_minute(n: u32) -> RateLimit {
+        assert!(n > 0, "a rate limit must allow some requests");
+        RateLimit {
+            burst: n,
+            per_second: n as f64 / 60.0,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    /// # Panics
+    ///
+    /// If `limit.per_second` isn't a positive, finite rate.
+    pub fn new(limit: RateLimit) -> RateLimiter {
+        assert!(
+            limit.per_second > 0.0 && limit.per_second.is_finite(),
+            "bad refill rate {}",
+            limit.per_second
+        );
+        RateLimiter {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token from `client`'s bucket, or return how long until
+    /// there'll be one. Taking the time as an argument lets tests run the
+    /// clock without sleeping.
+    pub fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let RateLimit { burst, per_second } = self.limit;
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= SWEEP_AT {
+            buckets.retain(|_, bucket| refill(bucket, now, self.limit) < burst as f64);
+        }
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: burst as f64,
+            updated: now,
+        });
+        refill(bucket, now, self.limit);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        }
+    }
+}
+
+/// Bring a bucket's tokens up to date, and return the new count.
+fn refill(bucket: &mut Bucket, now: Instant, limit: RateLimit) -> f64 {
+    let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(limit.burst as f64);
+    bucket.updated = now;
+    bucket.tokens
+}
+
+/// Middleware that turns away clients who are over the limit with a
+/// `429 Too Many Requests`.
+pub(crate) async fn rate_limit(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match state.limiter.check(client.ip(), Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => Error::RateLimited(wait).into_response(),
+    }
+}
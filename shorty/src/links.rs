@@ -0,0 +1,153 @@
+// The links themselves, kept in a `KvStore` under two kinds of key:
+//
+//     code:<code>  ->  the URL
+//     url:<url>    ->  the first code generated for it
+//
+// The second lets shortening the same URL twice give back the same code,
+// rather than filling the store with duplicates.
+
+use std::hash::RandomState;
+use std::path::Path;
+use std::sync::Mutex;
+
+use kvstore::KvStore;
+
+use crate::codes;
+use crate::error::Error;
+
+const MAX_URL_LEN: usize = 2048;
+
+pub struct Links {
+    store: KvStore,
+    hasher: RandomState,
+    /// Held while creating a link, so that two requests can't both see a
+    /// code as free and then both take it.
+    writing: Mutex<()>,
+}
+
+/// The result of shortening a URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shortened {
+    pub code: String,
+    /// False if the link already existed.
+    pub created: bool,
+}
+
+fn code_key(code: &str) -> Vec<u8> {
+    format!("code:{}", code).into_bytes()
+}
+
+fn url_key(url: &str) -> Vec<u8> {
+    format!("url:{}", url).into_bytes()
+}
+
+impl Links {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Links, Error> {
+        Ok(Links::new(KvStore::open(dir)?))
+    }
+
+    pub fn new(store: KvStore) -> Links {
+        Links {
+            store,
+            hasher: RandomState::new(),
+            writing: Mutex::new(()),
+        }
+    }
+
+    /// The URL `code` stands for.
+    pub fn resolve(&self, code: &str) -> Result<String, Error> {
+        let url = self.store.get(&code_key(code))?.ok_or(Error::NotFound)?;
+        // Only valid UTF-8 is ever stored, but the files could have been
+        // tampered with.
+        String::from_utf8(url).map_err(|_| Error::NotFound)
+    }
+
+    /// Make a short code for `url`: `custom` if it's given and available,
+    /// or else the code `url` already has, or else a new one.
+    pub fn shorten(&self, url: &str, custom: Option<&str>) -> Result<Shortened, Error> {
+        check_url(url)?;
+        let _writing = self.writing.lock().unwrap();
+
+        if let Some(code) = custom {
+            if !codes::valid_custom(code) {
+                return Err(Error::InvalidCode);
+            }
+            return match self.store.get(&code_key(code))? {
+                Some(existing) if existing == url.as_bytes() => Ok(Shortened {
+                    code: code.to_string(),
+                    created: false,
+                }),
+                Some(_) => Err(Error::CodeTaken(code.to_string())),
+                None => {
+                    self.store.put(&code_key(code), url.as_bytes())?;
+                    Ok(Shortened {
+                        code: code.to_string(),
+                        created: true,
+                    })
+                }
+            };
+        }
+
+        if let Some(code) = self.store.get(&url_key(url))? {
+            if let Ok(code) = String::from_utf8(code) {
+                return Ok(Shortened {
+                    code,
+                    created: false,
+                });
+            }
+        }
+        let mut attempt = 0;
+        let code = loop {
+            let code = codes::generate(&self.hasher, url, attempt);
+            if !self.store.contains_key(&code_key(&code)) {
+                break code;
+            }
+            attempt += 1;
+        };
+        // The code goes in first: a crash between the two writes leaves a
+        // working link that just isn't reused, rather than a dangling one.
+        self.store.put(&code_key(&code), url.as_bytes())?;
+        self.store.put(&url_key(url), code.as_bytes())?;
+        Ok(Shortened {
+            code,
+            created: true,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.store
+            .keys()
+            .iter()
+            .filter(|key| key.starts_with(b"code:"))
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Accept only absolute `http` and `https` URLs with a host, so the
+/// service can't be used to redirect to `javascript:` and the like.
+fn check_url(url: &str) -> Result<(), Error> {
+    if url.len() > MAX_URL_LEN {
+        return Err(Error::InvalidUrl("longer than 2048 bytes"));
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(Error::InvalidUrl("contains whitespace"));
+    }
+    let (scheme, rest) = url
+        .split_once("://")
+        .ok_or(Error::InvalidUrl("not an absolute URL"))?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return Err(Error::InvalidUrl(
+            "only http and https URLs can be shortened",
+        ));
+    }
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    if host.is_empty() || host.starts_with(':') {
+        return Err(Error::InvalidUrl("missing host"));
+    }
+    Ok(())
+}
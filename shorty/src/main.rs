@@ -0,0 +1,95 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::{env, process};
+
+use shorty::{AppState, Links, RateLimit, RateLimiter};
+
+#[derive(Debug)]
+struct Arguments {
+    addr: SocketAddr,
+    dir: String,
+    base_url: Option<String>,
+    per_minute: u32,
+}
+
+impl Arguments {
+    fn parse() -> Self {
+        let mut arguments = Arguments {
+            addr: SocketAddr::from(([127, 0, 0, 1], 3000)),
+            dir: String::from(".shorty"),
+            base_url: None,
+            per_minute: 30,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = |what: &str| {
+                args.next()
+                    .unwrap_or_else(|| usage_error(&format!("{} needs {}", arg, what)))
+            };
+            match arg.as_str() {
+                "-a" | "--addr" => {
+                    let addr = value("an address");
+                    arguments.addr = addr
+                        .parse()
+                        .unwrap_or_else(|_| usage_error(&format!("bad address {}", addr)));
+                }
+                "-C" | "--dir" => arguments.dir = value("a directory"),
+                "--base-url" => arguments.base_url = Some(value("a URL")),
+                "--rate" => {
+                    let rate = value("a number");
+                    arguments.per_minute = rate
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .unwrap_or_else(|| usage_error(&format!("bad rate {}", rate)));
+                }
+                "-h" | "--help" => {
+                    print_usage();
+                    process::exit(0);
+                }
+                _ => usage_error(&format!("unknown argument {}", arg)),
+            }
+        }
+        arguments
+    }
+}
+
+fn print_usage() {
+    eprintln!("shorty - a URL shortening service");
+    eprintln!("Usage: shorty [options]");
+    eprintln!("  -a, --addr ADDR     listen on ADDR (default 127.0.0.1:3000)");
+    eprintln!("  -C, --dir DIR       keep links in DIR (default .shorty)");
+    eprintln!("      --base-url URL  prefix for short URLs (default http://ADDR)");
+    eprintln!("      --rate N        allow each client N new links a minute (default 30)");
+}
+
+fn usage_error(message: &str) -> ! {
+    print_usage();
+    eprintln!("Error: {}", message);
+    process::exit(2);
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", e);
+    process::exit(1);
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Arguments::parse();
+    let links = Links::open(&args.dir).unwrap_or_else(|e| fail(e));
+    let state = AppState {
+        links: Arc::new(links),
+        limiter: Arc::new(RateLimiter::new(RateLimit::per_minute(args.per_minute))),
+        base_url: args
+            .base_url
+            .unwrap_or_else(|| format!("http://{}", args.addr)),
+    };
+    let listener = tokio::net::TcpListener::bind(args.addr)
+        .await
+        .unwrap_or_else(|e| fail(format!("failed to listen on {}: {}", args.addr, e)));
+    eprintln!("listening on http://{}", args.addr);
+    shorty::serve(listener, shorty::app(state))
+        .await
+        .unwrap_or_else(|e| fail(e));
+}
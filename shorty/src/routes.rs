@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::Redirect;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::limit::{self, RateLimiter};
+use crate::links::Links;
+
+/// What the handlers share.
+#[derive(Clone)]
+pub struct AppState {
+    pub links: Arc<Links>,
+    pub limiter: Arc<RateLimiter>,
+    /// Prepended to codes to make the short URLs in responses, for
+    /// example `https://sho.rt`.
+    pub base_url: String,
+}
+
+/// The service's routes. Only shortening is rate-limited: redirects are
+/// cheap, and many people behind one address may follow the same link.
+pub fn app(state: AppState) -> Router {
+    let limited = middleware::from_fn_with_state(state.clone(), limit::rate_limit);
+    Router::new()
+        .route("/shorten", post(shorten).route_layer(limited))
+        .route("/:code", get(redirect))
+        .with_state(state)
+}
+
+#[derive(Deserialize)]
+struct ShortenRequest {
+    url: String,
+    code: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ShortenResponse {
+    code: String,
+    short_url: String,
+}
+
+async fn shorten(
+    State(state): State<AppState>,
+    Json(request): Json<ShortenRequest>,
+) -> Result<(StatusCode, Json<ShortenResponse>), Error> {
+    let links = state.links.clone();
+    let shortened =
+        tokio::task::spawn_blocking(move || links.shorten(&request.url, request.code.as_deref()))
+            .await
+            .expect("shorten panicked")?;
+    let status = if shortened.created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    let short_url = format!(
+        "{}/{}",
+        state.base_url.trim_end_matches('/'),
+        shortened.code
+    );
+    Ok((
+        status,
+        Json(ShortenResponse {
+            code: shortened.code,
+            short_url,
+        }),
+    ))
+}
+
+/// A temporary redirect, so that browsers come back through the service
+/// each time rather than caching the destination forever.
+async fn redirect(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Redirect, Error> {
+    let links = state.links.clone();
+    let url = tokio::task::spawn_blocking(move || links.resolve(&code))
+        .await
+        .expect("resolve panicked")?;
+    Ok(Redirect::temporary(&url))
+}
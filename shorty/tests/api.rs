@@ -0,0 +1,186 @@
+// The service's routes, driven in-process with `tower::ServiceExt::oneshot`,
+// with no sockets involved. `MockConnectInfo` stands in for the client
+// address the rate limiter would get from a real connection.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::connect_info::MockConnectInfo;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use shorty::{AppState, Links, RateLimit, RateLimiter};
+use tower::ServiceExt;
+
+fn app(links: Links, limit: RateLimit) -> Router {
+    let state = AppState {
+        links: Arc::new(links),
+        limiter: Arc::new(RateLimiter::new(limit)),
+        base_url: "https://sho.rt/".to_string(),
+    };
+    shorty::app(state).layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4321))))
+}
+
+fn generous() -> RateLimit {
+    RateLimit::per_minute(1000)
+}
+
+async fn shorten(app: &Router, body: Value) -> (StatusCode, Value) {
+    let request = Request::post("/shorten")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn follow(app: &Router, code: &str) -> (StatusCode, Option<String>) {
+    let request = Request::get(format!("/{}", code))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let location = response
+        .headers()
+        .get(header::LOCATION)
+        .map(|value| value.to_str().unwrap().to_string());
+    (response.status(), location)
+}
+
+#[tokio::test]
+async fn shorten_and_redirect() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = app(Links::open(dir.path()).unwrap(), generous());
+
+    let (status, body) = shorten(&app, json!({ "url": "https://www.rust-lang.org/learn" })).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let code = body["code"].as_str().unwrap().to_string();
+    assert_eq!(code.len(), 7);
+    assert_eq!(body["short_url"], format!("https://sho.rt/{}", code));
+
+    assert_eq!(
+        follow(&app, &code).await,
+        (
+            StatusCode::TEMPORARY_REDIRECT,
+            Some("https://www.rust-lang.org/learn".to_string())
+        )
+    );
+
+    // The same URL gets the same code back.
+    let (status, again) = shorten(&app, json!({ "url": "https://www.rust-lang.org/learn" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(again["code"], code.as_str());
+
+    assert_eq!(follow(&app, "nope").await, (StatusCode::NOT_FOUND, None));
+}
+
+#[tokio::test]
+async fn custom_codes() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = app(Links::open(dir.path()).unwrap(), generous());
+
+    let (status, body) = shorten(
+        &app,
+        json!({ "url": "https://crates.io", "code": "crates" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["short_url"], "https://sho.rt/crates");
+    let (status, _) = shorten(
+        &app,
+        json!({ "url": "https://crates.io", "code": "crates" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = shorten(&app, json!({ "url": "https://docs.rs", "code": "crates" })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(body["error"].as_str().unwrap().contains("crates"));
+
+    for bad in ["ab", "has space", "a/b", "shorten"] {
+        let (status, _) = shorten(&app, json!({ "url": "https://docs.rs", "code": bad })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{:?}", bad);
+    }
+}
+
+#[tokio::test]
+async fn rejects_bad_urls() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = app(Links::open(dir.path()).unwrap(), generous());
+    for url in [
+        "not a url",
+        "ftp://example.com/file",
+        "javascript:alert(1)",
+        "https://",
+        "https:///path",
+        "http://user@/",
+    ] {
+        let (status, body) = shorten(&app, json!({ "url": url })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{:?}", url);
+        assert!(body["error"].as_str().unwrap().starts_with("invalid URL"));
+    }
+    let long = format!("https://example.com/{}", "a".repeat(3000));
+    let (status, _) = shorten(&app, json!({ "url": long })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Malformed JSON is turned away by axum's extractor before it gets
+    // as far as our code.
+    let (status, _) = shorten(&app, json!({ "link": "https://example.com" })).await;
+    assert!(status.is_client_error());
+}
+
+#[tokio::test]
+async fn rate_limited() {
+    let dir = tempfile::tempdir().unwrap();
+    let limit = RateLimit {
+        burst: 3,
+        per_second: 0.5,
+    };
+    let app = app(Links::open(dir.path()).unwrap(), limit);
+    for i in 0..3 {
+        let (status, _) =
+            shorten(&app, json!({ "url": format!("https://example.com/{}", i) })).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let request = Request::post("/shorten")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(r#"{"url": "https://example.com/3"}"#))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry: u64 = response.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=2).contains(&retry), "{}", retry);
+
+    // Redirects aren't limited.
+    for _ in 0..10 {
+        assert_eq!(follow(&app, "missing").await.0, StatusCode::NOT_FOUND);
+    }
+}
+
+#[tokio::test]
+async fn links_survive_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let code = {
+        let app = app(Links::open(dir.path()).unwrap(), generous());
+        let (_, body) = shorten(&app, json!({ "url": "https://example.com/kept" })).await;
+        body["code"].as_str().unwrap().to_string()
+    };
+    let links = Links::open(dir.path()).unwrap();
+    assert_eq!(links.len(), 1);
+    let app = app(links, generous());
+    assert_eq!(
+        follow(&app, &code).await.1.as_deref(),
+        Some("https://example.com/kept")
+    );
+}
@@ -0,0 +1,57 @@
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use shorty::{RateLimit, RateLimiter};
+
+#[test]
+fn token_bucket() {
+    let limiter = RateLimiter::new(RateLimit {
+        burst: 2,
+        per_second: 1.0,
+    });
+    let alice: IpAddr = "192.0.2.1".parse().unwrap();
+    let bob: IpAddr = "2001:db8::1".parse().unwrap();
+    let start = Instant::now();
+
+    assert_eq!(limiter.check(alice, start), Ok(()));
+    assert_eq!(limiter.check(alice, start), Ok(()));
+    let wait = limiter.check(alice, start).unwrap_err();
+    assert!(wait > Duration::from_millis(990) && wait <= Duration::from_secs(1));
+
+    // Bob has his own bucket.
+    assert_eq!(limiter.check(bob, start), Ok(()));
+
+    // Half a second refills half a token; a full second, a whole one.
+    let wait = limiter
+        .check(alice, start + Duration::from_millis(500))
+        .unwrap_err();
+    assert!(wait <= Duration::from_millis(500));
+    assert_eq!(limiter.check(alice, start + Duration::from_secs(1)), Ok(()));
+    assert!(limiter
+        .check(alice, start + Duration::from_secs(1))
+        .is_err());
+
+    // Buckets never hold more than the burst, however long they wait.
+    let later = start + Duration::from_secs(3600);
+    assert_eq!(limiter.check(alice, later), Ok(()));
+    assert_eq!(limiter.check(alice, later), Ok(()));
+    assert!(limiter.check(alice, later).is_err());
+}
+
+#[test]
+#[should_panic(expected = "must allow some requests")]
+fn zero_per_minute() {
+    RateLimit::per_minute(0);
+}
+
+#[test]
+fn refill_rate_must_be_positive() {
+    for per_second in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let limit = RateLimit {
+            burst: 1,
+            per_second,
+        };
+        let result = std::panic::catch_unwind(|| RateLimiter::new(limit));
+        assert!(result.is_err(), "accepted {}", per_second);
+    }
+}
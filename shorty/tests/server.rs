@@ -0,0 +1,53 @@
+// One request over a real socket, to check that `serve` supplies the
+// client address the rate limiter needs.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+
+use shorty::{AppState, Links, RateLimit, RateLimiter};
+
+fn request(addr: std::net::SocketAddr, text: String) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(text.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn over_tcp() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = AppState {
+        links: Arc::new(Links::open(dir.path()).unwrap()),
+        limiter: Arc::new(RateLimiter::new(RateLimit::per_minute(10))),
+        base_url: "http://localhost".to_string(),
+    };
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(shorty::serve(listener, shorty::app(state)));
+
+    let body = r#"{"url": "https://example.com/", "code": "example"}"#;
+    let post = format!(
+        "POST /shorten HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let response = tokio::task::spawn_blocking(move || request(addr, post))
+        .await
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 201"), "{}", response);
+    assert!(response.contains(r#""short_url":"http://localhost/example""#));
+
+    let get = "GET /example HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_string();
+    let response = tokio::task::spawn_blocking(move || request(addr, get))
+        .await
+        .unwrap();
+    assert!(response.starts_with("HTTP/1.1 307"), "{}", response);
+    assert!(
+        response.contains("location: https://example.com/\r\n"),
+        "{}",
+        response
+    );
+}
@@ -0,0 +1,118 @@
+// mini-channel under load. `mpsc` carries chat: each sender thread sends
+// its part of a generated transcript, numbered, and the receiver checks
+// that every sender's messages arrive whole and in order, none lost or
+// repeated. Then `spsc`'s ring carries numbered buffers through a small
+// capacity, so the two ends keep catching up with each other, until the
+// receiver hangs up partway and the buffers still in the ring have to be
+// dropped with it.
+
+use std::collections::HashMap;
+use std::thread;
+
+use fixtures::Rng;
+use mini_channel::{mpsc, spsc};
+
+/// Transcript messages per round.
+const MESSAGES: usize = 20_000;
+/// Values through the ring per round, at most.
+const VALUES: u64 = 20_000;
+
+pub fn round(threads: usize, seed: u64) -> Result<u64, String> {
+    Ok(chat(threads, seed)? + ring(seed)?)
+}
+
+fn chat(threads: usize, seed: u64) -> Result<u64, String> {
+    let transcript = fixtures::transcript(MESSAGES, threads, seed);
+    let mut parts: HashMap<&str, Vec<&str>> = HashMap::new();
+    for message in &transcript {
+        parts.entry(&message.from).or_default().push(&message.text);
+    }
+
+    let (sender, receiver) = mpsc::channel::<(String, usize, String)>();
+    thread::scope(|scope| {
+        for (&from, texts) in &parts {
+            let sender = sender.clone();
+            scope.spawn(move || {
+                for (i, text) in texts.iter().enumerate() {
+                    sender
+                        .send((from.to_string(), i, text.to_string()))
+                        .unwrap();
+                }
+            });
+        }
+        // Only the threads' clones are left, so the receiver sees the end
+        // when they're done.
+        drop(sender);
+
+        let mut next: HashMap<String, usize> = HashMap::new();
+        let mut received = 0;
+        for (from, i, text) in receiver.iter() {
+            let expected = next.entry(from.clone()).or_default();
+            if i != *expected {
+                return Err(format!(
+                    "{}'s message {} came when {} was due",
+                    from, i, expected
+                ));
+            }
+            if parts[from.as_str()][i] != text {
+                return Err(format!("{}'s message {} was garbled", from, i));
+            }
+            *expected += 1;
+            received += 1;
+        }
+        if received != transcript.len() {
+            return Err(format!(
+                "{} of {} messages arrived",
+                received,
+                transcript.len()
+            ));
+        }
+        Ok(received as u64)
+    })
+}
+
+fn ring(seed: u64) -> Result<u64, String> {
+    let mut rng = Rng::new(seed);
+    let capacity = 1 + rng.below(16) as usize;
+    // Where the receiver hangs up: sometimes only after the last value.
+    let stop = VALUES / 2 + rng.below(VALUES / 2 + 1);
+    let lengths: Vec<usize> = (0..VALUES).map(|_| rng.below(64) as usize).collect();
+
+    let (sender, receiver) = spsc::channel::<(u64, Vec<u8>)>(capacity);
+    let capacity = sender.capacity() as u64;
+    thread::scope(|scope| {
+        let lengths = &lengths;
+        let producer = scope.spawn(move || {
+            let mut sent = 0;
+            for (i, &length) in lengths.iter().enumerate() {
+                if sender.send((i as u64, vec![i as u8; length])).is_err() {
+                    break;
+                }
+                sent += 1;
+            }
+            sent
+        });
+        for expected in 0..stop {
+            let Ok((i, buffer)) = receiver.recv() else {
+                return Err(format!("the ring closed after {} values", expected));
+            };
+            if i != expected || buffer.len() != lengths[i as usize] {
+                return Err(format!("value {} came when {} was due", i, expected));
+            }
+            if buffer.iter().any(|&b| b != i as u8) {
+                return Err(format!("value {} was garbled", i));
+            }
+        }
+        drop(receiver);
+        let sent = producer.join().map_err(|_| "the producer panicked")?;
+        // The producer can get no more than a ring's worth ahead of what
+        // the receiver took.
+        if sent < stop || sent > stop + capacity {
+            return Err(format!(
+                "sent {} values to a receiver that took {}, with room for {}",
+                sent, stop, capacity
+            ));
+        }
+        Ok(stop)
+    })
+}
@@ -0,0 +1,115 @@
+// `kvstore` under load. Each thread owns a range of keys, and puts,
+// overwrites, deletes, and reads them at random, against a `HashMap` of
+// what each key should hold, while a low compaction threshold keeps the
+// background compactor rewriting the logs beneath them. Every so often,
+// each thread checks all of its keys. At the end, the store is closed and
+// reopened from disk, and must hold exactly what the models say.
+
+use std::collections::HashMap;
+use std::thread;
+
+use fixtures::Rng;
+use kvstore::{KvStore, Options};
+
+/// Operations per thread per round.
+const OPERATIONS: u64 = 2_000;
+/// Keys per thread: few enough that most writes overwrite, feeding the
+/// compactor.
+const KEYS: u64 = 64;
+/// How often a thread checks every key it owns.
+const CHECK_EVERY: u64 = 500;
+
+type Model = HashMap<Vec<u8>, Vec<u8>>;
+
+pub fn round(threads: usize, seed: u64) -> Result<u64, String> {
+    let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let options = Options {
+        compaction_threshold: Some(16 * 1024),
+    };
+    let store = KvStore::open_with(dir.path(), options).map_err(|e| e.to_string())?;
+    let models = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|t| {
+                let store = store.clone();
+                let seed = seed.wrapping_mul(31).wrapping_add(t as u64);
+                scope.spawn(move || work(&store, t, seed))
+            })
+            .collect();
+        crate::join_all(workers)
+    })?;
+    store.wait_for_compaction().map_err(|e| e.to_string())?;
+    drop(store);
+
+    let store = KvStore::open(dir.path()).map_err(|e| format!("reopening: {}", e))?;
+    let expected: usize = models.iter().map(HashMap::len).sum();
+    if store.len() != expected {
+        return Err(format!(
+            "reopened with {} keys, not {}",
+            store.len(),
+            expected
+        ));
+    }
+    for model in &models {
+        check(&store, model, "after reopening")?;
+    }
+    Ok(OPERATIONS * threads as u64)
+}
+
+/// Thread `t`'s share of the load, returning what its keys should hold.
+fn work(store: &KvStore, t: usize, seed: u64) -> Result<Model, String> {
+    let mut rng = Rng::new(seed);
+    let mut model = Model::new();
+    for i in 1..=OPERATIONS {
+        let key = format!("t{}-k{}", t, rng.below(KEYS)).into_bytes();
+        match rng.below(10) {
+            0..=5 => {
+                let length = rng.below(200) as usize;
+                let value: Vec<u8> = (0..length).map(|_| rng.below(256) as u8).collect();
+                store.put(&key, &value).map_err(|e| e.to_string())?;
+                model.insert(key, value);
+            }
+            6 | 7 => {
+                let deleted = store.delete(&key).map_err(|e| e.to_string())?;
+                if deleted != model.remove(&key).is_some() {
+                    return Err(format!(
+                        "deleting {} said {}",
+                        String::from_utf8_lossy(&key),
+                        deleted
+                    ));
+                }
+            }
+            _ => {
+                let value = store.get(&key).map_err(|e| e.to_string())?;
+                if value.as_ref() != model.get(&key) {
+                    return Err(format!(
+                        "{} holds the wrong value",
+                        String::from_utf8_lossy(&key)
+                    ));
+                }
+            }
+        }
+        if i % CHECK_EVERY == 0 {
+            check(store, &model, "mid-round")?;
+        }
+    }
+    Ok(model)
+}
+
+/// Check that every key in `model` holds its value in `store`.
+fn check(store: &KvStore, model: &Model, when: &str) -> Result<(), String> {
+    for (key, value) in model {
+        match store.get(key).map_err(|e| e.to_string())? {
+            Some(stored) if &stored == value => {}
+            stored => {
+                return Err(format!(
+                    "{}, {} holds {:?}, not {} bytes",
+                    when,
+                    String::from_utf8_lossy(key),
+                    stored.map(|v| v.len()),
+                    value.len()
+                ))
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+// Soak testing: the workspace's concurrent members run under randomized
+// load, round after round, for as long as asked. Unit tests run each path
+// once, briefly; a leak of a few bytes per operation, or a race that loses
+// one message in a million, only shows up after many rounds.
+//
+// Each workload checks its own invariants as it goes, and fails with a
+// `Violation` saying which. Between rounds, everything a round made has
+// been dropped, so the heap should be back where it was; with alloc-lab's
+// `Lab` as the global allocator, `Soak::run` checks that it is.
+
+mod channel;
+mod kv;
+mod tasks;
+
+use std::error::Error;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// One of the members under load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Workload {
+    /// `kvstore`: threads writing, deleting, and reading their own keys,
+    /// with the compactor rewriting the logs beneath them.
+    Kv,
+    /// `mini-channel`: a chat transcript through `mpsc` from several
+    /// senders, and values through `spsc`'s ring.
+    Channel,
+    /// `mini-runtime`: runtimes on several threads, each with a swarm of
+    /// sleeping, yielding tasks.
+    Tasks,
+}
+
+impl Workload {
+    pub const ALL: [Workload; 3] = [Workload::Kv, Workload::Channel, Workload::Tasks];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Workload::Kv => "kv",
+            Workload::Channel => "channel",
+            Workload::Tasks => "tasks",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Workload> {
+        Workload::ALL.into_iter().find(|w| w.name() == name)
+    }
+
+    /// Run one round on `threads` threads, returning how many operations
+    /// it did, or the invariant that failed.
+    fn round(self, threads: usize, seed: u64) -> Result<u64, String> {
+        match self {
+            Workload::Kv => kv::round(threads, seed),
+            Workload::Channel => channel::round(threads, seed),
+            Workload::Tasks => tasks::round(threads, seed),
+        }
+    }
+}
+
+/// How to soak.
+#[derive(Clone, Debug)]
+pub struct Soak {
+    /// Keep starting rounds until this much time has passed.
+    pub duration: Duration,
+    /// Each workload's rounds, at least, however short `duration` is. The
+    /// heap is first checked after the second.
+    pub rounds: u64,
+    pub threads: usize,
+    /// Round `n` uses `seed + n`, so a failure can be repeated alone.
+    pub seed: u64,
+    pub workloads: Vec<Workload>,
+    /// How far the live heap may grow, in bytes, past where it was after
+    /// the first round of every workload. The first round is a warm-up:
+    /// thread-locals, buffers, and the like are allocated once and kept.
+    pub max_growth: usize,
+}
+
+impl Default for Soak {
+    fn default() -> Soak {
+        Soak {
+            duration: Duration::from_secs(10),
+            rounds: 2,
+            threads: thread::available_parallelism().map_or(4, |n| n.get()),
+            seed: 1,
+            workloads: Workload::ALL.to_vec(),
+            max_growth: 256 * 1024,
+        }
+    }
+}
+
+/// What one round did.
+#[derive(Clone, Debug)]
+pub struct Round {
+    pub number: u64,
+    pub workload: Workload,
+    pub seed: u64,
+    pub operations: u64,
+    pub elapsed: Duration,
+    /// The live heap once the round was over. Zero unless `Lab` is the
+    /// global allocator.
+    pub live_bytes: usize,
+}
+
+/// An invariant that didn't hold, and the round that broke it.
+#[derive(Clone, Debug)]
+pub struct Violation {
+    pub workload: Workload,
+    pub round: u64,
+    pub seed: u64,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} round {} (seed {}): {}",
+            self.workload.name(),
+            self.round,
+            self.seed,
+            self.message
+        )
+    }
+}
+
+impl Error for Violation {}
+
+impl Soak {
+    /// Run the workloads' rounds in turn until `duration` is up, calling
+    /// `on_round` after each, and return how many rounds there were.
+    pub fn run(&self, mut on_round: impl FnMut(&Round)) -> Result<u64, Violation> {
+        let start = Instant::now();
+        let mut baseline = None;
+        let mut number = 0;
+        for cycle in 1.. {
+            for &workload in &self.workloads {
+                let seed = self.seed.wrapping_add(number);
+                let violation = |message| Violation {
+                    workload,
+                    round: number,
+                    seed,
+                    message,
+                };
+                let round_start = Instant::now();
+                let operations = workload.round(self.threads, seed).map_err(violation)?;
+                let live_bytes = alloc_lab::stats().live_bytes;
+                on_round(&Round {
+                    number,
+                    workload,
+                    seed,
+                    operations,
+                    elapsed: round_start.elapsed(),
+                    live_bytes,
+                });
+                if let Some(baseline) = baseline {
+                    let growth = live_bytes.saturating_sub(baseline);
+                    if growth > self.max_growth {
+                        return Err(violation(format!(
+                            "the live heap has grown {} bytes since the first rounds, \
+                             past the {} allowed",
+                            growth, self.max_growth
+                        )));
+                    }
+                }
+                number += 1;
+            }
+            // Measured once every workload has warmed up.
+            baseline.get_or_insert(alloc_lab::stats().live_bytes);
+            if cycle >= self.rounds && start.elapsed() >= self.duration {
+                break;
+            }
+        }
+        Ok(number)
+    }
+}
+
+/// Join `threads`, turning a panic into a failed invariant.
+fn join_all<T>(
+    threads: Vec<thread::ScopedJoinHandle<Result<T, String>>>,
+) -> Result<Vec<T>, String> {
+    threads
+        .into_iter()
+        .map(|thread| {
+            thread
+                .join()
+                .unwrap_or_else(|_| Err("a thread panicked".to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,112 @@
+use std::env;
+use std::process;
+use std::time::Duration;
+
+use alloc_lab::Lab;
+use argparse_lite::{Arg, Command};
+use stress::{Soak, Workload};
+
+// The heap checks need every allocation counted.
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+fn command() -> Command<'static> {
+    Command::new("stress")
+        .about("run the concurrent members under load, checking invariants and the heap")
+        .arg(
+            Arg::option("duration")
+                .short('d')
+                .value_name("SECS")
+                .default("10")
+                .help("keep starting rounds for this long"),
+        )
+        .arg(
+            Arg::option("rounds")
+                .short('r')
+                .value_name("N")
+                .default("2")
+                .help("run each workload at least this many times, however long it takes"),
+        )
+        .arg(
+            Arg::option("threads")
+                .short('t')
+                .value_name("N")
+                .help("threads per round (default: one per core)"),
+        )
+        .arg(
+            Arg::option("seed")
+                .short('s')
+                .value_name("N")
+                .default("1")
+                .help("seed for the first round; round n uses seed + n"),
+        )
+        .arg(
+            Arg::option("workload")
+                .short('w')
+                .value_name("NAME")
+                .multiple()
+                .help("kv, channel, or tasks; repeat for several (default: all)"),
+        )
+        .arg(
+            Arg::option("max-growth")
+                .value_name("KIB")
+                .default("256")
+                .help("how far the live heap may grow past the first rounds"),
+        )
+}
+
+fn parse() -> Soak {
+    let command = command();
+    let matches = command
+        .parse(env::args().skip(1))
+        .unwrap_or_else(|e| e.exit());
+    let get = |name| matches.get::<u64>(name).unwrap_or_else(|e| e.exit());
+    let mut soak = Soak {
+        duration: Duration::from_secs(get("duration").unwrap()),
+        rounds: get("rounds").unwrap(),
+        seed: get("seed").unwrap(),
+        max_growth: get("max-growth").unwrap() as usize * 1024,
+        ..Soak::default()
+    };
+    if let Some(threads) = get("threads") {
+        soak.threads = threads.max(1) as usize;
+    }
+    let names = matches.values("workload");
+    if !names.is_empty() {
+        soak.workloads = names
+            .iter()
+            .map(|name| {
+                Workload::from_name(name).unwrap_or_else(|| {
+                    eprintln!("Error: unknown workload '{}'", name);
+                    process::exit(2);
+                })
+            })
+            .collect();
+    }
+    soak
+}
+
+fn main() {
+    let soak = parse();
+    println!(
+        "soaking for {:?} on {} threads, from seed {}",
+        soak.duration, soak.threads, soak.seed
+    );
+    let result = soak.run(|round| {
+        println!(
+            "round {:>4}  {:<8} {:>9} ops  {:>8.1?}  {:>9} bytes live",
+            round.number,
+            round.workload.name(),
+            round.operations,
+            round.elapsed,
+            round.live_bytes
+        );
+    });
+    match result {
+        Ok(rounds) => println!("{} rounds, no violations", rounds),
+        Err(violation) => {
+            eprintln!("Error: {}", violation);
+            process::exit(1);
+        }
+    }
+}
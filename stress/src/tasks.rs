@@ -0,0 +1,62 @@
+// mini-runtime under load. The runtime is single-threaded, so the load is
+// several of them at once, one per thread, each with a swarm of tasks
+// that sleep for random moments, yield, and spawn tasks of their own. Each
+// task's result must come back through its `JoinHandle`, and every task
+// must finish: one that's never woken would hang the round.
+
+use std::thread;
+use std::time::Duration;
+
+use fixtures::Rng;
+use mini_runtime::{sleep, spawn, yield_now, Runtime};
+
+/// Tasks per runtime per round, each spawning one more.
+const TASKS: u64 = 500;
+
+pub fn round(threads: usize, seed: u64) -> Result<u64, String> {
+    let totals = thread::scope(|scope| {
+        let runtimes = (0..threads)
+            .map(|t| {
+                let seed = seed.wrapping_mul(31).wrapping_add(t as u64);
+                scope.spawn(move || swarm(seed))
+            })
+            .collect();
+        crate::join_all(runtimes)
+    })?;
+    Ok(totals.iter().sum())
+}
+
+/// Run one runtime's tasks, returning how many finished.
+fn swarm(seed: u64) -> Result<u64, String> {
+    let mut rng = Rng::new(seed);
+    let delays: Vec<u64> = (0..TASKS).map(|_| rng.below(500)).collect();
+    let runtime = Runtime::new();
+    let results = runtime.block_on(async move {
+        let handles: Vec<_> = delays
+            .into_iter()
+            .enumerate()
+            .map(|(i, delay)| {
+                spawn(async move {
+                    sleep(Duration::from_micros(delay)).await;
+                    yield_now().await;
+                    let child = spawn(async move {
+                        yield_now().await;
+                        i as u64 * 2
+                    });
+                    child.await + 1
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await);
+        }
+        results
+    });
+    for (i, &result) in results.iter().enumerate() {
+        if result != i as u64 * 2 + 1 {
+            return Err(format!("task {} returned {}", i, result));
+        }
+    }
+    Ok(TASKS * 2)
+}
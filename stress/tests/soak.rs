@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use alloc_lab::Lab;
+use stress::{Soak, Violation, Workload};
+
+// As in the binary, so the heap checks count something. The counts are
+// process-wide, so this file has one test, and nothing runs beside it.
+#[global_allocator]
+static ALLOC: Lab = Lab;
+
+#[test]
+fn a_short_soak_of_every_workload() {
+    let soak = Soak {
+        duration: Duration::ZERO,
+        rounds: 2,
+        threads: 4,
+        seed: 7,
+        ..Soak::default()
+    };
+    let mut rounds = Vec::new();
+    // Two rounds each, so the heap after the second is checked against
+    // the first.
+    let count = soak.run(|round| rounds.push(round.clone())).unwrap();
+    assert_eq!(count, 6);
+    let order: Vec<Workload> = rounds.iter().map(|r| r.workload).collect();
+    assert_eq!(order, [Workload::ALL, Workload::ALL].concat());
+    let seeds: Vec<u64> = rounds.iter().map(|r| r.seed).collect();
+    assert_eq!(seeds, [7, 8, 9, 10, 11, 12]);
+    assert!(rounds.iter().all(|r| r.operations > 0));
+    assert!(rounds.iter().all(|r| r.live_bytes > 0));
+
+    for workload in Workload::ALL {
+        assert_eq!(Workload::from_name(workload.name()), Some(workload));
+    }
+    assert_eq!(Workload::from_name("chat"), None);
+    let violation = Violation {
+        workload: Workload::Kv,
+        round: 3,
+        seed: 10,
+        message: "reopened with 5 keys, not 6".to_string(),
+    };
+    assert_eq!(
+        violation.to_string(),
+        "kv round 3 (seed 10): reopened with 5 keys, not 6"
+    );
+}
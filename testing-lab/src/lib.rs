@@ -0,0 +1,35 @@
+// Property-based testing, aimed at the workspace's own data structures.
+//
+// The library half is the test machinery: strategies that generate
+// `Interval`s and `Json` documents, a hand-written shrinker, and models
+// that `MiniMap`, `Queue`, and `BinaryTree` are checked against. The test
+// suites that use it are in `tests/`.
+
+pub mod model;
+pub mod shrink;
+pub mod strategies;
+
+use proptest::strategy::Strategy;
+use proptest::test_runner::{Config, RngAlgorithm, TestCaseError, TestError, TestRng, TestRunner};
+
+/// Run `test` on values from `strategy` until it fails, and return the
+/// counterexample proptest shrinks the failure to. Panics if the property
+/// holds.
+///
+/// The runner is seeded the same way every time, so the counterexample is
+/// too, which makes it something a test can check.
+pub fn counterexample<S: Strategy>(
+    strategy: S,
+    test: impl Fn(S::Value) -> Result<(), TestCaseError>,
+) -> S::Value {
+    let config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    match TestRunner::new_with_rng(config, rng).run(&strategy, test) {
+        Err(TestError::Fail(_, value)) => value,
+        Err(TestError::Abort(reason)) => panic!("gave up: {}", reason),
+        Ok(()) => panic!("expected the property to fail"),
+    }
+}
@@ -0,0 +1,170 @@
+// Model-based testing: run the same random sequence of operations on a
+// structure and on a simple, trusted model of it, and check after every
+// step that they agree.
+//
+// The models are from `std`. They aren't faster or cleverer than the
+// workspace's structures, just much more thoroughly tested, which is all a
+// model needs to be.
+
+use std::collections::{HashMap, VecDeque};
+
+use binary_tree::BinaryTree;
+use mini_map::MiniMap;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+use queue::Queue;
+
+/// An operation on a `MiniMap<String, u32>`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MapOp {
+    Insert(String, u32),
+    Remove(String),
+    RemoveEntry(String),
+    /// Add to the value for a key, if there is one, through `get_mut`.
+    Add(String, u32),
+    Extend(Vec<(String, u32)>),
+    Reserve(usize),
+    ShrinkToFit,
+    Clear,
+    /// Replace the map with a clone of itself.
+    Clone,
+}
+
+/// Keys are drawn from a small set, so that operations often hit keys
+/// already in the map.
+fn key() -> impl Strategy<Value = String> {
+    (0..16u8).prop_map(|n| format!("k{}", n))
+}
+
+pub fn map_op() -> impl Strategy<Value = MapOp> {
+    prop_oneof![
+        8 => (key(), any::<u32>()).prop_map(|(k, v)| MapOp::Insert(k, v)),
+        3 => key().prop_map(MapOp::Remove),
+        2 => key().prop_map(MapOp::RemoveEntry),
+        2 => (key(), 0..100u32).prop_map(|(k, n)| MapOp::Add(k, n)),
+        1 => prop::collection::vec((key(), any::<u32>()), 0..8).prop_map(MapOp::Extend),
+        1 => (0..64usize).prop_map(MapOp::Reserve),
+        1 => Just(MapOp::ShrinkToFit),
+        1 => Just(MapOp::Clear),
+        1 => Just(MapOp::Clone),
+    ]
+}
+
+/// Apply `ops` to an empty `MiniMap` and an empty `HashMap`, failing as
+/// soon as they disagree: about what an operation returned, about any
+/// key's value, or about what iteration yields.
+pub fn check_map(ops: &[MapOp]) -> Result<(), TestCaseError> {
+    let mut map: MiniMap<String, u32> = MiniMap::new();
+    let mut model: HashMap<String, u32> = HashMap::new();
+    for op in ops {
+        match op.clone() {
+            MapOp::Insert(k, v) => prop_assert_eq!(map.insert(k.clone(), v), model.insert(k, v)),
+            MapOp::Remove(k) => prop_assert_eq!(map.remove(k.as_str()), model.remove(&k)),
+            MapOp::RemoveEntry(k) => {
+                prop_assert_eq!(map.remove_entry(k.as_str()), model.remove_entry(&k))
+            }
+            MapOp::Add(k, n) => {
+                if let Some(v) = map.get_mut(k.as_str()) {
+                    *v = v.wrapping_add(n);
+                }
+                if let Some(v) = model.get_mut(&k) {
+                    *v = v.wrapping_add(n);
+                }
+            }
+            MapOp::Extend(pairs) => {
+                map.extend(pairs.clone());
+                model.extend(pairs);
+            }
+            MapOp::Reserve(n) => {
+                map.reserve(n);
+                prop_assert!(map.capacity() >= map.len() + n);
+            }
+            MapOp::ShrinkToFit => map.shrink_to_fit(),
+            MapOp::Clear => {
+                map.clear();
+                model.clear();
+            }
+            MapOp::Clone => {
+                let copy = map.clone();
+                prop_assert_eq!(&copy, &map);
+                map = copy;
+            }
+        }
+
+        prop_assert_eq!(map.len(), model.len());
+        for (k, v) in &model {
+            prop_assert_eq!(map.get(k.as_str()), Some(v));
+        }
+        let mut entries: Vec<(&String, &u32)> = map.iter().collect();
+        entries.sort();
+        let mut expected: Vec<(&String, &u32)> = model.iter().collect();
+        expected.sort();
+        prop_assert_eq!(entries, expected);
+    }
+    Ok(())
+}
+
+/// An operation on a `Queue<u8>`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueueOp {
+    Push(u8),
+    Pop,
+    Peek,
+    /// Drain the queue, but only look at the first `n` elements; dropping
+    /// the `Drain` must remove the rest anyway.
+    Drain(usize),
+}
+
+pub fn queue_op() -> impl Strategy<Value = QueueOp> {
+    prop_oneof![
+        6 => any::<u8>().prop_map(QueueOp::Push),
+        4 => Just(QueueOp::Pop),
+        2 => Just(QueueOp::Peek),
+        1 => (0..4usize).prop_map(QueueOp::Drain),
+    ]
+}
+
+/// Apply `ops` to an empty `Queue` and an empty `VecDeque`, failing as
+/// soon as they disagree.
+pub fn check_queue(ops: &[QueueOp]) -> Result<(), TestCaseError> {
+    let mut queue = Queue::new();
+    let mut model = VecDeque::new();
+    for op in ops {
+        match *op {
+            QueueOp::Push(x) => {
+                queue.push(x);
+                model.push_back(x);
+            }
+            QueueOp::Pop => prop_assert_eq!(queue.pop(), model.pop_front()),
+            QueueOp::Peek => prop_assert_eq!(queue.peek(), model.front()),
+            QueueOp::Drain(n) => {
+                let drained: Vec<u8> = queue.drain().take(n).collect();
+                let expected: Vec<u8> = model.drain(..).take(n).collect();
+                prop_assert_eq!(drained, expected);
+            }
+        }
+        prop_assert_eq!(queue.len(), model.len());
+        prop_assert_eq!(queue.is_empty(), model.is_empty());
+    }
+    Ok(())
+}
+
+/// Add `values` to a `BinaryTree`, checking it against a sorted `Vec`
+/// after each: its length, its in-order iteration, and `contains` for
+/// every value up to the largest.
+pub fn check_tree(values: &[u8]) -> Result<(), TestCaseError> {
+    let mut tree = BinaryTree::new();
+    let mut model: Vec<u8> = Vec::new();
+    for &value in values {
+        tree.add(value);
+        let at = model.partition_point(|&x| x <= value);
+        model.insert(at, value);
+
+        prop_assert_eq!(tree.len(), model.len());
+        prop_assert!(tree.iter().eq(model.iter()));
+        for probe in 0..=value {
+            prop_assert_eq!(tree.contains(&probe), model.binary_search(&probe).is_ok());
+        }
+    }
+    Ok(())
+}
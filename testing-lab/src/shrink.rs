@@ -0,0 +1,134 @@
+// A strategy with a shrinker written by hand.
+//
+// A proptest `Strategy` doesn't produce values directly: it produces a
+// `ValueTree`, which holds the current value and knows how to search for
+// a simpler one. When a test fails, proptest calls `simplify` to try a
+// simpler value; if that one passes, `complicate` backs off part way. It
+// stops when neither makes progress, and reports the last failing value.
+//
+// `prop_map` and friends build value trees out of simpler ones, which
+// usually does the job. This one is written out to show the moving parts.
+
+use std::ops::Range;
+
+use interval::Interval;
+use proptest::num::{i32, u32};
+use proptest::strategy::{NewTree, Strategy, ValueTree};
+use proptest::test_runner::TestRunner;
+
+/// Intervals whose lower bound is drawn from `lower` and whose width is
+/// drawn from `width`. They shrink toward `0..0`: narrower first, then
+/// closer to zero, then narrower again if moving made that possible.
+#[derive(Clone, Debug)]
+pub struct IntervalStrategy {
+    pub lower: Range<i32>,
+    pub width: Range<u32>,
+}
+
+impl Default for IntervalStrategy {
+    fn default() -> IntervalStrategy {
+        IntervalStrategy {
+            lower: -1000..1000,
+            width: 0..1000,
+        }
+    }
+}
+
+impl Strategy for IntervalStrategy {
+    type Tree = IntervalTree;
+    type Value = Interval<i32>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let lower = self.lower.new_tree(runner)?.current();
+        let width = self.width.new_tree(runner)?.current();
+        Ok(IntervalTree {
+            lower: i32::BinarySearch::new(lower),
+            width: u32::BinarySearch::new(width),
+            shrinking: Part::Width,
+            last: None,
+            pass_start: lower,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Part {
+    Width,
+    Lower,
+}
+
+/// The value tree for `IntervalStrategy`.
+///
+/// It shrinks the width and the lower bound separately, each with one of
+/// proptest's binary searches toward zero. Because the interval is built
+/// from a lower bound and a width, rather than two bounds, no step of the
+/// search can produce a backwards interval.
+///
+/// Moving the interval can make a narrower one fail the test too: if it
+/// must contain 5, then starting from `-100..10` the width can't shrink
+/// below 106, but once the lower bound has moved up to 0, a width of 6
+/// will do. So after shrinking the lower bound, it starts over with the
+/// width, until a pass leaves the lower bound where it was.
+#[derive(Clone, Debug)]
+pub struct IntervalTree {
+    lower: i32::BinarySearch,
+    width: u32::BinarySearch,
+    /// The part being shrunk now: the width until it can't go lower, then
+    /// the lower bound.
+    shrinking: Part,
+    /// The part the last `simplify` changed, which is what `complicate`
+    /// must undo.
+    last: Option<Part>,
+    /// The lower bound when this pass began shrinking it.
+    pass_start: i32,
+}
+
+impl ValueTree for IntervalTree {
+    type Value = Interval<i32>;
+
+    fn current(&self) -> Interval<i32> {
+        let lower = self.lower.current();
+        Interval {
+            lower,
+            upper: lower.saturating_add_unsigned(self.width.current()),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        loop {
+            match self.shrinking {
+                Part::Width => {
+                    if self.width.simplify() {
+                        self.last = Some(Part::Width);
+                        return true;
+                    }
+                    self.shrinking = Part::Lower;
+                    self.pass_start = self.lower.current();
+                }
+                Part::Lower => {
+                    if self.lower.simplify() {
+                        self.last = Some(Part::Lower);
+                        return true;
+                    }
+                    if self.lower.current() == self.pass_start {
+                        return false;
+                    }
+                    // Each search has found its limit; start new ones from
+                    // where they ended.
+                    self.width = u32::BinarySearch::new(self.width.current());
+                    self.lower = i32::BinarySearch::new(self.lower.current());
+                    self.shrinking = Part::Width;
+                    self.last = None;
+                }
+            }
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.last {
+            Some(Part::Width) => self.width.complicate(),
+            Some(Part::Lower) => self.lower.complicate(),
+            None => false,
+        }
+    }
+}
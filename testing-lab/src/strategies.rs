@@ -0,0 +1,62 @@
+// Strategies for generating the workspace's types.
+
+use std::collections::BTreeMap;
+
+use interval::Interval;
+use proptest::prelude::*;
+use tiny_json::Json;
+
+/// Any interval with bounds in `-1000..1000`, empty ones included.
+///
+/// Generating two bounds and sorting them, rather than generating pairs and
+/// filtering out the ones that are backwards, never throws a case away. And
+/// since shrinking works on the pair and sorts again, every shrunk value is
+/// a valid interval too.
+pub fn interval() -> impl Strategy<Value = Interval<i32>> {
+    (-1000..1000i32, -1000..1000i32).prop_map(|(a, b)| Interval::new(a.min(b), a.max(b)).unwrap())
+}
+
+/// A non-empty interval containing `value`.
+pub fn interval_containing(value: i32) -> impl Strategy<Value = Interval<i32>> {
+    (0..100, 1..100).prop_map(move |(below, above)| Interval {
+        lower: value - below,
+        upper: value + above,
+    })
+}
+
+/// Keys and strings for JSON: short, lowercase, and readable in a failure
+/// report. tiny-json's own round-trip test covers arbitrary Unicode.
+fn word() -> impl Strategy<Value = String> {
+    "[a-z]{0,6}"
+}
+
+/// A JSON number. Mostly small integers, which print exactly, with some
+/// fractions mixed in.
+fn number() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        3 => (-100i32..100).prop_map(f64::from),
+        1 => (-1000i32..1000).prop_map(|n| f64::from(n) / 8.0),
+    ]
+}
+
+/// A JSON document at most `depth` arrays or objects deep.
+///
+/// `prop_recursive` starts from the leaves and wraps them in containers.
+/// When a failing document shrinks, containers lose elements and leaves get
+/// simpler, but a container doesn't turn into a leaf: a failure found inside
+/// an object is reported inside an object, with one member.
+pub fn json(depth: u32) -> impl Strategy<Value = Json> {
+    let leaf = prop_oneof![
+        Just(Json::Null),
+        any::<bool>().prop_map(Json::Boolean),
+        number().prop_map(Json::Number),
+        word().prop_map(Json::String),
+    ];
+    leaf.prop_recursive(depth, 48, 6, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..6).prop_map(Json::Array),
+            prop::collection::btree_map(word(), inner, 0..6)
+                .prop_map(|members: BTreeMap<String, Json>| Json::Object(members)),
+        ]
+    })
+}
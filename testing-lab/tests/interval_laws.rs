@@ -0,0 +1,92 @@
+// The `interval` member's algebra, checked as laws over generated intervals
+// rather than a handful of examples.
+
+use std::cmp::Ordering;
+
+use interval::Interval;
+use proptest::prelude::*;
+use testing_lab::strategies::{interval, interval_containing};
+
+/// Every value in either interval, and one past each end.
+fn values(a: &Interval<i32>, b: &Interval<i32>) -> std::ops::RangeInclusive<i32> {
+    let hull = a.hull(b);
+    hull.lower - 1..=hull.upper
+}
+
+proptest! {
+    #[test]
+    fn intersection_holds_the_common_values(a in interval(), b in interval()) {
+        prop_assert_eq!(a.intersection(&b), b.intersection(&a));
+        prop_assert_eq!(a.overlaps(&b), a.intersection(&b).is_some());
+        for x in values(&a, &b) {
+            let both = a.contains(&x) && b.contains(&x);
+            prop_assert_eq!(a.intersection(&b).is_some_and(|i| i.contains(&x)), both);
+        }
+    }
+
+    #[test]
+    fn union_is_exact_or_refused(a in interval(), b in interval()) {
+        prop_assert_eq!(a.union(&b), b.union(&a));
+        let either = |x: &i32| a.contains(x) || b.contains(x);
+        match a.union(&b) {
+            Some(u) => {
+                for x in values(&a, &b) {
+                    prop_assert_eq!(u.contains(&x), either(&x), "at {}", x);
+                }
+            }
+            // Refused only if there really is a gap: a value between the
+            // two that neither contains.
+            None => {
+                let hull = a.hull(&b);
+                let gap = values(&a, &b).any(|x| !either(&x) && hull.contains(&x));
+                prop_assert!(gap);
+            }
+        }
+    }
+
+    #[test]
+    fn hull_contains_both(a in interval(), b in interval()) {
+        let hull = a.hull(&b);
+        for x in values(&a, &b) {
+            if a.contains(&x) || b.contains(&x) {
+                prop_assert!(hull.contains(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn ordering_means_entirely_before(a in interval(), b in interval()) {
+        prop_assert_eq!(a.partial_cmp(&b), b.partial_cmp(&a).map(Ordering::reverse));
+        if a < b {
+            for x in values(&a, &b).filter(|x| a.contains(x)) {
+                for y in values(&a, &b).filter(|y| b.contains(y)) {
+                    prop_assert!(x < y);
+                }
+            }
+            prop_assert!(!a.overlaps(&b));
+        }
+        if !a.is_empty() && !b.is_empty() && a != b {
+            prop_assert_eq!(a.partial_cmp(&b).is_none(), a.overlaps(&b));
+        }
+    }
+
+    #[test]
+    fn ordering_is_transitive(a in interval(), b in interval(), c in interval()) {
+        if a < b && b < c {
+            prop_assert!(a < c);
+        }
+    }
+
+    #[test]
+    fn shifting_moves_every_value(a in interval(), k in -1000..1000i32, x in -2000..2000i32) {
+        prop_assert_eq!((a + k).contains(&(x + k)), a.contains(&x));
+        prop_assert_eq!(a + k - k, a);
+    }
+
+    #[test]
+    fn targeted_strategy(value in -1000..1000i32, a in interval_containing(0)) {
+        prop_assert!(a.contains(&0));
+        let shifted = a + value;
+        prop_assert!(shifted.contains(&value));
+    }
+}
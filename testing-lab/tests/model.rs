@@ -0,0 +1,37 @@
+// The workspace's containers against std's, one random operation sequence
+// at a time. The checking is in `testing_lab::model`; these only choose how
+// long the sequences are.
+
+use proptest::prelude::*;
+use testing_lab::model::{check_map, check_queue, check_tree, map_op, queue_op, MapOp};
+
+proptest! {
+    #[test]
+    fn mini_map_matches_hash_map(ops in prop::collection::vec(map_op(), 0..200)) {
+        check_map(&ops)?;
+    }
+
+    #[test]
+    fn queue_matches_vec_deque(ops in prop::collection::vec(queue_op(), 0..200)) {
+        check_queue(&ops)?;
+    }
+
+    #[test]
+    fn binary_tree_matches_sorted_vec(values in prop::collection::vec(any::<u8>(), 0..100)) {
+        check_tree(&values)?;
+    }
+}
+
+/// A sequence that once needed care in `MiniMap`: a key removed, and then
+/// reinserted after the table grew past its tombstone.
+#[test]
+fn reinsert_after_growth() {
+    let mut ops = vec![
+        MapOp::Insert("k0".to_string(), 1),
+        MapOp::Remove("k0".to_string()),
+    ];
+    ops.extend((1..16).map(|n| MapOp::Insert(format!("k{}", n), n)));
+    ops.push(MapOp::Insert("k0".to_string(), 2));
+    ops.push(MapOp::Clone);
+    check_map(&ops).unwrap();
+}
@@ -0,0 +1,131 @@
+// What a failing property reports. These run deliberately false properties
+// with a `TestRunner` and check the counterexample it settles on, since a
+// minimal counterexample is most of the value of property testing.
+
+use interval::Interval;
+use mini_map::MiniMap;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+use testing_lab::counterexample;
+use testing_lab::model::{map_op, MapOp};
+use testing_lab::shrink::IntervalStrategy;
+use testing_lab::strategies::json;
+use tiny_json::Json;
+
+#[test]
+fn intervals_shrink_to_the_smallest_failure() {
+    // "No interval is 10 or wider" is false, and the simplest interval
+    // that shows it is 0..10.
+    let found = counterexample(IntervalStrategy::default(), |i| {
+        prop_assert!(i.upper - i.lower < 10);
+        Ok(())
+    });
+    assert_eq!(
+        found,
+        Interval {
+            lower: 0,
+            upper: 10
+        }
+    );
+}
+
+#[test]
+fn shrinking_never_produces_a_backwards_interval() {
+    let mut runner = TestRunner::deterministic();
+    for _ in 0..100 {
+        let mut tree = IntervalStrategy::default().new_tree(&mut runner).unwrap();
+        // Alternate at random between simplifying and backing off, as a
+        // runner would for a property that fails only sometimes.
+        for step in 0..64u32 {
+            let value = tree.current();
+            assert!(value.lower <= value.upper, "{:?}", value);
+            let moved = if step.count_ones() % 2 == 0 {
+                tree.simplify()
+            } else {
+                tree.complicate()
+            };
+            if !moved && !tree.simplify() {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn operation_sequences_shrink_to_the_essentials() {
+    // "A MiniMap never holds more than three entries." The counterexample
+    // adds four distinct keys, with values shrunk to 0, and every remove
+    // or clear is gone.
+    //
+    // Shrinking is greedy, though: it removes and simplifies elements one
+    // at a time, and stops when no single step keeps the test failing. An
+    // `Extend` of two pairs isn't split into two inserts, so the result is
+    // a minimum, not necessarily the smallest possible.
+    let found = counterexample(prop::collection::vec(map_op(), 0..100), |ops| {
+        let mut map = MiniMap::new();
+        for op in ops {
+            match op {
+                MapOp::Insert(k, v) => {
+                    map.insert(k, v);
+                }
+                MapOp::Remove(k) | MapOp::RemoveEntry(k) => {
+                    map.remove(&k);
+                }
+                MapOp::Extend(pairs) => map.extend(pairs),
+                MapOp::Clear => map.clear(),
+                _ => {}
+            }
+            prop_assert!(map.len() <= 3);
+        }
+        Ok(())
+    });
+    let mut keys = Vec::new();
+    for op in &found {
+        match op {
+            MapOp::Insert(k, 0) => keys.push(k.as_str()),
+            MapOp::Extend(pairs) if pairs.iter().all(|&(_, v)| v == 0) => {
+                keys.extend(pairs.iter().map(|(k, _)| k.as_str()))
+            }
+            other => panic!("not shrunk away: {:?} in {:?}", other, found),
+        }
+    }
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys.len(), 4, "{:?}", found);
+}
+
+#[test]
+fn documents_shrink_to_the_offending_leaf() {
+    // "No document contains the string "x" anywhere." A failure is usually
+    // found deep in some nest of arrays and objects. Shrinking leaves one
+    // leaf, the string "x", though possibly still inside a container.
+    fn strings(value: &Json) -> Vec<&str> {
+        match value {
+            Json::String(s) => vec![s.as_str()],
+            Json::Array(items) => items.iter().flat_map(strings).collect(),
+            Json::Object(members) => members
+                .iter()
+                .flat_map(|(k, v)| std::iter::once(k.as_str()).chain(strings(v)))
+                .collect(),
+            _ => vec![],
+        }
+    }
+    fn leaves(value: &Json) -> usize {
+        match value {
+            Json::Array(items) => items.iter().map(leaves).sum(),
+            Json::Object(members) => members.values().map(leaves).sum(),
+            _ => 1,
+        }
+    }
+    let found = counterexample(json(4), |value| {
+        prop_assert!(!strings(&value).iter().any(|s| s.contains('x')));
+        Ok(())
+    });
+    assert_eq!(leaves(&found), 1, "{:?}", found);
+    assert!(
+        strings(&found).iter().all(|&s| s.is_empty() || s == "x"),
+        "{:?}",
+        found
+    );
+}
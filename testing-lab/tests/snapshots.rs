@@ -0,0 +1,71 @@
+// Snapshot tests with insta. Rather than spelling out the expected output
+// in an assertion, the first run records it in `tests/snapshots/`, and
+// later runs compare against the recording. A difference fails the test
+// and leaves a `.snap.new` file beside the old one. `cargo insta review`
+// (from cargo-insta) shows the difference and accepts or rejects it.
+//
+// That suits output that's long, or that's easier to check by reading than
+// to derive: a table of results, printed documents, a shrunk
+// counterexample.
+
+use interval::Interval;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+use testing_lab::counterexample;
+use testing_lab::shrink::IntervalStrategy;
+use testing_lab::strategies::json;
+use tiny_json::{Json, Printer};
+
+#[test]
+fn interval_operations() {
+    let intervals = [
+        Interval::new(0, 5),
+        Interval::new(3, 8),
+        Interval::new(5, 10),
+        Interval::new(10, 10),
+        Interval::new(-2, 0),
+    ]
+    .map(Option::unwrap);
+
+    let mut table = String::new();
+    for a in &intervals {
+        for b in &intervals {
+            let show = |i: Option<Interval<i32>>| i.map_or("-".to_string(), |i| i.to_string());
+            table += &format!(
+                "{:>8} {:>8}   ∩ {:>8}   ∪ {:>8}   hull {:>8}   {:?}\n",
+                a.to_string(),
+                b.to_string(),
+                show(a.intersection(b)),
+                show(a.union(b)),
+                a.hull(b).to_string(),
+                a.partial_cmp(b)
+            );
+        }
+    }
+    insta::assert_snapshot!(table);
+}
+
+#[test]
+fn generated_documents() {
+    // A deterministic runner always generates the same documents, so this
+    // snapshot changes only if the `json` strategy or the printer does.
+    let mut runner = TestRunner::deterministic();
+    let documents: Vec<Json> = (0..6)
+        .map(|_| json(3).new_tree(&mut runner).unwrap().current())
+        .collect();
+    insta::assert_snapshot!(Printer::new()
+        .indent_width(2)
+        .print(&Json::Array(documents)));
+}
+
+#[test]
+fn shrunk_interval() {
+    // A failure report, as shrinking leaves it: "no interval containing 5
+    // is wider than 3".
+    let found = counterexample(IntervalStrategy::default(), |i| {
+        prop_assert!(!i.contains(&5) || i.upper - i.lower <= 3);
+        Ok(())
+    });
+    insta::assert_debug_snapshot!(found);
+}
@@ -0,0 +1,193 @@
+// Unicode text handling beyond what `str` provides: grapheme clusters,
+// normalization, case folding, and display width.
+//
+// A Rust `String` is a sequence of bytes that is guaranteed to be valid
+// UTF-8. Its `len` counts bytes and its `chars` are Unicode scalar values,
+// but what a reader sees as one character may be several of either.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+/// The ways of measuring a string's length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lengths {
+    /// UTF-8 bytes: what `str::len` returns.
+    pub bytes: usize,
+    /// Unicode scalar values: what `str::chars` yields.
+    pub chars: usize,
+    /// Extended grapheme clusters: what a reader would call characters.
+    pub graphemes: usize,
+    /// Columns occupied on a terminal.
+    pub width: usize,
+}
+
+pub fn lengths(s: &str) -> Lengths {
+    Lengths {
+        bytes: s.len(),
+        chars: s.chars().count(),
+        graphemes: s.graphemes(true).count(),
+        width: width(s),
+    }
+}
+
+/// Split `s` into extended grapheme clusters.
+pub fn graphemes(s: &str) -> Vec<&str> {
+    s.graphemes(true).collect()
+}
+
+/// The number of terminal columns `s` occupies.
+///
+/// This sums the widths of `s`'s grapheme clusters, taking each cluster to
+/// be as wide as its widest character. Summing individual characters instead
+/// would count an emoji sequence like "👩‍🔬" (woman, zero-width joiner,
+/// microscope) as four columns rather than two.
+pub fn width(s: &str) -> usize {
+    s.graphemes(true).map(grapheme_width).sum()
+}
+
+fn grapheme_width(grapheme: &str) -> usize {
+    grapheme
+        .chars()
+        .map(|c| c.width().unwrap_or(0))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Shorten `s` to at most `max_width` columns, ending with `ellipsis` if
+/// anything was cut. Never splits a grapheme cluster. Returns `s` itself,
+/// borrowed, if it already fits.
+///
+/// If even the ellipsis is wider than `max_width`, returns as much of the
+/// ellipsis as fits.
+pub fn truncate_to_width<'a>(s: &'a str, max_width: usize, ellipsis: &str) -> Cow<'a, str> {
+    if width(s) <= max_width {
+        return Cow::Borrowed(s);
+    }
+    let budget = max_width.saturating_sub(width(ellipsis));
+    let mut used = 0;
+    let mut end = 0;
+    for (offset, grapheme) in s.grapheme_indices(true) {
+        let w = grapheme_width(grapheme);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        end = offset + grapheme.len();
+    }
+    if width(ellipsis) > max_width {
+        return Cow::Owned(truncate_to_width(ellipsis, max_width, "").into_owned());
+    }
+    Cow::Owned(format!("{}{}", &s[..end], ellipsis))
+}
+
+/// The Unicode normalization forms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Form {
+    /// Canonical composition: "é" as one character, U+00E9.
+    Nfc,
+    /// Canonical decomposition: "é" as "e" followed by U+0301 COMBINING
+    /// ACUTE ACCENT.
+    Nfd,
+    /// Compatibility composition: also folds away formatting distinctions,
+    /// so "ﬁ" becomes "fi" and "²" becomes "2".
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+impl Form {
+    pub const ALL: [Form; 4] = [Form::Nfc, Form::Nfd, Form::Nfkc, Form::Nfkd];
+}
+
+impl fmt::Display for Form {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Form::Nfc => "NFC",
+            Form::Nfd => "NFD",
+            Form::Nfkc => "NFKC",
+            Form::Nfkd => "NFKD",
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownForm(pub String);
+
+impl fmt::Display for UnknownForm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown normalization form {:?}: expected nfc, nfd, nfkc, or nfkd",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownForm {}
+
+impl FromStr for Form {
+    type Err = UnknownForm;
+
+    fn from_str(s: &str) -> Result<Form, UnknownForm> {
+        match s.to_ascii_lowercase().as_str() {
+            "nfc" => Ok(Form::Nfc),
+            "nfd" => Ok(Form::Nfd),
+            "nfkc" => Ok(Form::Nfkc),
+            "nfkd" => Ok(Form::Nfkd),
+            _ => Err(UnknownForm(s.to_string())),
+        }
+    }
+}
+
+pub fn normalize(s: &str, form: Form) -> String {
+    match form {
+        Form::Nfc => s.nfc().collect(),
+        Form::Nfd => s.nfd().collect(),
+        Form::Nfkc => s.nfkc().collect(),
+        Form::Nfkd => s.nfkd().collect(),
+    }
+}
+
+/// Fold case for case-insensitive comparison.
+///
+/// This is not the same as lowercasing: folding "Straße" must match
+/// "STRASSE", and the final-position "ς" must match "σ". Going through
+/// uppercase first handles both, since "ß" uppercases to "SS" and both
+/// sigmas uppercase to "Σ".
+pub fn fold_case(s: &str) -> String {
+    s.to_uppercase().to_lowercase()
+}
+
+/// Compare two strings ignoring case and canonical-equivalence differences,
+/// following the Unicode Standard's definition of canonical caseless
+/// matching: NFD(fold(NFD(a))) == NFD(fold(NFD(b))).
+pub fn caseless_eq(a: &str, b: &str) -> bool {
+    let key = |s: &str| -> String { fold_case(&s.nfd().collect::<String>()).nfd().collect() };
+    key(a) == key(b)
+}
+
+/// Describe each character of `s`: its code point, UTF-8 bytes, and width.
+pub fn describe_chars(s: &str) -> Vec<String> {
+    s.chars()
+        .map(|c| {
+            let mut buf = [0; 4];
+            let bytes: Vec<String> = c
+                .encode_utf8(&mut buf)
+                .bytes()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            format!(
+                "U+{:04X} {:?} bytes [{}] width {}",
+                c as u32,
+                c,
+                bytes.join(" "),
+                c.width().unwrap_or(0)
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+use std::io::{self, Read};
+use std::{env, process};
+
+use textwork::Form;
+
+enum Command {
+    Width {
+        max: Option<usize>,
+        text: Option<String>,
+    },
+    Normalize {
+        form: Form,
+        text: Option<String>,
+    },
+    Graphemes {
+        text: Option<String>,
+    },
+}
+
+impl Command {
+    fn parse() -> Self {
+        let args: Vec<String> = env::args().skip(1).collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match args.as_slice() {
+            ["width", "--max", max, rest @ ..] if rest.len() <= 1 => {
+                let max = max
+                    .parse()
+                    .unwrap_or_else(|_| fail(&format!("--max expects a number, got {:?}", max)));
+                Command::Width {
+                    max: Some(max),
+                    text: rest.first().map(|s| s.to_string()),
+                }
+            }
+            ["width", rest @ ..] if rest.len() <= 1 => Command::Width {
+                max: None,
+                text: rest.first().map(|s| s.to_string()),
+            },
+            ["normalize", form, rest @ ..] if rest.len() <= 1 => Command::Normalize {
+                form: form.parse().unwrap_or_else(|e| fail(&format!("{}", e))),
+                text: rest.first().map(|s| s.to_string()),
+            },
+            ["graphemes", rest @ ..] if rest.len() <= 1 => Command::Graphemes {
+                text: rest.first().map(|s| s.to_string()),
+            },
+            _ => {
+                print_usage();
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn print_usage() {
+    eprintln!("textwork - look at Unicode text the way Rust sees it");
+    eprintln!("Usage: textwork width [--max <columns>] [text]");
+    eprintln!("       textwork normalize <nfc|nfd|nfkc|nfkd> [text]");
+    eprintln!("       textwork graphemes [text]");
+    eprintln!("If no text is given, it is read from standard input.");
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    process::exit(1);
+}
+
+/// The text argument, or else all of standard input minus one trailing
+/// newline.
+fn text_or_stdin(text: Option<String>) -> String {
+    match text {
+        Some(text) => text,
+        None => {
+            let mut input = String::new();
+            if let Err(e) = io::stdin().read_to_string(&mut input) {
+                fail(&format!("failed to read standard input: {}", e));
+            }
+            if input.ends_with('\n') {
+                input.pop();
+                if input.ends_with('\r') {
+                    input.pop();
+                }
+            }
+            input
+        }
+    }
+}
+
+fn main() {
+    match Command::parse() {
+        Command::Width { max, text } => {
+            let text = text_or_stdin(text);
+            match max {
+                Some(max) => println!("{}", textwork::truncate_to_width(&text, max, "…")),
+                None => {
+                    let lengths = textwork::lengths(&text);
+                    println!("bytes:     {}", lengths.bytes);
+                    println!("chars:     {}", lengths.chars);
+                    println!("graphemes: {}", lengths.graphemes);
+                    println!("width:     {}", lengths.width);
+                }
+            }
+        }
+        Command::Normalize { form, text } => {
+            let text = text_or_stdin(text);
+            let normalized = textwork::normalize(&text, form);
+            println!("{}", normalized);
+            eprintln!(
+                "{}: {} chars, {} bytes (was {} chars, {} bytes)",
+                form,
+                normalized.chars().count(),
+                normalized.len(),
+                text.chars().count(),
+                text.len()
+            );
+        }
+        Command::Graphemes { text } => {
+            let text = text_or_stdin(text);
+            for (i, grapheme) in textwork::graphemes(&text).into_iter().enumerate() {
+                println!("{:>3}: {:?}", i, grapheme);
+                for line in textwork::describe_chars(grapheme) {
+                    println!("       {}", line);
+                }
+            }
+        }
+    }
+}
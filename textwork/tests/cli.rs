@@ -0,0 +1,71 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn textwork(args: &[&str], stdin: Option<&str>) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_textwork"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut pipe = child.stdin.take().unwrap();
+    pipe.write_all(stdin.unwrap_or("").as_bytes()).unwrap();
+    drop(pipe);
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn width_reports_every_length() {
+    let output = textwork(&["width", "cafe\u{301}"], None);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "bytes:     6\nchars:     5\ngraphemes: 4\nwidth:     4\n"
+    );
+}
+
+#[test]
+fn width_truncates() {
+    let output = textwork(&["width", "--max", "5", "日本語のテキスト"], None);
+    assert_eq!(stdout(&output), "日本…\n");
+}
+
+#[test]
+fn normalize_reads_stdin() {
+    let output = textwork(&["normalize", "nfd"], Some("caf\u{e9}\n"));
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "cafe\u{301}\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("NFD: 5 chars, 6 bytes (was 4 chars, 5 bytes)"));
+}
+
+#[test]
+fn graphemes_lists_clusters_and_their_chars() {
+    let output = textwork(&["graphemes", "e\u{301}!"], None);
+    assert_eq!(
+        stdout(&output),
+        concat!(
+            "  0: \"e\\u{301}\"\n",
+            "       U+0065 'e' bytes [65] width 1\n",
+            "       U+0301 '\\u{301}' bytes [cc 81] width 0\n",
+            "  1: \"!\"\n",
+            "       U+0021 '!' bytes [21] width 1\n",
+        )
+    );
+}
+
+#[test]
+fn bad_arguments_fail_with_usage() {
+    let output = textwork(&["frobnicate"], None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Usage: textwork"));
+
+    let output = textwork(&["normalize", "nfx", "text"], None);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown normalization form"));
+}
@@ -0,0 +1,139 @@
+use std::borrow::Cow;
+
+use textwork::{
+    caseless_eq, fold_case, graphemes, lengths, normalize, truncate_to_width, width, Form, Lengths,
+};
+
+#[test]
+fn bytes_chars_and_graphemes_differ() {
+    assert_eq!(
+        lengths("hello"),
+        Lengths {
+            bytes: 5,
+            chars: 5,
+            graphemes: 5,
+            width: 5
+        }
+    );
+    // Precomposed é is one char of two bytes.
+    assert_eq!(
+        lengths("caf\u{e9}"),
+        Lengths {
+            bytes: 5,
+            chars: 4,
+            graphemes: 4,
+            width: 4
+        }
+    );
+    // Decomposed é is two chars, but still one grapheme.
+    assert_eq!(
+        lengths("cafe\u{301}"),
+        Lengths {
+            bytes: 6,
+            chars: 5,
+            graphemes: 4,
+            width: 4
+        }
+    );
+    // CJK characters are three bytes each and two columns wide.
+    assert_eq!(
+        lengths("日本"),
+        Lengths {
+            bytes: 6,
+            chars: 2,
+            graphemes: 2,
+            width: 4
+        }
+    );
+    // Woman scientist: woman + zero-width joiner + microscope.
+    assert_eq!(
+        lengths("👩\u{200d}🔬"),
+        Lengths {
+            bytes: 11,
+            chars: 3,
+            graphemes: 1,
+            width: 2
+        }
+    );
+}
+
+#[test]
+fn graphemes_keep_clusters_together() {
+    assert_eq!(graphemes("ae\u{301}o"), ["a", "e\u{301}", "o"]);
+    assert_eq!(graphemes("🇯🇵🇫🇷"), ["🇯🇵", "🇫🇷"]);
+    assert_eq!(graphemes("\r\n"), ["\r\n"]);
+    assert!(graphemes("").is_empty());
+}
+
+#[test]
+fn normalization_forms() {
+    let composed = "caf\u{e9}";
+    let decomposed = "cafe\u{301}";
+    assert_ne!(composed, decomposed);
+    assert_eq!(normalize(decomposed, Form::Nfc), composed);
+    assert_eq!(normalize(composed, Form::Nfd), decomposed);
+
+    // Compatibility forms fold ligatures and superscripts.
+    assert_eq!(normalize("\u{fb01}x\u{b2}", Form::Nfkc), "fix2");
+    assert_eq!(normalize("\u{fb01}x\u{b2}", Form::Nfc), "\u{fb01}x\u{b2}");
+
+    // Normalizing is idempotent.
+    for form in Form::ALL {
+        let once = normalize("Å\u{212b}ﬃ\u{1e9b}\u{323}", form);
+        assert_eq!(normalize(&once, form), once, "{}", form);
+    }
+}
+
+#[test]
+fn forms_parse_case_insensitively() {
+    assert_eq!("NFKD".parse(), Ok(Form::Nfkd));
+    assert_eq!("nfc".parse(), Ok(Form::Nfc));
+    assert!("nfx".parse::<Form>().is_err());
+    assert_eq!(Form::Nfkc.to_string(), "NFKC");
+}
+
+#[test]
+fn case_folding_is_not_lowercasing() {
+    assert_eq!(fold_case("Straße"), "strasse");
+    assert_eq!(fold_case("STRASSE"), "strasse");
+    assert_ne!("Straße".to_lowercase(), "STRASSE".to_lowercase());
+    assert_eq!(fold_case("ὈΔΥΣΣΕΎΣ"), fold_case("ὀδυσσεύς"));
+
+    assert!(caseless_eq("Straße", "STRASSE"));
+    assert!(caseless_eq("CAFE\u{301}", "caf\u{e9}"));
+    assert!(!caseless_eq("cafe", "café"));
+}
+
+#[test]
+fn width_counts_columns() {
+    assert_eq!(width(""), 0);
+    assert_eq!(width("abc"), 3);
+    assert_eq!(width("ｗｉｄｅ"), 8);
+    assert_eq!(width("e\u{301}"), 1);
+    assert_eq!(width("👍🏽"), 2);
+}
+
+#[test]
+fn truncation_respects_width_and_clusters() {
+    assert!(matches!(
+        truncate_to_width("short", 10, "…"),
+        Cow::Borrowed("short")
+    ));
+    assert_eq!(truncate_to_width("hello, world", 8, "…"), "hello, …");
+    assert_eq!(truncate_to_width("hello, world", 8, "..."), "hello...");
+
+    // Wide characters are never cut in half: "日本語" is 6 columns, and
+    // with a one-column ellipsis, only two of them fit in 5 columns.
+    let cut = truncate_to_width("日本語", 5, "…");
+    assert_eq!(cut, "日本…");
+    assert!(width(&cut) <= 5);
+
+    // Combining marks stay with their base.
+    assert_eq!(
+        truncate_to_width("e\u{301}e\u{301}e\u{301}", 2, "…"),
+        "e\u{301}…"
+    );
+
+    assert_eq!(truncate_to_width("abc", 0, "…"), "");
+    assert_eq!(truncate_to_width("abcdef", 2, "..."), "..");
+}
@@ -0,0 +1,10 @@
+// A small JSON library with no dependencies: a `Json` value enum, a
+// hand-written recursive-descent parser, and a printer.
+
+mod parse;
+mod print;
+mod value;
+
+pub use parse::{parse, parse_bytes, ErrorKind, ParseError, MAX_DEPTH};
+pub use print::Printer;
+pub use value::Json;
@@ -0,0 +1,35 @@
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use tiny_json::{parse, Printer};
+
+/// Read JSON from standard input and pretty-print it, indented by the
+/// number of spaces given as the first argument (default 2).
+fn main() -> ExitCode {
+    let width = match std::env::args().nth(1).map(|arg| arg.parse::<usize>()) {
+        None => 2,
+        Some(Ok(width)) => width,
+        Some(Err(_)) => {
+            eprintln!("usage: tiny-json [INDENT] < input.json");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("tiny-json: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    match parse(&input) {
+        Ok(value) => {
+            println!("{}", Printer::new().indent_width(width).print(&value));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            let (line, column) = e.line_column(&input);
+            eprintln!("tiny-json: {}:{}: {}", line, column, e.kind);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,425 @@
+// A recursive-descent parser for JSON, as specified by RFC 8259.
+//
+// Each grammar rule is a method that consumes its part of the input and
+// returns the value it parsed. Errors carry the byte offset where the
+// parser gave up.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::value::Json;
+
+/// How deeply arrays and objects may nest. The parser recurses once per
+/// level, so without a limit, a few kilobytes of `[` could overflow the
+/// stack.
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// A byte that can't appear here.
+    UnexpectedByte(u8),
+    /// A complete value followed by more than whitespace.
+    TrailingCharacters,
+    /// A malformed number, like `01`, `1.`, or `-`.
+    InvalidNumber,
+    /// A number too large for an `f64`.
+    NumberOutOfRange,
+    /// A backslash followed by something other than a valid escape.
+    InvalidEscape,
+    /// A `\u` escape for half of a surrogate pair, without the other half.
+    LoneSurrogate,
+    /// An unescaped control character (below U+0020) inside a string.
+    ControlCharacter,
+    /// An object key that isn't a string.
+    ExpectedKey,
+    /// An object key not followed by a colon.
+    ExpectedColon,
+    /// An array element or object member not followed by a comma or the
+    /// closing bracket.
+    ExpectedCommaOrClose,
+    /// Nesting deeper than `MAX_DEPTH`.
+    TooDeep,
+    /// Input that isn't valid UTF-8.
+    InvalidUtf8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    /// The byte offset in the input where the error was detected.
+    pub offset: usize,
+}
+
+impl ParseError {
+    /// The 1-based line and column of the error in `input`, which must be
+    /// the text that was parsed. Columns count characters, not bytes.
+    pub fn line_column(&self, input: &str) -> (usize, usize) {
+        let before = &input.as_bytes()[..self.offset.min(input.len())];
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+        let column = String::from_utf8_lossy(&before[line_start..])
+            .chars()
+            .count()
+            + 1;
+        (line, column)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedByte(b) if b.is_ascii_graphic() => {
+                write!(f, "unexpected character '{}'", *b as char)
+            }
+            ErrorKind::UnexpectedByte(b) => write!(f, "unexpected byte 0x{:02x}", b),
+            ErrorKind::TrailingCharacters => write!(f, "trailing characters after JSON value"),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::NumberOutOfRange => write!(f, "number out of range"),
+            ErrorKind::InvalidEscape => write!(f, "invalid escape sequence"),
+            ErrorKind::LoneSurrogate => write!(f, "unpaired surrogate in \\u escape"),
+            ErrorKind::ControlCharacter => write!(f, "control character in string"),
+            ErrorKind::ExpectedKey => write!(f, "expected string key"),
+            ErrorKind::ExpectedColon => write!(f, "expected ':'"),
+            ErrorKind::ExpectedCommaOrClose => write!(f, "expected ',' or closing bracket"),
+            ErrorKind::TooDeep => write!(f, "nesting deeper than {} levels", MAX_DEPTH),
+            ErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8"),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse a complete JSON document.
+pub fn parse(input: &str) -> std::result::Result<Json, ParseError> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        pos: 0,
+        depth: 0,
+    };
+    parser.skip_whitespace();
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.input.len() {
+        return Err(parser.error(ErrorKind::TrailingCharacters));
+    }
+    Ok(value)
+}
+
+/// Parse a JSON document from bytes, which must be UTF-8.
+pub fn parse_bytes(input: &[u8]) -> std::result::Result<Json, ParseError> {
+    match std::str::from_utf8(input) {
+        Ok(text) => parse(text),
+        Err(e) => Err(ParseError {
+            kind: ErrorKind::InvalidUtf8,
+            offset: e.valid_up_to(),
+        }),
+    }
+}
+
+struct Parser<'a> {
+    /// Valid UTF-8, since it came from a `&str`; the parser only ever
+    /// splits it at ASCII bytes, so every slice it takes is valid too.
+    input: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+impl Parser<'_> {
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        ParseError {
+            kind,
+            offset: self.pos,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    /// The next byte, or an `UnexpectedEnd` error.
+    fn next_byte(&mut self) -> Result<u8> {
+        let b = self
+            .peek()
+            .ok_or_else(|| self.error(ErrorKind::UnexpectedEnd))?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(b) => self.error(ErrorKind::UnexpectedByte(b)),
+            None => self.error(ErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// value = object / array / string / number / "true" / "false" / "null"
+    fn value(&mut self) -> Result<Json> {
+        match self.peek() {
+            Some(b'{') => self.nested(Parser::object),
+            Some(b'[') => self.nested(Parser::array),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true", Json::Boolean(true)),
+            Some(b'f') => self.literal("false", Json::Boolean(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json>) -> Result<Json> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json> {
+        for &expected in word.as_bytes() {
+            if self.peek() != Some(expected) {
+                return Err(self.unexpected());
+            }
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+
+    /// array = "[" [ value *( "," value ) ] "]"
+    fn array(&mut self) -> Result<Json> {
+        self.pos += 1; // '['
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(elements));
+        }
+        loop {
+            self.skip_whitespace();
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(elements));
+                }
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some(_) => return Err(self.error(ErrorKind::ExpectedCommaOrClose)),
+            }
+        }
+    }
+
+    /// object = "{" [ string ":" value *( "," string ":" value ) ] "}"
+    fn object(&mut self) -> Result<Json> {
+        self.pos += 1; // '{'
+        let mut members = BTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'"') => {}
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some(_) => return Err(self.error(ErrorKind::ExpectedKey)),
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b':') => self.pos += 1,
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some(_) => return Err(self.error(ErrorKind::ExpectedColon)),
+            }
+            self.skip_whitespace();
+            let value = self.value()?;
+            members.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(members));
+                }
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some(_) => return Err(self.error(ErrorKind::ExpectedCommaOrClose)),
+            }
+        }
+    }
+
+    /// number = [ "-" ] int [ frac ] [ exp ]
+    fn number(&mut self) -> Result<Json> {
+        let start = self.pos;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        // int = "0" / digit1-9 *digit
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error(ErrorKind::InvalidNumber)),
+        }
+        // frac = "." 1*digit
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.one_or_more_digits()?;
+        }
+        // exp = ("e" / "E") [ "-" / "+" ] 1*digit
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'-' | b'+') = self.peek() {
+                self.pos += 1;
+            }
+            self.one_or_more_digits()?;
+        }
+        // The grammar above is stricter than `f64::from_str`, so anything
+        // that got this far parses.
+        let text = std::str::from_utf8(&self.input[start..self.pos]).expect("ASCII digits");
+        let n: f64 = text.parse().expect("valid JSON number syntax");
+        if n.is_infinite() {
+            return Err(ParseError {
+                kind: ErrorKind::NumberOutOfRange,
+                offset: start,
+            });
+        }
+        Ok(Json::Number(n))
+    }
+
+    fn digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn one_or_more_digits(&mut self) -> Result<()> {
+        match self.peek() {
+            Some(b'0'..=b'9') => {
+                self.digits();
+                Ok(())
+            }
+            _ => Err(self.error(ErrorKind::InvalidNumber)),
+        }
+    }
+
+    /// string = quotation-mark *char quotation-mark
+    fn string(&mut self) -> Result<String> {
+        self.pos += 1; // '"'
+        let mut s = String::new();
+        loop {
+            // Copy the run of ordinary characters in one go.
+            let run_start = self.pos;
+            while let Some(b) = self.peek() {
+                if b == b'"' || b == b'\\' || b < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            s.push_str(
+                std::str::from_utf8(&self.input[run_start..self.pos]).expect("input is UTF-8"),
+            );
+
+            match self.peek() {
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    s.push(self.escape()?);
+                }
+                Some(_) => return Err(self.error(ErrorKind::ControlCharacter)),
+            }
+        }
+    }
+
+    /// The character for the escape sequence after a backslash.
+    fn escape(&mut self) -> Result<char> {
+        let escape_start = self.pos - 1;
+        Ok(match self.next_byte()? {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let unit = self.hex4()?;
+                let lone = ParseError {
+                    kind: ErrorKind::LoneSurrogate,
+                    offset: escape_start,
+                };
+                match unit {
+                    0xd800..=0xdbff => {
+                        // A high surrogate must be followed by an escaped
+                        // low surrogate; together they encode one
+                        // character beyond the Basic Multilingual Plane.
+                        if self.input.get(self.pos..self.pos + 2) != Some(b"\\u") {
+                            return Err(lone);
+                        }
+                        self.pos += 2;
+                        let low = self.hex4()?;
+                        if !(0xdc00..=0xdfff).contains(&low) {
+                            return Err(lone);
+                        }
+                        let c = 0x10000
+                            + ((u32::from(unit) - 0xd800) << 10)
+                            + (u32::from(low) - 0xdc00);
+                        char::from_u32(c).expect("surrogate pairs encode valid characters")
+                    }
+                    0xdc00..=0xdfff => return Err(lone),
+                    _ => char::from_u32(u32::from(unit))
+                        .expect("non-surrogates are valid characters"),
+                }
+            }
+            _ => {
+                return Err(ParseError {
+                    kind: ErrorKind::InvalidEscape,
+                    offset: escape_start,
+                })
+            }
+        })
+    }
+
+    /// Four hex digits, as in a `\u` escape.
+    fn hex4(&mut self) -> Result<u16> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let digit = match self.peek() {
+                Some(b @ b'0'..=b'9') => b - b'0',
+                Some(b @ b'a'..=b'f') => b - b'a' + 10,
+                Some(b @ b'A'..=b'F') => b - b'A' + 10,
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some(_) => return Err(self.error(ErrorKind::InvalidEscape)),
+            };
+            self.pos += 1;
+            value = value * 16 + u16::from(digit);
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,140 @@
+// Turning `Json` values back into text.
+
+use std::fmt::{self, Write};
+
+use crate::value::Json;
+
+/// Formats `Json` values. The default printer is compact; `indent` makes
+/// it pretty-print, putting each array element and object member on its
+/// own line.
+#[derive(Clone, Debug, Default)]
+pub struct Printer {
+    indent: Option<String>,
+}
+
+impl Printer {
+    pub fn new() -> Printer {
+        Printer::default()
+    }
+
+    /// Pretty-print, indenting each level with `indent` (for example, two
+    /// spaces or a tab).
+    pub fn indent(mut self, indent: &str) -> Printer {
+        self.indent = Some(indent.to_string());
+        self
+    }
+
+    /// Pretty-print, indenting each level with `width` spaces.
+    pub fn indent_width(self, width: usize) -> Printer {
+        self.indent(&" ".repeat(width))
+    }
+
+    pub fn print(&self, value: &Json) -> String {
+        let mut out = String::new();
+        self.write(&mut out, value)
+            .expect("writing to a String can't fail");
+        out
+    }
+
+    pub fn write<W: Write>(&self, out: &mut W, value: &Json) -> fmt::Result {
+        self.value(out, value, 0)
+    }
+
+    fn value<W: Write>(&self, out: &mut W, value: &Json, depth: usize) -> fmt::Result {
+        match value {
+            Json::Null => out.write_str("null"),
+            Json::Boolean(b) => write!(out, "{}", b),
+            Json::Number(n) => write_number(out, *n),
+            Json::String(s) => write_string(out, s),
+            Json::Array(elements) => {
+                if elements.is_empty() {
+                    return out.write_str("[]");
+                }
+                out.write_char('[')?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        out.write_char(',')?;
+                    }
+                    self.newline(out, depth + 1)?;
+                    self.value(out, element, depth + 1)?;
+                }
+                self.newline(out, depth)?;
+                out.write_char(']')
+            }
+            Json::Object(members) => {
+                if members.is_empty() {
+                    return out.write_str("{}");
+                }
+                out.write_char('{')?;
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.write_char(',')?;
+                    }
+                    self.newline(out, depth + 1)?;
+                    write_string(out, key)?;
+                    out.write_str(if self.indent.is_some() { ": " } else { ":" })?;
+                    self.value(out, value, depth + 1)?;
+                }
+                self.newline(out, depth)?;
+                out.write_char('}')
+            }
+        }
+    }
+
+    /// When pretty-printing, start a new line indented `depth` levels.
+    fn newline<W: Write>(&self, out: &mut W, depth: usize) -> fmt::Result {
+        if let Some(indent) = &self.indent {
+            out.write_char('\n')?;
+            for _ in 0..depth {
+                out.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write the shortest text that parses back to exactly `n`. Integers print
+/// without a fractional part; very large and very small magnitudes use
+/// exponent notation rather than long runs of zeros.
+fn write_number<W: Write>(out: &mut W, n: f64) -> fmt::Result {
+    if !n.is_finite() {
+        // JSON has no representation for these.
+        return out.write_str("null");
+    }
+    let magnitude = n.abs();
+    if magnitude != 0.0 && !(1e-6..1e16).contains(&magnitude) {
+        write!(out, "{:e}", n)
+    } else {
+        write!(out, "{}", n)
+    }
+}
+
+fn write_string<W: Write>(out: &mut W, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{8}' => out.write_str("\\b")?,
+            '\u{c}' => out.write_str("\\f")?,
+            c if c < ' ' => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// `{}` prints compactly; `{:#}` pretty-prints with two-space indentation.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printer = if f.alternate() {
+            Printer::new().indent_width(2)
+        } else {
+            Printer::new()
+        };
+        printer.write(f, self)
+    }
+}
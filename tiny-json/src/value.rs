@@ -0,0 +1,131 @@
+// The `Json` enum: one variant per kind of JSON value.
+
+use std::collections::BTreeMap;
+use std::ops::Index;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Boolean(bool),
+    /// JSON numbers are decimal, but like JavaScript, we store them as
+    /// `f64`. Parsing rejects numbers too large to represent, so this is
+    /// always finite.
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Objects keep their members sorted by key. If a key appears more than
+    /// once in the input, the last value wins.
+    Object(BTreeMap<String, Json>),
+}
+
+/// Indexing a value that isn't an array or object, or with a missing key or
+/// out-of-range index, yields `Null`, so lookups can be chained:
+/// `doc["users"][0]["name"]`.
+static NULL: Json = Json::Null;
+
+impl Json {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Json::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&BTreeMap<String, Json>> {
+        match self {
+            Json::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Look up a member of an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        self.as_object()?.get(key)
+    }
+}
+
+impl Index<&str> for Json {
+    type Output = Json;
+    fn index(&self, key: &str) -> &Json {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl Index<usize> for Json {
+    type Output = Json;
+    fn index(&self, index: usize) -> &Json {
+        self.as_array().and_then(|a| a.get(index)).unwrap_or(&NULL)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Boolean(b)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Json {
+        Json::Number(n)
+    }
+}
+
+impl From<i32> for Json {
+    fn from(n: i32) -> Json {
+        Json::Number(n.into())
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(elements: Vec<T>) -> Json {
+        Json::Array(elements.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Json>> FromIterator<(K, V)> for Json {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(members: I) -> Json {
+        Json::Object(
+            members
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,214 @@
+use tiny_json::{parse, parse_bytes, ErrorKind, Json, ParseError, MAX_DEPTH};
+
+fn error(input: &str) -> ParseError {
+    parse(input).expect_err(input)
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(parse("null"), Ok(Json::Null));
+    assert_eq!(parse(" true "), Ok(Json::Boolean(true)));
+    assert_eq!(parse("false"), Ok(Json::Boolean(false)));
+    assert_eq!(parse("-12.5e2"), Ok(Json::Number(-1250.0)));
+    assert_eq!(parse("0"), Ok(Json::Number(0.0)));
+    assert_eq!(parse(r#""hi""#), Ok(Json::from("hi")));
+}
+
+#[test]
+fn nesting() {
+    let doc =
+        parse(r#"{"users": [{"name": "Ferris", "tags": []}, {"name": "Corro"}], "n": 2}"#).unwrap();
+    assert_eq!(doc["users"][0]["name"].as_str(), Some("Ferris"));
+    assert_eq!(doc["users"][1]["name"].as_str(), Some("Corro"));
+    assert_eq!(doc["users"][0]["tags"].as_array(), Some(&[][..]));
+    assert_eq!(doc["n"].as_f64(), Some(2.0));
+    assert!(doc["users"][5]["missing"].is_null());
+    assert!(doc["n"]["x"].is_null());
+}
+
+#[test]
+fn duplicate_keys_last_wins() {
+    let doc = parse(r#"{"a": 1, "a": 2}"#).unwrap();
+    assert_eq!(doc["a"], Json::Number(2.0));
+    assert_eq!(doc.as_object().unwrap().len(), 1);
+}
+
+#[test]
+fn escapes() {
+    let doc = parse(r#""\"\\\/\b\f\n\r\tAé中🦀""#).unwrap();
+    assert_eq!(doc.as_str(), Some("\"\\/\u{8}\u{c}\n\r\tAé中🦀"));
+    // Unescaped non-ASCII passes straight through.
+    assert_eq!(parse("\"crab 🦀\"").unwrap().as_str(), Some("crab 🦀"));
+}
+
+#[test]
+fn error_offsets() {
+    assert_eq!(
+        error(""),
+        ParseError {
+            kind: ErrorKind::UnexpectedEnd,
+            offset: 0
+        }
+    );
+    assert_eq!(
+        error("[1, 2"),
+        ParseError {
+            kind: ErrorKind::UnexpectedEnd,
+            offset: 5
+        }
+    );
+    assert_eq!(
+        error("[1 2]"),
+        ParseError {
+            kind: ErrorKind::ExpectedCommaOrClose,
+            offset: 3
+        }
+    );
+    assert_eq!(
+        error("[1,]"),
+        ParseError {
+            kind: ErrorKind::UnexpectedByte(b']'),
+            offset: 3
+        }
+    );
+    assert_eq!(
+        error(r#"{"a" 1}"#),
+        ParseError {
+            kind: ErrorKind::ExpectedColon,
+            offset: 5
+        }
+    );
+    assert_eq!(
+        error("{1: 2}"),
+        ParseError {
+            kind: ErrorKind::ExpectedKey,
+            offset: 1
+        }
+    );
+    assert_eq!(
+        error("nul"),
+        ParseError {
+            kind: ErrorKind::UnexpectedEnd,
+            offset: 3
+        }
+    );
+    assert_eq!(
+        error("trUe"),
+        ParseError {
+            kind: ErrorKind::UnexpectedByte(b'U'),
+            offset: 2
+        }
+    );
+    assert_eq!(
+        error("1 2"),
+        ParseError {
+            kind: ErrorKind::TrailingCharacters,
+            offset: 2
+        }
+    );
+}
+
+#[test]
+fn number_errors() {
+    assert_eq!(error("01").kind, ErrorKind::TrailingCharacters);
+    assert_eq!(error("-").kind, ErrorKind::InvalidNumber);
+    assert_eq!(error("1.").offset, 2);
+    assert_eq!(error("1.e5").kind, ErrorKind::InvalidNumber);
+    assert_eq!(error("1e+").kind, ErrorKind::InvalidNumber);
+    assert_eq!(error("+1").kind, ErrorKind::UnexpectedByte(b'+'));
+    assert_eq!(error(".5").kind, ErrorKind::UnexpectedByte(b'.'));
+    assert_eq!(
+        error("[1e400]"),
+        ParseError {
+            kind: ErrorKind::NumberOutOfRange,
+            offset: 1
+        }
+    );
+    // Underflow rounds to zero instead of failing.
+    assert_eq!(parse("1e-400"), Ok(Json::Number(0.0)));
+}
+
+#[test]
+fn string_errors() {
+    assert_eq!(
+        error(r#""abc"#),
+        ParseError {
+            kind: ErrorKind::UnexpectedEnd,
+            offset: 4
+        }
+    );
+    assert_eq!(
+        error(r#""a\x""#),
+        ParseError {
+            kind: ErrorKind::InvalidEscape,
+            offset: 2
+        }
+    );
+    assert_eq!(
+        error(r#""\u12G4""#),
+        ParseError {
+            kind: ErrorKind::InvalidEscape,
+            offset: 5
+        }
+    );
+    assert_eq!(
+        error("\"a\tb\""),
+        ParseError {
+            kind: ErrorKind::ControlCharacter,
+            offset: 2
+        }
+    );
+    assert_eq!(
+        error(r#""x\ud800""#),
+        ParseError {
+            kind: ErrorKind::LoneSurrogate,
+            offset: 2
+        }
+    );
+    assert_eq!(error(r#""\udc00\ud800""#).kind, ErrorKind::LoneSurrogate);
+    assert_eq!(error(r#""\ud800A""#).kind, ErrorKind::LoneSurrogate);
+}
+
+#[test]
+fn depth_limit() {
+    let ok = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+    assert!(parse(&ok).is_ok());
+
+    let deep = "[".repeat(MAX_DEPTH + 1) + &"]".repeat(MAX_DEPTH + 1);
+    assert_eq!(
+        error(&deep),
+        ParseError {
+            kind: ErrorKind::TooDeep,
+            offset: MAX_DEPTH
+        }
+    );
+
+    // Far too deep to recurse through, but rejected long before the stack
+    // runs out.
+    let absurd = "[{\"a\":".repeat(100_000);
+    assert_eq!(error(&absurd).kind, ErrorKind::TooDeep);
+}
+
+#[test]
+fn invalid_utf8() {
+    assert_eq!(
+        parse_bytes(b"[\"ok\", \"\xff\"]"),
+        Err(ParseError {
+            kind: ErrorKind::InvalidUtf8,
+            offset: 8
+        })
+    );
+    assert_eq!(parse_bytes(b"[1]"), Ok(Json::from(vec![1])));
+}
+
+#[test]
+fn line_column() {
+    let input = "{\n  \"é\": [1,\n   2 3]\n}";
+    let e = error(input);
+    assert_eq!(e.kind, ErrorKind::ExpectedCommaOrClose);
+    assert_eq!(e.line_column(input), (3, 6));
+    assert_eq!(
+        e.to_string(),
+        format!("expected ',' or closing bracket at byte {}", e.offset)
+    );
+}
@@ -0,0 +1,100 @@
+use proptest::prelude::*;
+use tiny_json::{parse, Json, Printer};
+
+fn sample() -> Json {
+    parse(r#"{"name": "Ferris", "legs": 10, "tags": ["crab", "rust"], "home": {}, "pets": [], "x": null}"#)
+        .unwrap()
+}
+
+#[test]
+fn compact() {
+    assert_eq!(
+        sample().to_string(),
+        r#"{"home":{},"legs":10,"name":"Ferris","pets":[],"tags":["crab","rust"],"x":null}"#
+    );
+}
+
+#[test]
+fn pretty_default() {
+    let expected = r#"{
+  "home": {},
+  "legs": 10,
+  "name": "Ferris",
+  "pets": [],
+  "tags": [
+    "crab",
+    "rust"
+  ],
+  "x": null
+}"#;
+    assert_eq!(format!("{:#}", sample()), expected);
+    assert_eq!(Printer::new().indent_width(2).print(&sample()), expected);
+}
+
+#[test]
+fn configurable_indent() {
+    let value = parse(r#"[[1, {"a": true}]]"#).unwrap();
+    assert_eq!(
+        Printer::new().indent_width(4).print(&value),
+        "[\n    [\n        1,\n        {\n            \"a\": true\n        }\n    ]\n]"
+    );
+    assert_eq!(
+        Printer::new().indent("\t").print(&value),
+        "[\n\t[\n\t\t1,\n\t\t{\n\t\t\t\"a\": true\n\t\t}\n\t]\n]"
+    );
+    // Zero width still breaks lines, just without indentation.
+    assert_eq!(
+        Printer::new()
+            .indent_width(0)
+            .print(&Json::from(vec![1, 2])),
+        "[\n1,\n2\n]"
+    );
+}
+
+#[test]
+fn numbers() {
+    let print = |n: f64| Json::Number(n).to_string();
+    assert_eq!(print(0.0), "0");
+    assert_eq!(print(-3.0), "-3");
+    assert_eq!(print(0.1), "0.1");
+    assert_eq!(print(123456789.0), "123456789");
+    assert_eq!(print(1e16), "1e16");
+    assert_eq!(print(1.5e300), "1.5e300");
+    assert_eq!(print(2.5e-7), "2.5e-7");
+    assert_eq!(print(f64::NAN), "null");
+}
+
+#[test]
+fn string_escapes() {
+    let value = Json::from("quote \" slash \\ tab \t nl \n bell \u{7} é");
+    assert_eq!(
+        value.to_string(),
+        r#""quote \" slash \\ tab \t nl \n bell \u0007 é""#
+    );
+    assert_eq!(parse(&value.to_string()), Ok(value));
+}
+
+fn arb_json() -> impl Strategy<Value = Json> {
+    let leaf = prop_oneof![
+        Just(Json::Null),
+        any::<bool>().prop_map(Json::Boolean),
+        any::<f64>()
+            .prop_filter("finite", |n| n.is_finite())
+            .prop_map(Json::Number),
+        any::<String>().prop_map(Json::String),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Json::Array),
+            prop::collection::btree_map(any::<String>(), inner, 0..8).prop_map(Json::Object),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn round_trip(value in arb_json(), width in 0usize..5) {
+        prop_assert_eq!(parse(&value.to_string()), Ok(value.clone()));
+        prop_assert_eq!(parse(&Printer::new().indent_width(width).print(&value)), Ok(value));
+    }
+}
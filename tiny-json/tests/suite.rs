@@ -0,0 +1,62 @@
+// Conformance tests against a subset of Nicolas Seriot's JSONTestSuite
+// (https://github.com/nst/JSONTestSuite). Each file in `tests/suite` is
+// named after the suite's case: `y_` files must parse, and `n_` files must
+// be rejected.
+
+use std::fs;
+use std::path::Path;
+
+use tiny_json::parse_bytes;
+
+fn cases(prefix: &str) -> Vec<(String, Vec<u8>)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/suite");
+    let mut cases: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with(prefix)
+        })
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, fs::read(path).unwrap())
+        })
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "no {}* cases found", prefix);
+    cases
+}
+
+#[test]
+fn accepts() {
+    let failures: Vec<_> = cases("y_")
+        .into_iter()
+        .filter_map(|(name, input)| {
+            parse_bytes(&input)
+                .err()
+                .map(|e| format!("{}: {}", name, e))
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "rejected valid JSON:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn rejects() {
+    let failures: Vec<_> = cases("n_")
+        .into_iter()
+        .filter(|(_, input)| parse_bytes(input).is_ok())
+        .map(|(name, _)| name)
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "accepted invalid JSON:\n{}",
+        failures.join("\n")
+    );
+}
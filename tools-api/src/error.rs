@@ -0,0 +1,106 @@
+use std::fmt;
+
+use axum::extract::rejection::JsonRejection;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The body isn't the JSON the endpoint expects: malformed, missing a
+    /// field, the wrong content type, or too large.
+    BadRequest(JsonRejection),
+    /// A field is well-formed JSON, but not a value the endpoint accepts.
+    Invalid { field: &'static str, reason: String },
+    /// The pattern isn't a regular expression.
+    Regex(regex::Error),
+    /// The calculator couldn't evaluate the expression.
+    Eval(ch_03::EvalError),
+    /// A line of the text to sum isn't an integer.
+    NotANumber { line: usize, text: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::BadRequest(rejection) => write!(f, "{}", rejection.body_text()),
+            Error::Invalid { field, reason } => write!(f, "invalid {}: {}", field, reason),
+            Error::Regex(e) => write!(f, "{}", e),
+            Error::Eval(e) => write!(f, "{}", e),
+            Error::NotANumber { line, text } => {
+                write!(f, "line {} isn't an integer: {:?}", line, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<JsonRejection> for Error {
+    fn from(rejection: JsonRejection) -> Error {
+        Error::BadRequest(rejection)
+    }
+}
+
+/// The body of every error response. `kind` is for programs to match on,
+/// and `error` for people to read; `field`, `line`, and `column` say where
+/// the problem is, when there's a place to point to.
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let mut body = ErrorBody {
+            error: self.to_string(),
+            kind: "",
+            field: None,
+            line: None,
+            column: None,
+        };
+        let status = match &self {
+            // axum picks the status: 400 for bad JSON, 413 for too large,
+            // 415 for the wrong content type, 422 for the wrong shape.
+            Error::BadRequest(rejection) => {
+                body.kind = "bad_request";
+                rejection.status()
+            }
+            Error::Invalid { field, .. } => {
+                body.kind = "invalid_field";
+                body.field = Some(field);
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Error::Regex(_) => {
+                body.kind = "bad_pattern";
+                body.field = Some("pattern");
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Error::Eval(e) => {
+                body.kind = "eval_error";
+                body.field = Some("expression");
+                body.column = match e {
+                    ch_03::EvalError::Syntax { column, .. }
+                    | ch_03::EvalError::TooDeep { column } => Some(*column),
+                    _ => None,
+                };
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Error::NotANumber { line, .. } => {
+                body.kind = "not_a_number";
+                body.field = Some("text");
+                body.line = Some(*line);
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+        };
+        (status, Json(body)).into_response()
+    }
+}
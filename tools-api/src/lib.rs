@@ -0,0 +1,17 @@
+// The workspace's command-line tools as a web service: the same library
+// code, behind JSON endpoints instead of arguments and files.
+//
+//     POST /replace   {"pattern": "wor(ld)", "replacement": "$1", "text": "..."}
+//     POST /eval      {"expression": "2 * (3 + 4)"}
+//     POST /sum       {"text": "1\n2\n3\n"}
+//
+// The tools are libraries already: quickreplace's `Replacer`, the
+// calculator's `eval`, and `sum_lines` from chapter 7. What the service
+// adds is what a network needs: limits on what a request may ask for, and
+// errors a client program can act on.
+
+mod error;
+mod routes;
+
+pub use error::Error;
+pub use routes::{app, MAX_BODY, MAX_EXPRESSION};
@@ -0,0 +1,38 @@
+use std::net::SocketAddr;
+
+use argparse_lite::{Arg, Command};
+use common::{Context, Error};
+use tracing::info;
+
+fn command() -> Command<'static> {
+    Command::new("tools-api")
+        .about("the workspace's tools as a JSON web service")
+        .arg(
+            Arg::option("addr")
+                .short('a')
+                .value_name("ADDR")
+                .default("127.0.0.1:3000")
+                .help("the address to listen on"),
+        )
+}
+
+fn tools_api() -> Result<(), Error> {
+    let args = common::logging::args()?;
+    let command = command();
+    let matches = command.parse(args).unwrap_or_else(|e| e.exit());
+    let addr: SocketAddr = matches.get("addr").unwrap_or_else(|e| e.exit()).unwrap();
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to listen on {}", addr))?;
+        info!(%addr, "listening");
+        eprintln!("listening on http://{}", addr);
+        axum::serve(listener, tools_api::app()).await?;
+        Ok(())
+    })
+}
+
+fn main() -> std::process::ExitCode {
+    common::run(tools_api)
+}
@@ -0,0 +1,120 @@
+use axum::extract::rejection::JsonRejection;
+use axum::extract::DefaultBodyLimit;
+use axum::routing::post;
+use axum::{Json, Router};
+use ch_02::Replacer;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::Error;
+
+/// The largest request body accepted, in bytes.
+pub const MAX_BODY: usize = 1 << 20;
+
+/// The longest expression `/eval` accepts, in bytes. The calculator
+/// already limits nesting; this bounds the work a request can ask for.
+pub const MAX_EXPRESSION: usize = 4096;
+
+/// The service's routes. The handlers do no I/O and finish quickly on
+/// inputs within the limits, so they run on the async threads directly.
+pub fn app() -> Router {
+    Router::new()
+        .route("/replace", post(replace))
+        .route("/eval", post(eval))
+        .route("/sum", post(sum))
+        .layer(DefaultBodyLimit::max(MAX_BODY))
+}
+
+/// Taking the extractor's `Result`, rather than the `Json` itself, lets a
+/// malformed request get the same structured error as any other.
+type Body<T> = Result<Json<T>, JsonRejection>;
+
+#[derive(Deserialize)]
+struct ReplaceRequest {
+    pattern: String,
+    replacement: String,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct ReplaceResponse {
+    text: String,
+    replacements: usize,
+}
+
+async fn replace(body: Body<ReplaceRequest>) -> Result<Json<ReplaceResponse>, Error> {
+    let Json(request) = body?;
+    if request.pattern.is_empty() {
+        return Err(Error::Invalid {
+            field: "pattern",
+            reason: "must not be empty".to_string(),
+        });
+    }
+    let replacer = Replacer::new(&request.pattern, &request.replacement).map_err(Error::Regex)?;
+    let (text, replacements) = replacer.replace(&request.text);
+    debug!(replacements, "replaced");
+    Ok(Json(ReplaceResponse {
+        text: text.into_owned(),
+        replacements,
+    }))
+}
+
+#[derive(Deserialize)]
+struct EvalRequest {
+    expression: String,
+}
+
+#[derive(Serialize)]
+struct EvalResponse {
+    value: f64,
+}
+
+async fn eval(body: Body<EvalRequest>) -> Result<Json<EvalResponse>, Error> {
+    let Json(request) = body?;
+    if request.expression.len() > MAX_EXPRESSION {
+        return Err(Error::Invalid {
+            field: "expression",
+            reason: format!("longer than {} bytes", MAX_EXPRESSION),
+        });
+    }
+    let value = ch_03::eval(&request.expression).map_err(Error::Eval)?;
+    // JSON has no NaN or infinity, and serde would quietly send `null`.
+    if !value.is_finite() {
+        return Err(Error::Invalid {
+            field: "expression",
+            reason: format!("the result, {}, isn't a finite number", value),
+        });
+    }
+    Ok(Json(EvalResponse { value }))
+}
+
+#[derive(Deserialize)]
+struct SumRequest {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct SumResponse {
+    sum: i64,
+}
+
+async fn sum(body: Body<SumRequest>) -> Result<Json<SumResponse>, Error> {
+    let Json(request) = body?;
+    match ch_07::sum_lines(request.text.as_bytes()) {
+        Ok(sum) => Ok(Json(SumResponse { sum })),
+        Err(_) => {
+            // `sum_lines` stops at the first bad line without saying which;
+            // a client needs to know, so find it.
+            let (index, text) = request
+                .text
+                .lines()
+                .enumerate()
+                .find(|(_, line)| line.trim().parse::<i64>().is_err())
+                .expect("a string's bytes can only fail to parse");
+            Err(Error::NotANumber {
+                line: index + 1,
+                text: text.to_string(),
+            })
+        }
+    }
+}
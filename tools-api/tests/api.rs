@@ -0,0 +1,161 @@
+// The routes, driven in-process with `tower::ServiceExt::oneshot`, with no
+// sockets involved.
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tools_api::{app, MAX_BODY, MAX_EXPRESSION};
+use tower::ServiceExt;
+
+async fn post(path: &str, body: impl Into<Body>) -> (StatusCode, Value) {
+    let request = Request::post(path)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.into())
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn post_json(path: &str, body: Value) -> (StatusCode, Value) {
+    post(path, body.to_string()).await
+}
+
+#[tokio::test]
+async fn replace() {
+    let (status, body) = post_json(
+        "/replace",
+        json!({"pattern": r"(\w+)@(\w+)", "replacement": "$1 at $2", "text": "me@home, you@work"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body,
+        json!({"text": "me at home, you at work", "replacements": 2})
+    );
+
+    let (status, body) = post_json(
+        "/replace",
+        json!({"pattern": "x", "replacement": "y", "text": "no match"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"text": "no match", "replacements": 0}));
+}
+
+#[tokio::test]
+async fn replace_errors() {
+    let (status, body) = post_json(
+        "/replace",
+        json!({"pattern": "(unclosed", "replacement": "", "text": ""}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["kind"], "bad_pattern");
+    assert_eq!(body["field"], "pattern");
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("regex parse error"));
+
+    let (status, body) = post_json(
+        "/replace",
+        json!({"pattern": "", "replacement": "", "text": "abc"}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body,
+        json!({"error": "invalid pattern: must not be empty", "kind": "invalid_field", "field": "pattern"})
+    );
+}
+
+#[tokio::test]
+async fn eval() {
+    let (status, body) = post_json("/eval", json!({"expression": "2 * (3 + 4) ^ 2"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"value": 98.0}));
+}
+
+#[tokio::test]
+async fn eval_errors() {
+    let (status, body) = post_json("/eval", json!({"expression": "1 +"})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["kind"], "eval_error");
+    assert_eq!(body["column"], 4);
+
+    let (_, body) = post_json("/eval", json!({"expression": "1 / 0"})).await;
+    assert_eq!(body["error"], "division by zero");
+    assert!(body.get("column").is_none());
+
+    let (status, body) = post_json("/eval", json!({"expression": "sqrt(-1)"})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["kind"], "invalid_field");
+
+    let long = "1 + ".repeat(MAX_EXPRESSION / 4) + "1";
+    let (status, body) = post_json("/eval", json!({ "expression": long })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body["error"],
+        format!("invalid expression: longer than {} bytes", MAX_EXPRESSION)
+    );
+}
+
+#[tokio::test]
+async fn sum() {
+    let (status, body) = post_json("/sum", json!({"text": "1\n 2 \n-4\n"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"sum": -1}));
+
+    let (status, body) = post_json("/sum", json!({"text": "1\n2\nthree\n4"})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body,
+        json!({
+            "error": "line 3 isn't an integer: \"three\"",
+            "kind": "not_a_number",
+            "field": "text",
+            "line": 3
+        })
+    );
+}
+
+#[tokio::test]
+async fn malformed_requests_get_structured_errors() {
+    let (status, body) = post("/sum", "{not json").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["kind"], "bad_request");
+
+    let (status, body) = post_json("/eval", json!({"expr": "1"})).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["kind"], "bad_request");
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("missing field `expression`"));
+
+    let request = Request::post("/eval")
+        .body(Body::from(r#"{"expression": "1"}"#))
+        .unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let text = "1\n".repeat(MAX_BODY / 2);
+    let (status, body) = post_json("/sum", json!({ "text": text })).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body["kind"], "bad_request");
+}
+
+#[tokio::test]
+async fn unknown_routes_and_methods() {
+    let (status, _) = post_json("/nope", json!({})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let request = Request::get("/eval").body(Body::empty()).unwrap();
+    let response = app().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
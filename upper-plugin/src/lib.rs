@@ -0,0 +1,13 @@
+// An example quickreplace plugin, which puts each replacement in capitals:
+//
+//     cargo build -p upper-plugin
+//     quickreplace --plugin target/debug/libupper_plugin.so '\bfixme\b' '$0' in.rs out.rs
+//
+// Everything a plugin has to say to the program loading it is written by
+// `export_plugin!`; all that's left is the function.
+
+fn upper(text: &str) -> String {
+    text.to_uppercase()
+}
+
+replace_plugin::export_plugin!("upper", upper);
@@ -0,0 +1,66 @@
+// Approximate equality. Floating-point arithmetic rounds, so results that
+// are equal in exact arithmetic usually differ in the last few bits, and
+// tests need a tolerance.
+
+use crate::mat3::Mat3;
+use crate::vec3::Vec3;
+
+/// The tolerance `approx_eq` uses unless told otherwise.
+pub const DEFAULT_EPSILON: f64 = 1e-9;
+
+pub trait ApproxEq {
+    /// True if `self` and `other` differ by at most `epsilon`, compared
+    /// absolutely for magnitudes up to 1 and relatively above that.
+    fn approx_eq_eps(&self, other: &Self, epsilon: f64) -> bool;
+
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.approx_eq_eps(other, DEFAULT_EPSILON)
+    }
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq_eps(&self, other: &f64, epsilon: f64) -> bool {
+        if self == other {
+            // Also covers equal infinities.
+            return true;
+        }
+        let scale = self.abs().max(other.abs()).max(1.0);
+        (self - other).abs() <= epsilon * scale
+    }
+}
+
+impl ApproxEq for Vec3 {
+    fn approx_eq_eps(&self, other: &Vec3, epsilon: f64) -> bool {
+        (0..3).all(|i| self[i].approx_eq_eps(&other[i], epsilon))
+    }
+}
+
+impl ApproxEq for Mat3 {
+    fn approx_eq_eps(&self, other: &Mat3, epsilon: f64) -> bool {
+        (0..3).all(|i| self[i].approx_eq_eps(&other[i], epsilon))
+    }
+}
+
+/// Assert that two values are approximately equal, printing both if not.
+///
+///     use vecmath::{assert_approx_eq, Vec3};
+///     assert_approx_eq!(Vec3::new(0.1, 0.2, 0.0) * 3.0, Vec3::new(0.3, 0.6, 0.0));
+///     assert_approx_eq!(1.0, 1.001, 1e-2);
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_approx_eq!($left, $right, $crate::DEFAULT_EPSILON)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::ApproxEq::approx_eq_eps(left, right, $epsilon) {
+                    panic!(
+                        "assertion failed: `left ≈ right` (epsilon {:e})\n  left: {:?}\n right: {:?}",
+                        $epsilon, left, right
+                    );
+                }
+            }
+        }
+    };
+}
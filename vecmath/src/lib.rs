@@ -0,0 +1,10 @@
+// Three-dimensional vectors and matrices, with operator overloading so that
+// geometry code reads like the math it implements.
+
+mod approx;
+mod mat3;
+mod vec3;
+
+pub use approx::{ApproxEq, DEFAULT_EPSILON};
+pub use mat3::Mat3;
+pub use vec3::Vec3;
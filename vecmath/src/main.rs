@@ -0,0 +1,28 @@
+use std::f64::consts::FRAC_PI_2;
+
+use vecmath::{Mat3, Vec3};
+
+fn main() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(-2.0, 0.5, 4.0);
+    println!("a + b = {}", a + b);
+    println!("2a - b = {}", 2.0 * a - b);
+    println!("a · b = {}", a.dot(b));
+    println!("a × b = {}", a.cross(b));
+    println!("|a| = {:.4}, â = {:.4}", a.length(), a.normalize());
+
+    let quarter_turn = Mat3::rotation_z(FRAC_PI_2);
+    println!(
+        "rotate x̂ a quarter turn about ẑ: {:.3}",
+        quarter_turn * Vec3::X
+    );
+
+    let m = Mat3::from_rows(
+        Vec3::new(2.0, 0.0, 1.0),
+        Vec3::new(1.0, 3.0, 0.0),
+        Vec3::new(0.0, 1.0, 4.0),
+    );
+    let inverse = m.inverse().expect("matrix is invertible");
+    println!("det = {}, m⁻¹ = {:.3}", m.determinant(), inverse);
+    println!("m m⁻¹ = {:.3}", m * inverse);
+}
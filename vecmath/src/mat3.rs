@@ -0,0 +1,215 @@
+// `Mat3`: a 3×3 matrix, for linear transformations of `Vec3`s.
+
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+
+use crate::vec3::Vec3;
+
+/// A 3×3 matrix, stored as rows. Multiplying a `Vec3` treats the vector as
+/// a column: `m * v` transforms `v` by `m`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mat3 {
+    pub rows: [Vec3; 3],
+}
+
+impl Mat3 {
+    pub const ZERO: Mat3 = Mat3::from_rows(Vec3::ZERO, Vec3::ZERO, Vec3::ZERO);
+    pub const IDENTITY: Mat3 = Mat3::from_rows(Vec3::X, Vec3::Y, Vec3::Z);
+
+    pub const fn from_rows(r0: Vec3, r1: Vec3, r2: Vec3) -> Mat3 {
+        Mat3 { rows: [r0, r1, r2] }
+    }
+
+    pub fn from_cols(c0: Vec3, c1: Vec3, c2: Vec3) -> Mat3 {
+        Mat3::from_rows(c0, c1, c2).transpose()
+    }
+
+    /// A matrix that scales each axis by the corresponding component of
+    /// `factors`.
+    pub fn from_diagonal(factors: Vec3) -> Mat3 {
+        Mat3::from_rows(
+            Vec3::new(factors.x, 0.0, 0.0),
+            Vec3::new(0.0, factors.y, 0.0),
+            Vec3::new(0.0, 0.0, factors.z),
+        )
+    }
+
+    /// Rotation by `angle` radians about the x axis, counterclockwise when
+    /// looking down the axis toward the origin.
+    pub fn rotation_x(angle: f64) -> Mat3 {
+        let (s, c) = angle.sin_cos();
+        Mat3::from_rows(
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, c, -s),
+            Vec3::new(0.0, s, c),
+        )
+    }
+
+    pub fn rotation_y(angle: f64) -> Mat3 {
+        let (s, c) = angle.sin_cos();
+        Mat3::from_rows(
+            Vec3::new(c, 0.0, s),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(-s, 0.0, c),
+        )
+    }
+
+    pub fn rotation_z(angle: f64) -> Mat3 {
+        let (s, c) = angle.sin_cos();
+        Mat3::from_rows(
+            Vec3::new(c, -s, 0.0),
+            Vec3::new(s, c, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    /// Rotation by `angle` radians about `axis`, which needn't be unit
+    /// length (Rodrigues' formula).
+    pub fn rotation(axis: Vec3, angle: f64) -> Mat3 {
+        let Vec3 { x, y, z } = axis.normalize();
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        Mat3::from_rows(
+            Vec3::new(t * x * x + c, t * x * y - s * z, t * x * z + s * y),
+            Vec3::new(t * x * y + s * z, t * y * y + c, t * y * z - s * x),
+            Vec3::new(t * x * z - s * y, t * y * z + s * x, t * z * z + c),
+        )
+    }
+
+    pub fn col(&self, index: usize) -> Vec3 {
+        Vec3::new(
+            self.rows[0][index],
+            self.rows[1][index],
+            self.rows[2][index],
+        )
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        Mat3::from_rows(self.col(0), self.col(1), self.col(2))
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let [a, b, c] = self.rows;
+        a.dot(b.cross(c))
+    }
+
+    /// The inverse matrix, or `None` if the matrix is singular (or so
+    /// close to it that the inverse would be meaningless).
+    ///
+    /// How close is too close doesn't depend on the matrix's scale: a tiny
+    /// determinant only means trouble if it's tiny compared to the rows'
+    /// lengths, which bound it, so `Mat3::IDENTITY * 1e-6` has an inverse.
+    pub fn inverse(&self) -> Option<Mat3> {
+        let [a, b, c] = self.rows;
+        // The columns of the inverse are the cross products of pairs of
+        // rows, divided by the determinant.
+        let det = a.dot(b.cross(c));
+        let bound = a.length() * b.length() * c.length();
+        if det.abs() <= f64::EPSILON * bound || !det.is_finite() {
+            return None;
+        }
+        Some(Mat3::from_cols(b.cross(c), c.cross(a), a.cross(b)) * (1.0 / det))
+    }
+
+    pub fn trace(&self) -> f64 {
+        self.rows[0].x + self.rows[1].y + self.rows[2].z
+    }
+}
+
+impl Add for Mat3 {
+    type Output = Mat3;
+    fn add(self, rhs: Mat3) -> Mat3 {
+        let [a, b, c] = self.rows;
+        let [d, e, f] = rhs.rows;
+        Mat3::from_rows(a + d, b + e, c + f)
+    }
+}
+
+impl Sub for Mat3 {
+    type Output = Mat3;
+    fn sub(self, rhs: Mat3) -> Mat3 {
+        self + -rhs
+    }
+}
+
+impl Neg for Mat3 {
+    type Output = Mat3;
+    fn neg(self) -> Mat3 {
+        let [a, b, c] = self.rows;
+        Mat3::from_rows(-a, -b, -c)
+    }
+}
+
+impl Mul<f64> for Mat3 {
+    type Output = Mat3;
+    fn mul(self, rhs: f64) -> Mat3 {
+        let [a, b, c] = self.rows;
+        Mat3::from_rows(a * rhs, b * rhs, c * rhs)
+    }
+}
+
+impl Mul<Mat3> for f64 {
+    type Output = Mat3;
+    fn mul(self, rhs: Mat3) -> Mat3 {
+        rhs * self
+    }
+}
+
+/// Transform a vector: each component of the result is the dot product of
+/// a row with `rhs`.
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        let [a, b, c] = self.rows;
+        Vec3::new(a.dot(rhs), b.dot(rhs), c.dot(rhs))
+    }
+}
+
+/// Compose transformations: `(m * n) * v == m * (n * v)`.
+impl Mul for Mat3 {
+    type Output = Mat3;
+    fn mul(self, rhs: Mat3) -> Mat3 {
+        let [a, b, c] = self.rows;
+        let (c0, c1, c2) = (rhs.col(0), rhs.col(1), rhs.col(2));
+        let row = |r: Vec3| Vec3::new(r.dot(c0), r.dot(c1), r.dot(c2));
+        Mat3::from_rows(row(a), row(b), row(c))
+    }
+}
+
+/// `m[i]` is row `i`.
+impl Index<usize> for Mat3 {
+    type Output = Vec3;
+    fn index(&self, row: usize) -> &Vec3 {
+        &self.rows[row]
+    }
+}
+
+impl IndexMut<usize> for Mat3 {
+    fn index_mut(&mut self, row: usize) -> &mut Vec3 {
+        &mut self.rows[row]
+    }
+}
+
+/// `m[(i, j)]` is the element in row `i`, column `j`.
+impl Index<(usize, usize)> for Mat3 {
+    type Output = f64;
+    fn index(&self, (row, col): (usize, usize)) -> &f64 {
+        &self.rows[row][col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Mat3 {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut f64 {
+        &mut self.rows[row][col]
+    }
+}
+
+impl fmt::Display for Mat3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c] = self.rows;
+        match f.precision() {
+            Some(p) => write!(f, "[{:.*}, {:.*}, {:.*}]", p, a, p, b, p, c),
+            None => write!(f, "[{}, {}, {}]", a, b, c),
+        }
+    }
+}
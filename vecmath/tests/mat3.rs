@@ -0,0 +1,149 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use proptest::prelude::*;
+use vecmath::{assert_approx_eq, ApproxEq, Mat3, Vec3};
+
+fn sample() -> Mat3 {
+    Mat3::from_rows(
+        Vec3::new(2.0, 0.0, 1.0),
+        Vec3::new(1.0, 3.0, 0.0),
+        Vec3::new(0.0, 1.0, 4.0),
+    )
+}
+
+#[test]
+fn identity_and_indexing() {
+    let m = sample();
+    assert_eq!(m * Mat3::IDENTITY, m);
+    assert_eq!(Mat3::IDENTITY * m, m);
+    assert_eq!(m[1], Vec3::new(1.0, 3.0, 0.0));
+    assert_eq!(m[(2, 1)], 1.0);
+    assert_eq!(m.col(2), Vec3::new(1.0, 0.0, 4.0));
+
+    let mut n = m;
+    n[(0, 1)] = 7.0;
+    n[2] = Vec3::ONE;
+    assert_eq!(n[0], Vec3::new(2.0, 7.0, 1.0));
+    assert_eq!(n[2], Vec3::ONE);
+}
+
+#[test]
+fn arithmetic_operators() {
+    let m = sample();
+    assert_eq!(m + m, m * 2.0);
+    assert_eq!(2.0 * m, m * 2.0);
+    assert_eq!(m - m, Mat3::ZERO);
+    assert_eq!(-m + m, Mat3::ZERO);
+    assert_eq!(m * Vec3::new(1.0, 1.0, 1.0), Vec3::new(3.0, 4.0, 5.0));
+    assert_eq!(
+        m * m,
+        Mat3::from_rows(
+            Vec3::new(4.0, 1.0, 6.0),
+            Vec3::new(5.0, 9.0, 1.0),
+            Vec3::new(1.0, 7.0, 16.0),
+        )
+    );
+}
+
+#[test]
+fn transpose_determinant_trace() {
+    let m = sample();
+    assert_eq!(m.transpose().transpose(), m);
+    assert_eq!(m.transpose()[0], m.col(0));
+    assert_eq!(m.determinant(), 25.0);
+    assert_eq!(m.transpose().determinant(), 25.0);
+    assert_eq!(m.trace(), 9.0);
+    assert_eq!(
+        Mat3::from_diagonal(Vec3::new(2.0, 3.0, 4.0)).determinant(),
+        24.0
+    );
+    assert_eq!(Mat3::from_cols(Vec3::X, Vec3::Y, Vec3::Z), Mat3::IDENTITY);
+}
+
+#[test]
+fn inverse() {
+    let m = sample();
+    let inverse = m.inverse().unwrap();
+    assert_approx_eq!(m * inverse, Mat3::IDENTITY);
+    assert_approx_eq!(inverse * m, Mat3::IDENTITY);
+
+    let singular = Mat3::from_rows(Vec3::X, Vec3::Y, Vec3::X + Vec3::Y);
+    assert_eq!(singular.inverse(), None);
+    assert_eq!(Mat3::ZERO.inverse(), None);
+}
+
+#[test]
+fn inverse_at_any_scale() {
+    // A tiny determinant is fine if the matrix itself is tiny.
+    let small = Mat3::IDENTITY * 1e-6;
+    assert_approx_eq!(small.inverse().unwrap(), Mat3::IDENTITY * 1e6);
+    let m = sample() * 1e-5;
+    assert_approx_eq!(m * m.inverse().unwrap(), Mat3::IDENTITY);
+
+    // Rows that are nearly dependent are singular, however large they are.
+    let nearly = Mat3::from_rows(Vec3::X, Vec3::Y, Vec3::X + Vec3::Y + Vec3::Z * 1e-17);
+    assert_eq!(nearly.inverse(), None);
+    assert_eq!((nearly * 1e6).inverse(), None);
+    let singular = Mat3::from_rows(Vec3::X, Vec3::Y, Vec3::X + Vec3::Y) * 1e6;
+    assert_eq!(singular.inverse(), None);
+}
+
+#[test]
+fn rotations() {
+    assert_approx_eq!(Mat3::rotation_z(FRAC_PI_2) * Vec3::X, Vec3::Y);
+    assert_approx_eq!(Mat3::rotation_x(FRAC_PI_2) * Vec3::Y, Vec3::Z);
+    assert_approx_eq!(Mat3::rotation_y(FRAC_PI_2) * Vec3::Z, Vec3::X);
+    assert_approx_eq!(Mat3::rotation(Vec3::Z * 5.0, 0.3), Mat3::rotation_z(0.3));
+    assert_approx_eq!(Mat3::rotation(Vec3::ONE, 2.0 * PI / 3.0) * Vec3::X, Vec3::Y);
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        Mat3::IDENTITY.to_string(),
+        "[(1, 0, 0), (0, 1, 0), (0, 0, 1)]"
+    );
+    assert_eq!(
+        format!("{:.1}", Mat3::IDENTITY * 0.5),
+        "[(0.5, 0.0, 0.0), (0.0, 0.5, 0.0), (0.0, 0.0, 0.5)]"
+    );
+}
+
+fn vec3() -> impl Strategy<Value = Vec3> {
+    (-10.0..10.0f64, -10.0..10.0f64, -10.0..10.0f64).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+fn mat3() -> impl Strategy<Value = Mat3> {
+    (vec3(), vec3(), vec3()).prop_map(|(a, b, c)| Mat3::from_rows(a, b, c))
+}
+
+proptest! {
+    #[test]
+    fn multiplication_composes_transformations(m in mat3(), n in mat3(), v in vec3()) {
+        prop_assert!(((m * n) * v).approx_eq_eps(&(m * (n * v)), 1e-9));
+    }
+
+    #[test]
+    fn determinant_is_multiplicative(m in mat3(), n in mat3()) {
+        let lhs = (m * n).determinant();
+        let rhs = m.determinant() * n.determinant();
+        prop_assert!(lhs.approx_eq_eps(&rhs, 1e-9), "{} vs {}", lhs, rhs);
+    }
+
+    #[test]
+    fn inverse_undoes_the_transformation(m in mat3(), v in vec3()) {
+        // Skip badly conditioned matrices, whose inverses amplify rounding.
+        prop_assume!(m.determinant().abs() > 1.0);
+        let inverse = m.inverse().unwrap();
+        prop_assert!((inverse * (m * v)).approx_eq_eps(&v, 1e-6));
+    }
+
+    #[test]
+    fn rotations_preserve_length_and_determinant(axis in vec3(), angle in -PI..PI, v in vec3()) {
+        prop_assume!(axis.length() > 1e-3);
+        let r = Mat3::rotation(axis, angle);
+        prop_assert!((r * v).length().approx_eq(&v.length()));
+        prop_assert!(r.determinant().approx_eq(&1.0));
+        prop_assert!((r.transpose() * r).approx_eq(&Mat3::IDENTITY));
+    }
+}
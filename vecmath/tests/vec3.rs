@@ -0,0 +1,131 @@
+use vecmath::{assert_approx_eq, ApproxEq, Vec3};
+
+use proptest::prelude::*;
+
+#[test]
+fn arithmetic_operators() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(4.0, -5.0, 6.0);
+    assert_eq!(a + b, Vec3::new(5.0, -3.0, 9.0));
+    assert_eq!(a - b, Vec3::new(-3.0, 7.0, -3.0));
+    assert_eq!(-a, Vec3::new(-1.0, -2.0, -3.0));
+    assert_eq!(a * 2.0, Vec3::new(2.0, 4.0, 6.0));
+    assert_eq!(2.0 * a, a * 2.0);
+    assert_eq!(b / 2.0, Vec3::new(2.0, -2.5, 3.0));
+    assert_eq!(a.mul_elements(b), Vec3::new(4.0, -10.0, 18.0));
+
+    let mut c = a;
+    c += b;
+    c -= a;
+    c *= 3.0;
+    c /= 6.0;
+    assert_eq!(c, b / 2.0);
+
+    assert_eq!(
+        [a, b, Vec3::ONE].into_iter().sum::<Vec3>(),
+        Vec3::new(6.0, -2.0, 10.0)
+    );
+}
+
+#[test]
+fn indexing() {
+    let mut v = Vec3::new(7.0, 8.0, 9.0);
+    assert_eq!((v[0], v[1], v[2]), (7.0, 8.0, 9.0));
+    v[1] = -1.0;
+    assert_eq!(v.y, -1.0);
+    assert_eq!(<[f64; 3]>::from(v), [7.0, -1.0, 9.0]);
+    assert_eq!(Vec3::from([1.0, 2.0, 3.0]), Vec3::new(1.0, 2.0, 3.0));
+}
+
+#[test]
+#[should_panic(expected = "Vec3 index out of range: 3")]
+fn index_out_of_range() {
+    let _ = Vec3::ZERO[3];
+}
+
+#[test]
+fn dot_and_cross() {
+    assert_eq!(Vec3::X.dot(Vec3::Y), 0.0);
+    assert_eq!(Vec3::new(1.0, 2.0, 3.0).dot(Vec3::new(4.0, 5.0, 6.0)), 32.0);
+    assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+    assert_eq!(Vec3::Y.cross(Vec3::Z), Vec3::X);
+    assert_eq!(Vec3::Z.cross(Vec3::X), Vec3::Y);
+    assert_eq!(Vec3::Y.cross(Vec3::X), -Vec3::Z);
+}
+
+#[test]
+fn normalizing() {
+    let v = Vec3::new(3.0, 0.0, 4.0);
+    assert_eq!(v.length(), 5.0);
+    assert_approx_eq!(v.normalize(), Vec3::new(0.6, 0.0, 0.8));
+    assert_eq!(v.try_normalize(), Some(v.normalize()));
+    assert_eq!(Vec3::ZERO.try_normalize(), None);
+    assert!(Vec3::ZERO.normalize().x.is_nan());
+}
+
+#[test]
+fn reflect_and_lerp() {
+    let incoming = Vec3::new(1.0, -1.0, 0.0);
+    assert_eq!(incoming.reflect(Vec3::Y), Vec3::new(1.0, 1.0, 0.0));
+    let a = Vec3::ZERO;
+    let b = Vec3::new(2.0, 4.0, -6.0);
+    assert_eq!(a.lerp(b, 0.5), Vec3::new(1.0, 2.0, -3.0));
+    assert_eq!(b.min_element(), -6.0);
+    assert_eq!(b.max_element(), 4.0);
+}
+
+#[test]
+fn display() {
+    assert_eq!(Vec3::new(1.0, -2.5, 0.0).to_string(), "(1, -2.5, 0)");
+    assert_eq!(
+        format!("{:.2}", Vec3::splat(1.0 / 3.0)),
+        "(0.33, 0.33, 0.33)"
+    );
+}
+
+#[test]
+fn approx_eq_tolerances() {
+    assert!((0.1 + 0.2).approx_eq(&0.3));
+    assert!(1e12.approx_eq(&(1e12 + 1e-3)));
+    assert!(!1.0.approx_eq(&1.001));
+    assert!(1.0.approx_eq_eps(&1.001, 1e-2));
+    assert!(f64::INFINITY.approx_eq(&f64::INFINITY));
+    assert!(!f64::NAN.approx_eq(&f64::NAN));
+}
+
+#[test]
+#[should_panic(expected = "assertion failed: `left ≈ right`")]
+fn assert_approx_eq_panics() {
+    assert_approx_eq!(Vec3::X, Vec3::Y);
+}
+
+fn vec3() -> impl Strategy<Value = Vec3> {
+    (-100.0..100.0f64, -100.0..100.0f64, -100.0..100.0f64).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+proptest! {
+    #[test]
+    fn cross_product_is_perpendicular(a in vec3(), b in vec3()) {
+        let c = a.cross(b);
+        let scale = a.length() * b.length() * c.length() + 1.0;
+        prop_assert!(c.dot(a).abs() <= 1e-9 * scale);
+        prop_assert!(c.dot(b).abs() <= 1e-9 * scale);
+        prop_assert_eq!(b.cross(a), -c);
+    }
+
+    #[test]
+    fn lagrange_identity(a in vec3(), b in vec3()) {
+        // |a × b|² = |a|²|b|² - (a · b)²
+        let lhs = a.cross(b).length_squared();
+        let rhs = a.length_squared() * b.length_squared() - a.dot(b).powi(2);
+        prop_assert!(lhs.approx_eq_eps(&rhs, 1e-6), "{} vs {}", lhs, rhs);
+    }
+
+    #[test]
+    fn normalized_vectors_have_unit_length(v in vec3()) {
+        if let Some(n) = v.try_normalize() {
+            prop_assert!(n.length().approx_eq(&1.0));
+            prop_assert!((n * v.length()).approx_eq(&v));
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// Two engines from earlier chapters, compiled to WebAssembly for use from
+// JavaScript: chapter 3's arithmetic evaluator and chapter 2's
+// quickreplace.
+//
+//     import init, { eval as evaluate, replace } from "./www/playground.js";
+//     await init();
+//     evaluate("2 ^ 10");                            // 1024
+//     replace("(\\w+)@", "$1 at ", "me@example.com"); // "me at example.com"
+//
+// wasm-bindgen can't emit a function named `eval`, which strict-mode code
+// may not declare, so it's exported as `evaluate` and www/playground.js
+// re-exports it under its JavaScript name.
+
+use wasm_bindgen::prelude::*;
+
+/// Evaluate an arithmetic expression. Errors are thrown as JavaScript
+/// `Error`s.
+#[wasm_bindgen]
+pub fn evaluate(expr: &str) -> Result<f64, JsError> {
+    ch_03::eval(expr).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Replace every match of the regular expression `pattern` in `text`,
+/// as quickreplace does.
+#[wasm_bindgen]
+pub fn replace(pattern: &str, replacement: &str, text: &str) -> Result<String, JsError> {
+    ch_02::replace(pattern, replacement, text).map_err(|e| JsError::new(&e.to_string()))
+}
@@ -0,0 +1,125 @@
+// Run in Node, compiled to WebAssembly, with:
+//
+//     wasm-pack test --node wasm-playground
+//
+// or, natively, as ordinary tests, with `cargo test`. Natively there's no
+// JavaScript engine to create `Error`s in, so the tests of what JavaScript
+// sees when a call fails only run under WebAssembly.
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use ch_03::EvalError;
+use wasm_playground::{evaluate, replace};
+
+fn eval(expr: &str) -> f64 {
+    evaluate(expr).unwrap_or_else(|_| panic!("{:?} failed", expr))
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn arithmetic() {
+    assert_eq!(eval("1 + 2 * 3"), 7.0);
+    assert_eq!(eval("(1 + 2) * 3"), 9.0);
+    assert_eq!(eval("10 - 2 - 3"), 5.0);
+    assert_eq!(eval("100 / 10 / 5"), 2.0);
+    assert_eq!(eval("17 % 5"), 2.0);
+    assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+    assert_eq!(eval("-2 ^ 2"), -4.0);
+    assert_eq!(eval("2 * -3"), -6.0);
+    assert_eq!(eval("--3"), 3.0);
+    assert_eq!(eval(" 1.5e3\t+ .5 "), 1500.5);
+    assert_eq!(eval("1\n+\n1"), 2.0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn constants_and_functions() {
+    assert_eq!(eval("pi"), std::f64::consts::PI);
+    assert_eq!(eval("e ^ 2"), std::f64::consts::E.powf(2.0));
+    assert_eq!(eval("sqrt(16) + abs(-2)"), 6.0);
+    assert_eq!(eval("floor(2.7) + ceil(2.2) + round(2.5)"), 8.0);
+    assert!(eval("sin(pi)").abs() < 1e-12);
+    assert_eq!(eval("ln(e)"), 1.0);
+    assert_eq!(eval("log10(1000)"), 3.0);
+    assert_eq!(eval("sqrt(sqrt(81))"), 3.0);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn evaluation_errors() {
+    let syntax = |column, expected: &str| EvalError::Syntax {
+        column,
+        expected: expected.to_string(),
+    };
+    let cases = [
+        ("", syntax(1, "a value")),
+        ("1 +", syntax(4, "a value")),
+        ("1 2", syntax(3, "the end or an operator")),
+        ("2 * (3", syntax(7, "')' or an operator")),
+        ("sqrt 2", syntax(6, "the end or an operator")),
+        ("4 $ 4", syntax(3, "the end or an operator")),
+        ("tau", EvalError::UnknownConstant("tau".to_string())),
+        ("cbrt(8)", EvalError::UnknownFunction("cbrt".to_string())),
+        ("1 / 0", EvalError::DivisionByZero),
+        ("7 % (2 - 2)", EvalError::DivisionByZero),
+    ];
+    for (expr, error) in cases {
+        assert_eq!(ch_03::eval(expr), Err(error), "{:?}", expr);
+    }
+    assert_eq!(
+        ch_03::eval("2 * (3").unwrap_err().to_string(),
+        "expected ')' or an operator at column 7"
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn replacing() {
+    let replaced = |pattern, replacement, text| replace(pattern, replacement, text).unwrap();
+    assert_eq!(replaced("world", "Rust", "Hello, world!"), "Hello, Rust!");
+    assert_eq!(replaced("o", "0", "foo boo"), "f00 b00");
+    assert_eq!(replaced("xyz", "abc", "unchanged"), "unchanged");
+    assert_eq!(
+        replaced(r"(\w+)@(\w+)", "$2 at $1", "me@home"),
+        "home at me"
+    );
+    assert_eq!(
+        replaced(r"(?P<y>\d{4})-(?P<m>\d\d)", "$m/$y", "2024-03"),
+        "03/2024"
+    );
+    assert_eq!(replaced("é", "e", "café, résumé"), "cafe, resume");
+    assert_eq!(replaced(r"\s+", " ", "a \n\t b"), "a b");
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn bad_patterns() {
+    let e = ch_02::replace("(unclosed", "", "text").unwrap_err();
+    assert!(e.to_string().contains("unclosed group"), "{}", e);
+}
+
+// What JavaScript sees: a thrown `Error` carrying the Rust error's message.
+#[cfg(target_arch = "wasm32")]
+mod javascript {
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use wasm_playground::{evaluate, replace};
+
+    fn message(error: wasm_bindgen::JsError) -> String {
+        let value = JsValue::from(error);
+        let error: &js_sys::Error = value.dyn_ref().unwrap();
+        String::from(error.message())
+    }
+
+    #[wasm_bindgen_test]
+    fn errors_are_thrown() {
+        assert_eq!(message(evaluate("1 / 0").unwrap_err()), "division by zero");
+        assert_eq!(
+            message(evaluate("1 +").unwrap_err()),
+            "expected a value at column 4"
+        );
+        assert!(message(replace("(", "", "").unwrap_err()).contains("unclosed group"));
+    }
+}
@@ -0,0 +1,33 @@
+// `eval` can't be an identifier in a module, so it's imported under
+// another name.
+import init, { eval as evaluate, replace } from "./playground.js";
+
+await init();
+
+const $ = (id) => document.getElementById(id);
+
+// Show `compute()`'s result in `output`, or the error it throws.
+function show(output, compute) {
+  try {
+    output.textContent = compute();
+    output.className = "";
+  } catch (e) {
+    output.textContent = e.message;
+    output.className = "error";
+  }
+}
+
+function calculate() {
+  show($("result"), () => evaluate($("expr").value));
+}
+
+function substitute() {
+  show($("replaced"), () => replace($("pattern").value, $("replacement").value, $("text").value));
+}
+
+$("expr").addEventListener("input", calculate);
+for (const id of ["pattern", "replacement", "text"]) {
+  $(id).addEventListener("input", substitute);
+}
+calculate();
+substitute();
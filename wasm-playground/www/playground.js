@@ -0,0 +1,4 @@
+// The package's JavaScript interface: what wasm-bindgen generates, with
+// `evaluate` under the name it has in JavaScript. An export may be called
+// `eval` even though a function declaration may not.
+export { default, initSync, evaluate as eval, replace } from "../pkg/wasm_playground.js";
@@ -0,0 +1,43 @@
+// Running cargo.
+
+use std::env;
+use std::ffi::OsString;
+use std::process::Command;
+
+use common::Error;
+
+use crate::workspace;
+
+/// Run cargo with `args` at the top of the workspace, its output going
+/// straight to ours, and fail if it fails. Under `cargo xtask`, `$CARGO`
+/// names the cargo that ran us, so that's the one used.
+pub fn cargo(args: &[&str]) -> Result<(), Error> {
+    run(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()), args)
+}
+
+/// `cargo`, with the nightly toolchain. `$CARGO` is the stable toolchain's
+/// own cargo, which doesn't understand `+nightly`; the `cargo` on the path
+/// is rustup's, which does.
+pub fn nightly(args: &[&str]) -> Result<(), Error> {
+    let mut all = vec!["+nightly"];
+    all.extend(args);
+    run("cargo".into(), &all)
+}
+
+fn run(program: OsString, args: &[&str]) -> Result<(), Error> {
+    let status = Command::new(program)
+        .args(args)
+        .current_dir(workspace::root())
+        .env_remove("RUSTUP_TOOLCHAIN")
+        .status()
+        .map_err(|e| Error::new(format!("can't run cargo: {}", e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::new(format!(
+            "cargo {} failed: {}",
+            args.join(" "),
+            status
+        )))
+    }
+}
@@ -0,0 +1,120 @@
+// The golden files cli-tests compares program output against, and how to
+// make each one.
+//
+// A golden file is only worth having if it's what the program really
+// prints, so rather than editing them by hand, `cargo xtask golden` runs
+// the programs and writes what they print. `--check` compares instead,
+// for CI. The inputs the programs read (poem.txt, numbers.txt) are
+// written by hand, and aren't touched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use common::{Context, Error};
+
+use crate::workspace;
+
+/// Which of a program's outputs a golden file holds.
+#[derive(Debug)]
+pub enum Capture {
+    Stdout,
+    Stderr,
+    /// A file the program writes, in its working directory.
+    File(&'static str),
+}
+
+/// How to make one golden file.
+#[derive(Debug)]
+pub struct Golden {
+    /// The file's name in cli-tests/tests/golden.
+    pub name: &'static str,
+    pub package: &'static str,
+    /// `--bin NAME` or `--example NAME`.
+    pub target: [&'static str; 2],
+    pub args: &'static [&'static str],
+    /// Golden inputs to copy into the program's working directory, as
+    /// (golden file, name to give it).
+    pub inputs: &'static [(&'static str, &'static str)],
+    pub capture: Capture,
+}
+
+pub static GOLDEN: &[Golden] = &[
+    Golden {
+        name: "poem-bracketed.txt",
+        package: "ch_02",
+        target: ["--bin", "ch_02"],
+        args: &[r"(?m)^(\w+)", "[$1]", "in.txt", "out.txt"],
+        inputs: &[("poem.txt", "in.txt")],
+        capture: Capture::File("out.txt"),
+    },
+    Golden {
+        name: "quickreplace-usage.stderr",
+        package: "ch_02",
+        target: ["--bin", "ch_02"],
+        args: &["only", "three", "args"],
+        inputs: &[],
+        capture: Capture::Stderr,
+    },
+    Golden {
+        name: "calculator-usage.stderr",
+        package: "ch_03",
+        target: ["--bin", "ch_03"],
+        args: &["a", "b", "c"],
+        inputs: &[],
+        capture: Capture::Stderr,
+    },
+    Golden {
+        name: "numbers.stdout",
+        package: "ch_07",
+        target: ["--example", "read_and_sum"],
+        args: &["numbers.txt"],
+        inputs: &[("numbers.txt", "numbers.txt")],
+        capture: Capture::Stdout,
+    },
+];
+
+/// The directory the golden files live in.
+pub fn dir() -> PathBuf {
+    workspace::root().join("cli-tests/tests/golden")
+}
+
+/// Run `golden`'s program in a fresh directory under `scratch` and return
+/// what it printed, or wrote, that the golden file should hold.
+pub fn generate(golden: &Golden, scratch: &Path) -> Result<Vec<u8>, Error> {
+    let work = scratch.join(golden.name);
+    fs::create_dir_all(&work)?;
+    for (from, to) in golden.inputs {
+        fs::copy(dir().join(from), work.join(to))
+            .with_context(|| format!("failed to copy golden input {}", from))?;
+    }
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    // The same environment cli-tests gives its programs.
+    let mut command = Command::new(cargo);
+    for (var, _) in std::env::vars() {
+        if var.starts_with("QUICKREPLACE_")
+            || var.starts_with("LC_")
+            || ["RUST_LOG", "LANG", "LANGUAGE"].contains(&var.as_str())
+        {
+            command.env_remove(var);
+        }
+    }
+    let output = command
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(workspace::root().join("Cargo.toml"))
+        .args(["--package", golden.package])
+        .args(golden.target)
+        .arg("--")
+        .args(golden.args)
+        .current_dir(&work)
+        .env("NO_COLOR", "1")
+        .env("HOME", "/nonexistent")
+        .output()
+        .with_context(|| format!("failed to run {}", golden.package))?;
+    Ok(match golden.capture {
+        Capture::Stdout => output.stdout,
+        Capture::Stderr => output.stderr,
+        Capture::File(name) => fs::read(work.join(name))
+            .with_context(|| format!("{} didn't write {}", golden.package, name))?,
+    })
+}
@@ -0,0 +1,16 @@
+// The workspace's chores, as a program: `cargo xtask COMMAND`, where
+// `xtask` is an alias in .cargo/config.toml for running this member.
+//
+// Each command is the kind of thing that would otherwise be a shell
+// script: run every demo, regenerate the files tests compare against, run
+// the unsafe code under Miri, report on the state of each member. Written
+// in Rust, they run wherever cargo does, and they're checked by the same
+// compiler as everything else.
+
+mod cargo;
+pub mod golden;
+pub mod miri;
+pub mod status;
+pub mod workspace;
+
+pub use cargo::{cargo, nightly};
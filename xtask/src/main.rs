@@ -0,0 +1,121 @@
+use std::fs;
+
+use argparse_lite::{Arg, Command, Matches};
+use common::{Context, Error};
+use xtask::{cargo, golden, miri, nightly, status};
+
+fn command() -> Command<'static> {
+    Command::new("xtask")
+        .about("the workspace's chores; run as `cargo xtask`")
+        .subcommand(
+            Command::new("demos")
+                .about("build everything, then run each demo with its sample inputs")
+                .arg(Arg::positional("name").multiple().optional()),
+        )
+        .subcommand(
+            Command::new("golden")
+                .about("regenerate cli-tests' golden files and runner's snapshots")
+                .arg(
+                    Arg::flag("check").help("compare instead, failing if anything is out of date"),
+                ),
+        )
+        .subcommand(
+            Command::new("miri")
+                .about("run the tests of crates with unsafe code under Miri")
+                .arg(Arg::positional("crate").multiple().optional()),
+        )
+        .subcommand(Command::new("status").about("tabulate what's in each member"))
+}
+
+fn demos(names: Vec<&str>) -> Result<(), Error> {
+    cargo(&["build", "--workspace", "--bins"])?;
+    let mut args = vec![
+        "run",
+        "--quiet",
+        "--package",
+        "runner",
+        "--",
+        "run",
+        "--all",
+    ];
+    args.extend(names);
+    cargo(&args)
+}
+
+fn golden(check: bool) -> Result<(), Error> {
+    let scratch = tempfile::tempdir()?;
+    let mut stale = Vec::new();
+    for golden in golden::GOLDEN {
+        let path = golden::dir().join(golden.name);
+        let generated = golden::generate(golden, scratch.path())?;
+        let current = fs::read(&path).unwrap_or_default();
+        if generated == current {
+            println!("{:28} up to date", golden.name);
+        } else if check {
+            println!("{:28} out of date", golden.name);
+            stale.push(golden.name);
+        } else {
+            fs::write(&path, generated)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            println!("{:28} updated", golden.name);
+        }
+    }
+    // insta does the same for runner's snapshots, told which to do by
+    // INSTA_UPDATE.
+    std::env::set_var("INSTA_UPDATE", if check { "no" } else { "always" });
+    let snapshots = cargo(&["test", "--package", "runner", "--test", "snapshots"]);
+    if !stale.is_empty() {
+        return Err(Error::new(format!("out of date: {}", stale.join(", "))));
+    }
+    snapshots
+}
+
+fn miri(names: Vec<&str>) -> Result<(), Error> {
+    let chosen = |target: &miri::Target| {
+        names.is_empty() || names.contains(&target.package) || names.contains(&target.suite)
+    };
+    for name in &names {
+        if !miri::TARGETS
+            .iter()
+            .any(|target| target.package == *name || target.suite == *name)
+        {
+            return Err(Error::usage(format!("{} isn't a Miri target", name)));
+        }
+    }
+    nightly(&["miri", "setup"])?;
+    for target in miri::TARGETS {
+        if chosen(target) {
+            println!("{}: {}", target.package, target.why);
+            nightly(&miri::args(target))?;
+        }
+    }
+    Ok(())
+}
+
+fn status() {
+    println!("{}", status::Status::header());
+    for member in status::report() {
+        println!("{}", member);
+    }
+}
+
+fn xtask() -> Result<(), Error> {
+    let command = command();
+    let matches: Matches = command
+        .parse(std::env::args().skip(1))
+        .unwrap_or_else(|e| e.exit());
+    match matches.subcommand() {
+        Some(("demos", m)) => demos(m.values("name")),
+        Some(("golden", m)) => golden(m.flag("check")),
+        Some(("miri", m)) => miri(m.values("crate")),
+        Some(("status", _)) => {
+            status();
+            Ok(())
+        }
+        _ => unreachable!("argparse-lite requires a known command"),
+    }
+}
+
+fn main() -> std::process::ExitCode {
+    common::run(xtask)
+}
@@ -0,0 +1,54 @@
+// Running the workspace's `unsafe` code under Miri.
+//
+// Miri interprets a program instead of compiling it, checking every
+// pointer use against the rules `unsafe` code must follow. The tests it
+// runs are in the miri-suite member, one suite per crate with `unsafe`
+// blocks, written to avoid what Miri can't do and scaled down for how
+// slow it is.
+
+/// The package holding the suites.
+pub const PACKAGE: &str = "miri-suite";
+
+/// One crate's suite.
+#[derive(Debug)]
+pub struct Target {
+    /// The crate whose `unsafe` code the suite exercises.
+    pub package: &'static str,
+    /// The suite's name in miri-suite/tests.
+    pub suite: &'static str,
+    /// What the `unsafe` code does.
+    pub why: &'static str,
+}
+
+pub static TARGETS: &[Target] = &[
+    Target {
+        package: "ascii",
+        suite: "ascii",
+        why: "builds a String from bytes it has checked are ASCII",
+    },
+    Target {
+        package: "gap-buffer",
+        suite: "gap_buffer",
+        why: "moves elements around an uninitialized gap",
+    },
+    Target {
+        package: "mini-channel",
+        suite: "spsc",
+        why: "shares a ring buffer of uninitialized slots between threads",
+    },
+    Target {
+        package: "alloc-lab",
+        suite: "alloc_lab",
+        why: "is a global allocator, handing out raw memory",
+    },
+    Target {
+        package: "replace-plugin",
+        suite: "replace_plugin",
+        why: "hands buffers across a C ABI to be freed by the side that made them",
+    },
+];
+
+/// The nightly `cargo` arguments that run `target`'s suite under Miri.
+pub fn args(target: &Target) -> Vec<&'static str> {
+    vec!["miri", "test", "--package", PACKAGE, "--test", target.suite]
+}
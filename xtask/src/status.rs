@@ -0,0 +1,126 @@
+// A table of every member and how far along it is: what it builds, how
+// many tests it has, and whether it has a Readme and a demo.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use runner::DEMOS;
+
+use crate::workspace;
+
+/// What's in one member.
+#[derive(Debug)]
+pub struct Status {
+    pub member: String,
+    /// Whether a plain `cargo build` builds it.
+    pub default: bool,
+    pub lib: bool,
+    /// How many programs: `src/main.rs` and anything in `src/bin`.
+    pub bins: usize,
+    /// How many `#[test]` functions, anywhere under the member.
+    pub tests: usize,
+    pub benches: usize,
+    /// How many of its programs `demos run --all` runs.
+    pub demos: usize,
+    /// How many lines its Readme is, or `None` if it has none.
+    pub readme: Option<usize>,
+}
+
+impl Status {
+    pub fn of(member: &str) -> Status {
+        let dir = workspace::root().join(member);
+        let default = workspace::default_members().iter().any(|m| m == member);
+        let readme = ["Readme.md", "README.md"]
+            .iter()
+            .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+            .map(|text| text.lines().count());
+        Status {
+            member: member.to_string(),
+            default,
+            lib: dir.join("src/lib.rs").exists(),
+            bins: dir.join("src/main.rs").exists() as usize + count_rs(&dir.join("src/bin")),
+            tests: count_tests(&dir),
+            benches: count_rs(&dir.join("benches")),
+            demos: {
+                let package = workspace::package(member);
+                DEMOS.iter().filter(|demo| demo.package == package).count()
+            },
+            readme,
+        }
+    }
+
+    /// The header line for a table of `Status`es.
+    pub fn header() -> String {
+        format!(
+            "{:16} {:7} {:3} {:4} {:5} {:7} {:5} {}",
+            "member", "default", "lib", "bins", "tests", "benches", "demos", "readme"
+        )
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let yes = |b| if b { "yes" } else { "-" };
+        let readme = match self.readme {
+            Some(0) => "empty".to_string(),
+            Some(lines) => format!("{} lines", lines),
+            None => "missing".to_string(),
+        };
+        write!(
+            f,
+            "{:16} {:7} {:3} {:4} {:5} {:7} {:5} {}",
+            self.member,
+            yes(self.default),
+            yes(self.lib),
+            self.bins,
+            self.tests,
+            self.benches,
+            self.demos,
+            readme
+        )
+    }
+}
+
+/// Every member's status, in the manifest's order.
+pub fn report() -> Vec<Status> {
+    workspace::members().iter().map(|m| Status::of(m)).collect()
+}
+
+fn count_rs(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "rs"))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// The `#[test]` attributes in `.rs` files under `dir`, skipping build
+/// output.
+fn count_tests(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut count = 0;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "target") {
+                count += count_tests(&path);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let text = fs::read_to_string(&path).unwrap_or_default();
+            count += text
+                .lines()
+                .filter(|line| {
+                    let line = line.trim();
+                    line == "#[test]" || line.starts_with("#[tokio::test")
+                })
+                .count();
+        }
+    }
+    count
+}
@@ -0,0 +1,52 @@
+// What the workspace's `Cargo.toml` says about its members.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The workspace's top directory.
+pub fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+/// The directories listed in `members`, in order.
+pub fn members() -> Vec<String> {
+    list("members")
+}
+
+/// The directories listed in `default-members`.
+pub fn default_members() -> Vec<String> {
+    list("default-members")
+}
+
+/// The strings in the top-level manifest's array called `key`. The arrays
+/// are written one string per line, so this needn't parse TOML.
+fn list(key: &str) -> Vec<String> {
+    let manifest = fs::read_to_string(root().join("Cargo.toml")).expect("reading Cargo.toml");
+    let start = manifest
+        .lines()
+        .position(|line| line.starts_with(&format!("{} = [", key)))
+        .unwrap_or_else(|| panic!("no {} in Cargo.toml", key));
+    manifest
+        .lines()
+        .skip(start + 1)
+        .take_while(|line| !line.starts_with(']'))
+        .map(|line| line.trim().trim_end_matches(',').trim_matches('"'))
+        .filter(|name| !name.is_empty() && !name.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The package name in `member`'s manifest, which can differ from its
+/// directory: ch_01's package is `ch_1`.
+pub fn package(member: &str) -> String {
+    let manifest = fs::read_to_string(root().join(member).join("Cargo.toml"))
+        .unwrap_or_else(|e| panic!("reading {}/Cargo.toml: {}", member, e));
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("name = "))
+        .map(|name| name.trim_matches('"').to_string())
+        .unwrap_or_else(|| member.to_string())
+}
@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+use std::fs;
+
+use xtask::{golden, miri, status, workspace};
+
+#[test]
+fn members_are_read_from_the_manifest() {
+    let members = workspace::members();
+    assert_eq!(members.first().map(String::as_str), Some("ch_01"));
+    assert!(members.iter().any(|m| m == "xtask"));
+    let defaults = workspace::default_members();
+    assert!(defaults.iter().all(|m| members.contains(m)));
+    assert!(!defaults.iter().any(|m| m == "xtask"));
+    assert_eq!(workspace::package("ch_01"), "ch_1");
+}
+
+#[test]
+fn every_golden_output_has_a_recipe() {
+    let inputs: HashSet<&str> = golden::GOLDEN
+        .iter()
+        .flat_map(|g| g.inputs.iter().map(|(from, _)| *from))
+        .collect();
+    let outputs: HashSet<&str> = golden::GOLDEN.iter().map(|g| g.name).collect();
+    for entry in fs::read_dir(golden::dir()).unwrap() {
+        let name = entry.unwrap().file_name().into_string().unwrap();
+        assert!(
+            inputs.contains(name.as_str()) || outputs.contains(name.as_str()),
+            "nothing makes {}",
+            name
+        );
+    }
+    for name in &outputs {
+        assert!(!inputs.contains(name), "{} is both input and output", name);
+    }
+}
+
+#[test]
+fn miri_targets_have_unsafe_code_and_a_suite() {
+    let members = workspace::members();
+    assert!(members.iter().any(|m| m == miri::PACKAGE));
+    for target in miri::TARGETS {
+        assert!(members.iter().any(|m| m == target.package));
+        let src = workspace::root().join(target.package).join("src");
+        let mentions_unsafe = fs::read_dir(src).unwrap().any(|entry| {
+            let text = fs::read_to_string(entry.unwrap().path()).unwrap_or_default();
+            text.contains("unsafe {") || text.contains("unsafe impl")
+        });
+        assert!(mentions_unsafe, "{} has no unsafe code", target.package);
+        let suite = workspace::root()
+            .join(miri::PACKAGE)
+            .join("tests")
+            .join(format!("{}.rs", target.suite));
+        assert!(suite.exists(), "no suite {}", suite.display());
+        assert_eq!(miri::args(target).last(), Some(&target.suite));
+    }
+}
+
+#[test]
+fn status_covers_every_member() {
+    let report = status::report();
+    assert_eq!(report.len(), workspace::members().len());
+    let ch_02 = report.iter().find(|s| s.member == "ch_02").unwrap();
+    assert!(ch_02.default && ch_02.lib);
+    assert_eq!(ch_02.bins, 1);
+    assert_eq!(ch_02.demos, 1);
+    assert!(ch_02.readme.is_some_and(|lines| lines > 0));
+    let benches = report.iter().find(|s| s.member == "benches").unwrap();
+    assert!(!benches.default);
+    assert!(benches.benches > 0);
+    assert!(ch_02.to_string().starts_with("ch_02 "));
+    assert!(status::Status::header().starts_with("member "));
+}